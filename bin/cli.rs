@@ -3,7 +3,7 @@
 use ectoplasm_contracts::dex::factory::Factory;
 use ectoplasm_contracts::dex::router::Router;
 use ectoplasm_contracts::token::LpToken;
-use ectoplasm_contracts::tokens::{EctoToken, UsdcToken, WethToken, WbtcToken};
+use ectoplasm_contracts::tokens::{EctoToken, UsdcToken, WethToken, WbtcToken, WcsprToken};
 use odra::prelude::{Address, Addressable};
 use odra::host::{HostEnv, Deployer};
 use odra::schema::casper_contract_schema::NamedCLType;
@@ -51,19 +51,15 @@ impl DeployScript for RouterDeployScript {
         container: &mut DeployedContractsContainer
     ) -> Result<(), odra_cli::deploy::Error> {
         use ectoplasm_contracts::dex::router::RouterInitArgs;
-        use ectoplasm_contracts::token::LpTokenInitArgs;
         
         // Get factory address from container
         let factory = container.contract_ref::<Factory>(env)?;
         let factory_address = factory.address().clone();
         
         // Deploy WCSPR token if not exists
-        let wcspr = LpToken::load_or_deploy(
+        let wcspr = WcsprToken::load_or_deploy(
             &env,
-            LpTokenInitArgs {
-                name: String::from("Wrapped CSPR"),
-                symbol: String::from("WCSPR"),
-            },
+            NoArgs,
             container,
             600_000_000_000 // Increased gas limit for token deployment
         )?;
@@ -213,6 +209,7 @@ pub fn main() {
         .contract::<UsdcToken>()
         .contract::<WethToken>()
         .contract::<WbtcToken>()
+        .contract::<WcsprToken>()
         // Scenarios
         .scenario(CreatePairScenario)
         .build()
@@ -0,0 +1,152 @@
+//! Role-based access control
+//!
+//! `AccessControl` is embedded as a submodule by every permissioned contract.
+//! Each role is a set of accounts; holders of `ADMIN_ROLE` grant and revoke
+//! roles, and contracts gate entry points on the narrowest role that fits.
+
+use odra::prelude::*;
+use odra::OdraError;
+
+/// Role identifier
+pub type Role = u8;
+
+/// Configures the contract and manages every role
+pub const ADMIN_ROLE: Role = 0;
+
+/// Pauses and unpauses the contract
+pub const PAUSER_ROLE: Role = 1;
+
+/// Pushes external data (prices, staking rewards)
+pub const ORACLE_ROLE: Role = 2;
+
+/// Runs routine maintenance (rebalancing, stats updates)
+pub const KEEPER_ROLE: Role = 3;
+
+/// Registers assets, pools, validators and positions
+pub const REGISTRAR_ROLE: Role = 4;
+
+/// Switches individual actions off during incident response
+pub const GUARDIAN_ROLE: Role = 5;
+
+/// Funds reward streams (e.g. the EmissionMinter on StakingPool)
+pub const REWARD_DISTRIBUTOR_ROLE: Role = 6;
+
+/// Access control errors
+#[odra::odra_error]
+pub enum AccessError {
+    /// Caller does not hold the required role
+    MissingRole = 300,
+
+    /// The last admin cannot give up the admin role
+    LastAdmin = 301,
+}
+
+/// Role registry submodule
+#[odra::module]
+pub struct AccessControl {
+    /// Role membership: (role, account) -> has role
+    roles: Mapping<(Role, Address), bool>,
+    /// Number of accounts holding each role
+    member_counts: Mapping<Role, u32>,
+}
+
+#[odra::module]
+impl AccessControl {
+    /// Check if an account holds a role
+    pub fn has_role(&self, role: Role, account: Address) -> bool {
+        self.roles.get(&(role, account)).unwrap_or(false)
+    }
+
+    /// Get the number of accounts holding a role
+    pub fn get_role_member_count(&self, role: Role) -> u32 {
+        self.member_counts.get(&role).unwrap_or_default()
+    }
+
+    /// Grant a role to an account (admin only)
+    pub fn grant_role(&mut self, role: Role, account: Address) {
+        self.require_role(ADMIN_ROLE, AccessError::MissingRole);
+        self.grant(role, account);
+    }
+
+    /// Revoke a role from an account (admin only)
+    pub fn revoke_role(&mut self, role: Role, account: Address) {
+        self.require_role(ADMIN_ROLE, AccessError::MissingRole);
+        self.revoke(role, account);
+    }
+
+    /// Give up one of the caller's roles
+    pub fn renounce_role(&mut self, role: Role) {
+        let caller = self.env().caller();
+        self.revoke(role, caller);
+    }
+}
+
+impl AccessControl {
+    /// Grant a role without checking the caller (used during init)
+    pub fn grant(&mut self, role: Role, account: Address) {
+        if self.has_role(role, account) {
+            return;
+        }
+
+        self.roles.set(&(role, account), true);
+        self.member_counts.set(&role, self.get_role_member_count(role) + 1);
+
+        self.env().emit_event(RoleGranted {
+            role,
+            account,
+            sender: self.env().caller(),
+        });
+    }
+
+    /// Revoke a role without checking the caller
+    pub fn revoke(&mut self, role: Role, account: Address) {
+        if !self.has_role(role, account) {
+            return;
+        }
+
+        let count = self.get_role_member_count(role);
+        if role == ADMIN_ROLE && count == 1 {
+            self.env().revert(AccessError::LastAdmin);
+        }
+
+        self.roles.set(&(role, account), false);
+        self.member_counts.set(&role, count - 1);
+
+        self.env().emit_event(RoleRevoked {
+            role,
+            account,
+            sender: self.env().caller(),
+        });
+    }
+
+    /// Revert with `error` unless the caller holds `role`
+    pub fn require_role<E: Into<OdraError>>(&self, role: Role, error: E) {
+        if !self.has_role(role, self.env().caller()) {
+            self.env().revert(error);
+        }
+    }
+
+    /// Revert with `error` unless the caller holds at least one of `roles`
+    pub fn require_any_role<E: Into<OdraError>>(&self, roles: &[Role], error: E) {
+        let caller = self.env().caller();
+        if !roles.iter().any(|role| self.has_role(*role, caller)) {
+            self.env().revert(error);
+        }
+    }
+}
+
+/// Emitted when a role is granted
+#[odra::event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Address,
+    pub sender: Address,
+}
+
+/// Emitted when a role is revoked or renounced
+#[odra::event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Address,
+    pub sender: Address,
+}
@@ -0,0 +1,51 @@
+//! CEP-4626 Asynchronous Redemption Extension
+//!
+//! A Casper adaptation of ERC-7540's asynchronous redeem flow, for vaults
+//! whose assets cannot be paid out in the same transaction (e.g. staked CSPR
+//! that must unbond first).
+//!
+//! A redemption goes through three states:
+//! 1. **Pending** - `request_redeem` burned the shares and locked in the
+//!    assets owed; the assets are not yet available
+//! 2. **Claimable** - the assets are available to the request's controller
+//! 3. **Claimed** - `claim_redeem` paid the assets out
+//!
+//! Requests are identified by a `u64` request id. Shares are burned when the
+//! request is made, so the pending/claimable views report the assets owed.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+
+/// CEP-4626 Asynchronous Redemption Interface
+pub trait Cep4626AsyncRedeem {
+    /// Burns `owner`'s shares and opens a redemption request for `controller`
+    ///
+    /// - MUST emit the RedeemRequest event
+    /// - MUST support a flow where the caller spends an allowance on `owner`'s shares
+    /// - MUST revert if all of shares cannot be requested
+    ///
+    /// Returns the id of the new request
+    fn request_redeem(&mut self, shares: U256, controller: Address, owner: Address) -> u64;
+
+    /// Returns the assets of a request that are not yet claimable
+    ///
+    /// - MUST NOT include assets that are claimable or claimed
+    /// - MUST return 0 for unknown requests
+    /// - MUST NOT revert
+    fn pending_redeem_request(&self, request_id: u64) -> U256;
+
+    /// Returns the assets of a request that its controller can claim now
+    ///
+    /// - MUST NOT include assets that are pending or claimed
+    /// - MUST return 0 for unknown requests
+    /// - MUST NOT revert
+    fn claimable_redeem_request(&self, request_id: u64) -> U256;
+
+    /// Pays a claimable request out to its controller (caller only)
+    ///
+    /// - MUST emit the RedeemClaimed event
+    /// - MUST revert unless the whole request is claimable
+    ///
+    /// Returns the amount of assets paid
+    fn claim_redeem(&mut self, request_id: u64) -> U256;
+}
@@ -0,0 +1,357 @@
+//! Reusable CEP-4626 vault building blocks
+//!
+//! `Cep4626Base` is a submodule holding a vault's share ledger. The functions
+//! in this module implement the standard conversion math, limits and the
+//! deposit/mint/withdraw/redeem flows once, on top of `Cep4626Hooks`.
+//!
+//! A vault implements `Cep4626Hooks` to point the flows at its share ledger
+//! (usually an embedded `SubModule<Cep4626Base>`, or a separate share token)
+//! and to move its underlying asset in the `before_*`/`after_*` hooks, then
+//! forwards its `Cep4626Vault` methods to the functions here.
+//!
+//! Vaults may charge an entry fee on deposits and an exit fee on withdrawals,
+//! in bps of the assets moved. The previews include the fees, as the standard
+//! requires, and the fee is handed to the vault's `pay_*_fee` hooks.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::module::Module;
+use odra::OdraError;
+use super::events::{Deposit, FeesUpdated, SharePriceUpdated, Withdraw};
+use super::vault::helpers;
+
+/// Highest entry or exit fee a vault may charge, in bps
+pub const MAX_FEE_BPS: u32 = 1_000; // 10%
+
+/// Shares priced by `SharePriceUpdated` (one whole share at 18 decimals)
+pub const SHARE_PRICE_UNIT: u128 = 1_000_000_000_000_000_000; // 1e18
+
+/// Share ledger shared by CEP-4626 vaults
+#[odra::module]
+pub struct Cep4626Base {
+    /// Total shares outstanding
+    total_shares: Var<U256>,
+    /// Shares held per owner
+    shares: Mapping<Address, U256>,
+    /// Fee charged on deposited assets, in bps
+    entry_fee_bps: Var<u32>,
+    /// Fee charged on withdrawn assets, in bps
+    exit_fee_bps: Var<u32>,
+    /// Address receiving entry and exit fees
+    fee_recipient: Var<Option<Address>>,
+}
+
+#[odra::module]
+impl Cep4626Base {
+    /// Get the total shares outstanding
+    pub fn total_shares(&self) -> U256 {
+        self.total_shares.get_or_default()
+    }
+
+    /// Get the shares held by `owner`
+    pub fn shares_of(&self, owner: Address) -> U256 {
+        self.shares.get(&owner).unwrap_or_default()
+    }
+
+    /// Mint `amount` shares to `to`
+    pub fn mint_shares(&mut self, to: Address, amount: U256) {
+        self.total_shares.set(self.total_shares() + amount);
+        self.shares.set(&to, self.shares_of(to) + amount);
+    }
+
+    /// Burn `amount` shares from `from` (the caller checks the balance)
+    pub fn burn_shares(&mut self, from: Address, amount: U256) {
+        self.shares.set(&from, self.shares_of(from) - amount);
+        self.total_shares.set(self.total_shares() - amount);
+    }
+
+    /// Move `amount` shares from `from` to `to` (the caller checks the balance)
+    pub fn transfer_shares(&mut self, from: Address, to: Address, amount: U256) {
+        self.shares.set(&from, self.shares_of(from) - amount);
+        self.shares.set(&to, self.shares_of(to) + amount);
+    }
+
+    /// Get the entry fee, in bps
+    pub fn entry_fee_bps(&self) -> u32 {
+        self.entry_fee_bps.get_or_default()
+    }
+
+    /// Get the exit fee, in bps
+    pub fn exit_fee_bps(&self) -> u32 {
+        self.exit_fee_bps.get_or_default()
+    }
+
+    /// Get the fee recipient, if fees are enabled
+    pub fn fee_recipient(&self) -> Option<Address> {
+        self.fee_recipient.get_or_default()
+    }
+
+    /// Set the entry and exit fees (the caller checks access and `MAX_FEE_BPS`)
+    pub fn set_fees(&mut self, entry_fee_bps: u32, exit_fee_bps: u32, recipient: Address) {
+        self.entry_fee_bps.set(entry_fee_bps);
+        self.exit_fee_bps.set(exit_fee_bps);
+        self.fee_recipient.set(Some(recipient));
+
+        self.env().emit_event(FeesUpdated {
+            entry_fee_bps,
+            exit_fee_bps,
+            recipient,
+        });
+    }
+}
+
+/// Vault-specific behaviour plugged into the shared CEP-4626 flows
+///
+/// The `before_*`/`after_*` hooks default to doing nothing, and fees default
+/// to zero.
+pub trait Cep4626Hooks {
+    /// Total underlying assets managed by the vault
+    fn vault_total_assets(&self) -> U256;
+
+    /// Total shares outstanding
+    fn vault_total_shares(&self) -> U256;
+
+    /// Shares held by `owner`
+    fn vault_shares_of(&self, owner: Address) -> U256;
+
+    /// Mint `shares` to `to`
+    fn mint_vault_shares(&mut self, to: Address, shares: U256);
+
+    /// Burn `shares` from `from` (the balance is already checked)
+    fn burn_vault_shares(&mut self, from: Address, shares: U256);
+
+    /// Error raised when an owner has fewer shares than a withdrawal burns,
+    /// or a deposit would mint no shares
+    fn insufficient_shares_error(&self) -> OdraError;
+
+    /// Runs before shares are minted; takes custody of the assets
+    fn before_deposit(&mut self, _caller: Address, _receiver: Address, _assets: U256, _shares: U256) {}
+
+    /// Runs after shares are minted
+    fn after_deposit(&mut self, _caller: Address, _receiver: Address, _assets: U256, _shares: U256) {}
+
+    /// Runs before shares are burned; checks the caller may spend them
+    fn before_withdraw(
+        &mut self,
+        _caller: Address,
+        _receiver: Address,
+        _owner: Address,
+        _assets: U256,
+        _shares: U256,
+    ) {}
+
+    /// Runs after shares are burned; releases the assets
+    fn after_withdraw(
+        &mut self,
+        _caller: Address,
+        _receiver: Address,
+        _owner: Address,
+        _assets: U256,
+        _shares: U256,
+    ) {}
+
+    /// Fee charged on deposited assets, in bps
+    fn entry_fee_bps(&self) -> u32 {
+        0
+    }
+
+    /// Fee charged on withdrawn assets, in bps
+    fn exit_fee_bps(&self) -> u32 {
+        0
+    }
+
+    /// Collect the entry fee from the depositing `payer`
+    fn pay_entry_fee(&mut self, _payer: Address, _fee: U256) {}
+
+    /// Pay the exit fee out of the assets released for `owner`
+    fn pay_exit_fee(&mut self, _owner: Address, _fee: U256) {}
+}
+
+// ============================================
+// Conversion and Limits
+// ============================================
+
+/// Convert assets to shares at the vault's current exchange rate
+pub fn convert_to_shares<V: Cep4626Hooks>(vault: &V, assets: U256) -> U256 {
+    helpers::calculate_shares(assets, vault.vault_total_assets(), vault.vault_total_shares())
+}
+
+/// Convert shares to assets at the vault's current exchange rate
+pub fn convert_to_assets<V: Cep4626Hooks>(vault: &V, shares: U256) -> U256 {
+    helpers::calculate_assets(shares, vault.vault_total_assets(), vault.vault_total_shares())
+}
+
+/// Shares minted by depositing `assets`, entry fee included (rounded down)
+pub fn preview_deposit<V: Cep4626Hooks>(vault: &V, assets: U256) -> U256 {
+    let fee = helpers::fee_on_total(assets, vault.entry_fee_bps());
+    convert_to_shares(vault, assets - fee)
+}
+
+/// Assets needed to mint `shares`, entry fee included (rounded up)
+pub fn preview_mint<V: Cep4626Hooks>(vault: &V, shares: U256) -> U256 {
+    let assets = mint_cost(vault, shares);
+    assets + helpers::fee_on_raw(assets, vault.entry_fee_bps())
+}
+
+/// Shares burned to withdraw `assets`, exit fee included (rounded up)
+pub fn preview_withdraw<V: Cep4626Hooks>(vault: &V, assets: U256) -> U256 {
+    let fee = helpers::fee_on_raw(assets, vault.exit_fee_bps());
+    helpers::calculate_shares_up(assets + fee, vault.vault_total_assets(), vault.vault_total_shares())
+}
+
+/// Assets received for redeeming `shares`, exit fee included (rounded down)
+pub fn preview_redeem<V: Cep4626Hooks>(vault: &V, shares: U256) -> U256 {
+    let assets = convert_to_assets(vault, shares);
+    assets - helpers::fee_on_total(assets, vault.exit_fee_bps())
+}
+
+/// Assets `owner` could withdraw if the vault set no further limits
+pub fn max_withdraw<V: Cep4626Hooks>(vault: &V, owner: Address) -> U256 {
+    preview_redeem(vault, vault.vault_shares_of(owner))
+}
+
+/// Assets one `SHARE_PRICE_UNIT` of shares converts to
+pub fn share_price<V: Cep4626Hooks>(vault: &V) -> U256 {
+    convert_to_assets(vault, U256::from(SHARE_PRICE_UNIT))
+}
+
+/// Shares `owner` could redeem if the vault set no further limits
+pub fn max_redeem<V: Cep4626Hooks>(vault: &V, owner: Address) -> U256 {
+    vault.vault_shares_of(owner)
+}
+
+// ============================================
+// Deposit/Withdraw Flows
+// ============================================
+
+/// Deposit `assets` for shares minted to `receiver`
+///
+/// Returns the shares minted
+pub fn deposit<V: Cep4626Hooks + Module>(vault: &mut V, assets: U256, receiver: Address) -> U256 {
+    // Priced before the hooks move any assets
+    let shares = preview_deposit(vault, assets);
+    let fee = helpers::fee_on_total(assets, vault.entry_fee_bps());
+    deposit_exact(vault, receiver, assets - fee, fee, shares);
+    shares
+}
+
+/// Mint `shares` to `receiver` for the assets they are worth
+///
+/// Returns the assets deposited
+pub fn mint<V: Cep4626Hooks + Module>(vault: &mut V, shares: U256, receiver: Address) -> U256 {
+    let assets = mint_cost(vault, shares);
+    let fee = helpers::fee_on_raw(assets, vault.entry_fee_bps());
+    deposit_exact(vault, receiver, assets, fee, shares);
+    assets + fee
+}
+
+/// Withdraw `assets` to `receiver` by burning `owner`'s shares
+///
+/// Returns the shares burned
+pub fn withdraw<V: Cep4626Hooks + Module>(
+    vault: &mut V,
+    assets: U256,
+    receiver: Address,
+    owner: Address,
+) -> U256 {
+    let shares = preview_withdraw(vault, assets);
+    let fee = helpers::fee_on_raw(assets, vault.exit_fee_bps());
+    withdraw_exact(vault, receiver, owner, assets, fee, shares);
+    shares
+}
+
+/// Redeem `shares` of `owner` for assets sent to `receiver`
+///
+/// Returns the assets withdrawn
+pub fn redeem<V: Cep4626Hooks + Module>(
+    vault: &mut V,
+    shares: U256,
+    receiver: Address,
+    owner: Address,
+) -> U256 {
+    let gross = convert_to_assets(vault, shares);
+    let fee = helpers::fee_on_total(gross, vault.exit_fee_bps());
+    withdraw_exact(vault, receiver, owner, gross - fee, fee, shares);
+    gross - fee
+}
+
+/// Run the deposit hooks around minting an already priced `shares` amount
+///
+/// `assets` is what the vault keeps and `fee` is collected on top of it.
+pub fn deposit_exact<V: Cep4626Hooks + Module>(
+    vault: &mut V,
+    receiver: Address,
+    assets: U256,
+    fee: U256,
+    shares: U256,
+) {
+    // A deposit worth less than one share would be absorbed by existing holders
+    if shares == U256::zero() {
+        vault.env().revert(vault.insufficient_shares_error());
+    }
+
+    let caller = vault.env().caller();
+    vault.before_deposit(caller, receiver, assets, shares);
+    vault.mint_vault_shares(receiver, shares);
+    vault.after_deposit(caller, receiver, assets, shares);
+    if fee > U256::zero() {
+        vault.pay_entry_fee(caller, fee);
+    }
+
+    vault.env().emit_event(Deposit {
+        sender: caller,
+        owner: receiver,
+        assets: assets + fee,
+        shares,
+    });
+    emit_share_price(vault);
+}
+
+/// Run the withdraw hooks around burning an already priced `shares` amount
+///
+/// `assets` goes to `receiver` and `fee` is paid out on top of it.
+pub fn withdraw_exact<V: Cep4626Hooks + Module>(
+    vault: &mut V,
+    receiver: Address,
+    owner: Address,
+    assets: U256,
+    fee: U256,
+    shares: U256,
+) {
+    let caller = vault.env().caller();
+    vault.before_withdraw(caller, receiver, owner, assets, shares);
+    if vault.vault_shares_of(owner) < shares {
+        vault.env().revert(vault.insufficient_shares_error());
+    }
+    vault.burn_vault_shares(owner, shares);
+    vault.after_withdraw(caller, receiver, owner, assets, shares);
+    if fee > U256::zero() {
+        vault.pay_exit_fee(owner, fee);
+    }
+
+    vault.env().emit_event(Withdraw {
+        sender: caller,
+        receiver,
+        owner,
+        assets,
+        shares,
+    });
+    emit_share_price(vault);
+}
+
+/// Emit `SharePriceUpdated` with the vault's current totals
+///
+/// The deposit and withdraw flows call this; vaults also call it wherever
+/// else their assets or supply change (rewards, losses, ...).
+pub fn emit_share_price<V: Cep4626Hooks + Module>(vault: &V) {
+    vault.env().emit_event(SharePriceUpdated {
+        total_assets: vault.vault_total_assets(),
+        total_shares: vault.vault_total_shares(),
+        price: share_price(vault),
+    });
+}
+
+/// Assets needed to mint `shares` before any entry fee (rounded up)
+fn mint_cost<V: Cep4626Hooks>(vault: &V, shares: U256) -> U256 {
+    helpers::calculate_assets_up(shares, vault.vault_total_assets(), vault.vault_total_shares())
+}
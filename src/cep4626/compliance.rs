@@ -0,0 +1,143 @@
+//! CEP-4626 compliance checks shared by vault tests
+//!
+//! A vault's tests wrap the deployed vault in `VaultUnderTest` and call
+//! `check_compliance`, which walks one user through deposit, mint, withdraw
+//! and redeem and asserts the standard's properties along the way:
+//! - conversions are monotonic and never round in the user's favor
+//! - every preview equals what the matching call actually does
+//! - the amounts reported by `max_*` can actually be used
+//! - a deposit/redeem round trip never returns more than was put in
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+
+/// A deployed vault driven through its CEP-4626 entry points
+pub trait VaultUnderTest {
+    fn total_assets(&self) -> U256;
+    fn total_supply(&self) -> U256;
+    fn share_balance(&self, owner: Address) -> U256;
+    fn convert_to_shares(&self, assets: U256) -> U256;
+    fn convert_to_assets(&self, shares: U256) -> U256;
+    fn max_deposit(&self, receiver: Address) -> U256;
+    fn max_mint(&self, receiver: Address) -> U256;
+    fn max_withdraw(&self, owner: Address) -> U256;
+    fn max_redeem(&self, owner: Address) -> U256;
+    fn preview_deposit(&self, assets: U256) -> U256;
+    fn preview_mint(&self, shares: U256) -> U256;
+    fn preview_withdraw(&self, assets: U256) -> U256;
+    fn preview_redeem(&self, shares: U256) -> U256;
+
+    /// Deposit `assets` as `user` (funding the user as needed)
+    fn deposit(&mut self, user: Address, assets: U256) -> U256;
+
+    /// Mint `shares` to `user` (funding the user as needed)
+    fn mint(&mut self, user: Address, shares: U256) -> U256;
+
+    /// Withdraw `assets` of `user`'s to `user`
+    fn withdraw(&mut self, user: Address, assets: U256) -> U256;
+
+    /// Redeem `shares` of `user`'s to `user`
+    fn redeem(&mut self, user: Address, shares: U256) -> U256;
+}
+
+/// Run every check against `vault` with a `user` holding no shares yet
+///
+/// `amount` is the asset amount deposited; it must clear the vault's
+/// minimum deposit and be large enough to split into a few shares.
+pub fn check_compliance<V: VaultUnderTest>(vault: &mut V, user: Address, amount: U256) {
+    assert_eq!(vault.share_balance(user), U256::zero(), "user must start without shares");
+
+    check_conversions(vault, amount);
+    let deposited = check_deposit(vault, user, amount);
+    let minted_cost = check_mint(vault, user);
+    let withdrawn = check_withdraw(vault, user);
+    let redeemed = check_redeem_all(vault, user);
+
+    assert!(
+        withdrawn + redeemed <= deposited + minted_cost,
+        "round trip paid out {} for {} put in",
+        withdrawn + redeemed,
+        deposited + minted_cost
+    );
+}
+
+/// Conversions are monotonic and round trips never gain
+fn check_conversions<V: VaultUnderTest>(vault: &V, amount: U256) {
+    let amounts = [
+        U256::zero(),
+        U256::one(),
+        amount / 3,
+        amount / 2,
+        amount,
+        amount * 2,
+    ];
+    for pair in amounts.windows(2) {
+        assert!(vault.convert_to_shares(pair[0]) <= vault.convert_to_shares(pair[1]));
+        assert!(vault.convert_to_assets(pair[0]) <= vault.convert_to_assets(pair[1]));
+    }
+    for assets in amounts {
+        let shares = vault.convert_to_shares(assets);
+        assert!(vault.convert_to_assets(shares) <= assets, "converting {} back gained", assets);
+        assert!(vault.preview_redeem(vault.preview_deposit(assets)) <= assets);
+        assert!(vault.preview_mint(shares) >= vault.preview_redeem(shares));
+        assert!(vault.preview_withdraw(assets) >= vault.preview_deposit(assets));
+    }
+}
+
+/// `deposit` mints exactly `preview_deposit` within `max_deposit`
+fn check_deposit<V: VaultUnderTest>(vault: &mut V, user: Address, assets: U256) -> U256 {
+    assert!(assets <= vault.max_deposit(user), "max_deposit below {}", assets);
+
+    let preview = vault.preview_deposit(assets);
+    let supply_before = vault.total_supply();
+    let shares = vault.deposit(user, assets);
+
+    assert_eq!(shares, preview, "deposit minted a different amount than previewed");
+    assert_eq!(vault.share_balance(user), shares);
+    assert_eq!(vault.total_supply(), supply_before + shares);
+    assets
+}
+
+/// `mint` mints exactly the requested shares for `preview_mint` assets
+fn check_mint<V: VaultUnderTest>(vault: &mut V, user: Address) -> U256 {
+    let shares = vault.share_balance(user) / 2;
+    assert!(shares <= vault.max_mint(user), "max_mint below {}", shares);
+
+    let preview = vault.preview_mint(shares);
+    let balance_before = vault.share_balance(user);
+    let assets = vault.mint(user, shares);
+
+    assert_eq!(assets, preview, "mint charged a different amount than previewed");
+    assert_eq!(vault.share_balance(user), balance_before + shares, "mint minted other than requested");
+    assets
+}
+
+/// `withdraw` burns exactly `preview_withdraw` within `max_withdraw`
+fn check_withdraw<V: VaultUnderTest>(vault: &mut V, user: Address) -> U256 {
+    let max = vault.max_withdraw(user);
+    assert!(max <= vault.convert_to_assets(vault.share_balance(user)));
+
+    let assets = max / 4;
+    let preview = vault.preview_withdraw(assets);
+    let balance_before = vault.share_balance(user);
+    let shares = vault.withdraw(user, assets);
+
+    assert_eq!(shares, preview, "withdraw burned a different amount than previewed");
+    assert_eq!(vault.share_balance(user), balance_before - shares);
+    assets
+}
+
+/// `redeem` pays exactly `preview_redeem`, and `max_redeem` can be redeemed in full
+fn check_redeem_all<V: VaultUnderTest>(vault: &mut V, user: Address) -> U256 {
+    let shares = vault.max_redeem(user);
+    assert_eq!(shares, vault.share_balance(user), "max_redeem hides redeemable shares");
+
+    let preview = vault.preview_redeem(shares);
+    let assets = vault.redeem(user, shares);
+
+    assert_eq!(assets, preview, "redeem paid a different amount than previewed");
+    assert_eq!(vault.share_balance(user), U256::zero());
+    assert_eq!(vault.max_redeem(user), U256::zero());
+    assert_eq!(vault.max_withdraw(user), U256::zero());
+    assets
+}
@@ -30,3 +30,53 @@ pub struct Withdraw {
     /// Amount of shares burned
     pub shares: U256,
 }
+
+/// Event emitted when the vault's entry and exit fees are updated
+#[odra::event]
+pub struct FeesUpdated {
+    /// Fee charged on deposited assets, in bps
+    pub entry_fee_bps: u32,
+    /// Fee charged on withdrawn assets, in bps
+    pub exit_fee_bps: u32,
+    /// Address receiving the fees
+    pub recipient: Address,
+}
+
+/// Event emitted when shares are burned into an asynchronous redemption request
+#[odra::event]
+pub struct RedeemRequest {
+    /// Address that controls (and later claims) the request
+    pub controller: Address,
+    /// Address that owned the shares
+    pub owner: Address,
+    /// Id of the new request
+    pub request_id: u64,
+    /// Address that called the request function
+    pub sender: Address,
+    /// Amount of shares burned
+    pub shares: U256,
+    /// Amount of assets owed to the request
+    pub assets: U256,
+}
+
+/// Event emitted when an asynchronous redemption request is claimed
+#[odra::event]
+pub struct RedeemClaimed {
+    /// Address that controlled the request and received the assets
+    pub controller: Address,
+    /// Id of the claimed request
+    pub request_id: u64,
+    /// Amount of assets paid
+    pub assets: U256,
+}
+
+/// Event emitted whenever the vault's total assets or total shares change
+#[odra::event]
+pub struct SharePriceUpdated {
+    /// Total assets managed by the vault
+    pub total_assets: U256,
+    /// Total shares outstanding
+    pub total_shares: U256,
+    /// Assets per 1e18 shares
+    pub price: U256,
+}
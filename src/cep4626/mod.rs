@@ -11,6 +11,12 @@
 
 pub mod vault;
 pub mod events;
+pub mod base;
+pub mod async_redeem;
+#[cfg(test)]
+pub mod compliance;
 
 pub use vault::Cep4626Vault;
+pub use async_redeem::Cep4626AsyncRedeem;
+pub use base::{Cep4626Base, Cep4626Hooks};
 pub use events::*;
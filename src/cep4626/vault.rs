@@ -176,33 +176,187 @@ pub trait Cep4626Vault {
 }
 
 /// Helper functions for implementing CEP-4626 vaults
+/// 
+/// Conversions price against one virtual share and one virtual asset on top
+/// of the vault's totals. The empty vault still starts at 1:1, but a first
+/// depositor can no longer inflate the share price by donating assets: the
+/// virtual share captures part of any donation, so the attack costs the
+/// attacker more than it takes from later depositors.
 pub mod helpers {
     use super::*;
+    use crate::math::BPS_DENOMINATOR;
+    
+    /// Virtual shares added to the total supply in conversions
+    pub const VIRTUAL_SHARES: u64 = 1;
+    
+    /// Virtual assets added to the total assets in conversions
+    pub const VIRTUAL_ASSETS: u64 = 1;
     
-    /// Calculate shares from assets using the vault's exchange rate
+    /// Calculate shares from assets using the vault's exchange rate, rounding down
     /// 
-    /// Formula: shares = (assets * total_shares) / total_assets
-    /// If total_shares == 0, returns assets (1:1 initial rate)
+    /// Formula: shares = assets * (total_shares + 1) / (total_assets + 1)
     pub fn calculate_shares(assets: U256, total_assets: U256, total_shares: U256) -> U256 {
-        if total_shares == U256::zero() || total_assets == U256::zero() {
-            // Initial deposit: 1:1 ratio
-            assets
-        } else {
-            // shares = (assets * total_shares) / total_assets
-            (assets * total_shares) / total_assets
-        }
+        mul_div(assets, total_shares + U256::from(VIRTUAL_SHARES), total_assets + U256::from(VIRTUAL_ASSETS))
     }
     
-    /// Calculate assets from shares using the vault's exchange rate
+    /// Calculate shares from assets using the vault's exchange rate, rounding up
+    pub fn calculate_shares_up(assets: U256, total_assets: U256, total_shares: U256) -> U256 {
+        mul_div_up(assets, total_shares + U256::from(VIRTUAL_SHARES), total_assets + U256::from(VIRTUAL_ASSETS))
+    }
+    
+    /// Calculate assets from shares using the vault's exchange rate, rounding down
     /// 
-    /// Formula: assets = (shares * total_assets) / total_shares
-    /// If total_shares == 0, returns 0
+    /// Formula: assets = shares * (total_assets + 1) / (total_shares + 1)
     pub fn calculate_assets(shares: U256, total_assets: U256, total_shares: U256) -> U256 {
-        if total_shares == U256::zero() {
-            U256::zero()
+        mul_div(shares, total_assets + U256::from(VIRTUAL_ASSETS), total_shares + U256::from(VIRTUAL_SHARES))
+    }
+    
+    /// Calculate assets from shares using the vault's exchange rate, rounding up
+    pub fn calculate_assets_up(shares: U256, total_assets: U256, total_shares: U256) -> U256 {
+        mul_div_up(shares, total_assets + U256::from(VIRTUAL_ASSETS), total_shares + U256::from(VIRTUAL_SHARES))
+    }
+    
+    /// `a * b / denominator`, rounded down
+    pub fn mul_div(a: U256, b: U256, denominator: U256) -> U256 {
+        (a * b) / denominator
+    }
+    
+    /// `a * b / denominator`, rounded up
+    pub fn mul_div_up(a: U256, b: U256, denominator: U256) -> U256 {
+        let product = a * b;
+        let quotient = product / denominator;
+        if product % denominator == U256::zero() {
+            quotient
         } else {
-            // assets = (shares * total_assets) / total_shares
-            (shares * total_assets) / total_shares
+            quotient + U256::one()
+        }
+    }
+    
+    /// Fee to add on top of `assets` at `fee_bps` (rounded up)
+    pub fn fee_on_raw(assets: U256, fee_bps: u32) -> U256 {
+        mul_div_up(assets, U256::from(fee_bps), U256::from(BPS_DENOMINATOR))
+    }
+    
+    /// Fee part of `assets` that already includes a fee at `fee_bps` (rounded up)
+    pub fn fee_on_total(assets: U256, fee_bps: u32) -> U256 {
+        mul_div_up(assets, U256::from(fee_bps), U256::from(fee_bps) + U256::from(BPS_DENOMINATOR))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::helpers::{
+        calculate_assets, calculate_assets_up, calculate_shares, calculate_shares_up, fee_on_raw, fee_on_total,
+    };
+    use odra::casper_types::U256;
+    
+    /// Vault states covering 1:1, appreciated, depreciated and lopsided rates
+    fn vault_states() -> Vec<(U256, U256)> {
+        let values = [0u64, 1, 2, 3, 7, 10, 99, 1_000, 1_001, 123_457, 1_000_000_007];
+        let mut states = Vec::new();
+        for total_assets in values {
+            for total_shares in values {
+                states.push((U256::from(total_assets), U256::from(total_shares)));
+            }
+        }
+        states
+    }
+    
+    fn amounts() -> Vec<U256> {
+        [0u64, 1, 2, 3, 5, 17, 100, 999, 1_000, 65_537, 10_000_019]
+            .iter()
+            .map(|amount| U256::from(*amount))
+            .collect()
+    }
+    
+    #[test]
+    fn test_deposit_then_redeem_never_returns_more_than_deposited() {
+        for (total_assets, total_shares) in vault_states() {
+            for assets in amounts() {
+                let shares = calculate_shares(assets, total_assets, total_shares);
+                let back = calculate_assets(shares, total_assets + assets, total_shares + shares);
+                assert!(back <= assets, "deposit {} at ({}, {}) redeemed for {}", assets, total_assets, total_shares, back);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_mint_costs_at_least_what_the_shares_redeem_for() {
+        for (total_assets, total_shares) in vault_states() {
+            for shares in amounts() {
+                let cost = calculate_assets_up(shares, total_assets, total_shares);
+                let back = calculate_assets(shares, total_assets + cost, total_shares + shares);
+                assert!(back <= cost, "mint {} at ({}, {}) cost {} redeemed for {}", shares, total_assets, total_shares, cost, back);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_withdraw_burns_at_least_what_the_assets_buy() {
+        for (total_assets, total_shares) in vault_states() {
+            for assets in amounts() {
+                let burned = calculate_shares_up(assets, total_assets, total_shares);
+                let bought = calculate_shares(assets, total_assets, total_shares);
+                assert!(burned >= bought);
+                // Never cheaper than the exact (fractional) price
+                let exact_numerator = assets * (total_shares + U256::one());
+                assert!(burned * (total_assets + U256::one()) >= exact_numerator);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_round_up_differs_from_round_down_only_on_remainders() {
+        assert_eq!(calculate_shares_up(U256::from(10), U256::from(2), U256::from(0)), U256::from(4));
+        assert_eq!(calculate_shares(U256::from(10), U256::from(2), U256::from(0)), U256::from(3));
+        assert_eq!(calculate_assets_up(U256::from(9), U256::from(2), U256::from(2)), U256::from(9));
+    }
+    
+    #[test]
+    fn test_empty_vault_starts_at_one_to_one() {
+        assert_eq!(calculate_shares(U256::from(1_000), U256::zero(), U256::zero()), U256::from(1_000));
+        assert_eq!(calculate_assets(U256::from(1_000), U256::from(1_000), U256::from(1_000)), U256::from(1_000));
+    }
+    
+    #[test]
+    fn test_first_depositor_donation_attack_is_unprofitable() {
+        // Attacker deposits 1 wei for 1 share, then donates 1,000,000 to the vault
+        let attacker_shares = calculate_shares(U256::from(1), U256::zero(), U256::zero());
+        assert_eq!(attacker_shares, U256::from(1));
+        let donation = U256::from(1_000_000);
+        let mut total_assets = U256::from(1) + donation;
+        let mut total_shares = attacker_shares;
+        
+        // Without the virtual share this rounded to zero and the victim's
+        // deposit went to the attacker; now deposits that small mint nothing
+        // and the vault flows revert
+        assert_eq!(calculate_shares(U256::from(400_000), total_assets, total_shares), U256::zero());
+        
+        let victim_deposit = U256::from(2_000_000);
+        let victim_shares = calculate_shares(victim_deposit, total_assets, total_shares);
+        assert!(victim_shares > U256::zero());
+        total_assets = total_assets + victim_deposit;
+        total_shares = total_shares + victim_shares;
+        
+        // The attacker gets back less than they put in
+        let attacker_out = calculate_assets(attacker_shares, total_assets, total_shares);
+        assert!(attacker_out < U256::from(1) + donation);
+    }
+    
+    #[test]
+    fn test_fee_on_total_recovers_fee_on_raw() {
+        for fee_bps in [0u32, 1, 30, 100, 1_000] {
+            for assets in amounts() {
+                let fee = fee_on_raw(assets, fee_bps);
+                let total = assets + fee;
+                // Splitting the grossed-up amount never charges more than adding the fee did,
+                // so the caller always keeps at least `assets`
+                assert!(fee_on_total(total, fee_bps) <= fee);
+                assert!(total - fee_on_total(total, fee_bps) >= assets);
+            }
         }
+        assert_eq!(fee_on_raw(U256::from(10_000), 100), U256::from(100));
+        assert_eq!(fee_on_total(U256::from(10_100), 100), U256::from(100));
+        assert_eq!(fee_on_raw(U256::from(1), 1), U256::from(1));
     }
 }
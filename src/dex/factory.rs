@@ -4,11 +4,18 @@
 //! - Creating new trading pairs
 //! - Managing pair registry
 //! - Setting protocol fees
+//! - Guardian-controlled per-pair risk limits
+//! - Registering the incentive contracts pairs notify of LP balance changes
+//! - Rescuing tokens sent to the factory by mistake
 use odra::prelude::*;
+use odra::casper_types::U256;
 use odra::ContractRef;
 use crate::errors::DexError;
-use crate::events::PairCreated;
-use super::pair::PairFactoryContractRef;
+use crate::events::{IncentiveHooksUpdated, PairCreated, TokenRescued};
+use crate::token::Cep18TokenContractRef;
+use crate::incentives::incentive_manager::IncentiveManagerContractRef;
+use crate::incentives::lp_rewards_distributor::LpRewardsDistributorContractRef;
+use super::pair::{PairContractRef, PairFactoryContractRef};
 
 /// Factory contract for creating and managing pairs
 #[odra::module]
@@ -17,6 +24,8 @@ pub struct Factory {
     fee_to: Var<Option<Address>>,
     /// Fee setter address (admin)
     fee_to_setter: Var<Address>,
+    /// Guardian address allowed to set per-pair risk limits
+    guardian: Var<Address>,
     /// Address of the Pair Factory contract
     pair_factory: Var<Address>,
     /// Mapping from token pair to pair address
@@ -26,6 +35,12 @@ pub struct Factory {
     all_pairs: Mapping<u32, Address>,
     /// Total number of pairs
     all_pairs_length: Var<u32>,
+    /// Tokens that are part of any pair's reserves
+    pair_tokens: Mapping<Address, bool>,
+    /// LpRewardsDistributor that pairs sync on LP balance changes
+    lp_rewards_distributor: Var<Option<Address>>,
+    /// IncentiveManager that pairs notify on LP balance changes
+    incentive_manager: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -33,6 +48,7 @@ impl Factory {
     /// Initialize the factory with the fee setter address and pair factory address
     pub fn init(&mut self, fee_to_setter: Address, pair_factory: Address) {
         self.fee_to_setter.set(fee_to_setter);
+        self.guardian.set(fee_to_setter);
         self.pair_factory.set(pair_factory);
         self.fee_to.set(None);
         self.all_pairs_length.set(0);
@@ -48,6 +64,11 @@ impl Factory {
         self.fee_to_setter.get_or_revert_with(DexError::Unauthorized)
     }
 
+    /// Get the guardian address
+    pub fn guardian(&self) -> Address {
+        self.guardian.get_or_revert_with(DexError::Unauthorized)
+    }
+
     /// Get the pair address for two tokens
     pub fn get_pair(&self, token_a: Address, token_b: Address) -> Option<Address> {
         let (token0, token1) = self.sort_tokens(token_a, token_b);
@@ -99,6 +120,8 @@ impl Factory {
 
         // Store the pair
         self.pairs.set(&(token0, token1), pair_address);
+        self.pair_tokens.set(&token0, true);
+        self.pair_tokens.set(&token1, true);
         
         // Add to all pairs list
         let pair_index = self.all_pairs_length.get_or_default();
@@ -146,11 +169,109 @@ impl Factory {
         self.fee_to_setter.set(new_fee_to_setter);
     }
 
+    /// Set a new guardian address
+    /// Only callable by fee_to_setter
+    pub fn set_guardian(&mut self, new_guardian: Address) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.guardian.set(new_guardian);
+    }
+
+    /// Set the max price impact (in basis points) for a pair, 0 disables the check
+    /// Only callable by the guardian
+    pub fn set_pair_max_price_impact(
+        &mut self,
+        token_a: Address,
+        token_b: Address,
+        max_price_impact_bps: u32,
+    ) {
+        let caller = self.env().caller();
+        if caller != self.guardian() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        let pair = self.get_pair(token_a, token_b)
+            .unwrap_or_revert_with(&self.env(), DexError::PairNotFound);
+        let mut pair_ref = PairContractRef::new(self.env(), pair);
+        pair_ref.set_max_price_impact(max_price_impact_bps);
+    }
+
+    /// Set the incentive contracts every pair notifies when LP balances change (None disables)
+    /// Both must have this factory configured, since a hook that rejects the
+    /// pairs would make every LP mint and transfer revert
+    /// Only callable by fee_to_setter
+    pub fn set_incentive_hooks(
+        &mut self,
+        lp_rewards_distributor: Option<Address>,
+        incentive_manager: Option<Address>,
+    ) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        let self_address = self.env().self_address();
+        if let Some(distributor) = lp_rewards_distributor {
+            if LpRewardsDistributorContractRef::new(self.env(), distributor).get_factory() != Some(self_address) {
+                self.env().revert(DexError::InvalidConfiguration);
+            }
+        }
+        if let Some(manager) = incentive_manager {
+            if IncentiveManagerContractRef::new(self.env(), manager).get_factory() != Some(self_address) {
+                self.env().revert(DexError::InvalidConfiguration);
+            }
+        }
+
+        self.lp_rewards_distributor.set(lp_rewards_distributor);
+        self.incentive_manager.set(incentive_manager);
+
+        self.env().emit_event(IncentiveHooksUpdated {
+            lp_rewards_distributor,
+            incentive_manager,
+        });
+    }
+
+    /// Get the (LpRewardsDistributor, IncentiveManager) pairs notify of LP balance changes
+    pub fn incentive_hooks(&self) -> (Option<Address>, Option<Address>) {
+        (
+            self.lp_rewards_distributor.get_or_default(),
+            self.incentive_manager.get_or_default(),
+        )
+    }
+
     /// Check if a pair exists
     pub fn pair_exists(&self, token_a: Address, token_b: Address) -> bool {
         self.get_pair(token_a, token_b).is_some()
     }
 
+    /// Check if a token is part of any pair's reserves
+    pub fn is_pair_token(&self, token: Address) -> bool {
+        self.pair_tokens.get(&token).unwrap_or(false)
+    }
+
+    /// Send tokens stuck in the factory to `to`
+    /// Only callable by fee_to_setter; tokens used by any pair cannot be rescued
+    pub fn rescue_token(&mut self, token: Address, to: Address, amount: U256) {
+        let caller = self.env().caller();
+        if caller != self.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if self.is_pair_token(token) {
+            self.env().revert(DexError::TokenNotRescuable);
+        }
+
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(to, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        self.env().emit_event(TokenRescued {
+            token,
+            to,
+            amount,
+        });
+    }
+
     // ============ Internal Functions ============
 
     /// Sort two token addresses (smaller address first)
@@ -170,12 +291,17 @@ impl Factory {
 pub trait FactoryContract {
     fn fee_to(&self) -> Option<Address>;
     fn fee_to_setter(&self) -> Address;
+    fn guardian(&self) -> Address;
     fn get_pair(&self, token_a: Address, token_b: Address) -> Option<Address>;
     fn all_pairs_at(&self, index: u32) -> Option<Address>;
     fn all_pairs_length(&self) -> u32;
     fn create_pair(&mut self, token_a: Address, token_b: Address) -> Address;
     fn set_fee_to(&mut self, fee_to: Address);
     fn set_fee_to_setter(&mut self, new_fee_to_setter: Address);
+    fn set_guardian(&mut self, new_guardian: Address);
+    fn set_pair_max_price_impact(&mut self, token_a: Address, token_b: Address, max_price_impact_bps: u32);
+    fn is_pair_token(&self, token: Address) -> bool;
+    fn rescue_token(&mut self, token: Address, to: Address, amount: U256);
 }
 
 #[cfg(test)]
@@ -231,4 +357,118 @@ mod tests {
         factory.set_fee_to(fee_recipient);
         assert_eq!(factory.fee_to(), Some(fee_recipient));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_set_guardian() {
+        let (env, mut factory) = setup();
+        let admin = env.get_account(0);
+        let guardian = env.get_account(1);
+
+        assert_eq!(factory.guardian(), admin);
+
+        env.set_caller(admin);
+        factory.set_guardian(guardian);
+        assert_eq!(factory.guardian(), guardian);
+    }
+
+    #[test]
+    fn test_only_guardian_sets_pair_max_price_impact() {
+        let (env, mut factory) = setup();
+        let admin = env.get_account(0);
+        let guardian = env.get_account(1);
+        let (token_a, token_b) = (env.get_account(2), env.get_account(3));
+
+        env.set_caller(admin);
+        factory.set_guardian(guardian);
+        assert_eq!(
+            factory.try_set_pair_max_price_impact(token_a, token_b, 100).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+
+        // The guardian gets past the role check to the pair lookup
+        env.set_caller(guardian);
+        assert_eq!(
+            factory.try_set_pair_max_price_impact(token_a, token_b, 100).unwrap_err(),
+            DexError::PairNotFound.into()
+        );
+    }
+
+    #[test]
+    fn test_rescue_token() {
+        use crate::token::{LpToken, LpTokenInitArgs};
+
+        let (env, mut factory) = setup();
+        let admin = env.get_account(0);
+        let recipient = env.get_account(1);
+
+        let mut token = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Stuck Token"),
+            symbol: String::from("STUCK"),
+        });
+        token.mint(factory.address().clone(), U256::from(500));
+
+        env.set_caller(admin);
+        factory.rescue_token(token.address().clone(), recipient, U256::from(500));
+        assert_eq!(token.balance_of(recipient), U256::from(500));
+
+        env.set_caller(recipient);
+        assert_eq!(
+            factory.try_rescue_token(token.address().clone(), recipient, U256::from(1)).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn test_incentive_hooks_must_point_at_this_factory() {
+        use crate::incentives::incentive_manager::{IncentiveManager, IncentiveManagerInitArgs};
+        use crate::incentives::lp_rewards_distributor::{LpRewardsDistributor, LpRewardsDistributorInitArgs};
+
+        let (env, mut factory) = setup();
+        let admin = env.get_account(0);
+        let other_factory = env.get_account(5);
+
+        env.set_caller(admin);
+        let mut distributor = LpRewardsDistributor::deploy(&env, LpRewardsDistributorInitArgs {
+            scspr_token_address: env.get_account(1),
+            aecto_token_address: env.get_account(2),
+            lending_pool_address: env.get_account(3),
+            reward_token_address: env.get_account(4),
+        });
+        let mut manager = IncentiveManager::deploy(&env, IncentiveManagerInitArgs {
+            gas_discount_manager_address: env.get_account(1),
+            rewards_distributor_address: distributor.address(),
+            treasury_address: admin,
+        });
+
+        env.set_caller(env.get_account(6));
+        assert_eq!(
+            factory.try_set_incentive_hooks(Some(distributor.address()), None).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+        env.set_caller(admin);
+
+        // A hook without this factory would reject every pair's notification
+        assert_eq!(
+            factory.try_set_incentive_hooks(Some(distributor.address()), None).unwrap_err(),
+            DexError::InvalidConfiguration.into()
+        );
+        distributor.set_factory(other_factory);
+        assert_eq!(
+            factory.try_set_incentive_hooks(Some(distributor.address()), None).unwrap_err(),
+            DexError::InvalidConfiguration.into()
+        );
+        distributor.set_factory(factory.address());
+        assert_eq!(
+            factory.try_set_incentive_hooks(Some(distributor.address()), Some(manager.address())).unwrap_err(),
+            DexError::InvalidConfiguration.into()
+        );
+
+        manager.set_factory(Some(factory.address()));
+        factory.set_incentive_hooks(Some(distributor.address()), Some(manager.address()));
+        assert_eq!(factory.incentive_hooks(), (Some(distributor.address()), Some(manager.address())));
+
+        // Disabling needs no wiring
+        factory.set_incentive_hooks(None, None);
+        assert_eq!(factory.incentive_hooks(), (None, None));
+    }
+}
@@ -0,0 +1,292 @@
+//! Liquidity Locker contract for the DEX
+//!
+//! Projects lock LP tokens until a timestamp to prove liquidity cannot be
+//! pulled. Each lock can be:
+//! - Extended to a later unlock time
+//! - Split into two locks with the same unlock time
+//! - Transferred to a new owner
+//! - Withdrawn by its owner once the unlock time has passed
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::errors::DexError;
+use crate::events::{
+    LiquidityLocked, LiquidityUnlocked, LockExtended, LockOwnershipTransferred, LockSplit,
+};
+use crate::token::Cep18TokenContractRef;
+
+/// A time-locked LP token position
+#[odra::odra_type]
+pub struct Lock {
+    /// Owner allowed to manage and withdraw the lock
+    pub owner: Address,
+    /// Locked LP token address
+    pub lp_token: Address,
+    /// Amount of LP tokens locked
+    pub amount: U256,
+    /// Timestamp when the lock can be withdrawn
+    pub unlock_time: u64,
+    /// Whether the lock has been withdrawn
+    pub withdrawn: bool,
+}
+
+/// Liquidity Locker contract
+#[odra::module]
+pub struct LiquidityLocker {
+    /// Locks by ID
+    locks: Mapping<u64, Lock>,
+    /// Lock IDs ever owned by an address: owner -> Vec<lock_id>
+    user_locks: Mapping<Address, Vec<u64>>,
+    /// Total amount locked per LP token
+    total_locked: Mapping<Address, U256>,
+    /// Next lock ID
+    next_lock_id: Var<u64>,
+}
+
+#[odra::module]
+impl LiquidityLocker {
+    /// Initialize the locker
+    pub fn init(&mut self) {
+        self.next_lock_id.set(0);
+    }
+
+    /// Lock LP tokens until `unlock_time`
+    ///
+    /// The caller must have approved the locker to spend `amount` of `lp_token`.
+    /// Returns the ID of the new lock
+    pub fn lock(&mut self, lp_token: Address, amount: U256, unlock_time: u64) -> u64 {
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        if unlock_time <= self.env().get_block_time() {
+            self.env().revert(DexError::InvalidUnlockTime);
+        }
+
+        let caller = self.env().caller();
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), lp_token);
+        if !token_ref.transfer_from(caller, self.env().self_address(), amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        let total = self.total_locked.get(&lp_token).unwrap_or_default();
+        self.total_locked.set(&lp_token, total + amount);
+
+        let lock_id = self.create_lock(caller, lp_token, amount, unlock_time);
+
+        self.env().emit_event(LiquidityLocked {
+            lock_id,
+            owner: caller,
+            lp_token,
+            amount,
+            unlock_time,
+        });
+
+        lock_id
+    }
+
+    /// Extend a lock to a later unlock time (owner only)
+    pub fn extend_lock(&mut self, lock_id: u64, new_unlock_time: u64) {
+        let mut lock = self.active_lock_of_caller(lock_id);
+
+        if new_unlock_time <= lock.unlock_time {
+            self.env().revert(DexError::InvalidUnlockTime);
+        }
+
+        let old_unlock_time = lock.unlock_time;
+        lock.unlock_time = new_unlock_time;
+        self.locks.set(&lock_id, lock);
+
+        self.env().emit_event(LockExtended {
+            lock_id,
+            old_unlock_time,
+            new_unlock_time,
+        });
+    }
+
+    /// Split `amount` off a lock into a new lock with the same unlock time (owner only)
+    ///
+    /// Returns the ID of the new lock
+    pub fn split_lock(&mut self, lock_id: u64, amount: U256) -> u64 {
+        let mut lock = self.active_lock_of_caller(lock_id);
+
+        if amount.is_zero() || amount >= lock.amount {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        lock.amount = lock.amount - amount;
+        let (owner, lp_token, unlock_time) = (lock.owner, lock.lp_token, lock.unlock_time);
+        self.locks.set(&lock_id, lock);
+
+        let new_lock_id = self.create_lock(owner, lp_token, amount, unlock_time);
+
+        self.env().emit_event(LockSplit {
+            lock_id,
+            new_lock_id,
+            amount,
+        });
+
+        new_lock_id
+    }
+
+    /// Transfer ownership of a lock (owner only)
+    pub fn transfer_lock_ownership(&mut self, lock_id: u64, new_owner: Address) {
+        let mut lock = self.active_lock_of_caller(lock_id);
+
+        let previous_owner = lock.owner;
+        lock.owner = new_owner;
+        self.locks.set(&lock_id, lock);
+
+        let mut owner_locks = self.user_locks.get(&new_owner).unwrap_or_default();
+        owner_locks.push(lock_id);
+        self.user_locks.set(&new_owner, owner_locks);
+
+        self.env().emit_event(LockOwnershipTransferred {
+            lock_id,
+            previous_owner,
+            new_owner,
+        });
+    }
+
+    /// Withdraw LP tokens from an expired lock (owner only)
+    pub fn withdraw(&mut self, lock_id: u64) {
+        let mut lock = self.active_lock_of_caller(lock_id);
+
+        if self.env().get_block_time() < lock.unlock_time {
+            self.env().revert(DexError::LockNotExpired);
+        }
+
+        lock.withdrawn = true;
+        self.locks.set(&lock_id, lock.clone());
+
+        let total = self.total_locked.get(&lock.lp_token).unwrap_or_default();
+        self.total_locked.set(&lock.lp_token, total - lock.amount);
+
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), lock.lp_token);
+        if !token_ref.transfer(lock.owner, lock.amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        self.env().emit_event(LiquidityUnlocked {
+            lock_id,
+            owner: lock.owner,
+            lp_token: lock.lp_token,
+            amount: lock.amount,
+        });
+    }
+
+    // ============ View Functions ============
+
+    /// Get lock details
+    pub fn get_lock(&self, lock_id: u64) -> Option<Lock> {
+        self.locks.get(&lock_id)
+    }
+
+    /// Get all lock IDs ever owned by an address
+    /// Locks transferred away are still listed; check `Lock::owner`
+    pub fn get_user_locks(&self, owner: Address) -> Vec<u64> {
+        self.user_locks.get(&owner).unwrap_or_default()
+    }
+
+    /// Get the total amount of an LP token currently locked
+    pub fn get_total_locked(&self, lp_token: Address) -> U256 {
+        self.total_locked.get(&lp_token).unwrap_or_default()
+    }
+
+    /// Get the number of locks created
+    pub fn lock_count(&self) -> u64 {
+        self.next_lock_id.get_or_default()
+    }
+
+    // ============ Internal Functions ============
+
+    /// Store a new lock and register it for its owner
+    fn create_lock(&mut self, owner: Address, lp_token: Address, amount: U256, unlock_time: u64) -> u64 {
+        let lock_id = self.next_lock_id.get_or_default();
+        self.locks.set(&lock_id, Lock {
+            owner,
+            lp_token,
+            amount,
+            unlock_time,
+            withdrawn: false,
+        });
+        self.next_lock_id.set(lock_id + 1);
+
+        let mut owner_locks = self.user_locks.get(&owner).unwrap_or_default();
+        owner_locks.push(lock_id);
+        self.user_locks.set(&owner, owner_locks);
+
+        lock_id
+    }
+
+    /// Load a lock that has not been withdrawn and is owned by the caller
+    fn active_lock_of_caller(&self, lock_id: u64) -> Lock {
+        let lock = self.locks.get(&lock_id)
+            .unwrap_or_revert_with(&self.env(), DexError::LockNotFound);
+        if lock.withdrawn {
+            self.env().revert(DexError::LockNotFound);
+        }
+        if lock.owner != self.env().caller() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        lock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, HostEnv, NoArgs};
+    use crate::token::{LpToken, LpTokenHostRef, LpTokenInitArgs};
+
+    fn setup() -> (HostEnv, LiquidityLockerHostRef, LpTokenHostRef) {
+        let env = odra_test::env();
+        let locker = LiquidityLocker::deploy(&env, NoArgs);
+        let lp_token = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("LP Token"),
+            symbol: String::from("LP"),
+        });
+        (env, locker, lp_token)
+    }
+
+    #[test]
+    fn test_lock_split_and_withdraw() {
+        let (env, mut locker, mut lp_token) = setup();
+        let owner = env.get_account(0);
+        let amount = U256::from(1000);
+        let unlock_time = env.block_time() + 1000;
+
+        lp_token.mint(owner, amount);
+        env.set_caller(owner);
+        lp_token.approve(locker.address().clone(), amount);
+
+        let lock_id = locker.lock(lp_token.address().clone(), amount, unlock_time);
+        assert_eq!(lp_token.balance_of(owner), U256::zero());
+        assert_eq!(locker.get_total_locked(lp_token.address().clone()), amount);
+
+        let new_lock_id = locker.split_lock(lock_id, U256::from(400));
+        assert_eq!(locker.get_lock(lock_id).unwrap().amount, U256::from(600));
+        assert_eq!(locker.get_lock(new_lock_id).unwrap().amount, U256::from(400));
+
+        env.advance_block_time(1000);
+        locker.withdraw(lock_id);
+        assert_eq!(lp_token.balance_of(owner), U256::from(600));
+        assert_eq!(locker.get_total_locked(lp_token.address().clone()), U256::from(400));
+    }
+
+    #[test]
+    fn test_withdraw_before_unlock_fails() {
+        let (env, mut locker, mut lp_token) = setup();
+        let owner = env.get_account(0);
+        let amount = U256::from(1000);
+
+        lp_token.mint(owner, amount);
+        env.set_caller(owner);
+        lp_token.approve(locker.address().clone(), amount);
+        let lock_id = locker.lock(lp_token.address().clone(), amount, env.block_time() + 1000);
+
+        assert_eq!(
+            locker.try_withdraw(lock_id).unwrap_err(),
+            DexError::LockNotExpired.into()
+        );
+    }
+}
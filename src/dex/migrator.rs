@@ -0,0 +1,296 @@
+//! Liquidity Migrator contract for the DEX
+//!
+//! The Migrator bootstraps liquidity from other Uniswap V2-style DEXes:
+//! - Pulls LP tokens of a foreign pair from the caller
+//! - Burns them on the foreign pair to receive the underlying tokens
+//! - Re-adds the underlying tokens into the matching Ectoplasm pair
+//! - Refunds any amount not needed to match the Ectoplasm pair ratio
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::errors::DexError;
+use crate::events::LiquidityMigrated;
+use crate::math::AmmMath;
+use crate::token::Cep18TokenContractRef;
+use super::router::{FactoryContractRefContractRef, PairContractContractRef};
+
+/// Migrator contract for moving liquidity from foreign pairs
+#[odra::module]
+pub struct Migrator {
+    /// Ectoplasm Factory contract address
+    factory: Var<Address>,
+}
+
+#[odra::module]
+impl Migrator {
+    /// Initialize the migrator with the Ectoplasm factory address
+    pub fn init(&mut self, factory: Address) {
+        self.factory.set(factory);
+    }
+
+    /// Get the factory address
+    pub fn factory(&self) -> Address {
+        self.factory.get_or_revert_with(DexError::InvalidPair)
+    }
+
+    /// Migrate liquidity from a foreign pair into the Ectoplasm pair
+    ///
+    /// The caller must have approved the Migrator to spend `liquidity`
+    /// LP tokens of `foreign_pair`. `amount_a_min` and `amount_b_min` bound
+    /// the amounts of the foreign pair's token0 and token1 that end up in
+    /// the Ectoplasm pair.
+    ///
+    /// Returns (amount_a, amount_b, liquidity) added to the Ectoplasm pair
+    pub fn migrate(
+        &mut self,
+        foreign_pair: Address,
+        liquidity: U256,
+        amount_a_min: U256,
+        amount_b_min: U256,
+        to: Address,
+        deadline: u64,
+    ) -> (U256, U256, U256) {
+        self.ensure_deadline(deadline);
+
+        if liquidity.is_zero() {
+            self.env().revert(DexError::InsufficientLiquidityBurned);
+        }
+
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+
+        // Pull foreign LP tokens into the foreign pair and burn them
+        let mut foreign_ref = PairContractContractRef::new(self.env(), foreign_pair);
+        let token_a = foreign_ref.token0();
+        let token_b = foreign_ref.token1();
+
+        if !foreign_ref.transfer_from(caller, foreign_pair, liquidity) {
+            self.env().revert(DexError::TransferFailed);
+        }
+        let (amount_a_received, amount_b_received) = foreign_ref.burn(self_address);
+
+        // Re-add the underlying into the Ectoplasm pair
+        let pair = self.get_or_create_pair(token_a, token_b);
+        if pair == foreign_pair {
+            self.env().revert(DexError::InvalidPair);
+        }
+
+        let (amount_a, amount_b) = self.calculate_liquidity_amounts(
+            pair,
+            token_a,
+            token_b,
+            amount_a_received,
+            amount_b_received,
+            amount_a_min,
+            amount_b_min,
+        );
+
+        self.safe_transfer(token_a, pair, amount_a);
+        self.safe_transfer(token_b, pair, amount_b);
+
+        let mut pair_ref = PairContractContractRef::new(self.env(), pair);
+        let minted = pair_ref.mint(to);
+
+        // Refund whatever did not fit the Ectoplasm pair ratio
+        if amount_a_received > amount_a {
+            self.safe_transfer(token_a, caller, amount_a_received - amount_a);
+        }
+        if amount_b_received > amount_b {
+            self.safe_transfer(token_b, caller, amount_b_received - amount_b);
+        }
+
+        self.env().emit_event(LiquidityMigrated {
+            provider: caller,
+            foreign_pair,
+            pair,
+            foreign_liquidity: liquidity,
+            amount_a,
+            amount_b,
+            liquidity: minted,
+        });
+
+        (amount_a, amount_b, minted)
+    }
+
+    // ============ Internal Functions ============
+
+    /// Ensure the deadline has not passed
+    fn ensure_deadline(&self, deadline: u64) {
+        if self.env().get_block_time() > deadline {
+            self.env().revert(DexError::DeadlineExpired);
+        }
+    }
+
+    /// Get or create the Ectoplasm pair for two tokens
+    fn get_or_create_pair(&mut self, token_a: Address, token_b: Address) -> Address {
+        let factory = self.factory();
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), factory);
+
+        match factory_ref.get_pair(token_a, token_b) {
+            Some(pair) => pair,
+            None => {
+                let mut factory_ref_mut = FactoryContractRefContractRef::new(self.env(), factory);
+                factory_ref_mut.create_pair(token_a, token_b)
+            }
+        }
+    }
+
+    /// Calculate the amounts that match the Ectoplasm pair's current ratio
+    #[allow(clippy::too_many_arguments)]
+    fn calculate_liquidity_amounts(
+        &self,
+        pair: Address,
+        token_a: Address,
+        token_b: Address,
+        amount_a_desired: U256,
+        amount_b_desired: U256,
+        amount_a_min: U256,
+        amount_b_min: U256,
+    ) -> (U256, U256) {
+        let pair_ref = PairContractContractRef::new(self.env(), pair);
+        let (reserve0, reserve1, _) = pair_ref.get_reserves();
+        let (reserve_a, reserve_b) = if token_a < token_b {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        };
+
+        let (amount_a, amount_b) = if reserve_a.is_zero() || reserve_b.is_zero() {
+            (amount_a_desired, amount_b_desired)
+        } else {
+            let amount_b_optimal = AmmMath::quote(amount_a_desired, reserve_a, reserve_b)
+                .unwrap_or_else(|e| self.env().revert(e));
+            if amount_b_optimal <= amount_b_desired {
+                (amount_a_desired, amount_b_optimal)
+            } else {
+                let amount_a_optimal = AmmMath::quote(amount_b_desired, reserve_b, reserve_a)
+                    .unwrap_or_else(|e| self.env().revert(e));
+                (amount_a_optimal, amount_b_desired)
+            }
+        };
+
+        if amount_a < amount_a_min || amount_b < amount_b_min {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        (amount_a, amount_b)
+    }
+
+    /// Safe transfer tokens held by the migrator
+    fn safe_transfer(&self, token: Address, to: Address, amount: U256) {
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(to, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::mock_factory::{deploy_listed_pair, MockFactory, MockFactoryInitArgs};
+    use crate::dex::pair::{Pair, PairHostRef, PairInitArgs};
+    use crate::dex::router::{Router, RouterInitArgs};
+    use crate::token::{LpToken, LpTokenInitArgs};
+    use odra::host::{Deployer, HostRef};
+
+    #[test]
+    fn test_migrator_init() {
+        let env = odra_test::env();
+        let factory = env.get_account(1);
+
+        let migrator = Migrator::deploy(&env, MigratorInitArgs { factory });
+
+        assert_eq!(migrator.factory(), factory);
+    }
+
+    #[test]
+    fn test_migrate_moves_liquidity_and_enforces_minimums() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        env.set_caller(admin);
+
+        let token_a = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token A"),
+            symbol: String::from("TKA"),
+        });
+        let token_b = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token B"),
+            symbol: String::from("TKB"),
+        });
+        let (mut token0, mut token1) = if token_a.address() < token_b.address() {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+        let mut factory = MockFactory::deploy(&env, MockFactoryInitArgs { fee_to_setter: admin });
+        let pair_address = deploy_listed_pair(&env, &mut factory, token0.address(), token1.address());
+        let mut router = Router::deploy(&env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: env.get_account(9),
+        });
+        let mut migrator = Migrator::deploy(&env, MigratorInitArgs { factory: factory.address() });
+
+        // The Ectoplasm pair trades at 1:2
+        token0.mint(admin, U256::from(100_000));
+        token1.mint(admin, U256::from(200_000));
+        token0.approve(router.address(), U256::from(100_000));
+        token1.approve(router.address(), U256::from(200_000));
+        router.add_liquidity(
+            token0.address(), token1.address(), U256::from(100_000), U256::from(200_000),
+            U256::zero(), U256::zero(), admin, env.block_time() + 1_000,
+        );
+        let pair = PairHostRef::new(pair_address, env.clone());
+
+        // The user holds LP of a foreign pair trading at 1:1
+        let mut foreign = Pair::deploy(&env, PairInitArgs {
+            token0: token0.address(),
+            token1: token1.address(),
+            factory: admin,
+        });
+        token0.mint(foreign.address(), U256::from(100_000));
+        token1.mint(foreign.address(), U256::from(100_000));
+        let foreign_liquidity = foreign.mint(user);
+        assert_eq!(foreign_liquidity, U256::from(99_000));
+
+        env.set_caller(user);
+        foreign.approve(migrator.address(), foreign_liquidity);
+        let deadline = env.block_time() + 1_000;
+
+        // 99_000 of each comes out; only 49_500 of token0 fits next to 99_000 of token1
+        assert_eq!(
+            migrator.try_migrate(
+                foreign.address(), foreign_liquidity, U256::from(49_501), U256::zero(), user, deadline,
+            ).unwrap_err(),
+            DexError::InsufficientAmount.into()
+        );
+        assert_eq!(
+            migrator.try_migrate(
+                foreign.address(), foreign_liquidity, U256::zero(), U256::from(99_001), user, deadline,
+            ).unwrap_err(),
+            DexError::InsufficientAmount.into()
+        );
+
+        let (amount_a, amount_b, liquidity) = migrator.migrate(
+            foreign.address(), foreign_liquidity, U256::from(49_500), U256::from(99_000), user, deadline,
+        );
+        assert_eq!((amount_a, amount_b), (U256::from(49_500), U256::from(99_000)));
+        assert!(liquidity > U256::zero());
+        assert_eq!(pair.balance_of(user), liquidity);
+        assert_eq!(foreign.balance_of(user), U256::zero());
+
+        // The token0 that did not fit the ratio is refunded
+        assert_eq!(token0.balance_of(user), U256::from(49_500));
+        assert_eq!(token1.balance_of(user), U256::zero());
+        assert!(env.emitted_event(&migrator, LiquidityMigrated {
+            provider: user,
+            foreign_pair: foreign.address(),
+            pair: pair_address,
+            foreign_liquidity,
+            amount_a,
+            amount_b,
+            liquidity,
+        }));
+    }
+}
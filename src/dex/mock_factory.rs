@@ -0,0 +1,108 @@
+//! Factory stand-in for tests
+//!
+//! Odra's MockVM cannot deploy pairs from a contract, so tests that go
+//! through the Router deploy each `Pair` themselves and list it on a
+//! `MockFactory`, which answers the same queries as `Factory`.
+
+use odra::prelude::*;
+use odra::host::{Deployer, HostEnv, HostRef};
+use crate::errors::DexError;
+use super::pair::{Pair, PairInitArgs};
+
+/// Registry of directly deployed pairs, queried like a `Factory`
+#[odra::module]
+pub struct MockFactory {
+    /// Fee setter address (admin)
+    fee_to_setter: Var<Address>,
+    /// Mapping from sorted token pair to pair address
+    pairs: Mapping<(Address, Address), Address>,
+    /// List of all pairs (stored as index -> address)
+    all_pairs: Mapping<u32, Address>,
+    /// Total number of pairs
+    all_pairs_length: Var<u32>,
+    /// Tokens that are part of any pair's reserves
+    pair_tokens: Mapping<Address, bool>,
+    /// Incentive hooks returned to pairs
+    incentive_hooks: Var<(Option<Address>, Option<Address>)>,
+}
+
+#[odra::module]
+impl MockFactory {
+    /// Initialize the registry with the fee setter address
+    pub fn init(&mut self, fee_to_setter: Address) {
+        self.fee_to_setter.set(fee_to_setter);
+    }
+
+    /// List a deployed pair for two tokens
+    pub fn list_pair(&mut self, token_a: Address, token_b: Address, pair: Address) {
+        let key = Self::sort_tokens(token_a, token_b);
+        self.pairs.set(&key, pair);
+        self.pair_tokens.set(&token_a, true);
+        self.pair_tokens.set(&token_b, true);
+        let index = self.all_pairs_length.get_or_default();
+        self.all_pairs.set(&index, pair);
+        self.all_pairs_length.set(index + 1);
+    }
+
+    /// Set the hooks pairs notify on LP balance changes
+    pub fn set_incentive_hooks(&mut self, lp_rewards_distributor: Option<Address>, incentive_manager: Option<Address>) {
+        self.incentive_hooks.set((lp_rewards_distributor, incentive_manager));
+    }
+
+    pub fn fee_to_setter(&self) -> Address {
+        self.fee_to_setter.get_or_revert_with(DexError::Unauthorized)
+    }
+
+    pub fn get_pair(&self, token_a: Address, token_b: Address) -> Option<Address> {
+        self.pairs.get(&Self::sort_tokens(token_a, token_b))
+    }
+
+    pub fn pair_exists(&self, token_a: Address, token_b: Address) -> bool {
+        self.get_pair(token_a, token_b).is_some()
+    }
+
+    pub fn all_pairs_at(&self, index: u32) -> Option<Address> {
+        self.all_pairs.get(&index)
+    }
+
+    pub fn all_pairs_length(&self) -> u32 {
+        self.all_pairs_length.get_or_default()
+    }
+
+    pub fn is_pair_token(&self, token: Address) -> bool {
+        self.pair_tokens.get(&token).unwrap_or(false)
+    }
+
+    pub fn incentive_hooks(&self) -> (Option<Address>, Option<Address>) {
+        self.incentive_hooks.get_or_default()
+    }
+
+    /// Pairs cannot be deployed from a contract here; list them instead
+    pub fn create_pair(&mut self, _token_a: Address, _token_b: Address) -> Address {
+        self.env().revert(DexError::PairNotFound)
+    }
+
+    fn sort_tokens(token_a: Address, token_b: Address) -> (Address, Address) {
+        if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        }
+    }
+}
+
+/// Deploy a pair for two tokens and list it on `factory`
+pub fn deploy_listed_pair(
+    env: &HostEnv,
+    factory: &mut MockFactoryHostRef,
+    token_a: Address,
+    token_b: Address,
+) -> Address {
+    let pair = Pair::deploy(env, PairInitArgs {
+        token0: token_a,
+        token1: token_b,
+        factory: factory.address(),
+    });
+    factory.list_pair(token_a, token_b, pair.address());
+    pair.address()
+}
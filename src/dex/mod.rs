@@ -4,14 +4,23 @@
 //! - Pair: Individual liquidity pools for token pairs
 //! - Factory: Creates and manages pairs
 //! - Router: User-facing contract for swaps and liquidity management
+//! - Migrator: Moves liquidity from foreign pairs into Ectoplasm pairs
+//! - LiquidityLocker: Time-locks LP tokens for token launches
 
 pub mod pair;
 pub mod factory;
 pub mod router;
+pub mod migrator;
+pub mod locker;
+
+#[cfg(test)]
+pub mod mock_factory;
 
 #[cfg(test)]
 pub mod tests;
 
 pub use pair::Pair;
 pub use factory::Factory;
-pub use router::Router;
\ No newline at end of file
+pub use router::Router;
+pub use migrator::Migrator;
+pub use locker::LiquidityLocker;
\ No newline at end of file
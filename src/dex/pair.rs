@@ -5,13 +5,51 @@
 //! - Adding liquidity (minting LP tokens)
 //! - Removing liquidity (burning LP tokens)
 //! - Swapping tokens
+//! - Recording cumulative price observations for TWAP oracles
+//! - Tracking lifetime and daily volume and fees for APR calculation
+//! - Notifying the factory's incentive hooks when LP balances change
 use odra::prelude::*;
-use odra::casper_types::U256;
+use odra::casper_types::bytesrepr::Bytes;
+use odra::casper_types::{PublicKey, U256, U512};
 use odra::ContractRef;
 use crate::errors::DexError;
-use crate::events::{LiquidityAdded, LiquidityRemoved, Swap, Sync};
-use crate::math::MINIMUM_LIQUIDITY;
-use crate::token::{LpToken, Cep18TokenContractRef};
+use crate::events::{LiquidityAdded, LiquidityRemoved, MaxPriceImpactUpdated, Swap, Sync};
+use crate::math::{full_mul, mul_div, sqrt_full, BPS_DENOMINATOR, FEE_DENOMINATOR, FEE_NUMERATOR, MINIMUM_LIQUIDITY};
+use crate::token::{LpToken, Cep18TokenContractRef, TokenMetadata};
+use crate::incentives::incentive_manager::IncentiveManagerContractRef;
+use crate::incentives::lp_rewards_distributor::LpRewardsDistributorContractRef;
+use super::factory::FactoryContractRef;
+
+/// Number of price observations kept in the ring buffer
+pub const OBSERVATION_CARDINALITY: u32 = 64;
+
+/// Length of a stats day in seconds
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Swap volume and LP fees, per token
+#[odra::odra_type]
+#[derive(Default)]
+pub struct PairStats {
+    /// Total token0 swapped in
+    pub volume0: U256,
+    /// Total token1 swapped in
+    pub volume1: U256,
+    /// Fees paid in token0
+    pub fees0: U256,
+    /// Fees paid in token1
+    pub fees1: U256,
+}
+
+/// Cumulative prices recorded at a point in time
+#[odra::odra_type]
+pub struct Observation {
+    /// Block timestamp of the observation
+    pub timestamp: u64,
+    /// Cumulative price of token0 (scaled by 1e18, wrapping)
+    pub price0_cumulative: U256,
+    /// Cumulative price of token1 (scaled by 1e18, wrapping)
+    pub price1_cumulative: U256,
+}
 
 /// Liquidity Pair contract
 #[odra::module(factory=on)]
@@ -29,17 +67,28 @@ pub struct Pair {
     /// Block timestamp of last update
     block_timestamp_last: Var<u64>,
     /// Cumulative price of token0 (for oracle)
-    #[allow(dead_code)]
     price0_cumulative_last: Var<U256>,
     /// Cumulative price of token1 (for oracle)
-    #[allow(dead_code)]
     price1_cumulative_last: Var<U256>,
-    /// K value from last liquidity event (for fee calculation)
+    /// Ring buffer of price observations: slot -> observation
+    observations: Mapping<u32, Observation>,
+    /// Slot of the most recent observation
+    observation_index: Var<u32>,
+    /// Number of populated observation slots
+    observation_count: Var<u32>,
+    /// Lifetime volume and fees
+    lifetime_stats: Var<PairStats>,
+    /// Volume and fees per day: day -> stats
+    daily_stats: Mapping<u64, PairStats>,
+    /// Legacy K value from the last liquidity event (no longer written or
+    /// read; kept so the storage layout is unchanged)
     k_last: Var<U256>,
     /// Factory address
     factory: Var<Address>,
     /// Reentrancy lock
     locked: Var<bool>,
+    /// Max price impact per swap in basis points (0 = disabled)
+    max_price_impact_bps: Var<u32>,
 }
 
 #[odra::module(factory=on)]
@@ -90,6 +139,29 @@ impl Pair {
         )
     }
 
+    /// Get the max price impact per swap in basis points (0 = disabled)
+    pub fn max_price_impact_bps(&self) -> u32 {
+        self.max_price_impact_bps.get_or_default()
+    }
+
+    /// Set the max price impact per swap in basis points (0 = disabled)
+    /// Only callable by the factory
+    pub fn set_max_price_impact(&mut self, max_price_impact_bps: u32) {
+        let factory = self.factory.get_or_revert_with(DexError::Unauthorized);
+        if self.env().caller() != factory {
+            self.env().revert(DexError::Unauthorized);
+        }
+        if max_price_impact_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.max_price_impact_bps.set(max_price_impact_bps);
+
+        self.env().emit_event(MaxPriceImpactUpdated {
+            pair: self.env().self_address(),
+            max_price_impact_bps,
+        });
+    }
+
     /// Get LP token total supply
     pub fn total_supply(&self) -> U256 {
         self.lp_token.total_supply()
@@ -102,7 +174,9 @@ impl Pair {
 
     /// Transfer LP tokens
     pub fn transfer(&mut self, to: Address, amount: U256) -> bool {
-        self.lp_token.transfer(to, amount)
+        let success = self.lp_token.transfer(to, amount);
+        self.notify_incentives(Some(self.env().caller()), Some(to));
+        success
     }
 
     /// Approve LP token spending
@@ -110,9 +184,61 @@ impl Pair {
         self.lp_token.approve(spender, amount)
     }
 
+    /// Raise an LP token allowance
+    pub fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool {
+        self.lp_token.increase_allowance(spender, amount)
+    }
+
+    /// Lower an LP token allowance
+    pub fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool {
+        self.lp_token.decrease_allowance(spender, amount)
+    }
+
+    /// Approve LP token spending with an off-chain signature from the owner
+    pub fn permit(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        deadline: u64,
+        public_key: PublicKey,
+        signature: Bytes,
+    ) {
+        self.lp_token.permit(owner, spender, value, deadline, public_key, signature);
+    }
+
+    /// Get the next LP token permit nonce of an owner
+    pub fn nonces(&self, owner: Address) -> u64 {
+        self.lp_token.nonces(owner)
+    }
+
+    /// Get the digest an owner signs to approve LP token spending
+    pub fn permit_digest(
+        &self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        nonce: u64,
+        deadline: u64,
+    ) -> [u8; 32] {
+        self.lp_token.permit_digest(owner, spender, value, nonce, deadline)
+    }
+
+    /// Get the version of the LP token contract code
+    pub fn contract_version(&self) -> String {
+        self.lp_token.contract_version()
+    }
+
+    /// Get the LP token metadata
+    pub fn metadata(&self) -> TokenMetadata {
+        self.lp_token.metadata()
+    }
+
     /// Transfer LP tokens from another address
     pub fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool {
-        self.lp_token.transfer_from(from, to, amount)
+        let success = self.lp_token.transfer_from(from, to, amount);
+        self.notify_incentives(Some(from), Some(to));
+        success
     }
 
     /// Mint LP tokens when liquidity is added
@@ -135,8 +261,9 @@ impl Pair {
 
         if total_supply.is_zero() {
             // First liquidity provision: sqrt(amount0 * amount1) - MINIMUM_LIQUIDITY
-            let product = self.safe_mul(amount0, amount1);
-            liquidity = self.safe_sub(self.sqrt(product), U256::from(MINIMUM_LIQUIDITY));
+            // (the product is taken in 512 bits so large deposits cannot overflow)
+            let root = sqrt_full(full_mul(amount0, amount1));
+            liquidity = self.safe_sub(root, U256::from(MINIMUM_LIQUIDITY));
             
             // Permanently lock MINIMUM_LIQUIDITY tokens
             // Get self_address before mutable borrow
@@ -147,8 +274,8 @@ impl Pair {
             );
         } else {
             // Subsequent liquidity: min(amount0 * totalSupply / reserve0, amount1 * totalSupply / reserve1)
-            let liquidity0 = self.safe_div(self.safe_mul(amount0, total_supply), reserve0);
-            let liquidity1 = self.safe_div(self.safe_mul(amount1, total_supply), reserve1);
+            let liquidity0 = self.mul_div(amount0, total_supply, reserve0);
+            let liquidity1 = self.mul_div(amount1, total_supply, reserve1);
             liquidity = if liquidity0 < liquidity1 { liquidity0 } else { liquidity1 };
         }
 
@@ -160,10 +287,7 @@ impl Pair {
 
         // Update reserves
         self.update_reserves(balance0, balance1);
-
-        // Update k_last for fee calculation
-        let (new_reserve0, new_reserve1, _) = self.get_reserves();
-        self.k_last.set(self.safe_mul(new_reserve0, new_reserve1));
+        self.notify_incentives(None, Some(to));
 
         self.env().emit_event(LiquidityAdded {
             provider: to,
@@ -199,8 +323,8 @@ impl Pair {
         }
 
         // Calculate amounts to return: amount = liquidity * balance / totalSupply
-        let amount0 = self.safe_div(self.safe_mul(liquidity, balance0), total_supply);
-        let amount1 = self.safe_div(self.safe_mul(liquidity, balance1), total_supply);
+        let amount0 = self.mul_div(liquidity, balance0, total_supply);
+        let amount1 = self.mul_div(liquidity, balance1, total_supply);
 
         if amount0.is_zero() && amount1.is_zero() {
             self.env().revert(DexError::InsufficientLiquidityBurned);
@@ -302,16 +426,20 @@ impl Pair {
             self.safe_mul(amount1_in, U256::from(3)),
         );
 
-        let k_new = self.safe_mul(balance0_adjusted, balance1_adjusted);
-        let k_old = self.safe_mul(
-            self.safe_mul(reserve0, reserve1),
-            U256::from(1000000),
-        );
+        // Compare in 512 bits so large 18-decimal reserves cannot overflow
+        let k_new = full_mul(balance0_adjusted, balance1_adjusted);
+        let k_old = full_mul(reserve0, reserve1)
+            .checked_mul(U512::from(1_000_000u64))
+            .unwrap_or_else(|| self.env().revert(DexError::Overflow));
 
         if k_new < k_old {
             self.env().revert(DexError::KInvariantViolated);
         }
 
+        self.record_stats(amount0_in, amount1_in);
+
+        self.ensure_price_impact(reserve0, reserve1, balance0, balance1);
+
         // Update reserves
         self.update_reserves(balance0, balance1);
 
@@ -362,10 +490,7 @@ impl Pair {
         if reserve0.is_zero() {
             self.env().revert(DexError::InsufficientLiquidity);
         }
-        self.safe_div(
-            self.safe_mul(reserve1, U256::from(10u128.pow(18))),
-            reserve0,
-        )
+        self.mul_div(reserve1, U256::from(10u128.pow(18)), reserve0)
     }
 
     /// Get the price of token1 in terms of token0
@@ -374,19 +499,145 @@ impl Pair {
         if reserve1.is_zero() {
             self.env().revert(DexError::InsufficientLiquidity);
         }
-        self.safe_div(
-            self.safe_mul(reserve0, U256::from(10u128.pow(18))),
-            reserve1,
-        )
+        self.mul_div(reserve0, U256::from(10u128.pow(18)), reserve1)
+    }
+
+    // ============ Oracle Functions ============
+
+    /// Get the cumulative price of token0 as of the last reserve update
+    pub fn price0_cumulative_last(&self) -> U256 {
+        self.price0_cumulative_last.get_or_default()
+    }
+
+    /// Get the cumulative price of token1 as of the last reserve update
+    pub fn price1_cumulative_last(&self) -> U256 {
+        self.price1_cumulative_last.get_or_default()
+    }
+
+    /// Get the number of populated observation slots
+    pub fn observation_count(&self) -> u32 {
+        self.observation_count.get_or_default()
+    }
+
+    /// Get the observation stored in a ring buffer slot
+    pub fn get_observation(&self, index: u32) -> Option<Observation> {
+        self.observations.get(&index)
+    }
+
+    /// Get the cumulative prices (price0, price1) as of `seconds_ago` before now
+    ///
+    /// The TWAP over a window is the difference of two observed cumulative
+    /// prices divided by the window length. Cumulative prices wrap on overflow,
+    /// so consumers must use wrapping subtraction.
+    pub fn observe(&self, seconds_ago: u64) -> (U256, U256) {
+        let now = self.env().get_block_time();
+        if seconds_ago > now {
+            self.env().revert(DexError::ObservationTooOld);
+        }
+        let target = now - seconds_ago;
+
+        // At or after the last update the price is the current spot price
+        let last_timestamp = self.block_timestamp_last.get_or_default();
+        if target >= last_timestamp {
+            return self.cumulative_prices_at(target);
+        }
+
+        // Walk back from the newest observation to the one at or before target
+        let count = self.observation_count.get_or_default();
+        let newest = self.observation_index.get_or_default();
+        let mut after: Option<Observation> = None;
+        for i in 0..count {
+            let slot = (newest + OBSERVATION_CARDINALITY - i) % OBSERVATION_CARDINALITY;
+            let observation = self.observations.get(&slot)
+                .unwrap_or_revert_with(&self.env(), DexError::ObservationTooOld);
+
+            if observation.timestamp <= target {
+                return match after {
+                    None => (observation.price0_cumulative, observation.price1_cumulative),
+                    Some(next) => self.interpolate(&observation, &next, target),
+                };
+            }
+            after = Some(observation);
+        }
+
+        self.env().revert(DexError::ObservationTooOld)
+    }
+
+    // ============ Analytics Functions ============
+
+    /// Get lifetime volume and fees
+    pub fn get_lifetime_stats(&self) -> PairStats {
+        self.lifetime_stats.get_or_default()
+    }
+
+    /// Get volume and fees for a day (timestamp / SECONDS_PER_DAY)
+    pub fn get_daily_stats(&self, day: u64) -> PairStats {
+        self.daily_stats.get(&day).unwrap_or_default()
+    }
+
+    /// Get the current stats day
+    pub fn current_day(&self) -> u64 {
+        self.env().get_block_time() / SECONDS_PER_DAY
+    }
+
+    /// Get the annualized LP fee APR over the last `days` days (including today)
+    /// Scaled by 1e18 (1e18 = 100%), valued in token0 at the current spot price
+    pub fn get_fee_apr(&self, days: u64) -> U256 {
+        let (reserve0, reserve1, _) = self.get_reserves();
+        if days == 0 || reserve0.is_zero() || reserve1.is_zero() {
+            return U256::zero();
+        }
+
+        let today = self.current_day();
+        let mut fees0 = U256::zero();
+        let mut fees1 = U256::zero();
+        for day in today.saturating_sub(days - 1)..=today {
+            let stats = self.get_daily_stats(day);
+            fees0 = fees0 + stats.fees0;
+            fees1 = fees1 + stats.fees1;
+        }
+
+        // Value token1 fees in token0 and compare against the pool value (2 * reserve0)
+        let fees_in_token0 = fees0 + self.mul_div(fees1, reserve0, reserve1);
+        let scale = U256::from(10u128.pow(18));
+        let period_return = self.mul_div(fees_in_token0, scale, self.safe_mul(reserve0, U256::from(2)));
+        period_return * U256::from(365) / U256::from(days)
     }
 
     // ============ Internal Functions ============
 
+    /// Add a swap's input volume and LP fees to the lifetime and daily stats
+    fn record_stats(&mut self, amount0_in: U256, amount1_in: U256) {
+        let fee0 = self.safe_mul(amount0_in, U256::from(FEE_NUMERATOR)) / U256::from(FEE_DENOMINATOR);
+        let fee1 = self.safe_mul(amount1_in, U256::from(FEE_NUMERATOR)) / U256::from(FEE_DENOMINATOR);
+
+        let mut lifetime = self.lifetime_stats.get_or_default();
+        lifetime.volume0 = lifetime.volume0.saturating_add(amount0_in);
+        lifetime.volume1 = lifetime.volume1.saturating_add(amount1_in);
+        lifetime.fees0 = lifetime.fees0.saturating_add(fee0);
+        lifetime.fees1 = lifetime.fees1.saturating_add(fee1);
+        self.lifetime_stats.set(lifetime);
+
+        let day = self.current_day();
+        let mut daily = self.get_daily_stats(day);
+        daily.volume0 = daily.volume0.saturating_add(amount0_in);
+        daily.volume1 = daily.volume1.saturating_add(amount1_in);
+        daily.fees0 = daily.fees0.saturating_add(fee0);
+        daily.fees1 = daily.fees1.saturating_add(fee1);
+        self.daily_stats.set(&day, daily);
+    }
+
     /// Update reserves and emit Sync event
     fn update_reserves(&mut self, balance0: U256, balance1: U256) {
+        let now = self.env().get_block_time();
+        let (price0_cumulative, price1_cumulative) = self.cumulative_prices_at(now);
+        self.price0_cumulative_last.set(price0_cumulative);
+        self.price1_cumulative_last.set(price1_cumulative);
+        self.write_observation(now, price0_cumulative, price1_cumulative);
+
         self.reserve0.set(balance0);
         self.reserve1.set(balance1);
-        self.block_timestamp_last.set(self.env().get_block_time());
+        self.block_timestamp_last.set(now);
 
         self.env().emit_event(Sync {
             pair: self.env().self_address(),
@@ -395,6 +646,95 @@ impl Pair {
         });
     }
 
+    /// Cumulative prices extrapolated from the last update to `timestamp`
+    /// using the current reserves
+    fn cumulative_prices_at(&self, timestamp: u64) -> (U256, U256) {
+        let (reserve0, reserve1, last_timestamp) = self.get_reserves();
+        let price0_cumulative = self.price0_cumulative_last.get_or_default();
+        let price1_cumulative = self.price1_cumulative_last.get_or_default();
+
+        if timestamp <= last_timestamp || reserve0.is_zero() || reserve1.is_zero() {
+            return (price0_cumulative, price1_cumulative);
+        }
+
+        let elapsed = U256::from(timestamp - last_timestamp);
+        let scale = U256::from(10u128.pow(18));
+        let price0 = self.mul_div(reserve1, scale, reserve0);
+        let price1 = self.mul_div(reserve0, scale, reserve1);
+
+        // Cumulative prices are allowed to wrap, like Uniswap V2
+        (
+            price0_cumulative.overflowing_add(price0.overflowing_mul(elapsed).0).0,
+            price1_cumulative.overflowing_add(price1.overflowing_mul(elapsed).0).0,
+        )
+    }
+
+    /// Record an observation, at most one per block timestamp
+    fn write_observation(&mut self, timestamp: u64, price0_cumulative: U256, price1_cumulative: U256) {
+        let count = self.observation_count.get_or_default();
+        let mut index = self.observation_index.get_or_default();
+
+        if count > 0 {
+            let latest = self.observations.get(&index)
+                .unwrap_or_revert_with(&self.env(), DexError::ObservationTooOld);
+            if latest.timestamp >= timestamp {
+                return;
+            }
+            index = (index + 1) % OBSERVATION_CARDINALITY;
+        }
+
+        self.observations.set(&index, Observation {
+            timestamp,
+            price0_cumulative,
+            price1_cumulative,
+        });
+        self.observation_index.set(index);
+        if count < OBSERVATION_CARDINALITY {
+            self.observation_count.set(count + 1);
+        }
+    }
+
+    /// Linearly interpolate cumulative prices between two observations
+    /// The price is constant between observations, so this is exact
+    fn interpolate(&self, before: &Observation, after: &Observation, target: u64) -> (U256, U256) {
+        let span = U256::from(after.timestamp - before.timestamp);
+        let delta = U256::from(target - before.timestamp);
+
+        let diff0 = after.price0_cumulative.overflowing_sub(before.price0_cumulative).0;
+        let diff1 = after.price1_cumulative.overflowing_sub(before.price1_cumulative).0;
+
+        (
+            before.price0_cumulative.overflowing_add(self.mul_div(diff0, delta, span)).0,
+            before.price1_cumulative.overflowing_add(self.mul_div(diff1, delta, span)).0,
+        )
+    }
+
+    /// Revert if moving from the old reserves to the new balances
+    /// changes the spot price by more than the configured max price impact
+    fn ensure_price_impact(&self, reserve0: U256, reserve1: U256, balance0: U256, balance1: U256) {
+        let max_impact_bps = self.max_price_impact_bps.get_or_default();
+        if max_impact_bps == 0 {
+            return;
+        }
+
+        // price_before = reserve1 / reserve0, price_after = balance1 / balance0
+        // impact = |price_after - price_before| / price_before
+        //        = |balance1 * reserve0 - reserve1 * balance0| / (reserve1 * balance0)
+        let after = full_mul(balance1, reserve0);
+        let before = full_mul(reserve1, balance0);
+        let diff = if after > before { after - before } else { before - after };
+
+        // impact_bps > max_impact_bps  <=>  diff * BPS_DENOMINATOR > max_impact_bps * before
+        let scaled_diff = diff.checked_mul(U512::from(BPS_DENOMINATOR))
+            .unwrap_or_else(|| self.env().revert(DexError::Overflow));
+        let scaled_limit = before.checked_mul(U512::from(max_impact_bps))
+            .unwrap_or_else(|| self.env().revert(DexError::Overflow));
+
+        if scaled_diff > scaled_limit {
+            self.env().revert(DexError::PriceImpactTooHigh);
+        }
+    }
+
     /// Get token balance of this contract
     fn get_token_balance(&self, token: Address) -> U256 {
         let token_ref = Cep18TokenContractRef::new(self.env(), token);
@@ -423,6 +763,31 @@ impl Pair {
         self.locked.set(false);
     }
 
+    /// Push LP balance changes to the factory's incentive hooks so positions
+    /// and boosts stay in sync without users calling them
+    /// Burns need no call: the LP was synced when it was sent to the pair
+    fn notify_incentives(&self, from: Option<Address>, to: Option<Address>) {
+        // Pairs deployed outside a Factory have no hooks
+        let factory = self.factory.get_or_revert_with(DexError::InvalidPair);
+        if !factory.is_contract() {
+            return;
+        }
+        let (lp_rewards_distributor, incentive_manager) =
+            FactoryContractRef::new(self.env(), factory).incentive_hooks();
+
+        let self_address = self.env().self_address();
+        let users = [from, to].into_iter().flatten().filter(|user| *user != self_address);
+        if let Some(distributor) = lp_rewards_distributor {
+            let mut distributor = LpRewardsDistributorContractRef::new(self.env(), distributor);
+            for user in users.clone() {
+                distributor.sync_lp_position(user, self_address);
+            }
+        }
+        if let Some(manager) = incentive_manager {
+            IncentiveManagerContractRef::new(self.env(), manager).on_lp_transfer(from, to);
+        }
+    }
+
     /// Safe multiplication with overflow check
     fn safe_mul(&self, a: U256, b: U256) -> U256 {
         a.checked_mul(b).unwrap_or_else(|| {
@@ -437,26 +802,10 @@ impl Pair {
         })
     }
 
-    /// Safe division with zero check
-    fn safe_div(&self, a: U256, b: U256) -> U256 {
-        if b.is_zero() {
-            self.env().revert(DexError::DivisionByZero);
-        }
-        a / b
-    }
 
-    /// Integer square root using Newton's method
-    fn sqrt(&self, n: U256) -> U256 {
-        if n.is_zero() {
-            return U256::zero();
-        }
-        let mut x = n;
-        let mut y = (x + U256::one()) / 2;
-        while y < x {
-            x = y;
-            y = (x + n / x) / 2;
-        }
-        x
+    /// Calculate a * b / denominator with a 512-bit intermediate product
+    fn mul_div(&self, a: U256, b: U256, denominator: U256) -> U256 {
+        mul_div(a, b, denominator).unwrap_or_else(|e| self.env().revert(e))
     }
 }
 
@@ -5,11 +5,19 @@
 //! - Adding/removing liquidity
 //! - Swapping tokens (exact input and exact output)
 //! - Multi-hop swaps through multiple pairs
+//! - Swaps of fee-on-transfer tokens, priced from what each pair received
 //! - Deadline protection
+//! - Sweeping stray tokens and CSPR left on the router
+//! - Routing WCSPR -> sCSPR through liquid staking when it beats the pool
+//! - Reporting swap volume to trade mining
+//! - Accruing gas rebates for swaps and liquidity changes
+//! - Reporting interactions and swap fees to the referral registry
 use odra::prelude::*;
-use odra::casper_types::U256;
+use odra::casper_types::{U256, U512};
 use odra::ContractRef;
 use crate::errors::DexError;
+use crate::events::TokenRescued;
+use crate::math::{mul_div, FEE_DENOMINATOR, FEE_NUMERATOR};
 use crate::token::Cep18TokenContractRef;
 
 /// External interface for Pair contract
@@ -29,6 +37,44 @@ pub trait PairContract {
 pub trait FactoryContractRef {
     fn get_pair(&self, token_a: Address, token_b: Address) -> Option<Address>;
     fn create_pair(&mut self, token_a: Address, token_b: Address) -> Address;
+    fn fee_to_setter(&self) -> Address;
+    fn is_pair_token(&self, token: Address) -> bool;
+}
+
+/// External interface for the liquid staking StakingManager
+#[odra::external_contract]
+pub trait StakingManagerContract {
+    fn stake(&mut self) -> U256;
+    fn get_scspr_by_cspr(&self, cspr_amount: U256) -> U256;
+    fn get_scspr_token(&self) -> Address;
+    fn get_minimum_stake(&self) -> U256;
+    fn is_paused(&self) -> bool;
+}
+
+/// External interface for the TradeMining incentives contract
+#[odra::external_contract]
+pub trait TradeMiningContract {
+    fn record_volume(&mut self, trader: Address, pair: Address, amount_in: U256);
+}
+
+/// External interface for the IncentiveManager
+#[odra::external_contract]
+pub trait IncentiveManagerContract {
+    fn process_dex_transaction(&mut self, user: Address, estimated_gas: U256) -> U256;
+}
+
+/// External interface for the ReferralRegistry
+#[odra::external_contract]
+pub trait ReferralRegistryContract {
+    fn record_interaction(&mut self, user: Address);
+    fn record_fees(&mut self, user: Address, token: Address, amount: U256) -> U256;
+}
+
+/// External interface for Wrapped CSPR (implemented by `tokens::WcsprToken`)
+#[odra::external_contract]
+pub trait WrappedCsprContract {
+    /// Burn WCSPR from the caller and send it the same amount of CSPR
+    fn withdraw(&mut self, amount: U256);
 }
 
 /// Router contract for user interactions
@@ -38,6 +84,16 @@ pub struct Router {
     factory: Var<Address>,
     /// WCSPR (Wrapped CSPR) token address for native token swaps
     wcspr: Var<Address>,
+    /// Liquid staking StakingManager used as an alternative WCSPR -> sCSPR route
+    staking_manager: Var<Address>,
+    /// TradeMining contract that swap volume is reported to
+    trade_mining: Var<Address>,
+    /// IncentiveManager that accrues gas rebates for router transactions
+    incentive_manager: Var<Address>,
+    /// Estimated gas cost of a router transaction, in the rebate token
+    gas_estimate: Var<U256>,
+    /// ReferralRegistry that interactions and swap fees are reported to
+    referral_registry: Var<Address>,
 }
 
 #[odra::module]
@@ -58,6 +114,72 @@ impl Router {
         self.wcspr.get_or_revert_with(DexError::InvalidPair)
     }
 
+    /// Get the StakingManager address, if staking routing is enabled
+    pub fn staking_manager(&self) -> Option<Address> {
+        self.staking_manager.get()
+    }
+
+    /// Set the StakingManager used for WCSPR -> sCSPR routing
+    /// Only callable by the factory's fee_to_setter
+    pub fn set_staking_manager(&mut self, staking_manager: Address) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.staking_manager.set(staking_manager);
+    }
+
+    /// Get the TradeMining address, if volume reporting is enabled
+    pub fn trade_mining(&self) -> Option<Address> {
+        self.trade_mining.get()
+    }
+
+    /// Set the TradeMining contract that swap volume is reported to
+    /// Only callable by the factory's fee_to_setter
+    pub fn set_trade_mining(&mut self, trade_mining: Address) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.trade_mining.set(trade_mining);
+    }
+
+    /// Get the IncentiveManager address, if gas rebates are enabled
+    pub fn incentive_manager(&self) -> Option<Address> {
+        self.incentive_manager.get()
+    }
+
+    /// Get the estimated gas cost of a router transaction, in the rebate token
+    pub fn gas_estimate(&self) -> U256 {
+        self.gas_estimate.get_or_default()
+    }
+
+    /// Set the IncentiveManager that accrues gas rebates and the per-transaction gas estimate
+    /// Only callable by the factory's fee_to_setter
+    pub fn set_incentive_manager(&mut self, incentive_manager: Address, gas_estimate: U256) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.incentive_manager.set(incentive_manager);
+        self.gas_estimate.set(gas_estimate);
+    }
+
+    /// Get the ReferralRegistry address, if referral reporting is enabled
+    pub fn referral_registry(&self) -> Option<Address> {
+        self.referral_registry.get()
+    }
+
+    /// Set the ReferralRegistry that interactions and swap fees are reported to
+    /// Only callable by the factory's fee_to_setter
+    pub fn set_referral_registry(&mut self, referral_registry: Address) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+        self.referral_registry.set(referral_registry);
+    }
+
     // ============ Liquidity Functions ============
 
     /// Add liquidity to a pair
@@ -95,10 +217,51 @@ impl Router {
         // Mint LP tokens
         let mut pair_ref = PairContractContractRef::new(self.env(), pair);
         let liquidity = pair_ref.mint(to);
+        self.settle_gas_rebate(self.env().caller());
+        self.record_referral_interaction(self.env().caller());
 
         (amount_a, amount_b, liquidity)
     }
 
+    /// Create a new pair and seed its initial reserves in one call
+    /// Reverts if the pair already exists, so the initial price cannot be
+    /// set by someone front-running the launch.
+    /// Returns (pair, liquidity)
+    pub fn create_pair_and_add_liquidity(
+        &mut self,
+        token_a: Address,
+        token_b: Address,
+        amount_a: U256,
+        amount_b: U256,
+        to: Address,
+        deadline: u64,
+    ) -> (Address, U256) {
+        self.ensure_deadline(deadline);
+
+        if amount_a.is_zero() || amount_b.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        let factory = self.factory();
+        let mut factory_ref = FactoryContractRefContractRef::new(self.env(), factory);
+        if factory_ref.get_pair(token_a, token_b).is_some() {
+            self.env().revert(DexError::PairExists);
+        }
+        let pair = factory_ref.create_pair(token_a, token_b);
+
+        // Transfer tokens to pair
+        self.safe_transfer_from(token_a, self.env().caller(), pair, amount_a);
+        self.safe_transfer_from(token_b, self.env().caller(), pair, amount_b);
+
+        // Mint LP tokens
+        let mut pair_ref = PairContractContractRef::new(self.env(), pair);
+        let liquidity = pair_ref.mint(to);
+        self.settle_gas_rebate(self.env().caller());
+        self.record_referral_interaction(self.env().caller());
+
+        (pair, liquidity)
+    }
+
     /// Remove liquidity from a pair
     /// Returns (amount_a, amount_b)
     pub fn remove_liquidity(
@@ -137,6 +300,8 @@ impl Router {
         if amount_b < amount_b_min {
             self.env().revert(DexError::InsufficientAmount);
         }
+        self.settle_gas_rebate(self.env().caller());
+        self.record_referral_interaction(self.env().caller());
 
         (amount_a, amount_b)
     }
@@ -167,6 +332,47 @@ impl Router {
 
         // Execute swaps
         self.execute_swap(&amounts, &path, to);
+        self.report_volume(self.env().caller(), &amounts, &path);
+        self.report_referral_fees(self.env().caller(), &amounts, &path);
+        self.settle_gas_rebate(self.env().caller());
+
+        amounts
+    }
+
+    /// Swap exact input amount for output tokens, for tokens that take a fee on transfer
+    ///
+    /// Each hop is priced from what the pair actually received, and the
+    /// minimum output is checked against what `to` actually received.
+    /// Returns the amount received by each pair and finally by `to`.
+    pub fn swap_exact_tokens_for_tokens_supporting_fee_on_transfer_tokens(
+        &mut self,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<U256> {
+        self.ensure_deadline(deadline);
+        if path.len() < 2 {
+            self.env().revert(DexError::InvalidPath);
+        }
+
+        let caller = self.env().caller();
+        let pair = self.get_pair_address(path[0], path[1]);
+        self.safe_transfer_from(path[0], caller, pair, amount_in);
+
+        let output = path[path.len() - 1];
+        let balance_before = self.balance_of(output, to);
+        let mut amounts = self.execute_swap_supporting_fee_on_transfer(&path, to);
+        let amount_out = self.safe_sub(self.balance_of(output, to), balance_before);
+        if amount_out < amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
+        amounts.push(amount_out);
+
+        self.report_volume(caller, &amounts, &path);
+        self.report_referral_fees(caller, &amounts, &path);
+        self.settle_gas_rebate(caller);
 
         amounts
     }
@@ -188,16 +394,129 @@ impl Router {
             self.env().revert(DexError::ExcessiveSlippage);
         }
 
-        // Transfer input tokens to first pair
+        // Pull the maximum input and forward the computed amount to the first pair
+        let caller = self.env().caller();
         let pair = self.get_pair_address(path[0], path[1]);
-        self.safe_transfer_from(path[0], self.env().caller(), pair, amounts[0]);
+        self.safe_transfer_from(path[0], caller, self.env().self_address(), amount_in_max);
+        self.safe_transfer(path[0], pair, amounts[0]);
 
         // Execute swaps
         self.execute_swap(&amounts, &path, to);
+        self.report_volume(caller, &amounts, &path);
+        self.report_referral_fees(caller, &amounts, &path);
+        self.settle_gas_rebate(caller);
+
+        // Return the caller's unused input, and nothing else held by the router
+        self.refund_token(path[0], caller, amount_in_max - amounts[0]);
 
         amounts
     }
 
+    /// Swap exact WCSPR for sCSPR through whichever of the WCSPR/sCSPR pool
+    /// or the StakingManager exchange rate gives more sCSPR
+    pub fn swap_exact_wcspr_for_scspr(
+        &mut self,
+        amount_in: U256,
+        amount_out_min: U256,
+        to: Address,
+        deadline: u64,
+    ) -> U256 {
+        self.ensure_deadline(deadline);
+
+        let (amount_out, via_staking) = self.get_scspr_quote(amount_in);
+        if amount_out.is_zero() || amount_out < amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
+
+        let wcspr = self.wcspr();
+        let caller = self.env().caller();
+
+        if !via_staking {
+            let path = vec![wcspr, self.scspr_token()];
+            let amounts = self.get_amounts_out_internal(amount_in, &path);
+            let pair = self.get_pair_address(path[0], path[1]);
+            self.safe_transfer_from(wcspr, caller, pair, amounts[0]);
+            self.execute_swap(&amounts, &path, to);
+            self.report_volume(caller, &amounts, &path);
+            self.report_referral_fees(caller, &amounts, &path);
+            self.settle_gas_rebate(caller);
+            return amounts[1];
+        }
+
+        // Unwrap WCSPR and stake the CSPR at the StakingManager exchange rate
+        let self_address = self.env().self_address();
+        self.safe_transfer_from(wcspr, caller, self_address, amount_in);
+        WrappedCsprContractContractRef::new(self.env(), wcspr).withdraw(amount_in);
+
+        let staking_manager = self.staking_manager.get_or_revert_with(DexError::InvalidConfiguration);
+        let minted = StakingManagerContractContractRef::new(self.env(), staking_manager)
+            .with_tokens(U512::from(amount_in.as_u128()))
+            .stake();
+        if minted < amount_out_min {
+            self.env().revert(DexError::InsufficientOutputAmount);
+        }
+
+        self.safe_transfer(self.scspr_token(), to, minted);
+        self.settle_gas_rebate(caller);
+        self.record_referral_interaction(caller);
+        minted
+    }
+
+    /// Get the best sCSPR output for `wcspr_amount` WCSPR
+    /// Returns (amount_out, via_staking)
+    pub fn get_scspr_quote(&self, wcspr_amount: U256) -> (U256, bool) {
+        let pool_out = self.pool_quote(self.wcspr(), self.scspr_token(), wcspr_amount);
+        let staking_out = self.staking_quote(wcspr_amount);
+
+        if staking_out > pool_out {
+            (staking_out, true)
+        } else {
+            (pool_out, false)
+        }
+    }
+
+    // ============ Sweep Functions ============
+
+    /// Send the router's entire balance of `token` to `to`
+    /// The router never holds funds between calls, so anything here is stray.
+    /// Only callable by the factory's fee_to_setter; tokens used by any pair cannot be swept
+    pub fn sweep_token(&mut self, token: Address, amount_minimum: U256, to: Address) {
+        self.ensure_fee_to_setter();
+        self.ensure_not_pair_token(token);
+        let balance = self.token_balance(token);
+        if balance < amount_minimum {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        if !balance.is_zero() {
+            self.safe_transfer(token, to, balance);
+        }
+    }
+
+    /// Send tokens stuck in the router to `to`
+    /// Only callable by the factory's fee_to_setter; tokens used by any pair cannot be rescued
+    pub fn rescue_token(&mut self, token: Address, to: Address, amount: U256) {
+        self.ensure_fee_to_setter();
+        self.ensure_not_pair_token(token);
+
+        self.safe_transfer(token, to, amount);
+
+        self.env().emit_event(TokenRescued {
+            token,
+            to,
+            amount,
+        });
+    }
+
+    /// Send the router's entire CSPR balance to the caller
+    /// Only callable by the factory's fee_to_setter
+    pub fn refund_cspr(&mut self) {
+        self.ensure_fee_to_setter();
+        let balance = self.env().self_balance();
+        if balance > U512::zero() {
+            self.env().transfer_tokens(&self.env().caller(), &balance);
+        }
+    }
+
     // ============ Quote Functions ============
 
     /// Get the output amount for a given input amount
@@ -276,6 +595,22 @@ impl Router {
         }
     }
 
+    /// Revert if `token` is used by any pair
+    fn ensure_not_pair_token(&self, token: Address) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if factory_ref.is_pair_token(token) {
+            self.env().revert(DexError::TokenNotRescuable);
+        }
+    }
+
+    /// Revert unless the caller is the factory's fee_to_setter
+    fn ensure_fee_to_setter(&self) {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if self.env().caller() != factory_ref.fee_to_setter() {
+            self.env().revert(DexError::Unauthorized);
+        }
+    }
+
     /// Sort two token addresses
     fn sort_tokens(&self, token_a: Address, token_b: Address) -> (Address, Address) {
         if token_a < token_b {
@@ -461,6 +796,81 @@ impl Router {
         }
     }
 
+    /// Execute a multi-hop swap, pricing each hop from the input its pair received
+    /// Returns the input received by each pair.
+    fn execute_swap_supporting_fee_on_transfer(&self, path: &[Address], to: Address) -> Vec<U256> {
+        let mut amounts = Vec::with_capacity(path.len());
+        for i in 0..path.len() - 1 {
+            let (input, output) = (path[i], path[i + 1]);
+            let (token0, _) = self.sort_tokens(input, output);
+            let pair = self.get_pair_address(input, output);
+
+            let (reserve_in, reserve_out) = self.get_reserves_internal(input, output);
+            let amount_in = self.safe_sub(self.balance_of(input, pair), reserve_in);
+            let amount_out = self.get_amount_out_internal(amount_in, reserve_in, reserve_out);
+            amounts.push(amount_in);
+
+            let (amount0_out, amount1_out) = if input == token0 {
+                (U256::zero(), amount_out)
+            } else {
+                (amount_out, U256::zero())
+            };
+
+            let recipient = if i < path.len() - 2 {
+                self.get_pair_address(output, path[i + 2])
+            } else {
+                to
+            };
+
+            let mut pair_ref = PairContractContractRef::new(self.env(), pair);
+            pair_ref.swap(amount0_out, amount1_out, recipient);
+        }
+        amounts
+    }
+
+    /// Report each hop's input volume to trade mining, if configured
+    fn report_volume(&self, trader: Address, amounts: &[U256], path: &[Address]) {
+        let trade_mining = match self.trade_mining.get() {
+            Some(trade_mining) => trade_mining,
+            None => return,
+        };
+        let mut mining_ref = TradeMiningContractContractRef::new(self.env(), trade_mining);
+        for i in 0..path.len() - 1 {
+            let pair = self.get_pair_address(path[i], path[i + 1]);
+            mining_ref.record_volume(trader, pair, amounts[i]);
+        }
+    }
+
+    /// Report each hop's swap fee to the referral registry, if configured
+    fn report_referral_fees(&self, trader: Address, amounts: &[U256], path: &[Address]) {
+        let referral_registry = match self.referral_registry.get() {
+            Some(referral_registry) => referral_registry,
+            None => return,
+        };
+        let mut registry_ref = ReferralRegistryContractContractRef::new(self.env(), referral_registry);
+        for i in 0..path.len() - 1 {
+            let fee = amounts[i] * U256::from(FEE_NUMERATOR) / U256::from(FEE_DENOMINATOR);
+            registry_ref.record_fees(trader, path[i], fee);
+        }
+    }
+
+    /// Report `user`'s interaction to the referral registry, if configured
+    fn record_referral_interaction(&self, user: Address) {
+        if let Some(referral_registry) = self.referral_registry.get() {
+            ReferralRegistryContractContractRef::new(self.env(), referral_registry).record_interaction(user);
+        }
+    }
+
+    /// Accrue `user`'s gas rebate for this transaction, if rebates are configured
+    fn settle_gas_rebate(&self, user: Address) {
+        let incentive_manager = match self.incentive_manager.get() {
+            Some(incentive_manager) => incentive_manager,
+            None => return,
+        };
+        IncentiveManagerContractContractRef::new(self.env(), incentive_manager)
+            .process_dex_transaction(user, self.gas_estimate.get_or_default());
+    }
+
     /// Safe transfer tokens from one address to another
     fn safe_transfer_from(
         &self,
@@ -476,6 +886,64 @@ impl Router {
         }
     }
 
+    /// Get the sCSPR token address from the StakingManager
+    fn scspr_token(&self) -> Address {
+        let staking_manager = self.staking_manager.get_or_revert_with(DexError::InvalidConfiguration);
+        StakingManagerContractContractRef::new(self.env(), staking_manager).get_scspr_token()
+    }
+
+    /// Output of a single-hop pool swap, or zero if the pool cannot fill it
+    fn pool_quote(&self, token_in: Address, token_out: Address, amount_in: U256) -> U256 {
+        let factory_ref = FactoryContractRefContractRef::new(self.env(), self.factory());
+        if factory_ref.get_pair(token_in, token_out).is_none() || amount_in.is_zero() {
+            return U256::zero();
+        }
+        let (reserve_in, reserve_out) = self.get_reserves_internal(token_in, token_out);
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            return U256::zero();
+        }
+        self.get_amount_out_internal(amount_in, reserve_in, reserve_out)
+    }
+
+    /// sCSPR minted by staking `cspr_amount`, or zero if staking is unavailable
+    fn staking_quote(&self, cspr_amount: U256) -> U256 {
+        let staking_manager = match self.staking_manager.get() {
+            Some(staking_manager) => staking_manager,
+            None => return U256::zero(),
+        };
+        let manager_ref = StakingManagerContractContractRef::new(self.env(), staking_manager);
+        if manager_ref.is_paused() || cspr_amount < manager_ref.get_minimum_stake() {
+            return U256::zero();
+        }
+        manager_ref.get_scspr_by_cspr(cspr_amount)
+    }
+
+    /// Safe transfer tokens held by the router
+    fn safe_transfer(&self, token: Address, to: Address, amount: U256) {
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(to, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+    }
+
+    /// Get `owner`'s balance of a token
+    fn balance_of(&self, token: Address, owner: Address) -> U256 {
+        Cep18TokenContractRef::new(self.env(), token).balance_of(owner)
+    }
+
+    /// Get the router's balance of a token
+    fn token_balance(&self, token: Address) -> U256 {
+        let token_ref = Cep18TokenContractRef::new(self.env(), token);
+        token_ref.balance_of(self.env().self_address())
+    }
+
+    /// Return `amount` of `token` held by the router to `to`
+    fn refund_token(&self, token: Address, to: Address, amount: U256) {
+        if !amount.is_zero() {
+            self.safe_transfer(token, to, amount);
+        }
+    }
+
     /// Internal quote calculation
     fn quote_internal(&self, amount_a: U256, reserve_a: U256, reserve_b: U256) -> U256 {
         if amount_a.is_zero() {
@@ -485,7 +953,7 @@ impl Router {
             self.env().revert(DexError::InsufficientLiquidity);
         }
         // amount_b = amount_a * reserve_b / reserve_a
-        self.safe_div(self.safe_mul(amount_a, reserve_b), reserve_a)
+        self.mul_div(amount_a, reserve_b, reserve_a)
     }
 
     /// Internal get_amount_out calculation
@@ -498,12 +966,11 @@ impl Router {
         }
         // amount_out = (amount_in * 997 * reserve_out) / (reserve_in * 1000 + amount_in * 997)
         let amount_in_with_fee = self.safe_mul(amount_in, U256::from(997));
-        let numerator = self.safe_mul(amount_in_with_fee, reserve_out);
         let denominator = self.safe_add(
             self.safe_mul(reserve_in, U256::from(1000)),
             amount_in_with_fee,
         );
-        self.safe_div(numerator, denominator)
+        self.mul_div(amount_in_with_fee, reserve_out, denominator)
     }
 
     /// Internal get_amount_in calculation
@@ -515,15 +982,16 @@ impl Router {
             self.env().revert(DexError::InsufficientLiquidity);
         }
         // amount_in = (reserve_in * amount_out * 1000) / ((reserve_out - amount_out) * 997) + 1
-        let numerator = self.safe_mul(
-            self.safe_mul(reserve_in, amount_out),
-            U256::from(1000),
-        );
         let denominator = self.safe_mul(
             self.safe_sub(reserve_out, amount_out),
             U256::from(997),
         );
-        self.safe_add(self.safe_div(numerator, denominator), U256::one())
+        let amount_in = self.mul_div(
+            reserve_in,
+            self.safe_mul(amount_out, U256::from(1000)),
+            denominator,
+        );
+        self.safe_add(amount_in, U256::one())
     }
 
     /// Safe multiplication with overflow check
@@ -533,6 +1001,11 @@ impl Router {
         })
     }
 
+    /// Calculate a * b / denominator with a 512-bit intermediate product
+    fn mul_div(&self, a: U256, b: U256, denominator: U256) -> U256 {
+        mul_div(a, b, denominator).unwrap_or_else(|e| self.env().revert(e))
+    }
+
     /// Safe addition with overflow check
     fn safe_add(&self, a: U256, b: U256) -> U256 {
         a.checked_add(b).unwrap_or_else(|| {
@@ -546,14 +1019,6 @@ impl Router {
             self.env().revert(DexError::Underflow);
         })
     }
-
-    /// Safe division with zero check
-    fn safe_div(&self, a: U256, b: U256) -> U256 {
-        if b.is_zero() {
-            self.env().revert(DexError::DivisionByZero);
-        }
-        a / b
-    }
 }
 
 #[cfg(test)]
@@ -5,6 +5,8 @@
 //! - Liquidity provision
 //! - Token swaps
 //! - Fee collection
+//! - Fee-on-transfer tokens
+//! - Blacklisting stablecoins
 
 #[cfg(test)]
 mod integration_tests {
@@ -324,6 +326,426 @@ mod integration_tests {
         assert_eq!(pair.total_supply(), U256::zero());
     }
 
+    #[test]
+    fn test_pair_mints_when_reserve_product_exceeds_u256() {
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let mut pair = Pair::deploy(&test_env.env, PairInitArgs {
+            token0: test_env.token_a.address(),
+            token1: test_env.token_b.address(),
+            factory: admin,
+        });
+        let pair_address = pair.address();
+
+        // 1e40 of each token: the reserve product is 1e80, past U256::MAX
+        let amount = U256::from(10u128.pow(20)) * U256::from(10u128.pow(20));
+        test_env.token_a.mint(pair_address, amount);
+        test_env.token_b.mint(pair_address, amount);
+        assert_eq!(pair.mint(admin), amount - U256::from(1_000));
+
+        // Later deposits keep working too
+        test_env.token_a.mint(pair_address, amount);
+        test_env.token_b.mint(pair_address, amount);
+        assert_eq!(pair.mint(admin), amount);
+        assert_eq!(pair.total_supply(), amount * 2);
+    }
+
+    #[test]
+    fn test_pair_observe_cumulative_prices() {
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+
+        let mut pair = Pair::deploy(&test_env.env, PairInitArgs {
+            token0: test_env.token_a.address().clone(),
+            token1: test_env.token_b.address().clone(),
+            factory: admin,
+        });
+        let pair_address = pair.address().clone();
+
+        // Equal reserves: both prices are 1e18
+        test_env.token_a.mint(pair_address, U256::from(1000));
+        test_env.token_b.mint(pair_address, U256::from(1000));
+        pair.sync();
+
+        test_env.env.advance_block_time(100);
+        pair.sync();
+        assert_eq!(pair.observation_count(), 2);
+
+        let one = U256::from(10u128.pow(18));
+        assert_eq!(pair.observe(0), (one * 100, one * 100));
+        assert_eq!(pair.observe(50), (one * 50, one * 50));
+        assert_eq!(pair.observe(100), (U256::zero(), U256::zero()));
+
+        // Extrapolates past the last update with the current price
+        test_env.env.advance_block_time(20);
+        assert_eq!(pair.observe(0), (one * 120, one * 120));
+    }
+
+    #[test]
+    fn test_pair_tracks_volume_and_fees() {
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let trader = test_env.env.get_account(1);
+
+        let mut pair = Pair::deploy(&test_env.env, PairInitArgs {
+            token0: test_env.token_a.address().clone(),
+            token1: test_env.token_b.address().clone(),
+            factory: admin,
+        });
+        let pair_address = pair.address().clone();
+
+        test_env.token_a.mint(pair_address, U256::from(1_000_000));
+        test_env.token_b.mint(pair_address, U256::from(1_000_000));
+        pair.sync();
+
+        // Swap 10_000 of token A in for 9_000 of token B out
+        test_env.token_a.mint(pair_address, U256::from(10_000));
+        let (amount0_out, amount1_out) = if pair.token0() == test_env.token_a.address().clone() {
+            (U256::zero(), U256::from(9_000))
+        } else {
+            (U256::from(9_000), U256::zero())
+        };
+        pair.swap(amount0_out, amount1_out, trader);
+
+        let lifetime = pair.get_lifetime_stats();
+        let daily = pair.get_daily_stats(pair.current_day());
+        assert_eq!(lifetime, daily);
+        assert_eq!(lifetime.volume0 + lifetime.volume1, U256::from(10_000));
+        assert_eq!(lifetime.fees0 + lifetime.fees1, U256::from(30));
+        assert!(pair.get_fee_apr(1) > U256::zero());
+    }
+
+    #[test]
+    fn test_pair_rejects_swaps_above_max_price_impact() {
+        use crate::errors::DexError;
+        use crate::events::MaxPriceImpactUpdated;
+        use crate::math::AmmMath;
+
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let trader = test_env.env.get_account(1);
+
+        let mut pair = Pair::deploy(&test_env.env, PairInitArgs {
+            token0: test_env.token_a.address().clone(),
+            token1: test_env.token_b.address().clone(),
+            factory: admin,
+        });
+        let pair_address = pair.address().clone();
+        let a_is_token0 = pair.token0() == test_env.token_a.address().clone();
+
+        let reserve = U256::from(1_000_000);
+        test_env.token_a.mint(pair_address, reserve);
+        test_env.token_b.mint(pair_address, reserve);
+        pair.sync();
+
+        // Only the factory (which checks for the guardian) sets the limit
+        test_env.env.set_caller(trader);
+        assert_eq!(pair.try_set_max_price_impact(100).unwrap_err(), DexError::Unauthorized.into());
+        test_env.env.set_caller(admin);
+        pair.set_max_price_impact(100);
+        assert_eq!(pair.max_price_impact_bps(), 100);
+        assert!(test_env.env.emitted_event(&pair, MaxPriceImpactUpdated {
+            pair: pair_address,
+            max_price_impact_bps: 100,
+        }));
+
+        // 10_000 in moves the price ~2%, above the 1% limit
+        test_env.token_a.mint(pair_address, U256::from(10_000));
+        let b_out = AmmMath::get_amount_out(U256::from(10_000), reserve, reserve).unwrap();
+        let (amount0_out, amount1_out) = if a_is_token0 { (U256::zero(), b_out) } else { (b_out, U256::zero()) };
+        assert_eq!(
+            pair.try_swap(amount0_out, amount1_out, trader).unwrap_err(),
+            DexError::PriceImpactTooHigh.into()
+        );
+
+        // Skim the rejected input back out; a 2_000 swap (~0.4%) goes through
+        pair.skim(admin);
+        test_env.token_a.mint(pair_address, U256::from(2_000));
+        let b_out = AmmMath::get_amount_out(U256::from(2_000), reserve, reserve).unwrap();
+        let (amount0_out, amount1_out) = if a_is_token0 { (U256::zero(), b_out) } else { (b_out, U256::zero()) };
+        pair.swap(amount0_out, amount1_out, trader);
+        assert_eq!(test_env.token_b.balance_of(trader), b_out);
+    }
+
+    #[test]
+    fn test_pair_balance_accounting_with_fee_on_transfer_token() {
+        use crate::errors::DexError;
+        use crate::math::AmmMath;
+        use crate::tokens::{FeeOnTransferToken, FeeOnTransferTokenInitArgs};
+
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let trader = test_env.env.get_account(1);
+        let mut fot = FeeOnTransferToken::deploy(&test_env.env, FeeOnTransferTokenInitArgs { fee_bps: 100 });
+
+        let mut pair = Pair::deploy(&test_env.env, PairInitArgs {
+            token0: fot.address().clone(),
+            token1: test_env.token_b.address().clone(),
+            factory: admin,
+        });
+        let pair_address = pair.address().clone();
+        let fot_is_token0 = pair.token0() == fot.address().clone();
+
+        let reserve = U256::from(1_000_000);
+        fot.mint(pair_address, reserve);
+        test_env.token_b.mint(pair_address, reserve);
+        pair.sync();
+
+        // Sending 10_000 FOT only delivers 9_900 to the pair
+        fot.mint(trader, U256::from(10_000));
+        test_env.env.set_caller(trader);
+        fot.transfer(pair_address, U256::from(10_000));
+        assert_eq!(fot.balance_of(pair_address), reserve + U256::from(9_900));
+
+        // Pricing the amount sent breaks k; pricing what the pair received works
+        let sent_quote = AmmMath::get_amount_out(U256::from(10_000), reserve, reserve).unwrap();
+        let (amount0_out, amount1_out) = if fot_is_token0 { (U256::zero(), sent_quote) } else { (sent_quote, U256::zero()) };
+        assert_eq!(
+            pair.try_swap(amount0_out, amount1_out, trader).unwrap_err(),
+            DexError::KInvariantViolated.into()
+        );
+        let b_out = AmmMath::get_amount_out(U256::from(9_900), reserve, reserve).unwrap();
+        let (amount0_out, amount1_out) = if fot_is_token0 { (U256::zero(), b_out) } else { (b_out, U256::zero()) };
+        pair.swap(amount0_out, amount1_out, trader);
+        assert_eq!(test_env.token_b.balance_of(trader), b_out);
+
+        // Paying FOT out books the full amount sent, though the trader receives 99%
+        let fot_reserve = reserve + U256::from(9_900);
+        let fot_out = AmmMath::get_amount_out(U256::from(5_000), reserve - b_out, fot_reserve).unwrap();
+        test_env.token_b.transfer(pair_address, U256::from(5_000));
+        let (amount0_out, amount1_out) = if fot_is_token0 { (fot_out, U256::zero()) } else { (U256::zero(), fot_out) };
+        pair.swap(amount0_out, amount1_out, trader);
+        assert_eq!(fot.balance_of(trader), fot_out - fot_out / 100);
+
+        let (reserve0, reserve1, _) = pair.get_reserves();
+        let (fot_reserve, b_reserve) = if fot_is_token0 { (reserve0, reserve1) } else { (reserve1, reserve0) };
+        assert_eq!(fot_reserve, fot.balance_of(pair_address));
+        assert_eq!(b_reserve, test_env.token_b.balance_of(pair_address));
+    }
+
+    #[test]
+    fn test_router_swaps_fee_on_transfer_token() {
+        use crate::dex::mock_factory::{deploy_listed_pair, MockFactory, MockFactoryInitArgs};
+        use crate::errors::DexError;
+        use crate::math::AmmMath;
+        use crate::tokens::{FeeOnTransferToken, FeeOnTransferTokenInitArgs};
+
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let trader = test_env.env.get_account(1);
+        let mut fot = FeeOnTransferToken::deploy(&test_env.env, FeeOnTransferTokenInitArgs { fee_bps: 100 });
+        let fot_address = fot.address().clone();
+        let token_b_address = test_env.token_b.address().clone();
+        let deadline = test_env.env.block_time() + 100;
+
+        test_env.env.set_caller(admin);
+        let mut factory = MockFactory::deploy(&test_env.env, MockFactoryInitArgs { fee_to_setter: admin });
+        deploy_listed_pair(&test_env.env, &mut factory, fot_address, token_b_address);
+        let mut router = Router::deploy(&test_env.env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: test_env.wcspr.address(),
+        });
+        let router_address = router.address();
+
+        // Liquidity: the pair receives 990_000 FOT against 1_000_000 B
+        let liquidity = U256::from(1_000_000);
+        fot.mint(admin, liquidity);
+        test_env.token_b.mint(admin, liquidity);
+        fot.approve(router_address, liquidity);
+        test_env.token_b.approve(router_address, liquidity);
+        router.add_liquidity(
+            fot_address, token_b_address, liquidity, liquidity, U256::zero(), U256::zero(), admin, deadline,
+        );
+        let (fot_reserve, b_reserve) = router.get_reserves(fot_address, token_b_address);
+        assert_eq!(fot_reserve, U256::from(990_000));
+
+        // Selling FOT: the plain swap prices the amount sent and breaks k
+        let amount_in = U256::from(10_000);
+        fot.mint(trader, amount_in);
+        test_env.env.set_caller(trader);
+        fot.approve(router_address, amount_in);
+        let path = vec![fot_address, token_b_address];
+        assert_eq!(
+            router
+                .try_swap_exact_tokens_for_tokens(amount_in, U256::zero(), path.clone(), trader, deadline)
+                .unwrap_err(),
+            DexError::KInvariantViolated.into()
+        );
+        let b_out = AmmMath::get_amount_out(U256::from(9_900), fot_reserve, b_reserve).unwrap();
+        let amounts = router.swap_exact_tokens_for_tokens_supporting_fee_on_transfer_tokens(
+            amount_in, b_out, path, trader, deadline,
+        );
+        assert_eq!(amounts, vec![U256::from(9_900), b_out]);
+        assert_eq!(test_env.token_b.balance_of(trader), b_out);
+
+        // Buying FOT: the minimum output is checked against what the trader received
+        let (b_reserve, fot_reserve) = router.get_reserves(token_b_address, fot_address);
+        let b_in = U256::from(5_000);
+        let fot_out = AmmMath::get_amount_out(b_in, b_reserve, fot_reserve).unwrap();
+        let received = fot_out - fot_out / 100;
+        test_env.token_b.approve(router_address, b_in);
+        let path = vec![token_b_address, fot_address];
+        assert_eq!(
+            router
+                .try_swap_exact_tokens_for_tokens_supporting_fee_on_transfer_tokens(b_in, fot_out, path.clone(), trader, deadline)
+                .unwrap_err(),
+            DexError::InsufficientOutputAmount.into()
+        );
+        router.swap_exact_tokens_for_tokens_supporting_fee_on_transfer_tokens(
+            b_in, received, path, trader, deadline,
+        );
+        assert_eq!(fot.balance_of(trader), received);
+    }
+
+    #[test]
+    fn test_wcspr_unwraps_through_the_router_into_scspr() {
+        use crate::lst::scspr_token::{ScsprToken, ScsprTokenInitArgs};
+        use crate::lst::staking_manager::{StakingManager, StakingManagerInitArgs};
+        use crate::tokens::WcsprToken;
+        use odra::casper_types::U512;
+        use odra::host::NoArgs;
+
+        let test_env = TestEnv::new();
+        let env = &test_env.env;
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let validator = env.get_account(2);
+
+        env.set_caller(admin);
+        let mut wcspr = WcsprToken::deploy(env, NoArgs);
+        let mut router = Router::deploy(env, RouterInitArgs {
+            factory: test_env.factory.address().clone(),
+            wcspr: wcspr.address().clone(),
+        });
+        let mut scspr = ScsprToken::deploy(env, ScsprTokenInitArgs { staking_manager: admin });
+        let mut manager = StakingManager::deploy(env, StakingManagerInitArgs {
+            scspr_token_address: scspr.address().clone(),
+        });
+        scspr.set_staking_manager(manager.address().clone());
+        manager.add_validator(validator);
+        manager.set_validator_public_key(validator, env.get_validator(0));
+        manager.set_minimum_stake(U256::one());
+        router.set_staking_manager(manager.address().clone());
+
+        // Wrap CSPR, then have the router unwrap it and stake the CSPR
+        let amount = U256::from(1_000_000);
+        env.set_caller(user);
+        wcspr.with_tokens(U512::from(1_000_000u64)).deposit();
+        wcspr.approve(router.address().clone(), amount);
+        assert_eq!(router.get_scspr_quote(amount), (amount, true));
+        let minted = router.swap_exact_wcspr_for_scspr(amount, amount, user, env.block_time() + 100);
+
+        assert_eq!(minted, amount);
+        assert_eq!(scspr.balance_of(user), amount);
+        assert_eq!(manager.get_total_cspr_staked(), amount);
+        assert_eq!(wcspr.total_supply(), U256::zero());
+        assert_eq!(env.balance_of(&wcspr.address()), U512::zero());
+        assert_eq!(env.balance_of(&router.address()), U512::zero());
+    }
+
+    #[test]
+    fn test_wcspr_to_scspr_pool_swap_reports_referral_fees_once() {
+        use crate::dex::mock_factory::{deploy_listed_pair, MockFactory, MockFactoryInitArgs};
+        use crate::incentives::referral_registry::{ReferralRegistry, ReferralRegistryInitArgs};
+        use crate::lst::staking_manager::{StakingManager, StakingManagerInitArgs};
+
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let trader = test_env.env.get_account(1);
+        let referrer = test_env.env.get_account(2);
+        let wcspr_address = test_env.wcspr.address().clone();
+        let deadline = test_env.env.block_time() + 100;
+
+        // Below the minimum stake, so the swap goes through the pool
+        let mut scspr = LpToken::deploy(&test_env.env, LpTokenInitArgs {
+            name: String::from("Staked CSPR"),
+            symbol: String::from("sCSPR"),
+        });
+        let manager = StakingManager::deploy(&test_env.env, StakingManagerInitArgs {
+            scspr_token_address: scspr.address().clone(),
+        });
+        let mut registry = ReferralRegistry::deploy(&test_env.env, ReferralRegistryInitArgs {
+            reward_token_address: wcspr_address,
+            referral_bps: 1_000,
+        });
+        test_env.env.set_caller(admin);
+        let mut factory = MockFactory::deploy(&test_env.env, MockFactoryInitArgs { fee_to_setter: admin });
+        deploy_listed_pair(&test_env.env, &mut factory, wcspr_address, scspr.address());
+        let mut router = Router::deploy(&test_env.env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: wcspr_address,
+        });
+        let router_address = router.address();
+        router.set_staking_manager(manager.address().clone());
+        router.set_referral_registry(registry.address().clone());
+        registry.set_reporter(router_address, true);
+        test_env.wcspr.mint(admin, U256::from(1_001_000));
+        test_env.wcspr.approve(registry.address().clone(), U256::from(1_000));
+        registry.fund(U256::from(1_000));
+
+        let liquidity = U256::from(1_000_000);
+        scspr.mint(admin, liquidity);
+        test_env.wcspr.approve(router_address, liquidity);
+        scspr.approve(router_address, liquidity);
+        router.add_liquidity(
+            wcspr_address, scspr.address().clone(), liquidity, liquidity, U256::zero(), U256::zero(), admin, deadline,
+        );
+
+        let amount_in = U256::from(10_000);
+        test_env.wcspr.mint(trader, amount_in);
+        test_env.env.set_caller(trader);
+        registry.set_referrer(referrer);
+        test_env.wcspr.approve(router_address, amount_in);
+        router.swap_exact_wcspr_for_scspr(amount_in, U256::zero(), trader, deadline);
+
+        // 30 WCSPR of fees at 10% accrues 3 to the referrer, once
+        assert_eq!(registry.get_claimable(referrer), U256::from(3));
+        assert_eq!(registry.get_reward_pool(), U256::from(997));
+    }
+
+    #[test]
+    fn test_pair_swap_to_blacklisted_usdc_holder_reverts_with_token_error() {
+        use crate::errors::TokenError;
+        use crate::tokens::UsdcToken;
+
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let trader = test_env.env.get_account(1);
+        test_env.env.set_caller(admin);
+        let mut usdc = UsdcToken::deploy(&test_env.env, odra::host::NoArgs);
+
+        let mut pair = Pair::deploy(&test_env.env, PairInitArgs {
+            token0: usdc.address().clone(),
+            token1: test_env.token_b.address().clone(),
+            factory: admin,
+        });
+        let pair_address = pair.address().clone();
+        usdc.mint(pair_address, U256::from(1_000_000));
+        test_env.token_b.mint(pair_address, U256::from(1_000_000));
+        pair.sync();
+
+        // A frozen recipient makes the pair's payout fail with the token's own error
+        usdc.blacklist(trader);
+        assert!(usdc.is_blacklisted(trader));
+        test_env.token_b.mint(pair_address, U256::from(10_000));
+        let (amount0_out, amount1_out) = if pair.token0() == usdc.address().clone() {
+            (U256::from(9_000), U256::zero())
+        } else {
+            (U256::zero(), U256::from(9_000))
+        };
+        assert_eq!(
+            pair.try_swap(amount0_out, amount1_out, trader).unwrap_err(),
+            TokenError::AccountBlacklisted.into()
+        );
+
+        usdc.unblacklist(trader);
+        pair.swap(amount0_out, amount1_out, trader);
+        assert_eq!(usdc.balance_of(trader), U256::from(9_000));
+
+        test_env.env.set_caller(trader);
+        assert_eq!(usdc.try_blacklist(admin).unwrap_err(), TokenError::Unauthorized.into());
+    }
+
     #[test]
     fn test_calculate_liquidity_first_deposit() {
         use crate::math::AmmMath;
@@ -378,6 +800,96 @@ mod integration_tests {
         assert_eq!(amount1, U256::from(1000));
     }
 
+    #[test]
+    fn test_router_sweep_is_fee_to_setter_only() {
+        use crate::errors::DexError;
+
+        let mut test_env = TestEnv::new();
+        let admin = test_env.env.get_account(0);
+        let user = test_env.env.get_account(1);
+        let router = test_env.router.address().clone();
+
+        // Leave stray tokens on the router
+        test_env.token_a.mint(user, U256::from(500));
+        test_env.env.set_caller(user);
+        test_env.token_a.transfer(router, U256::from(500));
+
+        let token = test_env.token_a.address().clone();
+        assert_eq!(
+            test_env.router.try_sweep_token(token, U256::zero(), user).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+        assert_eq!(
+            test_env.router.try_refund_cspr().unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+
+        test_env.env.set_caller(admin);
+        assert_eq!(
+            test_env.router.try_sweep_token(token, U256::from(501), admin).unwrap_err(),
+            DexError::InsufficientAmount.into()
+        );
+        test_env.router.sweep_token(token, U256::from(500), admin);
+        assert_eq!(test_env.token_a.balance_of(admin), U256::from(500));
+        assert_eq!(test_env.token_a.balance_of(router), U256::zero());
+        test_env.router.refund_cspr();
+    }
+
+    #[test]
+    fn test_exact_output_swap_refunds_only_the_callers_unused_input() {
+        use crate::dex::mock_factory::{deploy_listed_pair, MockFactory, MockFactoryInitArgs};
+        use crate::errors::DexError;
+
+        let test_env = TestEnv::new();
+        let env = &test_env.env;
+        let (admin, trader, stranger) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        let (mut token_a, mut token_b) = (test_env.token_a, test_env.token_b);
+        let (token_a_address, token_b_address) = (token_a.address(), token_b.address());
+        let deadline = env.block_time() + 100;
+
+        env.set_caller(admin);
+        let mut factory = MockFactory::deploy(env, MockFactoryInitArgs { fee_to_setter: admin });
+        deploy_listed_pair(env, &mut factory, token_a_address, token_b_address);
+        let mut router = Router::deploy(env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: test_env.wcspr.address(),
+        });
+        let router_address = router.address();
+
+        let liquidity = U256::from(1_000_000);
+        token_a.mint(admin, liquidity);
+        token_b.mint(admin, liquidity);
+        token_a.approve(router_address, liquidity);
+        token_b.approve(router_address, liquidity);
+        router.add_liquidity(
+            token_a_address, token_b_address, liquidity, liquidity, U256::zero(), U256::zero(), admin, deadline,
+        );
+
+        // Someone else's tokens end up on the router
+        token_a.mint(stranger, U256::from(500));
+        env.set_caller(stranger);
+        token_a.transfer(router_address, U256::from(500));
+
+        // The trader gets back only what the swap did not use
+        let amount_in_max = U256::from(20_000);
+        token_a.mint(trader, amount_in_max);
+        env.set_caller(trader);
+        token_a.approve(router_address, amount_in_max);
+        let amounts = router.swap_tokens_for_exact_tokens(
+            U256::from(10_000), amount_in_max, vec![token_a_address, token_b_address], trader, deadline,
+        );
+        assert_eq!(token_a.balance_of(trader), amount_in_max - amounts[0]);
+        assert_eq!(token_b.balance_of(trader), U256::from(10_000));
+        assert_eq!(token_a.balance_of(router_address), U256::from(500));
+
+        // Pair tokens cannot be swept, even by the fee_to_setter
+        env.set_caller(admin);
+        assert_eq!(
+            router.try_sweep_token(token_a_address, U256::zero(), admin).unwrap_err(),
+            DexError::TokenNotRescuable.into()
+        );
+    }
+
     #[test]
     fn test_k_invariant_verification() {
         use crate::math::AmmMath;
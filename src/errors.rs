@@ -73,6 +73,57 @@ pub enum DexError {
     
     /// Invalid configuration
     InvalidConfiguration = 23,
+    
+    /// Lock does not exist
+    LockNotFound = 24,
+    
+    /// Lock has not reached its unlock time
+    LockNotExpired = 25,
+    
+    /// Unlock time is in the past or earlier than the current one
+    InvalidUnlockTime = 26,
+    
+    /// Swap moves the price beyond the pair's max price impact
+    PriceImpactTooHigh = 27,
+    
+    /// No price observation old enough for the requested window
+    ObservationTooOld = 28,
+    
+    /// Epoch has not closed yet
+    EpochNotClosed = 29,
+    
+    /// Reward already claimed
+    AlreadyClaimed = 30,
+    
+    /// Token is part of a pair's reserves and cannot be rescued
+    TokenNotRescuable = 31,
+    
+    /// Merkle proof does not match the root
+    InvalidProof = 32,
+    
+    /// Airdrop campaign not found
+    CampaignNotFound = 33,
+    
+    /// Airdrop campaign has expired
+    CampaignExpired = 34,
+    
+    /// Airdrop campaign has not expired yet
+    CampaignNotExpired = 35,
+    
+    /// Keeper task is inactive, ran too recently or its condition did not hold
+    TaskConditionNotMet = 36,
+    
+    /// Keeper task not found
+    TaskNotFound = 37,
+    
+    /// Users cannot refer themselves
+    InvalidReferrer = 38,
+    
+    /// Referral is bound on the first interaction and cannot change
+    ReferralAlreadyBound = 39,
+    
+    /// Claim would pay out more than the airdrop campaign was funded with
+    CampaignBudgetExceeded = 40,
 }
 
 /// Custom errors for the LP Token contract
@@ -83,4 +134,31 @@ pub enum TokenError {
     
     /// Insufficient balance for operation
     InsufficientBalance = 101,
+    
+    /// Permit deadline has passed
+    PermitExpired = 102,
+    
+    /// Permit signature is invalid or not from the owner
+    InvalidSignature = 103,
+    
+    /// Caller is not allowed to mint or burn
+    Unauthorized = 104,
+    
+    /// Mint would take the minter's outstanding supply above its cap
+    MintCapExceeded = 105,
+    
+    /// Past votes were requested for a time that has not finished yet
+    FutureLookup = 106,
+    
+    /// Transfer fee is above 100%
+    InvalidFee = 107,
+    
+    /// Account is blacklisted by the token admin
+    AccountBlacklisted = 108,
+    
+    /// A contract address the token depends on has not been set
+    NotConfigured = 109,
+    
+    /// Amount is worth less than one share
+    ZeroShares = 110,
 }
\ No newline at end of file
@@ -76,26 +76,149 @@ pub struct Sync {
     pub reserve1: U256,
 }
 
-/// Event emitted when LP tokens are transferred
+// ============ CEP-18 Events ============
+// Names and fields follow the CEP-18 standard so explorers and wallets
+// can index balances and transfers.
+
+/// Event emitted when tokens are minted
 #[odra::event]
-pub struct Transfer {
-    /// From address
-    pub from: Address,
-    /// To address
-    pub to: Address,
-    /// Amount transferred
-    pub value: U256,
+pub struct Mint {
+    /// Recipient address
+    pub recipient: Address,
+    /// Amount minted
+    pub amount: U256,
 }
 
-/// Event emitted when approval is granted
+/// Event emitted when tokens are burned
 #[odra::event]
-pub struct Approval {
+pub struct Burn {
+    /// Address the tokens were burned from
+    pub owner: Address,
+    /// Amount burned
+    pub amount: U256,
+}
+
+/// Event emitted when an allowance is set
+#[odra::event]
+pub struct SetAllowance {
     /// Owner address
     pub owner: Address,
     /// Spender address
     pub spender: Address,
-    /// Amount approved
-    pub value: U256,
+    /// New allowance
+    pub allowance: U256,
+}
+
+/// Event emitted when an allowance is raised
+#[odra::event]
+pub struct IncreaseAllowance {
+    /// Owner address
+    pub owner: Address,
+    /// Spender address
+    pub spender: Address,
+    /// New allowance
+    pub allowance: U256,
+    /// Amount added
+    pub inc_by: U256,
+}
+
+/// Event emitted when an allowance is lowered
+#[odra::event]
+pub struct DecreaseAllowance {
+    /// Owner address
+    pub owner: Address,
+    /// Spender address
+    pub spender: Address,
+    /// New allowance
+    pub allowance: U256,
+    /// Amount removed
+    pub decr_by: U256,
+}
+
+/// Event emitted when tokens are transferred by their owner
+#[odra::event]
+pub struct Transfer {
+    /// Sender address
+    pub sender: Address,
+    /// Recipient address
+    pub recipient: Address,
+    /// Amount transferred
+    pub amount: U256,
+}
+
+/// Event emitted when tokens are transferred by an approved spender
+#[odra::event]
+pub struct TransferFrom {
+    /// Spender address
+    pub spender: Address,
+    /// Owner the tokens were taken from
+    pub owner: Address,
+    /// Recipient address
+    pub recipient: Address,
+    /// Amount transferred
+    pub amount: U256,
+}
+
+/// Event emitted when a token minter is authorized, re-capped or removed
+#[odra::event]
+pub struct MinterUpdated {
+    /// Minter address
+    pub minter: Address,
+    /// Most tokens the minter may have outstanding (zero when removed)
+    pub cap: U256,
+}
+
+/// Event emitted when a minter mints tokens
+#[odra::event]
+pub struct TokensMinted {
+    /// Minter address
+    pub minter: Address,
+    /// Recipient address
+    pub to: Address,
+    /// Amount minted
+    pub amount: U256,
+}
+
+/// Event emitted when a minter burns tokens
+#[odra::event]
+pub struct TokensBurned {
+    /// Minter address
+    pub minter: Address,
+    /// Address the tokens were burned from
+    pub from: Address,
+    /// Amount burned
+    pub amount: U256,
+}
+
+/// Event emitted when a token admin blacklists or clears an account
+#[odra::event]
+pub struct BlacklistUpdated {
+    /// Account address
+    pub account: Address,
+    /// Whether the account is now blacklisted
+    pub blacklisted: bool,
+}
+
+/// Event emitted when an account changes its vote delegate
+#[odra::event]
+pub struct DelegateChanged {
+    /// Account whose votes moved
+    pub delegator: Address,
+    /// Previous delegate (None if the account had never delegated)
+    pub from_delegate: Option<Address>,
+    /// New delegate
+    pub to_delegate: Address,
+}
+
+/// Event emitted when a delegate's vote weight changes
+#[odra::event]
+pub struct DelegateVotesChanged {
+    /// Delegate address
+    pub delegate: Address,
+    /// Votes before the change
+    pub previous_votes: U256,
+    /// Votes after the change
+    pub new_votes: U256,
 }
 
 /// Event emitted when fee is collected
@@ -107,4 +230,113 @@ pub struct FeeCollected {
     pub recipient: Address,
     /// Amount collected
     pub amount: U256,
-}
\ No newline at end of file
+}
+
+/// Event emitted when liquidity is migrated from a foreign pair
+#[odra::event]
+pub struct LiquidityMigrated {
+    /// Address that owned the foreign LP tokens
+    pub provider: Address,
+    /// Address of the foreign pair
+    pub foreign_pair: Address,
+    /// Address of the Ectoplasm pair
+    pub pair: Address,
+    /// Foreign LP tokens burned
+    pub foreign_liquidity: U256,
+    /// Amount of token A added
+    pub amount_a: U256,
+    /// Amount of token B added
+    pub amount_b: U256,
+    /// Ectoplasm LP tokens minted
+    pub liquidity: U256,
+}
+
+/// Event emitted when LP tokens are locked
+#[odra::event]
+pub struct LiquidityLocked {
+    /// Lock identifier
+    pub lock_id: u64,
+    /// Owner of the lock
+    pub owner: Address,
+    /// Locked LP token
+    pub lp_token: Address,
+    /// Amount of LP tokens locked
+    pub amount: U256,
+    /// Timestamp when the lock can be withdrawn
+    pub unlock_time: u64,
+}
+
+/// Event emitted when a lock's unlock time is extended
+#[odra::event]
+pub struct LockExtended {
+    /// Lock identifier
+    pub lock_id: u64,
+    /// Previous unlock time
+    pub old_unlock_time: u64,
+    /// New unlock time
+    pub new_unlock_time: u64,
+}
+
+/// Event emitted when a lock is split in two
+#[odra::event]
+pub struct LockSplit {
+    /// Original lock identifier
+    pub lock_id: u64,
+    /// Newly created lock identifier
+    pub new_lock_id: u64,
+    /// Amount moved to the new lock
+    pub amount: U256,
+}
+
+/// Event emitted when ownership of a lock is transferred
+#[odra::event]
+pub struct LockOwnershipTransferred {
+    /// Lock identifier
+    pub lock_id: u64,
+    /// Previous owner
+    pub previous_owner: Address,
+    /// New owner
+    pub new_owner: Address,
+}
+
+/// Event emitted when locked LP tokens are withdrawn
+#[odra::event]
+pub struct LiquidityUnlocked {
+    /// Lock identifier
+    pub lock_id: u64,
+    /// Owner of the lock
+    pub owner: Address,
+    /// Unlocked LP token
+    pub lp_token: Address,
+    /// Amount of LP tokens withdrawn
+    pub amount: U256,
+}
+
+/// Event emitted when a pair's max price impact is updated
+#[odra::event]
+pub struct MaxPriceImpactUpdated {
+    /// Address of the pair
+    pub pair: Address,
+    /// New max price impact in basis points (0 = disabled)
+    pub max_price_impact_bps: u32,
+}
+
+/// Event emitted when the incentive contracts notified of LP balance changes are updated
+#[odra::event]
+pub struct IncentiveHooksUpdated {
+    /// LpRewardsDistributor synced on LP balance changes (None = disabled)
+    pub lp_rewards_distributor: Option<Address>,
+    /// IncentiveManager notified on LP balance changes (None = disabled)
+    pub incentive_manager: Option<Address>,
+}
+
+/// Event emitted when tokens stuck in the Router or Factory are rescued
+#[odra::event]
+pub struct TokenRescued {
+    /// Rescued token
+    pub token: Address,
+    /// Recipient of the rescued tokens
+    pub to: Address,
+    /// Amount rescued
+    pub amount: U256,
+}
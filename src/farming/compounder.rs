@@ -0,0 +1,692 @@
+//! LP Compounder - CEP-4626 vault auto-compounding farming rewards
+//!
+//! Users deposit LP tokens of an ECTO pair and receive shares. The vault
+//! stakes all LP in a StakingPool farm. Keepers periodically harvest the
+//! farm's ECTO rewards, take a performance fee, swap half of the rest into
+//! the pair's other token through the Router, add liquidity and stake the
+//! new LP, so the share price grows as rewards are compounded.
+
+use odra::prelude::*;
+use odra::casper_types::bytesrepr::Bytes;
+use odra::casper_types::{PublicKey, U256};
+use odra::ContractRef;
+use odra::OdraError;
+use super::errors::FarmingError;
+use super::events::Harvested;
+use super::staking_pool::StakingPoolContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE, KEEPER_ROLE, PAUSER_ROLE};
+use crate::cep4626::{base, Cep4626Hooks, Cep4626Vault};
+use crate::dex::router::{PairContractContractRef, RouterContractRef};
+use crate::math::BPS_DENOMINATOR;
+use crate::token::{Cep18Base, Cep18TokenContractRef, TokenMetadata};
+
+/// Default share of harvested rewards taken as performance fee (3%)
+pub const DEFAULT_PERFORMANCE_FEE_BPS: u32 = 300;
+
+/// Maximum performance fee (20%)
+pub const MAX_PERFORMANCE_FEE_BPS: u32 = 2_000;
+
+/// Auto-compounding LP vault
+#[odra::module]
+pub struct LpCompounder {
+    /// cLP share ledger (CEP-18)
+    token: SubModule<Cep18Base>,
+    
+    /// LP token (pair) deposited into the vault
+    lp_token: Var<Address>,
+    /// Pair token that is not ECTO
+    other_token: Var<Address>,
+    /// ECTO reward token
+    reward_token: Var<Address>,
+    /// StakingPool farm the LP is staked in
+    staking_pool: Var<Address>,
+    /// Farm pool ID
+    pool_id: Var<u32>,
+    /// Router used to swap rewards and add liquidity
+    router: Var<Address>,
+    
+    /// Share of harvested rewards taken as performance fee (bps)
+    performance_fee_bps: Var<u32>,
+    /// Address receiving the performance fee
+    fee_recipient: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Paused state
+    paused: Var<bool>,
+}
+
+#[odra::module]
+impl LpCompounder {
+    /// Initialize the compounder for `pool_id` of `staking_pool`
+    ///
+    /// The pool's LP token must be a pair with ECTO as one side.
+    pub fn init(
+        &mut self,
+        staking_pool_address: Address,
+        pool_id: u32,
+        router_address: Address,
+        reward_token_address: Address,
+    ) {
+        let caller = self.env().caller();
+    
+        let pool = StakingPoolContractRef::new(self.env(), staking_pool_address)
+            .get_pool_info(pool_id)
+            .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
+        let pair = PairContractContractRef::new(self.env(), pool.lp_token);
+        let (token0, token1) = (pair.token0(), pair.token1());
+        let other_token = if token0 == reward_token_address {
+            token1
+        } else if token1 == reward_token_address {
+            token0
+        } else {
+            self.env().revert(FarmingError::InvalidConfiguration)
+        };
+    
+        self.token.init(String::from("Ectoplasm Compounding LP"), String::from("cLP"), 18);
+    
+        self.lp_token.set(pool.lp_token);
+        self.other_token.set(other_token);
+        self.reward_token.set(reward_token_address);
+        self.staking_pool.set(staking_pool_address);
+        self.pool_id.set(pool_id);
+        self.router.set(router_address);
+    
+        self.performance_fee_bps.set(DEFAULT_PERFORMANCE_FEE_BPS);
+        self.fee_recipient.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(PAUSER_ROLE, caller);
+        self.access.grant(KEEPER_ROLE, caller);
+        self.paused.set(false);
+    }
+    
+    // ========================================
+    // CEP-18 Token Functions
+    // ========================================
+    
+    delegate! {
+        to self.token {
+            fn name(&self) -> String;
+            fn symbol(&self) -> String;
+            fn decimals(&self) -> u8;
+            fn total_supply(&self) -> U256;
+            fn balance_of(&self, owner: Address) -> U256;
+            fn allowance(&self, owner: Address, spender: Address) -> U256;
+            fn transfer(&mut self, recipient: Address, amount: U256) -> bool;
+            fn approve(&mut self, spender: Address, amount: U256) -> bool;
+            fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn transfer_from(&mut self, owner: Address, recipient: Address, amount: U256) -> bool;
+            fn permit(&mut self, owner: Address, spender: Address, value: U256, deadline: u64, public_key: PublicKey, signature: Bytes);
+            fn nonces(&self, owner: Address) -> u64;
+            fn domain_separator(&self) -> [u8; 32];
+            fn permit_digest(&self, owner: Address, spender: Address, value: U256, nonce: u64, deadline: u64) -> [u8; 32];
+            fn contract_version(&self) -> String;
+            fn metadata(&self) -> TokenMetadata;
+        }
+    }
+    
+    // ========================================
+    // CEP-4626 Entry Points
+    // ========================================
+    
+    /// Deposit the caller's LP and mint shares to `receiver`
+    pub fn deposit(&mut self, assets: U256, receiver: Address) -> U256 {
+        Cep4626Vault::deposit(self, assets, receiver)
+    }
+    
+    /// Mint exactly `shares` to `receiver` for the caller's LP
+    pub fn mint(&mut self, shares: U256, receiver: Address) -> U256 {
+        Cep4626Vault::mint(self, shares, receiver)
+    }
+    
+    /// Burn `owner`'s shares for `assets` LP sent to `receiver`
+    pub fn withdraw(&mut self, assets: U256, receiver: Address, owner: Address) -> U256 {
+        Cep4626Vault::withdraw(self, assets, receiver, owner)
+    }
+    
+    /// Burn exactly `shares` of `owner`'s for LP sent to `receiver`
+    pub fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
+        Cep4626Vault::redeem(self, shares, receiver, owner)
+    }
+    
+    pub fn max_deposit(&self, receiver: Address) -> U256 {
+        Cep4626Vault::max_deposit(self, receiver)
+    }
+    
+    pub fn max_mint(&self, receiver: Address) -> U256 {
+        Cep4626Vault::max_mint(self, receiver)
+    }
+    
+    pub fn max_withdraw(&self, owner: Address) -> U256 {
+        Cep4626Vault::max_withdraw(self, owner)
+    }
+    
+    pub fn max_redeem(&self, owner: Address) -> U256 {
+        Cep4626Vault::max_redeem(self, owner)
+    }
+    
+    /// Get the LP staked by the vault
+    pub fn total_assets(&self) -> U256 {
+        StakingPoolContractRef::new(self.env(), self.get_staking_pool())
+            .get_user_stake(self.env().self_address(), self.pool_id.get_or_default())
+            .map(|stake| stake.amount)
+            .unwrap_or_default()
+    }
+    
+    pub fn convert_to_shares(&self, assets: U256) -> U256 {
+        base::convert_to_shares(self, assets)
+    }
+    
+    pub fn convert_to_assets(&self, shares: U256) -> U256 {
+        base::convert_to_assets(self, shares)
+    }
+    
+    pub fn preview_deposit(&self, assets: U256) -> U256 {
+        base::preview_deposit(self, assets)
+    }
+    
+    pub fn preview_mint(&self, shares: U256) -> U256 {
+        base::preview_mint(self, shares)
+    }
+    
+    pub fn preview_withdraw(&self, assets: U256) -> U256 {
+        base::preview_withdraw(self, assets)
+    }
+    
+    pub fn preview_redeem(&self, shares: U256) -> U256 {
+        base::preview_redeem(self, shares)
+    }
+    
+    // ========================================
+    // Compounding
+    // ========================================
+    
+    /// Claim the farm's ECTO rewards and compound them into staked LP (keeper only)
+    ///
+    /// The performance fee is taken from the claimed ECTO, half of the rest is
+    /// swapped into the pair's other token for at least `min_swap_out`, and
+    /// both halves are added as liquidity and staked, using at least
+    /// `min_ecto_added` ECTO and `min_other_added` of the other token. Tokens
+    /// the router does not use stay in the vault for the next harvest. The
+    /// swap and the liquidity add revert once `deadline` has passed.
+    ///
+    /// Returns the LP added to the vault
+    pub fn harvest(
+        &mut self,
+        min_swap_out: U256,
+        min_ecto_added: U256,
+        min_other_added: U256,
+        deadline: u64,
+    ) -> U256 {
+        self.access.require_any_role(&[KEEPER_ROLE, ADMIN_ROLE], FarmingError::Unauthorized);
+        self.ensure_not_paused();
+    
+        let self_address = self.env().self_address();
+        let ecto_address = self.reward_token.get_or_revert_with(FarmingError::InvalidConfiguration);
+        let mut ecto = Cep18TokenContractRef::new(self.env(), ecto_address);
+        let leftover = ecto.balance_of(self_address);
+        StakingPoolContractRef::new(self.env(), self.get_staking_pool())
+            .claim_rewards(self.pool_id.get_or_default());
+        let rewards = ecto.balance_of(self_address) - leftover;
+    
+        // Take the performance fee
+        let fee = rewards * U256::from(self.performance_fee_bps.get_or_default())
+            / U256::from(BPS_DENOMINATOR);
+        if fee > U256::zero() && !ecto.transfer(self.get_fee_recipient(), fee) {
+            self.env().revert(FarmingError::TransferFailed);
+        }
+    
+        // Swap half of the rest into the other pair token
+        let to_compound = leftover + rewards - fee;
+        let router_address = self.router.get_or_revert_with(FarmingError::InvalidConfiguration);
+        let mut router = RouterContractRef::new(self.env(), router_address);
+        let other_address = self.other_token.get_or_revert_with(FarmingError::InvalidConfiguration);
+        ecto.approve(router_address, to_compound);
+        router.swap_exact_tokens_for_tokens(
+            to_compound / 2,
+            min_swap_out,
+            vec![ecto_address, other_address],
+            self_address,
+            deadline,
+        );
+    
+        // Add both halves back as liquidity and stake it
+        let ecto_amount = ecto.balance_of(self_address);
+        let mut other_token = Cep18TokenContractRef::new(self.env(), other_address);
+        let other_amount = other_token.balance_of(self_address);
+        other_token.approve(router_address, other_amount);
+        let (_, _, liquidity) = router.add_liquidity(
+            ecto_address,
+            other_address,
+            ecto_amount,
+            other_amount,
+            min_ecto_added,
+            min_other_added,
+            self_address,
+            deadline,
+        );
+        self.stake_lp(liquidity);
+    
+        self.env().emit_event(Harvested {
+            pool_id: self.pool_id.get_or_default(),
+            rewards,
+            performance_fee: fee,
+            liquidity_added: liquidity,
+            timestamp: self.env().get_block_time(),
+        });
+        base::emit_share_price(self);
+    
+        liquidity
+    }
+    
+    // ========================================
+    // Admin Functions
+    // ========================================
+    
+    /// Set the performance fee and its recipient (admin only)
+    pub fn set_performance_fee(&mut self, fee_bps: u32, recipient: Address) {
+        self.access.require_role(ADMIN_ROLE, FarmingError::Unauthorized);
+    
+        if fee_bps > MAX_PERFORMANCE_FEE_BPS {
+            self.env().revert(FarmingError::InvalidConfiguration);
+        }
+        self.performance_fee_bps.set(fee_bps);
+        self.fee_recipient.set(recipient);
+    }
+    
+    /// Get the performance fee, in bps
+    pub fn get_performance_fee(&self) -> u32 {
+        self.performance_fee_bps.get_or_default()
+    }
+    
+    /// Get the address receiving the performance fee
+    pub fn get_fee_recipient(&self) -> Address {
+        self.fee_recipient.get_or_revert_with(FarmingError::InvalidConfiguration)
+    }
+    
+    /// Get the StakingPool farm the LP is staked in
+    pub fn get_staking_pool(&self) -> Address {
+        self.staking_pool.get_or_revert_with(FarmingError::InvalidConfiguration)
+    }
+    
+    /// Get the farm pool ID
+    pub fn get_pool_id(&self) -> u32 {
+        self.pool_id.get_or_default()
+    }
+    
+    pub fn pause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, FarmingError::Unauthorized);
+        self.paused.set(true);
+    }
+    
+    pub fn unpause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, FarmingError::Unauthorized);
+        self.paused.set(false);
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        self.paused.get_or_default()
+    }
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    /// Stake LP held by the vault in the farm
+    fn stake_lp(&mut self, amount: U256) {
+        let staking_pool = self.get_staking_pool();
+        Cep18TokenContractRef::new(self.env(), self.asset()).approve(staking_pool, amount);
+        StakingPoolContractRef::new(self.env(), staking_pool)
+            .stake(self.pool_id.get_or_default(), amount);
+    }
+    
+    /// LP the farm still accepts from the vault (pause, pool status and stake caps)
+    fn farm_headroom(&self) -> U256 {
+        StakingPoolContractRef::new(self.env(), self.get_staking_pool())
+            .get_stake_headroom(self.env().self_address(), self.pool_id.get_or_default())
+    }
+    
+    fn ensure_not_paused(&self) {
+        if self.paused.get_or_default() {
+            self.env().revert(FarmingError::ContractPaused);
+        }
+    }
+}
+
+// ============================================================================
+// CEP-4626 Implementation
+// ============================================================================
+
+impl Cep4626Hooks for LpCompounder {
+    fn vault_total_assets(&self) -> U256 {
+        self.total_assets()
+    }
+    
+    fn vault_total_shares(&self) -> U256 {
+        self.token.total_supply()
+    }
+    
+    fn vault_shares_of(&self, owner: Address) -> U256 {
+        self.token.balance_of(owner)
+    }
+    
+    fn mint_vault_shares(&mut self, to: Address, shares: U256) {
+        self.token.mint(to, shares);
+    }
+    
+    fn burn_vault_shares(&mut self, from: Address, shares: U256) {
+        self.token.burn(from, shares);
+    }
+    
+    fn insufficient_shares_error(&self) -> OdraError {
+        FarmingError::InsufficientBalance.into()
+    }
+    
+    fn before_deposit(&mut self, caller: Address, _receiver: Address, assets: U256, _shares: U256) {
+        self.ensure_not_paused();
+    
+        let self_address = self.env().self_address();
+        if !Cep18TokenContractRef::new(self.env(), self.asset()).transfer_from(caller, self_address, assets) {
+            self.env().revert(FarmingError::TransferFailed);
+        }
+        self.stake_lp(assets);
+    }
+    
+    fn before_withdraw(
+        &mut self,
+        caller: Address,
+        _receiver: Address,
+        owner: Address,
+        _assets: U256,
+        shares: U256,
+    ) {
+        self.ensure_not_paused();
+    
+        // Check allowance if caller is not owner
+        if caller != owner {
+            let allowance = self.allowance(owner, caller);
+            if allowance < shares {
+                self.env().revert(FarmingError::Unauthorized);
+            }
+            self.token.raw_approve(owner, caller, allowance - shares);
+        }
+    }
+    
+    fn after_withdraw(
+        &mut self,
+        _caller: Address,
+        receiver: Address,
+        _owner: Address,
+        assets: U256,
+        _shares: U256,
+    ) {
+        StakingPoolContractRef::new(self.env(), self.get_staking_pool())
+            .unstake(self.pool_id.get_or_default(), assets);
+        if !Cep18TokenContractRef::new(self.env(), self.asset()).transfer(receiver, assets) {
+            self.env().revert(FarmingError::TransferFailed);
+        }
+    }
+}
+
+impl Cep4626Vault for LpCompounder {
+    fn asset(&self) -> Address {
+        self.lp_token.get_or_revert_with(FarmingError::InvalidConfiguration)
+    }
+    
+    fn total_assets(&self) -> U256 {
+        LpCompounder::total_assets(self)
+    }
+    
+    fn convert_to_shares(&self, assets: U256) -> U256 {
+        base::convert_to_shares(self, assets)
+    }
+    
+    fn convert_to_assets(&self, shares: U256) -> U256 {
+        base::convert_to_assets(self, shares)
+    }
+    
+    fn max_deposit(&self, _receiver: Address) -> U256 {
+        if self.paused.get_or_default() {
+            return U256::zero();
+        }
+        self.farm_headroom()
+    }
+    
+    fn max_mint(&self, _receiver: Address) -> U256 {
+        if self.paused.get_or_default() {
+            return U256::zero();
+        }
+        let headroom = self.farm_headroom();
+        if headroom == U256::MAX {
+            return U256::MAX;
+        }
+        self.convert_to_shares(headroom)
+    }
+    
+    fn max_withdraw(&self, owner: Address) -> U256 {
+        if self.paused.get_or_default() {
+            return U256::zero();
+        }
+        base::max_withdraw(self, owner)
+    }
+    
+    fn max_redeem(&self, owner: Address) -> U256 {
+        if self.paused.get_or_default() {
+            return U256::zero();
+        }
+        base::max_redeem(self, owner)
+    }
+    
+    fn preview_deposit(&self, assets: U256) -> U256 {
+        base::preview_deposit(self, assets)
+    }
+    
+    fn preview_mint(&self, shares: U256) -> U256 {
+        base::preview_mint(self, shares)
+    }
+    
+    fn preview_withdraw(&self, assets: U256) -> U256 {
+        base::preview_withdraw(self, assets)
+    }
+    
+    fn preview_redeem(&self, shares: U256) -> U256 {
+        base::preview_redeem(self, shares)
+    }
+    
+    fn deposit(&mut self, assets: U256, receiver: Address) -> U256 {
+        base::deposit(self, assets, receiver)
+    }
+    
+    fn mint(&mut self, shares: U256, receiver: Address) -> U256 {
+        base::mint(self, shares, receiver)
+    }
+    
+    fn withdraw(&mut self, assets: U256, receiver: Address, owner: Address) -> U256 {
+        base::withdraw(self, assets, receiver, owner)
+    }
+    
+    fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
+        base::redeem(self, shares, receiver, owner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::DexError;
+    use crate::dex::mock_factory::{deploy_listed_pair, MockFactory, MockFactoryInitArgs};
+    use crate::dex::pair::PairHostRef;
+    use crate::dex::router::{Router, RouterInitArgs, RouterHostRef};
+    use crate::events::{Mint, Transfer};
+    use crate::farming::staking_pool::{StakingPool, StakingPoolInitArgs};
+    use crate::token::{LpToken, LpTokenInitArgs, LpTokenHostRef};
+    use crate::tokens::{EctoToken, EctoTokenHostRef};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+
+    struct Setup {
+        env: HostEnv,
+        ecto: EctoTokenHostRef,
+        other: LpTokenHostRef,
+        router: RouterHostRef,
+        pair: PairHostRef,
+        vault: LpCompounderHostRef,
+    }
+
+    /// An ECTO/TKB pair farmed with 10_000 ECTO over 100 seconds
+    fn setup() -> Setup {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut other = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token B"),
+            symbol: String::from("TKB"),
+        });
+        let mut factory = MockFactory::deploy(&env, MockFactoryInitArgs { fee_to_setter: admin });
+        let pair_address = deploy_listed_pair(&env, &mut factory, ecto.address(), other.address());
+        let mut router = Router::deploy(&env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: env.get_account(9),
+        });
+
+        let seed = U256::from(1_000_000);
+        ecto.mint(admin, seed);
+        other.mint(admin, seed);
+        ecto.approve(router.address(), seed);
+        other.approve(router.address(), seed);
+        router.add_liquidity(
+            ecto.address(), other.address(), seed, seed, U256::zero(), U256::zero(), admin, env.block_time() + 1_000,
+        );
+        let pair = PairHostRef::new(pair_address, env.clone());
+
+        let mut farm = StakingPool::deploy(&env, StakingPoolInitArgs {
+            reward_token_address: ecto.address(),
+        });
+        let pool_id = farm.create_pool(pair_address, U256::one());
+        let budget = U256::from(10_000);
+        ecto.mint(admin, budget);
+        ecto.approve(farm.address(), budget);
+        farm.notify_reward_amount(pool_id, budget, 100);
+
+        let vault = LpCompounder::deploy(&env, LpCompounderInitArgs {
+            staking_pool_address: farm.address(),
+            pool_id,
+            router_address: router.address(),
+            reward_token_address: ecto.address(),
+        });
+
+        Setup { env, ecto, other, router, pair, vault }
+    }
+
+    /// Give `user` LP by adding 100_000 of each token and deposit it all
+    fn deposit_lp(s: &mut Setup, user: Address) -> U256 {
+        let amount = U256::from(100_000);
+        s.env.set_caller(s.env.get_account(0));
+        s.ecto.mint(user, amount);
+        s.other.mint(user, amount);
+
+        s.env.set_caller(user);
+        s.ecto.approve(s.router.address(), amount);
+        s.other.approve(s.router.address(), amount);
+        let (_, _, lp) = s.router.add_liquidity(
+            s.ecto.address(), s.other.address(), amount, amount,
+            U256::zero(), U256::zero(), user, s.env.block_time() + 1_000,
+        );
+        s.pair.approve(s.vault.address(), lp);
+        assert_eq!(s.vault.deposit(lp, user), lp);
+        lp
+    }
+
+    #[test]
+    fn test_harvest_compounds_rewards_into_share_price() {
+        let mut s = setup();
+        let admin = s.env.get_account(0);
+        let user = s.env.get_account(1);
+        let lp = deposit_lp(&mut s, user);
+        assert_eq!(s.vault.total_assets(), lp);
+
+        s.env.advance_block_time(100);
+        let fees_before = s.ecto.balance_of(admin);
+        s.env.set_caller(admin);
+        let added = s.vault.harvest(U256::zero(), U256::zero(), U256::zero(), s.env.block_time() + 100);
+
+        // 3% of the ~10_000 ECTO harvested went to the fee recipient
+        let fee = s.ecto.balance_of(admin) - fees_before;
+        assert!(fee >= U256::from(299) && fee <= U256::from(300));
+        assert!(added > U256::zero());
+        assert_eq!(s.vault.total_assets(), lp + added);
+
+        // The depositor redeems for more LP than they put in
+        s.env.set_caller(user);
+        let shares = s.vault.balance_of(user);
+        let assets = s.vault.redeem(shares, user, user);
+        assert!(assets > lp);
+        assert_eq!(s.pair.balance_of(user), assets);
+    }
+
+    #[test]
+    fn test_harvest_is_keeper_only() {
+        let mut s = setup();
+        let user = s.env.get_account(1);
+        deposit_lp(&mut s, user);
+        s.env.advance_block_time(100);
+
+        s.env.set_caller(user);
+        assert_eq!(
+            s.vault.try_harvest(U256::zero(), U256::zero(), U256::zero(), s.env.block_time() + 100).unwrap_err(),
+            FarmingError::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn test_harvest_enforces_liquidity_minimums_and_deadline() {
+        let mut s = setup();
+        let user = s.env.get_account(1);
+        deposit_lp(&mut s, user);
+        s.env.advance_block_time(100);
+
+        // Less than 10_000 ECTO is harvested, so neither side can reach it
+        s.env.set_caller(s.env.get_account(0));
+        let deadline = s.env.block_time() + 100;
+        let min = U256::from(10_000);
+        assert_eq!(
+            s.vault.try_harvest(U256::zero(), min, min, deadline).unwrap_err(),
+            DexError::InsufficientAmount.into()
+        );
+
+        // A harvest held back past its deadline is not executed
+        s.env.advance_block_time(101);
+        assert_eq!(
+            s.vault.try_harvest(U256::zero(), U256::zero(), U256::zero(), deadline).unwrap_err(),
+            DexError::DeadlineExpired.into()
+        );
+    }
+
+    #[test]
+    fn test_shares_are_cep18() {
+        let mut s = setup();
+        let user = s.env.get_account(1);
+        let other = s.env.get_account(2);
+        let lp = deposit_lp(&mut s, user);
+
+        assert_eq!(s.vault.symbol(), "cLP");
+        assert!(s.env.emitted_event(&s.vault, Mint { recipient: user, amount: lp }));
+
+        s.env.set_caller(user);
+        let amount = lp / 4;
+        assert!(s.vault.transfer(other, amount));
+        assert!(s.env.emitted_event(&s.vault, Transfer { sender: user, recipient: other, amount }));
+
+        assert!(s.vault.approve(other, amount));
+        s.env.set_caller(other);
+        assert!(s.vault.transfer_from(user, other, amount));
+        assert_eq!(s.vault.balance_of(other), amount * 2);
+        assert_eq!(s.vault.allowance(user, other), U256::zero());
+    }
+}
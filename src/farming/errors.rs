@@ -22,4 +22,22 @@ pub enum FarmingError {
     NoRewardsToClaim = 8,
     /// Pool not active
     PoolNotActive = 9,
+    /// Invalid reward period duration
+    InvalidDuration = 10,
+    /// Early exit penalty above the maximum
+    InvalidPenalty = 11,
+    /// Invalid configuration
+    InvalidConfiguration = 12,
+    /// Position is locked
+    PositionLocked = 13,
+    /// Pool already migrated
+    PoolMigrated = 14,
+    /// Stake would exceed the pool or per-user cap
+    StakeCapExceeded = 15,
+    /// LP token is not a Factory pair
+    NotFactoryPair = 16,
+    /// Reward budget or duration below the listing minimum
+    BelowListingMinimum = 17,
+    /// Token transfer failed
+    TransferFailed = 18,
 }
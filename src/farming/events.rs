@@ -21,6 +21,34 @@ pub struct Unstaked {
     pub timestamp: u64,
 }
 
+/// Event emitted when LP tokens are staked on behalf of another user
+#[odra::event]
+pub struct StakedFor {
+    pub sender: Address,
+    pub beneficiary: Address,
+    pub pool_id: u32,
+    pub amount: U256,
+}
+
+/// Event emitted when a stake is locked for a reward multiplier
+#[odra::event]
+pub struct StakeLocked {
+    pub user: Address,
+    pub pool_id: u32,
+    pub multiplier_bps: u32,
+    pub lock_expiry: u64,
+}
+
+/// Event emitted when a locked stake is unstaked before expiry
+#[odra::event]
+pub struct EarlyExit {
+    pub user: Address,
+    pub pool_id: u32,
+    pub amount: U256,
+    pub penalty: U256,
+    pub timestamp: u64,
+}
+
 /// Event emitted when rewards are claimed
 #[odra::event]
 pub struct RewardsClaimed {
@@ -30,6 +58,34 @@ pub struct RewardsClaimed {
     pub timestamp: u64,
 }
 
+/// Event emitted when the LP compounder reinvests harvested rewards
+#[odra::event]
+pub struct Harvested {
+    pub pool_id: u32,
+    pub rewards: U256,
+    pub performance_fee: U256,
+    pub liquidity_added: U256,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a user's stake is moved to a new StakingPool
+#[odra::event]
+pub struct StakeMigrated {
+    pub user: Address,
+    pub pool_id: u32,
+    pub amount: U256,
+    pub new_staking_pool: Address,
+}
+
+/// Event emitted when a pool's opted-in stakes are moved to a new StakingPool
+#[odra::event]
+pub struct PoolMigrated {
+    pub pool_id: u32,
+    pub new_staking_pool: Address,
+    pub users_migrated: u32,
+    pub amount_migrated: U256,
+}
+
 /// Event emitted when a new pool is created
 #[odra::event]
 pub struct PoolCreated {
@@ -39,6 +95,25 @@ pub struct PoolCreated {
     pub created_by: Address,
 }
 
+/// Event emitted when a pool is funded for a new reward period
+#[odra::event]
+pub struct RewardAdded {
+    pub pool_id: u32,
+    pub amount: U256,
+    pub reward_rate: U256,
+    pub period_finish: u64,
+    pub funded_by: Address,
+}
+
+/// Event emitted when a pool's stake caps are updated
+#[odra::event]
+pub struct StakeLimitsUpdated {
+    pub pool_id: u32,
+    pub max_total_staked: U256,
+    pub max_user_stake: U256,
+    pub updated_by: Address,
+}
+
 /// Event emitted when pool reward rate is updated
 #[odra::event]
 pub struct RewardRateUpdated {
@@ -1,13 +1,16 @@
 //! Yield Farming - LP token staking with ECTO rewards
 //! 
-//! Users can stake LP tokens (e.g., sCSPR/ECTO) to earn ECTO rewards
+//! Users can stake LP tokens (e.g., sCSPR/ECTO) to earn ECTO rewards, or
+//! deposit them in the LP compounder to have the rewards reinvested
 
 pub mod staking_pool;
 pub mod rewards_distributor;
+pub mod compounder;
 pub mod errors;
 pub mod events;
 
-pub use staking_pool::StakingPool;
+pub use staking_pool::{StakingPool, LockTier, ListingParams};
 pub use rewards_distributor::RewardsDistributor;
+pub use compounder::LpCompounder;
 pub use errors::FarmingError;
 pub use events::*;
@@ -2,6 +2,27 @@
 //! 
 //! Users stake LP tokens (e.g., sCSPR/ECTO LP) and earn ECTO rewards
 //! based on their share of the pool and time staked.
+//!
+//! Rewards are only streamed from ECTO the pool has been funded with via
+//! `notify_reward_amount`; emissions stop at the end of the reward period
+//! or once the funded budget is used up, whichever comes first.
+//!
+//! Stakes can be locked for 30/90/180 days to earn rewards at 1.2x/1.5x/2x
+//! weight. Unstaking before the lock expires forfeits the boost and leaves
+//! an early exit penalty in the pool for the remaining stakers.
+//!
+//! When a boost source is set, each stake's weight is also multiplied by the
+//! staker's LpRewardsDistributor boost (aECTO, borrowing, sCSPR), refreshed
+//! whenever the stake is touched or synced with `sync_stake`.
+//!
+//! To upgrade reward logic, the admin deploys a new StakingPool with the same
+//! pools and calls `migrate_pool` in batches until it completes; each batch
+//! re-stakes up to `max_users` opted-in users' LP there on their behalf and
+//! skips stakes the new contract would reject.
+//!
+//! Once the admin sets listing parameters, anyone can list a pool for a
+//! Factory pair with `create_funded_pool` by escrowing its whole reward
+//! budget upfront and paying the listing fee.
 
 use odra::prelude::*;
 use odra::casper_types::U256;
@@ -9,6 +30,54 @@ use odra::ContractRef;
 use super::errors::FarmingError;
 use super::events::*;
 use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE, PAUSER_ROLE, REGISTRAR_ROLE, REWARD_DISTRIBUTOR_ROLE};
+use crate::math::BPS_DENOMINATOR;
+use crate::incentives::lp_rewards_distributor::LpRewardsDistributorContractRef;
+use crate::dex::factory::FactoryContractRef;
+use crate::dex::pair::PairContractRef;
+
+/// Default share of an early unstake left to the remaining stakers (10%)
+pub const DEFAULT_EARLY_EXIT_PENALTY_BPS: u32 = 1_000;
+
+/// Maximum early exit penalty (50%)
+pub const MAX_EARLY_EXIT_PENALTY_BPS: u32 = 5_000;
+
+const DAY: u64 = 86_400;
+
+/// Lock period of a stake and its reward multiplier
+#[odra::odra_type]
+pub enum LockTier {
+    /// Unlocked, 1x
+    None,
+    /// 30 days, 1.2x
+    Days30,
+    /// 90 days, 1.5x
+    Days90,
+    /// 180 days, 2x
+    Days180,
+}
+
+impl LockTier {
+    /// Lock period in seconds
+    pub fn duration(&self) -> u64 {
+        match self {
+            LockTier::None => 0,
+            LockTier::Days30 => 30 * DAY,
+            LockTier::Days90 => 90 * DAY,
+            LockTier::Days180 => 180 * DAY,
+        }
+    }
+
+    /// Reward weight in basis points (10_000 = 1x)
+    pub fn multiplier_bps(&self) -> u32 {
+        match self {
+            LockTier::None => 10_000,
+            LockTier::Days30 => 12_000,
+            LockTier::Days90 => 15_000,
+            LockTier::Days180 => 20_000,
+        }
+    }
+}
 
 /// Pool information
 #[odra::odra_type]
@@ -17,16 +86,43 @@ pub struct PoolInfo {
     pub pool_id: u32,
     /// LP token address
     pub lp_token: Address,
-    /// Reward rate (ECTO per second per staked token)
+    /// Reward rate (ECTO per second, shared across all stakers)
     pub reward_rate: U256,
-    /// Total staked in pool
+    /// Total staked in pool (including early exit penalties not yet credited)
     pub total_staked: U256,
+    /// Sum of stakers' reward weights
+    pub total_weight: U256,
     /// Last update timestamp
     pub last_update: u64,
-    /// Accumulated reward per token
+    /// Accumulated reward per unit of weight
     pub reward_per_token_stored: U256,
+    /// Accumulated early exit penalty LP per unit of weight
+    pub penalty_per_weight_stored: U256,
     /// Is pool active
     pub is_active: bool,
+    /// End of the current funded reward period (0 = never funded)
+    pub period_finish: u64,
+    /// Funded ECTO not yet streamed to stakers
+    pub reward_budget: U256,
+    /// Maximum total staked (0 = no cap)
+    pub max_total_staked: U256,
+    /// Maximum stake per user (0 = no cap)
+    pub max_user_stake: U256,
+}
+
+/// Terms for permissionless pool listing
+#[odra::odra_type]
+pub struct ListingParams {
+    /// Factory whose pairs can be listed
+    pub factory: Address,
+    /// Minimum ECTO budget escrowed by the lister
+    pub min_budget: U256,
+    /// Minimum reward period in seconds
+    pub min_duration: u64,
+    /// ECTO listing fee paid on top of the budget
+    pub listing_fee: U256,
+    /// Receiver of listing fees
+    pub fee_recipient: Address,
 }
 
 /// User stake information
@@ -34,14 +130,37 @@ pub struct PoolInfo {
 pub struct UserStake {
     /// Amount staked
     pub amount: U256,
+    /// Reward weight (amount scaled by the lock multiplier and LP boost)
+    pub weight: U256,
+    /// LP boost applied to the weight (scaled by 1e18, 1e18 = 1x)
+    pub boost: U256,
+    /// Lock tier (None once the lock has expired)
+    pub lock_tier: LockTier,
+    /// Lock expiry timestamp (0 if unlocked)
+    pub lock_expiry: u64,
     /// Reward debt (for reward calculation)
     pub reward_debt: U256,
+    /// Penalty debt (for early exit penalty sharing)
+    pub penalty_debt: U256,
     /// Pending rewards
     pub pending_rewards: U256,
     /// Last update timestamp
     pub last_update: u64,
 }
 
+/// Progress of a pool's batched migration
+#[odra::odra_type]
+pub struct MigrationProgress {
+    /// StakingPool the pool is moving to
+    pub new_staking_pool: Address,
+    /// Next migration queue index to process
+    pub next_index: u32,
+    /// Users moved so far
+    pub users_migrated: u32,
+    /// LP moved so far
+    pub amount_migrated: U256,
+}
+
 /// Staking Pool contract
 #[odra::module]
 pub struct StakingPool {
@@ -53,10 +172,26 @@ pub struct StakingPool {
     user_stakes: Mapping<(Address, u32), UserStake>,
     /// Next pool ID
     next_pool_id: Var<u32>,
-    /// Admin address
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     /// Paused state
     paused: Var<bool>,
+    /// Share of an early unstake left to the remaining stakers (bps)
+    early_exit_penalty_bps: Var<u32>,
+    /// LpRewardsDistributor whose boost multiplies stake weights
+    boost_source: Var<Option<Address>>,
+    /// Users who opted in to migrating their stake: (user, pool_id) -> opted in
+    migration_opt_ins: Mapping<(Address, u32), bool>,
+    /// Users who ever opted in, per pool: (pool_id, index) -> user
+    migration_queue: Mapping<(u32, u32), Address>,
+    /// Length of each pool's migration queue
+    migration_queue_len: Mapping<u32, u32>,
+    /// StakingPool each migrated pool moved to
+    migrated_to: Mapping<u32, Address>,
+    /// Permissionless listing terms (None = listing disabled)
+    listing: Var<Option<ListingParams>>,
+    /// Batched migrations in progress (pool_id -> progress)
+    migrations: Mapping<u32, MigrationProgress>,
 }
 
 #[odra::module]
@@ -66,55 +201,107 @@ impl StakingPool {
         let caller = self.env().caller();
         self.reward_token.set(reward_token_address);
         self.next_pool_id.set(0);
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(PAUSER_ROLE, caller);
+        self.access.grant(REGISTRAR_ROLE, caller);
         self.paused.set(false);
+        self.early_exit_penalty_bps.set(DEFAULT_EARLY_EXIT_PENALTY_BPS);
     }
     
     // ========================================
-    // Pool Management (Admin)
+    // Pool Management (Registrar / Admin)
     // ========================================
     
-    /// Create a new staking pool
+    /// Create a new staking pool (registrar only)
     /// 
     /// # Arguments
     /// * `lp_token` - LP token address to stake
-    /// * `reward_rate` - ECTO rewards per second per staked token (scaled by 1e18)
+    /// * `reward_rate` - ECTO rewards per second for the whole pool
+    /// 
+    /// Nothing is emitted until the pool is funded with `notify_reward_amount`.
     pub fn create_pool(&mut self, lp_token: Address, reward_rate: U256) -> u32 {
-        self.only_admin();
+        self.access.require_role(REGISTRAR_ROLE, FarmingError::Unauthorized);
         
         if reward_rate == U256::zero() {
             self.env().revert(FarmingError::InvalidRewardRate);
         }
         
-        let pool_id = self.next_pool_id.get_or_default();
+        self.add_pool(lp_token, reward_rate)
+    }
+    
+    /// List a pool for a Factory pair, escrowing its whole reward budget (anyone)
+    /// 
+    /// Pulls `budget` plus the listing fee in ECTO from the caller and streams
+    /// the budget over `duration` seconds from now. Both must meet the
+    /// listing minimums set by the admin.
+    pub fn create_funded_pool(&mut self, lp_token: Address, budget: U256, duration: u64) -> u32 {
+        self.ensure_not_paused();
         
-        let pool = PoolInfo {
-            pool_id,
-            lp_token,
-            reward_rate,
-            total_staked: U256::zero(),
-            last_update: self.env().get_block_time(),
-            reward_per_token_stored: U256::zero(),
-            is_active: true,
-        };
+        let params = self.listing.get_or_default()
+            .unwrap_or_revert_with(&self.env(), FarmingError::InvalidConfiguration);
+        if budget < params.min_budget || duration < params.min_duration {
+            self.env().revert(FarmingError::BelowListingMinimum);
+        }
+        if duration == 0 {
+            self.env().revert(FarmingError::InvalidDuration);
+        }
+        let reward_rate = budget / U256::from(duration);
+        if reward_rate == U256::zero() {
+            self.env().revert(FarmingError::InvalidRewardRate);
+        }
         
-        self.pools.set(&pool_id, pool);
-        self.next_pool_id.set(pool_id + 1);
+        let pair = PairContractRef::new(self.env(), lp_token);
+        let factory = FactoryContractRef::new(self.env(), params.factory);
+        if factory.get_pair(pair.token0(), pair.token1()) != Some(lp_token) {
+            self.env().revert(FarmingError::NotFactoryPair);
+        }
         
-        let admin = self.admin.get_or_revert_with(FarmingError::Unauthorized);
-        self.env().emit_event(PoolCreated {
-            pool_id,
-            lp_token,
-            reward_rate,
-            created_by: admin,
-        });
+        let caller = self.env().caller();
+        if params.listing_fee > U256::zero() {
+            let reward_token_address = self.reward_token.get_or_revert_with(FarmingError::Unauthorized);
+            Cep18TokenContractRef::new(self.env(), reward_token_address)
+                .transfer_from(caller, params.fee_recipient, params.listing_fee);
+        }
         
+        let pool_id = self.add_pool(lp_token, reward_rate);
+        self.fund_pool(pool_id, caller, budget, duration);
         pool_id
     }
     
-    /// Update pool reward rate
+    /// Set the terms for permissionless listing, or disable it with None (admin only)
+    pub fn set_listing_params(&mut self, params: Option<ListingParams>) {
+        self.access.require_role(ADMIN_ROLE, FarmingError::Unauthorized);
+        self.listing.set(params);
+    }
+    
+    /// Fund a pool with `amount` ECTO streamed over the next `duration` seconds
+    /// (reward distributor or admin)
+    /// 
+    /// The ECTO is pulled from the caller. Any budget left from the current
+    /// period is rolled into the new one, and the reward rate is reset so the
+    /// whole budget is streamed by the end of the new period.
+    pub fn notify_reward_amount(&mut self, pool_id: u32, amount: U256, duration: u64) {
+        self.access.require_any_role(&[REWARD_DISTRIBUTOR_ROLE, ADMIN_ROLE], FarmingError::Unauthorized);
+        
+        if amount == U256::zero() {
+            self.env().revert(FarmingError::ZeroAmount);
+        }
+        if duration == 0 {
+            self.env().revert(FarmingError::InvalidDuration);
+        }
+        if self.pools.get(&pool_id).is_none() {
+            self.env().revert(FarmingError::PoolNotFound);
+        }
+        
+        let caller = self.env().caller();
+        self.fund_pool(pool_id, caller, amount, duration);
+    }
+    
+    /// Update pool reward rate (admin only)
+    /// 
+    /// Emissions remain bounded by the pool's funded budget and period.
     pub fn update_reward_rate(&mut self, pool_id: u32, new_rate: U256) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, FarmingError::Unauthorized);
         
         let mut pool = self.pools.get(&pool_id)
             .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
@@ -125,18 +312,38 @@ impl StakingPool {
         pool.reward_rate = new_rate;
         self.pools.set(&pool_id, pool);
         
-        let admin = self.admin.get_or_revert_with(FarmingError::Unauthorized);
         self.env().emit_event(RewardRateUpdated {
             pool_id,
             old_rate,
             new_rate,
-            updated_by: admin,
+            updated_by: self.env().caller(),
         });
     }
     
-    /// Set pool active status
+    /// Cap a pool's total stake and each user's stake (admin only, 0 = no cap)
+    /// 
+    /// Caps only limit new stakes; positions already above them are kept.
+    pub fn set_stake_limits(&mut self, pool_id: u32, max_total_staked: U256, max_user_stake: U256) {
+        self.access.require_role(ADMIN_ROLE, FarmingError::Unauthorized);
+        
+        let mut pool = self.pools.get(&pool_id)
+            .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
+        
+        pool.max_total_staked = max_total_staked;
+        pool.max_user_stake = max_user_stake;
+        self.pools.set(&pool_id, pool);
+        
+        self.env().emit_event(StakeLimitsUpdated {
+            pool_id,
+            max_total_staked,
+            max_user_stake,
+            updated_by: self.env().caller(),
+        });
+    }
+    
+    /// Set pool active status (admin only)
     pub fn set_pool_active(&mut self, pool_id: u32, active: bool) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, FarmingError::Unauthorized);
         
         let mut pool = self.pools.get(&pool_id)
             .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
@@ -150,61 +357,36 @@ impl StakingPool {
     // ========================================
     
     /// Stake LP tokens
+    /// 
+    /// Staking into a position that is still locked re-locks the whole
+    /// position for its tier's full period.
     pub fn stake(&mut self, pool_id: u32, amount: U256) {
-        self.ensure_not_paused();
-        
-        if amount == U256::zero() {
-            self.env().revert(FarmingError::ZeroAmount);
-        }
-        
         let caller = self.env().caller();
-        
-        // Get pool
-        let pool = self.pools.get(&pool_id)
-            .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
-        
-        if !pool.is_active {
-            self.env().revert(FarmingError::PoolNotActive);
-        }
-        
-        // Update pool rewards
-        self.update_pool_rewards(pool_id);
-        
-        // Update user rewards
-        self.update_user_rewards(caller, pool_id);
-        
-        // Transfer LP tokens from user
-        let mut lp_token = Cep18TokenContractRef::new(self.env(), pool.lp_token);
-        lp_token.transfer_from(caller, Address::from(self.env().self_address()), amount);
-        
-        // Update user stake
-        let mut user_stake = self.user_stakes.get(&(caller, pool_id))
-            .unwrap_or(UserStake {
-                amount: U256::zero(),
-                reward_debt: U256::zero(),
-                pending_rewards: U256::zero(),
-                last_update: self.env().get_block_time(),
-            });
-        
-        user_stake.amount = user_stake.amount + amount;
-        user_stake.last_update = self.env().get_block_time();
-        self.user_stakes.set(&(caller, pool_id), user_stake);
-        
-        // Update pool total
-        let mut pool = self.pools.get(&pool_id).unwrap();
-        pool.total_staked = pool.total_staked + amount;
-        self.pools.set(&pool_id, pool);
-        
-        let timestamp = self.env().get_block_time();
-        self.env().emit_event(Staked {
-            user: caller,
-            pool_id,
-            amount,
-            timestamp,
-        });
+        self.stake_internal(caller, pool_id, amount, LockTier::None);
+    }
+    
+    /// Stake the caller's LP tokens into `beneficiary`'s position
+    /// 
+    /// Lets zappers and vaults stake for end users: the beneficiary owns the
+    /// stake and earns its rewards. Reverts if the beneficiary's position is
+    /// locked, since only its owner may extend the lock.
+    pub fn stake_for(&mut self, beneficiary: Address, pool_id: u32, amount: U256) {
+        self.stake_internal(beneficiary, pool_id, amount, LockTier::None);
+    }
+    
+    /// Stake LP tokens locked for `tier`'s period to earn its reward multiplier
+    /// 
+    /// The whole position is locked for the full period from now, at the
+    /// higher of `tier` and the position's current tier.
+    pub fn stake_locked(&mut self, pool_id: u32, amount: U256, tier: LockTier) {
+        let caller = self.env().caller();
+        self.stake_internal(caller, pool_id, amount, tier);
     }
     
     /// Unstake LP tokens
+    /// 
+    /// Unstaking from a locked position forfeits its boost and leaves the
+    /// early exit penalty in the pool for the remaining stakers.
     pub fn unstake(&mut self, pool_id: u32, amount: U256) {
         self.ensure_not_paused();
         
@@ -228,24 +410,50 @@ impl StakingPool {
             self.env().revert(FarmingError::InsufficientBalance);
         }
         
-        // Update user stake
+        // Expired locks were cleared above, so any lock left is exited early
+        let early_exit = user_stake.lock_tier != LockTier::None;
+        
+        // Update user stake, dropping the boost on early exit
         user_stake.amount = user_stake.amount - amount;
+        if early_exit {
+            user_stake.lock_tier = LockTier::None;
+            user_stake.lock_expiry = 0;
+        }
         user_stake.last_update = self.env().get_block_time();
-        self.user_stakes.set(&(caller, pool_id), user_stake);
         
-        // Get pool info before updating
+        // Update pool totals
         let mut pool = self.pools.get(&pool_id).unwrap();
         let lp_token_address = pool.lp_token;
-        
-        // Update pool total
         pool.total_staked = pool.total_staked - amount;
+        Self::reweigh(&mut pool, &mut user_stake);
+        
+        // Leave the penalty staked for whoever remains
+        let mut penalty = U256::zero();
+        if early_exit && pool.total_weight > U256::zero() {
+            penalty = amount * U256::from(self.early_exit_penalty_bps.get_or_default())
+                / U256::from(BPS_DENOMINATOR);
+            pool.penalty_per_weight_stored = pool.penalty_per_weight_stored
+                + (penalty * U256::from(1_000_000_000_000_000_000u128)) / pool.total_weight;
+            pool.total_staked = pool.total_staked + penalty;
+        }
+        
+        self.user_stakes.set(&(caller, pool_id), user_stake);
         self.pools.set(&pool_id, pool);
         
         // Transfer LP tokens back to user
         let mut lp_token = Cep18TokenContractRef::new(self.env(), lp_token_address);
-        lp_token.transfer(caller, amount);
+        lp_token.transfer(caller, amount - penalty);
         
         let timestamp = self.env().get_block_time();
+        if early_exit {
+            self.env().emit_event(EarlyExit {
+                user: caller,
+                pool_id,
+                amount,
+                penalty,
+                timestamp,
+            });
+        }
         self.env().emit_event(Unstaked {
             user: caller,
             pool_id,
@@ -254,6 +462,19 @@ impl StakingPool {
         });
     }
     
+    /// Re-apply a user's current LP boost and lock expiry to their reward weight (anyone can call)
+    /// 
+    /// Weights are otherwise only refreshed the next time the user interacts,
+    /// so call this when a user's boost changes or their lock expires.
+    pub fn sync_stake(&mut self, user: Address, pool_id: u32) {
+        if self.user_stakes.get(&(user, pool_id)).is_none() {
+            self.env().revert(FarmingError::InsufficientBalance);
+        }
+        
+        self.update_pool_rewards(pool_id);
+        self.update_user_rewards(user, pool_id);
+    }
+    
     /// Claim pending rewards
     pub fn claim_rewards(&mut self, pool_id: u32) {
         self.ensure_not_paused();
@@ -294,52 +515,434 @@ impl StakingPool {
         });
     }
     
+    // ========================================
+    // Migration
+    // ========================================
+    
+    /// Opt the caller's stake in `pool_id` in to (or out of) migration to a new contract version
+    /// 
+    /// Only unlocked, nonzero stakes can opt in. Once the pool has been
+    /// migrated, opting in moves the caller's stake right away.
+    pub fn set_migration_opt_in(&mut self, pool_id: u32, opted_in: bool) {
+        let caller = self.env().caller();
+        if self.pools.get(&pool_id).is_none() {
+            self.env().revert(FarmingError::PoolNotFound);
+        }
+        
+        if opted_in {
+            self.update_pool_rewards(pool_id);
+            self.update_user_rewards(caller, pool_id);
+            let user_stake = self.user_stakes.get(&(caller, pool_id))
+                .filter(|user_stake| user_stake.amount > U256::zero())
+                .unwrap_or_revert_with(&self.env(), FarmingError::InsufficientBalance);
+            if user_stake.lock_tier != LockTier::None {
+                self.env().revert(FarmingError::PositionLocked);
+            }
+            
+            if let Some(new_staking_pool) = self.migrated_to.get(&pool_id) {
+                self.migrate_stake(caller, pool_id, new_staking_pool);
+                return;
+            }
+            
+            // Queue first-time opt-ins for the admin's migration
+            if self.migration_opt_ins.get(&(caller, pool_id)).is_none() {
+                let index = self.migration_queue_len.get(&pool_id).unwrap_or_default();
+                self.migration_queue.set(&(pool_id, index), caller);
+                self.migration_queue_len.set(&pool_id, index + 1);
+            }
+        }
+        
+        self.migration_opt_ins.set(&(caller, pool_id), opted_in);
+    }
+    
+    /// Move up to `max_users` queued opt-ins of `pool_id` to the same pool of `new_staking_pool` (admin only)
+    /// 
+    /// The new contract must have a pool with the same ID and LP token. The
+    /// first batch closes the pool to new stakes; later batches must name the
+    /// same target. Each stake is re-staked there on its owner's behalf and
+    /// rewards already earned stay claimable here. Stakes the new contract
+    /// would reject are skipped and stay opted in, so their owners can move
+    /// by opting in again. Once the queue is exhausted the pool is migrated,
+    /// and users who opt in later are moved as they do.
+    /// 
+    /// # Returns
+    /// Queue entries left to process (0 once the migration is complete)
+    pub fn migrate_pool(&mut self, pool_id: u32, new_staking_pool: Address, max_users: u32) -> u32 {
+        self.access.require_role(ADMIN_ROLE, FarmingError::Unauthorized);
+        
+        let pool = self.pools.get(&pool_id)
+            .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
+        if self.migrated_to.get(&pool_id).is_some() {
+            self.env().revert(FarmingError::PoolMigrated);
+        }
+        if max_users == 0 {
+            self.env().revert(FarmingError::InvalidConfiguration);
+        }
+        
+        let mut progress = match self.migrations.get(&pool_id) {
+            Some(progress) => {
+                if progress.new_staking_pool != new_staking_pool {
+                    self.env().revert(FarmingError::InvalidConfiguration);
+                }
+                progress
+            }
+            None => {
+                let target_lp = StakingPoolContractRef::new(self.env(), new_staking_pool)
+                    .get_pool_info(pool_id)
+                    .map(|target| target.lp_token);
+                if target_lp != Some(pool.lp_token) {
+                    self.env().revert(FarmingError::InvalidConfiguration);
+                }
+                let mut pool = pool;
+                pool.is_active = false;
+                self.pools.set(&pool_id, pool);
+                MigrationProgress {
+                    new_staking_pool,
+                    next_index: 0,
+                    users_migrated: 0,
+                    amount_migrated: U256::zero(),
+                }
+            }
+        };
+        
+        self.update_pool_rewards(pool_id);
+        
+        let queue_len = self.migration_queue_len.get(&pool_id).unwrap_or_default();
+        let batch_end = queue_len.min(progress.next_index.saturating_add(max_users));
+        for index in progress.next_index..batch_end {
+            let user = self.migration_queue.get(&(pool_id, index)).unwrap();
+            if !self.migration_opt_ins.get(&(user, pool_id)).unwrap_or_default() {
+                continue;
+            }
+            let amount = self.migrate_stake(user, pool_id, new_staking_pool);
+            if amount > U256::zero() {
+                progress.users_migrated += 1;
+                progress.amount_migrated = progress.amount_migrated + amount;
+            }
+        }
+        progress.next_index = batch_end;
+        
+        let remaining = queue_len - batch_end;
+        if remaining > 0 {
+            self.migrations.set(&pool_id, progress);
+            return remaining;
+        }
+        
+        self.migrated_to.set(&pool_id, new_staking_pool);
+        self.env().emit_event(PoolMigrated {
+            pool_id,
+            new_staking_pool,
+            users_migrated: progress.users_migrated,
+            amount_migrated: progress.amount_migrated,
+        });
+        self.migrations.set(&pool_id, progress);
+        0
+    }
+    
     // ========================================
     // Internal Functions
     // ========================================
     
+    /// Re-stake `user`'s LP in `new_staking_pool` on their behalf (pool rewards already updated)
+    /// 
+    /// Skips positions that are locked here or in the new contract, since
+    /// locks do not carry over, and positions the new contract has no room
+    /// for (paused, inactive or capped). Returns the LP moved.
+    fn migrate_stake(&mut self, user: Address, pool_id: u32, new_staking_pool: Address) -> U256 {
+        self.update_user_rewards(user, pool_id);
+        
+        let mut new_pool = StakingPoolContractRef::new(self.env(), new_staking_pool);
+        let locked_there = new_pool.get_user_stake(user, pool_id)
+            .map(|stake| stake.lock_tier != LockTier::None)
+            .unwrap_or_default();
+        let mut user_stake = self.user_stakes.get(&(user, pool_id)).unwrap();
+        if user_stake.lock_tier != LockTier::None || locked_there || user_stake.amount == U256::zero() {
+            return U256::zero();
+        }
+        if new_pool.get_stake_headroom(user, pool_id) < user_stake.amount {
+            return U256::zero();
+        }
+        
+        let amount = user_stake.amount;
+        user_stake.amount = U256::zero();
+        let mut pool = self.pools.get(&pool_id).unwrap();
+        let lp_token_address = pool.lp_token;
+        pool.total_staked = pool.total_staked - amount;
+        Self::reweigh(&mut pool, &mut user_stake);
+        self.user_stakes.set(&(user, pool_id), user_stake);
+        self.pools.set(&pool_id, pool);
+        self.migration_opt_ins.set(&(user, pool_id), false);
+        
+        Cep18TokenContractRef::new(self.env(), lp_token_address).increase_allowance(new_staking_pool, amount);
+        new_pool.stake_for(user, pool_id, amount);
+        
+        self.env().emit_event(StakeMigrated {
+            user,
+            pool_id,
+            amount,
+            new_staking_pool,
+        });
+        amount
+    }
+    
+    /// Register a new pool and emit `PoolCreated`
+    fn add_pool(&mut self, lp_token: Address, reward_rate: U256) -> u32 {
+        let pool_id = self.next_pool_id.get_or_default();
+        
+        let pool = PoolInfo {
+            pool_id,
+            lp_token,
+            reward_rate,
+            total_staked: U256::zero(),
+            total_weight: U256::zero(),
+            last_update: self.env().get_block_time(),
+            reward_per_token_stored: U256::zero(),
+            penalty_per_weight_stored: U256::zero(),
+            is_active: true,
+            period_finish: 0,
+            reward_budget: U256::zero(),
+            max_total_staked: U256::zero(),
+            max_user_stake: U256::zero(),
+        };
+        
+        self.pools.set(&pool_id, pool);
+        self.next_pool_id.set(pool_id + 1);
+        
+        self.env().emit_event(PoolCreated {
+            pool_id,
+            lp_token,
+            reward_rate,
+            created_by: self.env().caller(),
+        });
+        
+        pool_id
+    }
+    
+    /// Pull `amount` ECTO from `funder` into the pool's budget, streamed over `duration` seconds
+    fn fund_pool(&mut self, pool_id: u32, funder: Address, amount: U256, duration: u64) {
+        self.update_pool_rewards(pool_id);
+        
+        let reward_token_address = self.reward_token.get_or_revert_with(FarmingError::Unauthorized);
+        let mut reward_token = Cep18TokenContractRef::new(self.env(), reward_token_address);
+        reward_token.transfer_from(funder, Address::from(self.env().self_address()), amount);
+        
+        let mut pool = self.pools.get(&pool_id).unwrap();
+        let budget = pool.reward_budget + amount;
+        let reward_rate = budget / U256::from(duration);
+        if reward_rate == U256::zero() {
+            self.env().revert(FarmingError::InvalidRewardRate);
+        }
+        
+        let period_finish = self.env().get_block_time() + duration;
+        pool.reward_rate = reward_rate;
+        pool.reward_budget = budget;
+        pool.period_finish = period_finish;
+        self.pools.set(&pool_id, pool);
+        
+        self.env().emit_event(RewardAdded {
+            pool_id,
+            amount,
+            reward_rate,
+            period_finish,
+            funded_by: funder,
+        });
+    }
+    
     fn update_pool_rewards(&mut self, pool_id: u32) {
         let mut pool = self.pools.get(&pool_id).unwrap();
         
-        if pool.total_staked == U256::zero() {
+        if pool.total_weight == U256::zero() {
             pool.last_update = self.env().get_block_time();
             self.pools.set(&pool_id, pool);
             return;
         }
         
         let current_time = self.env().get_block_time();
-        let time_elapsed = current_time - pool.last_update;
         
-        // Calculate rewards: reward_rate * time_elapsed
-        let rewards = pool.reward_rate * U256::from(time_elapsed);
+        // Only stream within the funded period
+        let accrual_end = if current_time < pool.period_finish {
+            current_time
+        } else {
+            pool.period_finish
+        };
+        let time_elapsed = accrual_end.saturating_sub(pool.last_update);
+        
+        // Calculate rewards: reward_rate * time_elapsed, capped at the funded budget
+        let mut rewards = pool.reward_rate * U256::from(time_elapsed);
+        if rewards > pool.reward_budget {
+            rewards = pool.reward_budget;
+        }
         
         // Update reward per token
-        let reward_per_token_increase = (rewards * U256::from(1_000_000_000_000_000_000u128)) / pool.total_staked;
+        let reward_per_token_increase = (rewards * U256::from(1_000_000_000_000_000_000u128)) / pool.total_weight;
         pool.reward_per_token_stored = pool.reward_per_token_stored + reward_per_token_increase;
+        pool.reward_budget = pool.reward_budget - rewards;
         pool.last_update = current_time;
         
         self.pools.set(&pool_id, pool);
     }
     
-    fn update_user_rewards(&mut self, user: Address, pool_id: u32) {
+    /// Stake the caller's LP into `beneficiary`'s position
+    fn stake_internal(&mut self, beneficiary: Address, pool_id: u32, amount: U256, tier: LockTier) {
+        self.ensure_not_paused();
+        
+        if amount == U256::zero() {
+            self.env().revert(FarmingError::ZeroAmount);
+        }
+        
+        let caller = self.env().caller();
+        
+        // Get pool
+        let pool = self.pools.get(&pool_id)
+            .unwrap_or_revert_with(&self.env(), FarmingError::PoolNotFound);
+        
+        if !pool.is_active {
+            self.env().revert(FarmingError::PoolNotActive);
+        }
+        
+        // Update pool rewards
+        self.update_pool_rewards(pool_id);
+        
+        // Update user rewards
+        self.update_user_rewards(beneficiary, pool_id);
+        
+        // Transfer LP tokens from user
+        let mut lp_token = Cep18TokenContractRef::new(self.env(), pool.lp_token);
+        lp_token.transfer_from(caller, Address::from(self.env().self_address()), amount);
+        
+        // Update user stake, re-locking a still locked position at its higher tier
+        let now = self.env().get_block_time();
+        let mut user_stake = self.user_stakes.get(&(beneficiary, pool_id)).unwrap();
+        if caller != beneficiary && user_stake.lock_tier != LockTier::None {
+            self.env().revert(FarmingError::PositionLocked);
+        }
         let pool = self.pools.get(&pool_id).unwrap();
+        if amount > Self::stake_headroom(&pool, user_stake.amount) {
+            self.env().revert(FarmingError::StakeCapExceeded);
+        }
+        let tier = if user_stake.lock_tier.multiplier_bps() > tier.multiplier_bps() {
+            user_stake.lock_tier.clone()
+        } else {
+            tier
+        };
+        
+        user_stake.amount = user_stake.amount + amount;
+        user_stake.last_update = now;
+        if tier != LockTier::None {
+            user_stake.lock_expiry = now + tier.duration();
+            user_stake.lock_tier = tier;
+        }
+        let lock = (user_stake.lock_tier.multiplier_bps(), user_stake.lock_expiry);
+        
+        // Update pool totals
+        let mut pool = self.pools.get(&pool_id).unwrap();
+        pool.total_staked = pool.total_staked + amount;
+        Self::reweigh(&mut pool, &mut user_stake);
+        self.user_stakes.set(&(beneficiary, pool_id), user_stake);
+        self.pools.set(&pool_id, pool);
+        
+        self.env().emit_event(Staked {
+            user: beneficiary,
+            pool_id,
+            amount,
+            timestamp: now,
+        });
+        if caller != beneficiary {
+            self.env().emit_event(StakedFor {
+                sender: caller,
+                beneficiary,
+                pool_id,
+                amount,
+            });
+        }
+        if lock.1 != 0 {
+            self.env().emit_event(StakeLocked {
+                user: beneficiary,
+                pool_id,
+                multiplier_bps: lock.0,
+                lock_expiry: lock.1,
+            });
+        }
+    }
+    
+    fn update_user_rewards(&mut self, user: Address, pool_id: u32) {
+        let mut pool = self.pools.get(&pool_id).unwrap();
+        let now = self.env().get_block_time();
         let mut user_stake = self.user_stakes.get(&(user, pool_id))
             .unwrap_or(UserStake {
                 amount: U256::zero(),
+                weight: U256::zero(),
+                boost: U256::from(1_000_000_000_000_000_000u128), // 1e18
+                lock_tier: LockTier::None,
+                lock_expiry: 0,
                 reward_debt: U256::zero(),
+                penalty_debt: U256::zero(),
                 pending_rewards: U256::zero(),
-                last_update: self.env().get_block_time(),
+                last_update: now,
             });
         
-        if user_stake.amount > U256::zero() {
+        if user_stake.weight > U256::zero() {
             // Calculate pending rewards
             let reward_per_token_delta = pool.reward_per_token_stored - user_stake.reward_debt;
-            let new_rewards = (user_stake.amount * reward_per_token_delta) / U256::from(1_000_000_000_000_000_000u128);
+            let new_rewards = (user_stake.weight * reward_per_token_delta) / U256::from(1_000_000_000_000_000_000u128);
             user_stake.pending_rewards = user_stake.pending_rewards + new_rewards;
+            
+            // Credit the user's share of early exit penalties to their stake
+            let penalty_per_weight_delta = pool.penalty_per_weight_stored - user_stake.penalty_debt;
+            let penalty_share = (user_stake.weight * penalty_per_weight_delta) / U256::from(1_000_000_000_000_000_000u128);
+            user_stake.amount = user_stake.amount + penalty_share;
         }
         
         user_stake.reward_debt = pool.reward_per_token_stored;
+        user_stake.penalty_debt = pool.penalty_per_weight_stored;
+        
+        // The boost ends with the lock
+        if user_stake.lock_tier != LockTier::None && now >= user_stake.lock_expiry {
+            user_stake.lock_tier = LockTier::None;
+            user_stake.lock_expiry = 0;
+        }
+        
+        user_stake.boost = self.current_boost(user);
+        Self::reweigh(&mut pool, &mut user_stake);
         self.user_stakes.set(&(user, pool_id), user_stake);
+        self.pools.set(&pool_id, pool);
+    }
+    
+    /// LP a user can still stake under the pool's caps (`U256::MAX` if uncapped)
+    fn stake_headroom(pool: &PoolInfo, staked: U256) -> U256 {
+        let pool_room = if pool.max_total_staked == U256::zero() {
+            U256::MAX
+        } else {
+            pool.max_total_staked.saturating_sub(pool.total_staked)
+        };
+        let user_room = if pool.max_user_stake == U256::zero() {
+            U256::MAX
+        } else {
+            pool.max_user_stake.saturating_sub(staked)
+        };
+        if pool_room < user_room {
+            pool_room
+        } else {
+            user_room
+        }
+    }
+    
+    /// The user's LP boost from the boost source (1e18 = 1x without one)
+    fn current_boost(&self, user: Address) -> U256 {
+        match self.boost_source.get_or_default() {
+            Some(source) => LpRewardsDistributorContractRef::new(self.env(), source)
+                .get_boost_factors(user)
+                .total_multiplier,
+            None => U256::from(1_000_000_000_000_000_000u128), // 1e18
+        }
+    }
+    
+    /// Recompute a stake's weight from its amount, lock and boost, keeping the pool total in sync
+    fn reweigh(pool: &mut PoolInfo, user_stake: &mut UserStake) {
+        let weight = user_stake.amount * U256::from(user_stake.lock_tier.multiplier_bps()) * user_stake.boost
+            / (U256::from(BPS_DENOMINATOR) * U256::from(1_000_000_000_000_000_000u128));
+        pool.total_weight = pool.total_weight - user_stake.weight + weight;
+        user_stake.weight = weight;
     }
     
     // ========================================
@@ -354,6 +957,52 @@ impl StakingPool {
         self.user_stakes.get(&(user, pool_id))
     }
     
+    /// Get the LpRewardsDistributor whose boost multiplies stake weights
+    pub fn get_boost_source(&self) -> Option<Address> {
+        self.boost_source.get_or_default()
+    }
+    
+    /// Get the permissionless listing terms (None if listing is disabled)
+    pub fn get_listing_params(&self) -> Option<ListingParams> {
+        self.listing.get_or_default()
+    }
+    
+    /// Get the LP `user` can still stake in `pool_id` (`U256::MAX` if uncapped)
+    /// 
+    /// Zero while the contract is paused or the pool is inactive.
+    pub fn get_stake_headroom(&self, user: Address, pool_id: u32) -> U256 {
+        let pool = match self.pools.get(&pool_id) {
+            Some(pool) => pool,
+            None => return U256::zero(),
+        };
+        if self.paused.get_or_default() || !pool.is_active {
+            return U256::zero();
+        }
+        let staked = self.user_stakes.get(&(user, pool_id))
+            .map(|user_stake| user_stake.amount)
+            .unwrap_or_default();
+        Self::stake_headroom(&pool, staked)
+    }
+    
+    /// Check whether `user` opted in to migrating their stake in `pool_id`
+    pub fn is_migration_opted_in(&self, user: Address, pool_id: u32) -> bool {
+        self.migration_opt_ins.get(&(user, pool_id)).unwrap_or_default()
+    }
+    
+    /// Get the StakingPool `pool_id` was migrated to, if any
+    pub fn get_migration_target(&self, pool_id: u32) -> Option<Address> {
+        self.migrated_to.get(&pool_id)
+    }
+    
+    /// Get the progress of `pool_id`'s batched migration, if one has started
+    pub fn get_migration_progress(&self, pool_id: u32) -> Option<MigrationProgress> {
+        self.migrations.get(&pool_id)
+    }
+    
+    pub fn get_early_exit_penalty(&self) -> u32 {
+        self.early_exit_penalty_bps.get_or_default()
+    }
+    
     pub fn get_pending_rewards(&self, user: Address, pool_id: u32) -> U256 {
         let user_stake = self.user_stakes.get(&(user, pool_id));
         if let Some(stake) = user_stake {
@@ -367,27 +1016,518 @@ impl StakingPool {
     // Admin Functions
     // ========================================
     
+    /// Set the share of an early unstake left to the remaining stakers (admin only)
+    pub fn set_early_exit_penalty(&mut self, penalty_bps: u32) {
+        self.access.require_role(ADMIN_ROLE, FarmingError::Unauthorized);
+        
+        if penalty_bps > MAX_EARLY_EXIT_PENALTY_BPS {
+            self.env().revert(FarmingError::InvalidPenalty);
+        }
+        self.early_exit_penalty_bps.set(penalty_bps);
+    }
+    
+    /// Set the LpRewardsDistributor whose boost multiplies stake weights (admin only)
+    /// 
+    /// `None` turns boosts off. Existing stakes pick up the change when synced.
+    pub fn set_boost_source(&mut self, source: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, FarmingError::Unauthorized);
+        self.boost_source.set(source);
+    }
+    
     pub fn pause(&mut self) {
-        self.only_admin();
+        self.access.require_role(PAUSER_ROLE, FarmingError::Unauthorized);
         self.paused.set(true);
     }
     
     pub fn unpause(&mut self) {
-        self.only_admin();
+        self.access.require_role(PAUSER_ROLE, FarmingError::Unauthorized);
         self.paused.set(false);
     }
     
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(FarmingError::Unauthorized);
-        if caller != admin {
-            self.env().revert(FarmingError::Unauthorized);
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
         }
     }
     
+    
     fn ensure_not_paused(&self) {
         if self.paused.get_or_default() {
             self.env().revert(FarmingError::ContractPaused);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::{LpToken, LpTokenInitArgs, LpTokenHostRef};
+    use crate::tokens::{EctoToken, EctoTokenHostRef};
+    use crate::incentives::lp_rewards_distributor::{LpRewardsDistributor, LpRewardsDistributorInitArgs};
+    use crate::lending::lending_pool::{LendingPool, LendingPoolInitArgs};
+    use crate::dex::mock_factory::{deploy_listed_pair, MockFactory, MockFactoryInitArgs};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+
+    struct Farm {
+        env: HostEnv,
+        ecto: EctoTokenHostRef,
+        lp: LpTokenHostRef,
+        farm: StakingPoolHostRef,
+        pool_id: u32,
+    }
+
+    fn deploy_farm() -> Farm {
+        let env = odra_test::env();
+        env.set_caller(env.get_account(0));
+        let ecto = EctoToken::deploy(&env, NoArgs);
+        let lp = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("LP Token"),
+            symbol: String::from("LP"),
+        });
+        let mut farm = StakingPool::deploy(&env, StakingPoolInitArgs {
+            reward_token_address: ecto.address(),
+        });
+        let pool_id = farm.create_pool(lp.address(), U256::one());
+        Farm { env, ecto, lp, farm, pool_id }
+    }
+
+    fn stake(f: &mut Farm, user: Address, amount: U256) {
+        f.env.set_caller(f.env.get_account(0));
+        f.lp.mint(user, amount);
+        f.env.set_caller(user);
+        f.lp.approve(f.farm.address(), amount);
+        f.farm.stake(f.pool_id, amount);
+    }
+
+    fn fund(f: &mut Farm, amount: U256, duration: u64) {
+        let admin = f.env.get_account(0);
+        f.env.set_caller(admin);
+        f.ecto.mint(admin, amount);
+        f.ecto.approve(f.farm.address(), amount);
+        f.farm.notify_reward_amount(f.pool_id, amount, duration);
+    }
+
+    #[test]
+    fn test_unfunded_pool_emits_nothing() {
+        let mut f = deploy_farm();
+        let user = f.env.get_account(1);
+        stake(&mut f, user, U256::from(100));
+
+        f.env.advance_block_time(1_000);
+        assert_eq!(
+            f.farm.try_claim_rewards(f.pool_id).unwrap_err(),
+            FarmingError::NoRewardsToClaim.into()
+        );
+
+        assert_eq!(
+            f.farm.try_notify_reward_amount(f.pool_id, U256::from(1_000), 100).unwrap_err(),
+            FarmingError::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn test_funded_period_streams_budget_then_stops() {
+        let mut f = deploy_farm();
+        let user = f.env.get_account(1);
+        fund(&mut f, U256::from(1_000), 100);
+        stake(&mut f, user, U256::from(100));
+
+        let pool = f.farm.get_pool_info(f.pool_id).unwrap();
+        assert_eq!(pool.reward_rate, U256::from(10));
+        assert_eq!(f.ecto.balance_of(f.farm.address()), U256::from(1_000));
+
+        // Halfway through, half the budget has been streamed
+        f.env.advance_block_time(50);
+        f.farm.claim_rewards(f.pool_id);
+        assert_eq!(f.ecto.balance_of(user), U256::from(500));
+
+        // Well past the end, only the rest of the budget is paid
+        f.env.advance_block_time(500);
+        f.farm.claim_rewards(f.pool_id);
+        assert_eq!(f.ecto.balance_of(user), U256::from(1_000));
+        assert_eq!(f.farm.get_pool_info(f.pool_id).unwrap().reward_budget, U256::zero());
+
+        f.env.advance_block_time(500);
+        assert_eq!(
+            f.farm.try_claim_rewards(f.pool_id).unwrap_err(),
+            FarmingError::NoRewardsToClaim.into()
+        );
+    }
+
+    #[test]
+    fn test_refunding_rolls_leftover_into_new_period() {
+        let mut f = deploy_farm();
+        let user = f.env.get_account(1);
+        stake(&mut f, user, U256::from(100));
+        fund(&mut f, U256::from(1_000), 100);
+
+        f.env.advance_block_time(40);
+        fund(&mut f, U256::from(400), 50);
+
+        // 600 left over plus 400 new, streamed over 50
+        let pool = f.farm.get_pool_info(f.pool_id).unwrap();
+        assert_eq!(pool.reward_budget, U256::from(1_000));
+        assert_eq!(pool.reward_rate, U256::from(20));
+
+        f.env.advance_block_time(100);
+        f.env.set_caller(user);
+        f.farm.claim_rewards(f.pool_id);
+        assert_eq!(f.ecto.balance_of(user), U256::from(1_400));
+    }
+
+    #[test]
+    fn test_locked_stake_earns_boosted_share() {
+        let mut f = deploy_farm();
+        let (plain, locked) = (f.env.get_account(1), f.env.get_account(2));
+        stake(&mut f, plain, U256::from(100));
+        f.env.set_caller(f.env.get_account(0));
+        f.lp.mint(locked, U256::from(100));
+        f.env.set_caller(locked);
+        f.lp.approve(f.farm.address(), U256::from(100));
+        f.farm.stake_locked(f.pool_id, U256::from(100), LockTier::Days180);
+        fund(&mut f, U256::from(3_000), 100);
+
+        let position = f.farm.get_user_stake(locked, f.pool_id).unwrap();
+        assert_eq!(position.weight, U256::from(200));
+        assert_eq!(position.lock_expiry, f.env.block_time() + 180 * DAY);
+
+        f.env.advance_block_time(100);
+        f.env.set_caller(plain);
+        f.farm.claim_rewards(f.pool_id);
+        f.env.set_caller(locked);
+        f.farm.claim_rewards(f.pool_id);
+        assert_eq!(f.ecto.balance_of(plain), U256::from(1_000));
+        assert_eq!(f.ecto.balance_of(locked), U256::from(2_000));
+    }
+
+    #[test]
+    fn test_early_exit_forfeits_boost_and_pays_penalty() {
+        let mut f = deploy_farm();
+        let (plain, locked) = (f.env.get_account(1), f.env.get_account(2));
+        stake(&mut f, plain, U256::from(100));
+        f.env.set_caller(f.env.get_account(0));
+        f.lp.mint(locked, U256::from(200));
+        f.env.set_caller(locked);
+        f.lp.approve(f.farm.address(), U256::from(200));
+        f.farm.stake_locked(f.pool_id, U256::from(200), LockTier::Days90);
+
+        // 10% of the early unstake stays behind, and the rest of the position is unboosted
+        f.farm.unstake(f.pool_id, U256::from(100));
+        assert_eq!(f.lp.balance_of(locked), U256::from(90));
+        let position = f.farm.get_user_stake(locked, f.pool_id).unwrap();
+        assert_eq!(position.lock_tier, LockTier::None);
+        assert_eq!(position.weight, U256::from(100));
+
+        // The penalty is shared by weight between the remaining stakers
+        f.farm.sync_stake(plain, f.pool_id);
+        f.farm.sync_stake(locked, f.pool_id);
+        assert_eq!(f.farm.get_user_stake(plain, f.pool_id).unwrap().amount, U256::from(105));
+        assert_eq!(f.farm.get_user_stake(locked, f.pool_id).unwrap().amount, U256::from(105));
+        assert_eq!(f.farm.get_pool_info(f.pool_id).unwrap().total_staked, U256::from(210));
+    }
+
+    #[test]
+    fn test_expired_lock_exits_without_penalty() {
+        let mut f = deploy_farm();
+        let user = f.env.get_account(1);
+        f.env.set_caller(f.env.get_account(0));
+        f.lp.mint(user, U256::from(100));
+        f.env.set_caller(user);
+        f.lp.approve(f.farm.address(), U256::from(100));
+        f.farm.stake_locked(f.pool_id, U256::from(100), LockTier::Days30);
+
+        f.env.advance_block_time(30 * DAY);
+        f.farm.sync_stake(user, f.pool_id);
+        assert_eq!(f.farm.get_user_stake(user, f.pool_id).unwrap().weight, U256::from(100));
+
+        f.farm.unstake(f.pool_id, U256::from(100));
+        assert_eq!(f.lp.balance_of(user), U256::from(100));
+    }
+
+    #[test]
+    fn test_lp_boost_multiplies_weight_until_synced() {
+        let mut f = deploy_farm();
+        let (boosted, plain) = (f.env.get_account(1), f.env.get_account(2));
+        let placeholder = f.env.get_account(9);
+
+        // Holding 1_000 aECTO earns a 1.3x LP boost
+        let mut aecto = LpToken::deploy(&f.env, LpTokenInitArgs {
+            name: String::from("aECTO"),
+            symbol: String::from("aECTO"),
+        });
+        let scspr = LpToken::deploy(&f.env, LpTokenInitArgs {
+            name: String::from("sCSPR"),
+            symbol: String::from("sCSPR"),
+        });
+        let lending_pool = LendingPool::deploy(&f.env, LendingPoolInitArgs {
+            aecto_vault_address: aecto.address(),
+            collateral_manager_address: placeholder,
+            interest_rate_strategy_address: placeholder,
+            liquidation_engine_address: placeholder,
+            price_oracle_address: placeholder,
+            ecto_token_address: f.ecto.address(),
+        });
+        let distributor = LpRewardsDistributor::deploy(&f.env, LpRewardsDistributorInitArgs {
+            scspr_token_address: scspr.address(),
+            aecto_token_address: aecto.address(),
+            lending_pool_address: lending_pool.address(),
+            reward_token_address: f.ecto.address(),
+        });
+        aecto.mint(boosted, U256::from(1_000_000_000_000_000_000_000u128));
+        f.farm.set_boost_source(Some(distributor.address()));
+
+        stake(&mut f, boosted, U256::from(100));
+        stake(&mut f, plain, U256::from(100));
+        fund(&mut f, U256::from(2_300), 100);
+        assert_eq!(f.farm.get_user_stake(boosted, f.pool_id).unwrap().weight, U256::from(130));
+
+        f.env.advance_block_time(100);
+        f.env.set_caller(boosted);
+        f.farm.claim_rewards(f.pool_id);
+        f.env.set_caller(plain);
+        f.farm.claim_rewards(f.pool_id);
+        assert_eq!(f.ecto.balance_of(boosted), U256::from(1_300));
+        assert_eq!(f.ecto.balance_of(plain), U256::from(1_000));
+
+        // Losing the aECTO only lowers the weight once the stake is synced
+        f.env.set_caller(boosted);
+        aecto.transfer(plain, U256::from(1_000_000_000_000_000_000_000u128));
+        assert_eq!(f.farm.get_user_stake(boosted, f.pool_id).unwrap().weight, U256::from(130));
+        f.farm.sync_stake(boosted, f.pool_id);
+        let position = f.farm.get_user_stake(boosted, f.pool_id).unwrap();
+        assert_eq!(position.weight, U256::from(100));
+        assert_eq!(position.boost, U256::from(1_000_000_000_000_000_000u128));
+    }
+
+    #[test]
+    fn test_reward_distributor_funds_pools_without_admin_rights() {
+        let mut f = deploy_farm();
+        let distributor = f.env.get_account(5);
+        let budget = U256::from(1_000);
+
+        f.env.set_caller(f.env.get_account(0));
+        f.ecto.mint(distributor, budget);
+        f.env.set_caller(distributor);
+        f.ecto.approve(f.farm.address(), budget);
+        assert_eq!(
+            f.farm.try_notify_reward_amount(f.pool_id, budget, 100).unwrap_err(),
+            FarmingError::Unauthorized.into()
+        );
+
+        f.env.set_caller(f.env.get_account(0));
+        f.farm.grant_role(REWARD_DISTRIBUTOR_ROLE, distributor);
+        f.env.set_caller(distributor);
+        f.farm.notify_reward_amount(f.pool_id, budget, 100);
+        assert_eq!(f.farm.get_pool_info(f.pool_id).unwrap().reward_budget, budget);
+
+        // The role grants nothing else
+        assert_eq!(
+            f.farm.try_set_stake_limits(f.pool_id, U256::zero(), U256::zero()).unwrap_err(),
+            FarmingError::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn test_stake_for_credits_beneficiary() {
+        let mut f = deploy_farm();
+        let (zapper, beneficiary) = (f.env.get_account(1), f.env.get_account(2));
+        f.env.set_caller(f.env.get_account(0));
+        f.lp.mint(zapper, U256::from(300));
+        f.env.set_caller(zapper);
+        f.lp.approve(f.farm.address(), U256::from(300));
+        f.farm.stake_for(beneficiary, f.pool_id, U256::from(100));
+        fund(&mut f, U256::from(1_000), 100);
+
+        assert!(f.farm.get_user_stake(zapper, f.pool_id).is_none());
+        assert_eq!(f.farm.get_user_stake(beneficiary, f.pool_id).unwrap().amount, U256::from(100));
+
+        // Rewards and the LP belong to the beneficiary
+        f.env.advance_block_time(100);
+        f.env.set_caller(beneficiary);
+        f.farm.claim_rewards(f.pool_id);
+        f.farm.unstake(f.pool_id, U256::from(100));
+        assert_eq!(f.ecto.balance_of(beneficiary), U256::from(1_000));
+        assert_eq!(f.lp.balance_of(beneficiary), U256::from(100));
+
+        // Only the owner can add to a locked position
+        f.lp.approve(f.farm.address(), U256::from(100));
+        f.farm.stake_locked(f.pool_id, U256::from(100), LockTier::Days30);
+        f.env.set_caller(zapper);
+        assert_eq!(
+            f.farm.try_stake_for(beneficiary, f.pool_id, U256::from(100)).unwrap_err(),
+            FarmingError::PositionLocked.into()
+        );
+    }
+
+    #[test]
+    fn test_migrate_pool_moves_opted_in_stakes() {
+        let mut f = deploy_farm();
+        let (migrating, staying, late) = (f.env.get_account(1), f.env.get_account(2), f.env.get_account(3));
+        stake(&mut f, migrating, U256::from(100));
+        stake(&mut f, staying, U256::from(200));
+        stake(&mut f, late, U256::from(300));
+        f.env.set_caller(migrating);
+        f.farm.set_migration_opt_in(f.pool_id, true);
+
+        f.env.set_caller(f.env.get_account(0));
+        let mut new_farm = StakingPool::deploy(&f.env, StakingPoolInitArgs {
+            reward_token_address: f.ecto.address(),
+        });
+        assert_eq!(new_farm.create_pool(f.lp.address(), U256::one()), f.pool_id);
+        assert_eq!(f.farm.migrate_pool(f.pool_id, new_farm.address(), 10), 0);
+
+        assert_eq!(f.farm.get_user_stake(migrating, f.pool_id).unwrap().amount, U256::zero());
+        assert_eq!(new_farm.get_user_stake(migrating, f.pool_id).unwrap().amount, U256::from(100));
+        assert_eq!(f.farm.get_user_stake(staying, f.pool_id).unwrap().amount, U256::from(200));
+        assert_eq!(f.lp.balance_of(new_farm.address()), U256::from(100));
+
+        let pool = f.farm.get_pool_info(f.pool_id).unwrap();
+        assert!(!pool.is_active);
+        assert_eq!(pool.total_staked, U256::from(500));
+        assert_eq!(
+            f.farm.try_migrate_pool(f.pool_id, new_farm.address(), 10).unwrap_err(),
+            FarmingError::PoolMigrated.into()
+        );
+
+        // Opting in after the migration moves the stake right away
+        f.env.set_caller(late);
+        f.farm.set_migration_opt_in(f.pool_id, true);
+        assert_eq!(new_farm.get_user_stake(late, f.pool_id).unwrap().amount, U256::from(300));
+        assert_eq!(f.farm.get_pool_info(f.pool_id).unwrap().total_staked, U256::from(200));
+    }
+
+    #[test]
+    fn test_migrate_pool_runs_in_batches_and_skips_rejected_stakes() {
+        let mut f = deploy_farm();
+        let (first, capped, last) = (f.env.get_account(1), f.env.get_account(2), f.env.get_account(3));
+        let empty = f.env.get_account(4);
+
+        // Opting in needs a stake to migrate
+        f.env.set_caller(empty);
+        assert_eq!(
+            f.farm.try_set_migration_opt_in(f.pool_id, true).unwrap_err(),
+            FarmingError::InsufficientBalance.into()
+        );
+
+        stake(&mut f, first, U256::from(100));
+        stake(&mut f, capped, U256::from(500));
+        stake(&mut f, last, U256::from(100));
+        for user in [first, capped, last] {
+            f.env.set_caller(user);
+            f.farm.set_migration_opt_in(f.pool_id, true);
+        }
+
+        // The new pool has no room for the 500 LP stake
+        f.env.set_caller(f.env.get_account(0));
+        let mut new_farm = StakingPool::deploy(&f.env, StakingPoolInitArgs {
+            reward_token_address: f.ecto.address(),
+        });
+        new_farm.create_pool(f.lp.address(), U256::one());
+        new_farm.set_stake_limits(f.pool_id, U256::zero(), U256::from(200));
+        let other_farm = StakingPool::deploy(&f.env, StakingPoolInitArgs {
+            reward_token_address: f.ecto.address(),
+        });
+
+        assert_eq!(f.farm.migrate_pool(f.pool_id, new_farm.address(), 2), 1);
+        assert!(!f.farm.get_pool_info(f.pool_id).unwrap().is_active);
+        assert_eq!(f.farm.get_migration_target(f.pool_id), None);
+        assert_eq!(new_farm.get_user_stake(first, f.pool_id).unwrap().amount, U256::from(100));
+        assert_eq!(f.farm.get_user_stake(capped, f.pool_id).unwrap().amount, U256::from(500));
+        assert!(f.farm.is_migration_opted_in(capped, f.pool_id));
+
+        // Later batches must keep the same target
+        assert_eq!(
+            f.farm.try_migrate_pool(f.pool_id, other_farm.address(), 2).unwrap_err(),
+            FarmingError::InvalidConfiguration.into()
+        );
+        assert_eq!(f.farm.migrate_pool(f.pool_id, new_farm.address(), 2), 0);
+        assert_eq!(new_farm.get_user_stake(last, f.pool_id).unwrap().amount, U256::from(100));
+        assert_eq!(f.farm.get_migration_target(f.pool_id), Some(new_farm.address()));
+
+        let progress = f.farm.get_migration_progress(f.pool_id).unwrap();
+        assert_eq!(progress.users_migrated, 2);
+        assert_eq!(progress.amount_migrated, U256::from(200));
+        assert!(f.env.emitted_event(&f.farm, PoolMigrated {
+            pool_id: f.pool_id,
+            new_staking_pool: new_farm.address(),
+            users_migrated: 2,
+            amount_migrated: U256::from(200),
+        }));
+    }
+
+    #[test]
+    fn test_stake_caps_limit_pool_and_user() {
+        let mut f = deploy_farm();
+        let (first, second) = (f.env.get_account(1), f.env.get_account(2));
+        f.farm.set_stake_limits(f.pool_id, U256::from(250), U256::from(150));
+        assert_eq!(f.farm.get_stake_headroom(first, f.pool_id), U256::from(150));
+
+        stake(&mut f, first, U256::from(150));
+        f.lp.mint(first, U256::one());
+        f.lp.approve(f.farm.address(), U256::one());
+        assert_eq!(
+            f.farm.try_stake(f.pool_id, U256::one()).unwrap_err(),
+            FarmingError::StakeCapExceeded.into()
+        );
+
+        // The pool cap binds before the second user's own cap
+        assert_eq!(f.farm.get_stake_headroom(second, f.pool_id), U256::from(100));
+        stake(&mut f, second, U256::from(100));
+        f.lp.mint(second, U256::one());
+        f.lp.approve(f.farm.address(), U256::one());
+        assert_eq!(
+            f.farm.try_stake(f.pool_id, U256::one()).unwrap_err(),
+            FarmingError::StakeCapExceeded.into()
+        );
+        assert_eq!(f.farm.get_stake_headroom(second, f.pool_id), U256::zero());
+    }
+
+    #[test]
+    fn test_anyone_can_list_funded_pool_for_factory_pair() {
+        let mut f = deploy_farm();
+        let (admin, partner, treasury) = (f.env.get_account(0), f.env.get_account(1), f.env.get_account(2));
+        let mut factory = MockFactory::deploy(&f.env, MockFactoryInitArgs { fee_to_setter: admin });
+        let pair = deploy_listed_pair(&f.env, &mut factory, f.ecto.address(), f.lp.address());
+        let mut other_factory = MockFactory::deploy(&f.env, MockFactoryInitArgs { fee_to_setter: admin });
+        let unlisted_pair = deploy_listed_pair(&f.env, &mut other_factory, f.ecto.address(), f.lp.address());
+
+        f.env.set_caller(partner);
+        assert_eq!(
+            f.farm.try_create_funded_pool(pair, U256::from(10_000), 1_000).unwrap_err(),
+            FarmingError::InvalidConfiguration.into()
+        );
+
+        f.env.set_caller(admin);
+        f.farm.set_listing_params(Some(ListingParams {
+            factory: factory.address(),
+            min_budget: U256::from(5_000),
+            min_duration: 500,
+            listing_fee: U256::from(100),
+            fee_recipient: treasury,
+        }));
+        f.ecto.mint(partner, U256::from(10_100));
+
+        f.env.set_caller(partner);
+        f.ecto.approve(f.farm.address(), U256::from(10_100));
+        assert_eq!(
+            f.farm.try_create_funded_pool(pair, U256::from(1_000), 1_000).unwrap_err(),
+            FarmingError::BelowListingMinimum.into()
+        );
+        assert_eq!(
+            f.farm.try_create_funded_pool(unlisted_pair, U256::from(10_000), 1_000).unwrap_err(),
+            FarmingError::NotFactoryPair.into()
+        );
+
+        let pool_id = f.farm.create_funded_pool(pair, U256::from(10_000), 1_000);
+        let pool = f.farm.get_pool_info(pool_id).unwrap();
+        assert_eq!(pool.lp_token, pair);
+        assert_eq!(pool.reward_budget, U256::from(10_000));
+        assert_eq!(pool.reward_rate, U256::from(10));
+        assert_eq!(pool.period_finish, f.env.block_time() + 1_000);
+        assert_eq!(f.ecto.balance_of(treasury), U256::from(100));
+        assert_eq!(f.ecto.balance_of(f.farm.address()), U256::from(10_000));
+    }
+}
@@ -0,0 +1,367 @@
+//! Emission Minter - weekly ECTO emissions on a decaying schedule
+//!
+//! Each weekly epoch mints that epoch's emission and splits it by the
+//! configured shares:
+//! - Farming: spread across farming pool gauges by their GaugeController
+//!   weight and streamed over the epoch via `notify_reward_amount`
+//! - LP rewards: added to the LpRewardsDistributor's rewards pool
+//! - Treasury: sent to the treasury
+//!
+//! Every epoch's emission is the previous one reduced by the decay rate
+//! (1% per week by default). Anyone can trigger an epoch once it starts;
+//! farming emissions with no gauge weight behind them are not minted.
+//!
+//! The minter must be allowed to mint ECTO and hold the reward distributor
+//! role on every StakingPool its gauges point to.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::GovernanceError;
+use super::events::*;
+use super::gauge_controller::{GaugeControllerContractRef, GaugeType};
+use super::voting_escrow::WEEK;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+use crate::farming::staking_pool::StakingPoolContractRef;
+use crate::incentives::lp_rewards_distributor::LpRewardsDistributorContractRef;
+use crate::math::BPS_DENOMINATOR;
+use crate::token::Cep18TokenContractRef;
+use crate::tokens::EctoTokenContractRef;
+
+/// Default emission decay per epoch (1%)
+pub const DEFAULT_DECAY_BPS: u32 = 100;
+
+/// Default farming share of each epoch's emission (60%)
+pub const DEFAULT_FARMING_SHARE_BPS: u32 = 6_000;
+
+/// Default LP rewards share of each epoch's emission (30%)
+pub const DEFAULT_LP_REWARDS_SHARE_BPS: u32 = 3_000;
+
+/// Default treasury share of each epoch's emission (10%)
+pub const DEFAULT_TREASURY_SHARE_BPS: u32 = 1_000;
+
+/// Emission Minter contract
+#[odra::module]
+pub struct EmissionMinter {
+    /// ECTO token address
+    ecto_token: Var<Address>,
+    /// GaugeController address
+    gauge_controller: Var<Address>,
+    /// LpRewardsDistributor address
+    lp_rewards: Var<Address>,
+    /// Treasury address
+    treasury: Var<Address>,
+    /// Start of epoch 0 (a week boundary)
+    start_time: Var<u64>,
+    /// Next epoch to be emitted
+    next_epoch: Var<u32>,
+    /// Emission of the next epoch
+    next_emission: Var<U256>,
+    /// Emission decay per epoch (bps)
+    decay_bps: Var<u32>,
+    /// Farming share (bps)
+    farming_share_bps: Var<u32>,
+    /// LP rewards share (bps)
+    lp_rewards_share_bps: Var<u32>,
+    /// Treasury share (bps)
+    treasury_share_bps: Var<u32>,
+    /// Total ECTO minted
+    total_emitted: Var<U256>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+}
+
+#[odra::module]
+impl EmissionMinter {
+    /// Initialize the emission minter
+    ///
+    /// Epoch 0 starts at the next week boundary and emits `initial_emission`.
+    pub fn init(
+        &mut self,
+        ecto_token_address: Address,
+        gauge_controller_address: Address,
+        lp_rewards_address: Address,
+        treasury_address: Address,
+        initial_emission: U256,
+    ) {
+        let caller = self.env().caller();
+
+        self.ecto_token.set(ecto_token_address);
+        self.gauge_controller.set(gauge_controller_address);
+        self.lp_rewards.set(lp_rewards_address);
+        self.treasury.set(treasury_address);
+        self.start_time.set((self.env().get_block_time() / WEEK + 1) * WEEK);
+        self.next_epoch.set(0);
+        self.next_emission.set(initial_emission);
+        self.decay_bps.set(DEFAULT_DECAY_BPS);
+        self.farming_share_bps.set(DEFAULT_FARMING_SHARE_BPS);
+        self.lp_rewards_share_bps.set(DEFAULT_LP_REWARDS_SHARE_BPS);
+        self.treasury_share_bps.set(DEFAULT_TREASURY_SHARE_BPS);
+        self.total_emitted.set(U256::zero());
+        self.access.grant(ADMIN_ROLE, caller);
+    }
+
+    /// Mint and distribute the next epoch's emission once it has started (anyone)
+    ///
+    /// Returns the epoch emitted. Epochs missed are emitted one per call,
+    /// each by the gauge weights of its own week.
+    pub fn update_period(&mut self) -> u32 {
+        let epoch = self.next_epoch.get_or_default();
+        let epoch_start = self.get_epoch_start(epoch);
+        let now = self.env().get_block_time();
+        if now < epoch_start {
+            self.env().revert(GovernanceError::EpochNotStarted);
+        }
+
+        let emission = self.next_emission.get_or_default();
+        let denominator = U256::from(BPS_DENOMINATOR);
+        let farming_budget = emission * U256::from(self.farming_share_bps.get_or_default()) / denominator;
+        let lp_rewards_amount = emission * U256::from(self.lp_rewards_share_bps.get_or_default()) / denominator;
+        let treasury_amount = emission * U256::from(self.treasury_share_bps.get_or_default()) / denominator;
+
+        let farming_amount = self.emit_to_farming_gauges(farming_budget, epoch_start);
+
+        if lp_rewards_amount > U256::zero() {
+            let lp_rewards_address = self.lp_rewards.get_or_revert_with(GovernanceError::Unauthorized);
+            self.mint_to_self(lp_rewards_amount);
+            let mut ecto = Cep18TokenContractRef::new(self.env(), self.ecto());
+            ecto.increase_allowance(lp_rewards_address, lp_rewards_amount);
+            LpRewardsDistributorContractRef::new(self.env(), lp_rewards_address).add_rewards(lp_rewards_amount);
+        }
+
+        if treasury_amount > U256::zero() {
+            let treasury = self.treasury.get_or_revert_with(GovernanceError::Unauthorized);
+            EctoTokenContractRef::new(self.env(), self.ecto()).mint(treasury, treasury_amount);
+        }
+
+        let emitted = farming_amount + lp_rewards_amount + treasury_amount;
+        self.total_emitted.set(self.total_emitted.get_or_default() + emitted);
+        self.next_epoch.set(epoch + 1);
+        let decay = U256::from(self.decay_bps.get_or_default());
+        self.next_emission.set(emission * (denominator - decay) / denominator);
+
+        self.env().emit_event(EpochEmitted {
+            epoch,
+            emission: emitted,
+            farming: farming_amount,
+            lp_rewards: lp_rewards_amount,
+            treasury: treasury_amount,
+            timestamp: now,
+        });
+
+        epoch
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get the start timestamp of `epoch`
+    pub fn get_epoch_start(&self, epoch: u32) -> u64 {
+        self.start_time.get_or_default() + epoch as u64 * WEEK
+    }
+
+    /// Get the next epoch to be emitted
+    pub fn get_next_epoch(&self) -> u32 {
+        self.next_epoch.get_or_default()
+    }
+
+    /// Get the emission of the next epoch
+    pub fn get_next_emission(&self) -> U256 {
+        self.next_emission.get_or_default()
+    }
+
+    /// Get the farming, LP rewards and treasury shares (bps)
+    pub fn get_shares(&self) -> (u32, u32, u32) {
+        (
+            self.farming_share_bps.get_or_default(),
+            self.lp_rewards_share_bps.get_or_default(),
+            self.treasury_share_bps.get_or_default(),
+        )
+    }
+
+    /// Get the emission decay per epoch (bps)
+    pub fn get_decay_bps(&self) -> u32 {
+        self.decay_bps.get_or_default()
+    }
+
+    /// Get the total ECTO minted
+    pub fn get_total_emitted(&self) -> U256 {
+        self.total_emitted.get_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Set how each epoch's emission is split (admin only); shares must sum to 100%
+    pub fn set_shares(&mut self, farming_bps: u32, lp_rewards_bps: u32, treasury_bps: u32) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+
+        if farming_bps as u128 + lp_rewards_bps as u128 + treasury_bps as u128 != BPS_DENOMINATOR {
+            self.env().revert(GovernanceError::InvalidShares);
+        }
+
+        self.farming_share_bps.set(farming_bps);
+        self.lp_rewards_share_bps.set(lp_rewards_bps);
+        self.treasury_share_bps.set(treasury_bps);
+
+        self.env().emit_event(EmissionSharesUpdated {
+            farming_bps,
+            lp_rewards_bps,
+            treasury_bps,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Set the emission decay per epoch (admin only)
+    pub fn set_decay_bps(&mut self, decay_bps: u32) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+
+        if decay_bps as u128 >= BPS_DENOMINATOR {
+            self.env().revert(GovernanceError::InvalidShares);
+        }
+        self.decay_bps.set(decay_bps);
+    }
+
+    /// Set the treasury address (admin only)
+    pub fn set_treasury(&mut self, treasury: Address) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        self.treasury.set(treasury);
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// Stream `budget` to farming pool gauges by their weight at `epoch_start`
+    ///
+    /// Returns the ECTO minted; the share of gauges without weight is not.
+    fn emit_to_farming_gauges(&mut self, budget: U256, epoch_start: u64) -> U256 {
+        let controller_address = self.gauge_controller.get_or_revert_with(GovernanceError::Unauthorized);
+        let controller = GaugeControllerContractRef::new(self.env(), controller_address);
+        let scale = U256::from(1_000_000_000_000_000_000u128);
+
+        let mut emitted = U256::zero();
+        for gauge_id in 0..controller.get_gauge_count() {
+            let gauge = match controller.get_gauge(gauge_id) {
+                Some(gauge) => gauge,
+                None => continue,
+            };
+            if gauge.gauge_type != GaugeType::FarmingPool {
+                continue;
+            }
+
+            let amount = budget * controller.gauge_relative_weight(gauge_id, epoch_start) / scale;
+            if amount < U256::from(WEEK) {
+                // Too little to stream at a nonzero rate over the epoch
+                continue;
+            }
+
+            self.mint_to_self(amount);
+            let mut ecto = Cep18TokenContractRef::new(self.env(), self.ecto());
+            ecto.increase_allowance(gauge.target, amount);
+            StakingPoolContractRef::new(self.env(), gauge.target)
+                .notify_reward_amount(gauge.pool_id, amount, WEEK);
+            emitted += amount;
+        }
+
+        emitted
+    }
+
+    fn mint_to_self(&mut self, amount: U256) {
+        let self_address = Address::from(self.env().self_address());
+        EctoTokenContractRef::new(self.env(), self.ecto()).mint(self_address, amount);
+    }
+
+    fn ecto(&self) -> Address {
+        self.ecto_token.get_or_revert_with(GovernanceError::Unauthorized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access::REWARD_DISTRIBUTOR_ROLE;
+    use crate::farming::staking_pool::{StakingPool, StakingPoolInitArgs};
+    use crate::governance::gauge_controller::{GaugeController, GaugeControllerInitArgs};
+    use crate::governance::voting_escrow::{VotingEscrow, VotingEscrowInitArgs, MAX_LOCK_TIME};
+    use crate::incentives::lp_rewards_distributor::{LpRewardsDistributor, LpRewardsDistributorInitArgs};
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    #[test]
+    fn test_epochs_split_decaying_emission() {
+        let env = odra_test::env();
+        let (admin, voter, treasury) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut ve = VotingEscrow::deploy(&env, VotingEscrowInitArgs {
+            token_address: ecto.address(),
+        });
+        let mut controller = GaugeController::deploy(&env, GaugeControllerInitArgs {
+            voting_escrow_address: ve.address(),
+        });
+        let mut farm = StakingPool::deploy(&env, StakingPoolInitArgs {
+            reward_token_address: ecto.address(),
+        });
+        farm.create_pool(env.get_account(7), U256::one());
+        farm.create_pool(env.get_account(8), U256::one());
+        let lp_rewards = LpRewardsDistributor::deploy(&env, LpRewardsDistributorInitArgs {
+            scspr_token_address: env.get_account(5),
+            aecto_token_address: env.get_account(6),
+            lending_pool_address: env.get_account(7),
+            reward_token_address: ecto.address(),
+        });
+        controller.add_gauge(GaugeType::FarmingPool, farm.address(), 0);
+        controller.add_gauge(GaugeType::FarmingPool, farm.address(), 1);
+        controller.add_gauge(GaugeType::LpPair, env.get_account(9), 0);
+
+        let emission = U256::from(1_000_000_000_000_000_000_000_000u128);
+        let mut minter = EmissionMinter::deploy(&env, EmissionMinterInitArgs {
+            ecto_token_address: ecto.address(),
+            gauge_controller_address: controller.address(),
+            lp_rewards_address: lp_rewards.address(),
+            treasury_address: treasury,
+            initial_emission: emission,
+        });
+        farm.grant_role(REWARD_DISTRIBUTOR_ROLE, minter.address());
+        ecto.set_minter(minter.address(), U256::MAX);
+
+        // Three quarters of the vote on pool 0, the rest on pool 1
+        let locked = U256::from(1_000) * U256::from(MAX_LOCK_TIME);
+        ecto.mint(voter, locked);
+        env.set_caller(voter);
+        ecto.approve(ve.address(), locked);
+        ve.create_lock(locked, env.block_time() + 52 * WEEK);
+        controller.vote(0, 7_500);
+        controller.vote(1, 2_500);
+
+        assert_eq!(minter.try_update_period().unwrap_err(), GovernanceError::EpochNotStarted.into());
+        env.advance_block_time(minter.get_epoch_start(0) - env.block_time());
+        assert_eq!(minter.update_period(), 0);
+
+        assert_eq!(farm.get_pool_info(0).unwrap().reward_budget, emission * 45 / 100);
+        assert_eq!(farm.get_pool_info(1).unwrap().reward_budget, emission * 15 / 100);
+        assert_eq!(lp_rewards.get_rewards_pool_balance(), emission * 30 / 100);
+        assert_eq!(ecto.balance_of(treasury), emission / 10);
+        assert_eq!(minter.get_total_emitted(), emission);
+
+        // The next epoch emits 1% less and cannot run early
+        assert_eq!(minter.get_next_emission(), emission * 99 / 100);
+        assert_eq!(minter.try_update_period().unwrap_err(), GovernanceError::EpochNotStarted.into());
+        env.advance_block_time(WEEK);
+        assert_eq!(minter.update_period(), 1);
+        assert_eq!(ecto.balance_of(treasury), emission / 10 + emission * 99 / 1_000);
+    }
+}
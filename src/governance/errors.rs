@@ -0,0 +1,47 @@
+//! Error types for Governance
+
+use odra::prelude::*;
+
+#[odra::odra_error]
+pub enum GovernanceError {
+    /// Unauthorized access
+    Unauthorized = 400,
+    /// Contract paused
+    ContractPaused = 401,
+    /// Zero amount not allowed
+    ZeroAmount = 402,
+    /// Account already has a lock
+    LockExists = 403,
+    /// Account has no lock
+    NoLock = 404,
+    /// Lock has expired
+    LockExpired = 405,
+    /// Lock has not expired yet
+    LockNotExpired = 406,
+    /// Unlock time is in the past, too far out, or not later than the current one
+    InvalidUnlockTime = 407,
+    /// Gauge already exists for this target
+    GaugeExists = 408,
+    /// Gauge not found
+    GaugeNotFound = 409,
+    /// Vote weight above 100% of the voter's power
+    InvalidWeight = 410,
+    /// Voted for this gauge too recently
+    VoteTooSoon = 411,
+    /// Emission shares do not sum to 100%, or decay is 100% or more
+    InvalidShares = 412,
+    /// Next emission epoch has not started
+    EpochNotStarted = 413,
+    /// Epoch has not ended yet
+    EpochNotClosed = 414,
+    /// Fees already claimed for this epoch
+    AlreadyClaimed = 415,
+    /// Lending reserves already collected this epoch
+    ReservesAlreadyCollected = 416,
+    /// Treasury funds not committed to streams are too low
+    InsufficientTreasuryFunds = 417,
+    /// Stream end is not after its start, or the recipient is the treasury
+    InvalidStream = 418,
+    /// Stream not found or already canceled
+    StreamNotFound = 419,
+}
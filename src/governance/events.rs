@@ -0,0 +1,163 @@
+//! Events for Governance
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use super::gauge_controller::GaugeType;
+
+/// Event emitted when ECTO is locked or a lock is extended
+#[odra::event]
+pub struct LockUpdated {
+    pub user: Address,
+    /// ECTO added to the lock (zero when only extending)
+    pub amount: U256,
+    /// Total ECTO locked
+    pub locked: U256,
+    pub unlock_time: u64,
+    pub timestamp: u64,
+}
+
+/// Event emitted when an expired lock is withdrawn
+#[odra::event]
+pub struct LockWithdrawn {
+    pub user: Address,
+    pub amount: U256,
+    pub timestamp: u64,
+}
+
+/// Event emitted whenever the total locked ECTO changes
+#[odra::event]
+pub struct SupplyUpdated {
+    pub previous_supply: U256,
+    pub supply: U256,
+}
+
+/// Event emitted when a veECTO holder delegates its votes
+#[odra::event]
+pub struct VotesDelegated {
+    pub delegator: Address,
+    pub from_delegate: Address,
+    pub to_delegate: Address,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a gauge is added
+#[odra::event]
+pub struct GaugeAdded {
+    pub gauge_id: u32,
+    pub gauge_type: GaugeType,
+    pub target: Address,
+    pub pool_id: u32,
+}
+
+/// Event emitted when a user votes for a gauge
+#[odra::event]
+pub struct GaugeVoted {
+    pub user: Address,
+    pub gauge_id: u32,
+    pub weight_bps: u32,
+    pub timestamp: u64,
+}
+
+/// Event emitted when an epoch's emission is minted and distributed
+#[odra::event]
+pub struct EpochEmitted {
+    pub epoch: u32,
+    /// Total ECTO minted for the epoch
+    pub emission: U256,
+    pub farming: U256,
+    pub lp_rewards: U256,
+    pub treasury: U256,
+    pub timestamp: u64,
+}
+
+/// Event emitted when the emission split is updated
+#[odra::event]
+pub struct EmissionSharesUpdated {
+    pub farming_bps: u32,
+    pub lp_rewards_bps: u32,
+    pub treasury_bps: u32,
+    pub updated_by: Address,
+}
+
+/// Event emitted when fees received are credited to an epoch
+#[odra::event]
+pub struct FeesCheckpointed {
+    pub token: Address,
+    pub epoch: u64,
+    pub amount: U256,
+}
+
+/// Event emitted when fees of an epoch that started with no veECTO move to the next epoch
+#[odra::event]
+pub struct FeesRolledOver {
+    pub token: Address,
+    pub from_epoch: u64,
+    pub to_epoch: u64,
+    pub amount: U256,
+}
+
+/// Event emitted when a veECTO holder claims an epoch's fees
+#[odra::event]
+pub struct FeesClaimed {
+    pub user: Address,
+    pub token: Address,
+    pub epoch: u64,
+    pub amount: U256,
+}
+
+/// Event emitted when the treasury receives tokens (None = CSPR)
+#[odra::event]
+pub struct TreasuryDeposited {
+    pub token: Option<Address>,
+    pub from: Address,
+    pub amount: U256,
+}
+
+/// Event emitted when treasury funds are allocated to the incentive pools
+#[odra::event]
+pub struct TreasuryAllocated {
+    pub token: Address,
+    pub gas_subsidy: U256,
+    pub lp_rewards: U256,
+    pub development: U256,
+    /// Left in the treasury as reserves
+    pub reserves: U256,
+    pub timestamp: u64,
+}
+
+/// Event emitted when treasury funds are paid out directly (None = CSPR)
+#[odra::event]
+pub struct TreasurySpent {
+    pub token: Option<Address>,
+    pub to: Address,
+    pub amount: U256,
+}
+
+/// Event emitted when a payment stream is created (None = CSPR)
+#[odra::event]
+pub struct StreamCreated {
+    pub stream_id: u32,
+    pub recipient: Address,
+    pub token: Option<Address>,
+    pub amount: U256,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Event emitted when a recipient withdraws from a stream
+#[odra::event]
+pub struct StreamWithdrawn {
+    pub stream_id: u32,
+    pub recipient: Address,
+    pub amount: U256,
+}
+
+/// Event emitted when a stream is canceled
+#[odra::event]
+pub struct StreamCanceled {
+    pub stream_id: u32,
+    /// Vested amount paid to the recipient
+    pub recipient_amount: U256,
+    /// Unvested amount released back to the treasury
+    pub returned: U256,
+}
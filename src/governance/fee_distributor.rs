@@ -0,0 +1,417 @@
+//! Fee Distributor - protocol fees shared with veECTO lockers
+//!
+//! Fees received during each weekly epoch are shared pro-rata by veECTO
+//! balance at the start of that epoch, and become claimable once it ends:
+//! - DEX protocol fees: with the Factory's `fee_to` set to this contract,
+//!   the LP tokens it receives are credited per token by `checkpoint_token`
+//! - Lending reserves: once per epoch `collect_lending_reserves` pulls the
+//!   configured share of the LendingPool's reserves
+//!
+//! Fees checkpointed in an epoch that started with no veECTO roll into the
+//! next epoch, so they are never stranded without claimants. Checkpoints
+//! also walk the epochs that have started since the last one and keep
+//! rolling fees forward past every epoch that began with no veECTO.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::GovernanceError;
+use super::events::*;
+use super::voting_escrow::{VotingEscrowContractRef, WEEK};
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+use crate::lending::lending_pool::LendingPoolContractRef;
+use crate::math::BPS_DENOMINATOR;
+use crate::token::Cep18TokenContractRef;
+
+/// Default share of lending reserves collected each epoch (50%)
+pub const DEFAULT_RESERVE_SHARE_BPS: u32 = 5_000;
+
+/// Most epochs one checkpoint walks when rolling fees forward
+pub const MAX_ROLLOVER_EPOCHS: u64 = 52;
+
+/// Fee Distributor contract
+#[odra::module]
+pub struct FeeDistributor {
+    /// VotingEscrow address
+    voting_escrow: Var<Address>,
+    /// ECTO token address (the asset of lending reserves)
+    ecto_token: Var<Address>,
+    /// LendingPool whose reserves are shared
+    lending_pool: Var<Option<Address>>,
+    /// Share of lending reserves collected each epoch (bps)
+    reserve_share_bps: Var<u32>,
+    /// Start of epoch 0 (a week boundary)
+    start_time: Var<u64>,
+    /// Fees credited to each epoch: (token, epoch) -> amount
+    epoch_tokens: Mapping<(Address, u64), U256>,
+    /// Balance of each token already credited to an epoch and not yet claimed
+    token_last_balance: Mapping<Address, U256>,
+    /// Epochs whose lending reserves have been collected
+    reserves_collected: Mapping<u64, bool>,
+    /// Claimed flags: (user, token, epoch) -> claimed
+    claimed: Mapping<(Address, Address, u64), bool>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// First epoch per token not yet checked for fees to roll forward
+    rollover_from: Mapping<Address, u64>,
+}
+
+#[odra::module]
+impl FeeDistributor {
+    /// Initialize the fee distributor; epoch 0 is the week in progress
+    pub fn init(&mut self, voting_escrow_address: Address, ecto_token_address: Address) {
+        let caller = self.env().caller();
+        self.voting_escrow.set(voting_escrow_address);
+        self.ecto_token.set(ecto_token_address);
+        self.lending_pool.set(None);
+        self.reserve_share_bps.set(DEFAULT_RESERVE_SHARE_BPS);
+        self.start_time.set(self.env().get_block_time() / WEEK * WEEK);
+        self.access.grant(ADMIN_ROLE, caller);
+    }
+
+    // ========================================
+    // Fee Collection
+    // ========================================
+
+    /// Credit `token` received since the last checkpoint to the current epoch (anyone)
+    ///
+    /// Fees of started epochs that began with no veECTO are first rolled
+    /// forward. Returns the amount credited.
+    pub fn checkpoint_token(&mut self, token: Address) -> U256 {
+        self.roll_forward(token);
+
+        let balance = Cep18TokenContractRef::new(self.env(), token)
+            .balance_of(Address::from(self.env().self_address()));
+        let accounted = self.token_last_balance.get(&token).unwrap_or_default();
+        if balance <= accounted {
+            return U256::zero();
+        }
+
+        let amount = balance - accounted;
+        let epoch = self.crediting_epoch();
+        let credited = self.epoch_tokens.get(&(token, epoch)).unwrap_or_default();
+        self.epoch_tokens.set(&(token, epoch), credited + amount);
+        self.token_last_balance.set(&token, balance);
+
+        self.env().emit_event(FeesCheckpointed {
+            token,
+            epoch,
+            amount,
+        });
+
+        amount
+    }
+
+    /// Collect this epoch's share of the LendingPool's reserves (anyone, once per epoch)
+    ///
+    /// Returns the ECTO collected, which is capped at the pool's idle liquidity.
+    pub fn collect_lending_reserves(&mut self) -> U256 {
+        let epoch = self.get_current_epoch();
+        if self.reserves_collected.get(&epoch).unwrap_or(false) {
+            self.env().revert(GovernanceError::ReservesAlreadyCollected);
+        }
+        self.reserves_collected.set(&epoch, true);
+
+        let pool_address = self.lending_pool.get_or_default()
+            .unwrap_or_revert_with(&self.env(), GovernanceError::Unauthorized);
+        let mut pool = LendingPoolContractRef::new(self.env(), pool_address);
+
+        let share = U256::from(self.reserve_share_bps.get_or_default());
+        let mut amount = pool.get_total_reserves() * share / U256::from(BPS_DENOMINATOR);
+        let liquidity = pool.get_total_liquidity();
+        if amount > liquidity {
+            amount = liquidity;
+        }
+        if amount == U256::zero() {
+            return amount;
+        }
+
+        pool.collect_reserves(amount);
+        let ecto = self.ecto_token.get_or_revert_with(GovernanceError::Unauthorized);
+        self.checkpoint_token(ecto);
+        amount
+    }
+
+    /// Claim the caller's share of `token` fees credited to a finished epoch
+    pub fn claim(&mut self, token: Address, epoch: u64) -> U256 {
+        if epoch >= self.get_current_epoch() {
+            self.env().revert(GovernanceError::EpochNotClosed);
+        }
+
+        let caller = self.env().caller();
+        if self.is_claimed(caller, token, epoch) {
+            self.env().revert(GovernanceError::AlreadyClaimed);
+        }
+
+        let amount = self.get_claimable(caller, token, epoch);
+        self.claimed.set(&(caller, token, epoch), true);
+
+        if amount > U256::zero() {
+            let accounted = self.token_last_balance.get(&token).unwrap_or_default();
+            self.token_last_balance.set(&token, accounted - amount);
+            Cep18TokenContractRef::new(self.env(), token).transfer(caller, amount);
+        }
+
+        self.env().emit_event(FeesClaimed {
+            user: caller,
+            token,
+            epoch,
+            amount,
+        });
+
+        amount
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get `user`'s share of `token` fees credited to `epoch`
+    pub fn get_claimable(&self, user: Address, token: Address, epoch: u64) -> U256 {
+        if self.is_claimed(user, token, epoch) {
+            return U256::zero();
+        }
+        let fees = self.get_epoch_tokens(token, epoch);
+        if fees == U256::zero() {
+            return U256::zero();
+        }
+
+        let start = self.get_epoch_start(epoch);
+        let escrow = self.escrow();
+        let total = escrow.total_supply_at(start);
+        if total == U256::zero() {
+            return U256::zero();
+        }
+        fees * escrow.balance_of_at(user, start) / total
+    }
+
+    /// Get the `token` fees credited to `epoch`
+    pub fn get_epoch_tokens(&self, token: Address, epoch: u64) -> U256 {
+        self.epoch_tokens.get(&(token, epoch)).unwrap_or_default()
+    }
+
+    /// Get the epoch in progress
+    pub fn get_current_epoch(&self) -> u64 {
+        (self.env().get_block_time() - self.start_time.get_or_default()) / WEEK
+    }
+
+    /// Get the start timestamp of `epoch`
+    pub fn get_epoch_start(&self, epoch: u64) -> u64 {
+        self.start_time.get_or_default() + epoch * WEEK
+    }
+
+    /// Check whether `user` has claimed `token` fees for `epoch`
+    pub fn is_claimed(&self, user: Address, token: Address, epoch: u64) -> bool {
+        self.claimed.get(&(user, token, epoch)).unwrap_or(false)
+    }
+
+    /// Get the LendingPool whose reserves are shared
+    pub fn get_lending_pool(&self) -> Option<Address> {
+        self.lending_pool.get_or_default()
+    }
+
+    /// Get the share of lending reserves collected each epoch (bps)
+    pub fn get_reserve_share_bps(&self) -> u32 {
+        self.reserve_share_bps.get_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Set the LendingPool whose reserves are shared (admin only)
+    ///
+    /// The pool must name this contract its fee collector.
+    pub fn set_lending_pool(&mut self, lending_pool: Address) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        self.lending_pool.set(Some(lending_pool));
+    }
+
+    /// Set the share of lending reserves collected each epoch (admin only)
+    pub fn set_reserve_share_bps(&mut self, share_bps: u32) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        if share_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(GovernanceError::InvalidShares);
+        }
+        self.reserve_share_bps.set(share_bps);
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// The current epoch, or the next one if no veECTO existed at its start
+    fn crediting_epoch(&self) -> u64 {
+        let epoch = self.get_current_epoch();
+        if self.escrow().total_supply_at(self.get_epoch_start(epoch)) == U256::zero() {
+            epoch + 1
+        } else {
+            epoch
+        }
+    }
+
+    /// Move `token` fees of started epochs that began with no veECTO to the
+    /// epoch after, walking at most `MAX_ROLLOVER_EPOCHS` epochs per call
+    fn roll_forward(&mut self, token: Address) {
+        let current = self.get_current_epoch();
+        let from = self.rollover_from.get(&token).unwrap_or_default();
+        let to = current.min(from + MAX_ROLLOVER_EPOCHS - 1);
+        if from > to {
+            return;
+        }
+
+        let escrow = self.escrow();
+        for epoch in from..=to {
+            let amount = self.get_epoch_tokens(token, epoch);
+            if amount == U256::zero() || escrow.total_supply_at(self.get_epoch_start(epoch)) > U256::zero() {
+                continue;
+            }
+            let next = self.get_epoch_tokens(token, epoch + 1);
+            self.epoch_tokens.set(&(token, epoch), U256::zero());
+            self.epoch_tokens.set(&(token, epoch + 1), next + amount);
+
+            self.env().emit_event(FeesRolledOver {
+                token,
+                from_epoch: epoch,
+                to_epoch: epoch + 1,
+                amount,
+            });
+        }
+        self.rollover_from.set(&token, to + 1);
+    }
+
+    fn escrow(&self) -> VotingEscrowContractRef {
+        let escrow_address = self.voting_escrow.get_or_revert_with(GovernanceError::Unauthorized);
+        VotingEscrowContractRef::new(self.env(), escrow_address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::voting_escrow::{VotingEscrow, VotingEscrowInitArgs, MAX_LOCK_TIME};
+    use crate::token::{LpToken, LpTokenInitArgs};
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    #[test]
+    fn test_fees_split_by_ve_balance_at_epoch_start() {
+        let env = odra_test::env();
+        let (admin, alice, bob, carol) = (env.get_account(0), env.get_account(1), env.get_account(2), env.get_account(3));
+        env.advance_block_time(WEEK / 2);
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut ve = VotingEscrow::deploy(&env, VotingEscrowInitArgs {
+            token_address: ecto.address(),
+        });
+        let mut distributor = FeeDistributor::deploy(&env, FeeDistributorInitArgs {
+            voting_escrow_address: ve.address(),
+            ecto_token_address: ecto.address(),
+        });
+        let mut lp = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("LP Token"),
+            symbol: String::from("LP"),
+        });
+
+        let end = env.block_time() + 52 * WEEK;
+        for (user, slope) in [(alice, 300u64), (bob, 100)] {
+            let amount = U256::from(slope) * U256::from(MAX_LOCK_TIME);
+            env.set_caller(admin);
+            ecto.mint(user, amount);
+            env.set_caller(user);
+            ecto.approve(ve.address(), amount);
+            ve.create_lock(amount, end);
+        }
+
+        // Epoch 0 started before anyone locked, so its fees roll into epoch 1
+        env.set_caller(admin);
+        lp.mint(distributor.address(), U256::from(1_000));
+        assert_eq!(distributor.checkpoint_token(lp.address()), U256::from(1_000));
+        assert_eq!(distributor.get_epoch_tokens(lp.address(), 1), U256::from(1_000));
+
+        // Carol locks after epoch 1 starts and earns nothing from it
+        env.advance_block_time(distributor.get_epoch_start(1) + 100 - env.block_time());
+        ecto.mint(carol, U256::from(MAX_LOCK_TIME) * U256::from(1_000));
+        env.set_caller(carol);
+        ecto.approve(ve.address(), U256::from(MAX_LOCK_TIME) * U256::from(1_000));
+        ve.create_lock(U256::from(MAX_LOCK_TIME) * U256::from(1_000), end);
+        env.set_caller(admin);
+        lp.mint(distributor.address(), U256::from(400));
+        distributor.checkpoint_token(lp.address());
+        assert_eq!(distributor.get_epoch_tokens(lp.address(), 1), U256::from(1_400));
+
+        env.set_caller(alice);
+        assert_eq!(distributor.try_claim(lp.address(), 1).unwrap_err(), GovernanceError::EpochNotClosed.into());
+        env.advance_block_time(WEEK);
+        assert_eq!(distributor.claim(lp.address(), 1), U256::from(1_050));
+        assert_eq!(distributor.try_claim(lp.address(), 1).unwrap_err(), GovernanceError::AlreadyClaimed.into());
+        env.set_caller(bob);
+        assert_eq!(distributor.claim(lp.address(), 1), U256::from(350));
+        env.set_caller(carol);
+        assert_eq!(distributor.claim(lp.address(), 1), U256::zero());
+        assert_eq!(lp.balance_of(alice), U256::from(1_050));
+    }
+
+    #[test]
+    fn test_fees_roll_forward_over_several_empty_epochs() {
+        let env = odra_test::env();
+        let (admin, alice) = (env.get_account(0), env.get_account(1));
+        env.advance_block_time(WEEK / 2);
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut ve = VotingEscrow::deploy(&env, VotingEscrowInitArgs {
+            token_address: ecto.address(),
+        });
+        let mut distributor = FeeDistributor::deploy(&env, FeeDistributorInitArgs {
+            voting_escrow_address: ve.address(),
+            ecto_token_address: ecto.address(),
+        });
+        let mut lp = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("LP Token"),
+            symbol: String::from("LP"),
+        });
+
+        // Nobody locks during epochs 0 to 3
+        lp.mint(distributor.address(), U256::from(1_000));
+        distributor.checkpoint_token(lp.address());
+        assert_eq!(distributor.get_epoch_tokens(lp.address(), 1), U256::from(1_000));
+        env.advance_block_time(distributor.get_epoch_start(3) + 100 - env.block_time());
+
+        let amount = U256::from(100u64) * U256::from(MAX_LOCK_TIME);
+        ecto.mint(alice, amount);
+        env.set_caller(alice);
+        ecto.approve(ve.address(), amount);
+        ve.create_lock(amount, env.block_time() + 52 * WEEK);
+
+        // A checkpoint without new fees moves them past epochs 1 to 3
+        assert_eq!(distributor.checkpoint_token(lp.address()), U256::zero());
+        for epoch in 1..4 {
+            assert_eq!(distributor.get_epoch_tokens(lp.address(), epoch), U256::zero());
+        }
+        assert_eq!(distributor.get_epoch_tokens(lp.address(), 4), U256::from(1_000));
+        assert!(env.emitted_event(&distributor, FeesRolledOver {
+            token: lp.address(),
+            from_epoch: 3,
+            to_epoch: 4,
+            amount: U256::from(1_000),
+        }));
+
+        // Alice held all veECTO when epoch 4 started
+        env.advance_block_time(distributor.get_epoch_start(5) - env.block_time());
+        distributor.checkpoint_token(lp.address());
+        assert_eq!(distributor.get_epoch_tokens(lp.address(), 4), U256::from(1_000));
+        assert_eq!(distributor.claim(lp.address(), 4), U256::from(1_000));
+        assert_eq!(lp.balance_of(alice), U256::from(1_000));
+    }
+}
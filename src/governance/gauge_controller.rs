@@ -0,0 +1,504 @@
+//! Gauge Controller - veECTO votes on where emissions go
+//!
+//! Each gauge is a farming pool (a StakingPool address and pool ID) or an LP
+//! reward pair. veECTO holders split their voting power across gauges in
+//! basis points; a vote takes effect from the next weekly epoch and decays
+//! with the voter's veECTO balance.
+//!
+//! Every gauge's weight, and their sum, is tracked as a line like veECTO
+//! itself: a bias recorded at each week boundary and a slope that drops when
+//! the locks behind it expire. The emission minter reads each gauge's share
+//! of an epoch with `gauge_relative_weight`.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::GovernanceError;
+use super::events::*;
+use super::voting_escrow::{VotingEscrowContractRef, MAX_LOCK_TIME, WEEK};
+use crate::access::{AccessControl, Role, ADMIN_ROLE, PAUSER_ROLE, REGISTRAR_ROLE};
+use crate::math::BPS_DENOMINATOR;
+
+/// Minimum time between a user's votes for the same gauge
+pub const VOTE_DELAY: u64 = 10 * 86_400;
+
+/// Most weeks walked by a single checkpoint or weight query
+const MAX_WEEKS: u32 = 500;
+
+/// What a gauge directs emissions to
+#[odra::odra_type]
+pub enum GaugeType {
+    /// A StakingPool farming pool
+    FarmingPool,
+    /// An LpRewardsDistributor pair
+    LpPair,
+}
+
+/// Gauge information
+#[odra::odra_type]
+pub struct GaugeInfo {
+    /// Gauge ID
+    pub gauge_id: u32,
+    /// What the gauge directs emissions to
+    pub gauge_type: GaugeType,
+    /// StakingPool address for farming pools, pair address for LP pairs
+    pub target: Address,
+    /// Pool ID within the StakingPool (0 for LP pairs)
+    pub pool_id: u32,
+}
+
+/// A weight line, worth `bias` at its week and losing `slope` per second after
+#[odra::odra_type]
+pub struct WeightPoint {
+    pub bias: U256,
+    pub slope: U256,
+}
+
+/// A user's vote for one gauge
+#[odra::odra_type]
+pub struct VotedSlope {
+    /// Weight lost per second
+    pub slope: U256,
+    /// Share of the user's voting power (bps)
+    pub power: u32,
+    /// When the vote's weight reaches zero (the voter's unlock time)
+    pub end: u64,
+}
+
+/// Gauge Controller contract
+#[odra::module]
+pub struct GaugeController {
+    /// VotingEscrow address
+    voting_escrow: Var<Address>,
+    /// Gauges by ID
+    gauges: Mapping<u32, GaugeInfo>,
+    /// Gauge ID of each (target, pool_id)
+    gauge_ids: Mapping<(Address, u32), u32>,
+    /// Number of gauges
+    gauge_count: Var<u32>,
+    /// Weight lines at each week boundary: (gauge ID or None for the sum, week) -> point
+    points_weight: Mapping<(Option<u32>, u64), WeightPoint>,
+    /// Slope dropped from each line at each week boundary
+    changes_weight: Mapping<(Option<u32>, u64), U256>,
+    /// Latest week each line has been recorded up to
+    time_weight: Mapping<Option<u32>, u64>,
+    /// User votes: (user, gauge_id) -> VotedSlope
+    vote_user_slopes: Mapping<(Address, u32), VotedSlope>,
+    /// Share of each user's voting power in use (bps)
+    vote_user_power: Mapping<Address, u32>,
+    /// Last vote time: (user, gauge_id) -> timestamp
+    last_user_vote: Mapping<(Address, u32), u64>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Paused state
+    paused: Var<bool>,
+}
+
+#[odra::module]
+impl GaugeController {
+    /// Initialize the gauge controller
+    pub fn init(&mut self, voting_escrow_address: Address) {
+        let caller = self.env().caller();
+        self.voting_escrow.set(voting_escrow_address);
+        self.gauge_count.set(0);
+        self.time_weight.set(&None, self.next_week());
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(PAUSER_ROLE, caller);
+        self.access.grant(REGISTRAR_ROLE, caller);
+        self.paused.set(false);
+    }
+
+    // ========================================
+    // Gauge Management (Registrar)
+    // ========================================
+
+    /// Add a gauge for a farming pool or LP reward pair (registrar only)
+    ///
+    /// # Arguments
+    /// * `gauge_type` - What the gauge directs emissions to
+    /// * `target` - StakingPool address for farming pools, pair address for LP pairs
+    /// * `pool_id` - Pool ID within the StakingPool (0 for LP pairs)
+    pub fn add_gauge(&mut self, gauge_type: GaugeType, target: Address, pool_id: u32) -> u32 {
+        self.access.require_role(REGISTRAR_ROLE, GovernanceError::Unauthorized);
+
+        if self.gauge_ids.get(&(target, pool_id)).is_some() {
+            self.env().revert(GovernanceError::GaugeExists);
+        }
+
+        let gauge_id = self.gauge_count.get_or_default();
+        self.gauges.set(&gauge_id, GaugeInfo {
+            gauge_id,
+            gauge_type: gauge_type.clone(),
+            target,
+            pool_id,
+        });
+        self.gauge_ids.set(&(target, pool_id), gauge_id);
+        self.gauge_count.set(gauge_id + 1);
+        self.time_weight.set(&Some(gauge_id), self.next_week());
+
+        self.env().emit_event(GaugeAdded {
+            gauge_id,
+            gauge_type,
+            target,
+            pool_id,
+        });
+
+        gauge_id
+    }
+
+    // ========================================
+    // Voting
+    // ========================================
+
+    /// Put `weight_bps` of the caller's veECTO behind `gauge_id` from the next epoch
+    ///
+    /// Replaces the caller's previous vote for the gauge; a weight of 0
+    /// withdraws it. A user's votes across all gauges cannot exceed 100%.
+    pub fn vote(&mut self, gauge_id: u32, weight_bps: u32) {
+        self.ensure_not_paused();
+
+        if self.gauges.get(&gauge_id).is_none() {
+            self.env().revert(GovernanceError::GaugeNotFound);
+        }
+        if weight_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(GovernanceError::InvalidWeight);
+        }
+
+        let caller = self.env().caller();
+        let now = self.env().get_block_time();
+        let next = self.next_week();
+
+        let escrow_address = self.voting_escrow.get_or_revert_with(GovernanceError::Unauthorized);
+        let lock = VotingEscrowContractRef::new(self.env(), escrow_address).get_locked(caller);
+        if lock.amount == U256::zero() {
+            self.env().revert(GovernanceError::NoLock);
+        }
+        if lock.end <= next {
+            self.env().revert(GovernanceError::LockExpired);
+        }
+
+        if let Some(last_vote) = self.last_user_vote.get(&(caller, gauge_id)) {
+            if now < last_vote + VOTE_DELAY {
+                self.env().revert(GovernanceError::VoteTooSoon);
+            }
+        }
+
+        let old = self.vote_user_slopes.get(&(caller, gauge_id)).unwrap_or(VotedSlope {
+            slope: U256::zero(),
+            power: 0,
+            end: 0,
+        });
+        let old_bias = if old.end > next {
+            old.slope * U256::from(old.end - next)
+        } else {
+            U256::zero()
+        };
+
+        let lock_slope = lock.amount / U256::from(MAX_LOCK_TIME);
+        let new = VotedSlope {
+            slope: lock_slope * U256::from(weight_bps) / U256::from(BPS_DENOMINATOR),
+            power: weight_bps,
+            end: lock.end,
+        };
+        let new_bias = new.slope * U256::from(new.end - next);
+
+        let power_used = self.vote_user_power.get(&caller).unwrap_or_default() + weight_bps - old.power;
+        if power_used as u128 > BPS_DENOMINATOR {
+            self.env().revert(GovernanceError::InvalidWeight);
+        }
+        self.vote_user_power.set(&caller, power_used);
+
+        // Swap the old vote for the new one in the gauge's line and the sum
+        for line in [Some(gauge_id), None] {
+            let mut point = self.checkpoint_line(line);
+            point.bias = (point.bias + new_bias).saturating_sub(old_bias);
+            point.slope = if old.end > next {
+                (point.slope + new.slope).saturating_sub(old.slope)
+            } else {
+                point.slope + new.slope
+            };
+            self.points_weight.set(&(line, next), point);
+
+            if old.end > now {
+                let scheduled = self.changes_weight.get(&(line, old.end)).unwrap_or_default();
+                self.changes_weight.set(&(line, old.end), scheduled.saturating_sub(old.slope));
+            }
+            let scheduled = self.changes_weight.get(&(line, new.end)).unwrap_or_default();
+            self.changes_weight.set(&(line, new.end), scheduled + new.slope);
+        }
+
+        self.vote_user_slopes.set(&(caller, gauge_id), new);
+        self.last_user_vote.set(&(caller, gauge_id), now);
+
+        self.env().emit_event(GaugeVoted {
+            user: caller,
+            gauge_id,
+            weight_bps,
+            timestamp: now,
+        });
+    }
+
+    /// Record a gauge's weight line and the sum up to the next epoch (anyone)
+    pub fn checkpoint_gauge(&mut self, gauge_id: u32) {
+        if self.gauges.get(&gauge_id).is_none() {
+            self.env().revert(GovernanceError::GaugeNotFound);
+        }
+        self.checkpoint_line(Some(gauge_id));
+        self.checkpoint_line(None);
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get a gauge's share of all gauge weight in the epoch containing `timestamp` (1e18 = 100%)
+    pub fn gauge_relative_weight(&self, gauge_id: u32, timestamp: u64) -> U256 {
+        let total = self.get_total_weight(timestamp);
+        if total == U256::zero() {
+            return U256::zero();
+        }
+        self.get_gauge_weight(gauge_id, timestamp) * U256::from(1_000_000_000_000_000_000u128) / total
+    }
+
+    /// Get a gauge's weight in the epoch containing `timestamp`
+    pub fn get_gauge_weight(&self, gauge_id: u32, timestamp: u64) -> U256 {
+        self.line_at(Some(gauge_id), timestamp)
+    }
+
+    /// Get the summed weight of all gauges in the epoch containing `timestamp`
+    pub fn get_total_weight(&self, timestamp: u64) -> U256 {
+        self.line_at(None, timestamp)
+    }
+
+    /// Get gauge information
+    pub fn get_gauge(&self, gauge_id: u32) -> Option<GaugeInfo> {
+        self.gauges.get(&gauge_id)
+    }
+
+    /// Get the gauge ID for a target and pool ID
+    pub fn get_gauge_id(&self, target: Address, pool_id: u32) -> Option<u32> {
+        self.gauge_ids.get(&(target, pool_id))
+    }
+
+    /// Get the number of gauges
+    pub fn get_gauge_count(&self) -> u32 {
+        self.gauge_count.get_or_default()
+    }
+
+    /// Get `user`'s vote for `gauge_id`
+    pub fn get_user_vote(&self, user: Address, gauge_id: u32) -> Option<VotedSlope> {
+        self.vote_user_slopes.get(&(user, gauge_id))
+    }
+
+    /// Get the share of `user`'s voting power in use (bps)
+    pub fn get_user_vote_power(&self, user: Address) -> u32 {
+        self.vote_user_power.get(&user).unwrap_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    pub fn pause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, GovernanceError::Unauthorized);
+        self.paused.set(true);
+    }
+
+    pub fn unpause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, GovernanceError::Unauthorized);
+        self.paused.set(false);
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// Record a weight line at every week up to the next epoch, returning its point there
+    fn checkpoint_line(&mut self, line: Option<u32>) -> WeightPoint {
+        let now = self.env().get_block_time();
+        let mut t = self.time_weight.get(&line).unwrap_or_default();
+        let mut point = self.point_or_zero(line, t);
+
+        for _ in 0..MAX_WEEKS {
+            if t > now {
+                break;
+            }
+            t += WEEK;
+            point = self.decay_week(line, point, t);
+            self.points_weight.set(&(line, t), point.clone());
+            if t > now {
+                self.time_weight.set(&line, t);
+            }
+        }
+
+        point
+    }
+
+    /// Value of a weight line in the epoch containing `timestamp`
+    fn line_at(&self, line: Option<u32>, timestamp: u64) -> U256 {
+        let week = timestamp / WEEK * WEEK;
+        let mut t = self.time_weight.get(&line).unwrap_or_default();
+        if week <= t {
+            return self.point_or_zero(line, week).bias;
+        }
+
+        let mut point = self.point_or_zero(line, t);
+        for _ in 0..MAX_WEEKS {
+            t += WEEK;
+            point = self.decay_week(line, point, t);
+            if t >= week {
+                break;
+            }
+        }
+        point.bias
+    }
+
+    /// Move `point` forward one week to `t`, dropping the slope of votes ending there
+    fn decay_week(&self, line: Option<u32>, point: WeightPoint, t: u64) -> WeightPoint {
+        let decay = point.slope * U256::from(WEEK);
+        if point.bias > decay {
+            let slope_change = self.changes_weight.get(&(line, t)).unwrap_or_default();
+            WeightPoint {
+                bias: point.bias - decay,
+                slope: point.slope.saturating_sub(slope_change),
+            }
+        } else {
+            WeightPoint {
+                bias: U256::zero(),
+                slope: U256::zero(),
+            }
+        }
+    }
+
+    fn point_or_zero(&self, line: Option<u32>, t: u64) -> WeightPoint {
+        self.points_weight.get(&(line, t)).unwrap_or(WeightPoint {
+            bias: U256::zero(),
+            slope: U256::zero(),
+        })
+    }
+
+    /// Start of the next weekly epoch
+    fn next_week(&self) -> u64 {
+        (self.env().get_block_time() / WEEK + 1) * WEEK
+    }
+
+    fn ensure_not_paused(&self) {
+        if self.paused.get_or_default() {
+            self.env().revert(GovernanceError::ContractPaused);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::voting_escrow::{VotingEscrow, VotingEscrowInitArgs, VotingEscrowHostRef};
+    use crate::tokens::{EctoToken, EctoTokenHostRef};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+
+    struct Gauges {
+        env: HostEnv,
+        ecto: EctoTokenHostRef,
+        ve: VotingEscrowHostRef,
+        controller: GaugeControllerHostRef,
+    }
+
+    /// A controller with a farming pool gauge (0) and an LP pair gauge (1)
+    fn setup() -> Gauges {
+        let env = odra_test::env();
+        env.set_caller(env.get_account(0));
+        let ecto = EctoToken::deploy(&env, NoArgs);
+        let ve = VotingEscrow::deploy(&env, VotingEscrowInitArgs {
+            token_address: ecto.address(),
+        });
+        let mut controller = GaugeController::deploy(&env, GaugeControllerInitArgs {
+            voting_escrow_address: ve.address(),
+        });
+        controller.add_gauge(GaugeType::FarmingPool, env.get_account(8), 0);
+        controller.add_gauge(GaugeType::LpPair, env.get_account(9), 0);
+        Gauges { env, ecto, ve, controller }
+    }
+
+    /// Lock ECTO losing `slope` veECTO per second until `end`
+    fn lock(g: &mut Gauges, user: Address, slope: u64, end: u64) {
+        let amount = U256::from(slope) * U256::from(MAX_LOCK_TIME);
+        g.env.set_caller(g.env.get_account(0));
+        g.ecto.mint(user, amount);
+        g.env.set_caller(user);
+        g.ecto.approve(g.ve.address(), amount);
+        g.ve.create_lock(amount, end);
+    }
+
+    #[test]
+    fn test_votes_split_weight_from_next_epoch() {
+        let mut g = setup();
+        let (first, second) = (g.env.get_account(1), g.env.get_account(2));
+        let next = (g.env.block_time() / WEEK + 1) * WEEK;
+        let end = next + 52 * WEEK;
+        lock(&mut g, first, 1_000, end);
+        lock(&mut g, second, 1_000, end);
+
+        g.env.set_caller(first);
+        g.controller.vote(0, 10_000);
+        g.env.set_caller(second);
+        g.controller.vote(0, 5_000);
+        g.controller.vote(1, 5_000);
+
+        // Nothing counts until the next epoch
+        assert_eq!(g.controller.get_total_weight(g.env.block_time()), U256::zero());
+
+        let one = U256::from(1_000_000_000_000_000_000u128);
+        assert_eq!(g.controller.get_gauge_weight(0, next), U256::from(1_500 * (end - next)));
+        assert_eq!(g.controller.gauge_relative_weight(0, next), one * 3 / 4);
+        assert_eq!(g.controller.gauge_relative_weight(1, next), one / 4);
+
+        // Weights decay with the locks and vanish once they expire
+        let later = next + 10 * WEEK;
+        assert_eq!(g.controller.get_gauge_weight(1, later), U256::from(500 * (end - later)));
+        assert_eq!(g.controller.gauge_relative_weight(0, later), one * 3 / 4);
+        assert_eq!(g.controller.get_total_weight(end), U256::zero());
+    }
+
+    #[test]
+    fn test_revote_replaces_previous_weight() {
+        let mut g = setup();
+        let user = g.env.get_account(1);
+        let next = (g.env.block_time() / WEEK + 1) * WEEK;
+        let end = next + 52 * WEEK;
+        lock(&mut g, user, 1_000, end);
+
+        g.controller.vote(0, 6_000);
+        assert_eq!(g.controller.try_vote(1, 5_000).unwrap_err(), GovernanceError::InvalidWeight.into());
+        assert_eq!(g.controller.try_vote(0, 2_000).unwrap_err(), GovernanceError::VoteTooSoon.into());
+
+        g.env.advance_block_time(VOTE_DELAY);
+        let next = (g.env.block_time() / WEEK + 1) * WEEK;
+        g.controller.vote(0, 2_000);
+        g.controller.vote(1, 8_000);
+        assert_eq!(g.controller.get_user_vote_power(user), 10_000);
+        assert_eq!(g.controller.get_gauge_weight(0, next), U256::from(200 * (end - next)));
+        assert_eq!(g.controller.get_total_weight(next), U256::from(1_000 * (end - next)));
+    }
+
+    #[test]
+    fn test_vote_requires_lock_and_gauge() {
+        let mut g = setup();
+        let user = g.env.get_account(1);
+        g.env.set_caller(user);
+        assert_eq!(g.controller.try_vote(0, 10_000).unwrap_err(), GovernanceError::NoLock.into());
+        assert_eq!(g.controller.try_vote(5, 10_000).unwrap_err(), GovernanceError::GaugeNotFound.into());
+        assert_eq!(
+            g.controller.try_add_gauge(GaugeType::LpPair, g.env.get_account(9), 0).unwrap_err(),
+            GovernanceError::Unauthorized.into()
+        );
+    }
+}
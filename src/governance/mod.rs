@@ -0,0 +1,32 @@
+//! Governance - vote-escrowed ECTO
+//! 
+//! Users lock ECTO in the `VotingEscrow` for up to four years and receive a
+//! non-transferable veECTO balance that decays linearly to zero at unlock.
+//! Other modules read veECTO balances at any timestamp to weigh votes,
+//! share fees and boost rewards.
+//!
+//! veECTO holders vote in the `GaugeController` on how emissions are split
+//! across farming pools and LP reward pairs. The `EmissionMinter` mints
+//! each week's decaying ECTO emission and splits it between farming (by
+//! gauge weight), LP rewards and the treasury. The `FeeDistributor` shares
+//! DEX protocol fees and lending reserves with veECTO holders each epoch.
+//!
+//! The `Treasury` holds the protocol's CEP-18 tokens and CSPR, allocates
+//! them to the gas rebate escrow, LP rewards pool and dev multisig, and
+//! pays contributors through linear streams.
+
+pub mod voting_escrow;
+pub mod gauge_controller;
+pub mod emission_minter;
+pub mod fee_distributor;
+pub mod treasury;
+pub mod errors;
+pub mod events;
+
+pub use voting_escrow::{VotingEscrow, LockedBalance, Point};
+pub use gauge_controller::{GaugeController, GaugeType, GaugeInfo};
+pub use emission_minter::EmissionMinter;
+pub use fee_distributor::FeeDistributor;
+pub use treasury::{Treasury, Stream, AllocationShares};
+pub use errors::GovernanceError;
+pub use events::*;
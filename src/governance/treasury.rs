@@ -0,0 +1,466 @@
+//! Treasury - protocol funds custody, allocations and streams
+//!
+//! Holds CEP-18 tokens and CSPR (written `None` where a token is expected):
+//! - Anyone can deposit; emissions and fees can be sent here directly
+//! - The admin allocates a token amount by configured shares to the
+//!   IncentiveManager's gas rebate escrow, the LpRewardsDistributor's rewards
+//!   pool and the dev multisig; the rest stays as reserves
+//! - The admin streams funds to recipients, who withdraw what has vested
+//!   linearly between the stream's start and end
+//!
+//! Funds committed to streams cannot be allocated or spent until the stream
+//! is withdrawn or canceled.
+
+use odra::prelude::*;
+use odra::casper_types::{U256, U512};
+use odra::ContractRef;
+use super::errors::GovernanceError;
+use super::events::*;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+use crate::incentives::incentive_manager::IncentiveManagerContractRef;
+use crate::incentives::lp_rewards_distributor::LpRewardsDistributorContractRef;
+use crate::math::BPS_DENOMINATOR;
+use crate::token::Cep18TokenContractRef;
+
+/// Shares of an allocation sent to each destination (bps, the rest stays)
+#[odra::odra_type]
+pub struct AllocationShares {
+    /// To the IncentiveManager's gas rebate escrow
+    pub gas_subsidy_bps: u32,
+    /// To the LpRewardsDistributor's rewards pool
+    pub lp_rewards_bps: u32,
+    /// To the dev multisig
+    pub development_bps: u32,
+}
+
+/// Linear payment stream
+#[odra::odra_type]
+pub struct Stream {
+    /// Account paid by the stream
+    pub recipient: Address,
+    /// Token streamed (None = CSPR)
+    pub token: Option<Address>,
+    /// Total amount streamed
+    pub amount: U256,
+    /// Vesting starts at this timestamp
+    pub start: u64,
+    /// Fully vested at this timestamp
+    pub end: u64,
+    /// Amount withdrawn so far
+    pub withdrawn: U256,
+    /// Whether the stream was canceled
+    pub canceled: bool,
+}
+
+/// Treasury contract
+#[odra::module]
+pub struct Treasury {
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Allocation shares
+    shares: Var<AllocationShares>,
+    /// IncentiveManager receiving the gas subsidy share
+    incentive_manager: Var<Option<Address>>,
+    /// LpRewardsDistributor receiving the LP rewards share
+    lp_rewards: Var<Option<Address>>,
+    /// Multisig receiving the development share
+    dev_multisig: Var<Option<Address>>,
+    /// Streams by ID
+    streams: Mapping<u32, Stream>,
+    /// Number of streams
+    stream_count: Var<u32>,
+    /// Funds owed to open streams per token (None = CSPR)
+    committed: Mapping<Option<Address>, U256>,
+}
+
+#[odra::module]
+impl Treasury {
+    /// Initialize the treasury
+    ///
+    /// Default shares follow the IncentiveManager's split: 40% gas subsidy,
+    /// 30% LP rewards, 20% development, 10% reserves.
+    pub fn init(&mut self) {
+        let caller = self.env().caller();
+        self.access.grant(ADMIN_ROLE, caller);
+        self.shares.set(AllocationShares {
+            gas_subsidy_bps: 4_000,
+            lp_rewards_bps: 3_000,
+            development_bps: 2_000,
+        });
+        self.stream_count.set(0);
+    }
+
+    /// Deposit CEP-18 tokens pulled from the caller
+    pub fn deposit(&mut self, token: Address, amount: U256) {
+        if amount == U256::zero() {
+            self.env().revert(GovernanceError::ZeroAmount);
+        }
+        let caller = self.env().caller();
+        Cep18TokenContractRef::new(self.env(), token).transfer_from(caller, self.env().self_address(), amount);
+
+        self.env().emit_event(TreasuryDeposited {
+            token: Some(token),
+            from: caller,
+            amount,
+        });
+    }
+
+    /// Deposit the attached CSPR
+    #[odra(payable)]
+    pub fn deposit_cspr(&mut self) {
+        let amount = U256::from(self.env().attached_value().as_u128());
+        if amount == U256::zero() {
+            self.env().revert(GovernanceError::ZeroAmount);
+        }
+
+        self.env().emit_event(TreasuryDeposited {
+            token: None,
+            from: self.env().caller(),
+            amount,
+        });
+    }
+
+    /// Split `amount` of `token` between the gas rebate escrow, LP rewards pool
+    /// and dev multisig by the allocation shares (admin only)
+    /// Destinations that are not configured keep their share in reserves.
+    pub fn allocate(&mut self, token: Address, amount: U256) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        self.ensure_available(Some(token), amount);
+
+        let shares = self.shares.get_or_revert_with(GovernanceError::InvalidShares);
+        let denominator = U256::from(BPS_DENOMINATOR);
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+
+        let mut gas_subsidy = U256::zero();
+        if let Some(manager) = self.incentive_manager.get_or_default() {
+            gas_subsidy = amount * U256::from(shares.gas_subsidy_bps) / denominator;
+            if gas_subsidy > U256::zero() {
+                token_ref.increase_allowance(manager, gas_subsidy);
+                IncentiveManagerContractRef::new(self.env(), manager).fund_gas_rebates(gas_subsidy);
+            }
+        }
+
+        let mut lp_rewards = U256::zero();
+        if let Some(distributor) = self.lp_rewards.get_or_default() {
+            lp_rewards = amount * U256::from(shares.lp_rewards_bps) / denominator;
+            if lp_rewards > U256::zero() {
+                token_ref.increase_allowance(distributor, lp_rewards);
+                LpRewardsDistributorContractRef::new(self.env(), distributor).add_rewards(lp_rewards);
+            }
+        }
+
+        let mut development = U256::zero();
+        if let Some(multisig) = self.dev_multisig.get_or_default() {
+            development = amount * U256::from(shares.development_bps) / denominator;
+            if development > U256::zero() {
+                token_ref.transfer(multisig, development);
+            }
+        }
+
+        self.env().emit_event(TreasuryAllocated {
+            token,
+            gas_subsidy,
+            lp_rewards,
+            development,
+            reserves: amount - gas_subsidy - lp_rewards - development,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Pay out funds not committed to streams (admin only)
+    pub fn spend(&mut self, token: Option<Address>, to: Address, amount: U256) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        self.ensure_available(token, amount);
+        self.send(token, to, amount);
+
+        self.env().emit_event(TreasurySpent { token, to, amount });
+    }
+
+    /// Stream `amount` to `recipient`, vesting linearly from `start` to `end` (admin only)
+    pub fn create_stream(
+        &mut self,
+        recipient: Address,
+        token: Option<Address>,
+        amount: U256,
+        start: u64,
+        end: u64,
+    ) -> u32 {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        if amount == U256::zero() {
+            self.env().revert(GovernanceError::ZeroAmount);
+        }
+        if end <= start || recipient == self.env().self_address() {
+            self.env().revert(GovernanceError::InvalidStream);
+        }
+        self.ensure_available(token, amount);
+
+        self.committed.set(&token, self.get_committed(token) + amount);
+        let stream_id = self.stream_count.get_or_default();
+        self.streams.set(&stream_id, Stream {
+            recipient,
+            token,
+            amount,
+            start,
+            end,
+            withdrawn: U256::zero(),
+            canceled: false,
+        });
+        self.stream_count.set(stream_id + 1);
+
+        self.env().emit_event(StreamCreated {
+            stream_id,
+            recipient,
+            token,
+            amount,
+            start,
+            end,
+        });
+
+        stream_id
+    }
+
+    /// Withdraw what has vested on a stream (recipient only)
+    pub fn withdraw_from_stream(&mut self, stream_id: u32) -> U256 {
+        let mut stream = self.get_open_stream(stream_id);
+        if self.env().caller() != stream.recipient {
+            self.env().revert(GovernanceError::Unauthorized);
+        }
+
+        let amount = self.vested(&stream) - stream.withdrawn;
+        if amount == U256::zero() {
+            self.env().revert(GovernanceError::ZeroAmount);
+        }
+        stream.withdrawn += amount;
+        let (recipient, token) = (stream.recipient, stream.token);
+        self.streams.set(&stream_id, stream);
+        self.committed.set(&token, self.get_committed(token) - amount);
+        self.send(token, recipient, amount);
+
+        self.env().emit_event(StreamWithdrawn {
+            stream_id,
+            recipient,
+            amount,
+        });
+
+        amount
+    }
+
+    /// Cancel a stream, paying the recipient what has vested (admin only)
+    /// The unvested rest is released back to the treasury.
+    pub fn cancel_stream(&mut self, stream_id: u32) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        let mut stream = self.get_open_stream(stream_id);
+
+        let vested = self.vested(&stream);
+        let recipient_amount = vested - stream.withdrawn;
+        let returned = stream.amount - vested;
+        stream.withdrawn = vested;
+        stream.canceled = true;
+        let (recipient, token) = (stream.recipient, stream.token);
+        self.streams.set(&stream_id, stream);
+
+        self.committed.set(&token, self.get_committed(token) - recipient_amount - returned);
+        if recipient_amount > U256::zero() {
+            self.send(token, recipient, recipient_amount);
+        }
+
+        self.env().emit_event(StreamCanceled {
+            stream_id,
+            recipient_amount,
+            returned,
+        });
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get the treasury's balance of a token (None = CSPR)
+    pub fn get_balance(&self, token: Option<Address>) -> U256 {
+        match token {
+            Some(token) => Cep18TokenContractRef::new(self.env(), token).balance_of(self.env().self_address()),
+            None => U256::from(self.env().self_balance().as_u128()),
+        }
+    }
+
+    /// Get the funds owed to open streams (None = CSPR)
+    pub fn get_committed(&self, token: Option<Address>) -> U256 {
+        self.committed.get(&token).unwrap_or_default()
+    }
+
+    /// Get the funds that can be allocated, spent or streamed (None = CSPR)
+    pub fn get_available(&self, token: Option<Address>) -> U256 {
+        self.get_balance(token).saturating_sub(self.get_committed(token))
+    }
+
+    /// Get a stream
+    pub fn get_stream(&self, stream_id: u32) -> Option<Stream> {
+        self.streams.get(&stream_id)
+    }
+
+    /// Get the number of streams
+    pub fn get_stream_count(&self) -> u32 {
+        self.stream_count.get_or_default()
+    }
+
+    /// Get the amount a stream's recipient can withdraw now
+    pub fn get_withdrawable(&self, stream_id: u32) -> U256 {
+        match self.streams.get(&stream_id) {
+            Some(stream) if !stream.canceled => self.vested(&stream) - stream.withdrawn,
+            _ => U256::zero(),
+        }
+    }
+
+    /// Get the allocation shares
+    pub fn get_shares(&self) -> AllocationShares {
+        self.shares.get_or_revert_with(GovernanceError::InvalidShares)
+    }
+
+    /// Get the (IncentiveManager, LpRewardsDistributor, dev multisig) allocation destinations
+    pub fn get_destinations(&self) -> (Option<Address>, Option<Address>, Option<Address>) {
+        (
+            self.incentive_manager.get_or_default(),
+            self.lp_rewards.get_or_default(),
+            self.dev_multisig.get_or_default(),
+        )
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Set the allocation shares; together at most 100% (admin only)
+    pub fn set_shares(&mut self, shares: AllocationShares) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        let total = shares.gas_subsidy_bps as u128 + shares.lp_rewards_bps as u128 + shares.development_bps as u128;
+        if total > BPS_DENOMINATOR {
+            self.env().revert(GovernanceError::InvalidShares);
+        }
+        self.shares.set(shares);
+    }
+
+    /// Set the allocation destinations; None keeps that share in reserves (admin only)
+    pub fn set_destinations(
+        &mut self,
+        incentive_manager: Option<Address>,
+        lp_rewards: Option<Address>,
+        dev_multisig: Option<Address>,
+    ) {
+        self.access.require_role(ADMIN_ROLE, GovernanceError::Unauthorized);
+        self.incentive_manager.set(incentive_manager);
+        self.lp_rewards.set(lp_rewards);
+        self.dev_multisig.set(dev_multisig);
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn ensure_available(&self, token: Option<Address>, amount: U256) {
+        if amount == U256::zero() {
+            self.env().revert(GovernanceError::ZeroAmount);
+        }
+        if amount > self.get_available(token) {
+            self.env().revert(GovernanceError::InsufficientTreasuryFunds);
+        }
+    }
+
+    fn get_open_stream(&self, stream_id: u32) -> Stream {
+        match self.streams.get(&stream_id) {
+            Some(stream) if !stream.canceled => stream,
+            _ => self.env().revert(GovernanceError::StreamNotFound),
+        }
+    }
+
+    /// Amount of a stream vested at the current time
+    fn vested(&self, stream: &Stream) -> U256 {
+        let now = self.env().get_block_time();
+        if now <= stream.start {
+            U256::zero()
+        } else if now >= stream.end {
+            stream.amount
+        } else {
+            stream.amount * U256::from(now - stream.start) / U256::from(stream.end - stream.start)
+        }
+    }
+
+    fn send(&self, token: Option<Address>, to: Address, amount: U256) {
+        match token {
+            Some(token) => {
+                Cep18TokenContractRef::new(self.env(), token).transfer(to, amount);
+            }
+            None => self.env().transfer_tokens(&to, &U512::from(amount.as_u128())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::incentives::incentive_manager::{IncentiveManager, IncentiveManagerInitArgs};
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    #[test]
+    fn test_allocations_and_streams() {
+        let env = odra_test::env();
+        let (admin, dev, contributor) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut manager = IncentiveManager::deploy(&env, IncentiveManagerInitArgs {
+            gas_discount_manager_address: env.get_account(10),
+            rewards_distributor_address: env.get_account(11),
+            treasury_address: env.get_account(12),
+        });
+        manager.set_rebate_token(ecto.address());
+        let mut treasury = Treasury::deploy(&env, NoArgs);
+        treasury.set_destinations(Some(manager.address()), None, Some(dev));
+
+        ecto.mint(admin, U256::from(10_000));
+        ecto.approve(treasury.address(), U256::from(10_000));
+        treasury.deposit(ecto.address(), U256::from(10_000));
+
+        // 40% to the gas rebate escrow, 20% to dev; no LP rewards pool set so 30% + 10% stay
+        treasury.allocate(ecto.address(), U256::from(1_000));
+        assert_eq!(manager.get_rebate_pool(), U256::from(400));
+        assert_eq!(ecto.balance_of(dev), U256::from(200));
+        assert_eq!(treasury.get_available(Some(ecto.address())), U256::from(9_400));
+
+        // Stream 1,000 over 1,000 seconds; the committed funds cannot be spent
+        let start = env.block_time();
+        let stream_id = treasury.create_stream(contributor, Some(ecto.address()), U256::from(1_000), start, start + 1_000);
+        assert_eq!(treasury.get_available(Some(ecto.address())), U256::from(8_400));
+        assert_eq!(
+            treasury.try_spend(Some(ecto.address()), dev, U256::from(8_401)).unwrap_err(),
+            GovernanceError::InsufficientTreasuryFunds.into()
+        );
+
+        env.advance_block_time(250);
+        env.set_caller(contributor);
+        assert_eq!(treasury.withdraw_from_stream(stream_id), U256::from(250));
+        assert_eq!(ecto.balance_of(contributor), U256::from(250));
+
+        // Canceling pays what vested since and releases the rest
+        env.advance_block_time(250);
+        env.set_caller(admin);
+        treasury.cancel_stream(stream_id);
+        assert_eq!(ecto.balance_of(contributor), U256::from(500));
+        assert_eq!(treasury.get_committed(Some(ecto.address())), U256::zero());
+        assert_eq!(treasury.get_available(Some(ecto.address())), U256::from(8_900));
+        env.set_caller(contributor);
+        assert_eq!(
+            treasury.try_withdraw_from_stream(stream_id).unwrap_err(),
+            GovernanceError::StreamNotFound.into()
+        );
+    }
+}
@@ -0,0 +1,870 @@
+//! Voting Escrow - lock ECTO for time-decaying veECTO
+//!
+//! Locking `amount` ECTO until `unlock_time` gives a veECTO balance of
+//! `amount * (unlock_time - now) / MAX_LOCK_TIME`, decaying linearly to zero
+//! at unlock. Unlock times are rounded down to whole weeks, so every lock
+//! expires on a week boundary.
+//!
+//! Every lock change checkpoints the user's balance line and the total
+//! supply line, so both can be read at any past or future timestamp with
+//! `balance_of_at` and `total_supply_at`. The slope each lock sheds at its
+//! expiry is scheduled per week, letting the supply line be walked forward
+//! without touching individual locks.
+//!
+//! A lock votes for its owner unless the owner delegates it. Each account
+//! keeps a checkpointed vote line, the sum of the balance lines delegated to
+//! it, updated whenever a delegated lock or a delegation changes; like the
+//! supply line it sheds slope as locks expire. `get_past_votes` reads it
+//! without visiting the delegators.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::GovernanceError;
+use super::events::*;
+use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE, PAUSER_ROLE};
+
+/// One week in seconds; every unlock time is a multiple of it
+pub const WEEK: u64 = 7 * 86_400;
+
+/// Longest lock (4 years)
+pub const MAX_LOCK_TIME: u64 = 4 * 365 * 86_400;
+
+/// Most weeks walked by a single checkpoint or supply query
+const MAX_WEEKS: u32 = 255;
+
+/// ECTO locked by an account
+#[odra::odra_type]
+pub struct LockedBalance {
+    /// ECTO locked
+    pub amount: U256,
+    /// Unlock timestamp (0 if nothing is locked)
+    pub end: u64,
+}
+
+/// A veECTO balance line, worth `bias - slope * (t - ts)` at time `t`
+#[odra::odra_type]
+pub struct Point {
+    /// veECTO at `ts`
+    pub bias: U256,
+    /// veECTO lost per second
+    pub slope: U256,
+    /// Checkpoint timestamp
+    pub ts: u64,
+}
+
+impl Point {
+    /// Value of the line at `t` (no earlier than `ts`), floored at zero
+    fn value_at(&self, t: u64) -> U256 {
+        let decay = self.slope * U256::from(t.saturating_sub(self.ts));
+        if decay >= self.bias {
+            U256::zero()
+        } else {
+            self.bias - decay
+        }
+    }
+}
+
+/// Delegate chosen by an account from `ts` on
+#[odra::odra_type]
+pub struct DelegationCheckpoint {
+    pub delegatee: Address,
+    pub ts: u64,
+}
+
+/// Voting Escrow contract
+#[odra::module]
+pub struct VotingEscrow {
+    /// ECTO token address
+    token: Var<Address>,
+    /// Lock of each account
+    locked: Mapping<Address, LockedBalance>,
+    /// Total ECTO locked
+    total_locked: Var<U256>,
+    /// Latest supply checkpoint
+    epoch: Var<u32>,
+    /// Supply line at each checkpoint
+    point_history: Mapping<u32, Point>,
+    /// Latest checkpoint of each account
+    user_point_epoch: Mapping<Address, u32>,
+    /// Balance line of each account at each of its checkpoints: (user, epoch) -> Point
+    user_point_history: Mapping<(Address, u32), Point>,
+    /// Slope shed by locks expiring at each week boundary
+    slope_changes: Mapping<u64, U256>,
+    /// Delegation history of each account: (user, index) -> checkpoint
+    delegation_history: Mapping<(Address, u32), DelegationCheckpoint>,
+    /// Number of delegation checkpoints of each account
+    delegation_count: Mapping<Address, u32>,
+    /// Latest vote checkpoint of each delegatee
+    vote_point_epoch: Mapping<Address, u32>,
+    /// Vote line of each delegatee at each of its checkpoints: (delegatee, epoch) -> Point
+    vote_point_history: Mapping<(Address, u32), Point>,
+    /// Slope each delegatee's vote line sheds at each week boundary
+    vote_slope_changes: Mapping<(Address, u64), U256>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Paused state
+    paused: Var<bool>,
+}
+
+#[odra::module]
+impl VotingEscrow {
+    /// Initialize the voting escrow
+    pub fn init(&mut self, token_address: Address) {
+        let caller = self.env().caller();
+        self.token.set(token_address);
+        self.epoch.set(0);
+        self.point_history.set(&0, Point {
+            bias: U256::zero(),
+            slope: U256::zero(),
+            ts: self.env().get_block_time(),
+        });
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(PAUSER_ROLE, caller);
+        self.paused.set(false);
+    }
+
+    // ========================================
+    // Locking
+    // ========================================
+
+    /// Lock `amount` ECTO until `unlock_time` (rounded down to a whole week)
+    pub fn create_lock(&mut self, amount: U256, unlock_time: u64) {
+        self.ensure_not_paused();
+
+        if amount == U256::zero() {
+            self.env().revert(GovernanceError::ZeroAmount);
+        }
+
+        let caller = self.env().caller();
+        let old = self.lock_of(caller);
+        if old.amount > U256::zero() {
+            self.env().revert(GovernanceError::LockExists);
+        }
+
+        let end = self.checked_unlock_time(unlock_time);
+        self.update_lock(caller, old, LockedBalance { amount, end });
+    }
+
+    /// Add `amount` ECTO to the caller's lock without changing its unlock time
+    pub fn increase_amount(&mut self, amount: U256) {
+        self.ensure_not_paused();
+
+        if amount == U256::zero() {
+            self.env().revert(GovernanceError::ZeroAmount);
+        }
+
+        let caller = self.env().caller();
+        let old = self.active_lock_of(caller);
+        let new = LockedBalance {
+            amount: old.amount + amount,
+            end: old.end,
+        };
+        self.update_lock(caller, old, new);
+    }
+
+    /// Extend the caller's lock to `unlock_time` (rounded down to a whole week)
+    pub fn increase_unlock_time(&mut self, unlock_time: u64) {
+        self.ensure_not_paused();
+
+        let caller = self.env().caller();
+        let old = self.active_lock_of(caller);
+        let end = self.checked_unlock_time(unlock_time);
+        if end <= old.end {
+            self.env().revert(GovernanceError::InvalidUnlockTime);
+        }
+
+        let new = LockedBalance {
+            amount: old.amount,
+            end,
+        };
+        self.update_lock(caller, old, new);
+    }
+
+    /// Withdraw the caller's ECTO once its lock has expired
+    pub fn withdraw(&mut self) {
+        let caller = self.env().caller();
+        let old = self.lock_of(caller);
+        if old.amount == U256::zero() {
+            self.env().revert(GovernanceError::NoLock);
+        }
+        let now = self.env().get_block_time();
+        if now < old.end {
+            self.env().revert(GovernanceError::LockNotExpired);
+        }
+
+        let amount = old.amount;
+        let empty = LockedBalance {
+            amount: U256::zero(),
+            end: 0,
+        };
+        self.locked.set(&caller, empty.clone());
+        let previous_supply = self.total_locked.get_or_default();
+        self.total_locked.set(previous_supply - amount);
+        self.checkpoint_lock(caller, &old, &empty);
+
+        let token_address = self.token.get_or_revert_with(GovernanceError::Unauthorized);
+        let mut token = Cep18TokenContractRef::new(self.env(), token_address);
+        token.transfer(caller, amount);
+
+        self.env().emit_event(LockWithdrawn {
+            user: caller,
+            amount,
+            timestamp: now,
+        });
+        self.env().emit_event(SupplyUpdated {
+            previous_supply,
+            supply: previous_supply - amount,
+        });
+    }
+
+    /// Delegate the caller's veECTO votes to `delegatee` (the caller itself to take them back)
+    pub fn delegate(&mut self, delegatee: Address) {
+        let delegator = self.env().caller();
+        let ts = self.env().get_block_time();
+        let previous = self.delegate_at(delegator, ts);
+        if previous == delegatee {
+            return;
+        }
+
+        // Several delegations within one block time share a checkpoint
+        let count = self.delegation_count.get(&delegator).unwrap_or_default();
+        let last_ts = count.checked_sub(1)
+            .and_then(|i| self.delegation_history.get(&(delegator, i)))
+            .map(|checkpoint| checkpoint.ts);
+        let index = if last_ts == Some(ts) {
+            count - 1
+        } else {
+            self.delegation_count.set(&delegator, count + 1);
+            count
+        };
+        self.delegation_history.set(&(delegator, index), DelegationCheckpoint { delegatee, ts });
+
+        let lock = self.lock_of(delegator);
+        let line = Self::lock_line(&lock, ts);
+        self.move_vote_line(Some(previous), Some(delegatee), &line, lock.end);
+
+        self.env().emit_event(VotesDelegated {
+            delegator,
+            from_delegate: previous,
+            to_delegate: delegatee,
+            timestamp: ts,
+        });
+    }
+
+    /// Record the supply line up to now (anyone)
+    ///
+    /// Every lock change does this too; calling it keeps supply queries
+    /// cheap after long periods without lock activity.
+    pub fn checkpoint(&mut self) {
+        self.checkpoint_supply();
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get `user`'s veECTO balance now
+    pub fn balance_of(&self, user: Address) -> U256 {
+        self.balance_of_at(user, self.env().get_block_time())
+    }
+
+    /// Get `user`'s veECTO balance at `timestamp`
+    ///
+    /// Future timestamps assume the lock is left unchanged.
+    pub fn balance_of_at(&self, user: Address, timestamp: u64) -> U256 {
+        let latest = self.user_point_epoch.get(&user).unwrap_or_default();
+
+        // Latest of the user's checkpoints at or before `timestamp` (epochs start at 1)
+        let (mut low, mut high) = (0u32, latest);
+        while low < high {
+            let mid = (low + high + 1) / 2;
+            if self.user_point(user, mid).ts <= timestamp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        if low == 0 {
+            return U256::zero();
+        }
+        self.user_point(user, low).value_at(timestamp)
+    }
+
+    /// Get the account `user` delegates its votes to (itself by default)
+    pub fn delegates(&self, user: Address) -> Address {
+        self.delegate_at(user, self.env().get_block_time())
+    }
+
+    /// Get the veECTO votes of `account` now
+    pub fn get_votes(&self, account: Address) -> U256 {
+        self.get_past_votes(account, self.env().get_block_time())
+    }
+
+    /// Get the veECTO votes of `account` at `timestamp`
+    ///
+    /// Its own balance unless delegated away, plus the balances of every
+    /// account delegating to it at that time.
+    pub fn get_past_votes(&self, account: Address, timestamp: u64) -> U256 {
+        self.vote_line_at(account, timestamp).bias
+    }
+
+    /// Get the total veECTO supply now
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply_at(self.env().get_block_time())
+    }
+
+    /// Get the total veECTO supply at `timestamp`
+    ///
+    /// Future timestamps assume no locks are created or changed.
+    pub fn total_supply_at(&self, timestamp: u64) -> U256 {
+        let latest = self.epoch.get_or_default();
+
+        // Latest supply checkpoint at or before `timestamp`
+        let (mut low, mut high) = (0u32, latest);
+        while low < high {
+            let mid = (low + high + 1) / 2;
+            if self.supply_point(mid).ts <= timestamp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        let point = self.supply_point(low);
+        if point.ts > timestamp {
+            return U256::zero();
+        }
+        self.supply_line_at(point, timestamp)
+    }
+
+    /// Get `user`'s lock
+    pub fn get_locked(&self, user: Address) -> LockedBalance {
+        self.lock_of(user)
+    }
+
+    /// Get the total ECTO locked
+    pub fn get_total_locked(&self) -> U256 {
+        self.total_locked.get_or_default()
+    }
+
+    /// Get the ECTO token address
+    pub fn get_token(&self) -> Option<Address> {
+        self.token.get()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    pub fn pause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, GovernanceError::Unauthorized);
+        self.paused.set(true);
+    }
+
+    pub fn unpause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, GovernanceError::Unauthorized);
+        self.paused.set(false);
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// Replace `user`'s lock, pulling any added ECTO from them
+    fn update_lock(&mut self, user: Address, old: LockedBalance, new: LockedBalance) {
+        let added = new.amount - old.amount;
+        let previous_supply = self.total_locked.get_or_default();
+
+        self.locked.set(&user, new.clone());
+        self.total_locked.set(previous_supply + added);
+        self.checkpoint_lock(user, &old, &new);
+
+        if added > U256::zero() {
+            let token_address = self.token.get_or_revert_with(GovernanceError::Unauthorized);
+            let mut token = Cep18TokenContractRef::new(self.env(), token_address);
+            token.transfer_from(user, Address::from(self.env().self_address()), added);
+        }
+
+        self.env().emit_event(LockUpdated {
+            user,
+            amount: added,
+            locked: new.amount,
+            unlock_time: new.end,
+            timestamp: self.env().get_block_time(),
+        });
+        if added > U256::zero() {
+            self.env().emit_event(SupplyUpdated {
+                previous_supply,
+                supply: previous_supply + added,
+            });
+        }
+    }
+
+    /// Swap `user`'s balance line for the one of `new` in the supply line
+    fn checkpoint_lock(&mut self, user: Address, old: &LockedBalance, new: &LockedBalance) {
+        let now = self.env().get_block_time();
+        let old_line = Self::lock_line(old, now);
+        let new_line = Self::lock_line(new, now);
+
+        let (epoch, mut last) = self.checkpoint_supply();
+        last.slope = (last.slope + new_line.slope).saturating_sub(old_line.slope);
+        last.bias = (last.bias + new_line.bias).saturating_sub(old_line.bias);
+        self.point_history.set(&epoch, last);
+
+        // Reschedule the slope the lock sheds at expiry
+        if old.end > now {
+            let scheduled = self.slope_changes.get(&old.end).unwrap_or_default();
+            self.slope_changes.set(&old.end, scheduled.saturating_sub(old_line.slope));
+        }
+        if new.end > now {
+            let scheduled = self.slope_changes.get(&new.end).unwrap_or_default();
+            self.slope_changes.set(&new.end, scheduled + new_line.slope);
+        }
+
+        // The lock's votes go to whoever it is delegated to
+        let delegatee = self.delegate_at(user, now);
+        self.move_vote_line(Some(delegatee), None, &old_line, old.end);
+        self.move_vote_line(None, Some(delegatee), &new_line, new.end);
+
+        let user_epoch = self.user_point_epoch.get(&user).unwrap_or_default() + 1;
+        self.user_point_epoch.set(&user, user_epoch);
+        self.user_point_history.set(&(user, user_epoch), new_line);
+    }
+
+    /// Move a lock's balance line, expiring at `end`, between vote lines
+    fn move_vote_line(&mut self, from: Option<Address>, to: Option<Address>, line: &Point, end: u64) {
+        if from == to || line.bias.is_zero() {
+            return;
+        }
+        let now = self.env().get_block_time();
+        if let Some(delegatee) = from {
+            let mut point = self.vote_line_at(delegatee, now);
+            point.bias = point.bias.saturating_sub(line.bias);
+            point.slope = point.slope.saturating_sub(line.slope);
+            self.write_vote_point(delegatee, point);
+            let scheduled = self.vote_slope_changes.get(&(delegatee, end)).unwrap_or_default();
+            self.vote_slope_changes.set(&(delegatee, end), scheduled.saturating_sub(line.slope));
+        }
+        if let Some(delegatee) = to {
+            let mut point = self.vote_line_at(delegatee, now);
+            point.bias += line.bias;
+            point.slope += line.slope;
+            self.write_vote_point(delegatee, point);
+            let scheduled = self.vote_slope_changes.get(&(delegatee, end)).unwrap_or_default();
+            self.vote_slope_changes.set(&(delegatee, end), scheduled + line.slope);
+        }
+    }
+
+    /// Record `delegatee`'s vote line from now on
+    /// Several changes within one block time share a checkpoint
+    fn write_vote_point(&mut self, delegatee: Address, mut point: Point) {
+        let now = self.env().get_block_time();
+        point.ts = now;
+        let latest = self.vote_point_epoch.get(&delegatee).unwrap_or_default();
+        let epoch = if latest > 0 && self.vote_point(delegatee, latest).ts == now {
+            latest
+        } else {
+            self.vote_point_epoch.set(&delegatee, latest + 1);
+            latest + 1
+        };
+        self.vote_point_history.set(&(delegatee, epoch), point);
+    }
+
+    /// Vote line of `delegatee` at `timestamp`, applying scheduled slope changes
+    fn vote_line_at(&self, delegatee: Address, timestamp: u64) -> Point {
+        let latest = self.vote_point_epoch.get(&delegatee).unwrap_or_default();
+
+        // Latest of the delegatee's checkpoints at or before `timestamp` (epochs start at 1)
+        let (mut low, mut high) = (0u32, latest);
+        while low < high {
+            let mid = (low + high + 1) / 2;
+            if self.vote_point(delegatee, mid).ts <= timestamp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        if low == 0 {
+            return Point {
+                bias: U256::zero(),
+                slope: U256::zero(),
+                ts: timestamp,
+            };
+        }
+
+        let mut point = self.vote_point(delegatee, low);
+        let mut t = point.ts / WEEK * WEEK;
+        for _ in 0..MAX_WEEKS {
+            t += WEEK;
+            let mut slope_change = U256::zero();
+            if t > timestamp {
+                t = timestamp;
+            } else {
+                slope_change = self.vote_slope_changes.get(&(delegatee, t)).unwrap_or_default();
+            }
+
+            point.bias = point.value_at(t);
+            point.slope = point.slope.saturating_sub(slope_change);
+            point.ts = t;
+            if t == timestamp {
+                break;
+            }
+        }
+        point
+    }
+
+    /// Walk the supply line forward to now, recording a checkpoint per week
+    ///
+    /// Returns the latest epoch and its point.
+    fn checkpoint_supply(&mut self) -> (u32, Point) {
+        let now = self.env().get_block_time();
+        let mut epoch = self.epoch.get_or_default();
+        let mut last = self.supply_point(epoch);
+        if last.ts >= now {
+            return (epoch, last);
+        }
+
+        let mut t = last.ts / WEEK * WEEK;
+        for _ in 0..MAX_WEEKS {
+            t += WEEK;
+            let mut slope_change = U256::zero();
+            if t > now {
+                t = now;
+            } else {
+                slope_change = self.slope_changes.get(&t).unwrap_or_default();
+            }
+
+            last.bias = last.value_at(t);
+            last.slope = last.slope.saturating_sub(slope_change);
+            last.ts = t;
+            epoch += 1;
+            self.point_history.set(&epoch, last.clone());
+
+            if t == now {
+                break;
+            }
+        }
+
+        self.epoch.set(epoch);
+        (epoch, last)
+    }
+
+    /// Value of the supply line from `point` at `timestamp`, applying scheduled slope changes
+    fn supply_line_at(&self, mut point: Point, timestamp: u64) -> U256 {
+        let mut t = point.ts / WEEK * WEEK;
+        for _ in 0..MAX_WEEKS {
+            t += WEEK;
+            let mut slope_change = U256::zero();
+            if t > timestamp {
+                t = timestamp;
+            } else {
+                slope_change = self.slope_changes.get(&t).unwrap_or_default();
+            }
+
+            point.bias = point.value_at(t);
+            if t == timestamp {
+                break;
+            }
+            point.slope = point.slope.saturating_sub(slope_change);
+            point.ts = t;
+        }
+        point.bias
+    }
+
+    /// Balance line of `lock` from `now` (flat zero once expired)
+    fn lock_line(lock: &LockedBalance, now: u64) -> Point {
+        if lock.end > now && lock.amount > U256::zero() {
+            let slope = lock.amount / U256::from(MAX_LOCK_TIME);
+            Point {
+                bias: slope * U256::from(lock.end - now),
+                slope,
+                ts: now,
+            }
+        } else {
+            Point {
+                bias: U256::zero(),
+                slope: U256::zero(),
+                ts: now,
+            }
+        }
+    }
+
+    /// Round `unlock_time` down to a week, reverting unless it lies within the next `MAX_LOCK_TIME`
+    fn checked_unlock_time(&self, unlock_time: u64) -> u64 {
+        let now = self.env().get_block_time();
+        let end = unlock_time / WEEK * WEEK;
+        if end <= now || end > now + MAX_LOCK_TIME {
+            self.env().revert(GovernanceError::InvalidUnlockTime);
+        }
+        end
+    }
+
+    fn lock_of(&self, user: Address) -> LockedBalance {
+        self.locked.get(&user).unwrap_or(LockedBalance {
+            amount: U256::zero(),
+            end: 0,
+        })
+    }
+
+    /// `user`'s lock, reverting unless it holds ECTO and has not expired
+    fn active_lock_of(&self, user: Address) -> LockedBalance {
+        let lock = self.lock_of(user);
+        if lock.amount == U256::zero() {
+            self.env().revert(GovernanceError::NoLock);
+        }
+        if lock.end <= self.env().get_block_time() {
+            self.env().revert(GovernanceError::LockExpired);
+        }
+        lock
+    }
+
+    /// Delegate of `user` at `timestamp` (itself before any delegation)
+    fn delegate_at(&self, user: Address, timestamp: u64) -> Address {
+        let count = self.delegation_count.get(&user).unwrap_or_default();
+
+        // Number of the user's delegations at or before `timestamp`
+        let (mut low, mut high) = (0u32, count);
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.delegation_history.get(&(user, mid)).unwrap().ts <= timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        if low == 0 {
+            return user;
+        }
+        self.delegation_history.get(&(user, low - 1)).unwrap().delegatee
+    }
+
+    fn supply_point(&self, epoch: u32) -> Point {
+        self.point_history.get(&epoch).unwrap()
+    }
+
+    fn user_point(&self, user: Address, epoch: u32) -> Point {
+        self.user_point_history.get(&(user, epoch)).unwrap()
+    }
+
+    fn vote_point(&self, delegatee: Address, epoch: u32) -> Point {
+        self.vote_point_history.get(&(delegatee, epoch)).unwrap()
+    }
+
+    fn ensure_not_paused(&self) {
+        if self.paused.get_or_default() {
+            self.env().revert(GovernanceError::ContractPaused);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::{EctoToken, EctoTokenHostRef};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+
+    struct Escrow {
+        env: HostEnv,
+        ecto: EctoTokenHostRef,
+        ve: VotingEscrowHostRef,
+    }
+
+    fn deploy_escrow() -> Escrow {
+        let env = odra_test::env();
+        env.set_caller(env.get_account(0));
+        let ecto = EctoToken::deploy(&env, NoArgs);
+        let ve = VotingEscrow::deploy(&env, VotingEscrowInitArgs {
+            token_address: ecto.address(),
+        });
+        Escrow { env, ecto, ve }
+    }
+
+    /// Lock ECTO losing `slope` veECTO per second until `end`
+    fn lock(e: &mut Escrow, user: Address, slope: u64, end: u64) {
+        let amount = U256::from(slope) * U256::from(MAX_LOCK_TIME);
+        e.env.set_caller(e.env.get_account(0));
+        e.ecto.mint(user, amount);
+        e.env.set_caller(user);
+        e.ecto.approve(e.ve.address(), amount);
+        e.ve.create_lock(amount, end);
+    }
+
+    fn next_week(e: &Escrow) -> u64 {
+        (e.env.block_time() / WEEK + 1) * WEEK
+    }
+
+    #[test]
+    fn test_balance_decays_linearly_to_unlock() {
+        let mut e = deploy_escrow();
+        let user = e.env.get_account(1);
+        e.env.advance_block_time(WEEK / 3);
+        let start = e.env.block_time();
+        let end = next_week(&e) + 103 * WEEK;
+        lock(&mut e, user, 1_000, end + WEEK / 2);
+
+        assert_eq!(e.ve.get_locked(user).end, end);
+        assert_eq!(e.ve.balance_of(user), U256::from(1_000 * (end - start)));
+        assert_eq!(e.ve.total_supply(), e.ve.balance_of(user));
+
+        e.env.advance_block_time(10 * WEEK);
+        assert_eq!(e.ve.balance_of(user), U256::from(1_000 * (end - start - 10 * WEEK)));
+        assert_eq!(e.ve.balance_of_at(user, start), U256::from(1_000 * (end - start)));
+        assert_eq!(e.ve.balance_of_at(user, start - 1), U256::zero());
+
+        e.env.advance_block_time(end - e.env.block_time());
+        assert_eq!(e.ve.balance_of(user), U256::zero());
+        assert_eq!(e.ve.total_supply(), U256::zero());
+    }
+
+    #[test]
+    fn test_total_supply_matches_balances_at_any_time() {
+        let mut e = deploy_escrow();
+        let (first, second) = (e.env.get_account(1), e.env.get_account(2));
+        let start = e.env.block_time();
+        let first_end = next_week(&e) + 4 * WEEK;
+        lock(&mut e, first, 500, first_end);
+
+        e.env.advance_block_time(WEEK);
+        let second_end = next_week(&e) + 20 * WEEK;
+        lock(&mut e, second, 300, second_end);
+
+        e.env.advance_block_time(2 * WEEK);
+        e.env.set_caller(e.env.get_account(0));
+        e.ecto.mint(first, U256::from(MAX_LOCK_TIME) * U256::from(100));
+        e.env.set_caller(first);
+        e.ecto.approve(e.ve.address(), U256::from(MAX_LOCK_TIME) * U256::from(100));
+        e.ve.increase_amount(U256::from(MAX_LOCK_TIME) * U256::from(100));
+
+        let now = e.env.block_time();
+        for t in [start, start + WEEK, now - 1, now, first_end, first_end + 3 * WEEK, second_end] {
+            assert_eq!(
+                e.ve.total_supply_at(t),
+                e.ve.balance_of_at(first, t) + e.ve.balance_of_at(second, t),
+                "supply mismatch at {}",
+                t
+            );
+        }
+        assert!(e.ve.balance_of_at(first, first_end - 1) > U256::zero());
+        assert_eq!(e.ve.total_supply_at(second_end), U256::zero());
+    }
+
+    #[test]
+    fn test_lock_lifecycle() {
+        let mut e = deploy_escrow();
+        let user = e.env.get_account(1);
+        let end = next_week(&e) + WEEK;
+        let amount = U256::from(1_000) * U256::from(MAX_LOCK_TIME);
+
+        e.env.set_caller(user);
+        assert_eq!(
+            e.ve.try_create_lock(amount, e.env.block_time() + MAX_LOCK_TIME + 2 * WEEK).unwrap_err(),
+            GovernanceError::InvalidUnlockTime.into()
+        );
+        lock(&mut e, user, 1_000, end);
+        assert_eq!(
+            e.ve.try_create_lock(amount, end).unwrap_err(),
+            GovernanceError::LockExists.into()
+        );
+        assert_eq!(e.ve.try_withdraw().unwrap_err(), GovernanceError::LockNotExpired.into());
+
+        // Extending raises the balance
+        let before = e.ve.balance_of(user);
+        assert_eq!(
+            e.ve.try_increase_unlock_time(end).unwrap_err(),
+            GovernanceError::InvalidUnlockTime.into()
+        );
+        e.ve.increase_unlock_time(end + WEEK);
+        assert_eq!(e.ve.balance_of(user), before + U256::from(1_000 * WEEK));
+
+        e.env.advance_block_time(end + WEEK - e.env.block_time());
+        assert_eq!(
+            e.ve.try_increase_amount(U256::one()).unwrap_err(),
+            GovernanceError::LockExpired.into()
+        );
+        e.ve.withdraw();
+        assert_eq!(e.ecto.balance_of(user), amount);
+        assert_eq!(e.ve.get_total_locked(), U256::zero());
+        assert_eq!(e.ve.try_withdraw().unwrap_err(), GovernanceError::NoLock.into());
+    }
+
+    #[test]
+    fn test_delegated_votes_at_past_timestamps() {
+        let mut e = deploy_escrow();
+        let (alice, bob) = (e.env.get_account(1), e.env.get_account(2));
+        let end = next_week(&e) + 10 * WEEK;
+        lock(&mut e, alice, 1_000, end);
+        lock(&mut e, bob, 500, end);
+        let before = e.env.block_time();
+        assert_eq!(e.ve.get_votes(alice), e.ve.balance_of(alice));
+
+        e.env.advance_block_time(WEEK);
+        let delegated_at = e.env.block_time();
+        e.env.set_caller(alice);
+        e.ve.delegate(bob);
+        assert_eq!(e.ve.delegates(alice), bob);
+        assert_eq!(e.ve.get_votes(alice), U256::zero());
+        assert_eq!(e.ve.get_votes(bob), e.ve.balance_of(alice) + e.ve.balance_of(bob));
+
+        e.env.advance_block_time(WEEK);
+        e.ve.delegate(alice);
+        assert_eq!(e.ve.get_votes(alice), e.ve.balance_of(alice));
+        assert_eq!(e.ve.get_votes(bob), e.ve.balance_of(bob));
+
+        // Past reads still see the delegation that was active then
+        assert_eq!(e.ve.get_past_votes(alice, before), e.ve.balance_of_at(alice, before));
+        assert_eq!(e.ve.get_past_votes(alice, delegated_at), U256::zero());
+        assert_eq!(
+            e.ve.get_past_votes(bob, delegated_at),
+            e.ve.balance_of_at(alice, delegated_at) + e.ve.balance_of_at(bob, delegated_at)
+        );
+    }
+
+    #[test]
+    fn test_delegated_votes_follow_lock_changes_and_expiry() {
+        let mut e = deploy_escrow();
+        let (alice, bob) = (e.env.get_account(1), e.env.get_account(2));
+        let alice_end = next_week(&e) + 4 * WEEK;
+        let bob_end = next_week(&e) + 20 * WEEK;
+        lock(&mut e, alice, 1_000, alice_end);
+        lock(&mut e, bob, 500, bob_end);
+
+        e.env.set_caller(alice);
+        e.ve.delegate(bob);
+
+        // Adding to a delegated lock adds to the delegatee's votes
+        e.env.advance_block_time(WEEK);
+        let extra = U256::from(1_000) * U256::from(MAX_LOCK_TIME);
+        e.env.set_caller(e.env.get_account(0));
+        e.ecto.mint(alice, extra);
+        e.env.set_caller(alice);
+        e.ecto.approve(e.ve.address(), extra);
+        e.ve.increase_amount(extra);
+        assert_eq!(e.ve.get_votes(bob), e.ve.balance_of(alice) + e.ve.balance_of(bob));
+        assert_eq!(e.ve.get_votes(alice), U256::zero());
+
+        // Once alice's lock expires only bob's balance is left
+        e.env.advance_block_time(alice_end + WEEK - e.env.block_time());
+        assert_eq!(e.ve.get_votes(bob), e.ve.balance_of(bob));
+        for t in [alice_end - 1, alice_end, bob_end - 1] {
+            assert_eq!(
+                e.ve.get_past_votes(bob, t),
+                e.ve.balance_of_at(alice, t) + e.ve.balance_of_at(bob, t),
+                "votes mismatch at {}",
+                t
+            );
+        }
+    }
+}
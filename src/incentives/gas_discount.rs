@@ -1,19 +1,26 @@
 //! Gas Discount Manager
 //! 
-//! Provides tiered gas discounts based on native token holdings (sCSPR and aECTO).
+//! Provides tiered gas discounts based on native token holdings (sCSPR and aECTO)
+//! and 30-day trailing DEX volume on TradeMining-weighted pairs.
 //! Leverages Casper 2.0's fee elimination features when available.
 //! 
 //! Discount Tiers:
 //! - Tier 0: No holdings → 0% discount
-//! - Tier 1: 100+ sCSPR or 1,000+ aECTO → 10% discount
-//! - Tier 2: 500+ sCSPR or 5,000+ aECTO → 25% discount
-//! - Tier 3: 2,000+ sCSPR or 20,000+ aECTO → 40% discount
-//! - Tier 4: 10,000+ sCSPR or 100,000+ aECTO → 60% discount
+//! - Tier 1: 100+ sCSPR or 1,000+ aECTO or 10,000+ volume → 10% discount
+//! - Tier 2: 500+ sCSPR or 5,000+ aECTO or 50,000+ volume → 25% discount
+//! - Tier 3: 2,000+ sCSPR or 20,000+ aECTO or 250,000+ volume → 40% discount
+//! - Tier 4: 10,000+ sCSPR or 100,000+ aECTO or 1,000,000+ volume → 60% discount
 
 use odra::prelude::*;
 use odra::casper_types::U256;
 use odra::ContractRef;
+use super::trade_mining::TradeMiningContractRef;
 use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+use crate::math::bps_of;
+
+/// Trailing window of DEX volume counted towards tiers (days)
+pub const VOLUME_WINDOW_DAYS: u64 = 30;
 
 /// Gas discount tier
 #[odra::odra_type]
@@ -26,6 +33,8 @@ pub struct DiscountTier {
     pub min_scspr: U256,
     /// Minimum aECTO balance required (alternative)
     pub min_aecto: U256,
+    /// Minimum 30-day DEX volume required (alternative, 0 = not considered)
+    pub min_volume: U256,
 }
 
 /// User's discount information
@@ -50,8 +59,8 @@ pub struct GasDiscountManager {
     aecto_token: Var<Address>,
     /// Treasury address for gas subsidies
     treasury: Var<Address>,
-    /// Admin address
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     /// Discount tiers (tier_level -> DiscountTier)
     tiers: Mapping<u8, DiscountTier>,
     /// User discount cache (user -> UserDiscount)
@@ -62,6 +71,8 @@ pub struct GasDiscountManager {
     total_subsidized: Var<U256>,
     /// Cache validity period (seconds)
     cache_validity: Var<u64>,
+    /// TradeMining contract trailing DEX volume is read from
+    trade_mining: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -78,7 +89,7 @@ impl GasDiscountManager {
         self.scspr_token.set(scspr_token_address);
         self.aecto_token.set(aecto_token_address);
         self.treasury.set(treasury_address);
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
         self.enabled.set(true);
         self.total_subsidized.set(U256::zero());
         self.cache_validity.set(300); // 5 minutes default
@@ -95,47 +106,52 @@ impl GasDiscountManager {
             discount_percent: 0,
             min_scspr: U256::zero(),
             min_aecto: U256::zero(),
+            min_volume: U256::zero(),
         });
         
         // Tier 1: 10% discount
-        // 100 sCSPR (9 decimals) or 1,000 aECTO (18 decimals)
+        // 100 sCSPR (9 decimals) or 1,000 aECTO (18 decimals) or 10,000 volume (18 decimals)
         self.tiers.set(&1, DiscountTier {
             tier: 1,
             discount_percent: 10,
             min_scspr: U256::from(100) * U256::from(10u128.pow(9)),
             min_aecto: U256::from(1000) * U256::from(10u128.pow(18)),
+            min_volume: U256::from(10000) * U256::from(10u128.pow(18)),
         });
         
         // Tier 2: 25% discount
-        // 500 sCSPR or 5,000 aECTO
+        // 500 sCSPR or 5,000 aECTO or 50,000 volume
         self.tiers.set(&2, DiscountTier {
             tier: 2,
             discount_percent: 25,
             min_scspr: U256::from(500) * U256::from(10u128.pow(9)),
             min_aecto: U256::from(5000) * U256::from(10u128.pow(18)),
+            min_volume: U256::from(50000) * U256::from(10u128.pow(18)),
         });
         
         // Tier 3: 40% discount
-        // 2,000 sCSPR or 20,000 aECTO
+        // 2,000 sCSPR or 20,000 aECTO or 250,000 volume
         self.tiers.set(&3, DiscountTier {
             tier: 3,
             discount_percent: 40,
             min_scspr: U256::from(2000) * U256::from(10u128.pow(9)),
             min_aecto: U256::from(20000) * U256::from(10u128.pow(18)),
+            min_volume: U256::from(250000) * U256::from(10u128.pow(18)),
         });
         
         // Tier 4: 60% discount
-        // 10,000 sCSPR or 100,000 aECTO
+        // 10,000 sCSPR or 100,000 aECTO or 1,000,000 volume
         self.tiers.set(&4, DiscountTier {
             tier: 4,
             discount_percent: 60,
             min_scspr: U256::from(10000) * U256::from(10u128.pow(9)),
             min_aecto: U256::from(100000) * U256::from(10u128.pow(18)),
+            min_volume: U256::from(1000000) * U256::from(10u128.pow(18)),
         });
     }
     
     /// Get the discount tier for a user
-    /// Checks both sCSPR and aECTO balances and returns the highest tier
+    /// Checks sCSPR and aECTO balances and trailing volume and returns the highest tier
     pub fn get_user_tier(&mut self, user: Address) -> u8 {
         if !self.enabled.get_or_default() {
             return 0;
@@ -151,26 +167,25 @@ impl GasDiscountManager {
             }
         }
         
-        // Get user balances
-        let scspr_balance = self.get_scspr_balance(user);
-        let aecto_balance = self.get_aecto_balance(user);
-        
-        // Determine tier (check from highest to lowest)
-        let tier = if scspr_balance >= self.tiers.get(&4).unwrap().min_scspr 
-                      || aecto_balance >= self.tiers.get(&4).unwrap().min_aecto {
-            4
-        } else if scspr_balance >= self.tiers.get(&3).unwrap().min_scspr 
-                  || aecto_balance >= self.tiers.get(&3).unwrap().min_aecto {
-            3
-        } else if scspr_balance >= self.tiers.get(&2).unwrap().min_scspr 
-                  || aecto_balance >= self.tiers.get(&2).unwrap().min_aecto {
-            2
-        } else if scspr_balance >= self.tiers.get(&1).unwrap().min_scspr 
-                  || aecto_balance >= self.tiers.get(&1).unwrap().min_aecto {
-            1
-        } else {
-            0
-        };
+        self.compute_user_tier(user)
+    }
+    
+    /// Recalculate a user's tier from current balances, bypassing the cache
+    /// Called when a user's sCSPR balance changes so the cache never goes stale
+    pub fn refresh_user_tier(&mut self, user: Address) -> u8 {
+        if !self.enabled.get_or_default() {
+            return 0;
+        }
+        self.compute_user_tier(user)
+    }
+    
+    /// Determine a user's tier from balances and update the cache
+    fn compute_user_tier(&mut self, user: Address) -> u8 {
+        let tier = self.tier_for(
+            self.get_scspr_balance(user),
+            self.get_aecto_balance(user),
+            self.get_trailing_volume(user),
+        );
         
         // Update cache
         let discount_percent = self.tiers.get(&tier).unwrap().discount_percent;
@@ -203,10 +218,31 @@ impl GasDiscountManager {
             return U256::zero();
         }
         
-        // Calculate subsidy: gas_cost * discount_percent / 100
-        let subsidy = gas_cost * U256::from(discount_percent) / U256::from(100);
-        
-        subsidy
+        self.percent_of(gas_cost, discount_percent)
+    }
+    
+    /// Get a user's tier from current balances without reading or writing the cache
+    /// Lets other contracts query tiers from view entry points
+    pub fn preview_user_tier(&self, user: Address) -> u8 {
+        if !self.enabled.get_or_default() {
+            return 0;
+        }
+        self.tier_for(
+            self.get_scspr_balance(user),
+            self.get_aecto_balance(user),
+            self.get_trailing_volume(user),
+        )
+    }
+    
+    /// Get a user's discount percentage without touching the cache
+    pub fn preview_discount_percent(&self, user: Address) -> u8 {
+        let tier = self.preview_user_tier(user);
+        self.tiers.get(&tier).unwrap().discount_percent
+    }
+    
+    /// Get the gas subsidy for a transaction without touching the cache
+    pub fn preview_subsidy(&self, user: Address, gas_cost: U256) -> U256 {
+        self.percent_of(gas_cost, self.preview_discount_percent(user))
     }
     
     /// Record a gas subsidy (called by integrated contracts)
@@ -231,6 +267,34 @@ impl GasDiscountManager {
     // Helper Functions
     // ========================================
     
+    /// `percent` whole percent of `amount`, rounded half up
+    fn percent_of(&self, amount: U256, percent: u8) -> U256 {
+        bps_of(amount, u32::from(percent) * 100).unwrap_or_else(|e| self.env().revert(e))
+    }
+
+    /// Highest tier whose sCSPR, aECTO or volume minimum is met
+    fn tier_for(&self, scspr_balance: U256, aecto_balance: U256, volume: U256) -> u8 {
+        (1..=4u8).rev()
+            .find(|tier| {
+                let info = self.tiers.get(tier).unwrap();
+                scspr_balance >= info.min_scspr
+                    || aecto_balance >= info.min_aecto
+                    || (!info.min_volume.is_zero() && volume >= info.min_volume)
+            })
+            .unwrap_or(0)
+    }
+    
+    /// Get user's weighted DEX volume over the trailing window
+    fn get_trailing_volume(&self, user: Address) -> U256 {
+        let trade_mining_address = match self.trade_mining.get_or_default() {
+            Some(addr) => addr,
+            None => return U256::zero(),
+        };
+        
+        let trade_mining = TradeMiningContractRef::new(self.env(), trade_mining_address);
+        trade_mining.get_trailing_volume(user, VOLUME_WINDOW_DAYS)
+    }
+    
     /// Get user's sCSPR balance
     fn get_scspr_balance(&self, user: Address) -> U256 {
         let scspr_address = match self.scspr_token.get() {
@@ -288,8 +352,9 @@ impl GasDiscountManager {
         discount_percent: u8,
         min_scspr: U256,
         min_aecto: U256,
+        min_volume: U256,
     ) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         
         if tier > 4 {
             self.env().revert(DexError::InvalidConfiguration);
@@ -304,6 +369,7 @@ impl GasDiscountManager {
             discount_percent,
             min_scspr,
             min_aecto,
+            min_volume,
         });
         
         self.env().emit_event(TierUpdated {
@@ -315,7 +381,7 @@ impl GasDiscountManager {
     
     /// Enable or disable gas discounts
     pub fn set_enabled(&mut self, enabled: bool) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         self.enabled.set(enabled);
         
         self.env().emit_event(DiscountsToggled {
@@ -326,13 +392,24 @@ impl GasDiscountManager {
     
     /// Update cache validity period
     pub fn set_cache_validity(&mut self, seconds: u64) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         self.cache_validity.set(seconds);
     }
     
+    /// Set the TradeMining contract trailing volume is read from (None disables volume tiers)
+    pub fn set_trade_mining(&mut self, trade_mining: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.trade_mining.set(trade_mining);
+    }
+    
+    /// Get the TradeMining contract trailing volume is read from
+    pub fn get_trade_mining(&self) -> Option<Address> {
+        self.trade_mining.get_or_default()
+    }
+    
     /// Update treasury address
     pub fn set_treasury(&mut self, new_treasury: Address) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         self.treasury.set(new_treasury);
     }
     
@@ -340,18 +417,20 @@ impl GasDiscountManager {
     // Cache will be invalidated after cache_validity period expires
     // /// Clear user's discount cache (force recalculation)
     // pub fn clear_user_cache(&mut self, user: Address) {
-    //     self.only_admin();
+    //     self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
     //     // self.user_discounts.remove(&user);
     // }
     
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = match self.admin.get() {
-            Some(addr) => addr,
-            None => self.env().revert(DexError::Unauthorized),
-        };
-        if caller != admin {
-            self.env().revert(DexError::Unauthorized);
+    // ========================================
+    // Access Control
+    // ========================================
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
         }
     }
 }
@@ -389,7 +468,10 @@ use crate::errors::DexError;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use odra::host::{Deployer, HostEnv};
+    use crate::dex::pair::SECONDS_PER_DAY;
+    use crate::incentives::trade_mining::{TradeMining, TradeMiningInitArgs};
+    use crate::token::{LpToken, LpTokenInitArgs};
+    use odra::host::{Deployer, HostEnv, HostRef};
 
     #[test]
     fn test_tier_calculation() {
@@ -443,4 +525,88 @@ mod tests {
         let subsidy = manager.calculate_subsidy(user, gas_cost);
         assert_eq!(subsidy, U256::zero());
     }
+
+    #[test]
+    fn test_preview_tier_leaves_cache_untouched() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        
+        env.set_caller(admin);
+        let mut scspr = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Staked CSPR"),
+            symbol: String::from("sCSPR"),
+        });
+        let aecto = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("aECTO"),
+            symbol: String::from("aECTO"),
+        });
+        let mut manager = GasDiscountManager::deploy(&env, GasDiscountManagerInitArgs {
+            scspr_token_address: scspr.address(),
+            aecto_token_address: aecto.address(),
+            treasury_address: env.get_account(12),
+        });
+        
+        // Cache tier 0, then cross the tier 2 threshold
+        assert_eq!(manager.get_user_tier(user), 0);
+        scspr.mint(user, U256::from(500) * U256::from(10u128.pow(9)));
+        
+        assert_eq!(manager.preview_user_tier(user), 2);
+        assert_eq!(manager.preview_discount_percent(user), 25);
+        assert_eq!(manager.preview_subsidy(user, U256::from(1000)), U256::from(250));
+        assert_eq!(manager.get_user_discount_info(user).unwrap().tier, 0);
+        
+        // The cached path still serves the stale tier until refreshed
+        assert_eq!(manager.get_user_tier(user), 0);
+        assert_eq!(manager.refresh_user_tier(user), 2);
+        
+        manager.set_enabled(false);
+        assert_eq!(manager.preview_user_tier(user), 0);
+    }
+
+    #[test]
+    fn test_trailing_volume_lifts_tier() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let pair = env.get_account(4);
+        let unweighted_pair = env.get_account(5);
+        
+        env.set_caller(admin);
+        let token = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("sCSPR"),
+            symbol: String::from("sCSPR"),
+        });
+        let mut mining = TradeMining::deploy(&env, TradeMiningInitArgs {
+            reward_token_address: token.address(),
+            epoch_duration: SECONDS_PER_DAY,
+            emission_per_epoch: U256::zero(),
+        });
+        mining.set_reporter(admin, true);
+        mining.set_pair_weight(pair, 5_000);
+        let mut manager = GasDiscountManager::deploy(&env, GasDiscountManagerInitArgs {
+            scspr_token_address: token.address(),
+            aecto_token_address: token.address(),
+            treasury_address: env.get_account(12),
+        });
+        manager.set_trade_mining(Some(mining.address()));
+        
+        // Only weighted volume on eligible pairs counts
+        let volume = U256::from(50000) * U256::from(10u128.pow(18));
+        mining.record_volume(user, unweighted_pair, volume * 10);
+        assert_eq!(mining.get_trailing_volume(user, VOLUME_WINDOW_DAYS), U256::zero());
+        mining.record_volume(user, pair, volume);
+        env.advance_block_time(10 * SECONDS_PER_DAY);
+        mining.record_volume(user, pair, volume);
+        assert_eq!(mining.get_trailing_volume(user, VOLUME_WINDOW_DAYS), volume);
+        assert_eq!(manager.preview_user_tier(user), 2);
+        
+        // The first half leaves the window after 30 days
+        env.advance_block_time(25 * SECONDS_PER_DAY);
+        assert_eq!(manager.preview_user_tier(user), 1);
+        
+        // Zero disables the volume path for a tier
+        manager.update_tier(1, 10, U256::MAX, U256::MAX, U256::zero());
+        assert_eq!(manager.preview_user_tier(user), 0);
+    }
 }
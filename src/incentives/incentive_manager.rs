@@ -2,6 +2,11 @@
 //! 
 //! Main coordinator for all incentive mechanisms across the protocol.
 //! Integrates gas discounts and LP boost rewards.
+//! Escrows gas rebates that router users accrue and withdraw.
+//! Splits funded season rewards by participation scores snapshotted at
+//! season boundaries.
+//! Aggregates protocol TVL from the staking, lending and DEX contracts; DEX
+//! TVL is summed over the factory's pairs in bounded `sync_dex_tvl` batches.
 //! Manages treasury and emission schedules.
 
 use odra::prelude::*;
@@ -9,7 +14,48 @@ use odra::casper_types::U256;
 use odra::ContractRef;
 use super::gas_discount::GasDiscountManagerContractRef;
 use super::lp_rewards_distributor::LpRewardsDistributorContractRef;
+use super::points_ledger::PointsLedgerContractRef;
+use crate::dex::factory::FactoryContractRef;
+use crate::dex::pair::PairContractRef;
+use crate::lending::lending_pool::LendingPoolContractRef;
+use crate::lending::price_oracle::PriceOracleContractRef;
+use crate::lst::staking_manager::StakingManagerContractRef;
 use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE, REGISTRAR_ROLE};
+use crate::math::bps_of;
+
+/// Length of a gas rebate cap epoch (1 day)
+pub const REBATE_EPOCH_SECONDS: u64 = 86_400;
+
+/// Default length of a rewards season (30 days)
+pub const DEFAULT_SEASON_DURATION: u64 = 30 * 86_400;
+
+/// Default sCSPR balance that counts as an LST position (100 sCSPR, 9 decimals)
+pub const DEFAULT_MIN_LST_BALANCE: u128 = 100_000_000_000;
+
+/// Default LP balance of a pair that counts as a DEX LP position (0.001 LP, 18 decimals)
+pub const DEFAULT_MIN_LP_BALANCE: u128 = 1_000_000_000_000_000;
+
+/// Participation score recorded in a season
+///
+/// The last checkpoint at or before a season holds the score at its end.
+#[odra::odra_type]
+pub struct ScoreCheckpoint {
+    /// Season the score was recorded in
+    pub season: u64,
+    /// Score (products used x LP boost, scaled by 1e18)
+    pub score: U256,
+}
+
+/// Balances a synced position needs to count towards participation scores
+#[odra::odra_type]
+#[derive(Default)]
+pub struct ScoreMinimums {
+    /// Minimum sCSPR balance
+    pub lst_balance: U256,
+    /// Minimum LP balance in a pair
+    pub lp_balance: U256,
+}
 
 /// Protocol statistics
 #[odra::odra_type]
@@ -27,16 +73,43 @@ pub struct ProtocolStats {
     pub last_update: u64,
 }
 
+/// Contracts TVL is read from (unset sources count as zero)
+#[odra::odra_type]
+#[derive(Default)]
+pub struct TvlSources {
+    /// StakingManager holding staked CSPR
+    pub staking_manager: Option<Address>,
+    /// LendingPool holding ECTO liquidity and borrows
+    pub lending_pool: Option<Address>,
+    /// PriceOracle pricing assets in ECTO
+    pub price_oracle: Option<Address>,
+    /// ECTO token, valued at par
+    pub ecto_token: Option<Address>,
+}
+
+/// Protocol TVL by product, in ECTO (scaled by 1e18)
+#[odra::odra_type]
+pub struct TvlBreakdown {
+    /// Staked CSPR, valued as sCSPR
+    pub lst: U256,
+    /// Lending liquidity plus borrows
+    pub lending: U256,
+    /// Reserves of the factory's pairs
+    pub dex: U256,
+    /// Sum of the above
+    pub total: U256,
+}
+
 /// User participation metrics
 #[odra::odra_type]
 pub struct UserMetrics {
     /// User address
     pub user: Address,
-    /// Has LST position (sCSPR)
+    /// Has LST position (sCSPR, at least the score minimum when synced)
     pub has_lst: bool,
     /// Has yield position (aECTO)
     pub has_yield: bool,
-    /// Has DEX LP position
+    /// Has DEX LP position (at least the score minimum in a pair when synced)
     pub has_dex_lp: bool,
     /// Is borrower
     pub is_borrower: bool,
@@ -44,8 +117,6 @@ pub struct UserMetrics {
     pub gas_tier: u8,
     /// LP boost multiplier
     pub lp_boost: U256,
-    /// Total rewards earned
-    pub total_rewards: U256,
 }
 
 /// Incentive Manager contract
@@ -57,8 +128,8 @@ pub struct IncentiveManager {
     rewards_distributor: Var<Address>,
     /// Treasury address
     treasury: Var<Address>,
-    /// Admin address
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     /// Protocol stats
     protocol_stats: Var<ProtocolStats>,
     /// User metrics (user -> UserMetrics)
@@ -75,6 +146,58 @@ pub struct IncentiveManager {
     reserves_allocation: Var<u8>,
     /// Total treasury balance
     treasury_balance: Var<U256>,
+    /// sCSPR token allowed to push transfer notifications
+    scspr_token: Var<Option<Address>>,
+    /// Factory whose pairs are allowed to push LP transfer notifications
+    factory: Var<Option<Address>>,
+    /// Whether a user holds LP of a pair ((user, pair) -> held)
+    lp_pairs_held: Mapping<(Address, Address), bool>,
+    /// Number of pairs a user holds LP of
+    lp_pair_count: Mapping<Address, u32>,
+    /// Router allowed to accrue gas rebates
+    router: Var<Option<Address>>,
+    /// Token gas rebates are escrowed and paid in
+    rebate_token: Var<Option<Address>>,
+    /// Escrowed rebate tokens not yet accrued to users
+    rebate_pool: Var<U256>,
+    /// Max rebates accrued per epoch across all users
+    epoch_rebate_cap: Var<U256>,
+    /// Max rebates accrued per epoch by one user
+    user_rebate_cap: Var<U256>,
+    /// Rebates accrued per epoch (epoch -> amount)
+    epoch_rebated: Mapping<u64, U256>,
+    /// Rebates accrued per user per epoch ((user, epoch) -> amount)
+    user_epoch_rebated: Mapping<(Address, u64), U256>,
+    /// Accrued rebates awaiting withdrawal (user -> amount)
+    claimable_rebates: Mapping<Address, U256>,
+    /// Start of season 0
+    season_start: Var<u64>,
+    /// Length of a season (seconds)
+    season_duration: Var<u64>,
+    /// Token season rewards are paid in
+    season_reward_token: Var<Option<Address>>,
+    /// Rewards funded per season (season -> amount)
+    season_rewards: Mapping<u64, U256>,
+    /// Score checkpoints per user, None is the total ((user, index) -> checkpoint)
+    score_checkpoints: Mapping<(Option<Address>, u32), ScoreCheckpoint>,
+    /// Number of score checkpoints per user, None is the total
+    score_checkpoint_count: Mapping<Option<Address>, u32>,
+    /// Claimed flags ((user, season) -> claimed)
+    season_claimed: Mapping<(Address, u64), bool>,
+    /// Contracts TVL is aggregated from
+    tvl_sources: Var<TvlSources>,
+    /// PointsLedger tracking users' loyalty points
+    points_ledger: Var<Option<Address>>,
+    /// Balances synced positions need to count as products
+    score_minimums: Var<ScoreMinimums>,
+    /// DEX TVL published by the last completed pair sweep
+    dex_tvl: Var<U256>,
+    /// Timestamp the DEX TVL was last published
+    dex_tvl_updated_at: Var<u64>,
+    /// Next pair index of the sweep in progress
+    dex_tvl_cursor: Var<u32>,
+    /// DEX TVL summed so far by the sweep in progress
+    dex_tvl_partial: Var<U256>,
 }
 
 #[odra::module]
@@ -91,7 +214,8 @@ impl IncentiveManager {
         self.gas_discount_manager.set(gas_discount_manager_address);
         self.rewards_distributor.set(rewards_distributor_address);
         self.treasury.set(treasury_address);
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(REGISTRAR_ROLE, caller);
         
         // Initialize protocol stats
         self.protocol_stats.set(ProtocolStats {
@@ -104,6 +228,12 @@ impl IncentiveManager {
         
         self.user_count.set(0);
         self.treasury_balance.set(U256::zero());
+        self.season_start.set(self.env().get_block_time());
+        self.season_duration.set(DEFAULT_SEASON_DURATION);
+        self.score_minimums.set(ScoreMinimums {
+            lst_balance: U256::from(DEFAULT_MIN_LST_BALANCE),
+            lp_balance: U256::from(DEFAULT_MIN_LP_BALANCE),
+        });
         
         // Set default treasury allocations
         self.gas_subsidy_allocation.set(40); // 40%
@@ -112,7 +242,7 @@ impl IncentiveManager {
         self.reserves_allocation.set(10);    // 10%
     }
     
-    /// Register a user's participation in the protocol
+    /// Register a user's participation in the protocol (registrar only)
     /// This should be called when users interact with any protocol component
     pub fn register_user_activity(
         &mut self,
@@ -122,17 +252,10 @@ impl IncentiveManager {
         has_dex_lp: bool,
         is_borrower: bool,
     ) {
+        self.access.require_role(REGISTRAR_ROLE, DexError::Unauthorized);
+        
         // Get or create user metrics
-        let mut metrics = self.user_metrics.get(&user).unwrap_or(UserMetrics {
-            user,
-            has_lst: false,
-            has_yield: false,
-            has_dex_lp: false,
-            is_borrower: false,
-            gas_tier: 0,
-            lp_boost: U256::from(10u128.pow(18)), // 1.0x default
-            total_rewards: U256::zero(),
-        });
+        let mut metrics = self.user_metrics.get(&user).unwrap_or_else(|| Self::new_user_metrics(user));
         
         // Check if this is a new user
         let is_new_user = !metrics.has_lst && !metrics.has_yield && !metrics.has_dex_lp;
@@ -143,31 +266,14 @@ impl IncentiveManager {
         metrics.has_dex_lp = has_dex_lp;
         metrics.is_borrower = is_borrower;
         
-        // Update gas tier from gas discount manager
-        if let Some(gas_manager_address) = self.gas_discount_manager.get() {
-            let mut gas_manager = GasDiscountManagerContractRef::new(self.env(), gas_manager_address);
-            metrics.gas_tier = gas_manager.get_user_tier(user);
-        }
+        // Update gas tier and LP boost
+        self.refresh_tiers(&mut metrics, false);
         
-        // Update LP boost from rewards distributor
-        if let Some(rewards_address) = self.rewards_distributor.get() {
-            let rewards_dist = LpRewardsDistributorContractRef::new(self.env(), rewards_address);
-            let boost_factors = rewards_dist.get_boost_factors(user);
-            metrics.lp_boost = boost_factors.total_multiplier;
-        }
-        
-        self.user_metrics.set(&user, metrics);
+        self.save_metrics(user, metrics);
         
         // If new user, add to registry
         if is_new_user {
-            let count = self.user_count.get_or_default();
-            self.registered_users.set(&count, user);
-            self.user_count.set(count + 1);
-            
-            // Update active users count
-            let mut stats = self.protocol_stats.get_or_default();
-            stats.active_users = count + 1;
-            self.protocol_stats.set(stats);
+            self.add_registered_user(user);
         }
         
         self.env().emit_event(UserActivityRegistered {
@@ -180,13 +286,108 @@ impl IncentiveManager {
         });
     }
     
-    /// Process a DEX transaction with gas discount
-    /// Called by DEX router before executing swaps/liquidity operations
+    /// Sync the LST positions of an sCSPR transfer's sender and receiver
+    /// Called by the sCSPR token on every transfer, mint and burn so gas tiers
+    /// and LP boosts track balances without waiting for the cache to expire
+    pub fn on_scspr_transfer(&mut self, from: Option<Address>, to: Option<Address>) {
+        let caller = self.env().caller();
+        if self.scspr_token.get_or_default() != Some(caller) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        
+        let token = Cep18TokenContractRef::new(self.env(), caller);
+        let min_balance = self.score_minimums.get_or_default().lst_balance;
+        for user in [from, to].into_iter().flatten() {
+            let has_lst = Self::meets_minimum(token.balance_of(user), min_balance);
+            
+            // Only track accounts that hold sCSPR or are already registered
+            let existing = self.user_metrics.get(&user);
+            if existing.is_none() && !has_lst {
+                continue;
+            }
+            let mut metrics = existing.unwrap_or_else(|| Self::new_user_metrics(user));
+            let is_new_user = !metrics.has_lst && !metrics.has_yield && !metrics.has_dex_lp;
+            
+            metrics.has_lst = has_lst;
+            self.refresh_tiers(&mut metrics, true);
+            let gas_tier = metrics.gas_tier;
+            self.save_metrics(user, metrics);
+            
+            if is_new_user && has_lst {
+                self.add_registered_user(user);
+            }
+            
+            self.env().emit_event(LstPositionSynced {
+                user,
+                has_lst,
+                gas_tier,
+                timestamp: self.env().get_block_time(),
+            });
+        }
+    }
+    
+    /// Sync the DEX LP positions of an LP transfer's sender and receiver
+    /// Called by Factory pairs on every LP mint and transfer so `has_dex_lp`
+    /// and LP boosts track balances without registrar updates
+    pub fn on_lp_transfer(&mut self, from: Option<Address>, to: Option<Address>) {
+        let pair = self.env().caller();
+        self.ensure_factory_pair(pair);
+        
+        let pair_ref = PairContractRef::new(self.env(), pair);
+        let min_balance = self.score_minimums.get_or_default().lp_balance;
+        for user in [from, to].into_iter().flatten().filter(|user| *user != pair) {
+            let holds = Self::meets_minimum(pair_ref.balance_of(user), min_balance);
+            let held = self.lp_pairs_held.get(&(user, pair)).unwrap_or(false);
+            
+            // Only track accounts that hold LP or are already registered
+            let existing = self.user_metrics.get(&user);
+            if existing.is_none() && !holds {
+                continue;
+            }
+            
+            let mut count = self.lp_pair_count.get(&user).unwrap_or(0);
+            if holds != held {
+                count = if holds { count + 1 } else { count.saturating_sub(1) };
+                self.lp_pairs_held.set(&(user, pair), holds);
+                self.lp_pair_count.set(&user, count);
+            }
+            
+            let mut metrics = existing.unwrap_or_else(|| Self::new_user_metrics(user));
+            let is_new_user = !metrics.has_lst && !metrics.has_yield && !metrics.has_dex_lp;
+            
+            metrics.has_dex_lp = count > 0;
+            self.refresh_tiers(&mut metrics, false);
+            let has_dex_lp = metrics.has_dex_lp;
+            let lp_boost = metrics.lp_boost;
+            self.save_metrics(user, metrics);
+            
+            if is_new_user && has_dex_lp {
+                self.add_registered_user(user);
+            }
+            
+            self.env().emit_event(LpPositionSynced {
+                user,
+                pair,
+                has_dex_lp,
+                lp_boost,
+                timestamp: self.env().get_block_time(),
+            });
+        }
+    }
+    
+    /// Accrue a gas rebate for a DEX transaction (router only)
+    /// The discounted share of `estimated_gas` is moved from the escrowed rebate
+    /// pool to the user's claimable balance, limited by the per-epoch and
+    /// per-user caps. Returns the rebate accrued.
     pub fn process_dex_transaction(
         &mut self,
         user: Address,
         estimated_gas: U256,
     ) -> U256 {
+        if self.router.get_or_default() != Some(self.env().caller()) {
+            self.env().revert(DexError::Unauthorized);
+        }
+        
         let gas_manager_address = match self.gas_discount_manager.get() {
             Some(addr) => addr,
             None => return U256::zero(),
@@ -194,45 +395,165 @@ impl IncentiveManager {
         
         let mut gas_manager = GasDiscountManagerContractRef::new(self.env(), gas_manager_address);
         
-        // Calculate subsidy
-        let subsidy = gas_manager.calculate_subsidy(user, estimated_gas);
+        // Limit the subsidy by the caps and what is left in escrow
+        let epoch = self.env().get_block_time() / REBATE_EPOCH_SECONDS;
+        let epoch_rebated = self.epoch_rebated.get(&epoch).unwrap_or_default();
+        let user_rebated = self.user_epoch_rebated.get(&(user, epoch)).unwrap_or_default();
+        let subsidy = gas_manager.calculate_subsidy(user, estimated_gas)
+            .min(self.epoch_rebate_cap.get_or_default().saturating_sub(epoch_rebated))
+            .min(self.user_rebate_cap.get_or_default().saturating_sub(user_rebated))
+            .min(self.rebate_pool.get_or_default());
         
         if subsidy > U256::zero() {
             // Record the subsidy
             gas_manager.record_subsidy(user, subsidy);
             
+            self.rebate_pool.set(self.rebate_pool.get_or_default() - subsidy);
+            self.epoch_rebated.set(&epoch, epoch_rebated + subsidy);
+            self.user_epoch_rebated.set(&(user, epoch), user_rebated + subsidy);
+            let claimable = self.claimable_rebates.get(&user).unwrap_or_default();
+            self.claimable_rebates.set(&user, claimable + subsidy);
+            
             // Update protocol stats
             let mut stats = self.protocol_stats.get_or_default();
             stats.total_gas_subsidized = stats.total_gas_subsidized + subsidy;
             self.protocol_stats.set(stats);
             
-            // Update user metrics
-            if let Some(mut metrics) = self.user_metrics.get(&user) {
-                metrics.total_rewards = metrics.total_rewards + subsidy;
-                self.user_metrics.set(&user, metrics);
-            }
+            self.env().emit_event(GasRebateAccrued {
+                user,
+                epoch,
+                amount: subsidy,
+                timestamp: self.env().get_block_time(),
+            });
         }
         
         subsidy
     }
     
-    /// Allocate treasury funds to different pools
+    /// Escrow rebate tokens that fund gas rebates (pulled from the caller)
+    pub fn fund_gas_rebates(&mut self, amount: U256) {
+        let token = self.rebate_token.get_or_default()
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        
+        let caller = self.env().caller();
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer_from(caller, self.env().self_address(), amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+        self.rebate_pool.set(self.rebate_pool.get_or_default() + amount);
+        
+        self.env().emit_event(GasRebatesFunded {
+            funder: caller,
+            amount,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Withdraw the caller's accrued gas rebates
+    pub fn claim_gas_rebate(&mut self) -> U256 {
+        let caller = self.env().caller();
+        let amount = self.claimable_rebates.get(&caller).unwrap_or_default();
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        self.claimable_rebates.set(&caller, U256::zero());
+        
+        let token = self.rebate_token.get_or_default()
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(caller, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+        
+        self.env().emit_event(GasRebateClaimed {
+            user: caller,
+            amount,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        amount
+    }
+    
+    /// Add rewards to the current or a future season (pulled from the caller)
+    pub fn fund_season(&mut self, season: u64, amount: U256) {
+        if season < self.current_season() {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        let token = self.season_reward_token.get_or_default()
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        
+        let caller = self.env().caller();
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer_from(caller, self.env().self_address(), amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+        let funded = self.season_rewards.get(&season).unwrap_or_default();
+        self.season_rewards.set(&season, funded + amount);
+        
+        self.env().emit_event(SeasonFunded {
+            season,
+            amount,
+            funded_by: caller,
+        });
+    }
+    
+    /// Claim the caller's share of a finished season's rewards
+    /// Shares follow the scores snapshotted when the season ended
+    pub fn claim_season_rewards(&mut self, season: u64) -> U256 {
+        if season >= self.current_season() {
+            self.env().revert(DexError::EpochNotClosed);
+        }
+        let caller = self.env().caller();
+        if self.season_claimed.get(&(caller, season)).unwrap_or(false) {
+            self.env().revert(DexError::AlreadyClaimed);
+        }
+        
+        let amount = self.get_season_reward(caller, season);
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        self.season_claimed.set(&(caller, season), true);
+        
+        let token = self.season_reward_token.get_or_default()
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(caller, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+        
+        self.env().emit_event(SeasonRewardClaimed {
+            user: caller,
+            season,
+            amount,
+        });
+        
+        amount
+    }
+    
+    /// Record a planned allocation of treasury funds (events only)
+    /// Funds are held and allocated by `governance::Treasury`
     pub fn allocate_treasury_funds(&mut self, amount: U256) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         
         let gas_allocation = self.gas_subsidy_allocation.get_or_default();
         let lp_allocation = self.lp_rewards_allocation.get_or_default();
         let dev_allocation = self.development_allocation.get_or_default();
         let reserves_allocation = self.reserves_allocation.get_or_default();
         
-        // Calculate amounts
-        let gas_amount = amount * U256::from(gas_allocation) / U256::from(100);
-        let lp_amount = amount * U256::from(lp_allocation) / U256::from(100);
-        let dev_amount = amount * U256::from(dev_allocation) / U256::from(100);
-        let reserves_amount = amount * U256::from(reserves_allocation) / U256::from(100);
+        // Allocations are whole percentages
+        let share = |percent: u8| bps_of(amount, u32::from(percent) * 100).unwrap_or_else(|e| self.env().revert(e));
+        let gas_amount = share(gas_allocation);
+        let lp_amount = share(lp_allocation);
+        let dev_amount = share(dev_allocation);
+        let reserves_amount = share(reserves_allocation);
         
-        // Note: In production, you would transfer these amounts to respective pools
-        // For now, we just emit events
+        // Accounting only; the Treasury moves the funds
         
         self.env().emit_event(TreasuryAllocated {
             total_amount: amount,
@@ -290,22 +611,17 @@ impl IncentiveManager {
             is_borrower: false,
             gas_tier: 0,
             lp_boost: U256::from(10u128.pow(18)),
-            total_rewards: U256::zero(),
         });
         
         let total_apy = self.calculate_total_apy(user);
         
-        // Get gas discount percentage
-        let gas_discount = if metrics.gas_tier > 0 {
-            match metrics.gas_tier {
-                1 => 10,
-                2 => 25,
-                3 => 40,
-                4 => 60,
-                _ => 0,
+        // Get gas discount percentage from current balances
+        let gas_discount = match self.gas_discount_manager.get() {
+            Some(gas_manager_address) => {
+                GasDiscountManagerContractRef::new(self.env(), gas_manager_address)
+                    .preview_discount_percent(user)
             }
-        } else {
-            0
+            None => 0,
         };
         
         UserDashboard {
@@ -313,8 +629,46 @@ impl IncentiveManager {
             metrics,
             total_apy,
             gas_discount_percent: gas_discount,
-            protocol_stats: self.protocol_stats.get_or_default(),
+            points: self.points_ledger.get_or_default()
+                .map(|ledger| PointsLedgerContractRef::new(self.env(), ledger).get_points(user))
+                .unwrap_or_default(),
+            protocol_stats: self.get_protocol_stats(),
+        }
+    }
+    
+    /// Value up to `max_pairs` more of the factory's pairs towards the DEX TVL (anyone)
+    /// 
+    /// Pairs are swept in index order; once the sweep reaches the last pair
+    /// its sum is published as the DEX TVL and the next sweep starts over.
+    /// 
+    /// # Returns
+    /// Pairs left in the sweep (0 once the DEX TVL has been published)
+    pub fn sync_dex_tvl(&mut self, max_pairs: u32) -> u32 {
+        let factory_address = self.factory.get_or_default()
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        let factory = FactoryContractRef::new(self.env(), factory_address);
+        
+        let pair_count = factory.all_pairs_length();
+        let start = self.dex_tvl_cursor.get_or_default().min(pair_count);
+        let end = pair_count.min(start.saturating_add(max_pairs));
+        let partial = self.dex_tvl_partial.get_or_default() + self.get_dex_tvl_page(start, end - start);
+        
+        if end < pair_count {
+            self.dex_tvl_cursor.set(end);
+            self.dex_tvl_partial.set(partial);
+            return pair_count - end;
         }
+        
+        self.dex_tvl.set(partial);
+        self.dex_tvl_updated_at.set(self.env().get_block_time());
+        self.dex_tvl_cursor.set(0);
+        self.dex_tvl_partial.set(U256::zero());
+        self.env().emit_event(DexTvlSynced {
+            dex_tvl: partial,
+            pair_count,
+            timestamp: self.env().get_block_time(),
+        });
+        0
     }
     
     // ========================================
@@ -322,8 +676,90 @@ impl IncentiveManager {
     // ========================================
     
     /// Get protocol statistics
+    /// TVL is aggregated from the configured sources at call time
     pub fn get_protocol_stats(&self) -> ProtocolStats {
-        self.protocol_stats.get_or_default()
+        let mut stats = self.protocol_stats.get_or_default();
+        stats.total_tvl = self.get_tvl().total;
+        stats.last_update = self.env().get_block_time();
+        stats
+    }
+    
+    /// Get protocol TVL by product, read from the configured sources
+    /// DEX TVL is the value published by the last completed `sync_dex_tvl` sweep
+    pub fn get_tvl(&self) -> TvlBreakdown {
+        let sources = self.tvl_sources.get_or_default();
+        let oracle = sources.price_oracle.map(|address| PriceOracleContractRef::new(self.env(), address));
+        
+        let lst = match (sources.staking_manager, &oracle) {
+            (Some(staking_address), Some(oracle)) => {
+                let staking = StakingManagerContractRef::new(self.env(), staking_address);
+                let shares = staking.convert_to_shares(staking.total_assets());
+                let scspr = staking.get_scspr_token();
+                if shares.is_zero() || !oracle.is_price_fresh(scspr) {
+                    U256::zero()
+                } else {
+                    oracle.get_asset_value(scspr, shares)
+                }
+            }
+            _ => U256::zero(),
+        };
+        
+        let lending = match sources.lending_pool {
+            Some(pool_address) => {
+                let pool = LendingPoolContractRef::new(self.env(), pool_address);
+                pool.get_total_liquidity() + pool.get_total_borrows()
+            }
+            None => U256::zero(),
+        };
+        
+        let dex = self.dex_tvl.get_or_default();
+        
+        TvlBreakdown {
+            lst,
+            lending,
+            dex,
+            total: lst + lending + dex,
+        }
+    }
+    
+    /// Get the ECTO value of `count` of the factory's pairs from index `start`
+    /// Pair sides the oracle cannot price are valued at the other side's value;
+    /// pairs with no priceable side are left out
+    pub fn get_dex_tvl_page(&self, start: u32, count: u32) -> U256 {
+        let factory_address = match self.factory.get_or_default() {
+            Some(address) => address,
+            None => return U256::zero(),
+        };
+        let factory = FactoryContractRef::new(self.env(), factory_address);
+        let sources = self.tvl_sources.get_or_default();
+        let oracle = sources.price_oracle.map(|address| PriceOracleContractRef::new(self.env(), address));
+        
+        let end = factory.all_pairs_length().min(start.saturating_add(count));
+        let mut dex = U256::zero();
+        for index in start..end {
+            if let Some(pair_address) = factory.all_pairs_at(index) {
+                let pair = PairContractRef::new(self.env(), pair_address);
+                let (reserve0, reserve1, _) = pair.get_reserves();
+                let value0 = self.reserve_value(&sources, &oracle, pair.token0(), reserve0);
+                let value1 = self.reserve_value(&sources, &oracle, pair.token1(), reserve1);
+                dex = dex + match (value0, value1) {
+                    (Some(value0), Some(value1)) => value0 + value1,
+                    (Some(value), None) | (None, Some(value)) => value * U256::from(2),
+                    (None, None) => U256::zero(),
+                };
+            }
+        }
+        dex
+    }
+    
+    /// Get the timestamp the DEX TVL was last published (0 if never)
+    pub fn get_dex_tvl_updated_at(&self) -> u64 {
+        self.dex_tvl_updated_at.get_or_default()
+    }
+    
+    /// Get the contracts TVL is aggregated from
+    pub fn get_tvl_sources(&self) -> TvlSources {
+        self.tvl_sources.get_or_default()
     }
     
     /// Get user metrics
@@ -353,7 +789,7 @@ impl IncentiveManager {
         development: u8,
         reserves: u8,
     ) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         
         // Ensure total is 100%
         if gas_subsidy + lp_rewards + development + reserves != 100 {
@@ -374,26 +810,197 @@ impl IncentiveManager {
         });
     }
     
-    /// Update protocol stats (called periodically by keeper or admin)
-    pub fn update_protocol_stats(
-        &mut self,
-        total_tvl: U256,
-    ) {
-        self.only_admin();
-        
-        let mut stats = self.protocol_stats.get_or_default();
-        stats.total_tvl = total_tvl;
-        stats.last_update = self.env().get_block_time();
+    /// Set the sCSPR token whose transfers update user metrics (None disables)
+    pub fn set_scspr_token(&mut self, scspr_token: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.scspr_token.set(scspr_token);
+    }
+    
+    /// Get the sCSPR token whose transfers update user metrics
+    pub fn get_scspr_token(&self) -> Option<Address> {
+        self.scspr_token.get_or_default()
+    }
+    
+    /// Set the Factory whose pairs' LP transfers update user metrics (None disables)
+    pub fn set_factory(&mut self, factory: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.factory.set(factory);
+    }
+    
+    /// Get the Factory whose pairs' LP transfers update user metrics
+    pub fn get_factory(&self) -> Option<Address> {
+        self.factory.get_or_default()
+    }
+    
+    /// Set the PointsLedger shown on user dashboards (None hides points)
+    pub fn set_points_ledger(&mut self, points_ledger: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.points_ledger.set(points_ledger);
+    }
+    
+    /// Get the PointsLedger shown on user dashboards
+    pub fn get_points_ledger(&self) -> Option<Address> {
+        self.points_ledger.get_or_default()
+    }
+    
+    /// Set the sCSPR and per-pair LP balances synced positions need to count
+    /// towards participation scores, so dust cannot farm season rewards
+    /// Applies as each user's positions are next synced
+    pub fn set_score_minimums(&mut self, min_lst_balance: U256, min_lp_balance: U256) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.score_minimums.set(ScoreMinimums {
+            lst_balance: min_lst_balance,
+            lp_balance: min_lp_balance,
+        });
+    }
+    
+    /// Get the balances synced positions need to count towards participation scores
+    pub fn get_score_minimums(&self) -> ScoreMinimums {
+        self.score_minimums.get_or_default()
+    }
+    
+    /// Set the Router allowed to accrue gas rebates (None disables)
+    pub fn set_router(&mut self, router: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.router.set(router);
+    }
+    
+    /// Get the Router allowed to accrue gas rebates
+    pub fn get_router(&self) -> Option<Address> {
+        self.router.get_or_default()
+    }
+    
+    /// Set the token gas rebates are paid in (once; admin only)
+    pub fn set_rebate_token(&mut self, token: Address) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        if self.rebate_token.get_or_default().is_some() {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.rebate_token.set(Some(token));
+    }
+    
+    /// Set the per-epoch rebate caps across all users and per user (admin only)
+    pub fn set_rebate_caps(&mut self, epoch_cap: U256, user_cap: U256) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        if user_cap > epoch_cap {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.epoch_rebate_cap.set(epoch_cap);
+        self.user_rebate_cap.set(user_cap);
+    }
+    
+    /// Withdraw escrowed rebate tokens not yet accrued to users (admin only)
+    pub fn withdraw_rebate_pool(&mut self, amount: U256, to: Address) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        let pool = self.rebate_pool.get_or_default();
+        if amount > pool {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        self.rebate_pool.set(pool - amount);
         
-        self.protocol_stats.set(stats);
+        let token = self.rebate_token.get_or_default()
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidConfiguration);
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(to, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+    }
+    
+    /// Get the token gas rebates are paid in
+    pub fn get_rebate_token(&self) -> Option<Address> {
+        self.rebate_token.get_or_default()
+    }
+    
+    /// Get the escrowed rebate tokens not yet accrued to users
+    pub fn get_rebate_pool(&self) -> U256 {
+        self.rebate_pool.get_or_default()
+    }
+    
+    /// Get the (per-epoch, per-user) rebate caps
+    pub fn get_rebate_caps(&self) -> (U256, U256) {
+        (self.epoch_rebate_cap.get_or_default(), self.user_rebate_cap.get_or_default())
+    }
+    
+    /// Get the rebates accrued in an epoch
+    pub fn get_epoch_rebated(&self, epoch: u64) -> U256 {
+        self.epoch_rebated.get(&epoch).unwrap_or_default()
+    }
+    
+    /// Get a user's accrued, unclaimed gas rebates
+    pub fn get_claimable_rebate(&self, user: Address) -> U256 {
+        self.claimable_rebates.get(&user).unwrap_or_default()
+    }
+    
+    /// Set the token season rewards are paid in (once; admin only)
+    pub fn set_season_reward_token(&mut self, token: Address) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        if self.season_reward_token.get_or_default().is_some() {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.season_reward_token.set(Some(token));
+    }
+    
+    /// Get the token season rewards are paid in
+    pub fn get_season_reward_token(&self) -> Option<Address> {
+        self.season_reward_token.get_or_default()
+    }
+    
+    /// Get the current season number
+    pub fn current_season(&self) -> u64 {
+        let elapsed = self.env().get_block_time().saturating_sub(self.season_start.get_or_default());
+        elapsed / self.season_duration.get_or_default().max(1)
+    }
+    
+    /// Get the timestamp a season starts at
+    pub fn get_season_start(&self, season: u64) -> u64 {
+        self.season_start.get_or_default() + season * self.season_duration.get_or_default()
+    }
+    
+    /// Get the rewards funded for a season
+    pub fn get_season_rewards(&self, season: u64) -> U256 {
+        self.season_rewards.get(&season).unwrap_or_default()
     }
     
-    /// Deposit funds to treasury
+    /// Get a user's score at the end of a season (the live score for the current one)
+    pub fn get_season_score(&self, user: Address, season: u64) -> U256 {
+        self.score_at(Some(user), season)
+    }
+    
+    /// Get the total score at the end of a season (the live total for the current one)
+    pub fn get_season_total_score(&self, season: u64) -> U256 {
+        self.score_at(None, season)
+    }
+    
+    /// Get a user's unclaimed share of a finished season's rewards
+    pub fn get_season_reward(&self, user: Address, season: u64) -> U256 {
+        if season >= self.current_season() || self.season_claimed.get(&(user, season)).unwrap_or(false) {
+            return U256::zero();
+        }
+        let total_score = self.score_at(None, season);
+        if total_score.is_zero() {
+            return U256::zero();
+        }
+        self.get_season_rewards(season) * self.score_at(Some(user), season) / total_score
+    }
+    
+    /// Get the number of pairs a user holds LP of
+    pub fn get_lp_pair_count(&self, user: Address) -> u32 {
+        self.lp_pair_count.get(&user).unwrap_or(0)
+    }
+    
+    /// Set the contracts TVL is aggregated from (admin only)
+    /// Pairs are read from the factory set with `set_factory`
+    pub fn set_tvl_sources(&mut self, sources: TvlSources) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.tvl_sources.set(sources);
+    }
+    
+    /// Record a treasury deposit (accounting only)
+    /// Funds are held by `governance::Treasury`
     pub fn deposit_to_treasury(&mut self, amount: U256) {
         let caller = self.env().caller();
         
-        // In production, transfer tokens here
-        // For now, just update balance
+        // Accounting only; deposit the tokens with the Treasury
         let current = self.treasury_balance.get_or_default();
         self.treasury_balance.set(current + amount);
         
@@ -405,19 +1012,152 @@ impl IncentiveManager {
     }
     
     // ========================================
-    // Helper Functions
+    // Access Control
     // ========================================
     
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = match self.admin.get() {
-            Some(addr) => addr,
-            None => self.env().revert(DexError::Unauthorized),
-        };
-        if caller != admin {
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    // ========================================
+    // Internal Functions
+    // ========================================
+    
+    fn new_user_metrics(user: Address) -> UserMetrics {
+        UserMetrics {
+            user,
+            has_lst: false,
+            has_yield: false,
+            has_dex_lp: false,
+            is_borrower: false,
+            gas_tier: 0,
+            lp_boost: U256::from(10u128.pow(18)), // 1.0x default
+        }
+    }
+    
+    /// Update gas tier and LP boost (bypassing the gas tier cache if `refresh`)
+    fn refresh_tiers(&mut self, metrics: &mut UserMetrics, refresh: bool) {
+        if let Some(gas_manager_address) = self.gas_discount_manager.get() {
+            let mut gas_manager = GasDiscountManagerContractRef::new(self.env(), gas_manager_address);
+            metrics.gas_tier = if refresh {
+                gas_manager.refresh_user_tier(metrics.user)
+            } else {
+                gas_manager.get_user_tier(metrics.user)
+            };
+        }
+        
+        if let Some(rewards_address) = self.rewards_distributor.get() {
+            let rewards_dist = LpRewardsDistributorContractRef::new(self.env(), rewards_address);
+            let boost_factors = rewards_dist.get_boost_factors(metrics.user);
+            metrics.lp_boost = boost_factors.total_multiplier;
+        }
+    }
+    
+    /// Revert unless `pair` is the configured Factory's pair for its tokens
+    fn ensure_factory_pair(&self, pair: Address) {
+        let factory_address = self.factory.get_or_default()
+            .unwrap_or_revert_with(&self.env(), DexError::Unauthorized);
+        let pair_ref = PairContractRef::new(self.env(), pair);
+        let factory = FactoryContractRef::new(self.env(), factory_address);
+        if factory.get_pair(pair_ref.token0(), pair_ref.token1()) != Some(pair) {
             self.env().revert(DexError::Unauthorized);
         }
     }
+    
+    /// ECTO value of a pair reserve, or None if the oracle cannot price it
+    fn reserve_value(
+        &self,
+        sources: &TvlSources,
+        oracle: &Option<PriceOracleContractRef>,
+        token: Address,
+        reserve: U256,
+    ) -> Option<U256> {
+        if sources.ecto_token == Some(token) {
+            return Some(reserve);
+        }
+        match oracle {
+            Some(oracle) if oracle.is_price_fresh(token) => Some(oracle.get_asset_value(token, reserve)),
+            _ => None,
+        }
+    }
+    
+    /// Store a user's metrics and checkpoint their season score
+    fn save_metrics(&mut self, user: Address, metrics: UserMetrics) {
+        let score = Self::metrics_score(&metrics);
+        self.user_metrics.set(&user, metrics);
+        
+        let season = self.current_season();
+        let previous = self.score_at(Some(user), season);
+        if score == previous {
+            return;
+        }
+        let total = self.score_at(None, season) + score - previous;
+        self.push_score(Some(user), season, score);
+        self.push_score(None, season, total);
+    }
+    
+    /// Whether a synced balance counts as a position (nonzero and at least `minimum`)
+    fn meets_minimum(balance: U256, minimum: U256) -> bool {
+        balance > U256::zero() && balance >= minimum
+    }
+    
+    /// Participation score: products used, weighted by the LP boost
+    /// Synced positions only count from the score minimums up
+    fn metrics_score(metrics: &UserMetrics) -> U256 {
+        let products = [metrics.has_lst, metrics.has_yield, metrics.has_dex_lp, metrics.is_borrower]
+            .iter()
+            .filter(|used| **used)
+            .count();
+        U256::from(products) * metrics.lp_boost
+    }
+    
+    /// Record a score for a season, replacing one already recorded in it
+    fn push_score(&mut self, key: Option<Address>, season: u64, score: U256) {
+        let count = self.score_checkpoint_count.get(&key).unwrap_or(0);
+        let last = count.checked_sub(1).and_then(|index| self.score_checkpoints.get(&(key, index)));
+        let index = match last {
+            Some(checkpoint) if checkpoint.season == season => count - 1,
+            _ => {
+                self.score_checkpoint_count.set(&key, count + 1);
+                count
+            }
+        };
+        self.score_checkpoints.set(&(key, index), ScoreCheckpoint { season, score });
+    }
+    
+    /// Score at the end of a season: the last checkpoint recorded at or before it
+    fn score_at(&self, key: Option<Address>, season: u64) -> U256 {
+        let (mut low, mut high) = (0u32, self.score_checkpoint_count.get(&key).unwrap_or(0));
+        while low < high {
+            let mid = (low + high) / 2;
+            match self.score_checkpoints.get(&(key, mid)) {
+                Some(checkpoint) if checkpoint.season <= season => low = mid + 1,
+                _ => high = mid,
+            }
+        }
+        if low == 0 {
+            return U256::zero();
+        }
+        self.score_checkpoints.get(&(key, low - 1))
+            .map(|checkpoint| checkpoint.score)
+            .unwrap_or_default()
+    }
+    
+    fn add_registered_user(&mut self, user: Address) {
+        let count = self.user_count.get_or_default();
+        self.registered_users.set(&count, user);
+        self.user_count.set(count + 1);
+        
+        // Update active users count
+        let mut stats = self.protocol_stats.get_or_default();
+        stats.active_users = count + 1;
+        self.protocol_stats.set(stats);
+    }
 }
 
 // ========================================
@@ -431,6 +1171,8 @@ pub struct UserDashboard {
     pub metrics: UserMetrics,
     pub total_apy: U256,
     pub gas_discount_percent: u8,
+    /// Points in the PointsLedger's current round
+    pub points: U256,
     pub protocol_stats: ProtocolStats,
 }
 
@@ -448,6 +1190,66 @@ pub struct UserActivityRegistered {
     pub timestamp: u64,
 }
 
+#[odra::event]
+pub struct LstPositionSynced {
+    pub user: Address,
+    pub has_lst: bool,
+    pub gas_tier: u8,
+    pub timestamp: u64,
+}
+
+#[odra::event]
+pub struct LpPositionSynced {
+    pub user: Address,
+    pub pair: Address,
+    pub has_dex_lp: bool,
+    pub lp_boost: U256,
+    pub timestamp: u64,
+}
+
+#[odra::event]
+pub struct DexTvlSynced {
+    pub dex_tvl: U256,
+    pub pair_count: u32,
+    pub timestamp: u64,
+}
+
+#[odra::event]
+pub struct GasRebateAccrued {
+    pub user: Address,
+    pub epoch: u64,
+    pub amount: U256,
+    pub timestamp: u64,
+}
+
+#[odra::event]
+pub struct GasRebatesFunded {
+    pub funder: Address,
+    pub amount: U256,
+    pub timestamp: u64,
+}
+
+#[odra::event]
+pub struct GasRebateClaimed {
+    pub user: Address,
+    pub amount: U256,
+    pub timestamp: u64,
+}
+
+#[odra::event]
+pub struct SeasonFunded {
+    pub season: u64,
+    pub amount: U256,
+    pub funded_by: Address,
+}
+
+#[odra::event]
+pub struct SeasonRewardClaimed {
+    pub user: Address,
+    pub season: u64,
+    pub amount: U256,
+}
+
 #[odra::event]
 pub struct TreasuryAllocated {
     pub total_amount: U256,
@@ -479,7 +1281,16 @@ use crate::errors::DexError;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use odra::host::{Deployer, HostEnv};
+    use crate::dex::mock_factory::{deploy_listed_pair, MockFactory, MockFactoryInitArgs};
+    use crate::dex::pair::PairHostRef;
+    use crate::dex::router::{Router, RouterInitArgs};
+    use crate::incentives::gas_discount::{GasDiscountManager, GasDiscountManagerInitArgs};
+    use crate::incentives::lp_rewards_distributor::{LpRewardsDistributor, LpRewardsDistributorInitArgs};
+    use crate::lending::lending_pool::{LendingPool, LendingPoolInitArgs};
+    use crate::lending::price_oracle::PriceOracle;
+    use crate::token::{LpToken, LpTokenInitArgs};
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
 
     #[test]
     fn test_user_registration() {
@@ -547,4 +1358,366 @@ mod tests {
         // Should be: 8% (LST) + 10% (Yield) + 15% (LP base) + 1% (gas) = 34%
         assert!(total_apy >= U256::from(30)); // At least 30%
     }
+
+    #[test]
+    fn test_scspr_transfer_hook_only_accepts_token() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let scspr = env.get_account(5);
+        let user = env.get_account(1);
+        
+        env.set_caller(admin);
+        let init_args = IncentiveManagerInitArgs {
+            gas_discount_manager_address: env.get_account(10),
+            rewards_distributor_address: env.get_account(11),
+            treasury_address: env.get_account(12),
+        };
+        
+        let mut manager = IncentiveManager::deploy(&env, init_args);
+        
+        // No token configured yet
+        assert_eq!(
+            manager.try_on_scspr_transfer(Some(user), None).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+        
+        manager.set_scspr_token(Some(scspr));
+        assert_eq!(manager.get_scspr_token(), Some(scspr));
+        
+        env.set_caller(user);
+        assert_eq!(
+            manager.try_on_scspr_transfer(Some(user), None).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn test_dust_lst_positions_do_not_score() {
+        use crate::lst::scspr_token::{ScsprToken, ScsprTokenInitArgs};
+        
+        let env = odra_test::env();
+        let (admin, dust, holder) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+        
+        let mut scspr = ScsprToken::deploy(&env, ScsprTokenInitArgs { staking_manager: admin });
+        let aecto = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("aECTO"),
+            symbol: String::from("aECTO"),
+        });
+        let lending_pool = LendingPool::deploy(&env, LendingPoolInitArgs {
+            aecto_vault_address: aecto.address(),
+            collateral_manager_address: admin,
+            interest_rate_strategy_address: admin,
+            liquidation_engine_address: admin,
+            price_oracle_address: admin,
+            ecto_token_address: aecto.address(),
+        });
+        let distributor = LpRewardsDistributor::deploy(&env, LpRewardsDistributorInitArgs {
+            scspr_token_address: scspr.address(),
+            aecto_token_address: aecto.address(),
+            lending_pool_address: lending_pool.address(),
+            reward_token_address: aecto.address(),
+        });
+        let gas_manager = GasDiscountManager::deploy(&env, GasDiscountManagerInitArgs {
+            scspr_token_address: scspr.address(),
+            aecto_token_address: aecto.address(),
+            treasury_address: admin,
+        });
+        let mut manager = IncentiveManager::deploy(&env, IncentiveManagerInitArgs {
+            gas_discount_manager_address: gas_manager.address(),
+            rewards_distributor_address: distributor.address(),
+            treasury_address: admin,
+        });
+        manager.set_scspr_token(Some(scspr.address()));
+        scspr.set_incentive_manager(Some(manager.address()));
+        
+        let minimum = U256::from(DEFAULT_MIN_LST_BALANCE);
+        scspr.mint(dust, minimum - U256::one());
+        scspr.mint(holder, minimum);
+        assert!(manager.get_user_metrics(dust).is_none());
+        assert_eq!(manager.get_season_score(dust, 0), U256::zero());
+        assert!(manager.get_user_metrics(holder).unwrap().has_lst);
+        assert!(manager.get_season_score(holder, 0) > U256::zero());
+        
+        // Dropping below the minimum stops the position from scoring
+        env.set_caller(holder);
+        scspr.transfer(dust, U256::one());
+        assert!(!manager.get_user_metrics(holder).unwrap().has_lst);
+        assert_eq!(manager.get_season_score(holder, 0), U256::zero());
+        assert!(manager.get_user_metrics(dust).unwrap().has_lst);
+    }
+
+    #[test]
+    fn test_pair_hooks_sync_lp_positions() {
+        let env = odra_test::env();
+        let (admin, lp, other) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+        
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut token_b = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token B"),
+            symbol: String::from("TKB"),
+        });
+        let mut factory = MockFactory::deploy(&env, MockFactoryInitArgs { fee_to_setter: admin });
+        let pair_address = deploy_listed_pair(&env, &mut factory, ecto.address(), token_b.address());
+        let mut router = Router::deploy(&env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: env.get_account(9),
+        });
+        
+        let lending_pool = LendingPool::deploy(&env, LendingPoolInitArgs {
+            aecto_vault_address: token_b.address(),
+            collateral_manager_address: admin,
+            interest_rate_strategy_address: admin,
+            liquidation_engine_address: admin,
+            price_oracle_address: admin,
+            ecto_token_address: ecto.address(),
+        });
+        let mut distributor = LpRewardsDistributor::deploy(&env, LpRewardsDistributorInitArgs {
+            scspr_token_address: token_b.address(),
+            aecto_token_address: token_b.address(),
+            lending_pool_address: lending_pool.address(),
+            reward_token_address: ecto.address(),
+        });
+        let gas_manager = GasDiscountManager::deploy(&env, GasDiscountManagerInitArgs {
+            scspr_token_address: token_b.address(),
+            aecto_token_address: token_b.address(),
+            treasury_address: admin,
+        });
+        let mut manager = IncentiveManager::deploy(&env, IncentiveManagerInitArgs {
+            gas_discount_manager_address: gas_manager.address(),
+            rewards_distributor_address: distributor.address(),
+            treasury_address: admin,
+        });
+        distributor.set_factory(factory.address());
+        manager.set_factory(Some(factory.address()));
+        // Count any LP balance of this small pool
+        manager.set_score_minimums(U256::zero(), U256::zero());
+        
+        env.set_caller(other);
+        assert_eq!(
+            manager.try_on_lp_transfer(Some(other), None).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+        env.set_caller(admin);
+        factory.set_incentive_hooks(Some(distributor.address()), Some(manager.address()));
+        
+        // Adding liquidity syncs the provider without any extra call
+        let seed = U256::from(1_000_000);
+        ecto.mint(admin, seed);
+        token_b.mint(admin, seed);
+        ecto.approve(router.address(), seed);
+        token_b.approve(router.address(), seed);
+        let (_, _, liquidity) = router.add_liquidity(
+            ecto.address(), token_b.address(), seed, seed, U256::zero(), U256::zero(), lp, env.block_time() + 1_000,
+        );
+        let mut pair = PairHostRef::new(pair_address, env.clone());
+        assert_eq!(distributor.get_lp_position(lp, pair_address).unwrap().lp_amount, liquidity);
+        assert!(manager.get_user_metrics(lp).unwrap().has_dex_lp);
+        assert_eq!(manager.get_lp_pair_count(lp), 1);
+        
+        // Transfers move the position and the flag to the receiver
+        env.set_caller(lp);
+        pair.transfer(other, liquidity);
+        assert_eq!(distributor.get_lp_position(lp, pair_address).unwrap().lp_amount, U256::zero());
+        assert_eq!(distributor.get_lp_position(other, pair_address).unwrap().lp_amount, liquidity);
+        assert!(!manager.get_user_metrics(lp).unwrap().has_dex_lp);
+        assert!(manager.get_user_metrics(other).unwrap().has_dex_lp);
+        assert_eq!(manager.get_lp_pair_count(lp), 0);
+    }
+
+    #[test]
+    fn test_router_transactions_accrue_capped_gas_rebates() {
+        let env = odra_test::env();
+        let (admin, user) = (env.get_account(0), env.get_account(1));
+        env.set_caller(admin);
+        
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut token_b = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token B"),
+            symbol: String::from("TKB"),
+        });
+        let mut scspr = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Staked CSPR"),
+            symbol: String::from("sCSPR"),
+        });
+        let mut factory = MockFactory::deploy(&env, MockFactoryInitArgs { fee_to_setter: admin });
+        deploy_listed_pair(&env, &mut factory, ecto.address(), token_b.address());
+        let mut router = Router::deploy(&env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: env.get_account(9),
+        });
+        let gas_manager = GasDiscountManager::deploy(&env, GasDiscountManagerInitArgs {
+            scspr_token_address: scspr.address(),
+            aecto_token_address: token_b.address(),
+            treasury_address: admin,
+        });
+        let mut manager = IncentiveManager::deploy(&env, IncentiveManagerInitArgs {
+            gas_discount_manager_address: gas_manager.address(),
+            rewards_distributor_address: env.get_account(11),
+            treasury_address: admin,
+        });
+        
+        // Escrow 1,000 ECTO; a 1,000 ECTO transaction at 25% earns 250, capped at 200 per user
+        manager.set_router(Some(router.address()));
+        manager.set_rebate_token(ecto.address());
+        manager.set_rebate_caps(U256::from(300), U256::from(200));
+        ecto.mint(admin, U256::from(1_000));
+        ecto.approve(manager.address(), U256::from(1_000));
+        manager.fund_gas_rebates(U256::from(1_000));
+        router.set_incentive_manager(manager.address(), U256::from(1_000));
+        scspr.mint(user, U256::from(500) * U256::from(10u128.pow(9)));
+        
+        assert_eq!(
+            manager.try_process_dex_transaction(user, U256::from(1_000)).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+        
+        let seed = U256::from(1_000_000);
+        ecto.mint(user, seed * 2);
+        token_b.mint(user, seed * 2);
+        env.set_caller(user);
+        ecto.approve(router.address(), seed * 2);
+        token_b.approve(router.address(), seed * 2);
+        router.add_liquidity(
+            ecto.address(), token_b.address(), seed, seed, U256::zero(), U256::zero(), user, env.block_time() + 1_000,
+        );
+        assert_eq!(manager.get_claimable_rebate(user), U256::from(200));
+        assert_eq!(manager.get_rebate_pool(), U256::from(800));
+        
+        // The user cap is used up for this epoch
+        router.add_liquidity(
+            ecto.address(), token_b.address(), seed, seed, U256::zero(), U256::zero(), user, env.block_time() + 1_000,
+        );
+        assert_eq!(manager.get_claimable_rebate(user), U256::from(200));
+        
+        let balance = ecto.balance_of(user);
+        assert_eq!(manager.claim_gas_rebate(), U256::from(200));
+        assert_eq!(ecto.balance_of(user), balance + U256::from(200));
+        assert_eq!(manager.try_claim_gas_rebate().unwrap_err(), DexError::InsufficientAmount.into());
+        
+        // Caps reset with the next epoch
+        env.advance_block_time(REBATE_EPOCH_SECONDS);
+        router.add_liquidity(
+            ecto.address(), token_b.address(), U256::from(1_000), U256::from(1_000), U256::zero(), U256::zero(),
+            user, env.block_time() + 1_000,
+        );
+        assert_eq!(manager.get_claimable_rebate(user), U256::from(200));
+    }
+
+    #[test]
+    fn test_season_rewards_follow_snapshots() {
+        let env = odra_test::env();
+        let (admin, alice, bob) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+        
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut manager = IncentiveManager::deploy(&env, IncentiveManagerInitArgs {
+            gas_discount_manager_address: env.get_account(10),
+            rewards_distributor_address: env.get_account(11),
+            treasury_address: env.get_account(12),
+        });
+        manager.set_season_reward_token(ecto.address());
+        ecto.mint(admin, U256::from(1_000));
+        ecto.approve(manager.address(), U256::from(1_000));
+        manager.fund_season(0, U256::from(1_000));
+        
+        // Alice uses three products, Bob one
+        manager.register_user_activity(alice, true, true, true, false);
+        manager.register_user_activity(bob, true, false, false, false);
+        assert_eq!(
+            manager.try_claim_season_rewards(0).unwrap_err(),
+            DexError::EpochNotClosed.into()
+        );
+        
+        // Bob's activity after season 0 ended does not change its split
+        env.advance_block_time(DEFAULT_SEASON_DURATION);
+        assert_eq!(manager.current_season(), 1);
+        manager.register_user_activity(bob, true, true, true, true);
+        assert_eq!(manager.get_season_reward(alice, 0), U256::from(750));
+        assert_eq!(manager.get_season_reward(bob, 0), U256::from(250));
+        
+        env.set_caller(alice);
+        assert_eq!(manager.claim_season_rewards(0), U256::from(750));
+        assert_eq!(ecto.balance_of(alice), U256::from(750));
+        assert_eq!(
+            manager.try_claim_season_rewards(0).unwrap_err(),
+            DexError::AlreadyClaimed.into()
+        );
+        assert_eq!(manager.get_season_score(bob, 1), manager.get_season_score(bob, 0) * U256::from(4));
+    }
+
+    #[test]
+    fn test_tvl_aggregated_from_sources() {
+        
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        env.set_caller(admin);
+        
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut token_b = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token B"),
+            symbol: String::from("TKB"),
+        });
+        let mut token_c = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token C"),
+            symbol: String::from("TKC"),
+        });
+        let mut factory = MockFactory::deploy(&env, MockFactoryInitArgs { fee_to_setter: admin });
+        deploy_listed_pair(&env, &mut factory, ecto.address(), token_b.address());
+        deploy_listed_pair(&env, &mut factory, ecto.address(), token_c.address());
+        let mut router = Router::deploy(&env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: env.get_account(9),
+        });
+        let lending_pool = LendingPool::deploy(&env, LendingPoolInitArgs {
+            aecto_vault_address: token_b.address(),
+            collateral_manager_address: admin,
+            interest_rate_strategy_address: admin,
+            liquidation_engine_address: admin,
+            price_oracle_address: admin,
+            ecto_token_address: ecto.address(),
+        });
+        let mut oracle = PriceOracle::deploy(&env, NoArgs);
+        let mut manager = IncentiveManager::deploy(&env, IncentiveManagerInitArgs {
+            gas_discount_manager_address: env.get_account(10),
+            rewards_distributor_address: env.get_account(11),
+            treasury_address: env.get_account(12),
+        });
+        manager.set_factory(Some(factory.address()));
+        manager.set_tvl_sources(TvlSources {
+            staking_manager: None,
+            lending_pool: Some(lending_pool.address()),
+            price_oracle: Some(oracle.address()),
+            ecto_token: Some(ecto.address()),
+        });
+        
+        let seed = U256::from(1_000_000);
+        ecto.mint(admin, seed * U256::from(2));
+        ecto.approve(router.address(), seed * U256::from(2));
+        for token in [&mut token_b, &mut token_c] {
+            token.mint(admin, seed);
+            token.approve(router.address(), seed);
+            router.add_liquidity(
+                ecto.address(), token.address(), seed, seed, U256::zero(), U256::zero(), admin, env.block_time() + 1_000,
+            );
+        }
+        
+        // DEX TVL is published once a sweep covers every pair
+        assert_eq!(manager.sync_dex_tvl(1), 1);
+        assert_eq!(manager.get_tvl().dex, U256::zero());
+        assert_eq!(manager.sync_dex_tvl(1), 0);
+        // Unpriced sides are valued like the ECTO side
+        assert_eq!(manager.get_tvl().dex, seed * U256::from(4));
+        assert_eq!(manager.get_dex_tvl_updated_at(), env.block_time());
+        
+        oracle.set_price(token_b.address(), U256::from(2_000_000_000_000_000_000u128));
+        assert_eq!(manager.get_dex_tvl_page(0, 1), seed * U256::from(3));
+        assert_eq!(manager.get_tvl().dex, seed * U256::from(4));
+        assert_eq!(manager.sync_dex_tvl(10), 0);
+        let tvl = manager.get_tvl();
+        assert_eq!(tvl.dex, seed * U256::from(5));
+        assert_eq!(tvl.lending, U256::zero());
+        assert_eq!(manager.get_protocol_stats().total_tvl, tvl.total);
+    }
 }
@@ -0,0 +1,344 @@
+//! Keeper Registry
+//!
+//! Pays ECTO bounties to keepers for running the protocol's periodic
+//! maintenance calls, so none of them depend on an admin remembering.
+//!
+//! Mechanics:
+//! - The registrar registers a task: what to call, on which contract, how
+//!   often at most, and the bounty it pays
+//! - Anyone funds the shared bounty pool with ECTO
+//! - Anyone calls `execute(task_id)`; the call reverts unless the task's
+//!   interval has passed and its condition held, so keepers are only paid
+//!   for work that did something
+//!
+//! The registry needs the role each task's target requires (e.g. KEEPER_ROLE
+//! on the StakingManager for rebalancing).
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::governance::emission_minter::EmissionMinterContractRef;
+use crate::governance::fee_distributor::FeeDistributorContractRef;
+use crate::lst::staking_manager::StakingManagerContractRef;
+use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE, REGISTRAR_ROLE};
+
+/// Maintenance call a task runs, and the condition it must meet to pay out
+#[odra::odra_type]
+pub enum TaskKind {
+    /// StakingManager::compound; validator rewards must have accrued
+    LstCompound,
+    /// StakingManager::auto_rebalance; interval only
+    LstRebalance,
+    /// EmissionMinter::update_period; the next epoch must have started
+    EmissionPeriod,
+    /// FeeDistributor::collect_lending_reserves; reserves must be collected
+    ReserveCollection,
+}
+
+/// Registered task
+#[odra::odra_type]
+pub struct Task {
+    /// Call to run
+    pub kind: TaskKind,
+    /// Contract the call runs on
+    pub target: Address,
+    /// ECTO paid to the keeper per execution
+    pub bounty: U256,
+    /// Minimum seconds between executions
+    pub min_interval: u64,
+    /// Earliest timestamp the task can run again
+    pub next_run: u64,
+    /// Whether the task can be executed
+    pub active: bool,
+}
+
+/// Keeper Registry contract
+#[odra::module]
+pub struct KeeperRegistry {
+    /// Bounty token address (ECTO)
+    bounty_token: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Tasks by ID
+    tasks: Mapping<u32, Task>,
+    /// Number of tasks
+    task_count: Var<u32>,
+    /// ECTO available for bounties
+    bounty_pool: Var<U256>,
+    /// Bounties paid per keeper (keeper -> amount)
+    keeper_earnings: Mapping<Address, U256>,
+}
+
+#[odra::module]
+impl KeeperRegistry {
+    /// Initialize the registry
+    pub fn init(&mut self, bounty_token_address: Address) {
+        let caller = self.env().caller();
+        self.bounty_token.set(bounty_token_address);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(REGISTRAR_ROLE, caller);
+        self.task_count.set(0);
+        self.bounty_pool.set(U256::zero());
+    }
+
+    /// Register a task (registrar only)
+    pub fn register_task(&mut self, kind: TaskKind, target: Address, bounty: U256, min_interval: u64) -> u32 {
+        self.access.require_role(REGISTRAR_ROLE, DexError::Unauthorized);
+
+        let task_id = self.task_count.get_or_default();
+        self.tasks.set(&task_id, Task {
+            kind: kind.clone(),
+            target,
+            bounty,
+            min_interval,
+            next_run: self.env().get_block_time(),
+            active: true,
+        });
+        self.task_count.set(task_id + 1);
+
+        self.env().emit_event(TaskRegistered {
+            task_id,
+            kind,
+            target,
+            bounty,
+            min_interval,
+        });
+
+        task_id
+    }
+
+    /// Update a task's bounty, interval and status (registrar only)
+    pub fn update_task(&mut self, task_id: u32, bounty: U256, min_interval: u64, active: bool) {
+        self.access.require_role(REGISTRAR_ROLE, DexError::Unauthorized);
+
+        let mut task = self.get_task_or_revert(task_id);
+        task.bounty = bounty;
+        task.min_interval = min_interval;
+        task.active = active;
+        self.tasks.set(&task_id, task);
+    }
+
+    /// Add ECTO to the bounty pool (pulled from the caller)
+    pub fn fund(&mut self, amount: U256) {
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        let caller = self.env().caller();
+        let mut token = Cep18TokenContractRef::new(self.env(), self.bounty_token.get_or_revert_with(DexError::InvalidConfiguration));
+        if !token.transfer_from(caller, self.env().self_address(), amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+        self.bounty_pool.set(self.bounty_pool.get_or_default() + amount);
+    }
+
+    /// Run a task and pay its bounty to the caller
+    /// Reverts if the task is inactive, ran too recently, its condition did not
+    /// hold, or the pool cannot cover the bounty. Returns the bounty paid.
+    pub fn execute(&mut self, task_id: u32) -> U256 {
+        let mut task = self.get_task_or_revert(task_id);
+        let now = self.env().get_block_time();
+        if !task.active || now < task.next_run {
+            self.env().revert(DexError::TaskConditionNotMet);
+        }
+
+        let pool = self.bounty_pool.get_or_default();
+        if pool < task.bounty {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        if !self.run(&task) {
+            self.env().revert(DexError::TaskConditionNotMet);
+        }
+
+        task.next_run = now + task.min_interval;
+        let bounty = task.bounty;
+        self.tasks.set(&task_id, task);
+
+        let keeper = self.env().caller();
+        if bounty > U256::zero() {
+            self.bounty_pool.set(pool - bounty);
+            let earnings = self.keeper_earnings.get(&keeper).unwrap_or_default();
+            self.keeper_earnings.set(&keeper, earnings + bounty);
+
+            let mut token = Cep18TokenContractRef::new(self.env(), self.bounty_token.get_or_revert_with(DexError::InvalidConfiguration));
+            if !token.transfer(keeper, bounty) {
+                self.env().revert(DexError::TransferFailed);
+            }
+        }
+
+        self.env().emit_event(TaskExecuted {
+            task_id,
+            keeper,
+            bounty,
+            timestamp: now,
+        });
+
+        bounty
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get a task
+    pub fn get_task(&self, task_id: u32) -> Option<Task> {
+        self.tasks.get(&task_id)
+    }
+
+    /// Get the number of tasks
+    pub fn get_task_count(&self) -> u32 {
+        self.task_count.get_or_default()
+    }
+
+    /// Check whether a task's interval has passed and the pool covers its bounty
+    /// The task's own condition is only known once it runs
+    pub fn is_task_due(&self, task_id: u32) -> bool {
+        match self.tasks.get(&task_id) {
+            Some(task) => {
+                task.active
+                    && self.env().get_block_time() >= task.next_run
+                    && self.bounty_pool.get_or_default() >= task.bounty
+            }
+            None => false,
+        }
+    }
+
+    /// Get the ECTO available for bounties
+    pub fn get_bounty_pool(&self) -> U256 {
+        self.bounty_pool.get_or_default()
+    }
+
+    /// Get the bounties a keeper has been paid
+    pub fn get_keeper_earnings(&self, keeper: Address) -> U256 {
+        self.keeper_earnings.get(&keeper).unwrap_or_default()
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn get_task_or_revert(&self, task_id: u32) -> Task {
+        self.tasks.get(&task_id)
+            .unwrap_or_revert_with(&self.env(), DexError::TaskNotFound)
+    }
+
+    /// Run a task's call, returning whether its condition held
+    fn run(&self, task: &Task) -> bool {
+        match task.kind {
+            TaskKind::LstCompound => {
+                StakingManagerContractRef::new(self.env(), task.target).compound() > U256::zero()
+            }
+            TaskKind::LstRebalance => {
+                StakingManagerContractRef::new(self.env(), task.target).auto_rebalance();
+                true
+            }
+            TaskKind::EmissionPeriod => {
+                let mut minter = EmissionMinterContractRef::new(self.env(), task.target);
+                if self.env().get_block_time() < minter.get_epoch_start(minter.get_next_epoch()) {
+                    return false;
+                }
+                minter.update_period();
+                true
+            }
+            TaskKind::ReserveCollection => {
+                FeeDistributorContractRef::new(self.env(), task.target).collect_lending_reserves() > U256::zero()
+            }
+        }
+    }
+}
+
+// ========================================
+// Events
+// ========================================
+
+/// Emitted when a task is registered
+#[odra::event]
+pub struct TaskRegistered {
+    pub task_id: u32,
+    pub kind: TaskKind,
+    pub target: Address,
+    pub bounty: U256,
+    pub min_interval: u64,
+}
+
+/// Emitted when a keeper executes a task
+#[odra::event]
+pub struct TaskExecuted {
+    pub task_id: u32,
+    pub keeper: Address,
+    pub bounty: U256,
+    pub timestamp: u64,
+}
+
+// Import error type
+use crate::errors::DexError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::emission_minter::{EmissionMinter, EmissionMinterInitArgs};
+    use crate::governance::gauge_controller::{GaugeController, GaugeControllerInitArgs};
+    use crate::governance::voting_escrow::{VotingEscrow, VotingEscrowInitArgs, WEEK};
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    #[test]
+    fn test_keeper_paid_only_when_task_runs() {
+        let env = odra_test::env();
+        let (admin, keeper, treasury) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let ve = VotingEscrow::deploy(&env, VotingEscrowInitArgs {
+            token_address: ecto.address(),
+        });
+        let controller = GaugeController::deploy(&env, GaugeControllerInitArgs {
+            voting_escrow_address: ve.address(),
+        });
+        let mut minter = EmissionMinter::deploy(&env, EmissionMinterInitArgs {
+            ecto_token_address: ecto.address(),
+            gauge_controller_address: controller.address(),
+            lp_rewards_address: env.get_account(3),
+            treasury_address: treasury,
+            initial_emission: U256::from(1_000),
+        });
+        // Send the whole emission to the treasury so the test needs no gauges
+        minter.set_shares(0, 0, 10_000);
+
+        let mut registry = KeeperRegistry::deploy(&env, KeeperRegistryInitArgs {
+            bounty_token_address: ecto.address(),
+        });
+        let task_id = registry.register_task(TaskKind::EmissionPeriod, minter.address(), U256::from(10), WEEK);
+        ecto.mint(admin, U256::from(15));
+        ecto.approve(registry.address(), U256::from(15));
+        registry.fund(U256::from(15));
+
+        // Nothing to do before the first epoch starts
+        env.set_caller(keeper);
+        assert!(registry.is_task_due(task_id));
+        assert_eq!(registry.try_execute(task_id).unwrap_err(), DexError::TaskConditionNotMet.into());
+
+        env.advance_block_time(minter.get_epoch_start(0) - env.block_time());
+        assert_eq!(registry.execute(task_id), U256::from(10));
+        assert_eq!(ecto.balance_of(keeper), U256::from(10));
+        assert_eq!(ecto.balance_of(treasury), U256::from(1_000));
+
+        // Interval not elapsed, then pool too small for another bounty
+        assert_eq!(registry.try_execute(task_id).unwrap_err(), DexError::TaskConditionNotMet.into());
+        env.advance_block_time(WEEK);
+        assert!(!registry.is_task_due(task_id));
+        assert_eq!(registry.try_execute(task_id).unwrap_err(), DexError::InsufficientAmount.into());
+        assert_eq!(registry.get_keeper_earnings(keeper), U256::from(10));
+    }
+}
@@ -0,0 +1,308 @@
+//! Lending Incentives
+//!
+//! Streams ECTO to lending suppliers and borrowers.
+//!
+//! Mechanics:
+//! - Each market is a balance-tracking token: aECTO for suppliers and
+//!   dECTO for borrowers
+//! - Markets report a holder's new balance and the market total after every
+//!   change via `handle_action`; the caller is the market
+//! - Each market emits `emission_per_second` ECTO, split pro-rata by balance
+//! - Holders claim across any set of markets with `claim_rewards`
+//!
+//! The contract pays out of its own ECTO balance, so emissions must be funded
+//! by transferring ECTO to it.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// Reward state of a market
+#[odra::odra_type]
+pub struct MarketRewards {
+    /// ECTO emitted per second across the market
+    pub emission_per_second: U256,
+    /// Accumulated ECTO per unit of balance (scaled by 1e18)
+    pub index: U256,
+    /// Last index update timestamp
+    pub last_update: u64,
+    /// Market total balance as last reported
+    pub total_balance: U256,
+}
+
+/// Reward state of a holder in a market
+#[odra::odra_type]
+pub struct UserRewards {
+    /// Holder balance as last reported
+    pub balance: U256,
+    /// Market index at the last accrual
+    pub index: U256,
+    /// ECTO accrued and not yet claimed
+    pub accrued: U256,
+}
+
+/// Lending Incentives contract
+#[odra::module]
+pub struct LendingIncentives {
+    /// Reward token address (ECTO)
+    reward_token: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Reward state per market (market -> MarketRewards)
+    markets: Mapping<Address, MarketRewards>,
+    /// Reward state per holder (market + user -> UserRewards)
+    user_rewards: Mapping<(Address, Address), UserRewards>,
+    /// Total rewards claimed
+    total_claimed: Var<U256>,
+}
+
+#[odra::module]
+impl LendingIncentives {
+    /// Initialize the incentives controller
+    pub fn init(&mut self, reward_token_address: Address) {
+        let caller = self.env().caller();
+
+        self.reward_token.set(reward_token_address);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.total_claimed.set(U256::zero());
+    }
+
+    // ========================================
+    // Market Hook
+    // ========================================
+
+    /// Report a holder's balance after it changed (called by the market)
+    ///
+    /// Rewards up to now are accrued on the previously reported balances
+    /// before the new ones are stored.
+    pub fn handle_action(&mut self, user: Address, user_balance: U256, total_balance: U256) {
+        let market = self.env().caller();
+
+        let mut rewards = self.accrue_market(market);
+        let mut user_state = self.accrue_user(market, user, &rewards);
+
+        user_state.balance = user_balance;
+        rewards.total_balance = total_balance;
+
+        self.user_rewards.set(&(market, user), user_state);
+        self.markets.set(&market, rewards);
+    }
+
+    // ========================================
+    // Claiming
+    // ========================================
+
+    /// Claim the caller's rewards across `assets` (market addresses)
+    pub fn claim_rewards(&mut self, assets: Vec<Address>) -> U256 {
+        let user = self.env().caller();
+
+        let mut total = U256::zero();
+        for market in assets {
+            let rewards = self.accrue_market(market);
+            let mut user_state = self.accrue_user(market, user, &rewards);
+            total = total + user_state.accrued;
+
+            user_state.accrued = U256::zero();
+            self.user_rewards.set(&(market, user), user_state);
+            self.markets.set(&market, rewards);
+        }
+
+        if total.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        self.total_claimed.set(self.total_claimed.get_or_default() + total);
+
+        let reward_token = self.reward_token.get_or_revert_with(DexError::InvalidConfiguration);
+        let mut token = Cep18TokenContractRef::new(self.env(), reward_token);
+        if !token.transfer(user, total) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        self.env().emit_event(LendingRewardsClaimed {
+            user,
+            amount: total,
+            timestamp: self.env().get_block_time(),
+        });
+
+        total
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get the rewards a holder could claim across `assets` right now
+    pub fn get_unclaimed_rewards(&self, user: Address, assets: Vec<Address>) -> U256 {
+        let mut total = U256::zero();
+        for market in assets {
+            let rewards = self.accrue_market(market);
+            total = total + self.accrue_user(market, user, &rewards).accrued;
+        }
+        total
+    }
+
+    /// Get a market's reward state
+    pub fn get_market(&self, market: Address) -> Option<MarketRewards> {
+        self.markets.get(&market)
+    }
+
+    /// Get a holder's reward state in a market
+    pub fn get_user_rewards(&self, market: Address, user: Address) -> Option<UserRewards> {
+        self.user_rewards.get(&(market, user))
+    }
+
+    /// Get total rewards claimed
+    pub fn get_total_claimed(&self) -> U256 {
+        self.total_claimed.get_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Set a market's ECTO emission per second (admin only)
+    pub fn set_emission_rate(&mut self, market: Address, emission_per_second: U256) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+
+        // Settle the old rate up to now before switching
+        let mut rewards = self.accrue_market(market);
+        rewards.emission_per_second = emission_per_second;
+        self.markets.set(&market, rewards);
+
+        self.env().emit_event(EmissionRateUpdated {
+            market,
+            emission_per_second,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// Market state with the index advanced to now (not stored)
+    fn accrue_market(&self, market: Address) -> MarketRewards {
+        let now = self.env().get_block_time();
+        let mut rewards = self.markets.get(&market).unwrap_or(MarketRewards {
+            emission_per_second: U256::zero(),
+            index: U256::zero(),
+            last_update: now,
+            total_balance: U256::zero(),
+        });
+
+        if rewards.total_balance > U256::zero() && now > rewards.last_update {
+            let emitted = rewards.emission_per_second * U256::from(now - rewards.last_update);
+            rewards.index = rewards.index
+                + (emitted * U256::from(1_000_000_000_000_000_000u128)) / rewards.total_balance; // 1e18
+        }
+        rewards.last_update = now;
+        rewards
+    }
+
+    /// Holder state with rewards accrued up to the market index (not stored)
+    fn accrue_user(&self, market: Address, user: Address, rewards: &MarketRewards) -> UserRewards {
+        let mut user_state = self.user_rewards.get(&(market, user)).unwrap_or(UserRewards {
+            balance: U256::zero(),
+            index: U256::zero(),
+            accrued: U256::zero(),
+        });
+
+        let index_delta = rewards.index - user_state.index;
+        user_state.accrued = user_state.accrued
+            + (user_state.balance * index_delta) / U256::from(1_000_000_000_000_000_000u128); // 1e18
+        user_state.index = rewards.index;
+        user_state
+    }
+}
+
+// ========================================
+// Events
+// ========================================
+
+/// Emitted when a holder claims lending rewards
+#[odra::event]
+pub struct LendingRewardsClaimed {
+    pub user: Address,
+    pub amount: U256,
+    pub timestamp: u64,
+}
+
+/// Emitted when a market's emission rate is updated
+#[odra::event]
+pub struct EmissionRateUpdated {
+    pub market: Address,
+    pub emission_per_second: U256,
+    pub updated_by: Address,
+}
+
+// Import error type
+use crate::errors::DexError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    #[test]
+    fn test_rewards_stream_pro_rata_and_claim() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let market = env.get_account(1);
+        let alice = env.get_account(2);
+        let bob = env.get_account(3);
+
+        env.set_caller(admin);
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut incentives = LendingIncentives::deploy(&env, LendingIncentivesInitArgs {
+            reward_token_address: ecto.address(),
+        });
+        ecto.mint(incentives.address(), U256::from(10_000));
+        incentives.set_emission_rate(market, U256::from(10));
+
+        env.set_caller(market);
+        incentives.handle_action(alice, U256::from(100), U256::from(100));
+        env.advance_block_time(100);
+        incentives.handle_action(bob, U256::from(300), U256::from(400));
+        env.advance_block_time(100);
+
+        // Alice earns all of the first 1000, then a quarter of the next 1000
+        assert_eq!(incentives.get_unclaimed_rewards(alice, vec![market]), U256::from(1_250));
+        assert_eq!(incentives.get_unclaimed_rewards(bob, vec![market]), U256::from(750));
+
+        env.set_caller(alice);
+        assert_eq!(incentives.claim_rewards(vec![market]), U256::from(1_250));
+        assert_eq!(ecto.balance_of(alice), U256::from(1_250));
+        assert_eq!(incentives.try_claim_rewards(vec![market]).unwrap_err(), DexError::InsufficientAmount.into());
+    }
+
+    #[test]
+    fn test_only_admin_sets_emission_rate() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let market = env.get_account(1);
+
+        env.set_caller(admin);
+        let mut incentives = LendingIncentives::deploy(&env, LendingIncentivesInitArgs {
+            reward_token_address: env.get_account(10),
+        });
+
+        env.set_caller(market);
+        assert_eq!(
+            incentives.try_set_emission_rate(market, U256::from(10)).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+    }
+}
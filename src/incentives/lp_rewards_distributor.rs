@@ -9,11 +9,26 @@
 //! - +0.5x: Active borrower (borrowing ECTO)
 //! - +0.2x: Hold sCSPR (supporting network security)
 //! - Max: 2.0x total multiplier
+//! 
+//! LP positions are synced by anyone from the pair's own `balance_of`, and
+//! only pairs created by the configured Factory are accepted. LP tokens held
+//! are credited to the PointsLedger as TVL-days when a position updates.
+//! 
+//! Farming StakingPools using this distributor as their boost source apply
+//! the same multiplier to stake weights, so LP boosts and farm rewards stay
+//! in one system.
 
 use odra::prelude::*;
 use odra::casper_types::U256;
 use odra::ContractRef;
 use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE, REGISTRAR_ROLE};
+use crate::dex::factory::FactoryContractRef;
+use crate::dex::pair::{PairContractRef, SECONDS_PER_DAY};
+use crate::incentives::points_ledger::{PointsAction, PointsLedgerContractRef};
+
+/// Trailing window (days) used to compute a pair's base APR from its fee stats
+pub const APR_WINDOW_DAYS: u64 = 7;
 
 /// LP position with boost information
 #[odra::odra_type]
@@ -30,6 +45,8 @@ pub struct LpPosition {
     pub boost_multiplier: U256,
     /// Effective APR after boost
     pub effective_apr: U256,
+    /// Rewards accrued before the last sync, not yet claimed
+    pub pending_rewards: U256,
     /// Last update timestamp
     pub last_update: u64,
 }
@@ -58,8 +75,8 @@ pub struct LpRewardsDistributor {
     lending_pool: Var<Address>,
     /// Reward token address (ECTO)
     reward_token: Var<Address>,
-    /// Admin address
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     /// LP positions (user + pair -> LpPosition)
     lp_positions: Mapping<(Address, Address), LpPosition>,
     /// Total rewards distributed
@@ -80,6 +97,10 @@ pub struct LpRewardsDistributor {
     borrower_boost: Var<U256>,
     /// sCSPR boost (0.2e18 = 0.2x)
     scspr_boost: Var<U256>,
+    /// Factory whose pairs can hold LP positions
+    factory: Var<Option<Address>>,
+    /// PointsLedger credited with LP TVL-days
+    points_ledger: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -98,7 +119,8 @@ impl LpRewardsDistributor {
         self.aecto_token.set(aecto_token_address);
         self.lending_pool.set(lending_pool_address);
         self.reward_token.set(reward_token_address);
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(REGISTRAR_ROLE, caller);
         
         self.total_rewards_distributed.set(U256::zero());
         self.rewards_pool.set(U256::zero());
@@ -118,18 +140,30 @@ impl LpRewardsDistributor {
         self.scspr_boost.set(scale * U256::from(2) / U256::from(10)); // 0.2x
     }
     
-    /// Register or update an LP position
-    pub fn register_lp_position(
-        &mut self,
-        user: Address,
-        pair: Address,
-        lp_amount: U256,
-        base_apr: U256,
-    ) {
+    /// Sync `user`'s LP position in `pair` with their LP balance (anyone)
+    /// 
+    /// The LP amount is read from the pair's `balance_of` and the base APR
+    /// from its on-chain fee stats, so callers cannot inflate a position.
+    /// Rewards accrued at the previous amount are kept for the next claim.
+    pub fn sync_lp_position(&mut self, user: Address, pair: Address) {
         if !self.enabled.get_or_default() {
             return;
         }
         
+        self.ensure_factory_pair(pair);
+        
+        let current_time = self.env().get_block_time();
+        let pending_rewards = match self.lp_positions.get(&(user, pair)) {
+            Some(position) => {
+                self.credit_tvl_days(&position, current_time);
+                position.pending_rewards + self.accrued_rewards(&position, current_time)
+            }
+            None => U256::zero(),
+        };
+        
+        let lp_amount = PairContractRef::new(self.env(), pair).balance_of(user);
+        let base_apr = self.get_pair_base_apr(pair);
+        
         // Calculate boost multiplier
         let boost_factors = self.calculate_boost_factors(user);
         let boost_multiplier = boost_factors.total_multiplier;
@@ -146,7 +180,8 @@ impl LpRewardsDistributor {
             base_apr,
             boost_multiplier,
             effective_apr,
-            last_update: self.env().get_block_time(),
+            pending_rewards,
+            last_update: current_time,
         };
         
         self.lp_positions.set(&(user, pair), position);
@@ -158,7 +193,7 @@ impl LpRewardsDistributor {
             lp_amount,
             boost_multiplier,
             effective_apr,
-            timestamp: self.env().get_block_time(),
+            timestamp: current_time,
         });
     }
     
@@ -227,19 +262,9 @@ impl LpRewardsDistributor {
         
         let position = position.unwrap();
         
-        // Calculate rewards since last update
+        // Rewards kept from earlier syncs plus those since the last update
         let current_time = self.env().get_block_time();
-        let time_elapsed = current_time - position.last_update;
-        
-        // Calculate rewards based on effective APR
-        // rewards = (lp_amount * effective_apr * time_elapsed) / (365 days * 1e18)
-        let seconds_per_year = U256::from(365 * 24 * 60 * 60);
-        let scale = U256::from(10u128.pow(18));
-        
-        let rewards = position.lp_amount
-            * position.effective_apr
-            * U256::from(time_elapsed)
-            / (seconds_per_year * scale);
+        let rewards = position.pending_rewards + self.accrued_rewards(&position, current_time);
         
         if rewards == U256::zero() {
             return U256::zero();
@@ -259,7 +284,9 @@ impl LpRewardsDistributor {
         self.total_rewards_distributed.set(total + rewards);
         
         // Update position timestamp
+        self.credit_tvl_days(&position, current_time);
         let mut updated_position = position;
+        updated_position.pending_rewards = U256::zero();
         updated_position.last_update = current_time;
         self.lp_positions.set(&(caller, pair), updated_position);
         
@@ -279,40 +306,8 @@ impl LpRewardsDistributor {
         rewards
     }
     
-    /// Update an existing LP position (e.g., when LP amount changes)
-    pub fn update_lp_position(
-        &mut self,
-        user: Address,
-        pair: Address,
-        new_lp_amount: U256,
-    ) {
-        let position = self.lp_positions.get(&(user, pair));
-        if position.is_none() {
-            // If no position exists, register a new one with default base APR
-            self.register_lp_position(user, pair, new_lp_amount, U256::from(15)); // 15% default
-            return;
-        }
-        
-        let mut position = position.unwrap();
-        
-        // Recalculate boost
-        let boost_factors = self.calculate_boost_factors(user);
-        let boost_multiplier = boost_factors.total_multiplier;
-        
-        let scale = U256::from(10u128.pow(18));
-        let effective_apr = position.base_apr * boost_multiplier / scale;
-        
-        // Update position
-        position.lp_amount = new_lp_amount;
-        position.boost_multiplier = boost_multiplier;
-        position.effective_apr = effective_apr;
-        position.last_update = self.env().get_block_time();
-        
-        self.lp_positions.set(&(user, pair), position);
-    }
-    
     // Note: Odra Mapping doesn't support remove()
-    // To "remove" a position, set lp_amount to zero using update_lp_position
+    // A position drops to zero once the LP tokens leave and sync_lp_position is called
     // /// Remove an LP position
     // pub fn remove_lp_position(&mut self, user: Address, pair: Address) {
     //     // self.lp_positions.remove(&(user, pair));
@@ -328,6 +323,44 @@ impl LpRewardsDistributor {
     // Helper Functions
     // ========================================
     
+    /// Rewards earned by `position` since its last update
+    fn accrued_rewards(&self, position: &LpPosition, current_time: u64) -> U256 {
+        let time_elapsed = current_time - position.last_update;
+        
+        // rewards = (lp_amount * effective_apr * time_elapsed) / (365 days * 1e18)
+        let seconds_per_year = U256::from(365 * 24 * 60 * 60);
+        let scale = U256::from(10u128.pow(18));
+        
+        position.lp_amount
+            * position.effective_apr
+            * U256::from(time_elapsed)
+            / (seconds_per_year * scale)
+    }
+    
+    /// Credit the LP tokens `position` held since its last update as TVL-days
+    fn credit_tvl_days(&self, position: &LpPosition, current_time: u64) {
+        let ledger = match self.points_ledger.get_or_default() {
+            Some(ledger) => ledger,
+            None => return,
+        };
+        let tvl_days = position.lp_amount * U256::from(current_time - position.last_update)
+            / U256::from(SECONDS_PER_DAY);
+        if !tvl_days.is_zero() {
+            PointsLedgerContractRef::new(self.env(), ledger).credit(position.user, PointsAction::TvlDays, tvl_days);
+        }
+    }
+    
+    /// Revert unless `pair` was created by the configured factory
+    fn ensure_factory_pair(&self, pair: Address) {
+        let factory_address = self.factory.get_or_default()
+            .unwrap_or_revert_with(&self.env(), DexError::InvalidPair);
+        let pair_ref = PairContractRef::new(self.env(), pair);
+        let factory = FactoryContractRef::new(self.env(), factory_address);
+        if factory.get_pair(pair_ref.token0(), pair_ref.token1()) != Some(pair) {
+            self.env().revert(DexError::InvalidPair);
+        }
+    }
+    
     /// Get user's aECTO balance
     fn get_aecto_balance(&self, user: Address) -> U256 {
         let aecto_address = match self.aecto_token.get() {
@@ -377,6 +410,12 @@ impl LpRewardsDistributor {
         self.lp_positions.get(&(user, pair))
     }
     
+    /// Get a pair's base APR (scaled by 1e18) from its trailing fee stats
+    pub fn get_pair_base_apr(&self, pair: Address) -> U256 {
+        let pair_ref = PairStatsContractRef::new(self.env(), pair);
+        pair_ref.get_fee_apr(APR_WINDOW_DAYS)
+    }
+    
     /// Get boost factors for a user
     pub fn get_boost_factors(&self, user: Address) -> BoostFactors {
         self.calculate_boost_factors(user)
@@ -400,19 +439,7 @@ impl LpRewardsDistributor {
         }
         
         let position = position.unwrap();
-        
-        let current_time = self.env().get_block_time();
-        let time_elapsed = current_time - position.last_update;
-        
-        let seconds_per_year = U256::from(365 * 24 * 60 * 60);
-        let scale = U256::from(10u128.pow(18));
-        
-        let rewards = position.lp_amount
-            * position.effective_apr
-            * U256::from(time_elapsed)
-            / (seconds_per_year * scale);
-        
-        rewards
+        position.pending_rewards + self.accrued_rewards(&position, self.env().get_block_time())
     }
     
     // ========================================
@@ -446,7 +473,7 @@ impl LpRewardsDistributor {
         borrower_boost: U256,
         scspr_boost: U256,
     ) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         
         self.aecto_boost.set(aecto_boost);
         self.borrower_boost.set(borrower_boost);
@@ -462,15 +489,37 @@ impl LpRewardsDistributor {
     
     /// Update minimum balances for boosts
     pub fn update_min_balances(&mut self, min_aecto: U256, min_scspr: U256) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         
         self.min_aecto_for_boost.set(min_aecto);
         self.min_scspr_for_boost.set(min_scspr);
     }
     
+    /// Set the Factory whose pairs can hold LP positions (admin only)
+    pub fn set_factory(&mut self, factory: Address) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.factory.set(Some(factory));
+    }
+    
+    /// Get the Factory whose pairs can hold LP positions
+    pub fn get_factory(&self) -> Option<Address> {
+        self.factory.get_or_default()
+    }
+    
+    /// Set the PointsLedger credited with LP TVL-days (None disables)
+    pub fn set_points_ledger(&mut self, points_ledger: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.points_ledger.set(points_ledger);
+    }
+    
+    /// Get the PointsLedger credited with LP TVL-days
+    pub fn get_points_ledger(&self) -> Option<Address> {
+        self.points_ledger.get_or_default()
+    }
+    
     /// Enable or disable boosts
     pub fn set_enabled(&mut self, enabled: bool) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
         self.enabled.set(enabled);
         
         self.env().emit_event(BoostsToggled {
@@ -479,14 +528,16 @@ impl LpRewardsDistributor {
         });
     }
     
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = match self.admin.get() {
-            Some(addr) => addr,
-            None => self.env().revert(DexError::Unauthorized),
-        };
-        if caller != admin {
-            self.env().revert(DexError::Unauthorized);
+    // ========================================
+    // Access Control
+    // ========================================
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
         }
     }
 }
@@ -500,6 +551,11 @@ trait LendingPool {
     fn get_borrow_position(&self, user: Address) -> Option<BorrowPosition>;
 }
 
+#[odra::external_contract]
+trait PairStats {
+    fn get_fee_apr(&self, days: u64) -> U256;
+}
+
 #[odra::odra_type]
 pub struct BorrowPosition {
     pub user: Address,
@@ -563,7 +619,13 @@ use crate::errors::DexError;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use odra::host::{Deployer, HostEnv};
+    use crate::dex::mock_factory::{deploy_listed_pair, MockFactory, MockFactoryInitArgs};
+    use crate::dex::pair::PairHostRef;
+    use crate::dex::router::{Router, RouterInitArgs};
+    use crate::lending::lending_pool::{LendingPool as LendingPoolModule, LendingPoolInitArgs};
+    use crate::token::{LpToken, LpTokenInitArgs};
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
 
     #[test]
     fn test_boost_calculation() {
@@ -576,14 +638,14 @@ mod tests {
         let reward_token = env.get_account(13);
         
         env.set_caller(admin);
-        let init_args = RewardsDistributorInitArgs {
+        let init_args = LpRewardsDistributorInitArgs {
             scspr_token_address: scspr_token,
             aecto_token_address: aecto_token,
             lending_pool_address: lending_pool,
             reward_token_address: reward_token,
         };
         
-        let distributor = RewardsDistributor::deploy(&env, init_args);
+        let distributor = LpRewardsDistributor::deploy(&env, init_args);
         
         // Test base multiplier (no boosts)
         let user = env.get_account(1);
@@ -595,4 +657,75 @@ mod tests {
         assert_eq!(factors.is_borrower, false);
         assert_eq!(factors.has_scspr, false);
     }
+
+    #[test]
+    fn test_sync_reads_lp_balance_from_factory_pair() {
+        let env = odra_test::env();
+        let (admin, lp, other) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut token_b = LpToken::deploy(&env, LpTokenInitArgs {
+            name: String::from("Token B"),
+            symbol: String::from("TKB"),
+        });
+        let mut factory = MockFactory::deploy(&env, MockFactoryInitArgs { fee_to_setter: admin });
+        let pair_address = deploy_listed_pair(&env, &mut factory, ecto.address(), token_b.address());
+        let mut router = Router::deploy(&env, RouterInitArgs {
+            factory: factory.address(),
+            wcspr: env.get_account(9),
+        });
+        let seed = U256::from(1_000_000);
+        ecto.mint(admin, seed);
+        token_b.mint(admin, seed);
+        ecto.approve(router.address(), seed);
+        token_b.approve(router.address(), seed);
+        router.add_liquidity(
+            ecto.address(), token_b.address(), seed, seed, U256::zero(), U256::zero(), lp, env.block_time() + 1_000,
+        );
+        let mut pair = PairHostRef::new(pair_address, env.clone());
+
+        let lending_pool = LendingPoolModule::deploy(&env, LendingPoolInitArgs {
+            aecto_vault_address: token_b.address(),
+            collateral_manager_address: admin,
+            interest_rate_strategy_address: admin,
+            liquidation_engine_address: admin,
+            price_oracle_address: admin,
+            ecto_token_address: ecto.address(),
+        });
+        let mut distributor = LpRewardsDistributor::deploy(&env, LpRewardsDistributorInitArgs {
+            scspr_token_address: token_b.address(),
+            aecto_token_address: token_b.address(),
+            lending_pool_address: lending_pool.address(),
+            reward_token_address: ecto.address(),
+        });
+
+        // Nothing can be synced until the factory is set
+        env.set_caller(other);
+        assert_eq!(
+            distributor.try_sync_lp_position(lp, pair_address).unwrap_err(),
+            DexError::InvalidPair.into()
+        );
+        assert_eq!(
+            distributor.try_set_factory(factory.address()).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+        env.set_caller(admin);
+        distributor.set_factory(factory.address());
+
+        // Anyone can sync, but only to the pair's real balance
+        env.set_caller(other);
+        distributor.sync_lp_position(lp, pair_address);
+        let balance = pair.balance_of(lp);
+        assert!(balance > U256::zero());
+        assert_eq!(distributor.get_lp_position(lp, pair_address).unwrap().lp_amount, balance);
+        assert!(distributor.try_sync_lp_position(lp, token_b.address()).is_err());
+
+        env.set_caller(lp);
+        pair.transfer(other, balance);
+        distributor.sync_lp_position(lp, pair_address);
+        distributor.sync_lp_position(other, pair_address);
+        assert_eq!(distributor.get_lp_position(lp, pair_address).unwrap().lp_amount, U256::zero());
+        assert_eq!(distributor.get_lp_position(other, pair_address).unwrap().lp_amount, balance);
+    }
 }
@@ -0,0 +1,362 @@
+//! Merkle Distributor
+//!
+//! Settles airdrops and off-chain points conversions on-chain with one
+//! Merkle root per campaign instead of one transfer per recipient.
+//!
+//! Mechanics:
+//! - The admin creates a campaign with a root, a token budget and an expiry
+//! - Each leaf is `hash(account, amount)` (bytesrepr encoded); inner nodes
+//!   hash their two children in ascending order
+//! - Anyone can submit a claim with its proof; the tokens go to the account
+//! - After expiry the admin sweeps what was left unclaimed
+
+use odra::prelude::*;
+use odra::casper_types::bytesrepr::ToBytes;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// Airdrop campaign
+#[odra::odra_type]
+pub struct Campaign {
+    /// Token being distributed
+    pub token: Address,
+    /// Root of the (account, amount) tree
+    pub merkle_root: [u8; 32],
+    /// Tokens funded for the campaign
+    pub total_amount: U256,
+    /// Tokens claimed so far
+    pub claimed_amount: U256,
+    /// Claims close at this timestamp
+    pub expiry: u64,
+    /// Whether the unclaimed remainder has been swept
+    pub swept: bool,
+}
+
+/// Merkle Distributor contract
+#[odra::module]
+pub struct MerkleDistributor {
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Campaigns by ID
+    campaigns: Mapping<u32, Campaign>,
+    /// Number of campaigns
+    campaign_count: Var<u32>,
+    /// Claimed flags ((campaign_id, account) -> claimed)
+    claimed: Mapping<(u32, Address), bool>,
+}
+
+#[odra::module]
+impl MerkleDistributor {
+    /// Initialize the distributor
+    pub fn init(&mut self) {
+        let caller = self.env().caller();
+        self.access.grant(ADMIN_ROLE, caller);
+        self.campaign_count.set(0);
+    }
+
+    /// Create a campaign funded with `total_amount` of `token` pulled from the caller (admin only)
+    pub fn create_campaign(
+        &mut self,
+        token: Address,
+        merkle_root: [u8; 32],
+        total_amount: U256,
+        expiry: u64,
+    ) -> u32 {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+
+        if total_amount == U256::zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+        if expiry <= self.env().get_block_time() {
+            self.env().revert(DexError::CampaignExpired);
+        }
+
+        let caller = self.env().caller();
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer_from(caller, self.env().self_address(), total_amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        let campaign_id = self.campaign_count.get_or_default();
+        self.campaigns.set(&campaign_id, Campaign {
+            token,
+            merkle_root,
+            total_amount,
+            claimed_amount: U256::zero(),
+            expiry,
+            swept: false,
+        });
+        self.campaign_count.set(campaign_id + 1);
+
+        self.env().emit_event(CampaignCreated {
+            campaign_id,
+            token,
+            merkle_root,
+            total_amount,
+            expiry,
+        });
+
+        campaign_id
+    }
+
+    /// Claim `amount` for `account` from a campaign (anyone can submit)
+    pub fn claim(&mut self, campaign_id: u32, account: Address, amount: U256, proof: Vec<[u8; 32]>) {
+        let mut campaign = self.campaigns.get(&campaign_id)
+            .unwrap_or_revert_with(&self.env(), DexError::CampaignNotFound);
+
+        if self.env().get_block_time() >= campaign.expiry {
+            self.env().revert(DexError::CampaignExpired);
+        }
+        if self.is_claimed(campaign_id, account) {
+            self.env().revert(DexError::AlreadyClaimed);
+        }
+        if !self.verify_proof(campaign.merkle_root, self.leaf_hash(account, amount), &proof) {
+            self.env().revert(DexError::InvalidProof);
+        }
+
+        // A root that overcommits the budget cannot pay out other campaigns' tokens
+        if campaign.claimed_amount + amount > campaign.total_amount {
+            self.env().revert(DexError::CampaignBudgetExceeded);
+        }
+
+        self.claimed.set(&(campaign_id, account), true);
+        campaign.claimed_amount += amount;
+        let token = campaign.token;
+        self.campaigns.set(&campaign_id, campaign);
+
+        let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+        if !token_ref.transfer(account, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        self.env().emit_event(AirdropClaimed {
+            campaign_id,
+            account,
+            amount,
+        });
+    }
+
+    /// Send a campaign's unclaimed tokens to `to` once it has expired (admin only)
+    pub fn sweep(&mut self, campaign_id: u32, to: Address) -> U256 {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+
+        let mut campaign = self.campaigns.get(&campaign_id)
+            .unwrap_or_revert_with(&self.env(), DexError::CampaignNotFound);
+        if self.env().get_block_time() < campaign.expiry {
+            self.env().revert(DexError::CampaignNotExpired);
+        }
+        if campaign.swept {
+            self.env().revert(DexError::AlreadyClaimed);
+        }
+
+        let amount = campaign.total_amount - campaign.claimed_amount;
+        campaign.swept = true;
+        let token = campaign.token;
+        self.campaigns.set(&campaign_id, campaign);
+
+        if amount > U256::zero() {
+            let mut token_ref = Cep18TokenContractRef::new(self.env(), token);
+            if !token_ref.transfer(to, amount) {
+                self.env().revert(DexError::TransferFailed);
+            }
+        }
+
+        self.env().emit_event(CampaignSwept {
+            campaign_id,
+            to,
+            amount,
+        });
+
+        amount
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get a campaign
+    pub fn get_campaign(&self, campaign_id: u32) -> Option<Campaign> {
+        self.campaigns.get(&campaign_id)
+    }
+
+    /// Get the number of campaigns
+    pub fn get_campaign_count(&self) -> u32 {
+        self.campaign_count.get_or_default()
+    }
+
+    /// Check whether `account` has claimed from a campaign
+    pub fn is_claimed(&self, campaign_id: u32, account: Address) -> bool {
+        self.claimed.get(&(campaign_id, account)).unwrap_or(false)
+    }
+
+    /// Check a claim against a campaign's root without submitting it
+    pub fn verify_claim(&self, campaign_id: u32, account: Address, amount: U256, proof: Vec<[u8; 32]>) -> bool {
+        match self.campaigns.get(&campaign_id) {
+            Some(campaign) => self.verify_proof(campaign.merkle_root, self.leaf_hash(account, amount), &proof),
+            None => false,
+        }
+    }
+
+    /// Get the leaf hash of an (account, amount) entry
+    pub fn leaf_hash(&self, account: Address, amount: U256) -> [u8; 32] {
+        let data = (account, amount).to_bytes()
+            .unwrap_or_else(|_| self.env().revert(DexError::InvalidProof));
+        self.env().hash(data)
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// Hash `leaf` up through `proof` and compare with `root`
+    fn verify_proof(&self, root: [u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+        let mut node = leaf;
+        for sibling in proof {
+            let mut data = Vec::with_capacity(64);
+            if node <= *sibling {
+                data.extend_from_slice(&node);
+                data.extend_from_slice(sibling);
+            } else {
+                data.extend_from_slice(sibling);
+                data.extend_from_slice(&node);
+            }
+            node = self.env().hash(data);
+        }
+        node == root
+    }
+}
+
+// ========================================
+// Events
+// ========================================
+
+/// Emitted when a campaign is created
+#[odra::event]
+pub struct CampaignCreated {
+    pub campaign_id: u32,
+    pub token: Address,
+    pub merkle_root: [u8; 32],
+    pub total_amount: U256,
+    pub expiry: u64,
+}
+
+/// Emitted when an airdrop is claimed
+#[odra::event]
+pub struct AirdropClaimed {
+    pub campaign_id: u32,
+    pub account: Address,
+    pub amount: U256,
+}
+
+/// Emitted when a campaign's unclaimed tokens are swept
+#[odra::event]
+pub struct CampaignSwept {
+    pub campaign_id: u32,
+    pub to: Address,
+    pub amount: U256,
+}
+
+// Import error type
+use crate::errors::DexError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::EctoToken;
+    use odra::casper_types::crypto::blake2b;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    fn leaf(account: Address, amount: u64) -> [u8; 32] {
+        blake2b((account, U256::from(amount)).to_bytes().unwrap())
+    }
+
+    fn node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        blake2b([first, second].concat())
+    }
+
+    #[test]
+    fn test_claim_with_proof_then_sweep() {
+        let env = odra_test::env();
+        let (admin, alice, bob, carol) = (env.get_account(0), env.get_account(1), env.get_account(2), env.get_account(3));
+        env.set_caller(admin);
+
+        // Tree: ((alice, bob), carol)
+        let (a, b, c) = (leaf(alice, 100), leaf(bob, 200), leaf(carol, 300));
+        let ab = node(a, b);
+        let root = node(ab, c);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut distributor = MerkleDistributor::deploy(&env, NoArgs);
+        assert_eq!(distributor.leaf_hash(alice, U256::from(100)), a);
+        ecto.mint(admin, U256::from(600));
+        ecto.approve(distributor.address(), U256::from(600));
+        let expiry = env.block_time() + 1_000;
+        let id = distributor.create_campaign(ecto.address(), root, U256::from(600), expiry);
+
+        // Anyone can relay a claim; the tokens go to the account
+        env.set_caller(carol);
+        distributor.claim(id, alice, U256::from(100), vec![b, c]);
+        assert_eq!(ecto.balance_of(alice), U256::from(100));
+        assert_eq!(
+            distributor.try_claim(id, alice, U256::from(100), vec![b, c]).unwrap_err(),
+            DexError::AlreadyClaimed.into()
+        );
+        assert_eq!(
+            distributor.try_claim(id, bob, U256::from(250), vec![a, c]).unwrap_err(),
+            DexError::InvalidProof.into()
+        );
+        distributor.claim(id, carol, U256::from(300), vec![ab]);
+        assert!(distributor.verify_claim(id, bob, U256::from(200), vec![a, c]));
+
+        env.set_caller(admin);
+        assert_eq!(distributor.try_sweep(id, admin).unwrap_err(), DexError::CampaignNotExpired.into());
+        env.advance_block_time(1_000);
+        assert_eq!(
+            distributor.try_claim(id, bob, U256::from(200), vec![a, c]).unwrap_err(),
+            DexError::CampaignExpired.into()
+        );
+        assert_eq!(distributor.sweep(id, admin), U256::from(200));
+        assert_eq!(ecto.balance_of(admin), U256::from(200));
+        assert!(distributor.get_campaign(id).unwrap().swept);
+    }
+
+    #[test]
+    fn test_claims_cannot_exceed_the_campaign_budget() {
+        let env = odra_test::env();
+        let (admin, alice, bob) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+
+        // The tree promises 300 but the campaign is funded with 250
+        let (a, b) = (leaf(alice, 100), leaf(bob, 200));
+        let root = node(a, b);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut distributor = MerkleDistributor::deploy(&env, NoArgs);
+        ecto.mint(admin, U256::from(1_000));
+        ecto.approve(distributor.address(), U256::from(1_000));
+        let expiry = env.block_time() + 1_000;
+        let id = distributor.create_campaign(ecto.address(), root, U256::from(250), expiry);
+        // A second campaign's funds sit in the same contract
+        distributor.create_campaign(ecto.address(), [0u8; 32], U256::from(500), expiry);
+
+        distributor.claim(id, alice, U256::from(100), vec![b]);
+        assert_eq!(
+            distributor.try_claim(id, bob, U256::from(200), vec![a]).unwrap_err(),
+            DexError::CampaignBudgetExceeded.into()
+        );
+        assert_eq!(ecto.balance_of(distributor.address()), U256::from(650));
+        assert_eq!(distributor.get_campaign(id).unwrap().claimed_amount, U256::from(100));
+    }
+}
@@ -1,7 +1,19 @@
 pub mod gas_discount;
 pub mod lp_rewards_distributor;
 pub mod incentive_manager;
+pub mod trade_mining;
+pub mod lending_incentives;
+pub mod merkle_distributor;
+pub mod keeper_registry;
+pub mod referral_registry;
+pub mod points_ledger;
 
 pub use gas_discount::*;
 pub use lp_rewards_distributor::*;
 pub use incentive_manager::*;
+pub use trade_mining::*;
+pub use lending_incentives::*;
+pub use merkle_distributor::*;
+pub use keeper_registry::*;
+pub use referral_registry::*;
+pub use points_ledger::*;
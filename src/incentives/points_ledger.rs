@@ -0,0 +1,337 @@
+//! Points Ledger
+//!
+//! Loyalty points protocol modules credit for qualifying actions, converted
+//! to ECTO in rounds through the MerkleDistributor.
+//!
+//! Mechanics:
+//! - Reporter modules credit users per action: swap volume (TradeMining),
+//!   LP TVL-days (LpRewardsDistributor) and borrows (LendingPool)
+//! - Each action has a rate (points per 1e18 units) and a per-user daily cap
+//! - Points accrue in the current round; the admin closes a round by opening
+//!   its conversion window, tied to a MerkleDistributor campaign built from
+//!   the round's balances; the campaign's expiry ends the window
+//! - Later credits go to the next round, so no point converts twice
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::dex::pair::SECONDS_PER_DAY;
+use crate::incentives::merkle_distributor::MerkleDistributorContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// Action that earns points
+#[odra::odra_type]
+pub enum PointsAction {
+    /// Swap input volume
+    Volume,
+    /// LP tokens held, times days held
+    TvlDays,
+    /// Amount borrowed
+    Borrow,
+}
+
+/// Points earned by an action
+#[odra::odra_type]
+#[derive(Default)]
+pub struct ActionConfig {
+    /// Points per 1e18 units of the action (scaled by 1e18)
+    pub rate: U256,
+    /// Max points per user per day (0 = no cap)
+    pub daily_cap: U256,
+}
+
+/// Conversion window of a closed round
+#[odra::odra_type]
+pub struct ConversionWindow {
+    /// MerkleDistributor paying the round out
+    pub distributor: Address,
+    /// Campaign built from the round's balances
+    pub campaign_id: u32,
+    /// Timestamp the round closed and conversion opened
+    pub opens_at: u64,
+    /// Timestamp conversion closes (the campaign's expiry)
+    pub closes_at: u64,
+}
+
+/// Points Ledger contract
+#[odra::module]
+pub struct PointsLedger {
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Modules allowed to credit points
+    reporters: Mapping<Address, bool>,
+    /// Points earned per action
+    action_configs: Mapping<PointsAction, ActionConfig>,
+    /// Round points are currently credited to
+    current_round: Var<u32>,
+    /// Points per user per round ((user, round) -> points)
+    points: Mapping<(Address, u32), U256>,
+    /// Points per round (round -> points)
+    round_totals: Mapping<u32, U256>,
+    /// Points credited per user per action per day ((user, action, day) -> points)
+    daily_points: Mapping<(Address, PointsAction, u64), U256>,
+    /// Points ever credited per user
+    lifetime_points: Mapping<Address, U256>,
+    /// Conversion windows of closed rounds (round -> window)
+    windows: Mapping<u32, ConversionWindow>,
+}
+
+#[odra::module]
+impl PointsLedger {
+    /// Initialize the ledger
+    pub fn init(&mut self) {
+        let caller = self.env().caller();
+        self.access.grant(ADMIN_ROLE, caller);
+        self.current_round.set(0);
+    }
+
+    /// Credit points for `amount` of `action` by `user` (reporters only)
+    /// Returns the points credited after the daily cap
+    pub fn credit(&mut self, user: Address, action: PointsAction, amount: U256) -> U256 {
+        if !self.is_reporter(self.env().caller()) {
+            self.env().revert(DexError::Unauthorized);
+        }
+
+        let config = self.action_configs.get(&action).unwrap_or_default();
+        let earned = amount * config.rate / U256::from(10u128.pow(18));
+
+        let day = self.env().get_block_time() / SECONDS_PER_DAY;
+        let key = (user, action.clone(), day);
+        let credited_today = self.daily_points.get(&key).unwrap_or_default();
+        let points = if config.daily_cap.is_zero() {
+            earned
+        } else {
+            earned.min(config.daily_cap.saturating_sub(credited_today))
+        };
+        if points.is_zero() {
+            return U256::zero();
+        }
+
+        let round = self.current_round.get_or_default();
+        self.daily_points.set(&key, credited_today + points);
+        let balance = self.points.get(&(user, round)).unwrap_or_default();
+        self.points.set(&(user, round), balance + points);
+        let total = self.round_totals.get(&round).unwrap_or_default();
+        self.round_totals.set(&round, total + points);
+        let lifetime = self.get_lifetime_points(user);
+        self.lifetime_points.set(&user, lifetime + points);
+
+        self.env().emit_event(PointsCredited {
+            user,
+            action,
+            round,
+            points,
+        });
+
+        points
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get a user's points in the current round
+    pub fn get_points(&self, user: Address) -> U256 {
+        self.get_round_points(user, self.current_round.get_or_default())
+    }
+
+    /// Get a user's points in a round
+    pub fn get_round_points(&self, user: Address, round: u32) -> U256 {
+        self.points.get(&(user, round)).unwrap_or_default()
+    }
+
+    /// Get all points credited in a round
+    pub fn get_round_total(&self, round: u32) -> U256 {
+        self.round_totals.get(&round).unwrap_or_default()
+    }
+
+    /// Get the points ever credited to a user
+    pub fn get_lifetime_points(&self, user: Address) -> U256 {
+        self.lifetime_points.get(&user).unwrap_or_default()
+    }
+
+    /// Get the round points are currently credited to
+    pub fn get_current_round(&self) -> u32 {
+        self.current_round.get_or_default()
+    }
+
+    /// Get a closed round's conversion window
+    pub fn get_conversion_window(&self, round: u32) -> Option<ConversionWindow> {
+        self.windows.get(&round)
+    }
+
+    /// Check whether a round's points can currently be converted
+    pub fn is_conversion_open(&self, round: u32) -> bool {
+        match self.windows.get(&round) {
+            Some(window) => self.env().get_block_time() < window.closes_at,
+            None => false,
+        }
+    }
+
+    /// Get the points an action earns
+    pub fn get_action_config(&self, action: PointsAction) -> ActionConfig {
+        self.action_configs.get(&action).unwrap_or_default()
+    }
+
+    /// Get the points a user can still earn today for an action (None = no cap)
+    pub fn get_remaining_daily_cap(&self, user: Address, action: PointsAction) -> Option<U256> {
+        let config = self.action_configs.get(&action).unwrap_or_default();
+        if config.daily_cap.is_zero() {
+            return None;
+        }
+        let day = self.env().get_block_time() / SECONDS_PER_DAY;
+        let credited_today = self.daily_points.get(&(user, action, day)).unwrap_or_default();
+        Some(config.daily_cap.saturating_sub(credited_today))
+    }
+
+    /// Check whether an address may credit points
+    pub fn is_reporter(&self, account: Address) -> bool {
+        self.reporters.get(&account).unwrap_or(false)
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Allow or disallow a module to credit points
+    pub fn set_reporter(&mut self, reporter: Address, allowed: bool) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.reporters.set(&reporter, allowed);
+    }
+
+    /// Set the points an action earns and its per-user daily cap (0 = no cap)
+    pub fn set_action_config(&mut self, action: PointsAction, rate: U256, daily_cap: U256) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.action_configs.set(&action, ActionConfig { rate, daily_cap });
+    }
+
+    /// Close the current round and open its conversion window (admin only)
+    /// The MerkleDistributor campaign must pay out the round's balances as
+    /// read from `get_round_points`; the window closes at its expiry.
+    /// Returns the round closed.
+    pub fn open_conversion(&mut self, distributor: Address, campaign_id: u32) -> u32 {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+
+        let campaign = MerkleDistributorContractRef::new(self.env(), distributor)
+            .get_campaign(campaign_id)
+            .unwrap_or_revert_with(&self.env(), DexError::CampaignNotFound);
+        let now = self.env().get_block_time();
+        if campaign.expiry <= now {
+            self.env().revert(DexError::CampaignExpired);
+        }
+
+        let round = self.current_round.get_or_default();
+        self.windows.set(&round, ConversionWindow {
+            distributor,
+            campaign_id,
+            opens_at: now,
+            closes_at: campaign.expiry,
+        });
+        self.current_round.set(round + 1);
+
+        self.env().emit_event(ConversionWindowOpened {
+            round,
+            distributor,
+            campaign_id,
+            total_points: self.get_round_total(round),
+            closes_at: campaign.expiry,
+        });
+
+        round
+    }
+
+    // ========================================
+    // Access Control
+    // ========================================
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+}
+
+// ========================================
+// Events
+// ========================================
+
+/// Emitted when a module credits points
+#[odra::event]
+pub struct PointsCredited {
+    pub user: Address,
+    pub action: PointsAction,
+    pub round: u32,
+    pub points: U256,
+}
+
+/// Emitted when a round closes and its conversion window opens
+#[odra::event]
+pub struct ConversionWindowOpened {
+    pub round: u32,
+    pub distributor: Address,
+    pub campaign_id: u32,
+    pub total_points: U256,
+    pub closes_at: u64,
+}
+
+// Import error type
+use crate::errors::DexError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::incentives::merkle_distributor::MerkleDistributor;
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    #[test]
+    fn test_capped_credits_and_conversion_rounds() {
+        let env = odra_test::env();
+        let (admin, module, user) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut distributor = MerkleDistributor::deploy(&env, NoArgs);
+        let mut ledger = PointsLedger::deploy(&env, NoArgs);
+        ledger.set_reporter(module, true);
+        // 1 point per unit borrowed, at most 150 a day
+        ledger.set_action_config(PointsAction::Borrow, U256::from(10u128.pow(18)), U256::from(150));
+
+        env.set_caller(user);
+        assert_eq!(
+            ledger.try_credit(user, PointsAction::Borrow, U256::from(100)).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+
+        env.set_caller(module);
+        assert_eq!(ledger.credit(user, PointsAction::Borrow, U256::from(100)), U256::from(100));
+        assert_eq!(ledger.credit(user, PointsAction::Borrow, U256::from(100)), U256::from(50));
+        assert_eq!(ledger.get_remaining_daily_cap(user, PointsAction::Borrow), Some(U256::zero()));
+        // Unconfigured actions earn nothing
+        assert_eq!(ledger.credit(user, PointsAction::Volume, U256::from(100)), U256::zero());
+
+        // Close round 0 against a campaign paying its balances
+        env.set_caller(admin);
+        ecto.mint(admin, U256::from(150));
+        ecto.approve(distributor.address(), U256::from(150));
+        let expiry = env.block_time() + 7 * SECONDS_PER_DAY;
+        let campaign_id = distributor.create_campaign(ecto.address(), [0u8; 32], U256::from(150), expiry);
+        assert_eq!(ledger.open_conversion(distributor.address(), campaign_id), 0);
+        assert!(ledger.is_conversion_open(0));
+        assert_eq!(ledger.get_round_total(0), U256::from(150));
+
+        // The cap resets daily and new credits go to round 1
+        env.advance_block_time(SECONDS_PER_DAY);
+        env.set_caller(module);
+        ledger.credit(user, PointsAction::Borrow, U256::from(100));
+        assert_eq!(ledger.get_round_points(user, 0), U256::from(150));
+        assert_eq!(ledger.get_points(user), U256::from(100));
+        assert_eq!(ledger.get_lifetime_points(user), U256::from(250));
+
+        env.advance_block_time(7 * SECONDS_PER_DAY);
+        assert!(!ledger.is_conversion_open(0));
+    }
+}
@@ -0,0 +1,432 @@
+//! Referral Registry
+//!
+//! Protocol-wide referral program: referrers earn a share of the fees the
+//! users they bring in generate, paid in ECTO.
+//!
+//! Mechanics:
+//! - A user names their referrer with `set_referrer` before using the protocol
+//! - The referral is bound on the user's first interaction reported by a
+//!   protocol contract (Router swap or liquidity, StakingManager stake,
+//!   aECTO deposit); users who interact without a referrer stay unreferred
+//! - Reporters report fees users generate; for `referral_duration` seconds
+//!   after binding, the referrer accrues `referral_bps` of their ECTO value
+//! - Accruals are paid from a funded ECTO pool and claimed with `claim`
+//!
+//! Fees in tokens other than ECTO are valued with the PriceOracle; fees the
+//! oracle cannot price accrue nothing.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::lending::price_oracle::PriceOracleContractRef;
+use crate::math::{bps_of, BPS_DENOMINATOR};
+use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// Default referral window (180 days)
+pub const DEFAULT_REFERRAL_DURATION: u64 = 180 * 86_400;
+
+/// Referral bound on a user's first interaction
+#[odra::odra_type]
+pub struct Referral {
+    /// Account earning the referral share
+    pub referrer: Address,
+    /// Timestamp the referral was bound
+    pub bound_at: u64,
+}
+
+/// Referral Registry contract
+#[odra::module]
+pub struct ReferralRegistry {
+    /// Reward token address (ECTO)
+    reward_token: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Contracts allowed to report interactions and fees
+    reporters: Mapping<Address, bool>,
+    /// Oracle valuing fees paid in other tokens
+    price_oracle: Var<Option<Address>>,
+    /// Share of generated fees paid to referrers, in bps
+    referral_bps: Var<u32>,
+    /// Seconds after binding that a referral earns
+    referral_duration: Var<u64>,
+    /// Referrers named by users who have not interacted yet
+    pending_referrers: Mapping<Address, Address>,
+    /// Users whose first interaction was reported
+    interacted: Mapping<Address, bool>,
+    /// Bound referrals (user -> referral)
+    referrals: Mapping<Address, Referral>,
+    /// Users bound to each referrer
+    referral_counts: Mapping<Address, u32>,
+    /// ECTO available for new accruals
+    reward_pool: Var<U256>,
+    /// Accrued, unclaimed rewards per referrer
+    claimable: Mapping<Address, U256>,
+    /// Rewards claimed per referrer
+    total_earned: Mapping<Address, U256>,
+}
+
+#[odra::module]
+impl ReferralRegistry {
+    /// Initialize the registry
+    pub fn init(&mut self, reward_token_address: Address, referral_bps: u32) {
+        if referral_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
+        let caller = self.env().caller();
+        self.reward_token.set(reward_token_address);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.referral_bps.set(referral_bps);
+        self.referral_duration.set(DEFAULT_REFERRAL_DURATION);
+        self.reward_pool.set(U256::zero());
+    }
+
+    /// Name the caller's referrer (before their first interaction only)
+    pub fn set_referrer(&mut self, referrer: Address) {
+        let caller = self.env().caller();
+        if referrer == caller {
+            self.env().revert(DexError::InvalidReferrer);
+        }
+        if self.has_interacted(caller) {
+            self.env().revert(DexError::ReferralAlreadyBound);
+        }
+        self.pending_referrers.set(&caller, referrer);
+    }
+
+    /// Record a user's interaction, binding their named referrer on the first
+    /// (reporters only)
+    pub fn record_interaction(&mut self, user: Address) {
+        self.ensure_reporter();
+        self.bind(user);
+    }
+
+    /// Record fees a user generated, accruing the referrer's share if the
+    /// referral is still earning (reporters only)
+    /// Returns the ECTO accrued
+    pub fn record_fees(&mut self, user: Address, token: Address, amount: U256) -> U256 {
+        self.ensure_reporter();
+        self.bind(user);
+
+        let referral = match self.referrals.get(&user) {
+            Some(referral) => referral,
+            None => return U256::zero(),
+        };
+        let now = self.env().get_block_time();
+        if now >= referral.bound_at + self.referral_duration.get_or_default() {
+            return U256::zero();
+        }
+
+        let value = match self.fee_value(token, amount) {
+            Some(value) => value,
+            None => return U256::zero(),
+        };
+        let pool = self.reward_pool.get_or_default();
+        let reward = bps_of(value, self.referral_bps.get_or_default())
+            .unwrap_or_else(|e| self.env().revert(e))
+            .min(pool);
+        if reward.is_zero() {
+            return U256::zero();
+        }
+
+        self.reward_pool.set(pool - reward);
+        let claimable = self.claimable.get(&referral.referrer).unwrap_or_default();
+        self.claimable.set(&referral.referrer, claimable + reward);
+
+        self.env().emit_event(ReferralRewardAccrued {
+            referrer: referral.referrer,
+            user,
+            token,
+            fee_amount: amount,
+            reward,
+            timestamp: now,
+        });
+
+        reward
+    }
+
+    /// Add ECTO to the reward pool (pulled from the caller)
+    pub fn fund(&mut self, amount: U256) {
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        let caller = self.env().caller();
+        let mut token = Cep18TokenContractRef::new(self.env(), self.reward_token.get_or_revert_with(DexError::InvalidConfiguration));
+        if !token.transfer_from(caller, self.env().self_address(), amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+        self.reward_pool.set(self.reward_pool.get_or_default() + amount);
+    }
+
+    /// Claim the caller's accrued referral rewards
+    pub fn claim(&mut self) -> U256 {
+        let caller = self.env().caller();
+        let amount = self.claimable.get(&caller).unwrap_or_default();
+        if amount.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        self.claimable.set(&caller, U256::zero());
+        let earned = self.total_earned.get(&caller).unwrap_or_default();
+        self.total_earned.set(&caller, earned + amount);
+
+        let mut token = Cep18TokenContractRef::new(self.env(), self.reward_token.get_or_revert_with(DexError::InvalidConfiguration));
+        if !token.transfer(caller, amount) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        self.env().emit_event(ReferralRewardClaimed {
+            referrer: caller,
+            amount,
+            timestamp: self.env().get_block_time(),
+        });
+
+        amount
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get a user's bound referral
+    pub fn get_referral(&self, user: Address) -> Option<Referral> {
+        self.referrals.get(&user)
+    }
+
+    /// Get the referrer a user named before their first interaction
+    pub fn get_pending_referrer(&self, user: Address) -> Option<Address> {
+        self.pending_referrers.get(&user)
+    }
+
+    /// Check whether a user's first interaction was reported
+    pub fn has_interacted(&self, user: Address) -> bool {
+        self.interacted.get(&user).unwrap_or(false)
+    }
+
+    /// Check whether a user's referral still earns
+    pub fn is_referral_active(&self, user: Address) -> bool {
+        match self.referrals.get(&user) {
+            Some(referral) => {
+                self.env().get_block_time() < referral.bound_at + self.referral_duration.get_or_default()
+            }
+            None => false,
+        }
+    }
+
+    /// Get the number of users bound to a referrer
+    pub fn get_referral_count(&self, referrer: Address) -> u32 {
+        self.referral_counts.get(&referrer).unwrap_or(0)
+    }
+
+    /// Get a referrer's accrued, unclaimed rewards
+    pub fn get_claimable(&self, referrer: Address) -> U256 {
+        self.claimable.get(&referrer).unwrap_or_default()
+    }
+
+    /// Get the rewards a referrer has claimed
+    pub fn get_total_earned(&self, referrer: Address) -> U256 {
+        self.total_earned.get(&referrer).unwrap_or_default()
+    }
+
+    /// Get the ECTO available for new accruals
+    pub fn get_reward_pool(&self) -> U256 {
+        self.reward_pool.get_or_default()
+    }
+
+    /// Get the referral share (bps) and window (seconds)
+    pub fn get_referral_terms(&self) -> (u32, u64) {
+        (self.referral_bps.get_or_default(), self.referral_duration.get_or_default())
+    }
+
+    /// Check whether an address may report interactions and fees
+    pub fn is_reporter(&self, account: Address) -> bool {
+        self.reporters.get(&account).unwrap_or(false)
+    }
+
+    /// Get the oracle valuing non-ECTO fees
+    pub fn get_price_oracle(&self) -> Option<Address> {
+        self.price_oracle.get_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Allow or disallow an address to report interactions and fees
+    pub fn set_reporter(&mut self, reporter: Address, allowed: bool) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.reporters.set(&reporter, allowed);
+    }
+
+    /// Set the referral share (bps) and window (seconds)
+    /// The window applies to existing referrals too
+    pub fn set_referral_terms(&mut self, referral_bps: u32, referral_duration: u64) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        if referral_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+        self.referral_bps.set(referral_bps);
+        self.referral_duration.set(referral_duration);
+    }
+
+    /// Set the oracle valuing non-ECTO fees
+    pub fn set_price_oracle(&mut self, price_oracle: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.price_oracle.set(price_oracle);
+    }
+
+    // ========================================
+    // Access Control
+    // ========================================
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn ensure_reporter(&self) {
+        if !self.is_reporter(self.env().caller()) {
+            self.env().revert(DexError::Unauthorized);
+        }
+    }
+
+    /// Mark the user's first interaction, binding their named referrer
+    fn bind(&mut self, user: Address) {
+        if self.has_interacted(user) {
+            return;
+        }
+        self.interacted.set(&user, true);
+
+        let referrer = match self.pending_referrers.get(&user) {
+            Some(referrer) => referrer,
+            None => return,
+        };
+        let bound_at = self.env().get_block_time();
+        self.referrals.set(&user, Referral { referrer, bound_at });
+        let count = self.get_referral_count(referrer);
+        self.referral_counts.set(&referrer, count + 1);
+
+        self.env().emit_event(ReferralBound {
+            user,
+            referrer,
+            timestamp: bound_at,
+        });
+    }
+
+    /// ECTO value of a fee, or None if it cannot be priced
+    fn fee_value(&self, token: Address, amount: U256) -> Option<U256> {
+        if Some(token) == self.reward_token.get() {
+            return Some(amount);
+        }
+        let oracle = PriceOracleContractRef::new(self.env(), self.price_oracle.get_or_default()?);
+        if !oracle.is_price_fresh(token) {
+            return None;
+        }
+        Some(oracle.get_asset_value(token, amount))
+    }
+}
+
+// ========================================
+// Events
+// ========================================
+
+/// Emitted when a user's referral is bound on their first interaction
+#[odra::event]
+pub struct ReferralBound {
+    pub user: Address,
+    pub referrer: Address,
+    pub timestamp: u64,
+}
+
+/// Emitted when a referrer accrues a share of a referred user's fees
+#[odra::event]
+pub struct ReferralRewardAccrued {
+    pub referrer: Address,
+    pub user: Address,
+    pub token: Address,
+    pub fee_amount: U256,
+    pub reward: U256,
+    pub timestamp: u64,
+}
+
+/// Emitted when a referrer claims rewards
+#[odra::event]
+pub struct ReferralRewardClaimed {
+    pub referrer: Address,
+    pub amount: U256,
+    pub timestamp: u64,
+}
+
+// Import error type
+use crate::errors::DexError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    #[test]
+    fn test_referral_bound_on_first_interaction() {
+        let env = odra_test::env();
+        let (admin, reporter, referrer, user, late) = (
+            env.get_account(0), env.get_account(1), env.get_account(2), env.get_account(3), env.get_account(4),
+        );
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut registry = ReferralRegistry::deploy(&env, ReferralRegistryInitArgs {
+            reward_token_address: ecto.address(),
+            referral_bps: 1_000,
+        });
+        registry.set_reporter(reporter, true);
+        ecto.mint(admin, U256::from(150));
+        ecto.approve(registry.address(), U256::from(150));
+        registry.fund(U256::from(150));
+
+        env.set_caller(user);
+        assert_eq!(registry.try_set_referrer(user).unwrap_err(), DexError::InvalidReferrer.into());
+        registry.set_referrer(referrer);
+
+        // Fees are only reported by protocol contracts
+        assert_eq!(
+            registry.try_record_fees(user, ecto.address(), U256::from(1_000)).unwrap_err(),
+            DexError::Unauthorized.into()
+        );
+        env.set_caller(reporter);
+        registry.record_interaction(late);
+        assert_eq!(registry.record_fees(user, ecto.address(), U256::from(1_000)), U256::from(100));
+        assert_eq!(registry.get_referral(user).unwrap().referrer, referrer);
+        assert_eq!(registry.get_referral_count(referrer), 1);
+
+        // Accruals are capped by the pool
+        assert_eq!(registry.record_fees(user, ecto.address(), U256::from(1_000)), U256::from(50));
+
+        // A user who interacted first cannot name a referrer afterwards
+        env.set_caller(late);
+        assert_eq!(registry.try_set_referrer(referrer).unwrap_err(), DexError::ReferralAlreadyBound.into());
+
+        env.set_caller(referrer);
+        assert_eq!(registry.claim(), U256::from(150));
+        assert_eq!(ecto.balance_of(referrer), U256::from(150));
+
+        // Nothing accrues once the referral window has passed
+        env.advance_block_time(DEFAULT_REFERRAL_DURATION);
+        assert!(!registry.is_referral_active(user));
+        env.set_caller(admin);
+        ecto.mint(admin, U256::from(100));
+        ecto.approve(registry.address(), U256::from(100));
+        registry.fund(U256::from(100));
+        env.set_caller(reporter);
+        assert_eq!(registry.record_fees(user, ecto.address(), U256::from(1_000)), U256::zero());
+    }
+}
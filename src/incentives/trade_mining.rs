@@ -0,0 +1,444 @@
+//! Trade Mining
+//!
+//! Rewards traders with ECTO proportional to their fee-paying swap volume.
+//!
+//! Mechanics:
+//! - The Router reports every swap hop (trader, pair, amount in)
+//! - Volume is weighted per pair (basis points, 0 = not eligible)
+//! - Each epoch emits a fixed amount of ECTO split pro-rata by weighted volume
+//! - Rewards become claimable once the epoch has closed
+//! - Weighted daily volume per trader is kept for trailing-volume lookups
+//!   (e.g. gas discount tiers); only eligible pairs count
+//! - Weighted volume is also credited to the PointsLedger, if configured
+//!
+//! Sybil Caps:
+//! - Trades below `min_trade_volume` are ignored
+//! - Counted volume per trader per epoch is capped at `max_user_volume`
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use crate::dex::pair::SECONDS_PER_DAY;
+use crate::incentives::points_ledger::{PointsAction, PointsLedgerContractRef};
+use crate::math::BPS_DENOMINATOR;
+use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// Longest window `get_trailing_volume` sums over (days)
+pub const MAX_TRAILING_DAYS: u64 = 90;
+
+/// Trade Mining contract
+#[odra::module]
+pub struct TradeMining {
+    /// Reward token address (ECTO)
+    reward_token: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Contracts allowed to report volume (e.g. Router)
+    reporters: Mapping<Address, bool>,
+    /// Timestamp when epoch 0 starts
+    start_time: Var<u64>,
+    /// Epoch length (seconds)
+    epoch_duration: Var<u64>,
+    /// ECTO emitted per epoch
+    emission_per_epoch: Var<U256>,
+    /// Emission snapshot per epoch (epoch -> amount)
+    epoch_emissions: Mapping<u64, U256>,
+    /// Pair volume weights in basis points (pair -> weight)
+    pair_weights: Mapping<Address, u32>,
+    /// Minimum trade volume that counts
+    min_trade_volume: Var<U256>,
+    /// Maximum counted volume per trader per epoch (0 = no cap)
+    max_user_volume: Var<U256>,
+    /// Weighted volume per trader per epoch ((trader, epoch) -> volume)
+    user_volumes: Mapping<(Address, u64), U256>,
+    /// Total weighted volume per epoch (epoch -> volume)
+    epoch_volumes: Mapping<u64, U256>,
+    /// Claimed flags ((trader, epoch) -> claimed)
+    claimed: Mapping<(Address, u64), bool>,
+    /// Total rewards claimed
+    total_claimed: Var<U256>,
+    /// Weighted volume on eligible pairs per trader per day ((trader, day) -> volume)
+    daily_volumes: Mapping<(Address, u64), U256>,
+    /// PointsLedger credited with weighted volume
+    points_ledger: Var<Option<Address>>,
+}
+
+#[odra::module]
+impl TradeMining {
+    /// Initialize trade mining
+    pub fn init(
+        &mut self,
+        reward_token_address: Address,
+        epoch_duration: u64,
+        emission_per_epoch: U256,
+    ) {
+        if epoch_duration == 0 {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
+        let caller = self.env().caller();
+
+        self.reward_token.set(reward_token_address);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.start_time.set(self.env().get_block_time());
+        self.epoch_duration.set(epoch_duration);
+        self.emission_per_epoch.set(emission_per_epoch);
+        self.min_trade_volume.set(U256::zero());
+        self.max_user_volume.set(U256::zero());
+        self.total_claimed.set(U256::zero());
+    }
+
+    /// Record swap volume for a trader (called by reporters)
+    pub fn record_volume(&mut self, trader: Address, pair: Address, amount_in: U256) {
+        if !self.reporters.get(&self.env().caller()).unwrap_or(false) {
+            self.env().revert(DexError::Unauthorized);
+        }
+
+        let weight = self.pair_weights.get(&pair).unwrap_or_default();
+        if weight == 0 || amount_in < self.min_trade_volume.get_or_default() {
+            return;
+        }
+
+        let epoch = self.current_epoch();
+        let weighted = amount_in * U256::from(weight) / U256::from(BPS_DENOMINATOR);
+
+        let day = self.env().get_block_time() / SECONDS_PER_DAY;
+        let daily_volume = self.daily_volumes.get(&(trader, day)).unwrap_or_default();
+        self.daily_volumes.set(&(trader, day), daily_volume + weighted);
+        if let Some(ledger) = self.points_ledger.get_or_default() {
+            PointsLedgerContractRef::new(self.env(), ledger).credit(trader, PointsAction::Volume, weighted);
+        }
+
+        // Apply the per-trader cap
+        let user_volume = self.user_volumes.get(&(trader, epoch)).unwrap_or_default();
+        let max_user_volume = self.max_user_volume.get_or_default();
+        let counted = if max_user_volume.is_zero() {
+            weighted
+        } else if user_volume >= max_user_volume {
+            U256::zero()
+        } else {
+            weighted.min(max_user_volume - user_volume)
+        };
+
+        if counted.is_zero() {
+            return;
+        }
+
+        // Snapshot the emission the first time an epoch sees volume
+        if self.epoch_emissions.get(&epoch).is_none() {
+            self.epoch_emissions.set(&epoch, self.emission_per_epoch.get_or_default());
+        }
+
+        self.user_volumes.set(&(trader, epoch), user_volume + counted);
+        let epoch_volume = self.epoch_volumes.get(&epoch).unwrap_or_default();
+        self.epoch_volumes.set(&epoch, epoch_volume + counted);
+
+        self.env().emit_event(VolumeRecorded {
+            trader,
+            pair,
+            epoch,
+            volume: counted,
+        });
+    }
+
+    /// Claim rewards for a closed epoch
+    pub fn claim(&mut self, epoch: u64) -> U256 {
+        if epoch >= self.current_epoch() {
+            self.env().revert(DexError::EpochNotClosed);
+        }
+
+        let trader = self.env().caller();
+        if self.claimed.get(&(trader, epoch)).unwrap_or(false) {
+            self.env().revert(DexError::AlreadyClaimed);
+        }
+
+        let reward = self.pending_reward(trader, epoch);
+        if reward.is_zero() {
+            self.env().revert(DexError::InsufficientAmount);
+        }
+
+        self.claimed.set(&(trader, epoch), true);
+        self.total_claimed.set(self.total_claimed.get_or_default() + reward);
+
+        let reward_token = self.reward_token.get_or_revert_with(DexError::InvalidConfiguration);
+        let mut token = Cep18TokenContractRef::new(self.env(), reward_token);
+        if !token.transfer(trader, reward) {
+            self.env().revert(DexError::TransferFailed);
+        }
+
+        self.env().emit_event(TradeRewardClaimed {
+            trader,
+            epoch,
+            amount: reward,
+        });
+
+        reward
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get the current epoch number
+    pub fn current_epoch(&self) -> u64 {
+        let start_time = self.start_time.get_or_default();
+        let duration = self.epoch_duration.get_or_default();
+        (self.env().get_block_time() - start_time) / duration
+    }
+
+    /// Get a trader's unclaimed reward for an epoch
+    pub fn pending_reward(&self, trader: Address, epoch: u64) -> U256 {
+        if self.claimed.get(&(trader, epoch)).unwrap_or(false) {
+            return U256::zero();
+        }
+
+        let epoch_volume = self.epoch_volumes.get(&epoch).unwrap_or_default();
+        if epoch_volume.is_zero() {
+            return U256::zero();
+        }
+
+        let user_volume = self.user_volumes.get(&(trader, epoch)).unwrap_or_default();
+        let emission = self.epoch_emissions.get(&epoch).unwrap_or_default();
+        emission * user_volume / epoch_volume
+    }
+
+    /// Get a trader's counted volume for an epoch
+    pub fn get_user_volume(&self, trader: Address, epoch: u64) -> U256 {
+        self.user_volumes.get(&(trader, epoch)).unwrap_or_default()
+    }
+
+    /// Get the total counted volume for an epoch
+    pub fn get_epoch_volume(&self, epoch: u64) -> U256 {
+        self.epoch_volumes.get(&epoch).unwrap_or_default()
+    }
+
+    /// Get a trader's weighted volume over the last `days` days, including today
+    pub fn get_trailing_volume(&self, trader: Address, days: u64) -> U256 {
+        if days > MAX_TRAILING_DAYS {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
+        let today = self.env().get_block_time() / SECONDS_PER_DAY;
+        (today.saturating_sub(days.saturating_sub(1))..=today)
+            .take(days as usize)
+            .map(|day| self.daily_volumes.get(&(trader, day)).unwrap_or_default())
+            .fold(U256::zero(), |total, volume| total + volume)
+    }
+
+    /// Get a pair's volume weight in basis points
+    pub fn get_pair_weight(&self, pair: Address) -> u32 {
+        self.pair_weights.get(&pair).unwrap_or_default()
+    }
+
+    /// Check if an address may report volume
+    pub fn is_reporter(&self, reporter: Address) -> bool {
+        self.reporters.get(&reporter).unwrap_or(false)
+    }
+
+    /// Get total rewards claimed
+    pub fn get_total_claimed(&self) -> U256 {
+        self.total_claimed.get_or_default()
+    }
+
+    /// Get the PointsLedger credited with weighted volume
+    pub fn get_points_ledger(&self) -> Option<Address> {
+        self.points_ledger.get_or_default()
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Allow or disallow an address to report volume
+    pub fn set_reporter(&mut self, reporter: Address, allowed: bool) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.reporters.set(&reporter, allowed);
+    }
+
+    /// Set the PointsLedger credited with weighted volume (None disables)
+    pub fn set_points_ledger(&mut self, points_ledger: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.points_ledger.set(points_ledger);
+    }
+
+    /// Set a pair's volume weight in basis points (0 = not eligible)
+    pub fn set_pair_weight(&mut self, pair: Address, weight: u32) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+
+        if weight as u128 > BPS_DENOMINATOR {
+            self.env().revert(DexError::InvalidConfiguration);
+        }
+
+        self.pair_weights.set(&pair, weight);
+
+        self.env().emit_event(PairWeightUpdated {
+            pair,
+            weight,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Set the ECTO emitted per epoch (applies to epochs without volume yet)
+    pub fn set_emission_per_epoch(&mut self, emission_per_epoch: U256) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.emission_per_epoch.set(emission_per_epoch);
+    }
+
+    /// Set the sybil caps
+    pub fn set_sybil_caps(&mut self, min_trade_volume: U256, max_user_volume: U256) {
+        self.access.require_role(ADMIN_ROLE, DexError::Unauthorized);
+        self.min_trade_volume.set(min_trade_volume);
+        self.max_user_volume.set(max_user_volume);
+    }
+
+    // ========================================
+    // Access Control
+    // ========================================
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+}
+
+// ========================================
+// Events
+// ========================================
+
+/// Emitted when swap volume is counted for a trader
+#[odra::event]
+pub struct VolumeRecorded {
+    pub trader: Address,
+    pub pair: Address,
+    pub epoch: u64,
+    pub volume: U256,
+}
+
+/// Emitted when a trader claims an epoch's reward
+#[odra::event]
+pub struct TradeRewardClaimed {
+    pub trader: Address,
+    pub epoch: u64,
+    pub amount: U256,
+}
+
+/// Emitted when a pair's volume weight is updated
+#[odra::event]
+pub struct PairWeightUpdated {
+    pub pair: Address,
+    pub weight: u32,
+    pub updated_by: Address,
+}
+
+// Import error type
+use crate::errors::DexError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::Deployer;
+
+    #[test]
+    fn test_volume_split_and_caps() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let router = env.get_account(1);
+        let alice = env.get_account(2);
+        let bob = env.get_account(3);
+        let pair = env.get_account(4);
+        let ecto = env.get_account(10);
+
+        env.set_caller(admin);
+        let mut mining = TradeMining::deploy(&env, TradeMiningInitArgs {
+            reward_token_address: ecto,
+            epoch_duration: 1000,
+            emission_per_epoch: U256::from(1000),
+        });
+        mining.set_reporter(router, true);
+        mining.set_pair_weight(pair, 10_000);
+        mining.set_sybil_caps(U256::from(10), U256::from(300));
+
+        env.set_caller(router);
+        mining.record_volume(alice, pair, U256::from(100));
+        mining.record_volume(bob, pair, U256::from(500)); // capped at 300
+        mining.record_volume(bob, pair, U256::from(5)); // below minimum
+
+        assert_eq!(mining.get_user_volume(bob, 0), U256::from(300));
+        assert_eq!(mining.get_epoch_volume(0), U256::from(400));
+
+        env.advance_block_time(1000);
+        assert_eq!(mining.pending_reward(alice, 0), U256::from(250));
+        assert_eq!(mining.pending_reward(bob, 0), U256::from(750));
+    }
+
+    #[test]
+    fn test_points_credit_weighted_volume_on_eligible_pairs() {
+        use crate::incentives::points_ledger::PointsLedger;
+        use odra::host::{HostRef, NoArgs};
+
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let router = env.get_account(1);
+        let trader = env.get_account(2);
+        let (pair, unweighted_pair) = (env.get_account(4), env.get_account(5));
+
+        env.set_caller(admin);
+        let mut mining = TradeMining::deploy(&env, TradeMiningInitArgs {
+            reward_token_address: env.get_account(10),
+            epoch_duration: 1000,
+            emission_per_epoch: U256::from(1000),
+        });
+        let mut ledger = PointsLedger::deploy(&env, NoArgs);
+        // 1 point per unit of volume, no daily cap
+        ledger.set_action_config(PointsAction::Volume, U256::from(10u128.pow(18)), U256::zero());
+        ledger.set_reporter(mining.address(), true);
+        mining.set_points_ledger(Some(ledger.address()));
+        mining.set_reporter(router, true);
+        mining.set_pair_weight(pair, 2_500);
+
+        env.set_caller(router);
+        mining.record_volume(trader, unweighted_pair, U256::from(1000));
+        assert_eq!(ledger.get_points(trader), U256::zero());
+
+        mining.record_volume(trader, pair, U256::from(1000));
+        assert_eq!(ledger.get_points(trader), U256::from(250));
+    }
+
+    #[test]
+    fn test_role_gated_admin_functions() {
+        use crate::access::AccessError;
+
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let ops = env.get_account(1);
+        let pair = env.get_account(4);
+
+        env.set_caller(admin);
+        let mut mining = TradeMining::deploy(&env, TradeMiningInitArgs {
+            reward_token_address: env.get_account(10),
+            epoch_duration: 1000,
+            emission_per_epoch: U256::from(1000),
+        });
+
+        env.set_caller(ops);
+        assert_eq!(mining.try_set_pair_weight(pair, 5_000).unwrap_err(), DexError::Unauthorized.into());
+        assert_eq!(mining.try_grant_role(ADMIN_ROLE, ops).unwrap_err(), AccessError::MissingRole.into());
+
+        env.set_caller(admin);
+        mining.grant_role(ADMIN_ROLE, ops);
+
+        env.set_caller(ops);
+        mining.set_pair_weight(pair, 5_000);
+        assert_eq!(mining.get_pair_weight(pair), 5_000);
+
+        // The last admin cannot walk away
+        mining.renounce_role(ADMIN_ROLE);
+        env.set_caller(admin);
+        assert_eq!(mining.try_renounce_role(ADMIN_ROLE).unwrap_err(), AccessError::LastAdmin.into());
+    }
+}
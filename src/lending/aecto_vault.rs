@@ -2,41 +2,57 @@
 //! 
 //! Users deposit ECTO and receive aECTO shares that increase in value
 //! as interest accrues from borrowers.
+//! 
+//! The vault is the single deposit/withdraw entry point. It only accounts
+//! for shares: deposited ECTO is moved straight into the lending pool, which
+//! manages liquidity, and `total_assets` is read from the pool.
 
 use odra::prelude::*;
-use odra::casper_types::U256;
+use odra::casper_types::bytesrepr::Bytes;
+use odra::casper_types::{PublicKey, U256};
 use odra::ContractRef;
 use super::errors::LendingError;
-use crate::cep4626::{Cep4626Vault, Deposit as Cep4626Deposit, Withdraw as Cep4626Withdraw};
-use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE, PAUSER_ROLE};
+use odra::OdraError;
+use crate::cep4626::{base, Cep4626Base, Cep4626Hooks, Cep4626Vault};
+use crate::cep4626::base::MAX_FEE_BPS;
+use crate::cep4626::vault::helpers;
+use crate::token::{Cep18Base, Cep18TokenContractRef, TokenMetadata};
+use super::collateral_manager::CollateralManagerContractRef;
+use super::events::{AectoCollateralPledged, LiquidityMigrated};
+use super::lending_pool::LendingPoolContractRef;
+use crate::incentives::lending_incentives::LendingIncentivesContractRef;
+use crate::incentives::referral_registry::ReferralRegistryContractRef;
 
 /// aECTO Vault - Interest-bearing ECTO token
 #[odra::module]
 pub struct AectoVault {
-    /// Name of the token
-    name: Var<String>,
-    /// Symbol of the token
-    symbol: Var<String>,
-    /// Decimals
-    decimals: Var<u8>,
-    /// Total supply of aECTO shares
-    total_supply: Var<U256>,
-    /// User balances
-    balances: Mapping<Address, U256>,
-    /// Allowances
-    allowances: Mapping<(Address, Address), U256>,
+    /// aECTO share ledger (CEP-18)
+    token: SubModule<Cep18Base>,
+    /// Entry and exit fee settings (shares are held in `token`)
+    shares: SubModule<Cep4626Base>,
     
     /// Underlying ECTO token address
     ecto_token: Var<Address>,
-    /// Total ECTO deposited (including accrued interest)
+    /// Legacy total assets pushed by the pool before deposits were unified
+    /// (no longer read; kept so the storage layout is unchanged)
     total_assets: Var<U256>,
     
     /// Lending pool address (can deposit/withdraw)
     lending_pool: Var<Address>,
-    /// Admin address
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     /// Paused state
     paused: Var<bool>,
+    
+    /// Collateral manager that values and seizes pledged aECTO
+    collateral_manager: Var<Option<Address>>,
+    /// Holders whose aECTO balance is pledged as collateral
+    pledged: Mapping<Address, bool>,
+    /// Incentives controller notified of every balance change
+    incentives_controller: Var<Option<Address>>,
+    /// Protocol referral registry notified of deposits
+    referral_registry: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -45,16 +61,13 @@ impl AectoVault {
     pub fn init(&mut self, ecto_token_address: Address, lending_pool_address: Address) {
         let caller = self.env().caller();
         
-        self.name.set(String::from("Aave ECTO"));
-        self.symbol.set(String::from("aECTO"));
-        self.decimals.set(18);
-        self.total_supply.set(U256::zero());
+        self.token.init(String::from("Aave ECTO"), String::from("aECTO"), 18);
         
         self.ecto_token.set(ecto_token_address);
         self.lending_pool.set(lending_pool_address);
-        self.total_assets.set(U256::zero());
         
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(PAUSER_ROLE, caller);
         self.paused.set(false);
     }
     
@@ -62,141 +75,216 @@ impl AectoVault {
     // CEP-18 Token Functions
     // ========================================
     
-    pub fn name(&self) -> String {
-        self.name.get_or_default()
-    }
-    
-    pub fn symbol(&self) -> String {
-        self.symbol.get_or_default()
-    }
-    
-    pub fn decimals(&self) -> u8 {
-        self.decimals.get_or_default()
+    delegate! {
+        to self.token {
+            fn name(&self) -> String;
+            fn symbol(&self) -> String;
+            fn decimals(&self) -> u8;
+            fn total_supply(&self) -> U256;
+            fn balance_of(&self, owner: Address) -> U256;
+            fn allowance(&self, owner: Address, spender: Address) -> U256;
+            fn approve(&mut self, spender: Address, amount: U256) -> bool;
+            fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn permit(&mut self, owner: Address, spender: Address, value: U256, deadline: u64, public_key: PublicKey, signature: Bytes);
+            fn nonces(&self, owner: Address) -> u64;
+            fn domain_separator(&self) -> [u8; 32];
+            fn permit_digest(&self, owner: Address, spender: Address, value: U256, nonce: u64, deadline: u64) -> [u8; 32];
+            fn contract_version(&self) -> String;
+            fn metadata(&self) -> TokenMetadata;
+        }
     }
     
-    pub fn total_supply(&self) -> U256 {
-        self.total_supply.get_or_default()
+    pub fn transfer(&mut self, recipient: Address, amount: U256) -> bool {
+        let sender = self.env().caller();
+        self.transfer_internal(sender, recipient, amount);
+        true
     }
     
-    pub fn balance_of(&self, owner: Address) -> U256 {
-        self.balances.get(&owner).unwrap_or(U256::zero())
+    pub fn transfer_from(&mut self, owner: Address, recipient: Address, amount: U256) -> bool {
+        let spender = self.env().caller();
+        self.token.raw_transfer_from(spender, owner, recipient, amount);
+        self.after_transfer(owner, recipient);
+        true
     }
     
-    pub fn transfer(&mut self, recipient: Address, amount: U256) {
-        let sender = self.env().caller();
-        self.transfer_internal(sender, recipient, amount);
+    fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
+        self.token.raw_transfer(from, to, amount);
+        self.after_transfer(from, to);
     }
     
-    pub fn approve(&mut self, spender: Address, amount: U256) {
-        let owner = self.env().caller();
-        self.allowances.set(&(owner, spender), amount);
+    fn after_transfer(&mut self, from: Address, to: Address) {
+        self.notify_incentives(from);
+        self.notify_incentives(to);
+        self.ensure_pledge_healthy(from);
     }
     
-    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
-        self.allowances.get(&(owner, spender)).unwrap_or(U256::zero())
-    }
+    // ========================================
+    // Collateral Pledging
+    // ========================================
     
-    pub fn transfer_from(&mut self, owner: Address, recipient: Address, amount: U256) {
-        let spender = self.env().caller();
-        let current_allowance = self.allowance(owner, spender);
+    /// Pledge (or release) the caller's aECTO balance as lending collateral
+    /// Pledged aECTO stays in the wallet but transfers that would leave the
+    /// holder's health factor below 1 are rejected
+    pub fn pledge_as_collateral(&mut self, enabled: bool) {
+        let caller = self.env().caller();
+        self.pledged.set(&caller, enabled);
         
-        if current_allowance < amount {
-            self.env().revert(LendingError::InsufficientBalance);
+        if !enabled {
+            self.ensure_healthy(caller);
         }
         
-        self.allowances.set(&(owner, spender), current_allowance - amount);
-        self.transfer_internal(owner, recipient, amount);
+        self.env().emit_event(AectoCollateralPledged {
+            user: caller,
+            enabled,
+            timestamp: self.env().get_block_time(),
+        });
     }
     
-    fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
-        let from_balance = self.balance_of(from);
-        if from_balance < amount {
-            self.env().revert(LendingError::InsufficientBalance);
+    /// Check whether a holder's aECTO is pledged as collateral
+    pub fn is_pledged(&self, owner: Address) -> bool {
+        self.pledged.get(&owner).unwrap_or_default()
+    }
+    
+    /// Move pledged aECTO from a liquidated holder (collateral manager only)
+    pub fn seize_pledged(&mut self, from: Address, to: Address, amount: U256) {
+        let caller = self.env().caller();
+        if self.collateral_manager.get_or_default() != Some(caller) {
+            self.env().revert(LendingError::Unauthorized);
         }
         
-        self.balances.set(&from, from_balance - amount);
-        let to_balance = self.balance_of(to);
-        self.balances.set(&to, to_balance + amount);
+        self.token.raw_transfer(from, to, amount);
+        
+        self.notify_incentives(from);
+        self.notify_incentives(to);
     }
     
     // ========================================
-    // Vault Management (Lending Pool Only)
+    // CEP-4626 Entry Points
     // ========================================
     
-    /// Mint aECTO shares (lending pool only)
-    pub fn mint(&mut self, to: Address, amount: U256) {
-        self.only_lending_pool();
-        
-        let current_supply = self.total_supply.get_or_default();
-        self.total_supply.set(current_supply + amount);
-        
-        let balance = self.balance_of(to);
-        self.balances.set(&to, balance + amount);
+    /// Deposit the caller's ECTO and mint aECTO to `receiver`
+    pub fn deposit(&mut self, assets: U256, receiver: Address) -> U256 {
+        Cep4626Vault::deposit(self, assets, receiver)
     }
     
-    /// Burn aECTO shares (lending pool only)
-    pub fn burn(&mut self, from: Address, amount: U256) {
-        self.only_lending_pool();
-        
-        let balance = self.balance_of(from);
-        if balance < amount {
-            self.env().revert(LendingError::InsufficientBalance);
-        }
-        
-        self.balances.set(&from, balance - amount);
-        
-        let current_supply = self.total_supply.get_or_default();
-        self.total_supply.set(current_supply - amount);
+    /// Mint exactly `shares` aECTO to `receiver` for the caller's ECTO
+    pub fn mint(&mut self, shares: U256, receiver: Address) -> U256 {
+        Cep4626Vault::mint(self, shares, receiver)
+    }
+    
+    /// Burn `owner`'s aECTO for `assets` ECTO sent to `receiver`
+    pub fn withdraw(&mut self, assets: U256, receiver: Address, owner: Address) -> U256 {
+        Cep4626Vault::withdraw(self, assets, receiver, owner)
+    }
+    
+    /// Burn exactly `shares` of `owner`'s aECTO for ECTO sent to `receiver`
+    pub fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
+        Cep4626Vault::redeem(self, shares, receiver, owner)
+    }
+    
+    /// Get the most ECTO `receiver` can deposit right now
+    pub fn max_deposit(&self, receiver: Address) -> U256 {
+        Cep4626Vault::max_deposit(self, receiver)
+    }
+    
+    /// Get the most aECTO `receiver` can mint right now
+    pub fn max_mint(&self, receiver: Address) -> U256 {
+        Cep4626Vault::max_mint(self, receiver)
+    }
+    
+    /// Get the most ECTO `owner` can withdraw right now
+    /// Also bounded by the lending pool's utilization-based withdrawal limit
+    pub fn max_withdraw(&self, owner: Address) -> U256 {
+        Cep4626Vault::max_withdraw(self, owner)
     }
     
-    /// Update total assets (lending pool only)
-    pub fn update_total_assets(&mut self, new_total: U256) {
-        self.only_lending_pool();
-        self.total_assets.set(new_total);
+    /// Get the most aECTO `owner` can redeem right now
+    pub fn max_redeem(&self, owner: Address) -> U256 {
+        Cep4626Vault::max_redeem(self, owner)
     }
     
-    /// Get total assets
+    /// Get total assets managed for aECTO holders (read from the lending pool)
+    pub fn total_assets(&self) -> U256 {
+        self.get_total_assets()
+    }
+    
+    /// Get total assets managed for aECTO holders (read from the lending pool)
     pub fn get_total_assets(&self) -> U256 {
-        self.total_assets.get_or_default()
+        LendingPoolContractRef::new(self.env(), self.get_lending_pool()).get_total_assets()
     }
     
     /// Convert assets to shares (public wrapper for CEP-4626)
     pub fn convert_to_shares(&self, assets: U256) -> U256 {
-        let total_supply = self.total_supply();
-        let total_assets = self.total_assets.get_or_default();
-        
-        if total_supply == U256::zero() || total_assets == U256::zero() {
-            return assets; // 1:1 initial rate
-        }
-        
-        // shares = (assets * total_supply) / total_assets
-        (assets * total_supply) / total_assets
+        base::convert_to_shares(self, assets)
     }
     
     /// Convert shares to assets (public wrapper for CEP-4626)
     pub fn convert_to_assets(&self, shares: U256) -> U256 {
-        let total_supply = self.total_supply();
-        
-        if total_supply == U256::zero() {
-            return U256::zero();
-        }
-        
-        let total_assets = self.total_assets.get_or_default();
-        // assets = (shares * total_assets) / total_supply
-        (shares * total_assets) / total_supply
+        base::convert_to_assets(self, shares)
+    }
+    
+    /// Shares minted for depositing `assets`, net of the entry fee
+    pub fn preview_deposit(&self, assets: U256) -> U256 {
+        base::preview_deposit(self, assets)
+    }
+    
+    /// ECTO needed to mint `shares`, including the entry fee
+    pub fn preview_mint(&self, shares: U256) -> U256 {
+        base::preview_mint(self, shares)
+    }
+    
+    /// Shares burned to withdraw `assets`, including the exit fee
+    pub fn preview_withdraw(&self, assets: U256) -> U256 {
+        base::preview_withdraw(self, assets)
+    }
+    
+    /// ECTO received for redeeming `shares`, net of the exit fee
+    pub fn preview_redeem(&self, shares: U256) -> U256 {
+        base::preview_redeem(self, shares)
     }
     
     // ========================================
     // Admin Functions
     // ========================================
     
+    /// Move ECTO left in the vault by the old direct-deposit path into the
+    /// lending pool as liquidity (admin only)
+    /// 
+    /// Shares for that ECTO were minted when it was deposited, so none are
+    /// minted here. Run once right after upgrading, before new deposits, so
+    /// the share price reflects every deposited token.
+    pub fn migrate_liquidity_to_pool(&mut self) -> U256 {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        
+        let self_address = self.env().self_address();
+        let mut ecto_token = Cep18TokenContractRef::new(self.env(), self.asset());
+        let amount = ecto_token.balance_of(self_address);
+        if amount == U256::zero() {
+            return amount;
+        }
+        
+        let pool_address = self.get_lending_pool();
+        ecto_token.transfer(pool_address, amount);
+        LendingPoolContractRef::new(self.env(), pool_address)
+            .supply_liquidity(self_address, self_address, amount, U256::zero());
+        self.total_assets.set(U256::zero());
+        
+        self.env().emit_event(LiquidityMigrated {
+            amount,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        amount
+    }
+    
     pub fn pause(&mut self) {
-        self.only_admin();
+        self.access.require_role(PAUSER_ROLE, LendingError::Unauthorized);
         self.paused.set(true);
     }
     
     pub fn unpause(&mut self) {
-        self.only_admin();
+        self.access.require_role(PAUSER_ROLE, LendingError::Unauthorized);
         self.paused.set(false);
     }
     
@@ -204,208 +292,333 @@ impl AectoVault {
         self.paused.get_or_default()
     }
     
-    fn only_lending_pool(&self) {
-        let caller = self.env().caller();
-        let pool = self.lending_pool.get_or_revert_with(LendingError::Unauthorized);
-        if caller != pool {
-            self.env().revert(LendingError::Unauthorized);
+    /// Update the lending pool address (admin only)
+    pub fn set_lending_pool(&mut self, lending_pool: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.lending_pool.set(lending_pool);
+    }
+    
+    /// Get the lending pool address
+    pub fn get_lending_pool(&self) -> Address {
+        self.lending_pool.get_or_revert_with(LendingError::InvalidConfiguration)
+    }
+    
+    /// Set the collateral manager that values and seizes pledged aECTO (admin only)
+    pub fn set_collateral_manager(&mut self, collateral_manager: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.collateral_manager.set(Some(collateral_manager));
+    }
+    
+    /// Get the collateral manager that values and seizes pledged aECTO
+    pub fn get_collateral_manager(&self) -> Option<Address> {
+        self.collateral_manager.get_or_default()
+    }
+    
+    /// Set the incentives controller notified of balance changes (admin only)
+    pub fn set_incentives_controller(&mut self, controller: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.incentives_controller.set(Some(controller));
+    }
+    
+    /// Get the incentives controller notified of balance changes
+    pub fn get_incentives_controller(&self) -> Option<Address> {
+        self.incentives_controller.get_or_default()
+    }
+    
+    /// Set the protocol referral registry notified of deposits (admin only)
+    pub fn set_referral_registry(&mut self, registry: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.referral_registry.set(registry);
+    }
+    
+    /// Get the protocol referral registry notified of deposits
+    pub fn get_referral_registry(&self) -> Option<Address> {
+        self.referral_registry.get_or_default()
+    }
+    
+    /// Set the deposit and withdrawal fees paid to `recipient` (admin only)
+    pub fn set_fees(&mut self, entry_fee_bps: u32, exit_fee_bps: u32, recipient: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if entry_fee_bps > MAX_FEE_BPS || exit_fee_bps > MAX_FEE_BPS {
+            self.env().revert(LendingError::InvalidConfiguration);
         }
+        self.shares.set_fees(entry_fee_bps, exit_fee_bps, recipient);
     }
     
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
-        if caller != admin {
-            self.env().revert(LendingError::Unauthorized);
+    /// Get the deposit and withdrawal fees, in bps
+    pub fn get_fees(&self) -> (u32, u32) {
+        (self.shares.entry_fee_bps(), self.shares.exit_fee_bps())
+    }
+    
+    /// Get the address receiving deposit and withdrawal fees
+    pub fn get_fee_recipient(&self) -> Option<Address> {
+        self.shares.fee_recipient()
+    }
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    /// Report a holder's new balance to the incentives controller, if any
+    fn notify_incentives(&self, holder: Address) {
+        if let Some(controller) = self.incentives_controller.get_or_default() {
+            LendingIncentivesContractRef::new(self.env(), controller)
+                .handle_action(holder, self.balance_of(holder), self.total_supply());
+        }
+    }
+    
+    /// ECTO the lending pool accepts from suppliers right now
+    fn available_supply(&self) -> U256 {
+        LendingPoolContractRef::new(self.env(), self.get_lending_pool()).get_available_supply()
+    }
+    
+    /// ECTO the lending pool lets suppliers withdraw right now
+    fn available_withdrawal(&self) -> U256 {
+        LendingPoolContractRef::new(self.env(), self.get_lending_pool()).get_available_withdrawal()
+    }
+    
+    fn fee_recipient_or_revert(&self) -> Address {
+        self.shares.fee_recipient().unwrap_or_revert_with(&self.env(), LendingError::InvalidConfiguration)
+    }
+    
+    fn ensure_pledge_healthy(&self, owner: Address) {
+        if self.is_pledged(owner) {
+            self.ensure_healthy(owner);
         }
     }
+    
+    /// Revert if the holder's borrow position is below a health factor of 1
+    fn ensure_healthy(&self, owner: Address) {
+        let collateral_manager = match self.collateral_manager.get_or_default() {
+            Some(address) => address,
+            None => return,
+        };
+        
+        let pool = LendingPoolContractRef::new(self.env(), self.get_lending_pool());
+        let debt = pool.get_borrow_position(owner)
+            .map(|position| position.principal + position.interest_accrued)
+            .unwrap_or_default();
+        if debt == U256::zero() {
+            return;
+        }
+        
+        let health_factor = CollateralManagerContractRef::new(self.env(), collateral_manager)
+            .calculate_health_factor(owner, debt);
+        let scale = U256::from(1_000_000_000_000_000_000u128); // 1e18
+        if health_factor < scale {
+            self.env().revert(LendingError::HealthFactorTooLow);
+        }
+    }
+    
 }
 
 // ============================================================================
 // CEP-4626 Implementation
 // ============================================================================
 
+impl Cep4626Hooks for AectoVault {
+    fn vault_total_assets(&self) -> U256 {
+        self.get_total_assets()
+    }
+    
+    fn vault_total_shares(&self) -> U256 {
+        self.token.total_supply()
+    }
+    
+    fn vault_shares_of(&self, owner: Address) -> U256 {
+        self.token.balance_of(owner)
+    }
+    
+    fn mint_vault_shares(&mut self, to: Address, shares: U256) {
+        self.token.mint(to, shares);
+    }
+    
+    fn burn_vault_shares(&mut self, from: Address, shares: U256) {
+        self.token.burn(from, shares);
+    }
+    
+    fn insufficient_shares_error(&self) -> OdraError {
+        LendingError::InsufficientBalance.into()
+    }
+    
+    fn before_deposit(&mut self, caller: Address, receiver: Address, assets: U256, shares: U256) {
+        if self.paused.get_or_default() {
+            self.env().revert(LendingError::ContractPaused);
+        }
+        
+        // Transfer ECTO from user straight into the lending pool
+        let pool_address = self.get_lending_pool();
+        let mut ecto_token = Cep18TokenContractRef::new(self.env(), self.asset());
+        ecto_token.transfer_from(caller, pool_address, assets);
+        LendingPoolContractRef::new(self.env(), pool_address)
+            .supply_liquidity(caller, receiver, assets, shares);
+    }
+    
+    fn after_deposit(&mut self, _caller: Address, receiver: Address, _assets: U256, _shares: U256) {
+        self.notify_incentives(receiver);
+        if let Some(registry) = self.referral_registry.get_or_default() {
+            ReferralRegistryContractRef::new(self.env(), registry).record_interaction(receiver);
+        }
+    }
+    
+    fn before_withdraw(
+        &mut self,
+        caller: Address,
+        _receiver: Address,
+        owner: Address,
+        _assets: U256,
+        shares: U256,
+    ) {
+        if self.paused.get_or_default() {
+            self.env().revert(LendingError::ContractPaused);
+        }
+        
+        // Check allowance if caller is not owner
+        if caller != owner {
+            let allowance = self.allowance(owner, caller);
+            if allowance < shares {
+                self.env().revert(LendingError::Unauthorized);
+            }
+            self.token.raw_approve(owner, caller, allowance - shares);
+        }
+    }
+    
+    fn after_withdraw(
+        &mut self,
+        _caller: Address,
+        receiver: Address,
+        owner: Address,
+        assets: U256,
+        shares: U256,
+    ) {
+        self.notify_incentives(owner);
+        
+        // The pool checks liquidity and sends the ECTO to the receiver
+        LendingPoolContractRef::new(self.env(), self.get_lending_pool())
+            .release_liquidity(owner, receiver, assets, shares);
+        self.ensure_pledge_healthy(owner);
+    }
+    
+    fn entry_fee_bps(&self) -> u32 {
+        self.shares.entry_fee_bps()
+    }
+    
+    fn exit_fee_bps(&self) -> u32 {
+        self.shares.exit_fee_bps()
+    }
+    
+    fn pay_entry_fee(&mut self, payer: Address, fee: U256) {
+        let recipient = self.fee_recipient_or_revert();
+        Cep18TokenContractRef::new(self.env(), self.asset()).transfer_from(payer, recipient, fee);
+    }
+    
+    fn pay_exit_fee(&mut self, owner: Address, fee: U256) {
+        // The fee leaves the pool like the rest of the withdrawal
+        let recipient = self.fee_recipient_or_revert();
+        LendingPoolContractRef::new(self.env(), self.get_lending_pool())
+            .release_liquidity(owner, recipient, fee, U256::zero());
+    }
+}
+
 impl Cep4626Vault for AectoVault {
     fn asset(&self) -> Address {
         self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration)
     }
     
     fn total_assets(&self) -> U256 {
-        self.total_assets.get_or_default()
+        self.get_total_assets()
     }
     
     fn convert_to_shares(&self, assets: U256) -> U256 {
-        let total_supply = self.total_supply();
-        let total_assets = self.total_assets();
-        
-        if total_supply == U256::zero() || total_assets == U256::zero() {
-            return assets; // 1:1 initial rate
-        }
-        
-        // shares = (assets * total_supply) / total_assets
-        (assets * total_supply) / total_assets
+        base::convert_to_shares(self, assets)
     }
     
     fn convert_to_assets(&self, shares: U256) -> U256 {
-        let total_supply = self.total_supply();
-        
-        if total_supply == U256::zero() {
-            return U256::zero();
-        }
-        
-        let total_assets = self.total_assets();
-        // assets = (shares * total_assets) / total_supply
-        (shares * total_assets) / total_supply
+        base::convert_to_assets(self, shares)
     }
     
     fn max_deposit(&self, _receiver: Address) -> U256 {
         if self.paused.get_or_default() {
             return U256::zero();
         }
-        U256::MAX
+        // Deposit pauses and the supply cap live in the pool; the entry fee
+        // only lowers what reaches it
+        self.available_supply()
     }
     
     fn max_mint(&self, _receiver: Address) -> U256 {
         if self.paused.get_or_default() {
             return U256::zero();
         }
-        U256::MAX
+        let available = self.available_supply();
+        if available == U256::MAX {
+            return U256::MAX;
+        }
+        self.convert_to_shares(available)
     }
     
     fn max_withdraw(&self, owner: Address) -> U256 {
         if self.paused.get_or_default() {
             return U256::zero();
         }
-        let shares = self.balance_of(owner);
-        self.convert_to_assets(shares)
+        let assets = base::max_withdraw(self, owner);
+        // The exit fee is released from the pool on top of the withdrawal
+        let released = self.available_withdrawal();
+        let available = released - helpers::fee_on_total(released, self.shares.exit_fee_bps());
+        if assets > available {
+            available
+        } else {
+            assets
+        }
     }
     
     fn max_redeem(&self, owner: Address) -> U256 {
         if self.paused.get_or_default() {
             return U256::zero();
         }
-        self.balance_of(owner)
+        let shares = base::max_redeem(self, owner);
+        let available = self.convert_to_shares(self.available_withdrawal());
+        if shares > available {
+            available
+        } else {
+            shares
+        }
     }
     
     fn preview_deposit(&self, assets: U256) -> U256 {
-        self.convert_to_shares(assets)
+        base::preview_deposit(self, assets)
     }
     
     fn preview_mint(&self, shares: U256) -> U256 {
-        self.convert_to_assets(shares)
+        base::preview_mint(self, shares)
     }
     
     fn preview_withdraw(&self, assets: U256) -> U256 {
-        self.convert_to_shares(assets)
+        base::preview_withdraw(self, assets)
     }
     
     fn preview_redeem(&self, shares: U256) -> U256 {
-        self.convert_to_assets(shares)
+        base::preview_redeem(self, shares)
     }
     
     fn deposit(&mut self, assets: U256, receiver: Address) -> U256 {
-        if self.paused.get_or_default() {
-            self.env().revert(LendingError::ContractPaused);
-        }
-        
-        let caller = self.env().caller();
-        let shares = self.convert_to_shares(assets);
-        
-        // Transfer ECTO from user to vault
-        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
-        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
-        ecto_token.transfer_from(caller, Address::from(self.env().self_address()), assets);
-        
-        // Update total assets
-        let current_total = self.total_assets.get_or_default();
-        self.total_assets.set(current_total + assets);
-        
-        // Mint shares
-        let current_supply = self.total_supply.get_or_default();
-        self.total_supply.set(current_supply + shares);
-        
-        let balance = self.balance_of(receiver);
-        self.balances.set(&receiver, balance + shares);
-        
-        // Emit CEP-4626 event
-        self.env().emit_event(Cep4626Deposit {
-            sender: caller,
-            owner: receiver,
-            assets,
-            shares,
-        });
-        
-        shares
+        base::deposit(self, assets, receiver)
     }
     
     fn mint(&mut self, shares: U256, receiver: Address) -> U256 {
-        let assets = self.convert_to_assets(shares);
-        let actual_shares = self.deposit(assets, receiver);
-        
-        if actual_shares < shares {
-            self.env().revert(LendingError::InsufficientBalance);
-        }
-        
-        assets
+        base::mint(self, shares, receiver)
     }
     
     fn withdraw(&mut self, assets: U256, receiver: Address, owner: Address) -> U256 {
-        if self.paused.get_or_default() {
-            self.env().revert(LendingError::ContractPaused);
-        }
-        
-        let caller = self.env().caller();
-        let shares = self.convert_to_shares(assets);
-        
-        // Check allowance if caller is not owner
-        if caller != owner {
-            let allowance = self.allowance(owner, caller);
-            if allowance < shares {
-                self.env().revert(LendingError::Unauthorized);
-            }
-            self.allowances.set(&(owner, caller), allowance - shares);
-        }
-        
-        // Check owner has sufficient shares
-        let owner_balance = self.balance_of(owner);
-        if owner_balance < shares {
-            self.env().revert(LendingError::InsufficientBalance);
-        }
-        
-        // Burn shares
-        self.balances.set(&owner, owner_balance - shares);
-        let current_supply = self.total_supply.get_or_default();
-        self.total_supply.set(current_supply - shares);
-        
-        // Update total assets
-        let current_total = self.total_assets.get_or_default();
-        self.total_assets.set(current_total - assets);
-        
-        // Transfer ECTO to receiver
-        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
-        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
-        ecto_token.transfer(receiver, assets);
-        
-        // Emit CEP-4626 event
-        self.env().emit_event(Cep4626Withdraw {
-            sender: caller,
-            receiver,
-            owner,
-            assets,
-            shares,
-        });
-        
-        shares
+        base::withdraw(self, assets, receiver, owner)
     }
     
     fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
-        let assets = self.convert_to_assets(shares);
-        let actual_shares = self.withdraw(assets, receiver, owner);
-        
-        if actual_shares > shares {
-            self.env().revert(LendingError::InsufficientBalance);
-        }
-        
-        assets
+        base::redeem(self, shares, receiver, owner)
     }
 }
@@ -0,0 +1,699 @@
+//! CDP Engine - Issues ECTO against collateral vaults
+//!
+//! Maker-style issuance for the ECTO stablecoin:
+//! - Users open a vault for one collateral type (sCSPR, WETH, WBTC, ...),
+//!   deposit collateral and mint ECTO against it
+//! - Debt grows with the collateral type's stability fee; accrued fees are
+//!   minted to the fee recipient, so ECTO in circulation matches vault debt
+//! - Vaults below their liquidation ratio are liquidated through the
+//!   LiquidationEngine: liquidators burn ECTO and receive collateral at the
+//!   liquidation bonus
+//! - Debt left on a vault with no collateral becomes bad debt, which anyone
+//!   can burn ECTO to cover
+//!
+//! Debt is stored normalized by the collateral type's accumulated rate (RAY),
+//! so fees accrue to every vault without touching each one.
+//!
+//! The engine must be allowed to mint and burn ECTO.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::LendingError;
+use super::events::*;
+use super::liquidation::LiquidationEngineContractRef;
+use super::price_oracle::PriceOracleContractRef;
+use crate::math::{compound_apr, ray_mul, RAY};
+use crate::token::Cep18TokenContractRef;
+use crate::tokens::EctoTokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// Parameters and debt of a collateral type
+#[odra::odra_type]
+pub struct CollateralType {
+    /// Minimum collateral value per unit of debt (scaled by 1e18)
+    /// Example: 150% = 1.5 * 1e18
+    pub liquidation_ratio: U256,
+    /// Liquidation bonus for liquidators (scaled by 1e18)
+    /// Example: 5% = 0.05 * 1e18
+    pub liquidation_bonus: U256,
+    /// Annual stability fee (RAY)
+    /// Example: 2% = 0.02 * 1e27
+    pub stability_fee: U256,
+    /// Maximum ECTO debt across all vaults of this type
+    pub debt_ceiling: U256,
+    /// Accumulated stability fee rate (RAY, starts at 1.0)
+    pub rate: U256,
+    /// Timestamp the rate was last accrued
+    pub last_accrual: u64,
+    /// Sum of normalized debt across vaults of this type
+    pub normalized_debt: U256,
+    /// Whether new vaults and minting are allowed
+    pub is_enabled: bool,
+}
+
+/// CDP vault
+#[odra::odra_type]
+pub struct Vault {
+    /// Vault owner
+    pub owner: Address,
+    /// Collateral asset
+    pub collateral_asset: Address,
+    /// Collateral held
+    pub collateral: U256,
+    /// Debt divided by the collateral type's rate (RAY)
+    pub normalized_debt: U256,
+}
+
+/// CDP Engine contract
+#[odra::module]
+pub struct CdpEngine {
+    /// ECTO token address
+    ecto_token: Var<Address>,
+    /// Price oracle address
+    price_oracle: Var<Address>,
+    /// Liquidation engine address
+    liquidation_engine: Var<Address>,
+    /// Recipient of accrued stability fees
+    fee_recipient: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Collateral types (asset -> type)
+    collateral_types: Mapping<Address, CollateralType>,
+    /// Vaults by ID
+    vaults: Mapping<u64, Vault>,
+    /// Number of vaults opened
+    vault_count: Var<u64>,
+    /// Debt left on liquidated vaults without collateral
+    bad_debt: Var<U256>,
+}
+
+#[odra::module]
+impl CdpEngine {
+    /// Initialize the CDP engine
+    pub fn init(
+        &mut self,
+        ecto_token_address: Address,
+        price_oracle_address: Address,
+        liquidation_engine_address: Address,
+        fee_recipient: Address,
+    ) {
+        let caller = self.env().caller();
+        self.ecto_token.set(ecto_token_address);
+        self.price_oracle.set(price_oracle_address);
+        self.liquidation_engine.set(liquidation_engine_address);
+        self.fee_recipient.set(fee_recipient);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.vault_count.set(0);
+        self.bad_debt.set(U256::zero());
+    }
+    
+    // ========================================
+    // Vault Functions
+    // ========================================
+    
+    /// Open a vault for a collateral type; returns its ID
+    pub fn open_vault(&mut self, collateral_asset: Address) -> u64 {
+        let collateral_type = self.get_collateral_type_or_revert(collateral_asset);
+        if !collateral_type.is_enabled {
+            self.env().revert(LendingError::CollateralDisabled);
+        }
+        
+        let owner = self.env().caller();
+        let vault_id = self.vault_count.get_or_default();
+        self.vaults.set(&vault_id, Vault {
+            owner,
+            collateral_asset,
+            collateral: U256::zero(),
+            normalized_debt: U256::zero(),
+        });
+        self.vault_count.set(vault_id + 1);
+        
+        self.env().emit_event(VaultOpened {
+            vault_id,
+            owner,
+            collateral_asset,
+        });
+        
+        vault_id
+    }
+    
+    /// Deposit collateral into a vault (pulled from the caller)
+    pub fn deposit_collateral(&mut self, vault_id: u64, amount: U256) {
+        if amount.is_zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        let mut vault = self.get_vault_or_revert(vault_id);
+        
+        let caller = self.env().caller();
+        let mut token = Cep18TokenContractRef::new(self.env(), vault.collateral_asset);
+        token.transfer_from(caller, self.env().self_address(), amount);
+        
+        vault.collateral += amount;
+        let collateral = vault.collateral;
+        self.vaults.set(&vault_id, vault);
+        
+        self.env().emit_event(VaultCollateralChanged {
+            vault_id,
+            deposited: amount,
+            withdrawn: U256::zero(),
+            collateral,
+        });
+    }
+    
+    /// Withdraw collateral from a vault to its owner, keeping it safe (owner only)
+    pub fn withdraw_collateral(&mut self, vault_id: u64, amount: U256) {
+        let mut vault = self.get_owned_vault_or_revert(vault_id);
+        if amount.is_zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        if amount > vault.collateral {
+            self.env().revert(LendingError::CannotWithdrawCollateral);
+        }
+        
+        let collateral_type = self.accrue(vault.collateral_asset);
+        vault.collateral -= amount;
+        self.ensure_safe(&vault, &collateral_type);
+        
+        let owner = vault.owner;
+        let asset = vault.collateral_asset;
+        let collateral = vault.collateral;
+        self.vaults.set(&vault_id, vault);
+        
+        Cep18TokenContractRef::new(self.env(), asset).transfer(owner, amount);
+        
+        self.env().emit_event(VaultCollateralChanged {
+            vault_id,
+            deposited: U256::zero(),
+            withdrawn: amount,
+            collateral,
+        });
+    }
+    
+    /// Mint ECTO to the vault owner against its collateral (owner only)
+    pub fn mint(&mut self, vault_id: u64, amount: U256) {
+        let mut vault = self.get_owned_vault_or_revert(vault_id);
+        if amount.is_zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        
+        let mut collateral_type = self.accrue(vault.collateral_asset);
+        if !collateral_type.is_enabled {
+            self.env().revert(LendingError::CollateralDisabled);
+        }
+        
+        // Round the normalized debt up so minting never undercharges
+        let normalized = (amount * U256::from(RAY) + collateral_type.rate - 1) / collateral_type.rate;
+        vault.normalized_debt += normalized;
+        collateral_type.normalized_debt += normalized;
+        if Self::debt_of(collateral_type.normalized_debt, collateral_type.rate) > collateral_type.debt_ceiling {
+            self.env().revert(LendingError::DebtCeilingExceeded);
+        }
+        self.ensure_safe(&vault, &collateral_type);
+        
+        let owner = vault.owner;
+        let debt = Self::debt_of(vault.normalized_debt, collateral_type.rate);
+        self.collateral_types.set(&vault.collateral_asset, collateral_type);
+        self.vaults.set(&vault_id, vault);
+        
+        EctoTokenContractRef::new(self.env(), self.ecto()).mint(owner, amount);
+        
+        self.env().emit_event(VaultDebtMinted {
+            vault_id,
+            amount,
+            debt,
+        });
+    }
+    
+    /// Repay a vault's debt with ECTO from the caller, which is burned
+    /// Repays at most the vault's debt; returns the amount repaid
+    pub fn repay(&mut self, vault_id: u64, amount: U256) -> U256 {
+        let mut vault = self.get_vault_or_revert(vault_id);
+        let mut collateral_type = self.accrue(vault.collateral_asset);
+        
+        let debt = Self::debt_of(vault.normalized_debt, collateral_type.rate);
+        let amount = amount.min(debt);
+        if amount.is_zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        
+        let payer = self.env().caller();
+        self.burn_from(payer, amount);
+        
+        let normalized = self.normalized_repayment(&vault, &collateral_type, amount, debt);
+        vault.normalized_debt -= normalized;
+        collateral_type.normalized_debt -= normalized;
+        let remaining = Self::debt_of(vault.normalized_debt, collateral_type.rate);
+        self.collateral_types.set(&vault.collateral_asset, collateral_type);
+        self.vaults.set(&vault_id, vault);
+        
+        self.env().emit_event(VaultDebtRepaid {
+            vault_id,
+            payer,
+            amount,
+            debt: remaining,
+        });
+        
+        amount
+    }
+    
+    /// Liquidate an unsafe vault: burn up to `debt_to_cover` ECTO from the
+    /// caller for collateral at the liquidation bonus
+    /// The LiquidationEngine decides whether the vault can be liquidated and
+    /// caps the debt covered per call. Returns (debt covered, collateral seized).
+    pub fn liquidate(&mut self, vault_id: u64, debt_to_cover: U256) -> (U256, U256) {
+        let mut vault = self.get_vault_or_revert(vault_id);
+        let mut collateral_type = self.accrue(vault.collateral_asset);
+        if !self.oracle().is_price_fresh(vault.collateral_asset) {
+            self.env().revert(LendingError::StalePrice);
+        }
+        
+        let debt = Self::debt_of(vault.normalized_debt, collateral_type.rate);
+        let collateral_value = self.collateral_value(&vault);
+        let engine = LiquidationEngineContractRef::new(
+            self.env(),
+            self.liquidation_engine.get_or_revert_with(LendingError::InvalidConfiguration),
+        );
+        if debt.is_zero() || !engine.can_liquidate(Self::health_factor(collateral_value, debt, &collateral_type)) {
+            self.env().revert(LendingError::PositionHealthy);
+        }
+        
+        let (debt_covered, value_to_seize) = engine.calculate_liquidation_amounts(
+            debt_to_cover,
+            debt,
+            collateral_value,
+            collateral_type.liquidation_bonus,
+        );
+        
+        // Seizing the full value takes every token so no rounding dust is left behind
+        let collateral_seized = if value_to_seize == collateral_value {
+            vault.collateral
+        } else {
+            self.oracle().get_asset_amount(vault.collateral_asset, value_to_seize)
+        };
+        
+        let liquidator = self.env().caller();
+        self.burn_from(liquidator, debt_covered);
+        
+        let normalized = self.normalized_repayment(&vault, &collateral_type, debt_covered, debt);
+        vault.normalized_debt -= normalized;
+        collateral_type.normalized_debt -= normalized;
+        vault.collateral -= collateral_seized;
+        
+        // Debt left on a vault with nothing to back it is written off as bad debt
+        let mut written_off = U256::zero();
+        if vault.collateral.is_zero() && !vault.normalized_debt.is_zero() {
+            written_off = Self::debt_of(vault.normalized_debt, collateral_type.rate);
+            collateral_type.normalized_debt -= vault.normalized_debt;
+            vault.normalized_debt = U256::zero();
+            self.bad_debt.set(self.bad_debt.get_or_default() + written_off);
+        }
+        
+        let asset = vault.collateral_asset;
+        self.collateral_types.set(&asset, collateral_type);
+        self.vaults.set(&vault_id, vault);
+        
+        Cep18TokenContractRef::new(self.env(), asset).transfer(liquidator, collateral_seized);
+        
+        self.env().emit_event(VaultLiquidated {
+            vault_id,
+            liquidator,
+            debt_covered,
+            collateral_seized,
+            bad_debt: written_off,
+        });
+        
+        (debt_covered, collateral_seized)
+    }
+    
+    /// Burn ECTO from the caller to cover bad debt; returns the amount covered
+    pub fn cover_bad_debt(&mut self, amount: U256) -> U256 {
+        let amount = amount.min(self.bad_debt.get_or_default());
+        if amount.is_zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        let caller = self.env().caller();
+        self.burn_from(caller, amount);
+        self.bad_debt.set(self.bad_debt.get_or_default() - amount);
+        amount
+    }
+    
+    /// Accrue stability fees on a collateral type (anyone)
+    pub fn drip(&mut self, collateral_asset: Address) {
+        self.accrue(collateral_asset);
+    }
+    
+    // ========================================
+    // View Functions
+    // ========================================
+    
+    /// Get a vault
+    pub fn get_vault(&self, vault_id: u64) -> Option<Vault> {
+        self.vaults.get(&vault_id)
+    }
+    
+    /// Get the number of vaults opened
+    pub fn get_vault_count(&self) -> u64 {
+        self.vault_count.get_or_default()
+    }
+    
+    /// Get a vault's debt including stability fees not yet accrued
+    pub fn get_vault_debt(&self, vault_id: u64) -> U256 {
+        let vault = self.get_vault_or_revert(vault_id);
+        let rate = self.current_rate(&self.get_collateral_type_or_revert(vault.collateral_asset));
+        Self::debt_of(vault.normalized_debt, rate)
+    }
+    
+    /// Get a vault's health factor (scaled by 1e18; below 1.0 is liquidatable)
+    pub fn get_vault_health(&self, vault_id: u64) -> U256 {
+        let vault = self.get_vault_or_revert(vault_id);
+        let collateral_type = self.get_collateral_type_or_revert(vault.collateral_asset);
+        let debt = Self::debt_of(vault.normalized_debt, self.current_rate(&collateral_type));
+        Self::health_factor(self.collateral_value(&vault), debt, &collateral_type)
+    }
+    
+    /// Get a collateral type
+    pub fn get_collateral_type(&self, collateral_asset: Address) -> Option<CollateralType> {
+        self.collateral_types.get(&collateral_asset)
+    }
+    
+    /// Get the ECTO debt of a collateral type including fees not yet accrued
+    pub fn get_total_debt(&self, collateral_asset: Address) -> U256 {
+        let collateral_type = self.get_collateral_type_or_revert(collateral_asset);
+        Self::debt_of(collateral_type.normalized_debt, self.current_rate(&collateral_type))
+    }
+    
+    /// Get the debt written off on liquidated vaults and not yet covered
+    pub fn get_bad_debt(&self) -> U256 {
+        self.bad_debt.get_or_default()
+    }
+    
+    /// Get the recipient of accrued stability fees
+    pub fn get_fee_recipient(&self) -> Address {
+        self.fee_recipient.get_or_revert_with(LendingError::InvalidConfiguration)
+    }
+    
+    // ========================================
+    // Admin Functions
+    // ========================================
+    
+    /// Add or update a collateral type (admin only)
+    /// Fees up to now accrue at the previous stability fee
+    pub fn set_collateral_type(
+        &mut self,
+        collateral_asset: Address,
+        liquidation_ratio: U256,
+        liquidation_bonus: U256,
+        stability_fee: U256,
+        debt_ceiling: U256,
+        is_enabled: bool,
+    ) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if liquidation_ratio < U256::from(1_000_000_000_000_000_000u128) {
+            self.env().revert(LendingError::InvalidConfiguration);
+        }
+        
+        let mut collateral_type = match self.collateral_types.get(&collateral_asset) {
+            Some(_) => self.accrue(collateral_asset),
+            None => CollateralType {
+                liquidation_ratio,
+                liquidation_bonus,
+                stability_fee,
+                debt_ceiling,
+                rate: U256::from(RAY),
+                last_accrual: self.env().get_block_time(),
+                normalized_debt: U256::zero(),
+                is_enabled,
+            },
+        };
+        collateral_type.liquidation_ratio = liquidation_ratio;
+        collateral_type.liquidation_bonus = liquidation_bonus;
+        collateral_type.stability_fee = stability_fee;
+        collateral_type.debt_ceiling = debt_ceiling;
+        collateral_type.is_enabled = is_enabled;
+        self.collateral_types.set(&collateral_asset, collateral_type);
+    }
+    
+    /// Set the recipient of accrued stability fees (admin only)
+    pub fn set_fee_recipient(&mut self, fee_recipient: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.fee_recipient.set(fee_recipient);
+    }
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    // ========================================
+    // Internal Functions
+    // ========================================
+    
+    fn ecto(&self) -> Address {
+        self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration)
+    }
+    
+    fn oracle(&self) -> PriceOracleContractRef {
+        PriceOracleContractRef::new(
+            self.env(),
+            self.price_oracle.get_or_revert_with(LendingError::OracleNotInitialized),
+        )
+    }
+    
+    fn get_vault_or_revert(&self, vault_id: u64) -> Vault {
+        self.vaults.get(&vault_id)
+            .unwrap_or_revert_with(&self.env(), LendingError::VaultNotFound)
+    }
+    
+    fn get_owned_vault_or_revert(&self, vault_id: u64) -> Vault {
+        let vault = self.get_vault_or_revert(vault_id);
+        if vault.owner != self.env().caller() {
+            self.env().revert(LendingError::Unauthorized);
+        }
+        vault
+    }
+    
+    fn get_collateral_type_or_revert(&self, collateral_asset: Address) -> CollateralType {
+        self.collateral_types.get(&collateral_asset)
+            .unwrap_or_revert_with(&self.env(), LendingError::UnsupportedCollateral)
+    }
+    
+    /// Accumulated rate with fees accrued up to now
+    fn current_rate(&self, collateral_type: &CollateralType) -> U256 {
+        let elapsed = self.env().get_block_time().saturating_sub(collateral_type.last_accrual);
+        compound_apr(collateral_type.stability_fee, elapsed)
+            .and_then(|growth| ray_mul(collateral_type.rate, growth))
+            .unwrap_or_else(|_| self.env().revert(LendingError::MathOverflow))
+    }
+    
+    /// Accrue stability fees on a collateral type, minting them to the fee recipient
+    fn accrue(&mut self, collateral_asset: Address) -> CollateralType {
+        let mut collateral_type = self.get_collateral_type_or_revert(collateral_asset);
+        let now = self.env().get_block_time();
+        if now <= collateral_type.last_accrual {
+            return collateral_type;
+        }
+        
+        let rate = self.current_rate(&collateral_type);
+        let fees = collateral_type.normalized_debt * (rate - collateral_type.rate) / U256::from(RAY);
+        collateral_type.rate = rate;
+        collateral_type.last_accrual = now;
+        self.collateral_types.set(&collateral_asset, collateral_type.clone());
+        
+        if !fees.is_zero() {
+            EctoTokenContractRef::new(self.env(), self.ecto()).mint_fees(self.get_fee_recipient(), fees);
+            self.env().emit_event(StabilityFeesAccrued {
+                asset: collateral_asset,
+                amount: fees,
+                rate,
+            });
+        }
+        
+        collateral_type
+    }
+    
+    /// Debt of a normalized amount, rounded up
+    fn debt_of(normalized_debt: U256, rate: U256) -> U256 {
+        let ray = U256::from(RAY);
+        (normalized_debt * rate + ray - 1) / ray
+    }
+    
+    /// Normalized debt cleared by repaying `amount` of `debt`
+    /// Rounded down, except that repaying the whole debt clears it exactly
+    fn normalized_repayment(&self, vault: &Vault, collateral_type: &CollateralType, amount: U256, debt: U256) -> U256 {
+        if amount >= debt {
+            return vault.normalized_debt;
+        }
+        (amount * U256::from(RAY) / collateral_type.rate).min(vault.normalized_debt)
+    }
+    
+    fn collateral_value(&self, vault: &Vault) -> U256 {
+        if vault.collateral.is_zero() {
+            return U256::zero();
+        }
+        self.oracle().get_asset_value(vault.collateral_asset, vault.collateral)
+    }
+    
+    /// Collateral value over the value the liquidation ratio requires (scaled by 1e18)
+    fn health_factor(collateral_value: U256, debt: U256, collateral_type: &CollateralType) -> U256 {
+        if debt.is_zero() {
+            return U256::MAX;
+        }
+        let scale = U256::from(1_000_000_000_000_000_000u128);
+        let required = debt * collateral_type.liquidation_ratio / scale;
+        collateral_value * scale / required
+    }
+    
+    /// Revert unless the vault's collateral covers its debt at the liquidation ratio
+    fn ensure_safe(&self, vault: &Vault, collateral_type: &CollateralType) {
+        let debt = Self::debt_of(vault.normalized_debt, collateral_type.rate);
+        if debt.is_zero() {
+            return;
+        }
+        if !self.oracle().is_price_fresh(vault.collateral_asset) {
+            self.env().revert(LendingError::StalePrice);
+        }
+        let scale = U256::from(1_000_000_000_000_000_000u128);
+        if Self::health_factor(self.collateral_value(vault), debt, collateral_type) < scale {
+            self.env().revert(LendingError::VaultUnsafe);
+        }
+    }
+    
+    /// Pull ECTO from `from` and burn it
+    fn burn_from(&self, from: Address, amount: U256) {
+        let ecto = self.ecto();
+        let self_address = self.env().self_address();
+        Cep18TokenContractRef::new(self.env(), ecto).transfer_from(from, self_address, amount);
+        EctoTokenContractRef::new(self.env(), ecto).burn(self_address, amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lending::liquidation::LiquidationEngine;
+    use crate::lending::price_oracle::PriceOracle;
+    use crate::tokens::{EctoToken, WethToken};
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    const WAD: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn test_mint_accrue_fees_and_liquidate() {
+        let env = odra_test::env();
+        let (admin, user, treasury) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut weth = WethToken::deploy(&env, NoArgs);
+        let mut oracle = PriceOracle::deploy(&env, NoArgs);
+        let liquidation_engine = LiquidationEngine::deploy(&env, NoArgs);
+        let mut cdp = CdpEngine::deploy(&env, CdpEngineInitArgs {
+            ecto_token_address: ecto.address(),
+            price_oracle_address: oracle.address(),
+            liquidation_engine_address: liquidation_engine.address(),
+            fee_recipient: treasury,
+        });
+        // 150% ratio, 5% bonus, 5% stability fee
+        cdp.set_collateral_type(
+            weth.address(),
+            U256::from(WAD * 3 / 2),
+            U256::from(WAD / 20),
+            U256::from(RAY / 20),
+            U256::from(1_000_000),
+            true,
+        );
+        oracle.set_price(weth.address(), U256::from(2_000 * WAD));
+        ecto.set_minter(cdp.address(), U256::from(1_000_000));
+        ecto.set_fee_minter(cdp.address(), true);
+        weth.mint(user, U256::from(10));
+
+        env.set_caller(user);
+        let vault_id = cdp.open_vault(weth.address());
+        weth.approve(cdp.address(), U256::from(10));
+        cdp.deposit_collateral(vault_id, U256::from(10));
+        cdp.mint(vault_id, U256::from(10_000));
+        assert_eq!(ecto.balance_of(user), U256::from(10_000));
+        // 14,000 of debt needs 21,000 of collateral
+        assert_eq!(cdp.try_mint(vault_id, U256::from(4_000)).unwrap_err(), LendingError::VaultUnsafe.into());
+
+        // A year of stability fees is minted to the fee recipient
+        env.advance_block_time(crate::math::SECONDS_PER_YEAR);
+        cdp.drip(weth.address());
+        let debt = cdp.get_vault_debt(vault_id);
+        assert!(debt > U256::from(10_000));
+        assert!(ecto.balance_of(treasury) > U256::zero());
+
+        // Nothing is seized against the year-old price
+        env.set_caller(admin);
+        assert_eq!(
+            cdp.try_liquidate(vault_id, U256::from(5_000)).unwrap_err(),
+            LendingError::StalePrice.into()
+        );
+
+        // Price drop makes the vault liquidatable
+        oracle.set_price(weth.address(), U256::from(1_400 * WAD));
+        assert!(cdp.get_vault_health(vault_id) < U256::from(WAD));
+        ecto.mint(admin, U256::from(5_000));
+        ecto.approve(cdp.address(), U256::from(5_000));
+        let (covered, seized) = cdp.liquidate(vault_id, U256::from(5_000));
+        assert_eq!(covered, U256::from(5_000));
+        // 5,250 of value at 1,400 per WETH
+        assert_eq!(seized, U256::from(3));
+        assert_eq!(weth.balance_of(admin), U256::from(3));
+        assert_eq!(ecto.balance_of(admin), U256::zero());
+        assert!(cdp.get_vault_debt(vault_id) < debt);
+    }
+
+    #[test]
+    fn test_fees_accrue_and_liquidations_run_at_the_mint_cap() {
+        let env = odra_test::env();
+        let (admin, user, treasury) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut weth = WethToken::deploy(&env, NoArgs);
+        let mut oracle = PriceOracle::deploy(&env, NoArgs);
+        let liquidation_engine = LiquidationEngine::deploy(&env, NoArgs);
+        let mut cdp = CdpEngine::deploy(&env, CdpEngineInitArgs {
+            ecto_token_address: ecto.address(),
+            price_oracle_address: oracle.address(),
+            liquidation_engine_address: liquidation_engine.address(),
+            fee_recipient: treasury,
+        });
+        cdp.set_collateral_type(
+            weth.address(),
+            U256::from(WAD * 3 / 2),
+            U256::from(WAD / 20),
+            U256::from(RAY / 20),
+            U256::from(1_000_000),
+            true,
+        );
+        oracle.set_price(weth.address(), U256::from(2_000 * WAD));
+        // The cap only leaves room for the principal
+        ecto.set_minter(cdp.address(), U256::from(10_000));
+        ecto.set_fee_minter(cdp.address(), true);
+        weth.mint(user, U256::from(10));
+
+        env.set_caller(user);
+        let vault_id = cdp.open_vault(weth.address());
+        weth.approve(cdp.address(), U256::from(10));
+        cdp.deposit_collateral(vault_id, U256::from(10));
+        cdp.mint(vault_id, U256::from(10_000));
+        assert_eq!(ecto.get_minted(cdp.address()), ecto.get_minter_cap(cdp.address()));
+
+        // A year of fees is minted past the cap, and liquidation still works
+        env.advance_block_time(crate::math::SECONDS_PER_YEAR);
+        env.set_caller(admin);
+        oracle.set_price(weth.address(), U256::from(1_400 * WAD));
+        ecto.mint(admin, U256::from(5_000));
+        ecto.approve(cdp.address(), U256::from(5_000));
+        let (covered, _) = cdp.liquidate(vault_id, U256::from(5_000));
+        assert_eq!(covered, U256::from(5_000));
+        assert!(ecto.balance_of(treasury) > U256::zero());
+    }
+}
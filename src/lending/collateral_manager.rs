@@ -11,8 +11,11 @@ use odra::casper_types::U256;
 use odra::ContractRef;
 use super::errors::LendingError;
 use super::events::*;
+use super::aecto_vault::AectoVaultContractRef;
+use super::lending_pool::LendingPoolContractRef;
 use super::price_oracle::PriceOracleContractRef;
 use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE, REGISTRAR_ROLE};
 
 /// Collateral configuration for an asset
 #[odra::odra_type]
@@ -43,6 +46,34 @@ pub struct CollateralPosition {
     pub amount: U256,
 }
 
+/// One collateral asset in a user's portfolio, valued in ECTO
+#[odra::odra_type]
+pub struct CollateralAssetValue {
+    /// Collateral asset
+    pub asset: Address,
+    /// Amount deposited (or pledged, for aECTO)
+    pub amount: U256,
+    /// Current value in ECTO
+    pub value: U256,
+    /// Value weighted by LTV (what it lets the user borrow)
+    pub borrow_power: U256,
+    /// Value weighted by liquidation threshold
+    pub threshold_value: U256,
+}
+
+/// Every collateral position of a user with portfolio totals
+#[odra::odra_type]
+pub struct CollateralPortfolio {
+    /// Per-asset breakdown
+    pub positions: Vec<CollateralAssetValue>,
+    /// Total value in ECTO
+    pub total_value: U256,
+    /// Total LTV-weighted borrow power
+    pub total_borrow_power: U256,
+    /// Total threshold-weighted value
+    pub total_threshold_value: U256,
+}
+
 /// Collateral Manager contract
 #[odra::module]
 pub struct CollateralManager {
@@ -61,8 +92,8 @@ pub struct CollateralManager {
     /// Price oracle reference
     price_oracle: Var<Address>,
     
-    /// Admin address
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     
     /// Scale factor (1e18)
     scale: Var<U256>,
@@ -70,6 +101,12 @@ pub struct CollateralManager {
     /// Minimum health factor (scaled by 1e18)
     /// Example: 1.0 = 1e18
     min_health_factor: Var<U256>,
+    
+    /// Lending pool allowed to move user collateral
+    lending_pool: Var<Option<Address>>,
+    
+    /// aECTO vault whose pledged balances count as collateral in place
+    aecto_vault: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -80,17 +117,18 @@ impl CollateralManager {
     /// * `price_oracle_address` - Address of the price oracle
     pub fn init(&mut self, price_oracle_address: Address) {
         let caller = self.env().caller();
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(REGISTRAR_ROLE, caller);
         self.price_oracle.set(price_oracle_address);
         self.scale.set(U256::from(1_000_000_000_000_000_000u128)); // 1e18
         self.min_health_factor.set(U256::from(1_000_000_000_000_000_000u128)); // 1.0
     }
     
     // ========================================
-    // Collateral Configuration (Admin)
+    // Collateral Configuration (Registrar / Admin)
     // ========================================
     
-    /// Add a new collateral type
+    /// Add a new collateral type (registrar only)
     /// 
     /// # Arguments
     /// * `asset` - Collateral asset address
@@ -104,7 +142,7 @@ impl CollateralManager {
         liquidation_threshold: U256,
         liquidation_bonus: U256,
     ) {
-        self.only_admin();
+        self.access.require_role(REGISTRAR_ROLE, LendingError::Unauthorized);
         
         // Validate parameters
         let scale = self.scale.get_or_default();
@@ -125,17 +163,16 @@ impl CollateralManager {
         
         self.collateral_configs.set(&asset, config);
         
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
         self.env().emit_event(CollateralAdded {
             asset,
             ltv,
             liquidation_threshold,
             liquidation_bonus,
-            added_by: admin,
+            added_by: self.env().caller(),
         });
     }
     
-    /// Update collateral parameters
+    /// Update collateral parameters (admin only)
     pub fn update_collateral(
         &mut self,
         asset: Address,
@@ -143,7 +180,7 @@ impl CollateralManager {
         liquidation_threshold: U256,
         liquidation_bonus: U256,
     ) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
         
         let mut config = self.collateral_configs.get(&asset)
             .unwrap_or_revert_with(&self.env(), LendingError::UnsupportedCollateral);
@@ -163,19 +200,18 @@ impl CollateralManager {
         
         self.collateral_configs.set(&asset, config);
         
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
         self.env().emit_event(CollateralUpdated {
             asset,
             ltv,
             liquidation_threshold,
             liquidation_bonus,
-            updated_by: admin,
+            updated_by: self.env().caller(),
         });
     }
     
-    /// Enable/disable a collateral type
+    /// Enable/disable a collateral type (admin only)
     pub fn set_collateral_enabled(&mut self, asset: Address, enabled: bool) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
         
         let mut config = self.collateral_configs.get(&asset)
             .unwrap_or_revert_with(&self.env(), LendingError::UnsupportedCollateral);
@@ -208,6 +244,11 @@ impl CollateralManager {
             self.env().revert(LendingError::CollateralDisabled);
         }
         
+        // aECTO stays in the holder's wallet and is pledged on the vault instead
+        if self.aecto_vault.get_or_default() == Some(asset) {
+            self.env().revert(LendingError::OperationNotAllowed);
+        }
+        
         // Transfer collateral from user to contract
         let mut token = Cep18TokenContractRef::new(self.env(), asset);
         token.transfer_from(caller, Address::from(self.env().self_address()), amount);
@@ -231,16 +272,21 @@ impl CollateralManager {
             amount,
             timestamp,
         });
+        
+        self.notify_health_factor(caller);
     }
     
     /// Withdraw collateral
     /// 
+    /// The caller's debt is read from the lending pool, so the withdrawal
+    /// is rejected if it would leave the position below the minimum health factor.
+    /// 
     /// # Arguments
     /// * `asset` - Collateral asset address
     /// * `amount` - Amount to withdraw
-    /// * `user_debt` - User's current debt (for health factor check)
-    pub fn withdraw_collateral(&mut self, asset: Address, amount: U256, user_debt: U256) {
+    pub fn withdraw_collateral(&mut self, asset: Address, amount: U256) {
         let caller = self.env().caller();
+        let user_debt = self.user_debt(caller);
         
         if amount == U256::zero() {
             self.env().revert(LendingError::ZeroAmount);
@@ -278,6 +324,36 @@ impl CollateralManager {
             amount,
             timestamp,
         });
+        
+        self.notify_health_factor(caller);
+    }
+    
+    /// Release a user's collateral to `to` (lending pool only)
+    /// The pool is responsible for checking the position stays healthy
+    pub fn release_collateral(&mut self, user: Address, asset: Address, amount: U256, to: Address) {
+        self.only_lending_pool();
+        self.move_collateral(user, asset, amount, to);
+        
+        self.env().emit_event(CollateralWithdrawn {
+            user,
+            asset,
+            amount,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Seize a liquidated borrower's collateral and send it to `to` (lending pool only)
+    pub fn seize(&mut self, borrower: Address, asset: Address, amount: U256, to: Address) {
+        self.only_lending_pool();
+        self.move_collateral(borrower, asset, amount, to);
+        
+        self.env().emit_event(CollateralSeized {
+            borrower,
+            asset,
+            amount,
+            to,
+            timestamp: self.env().get_block_time(),
+        });
     }
     
     // ========================================
@@ -319,45 +395,31 @@ impl CollateralManager {
         let oracle_address = self.price_oracle.get_or_revert_with(LendingError::OracleNotInitialized);
         let oracle = PriceOracleContractRef::new(self.env(), oracle_address);
         
-        let count = self.user_collateral_count.get(&user).unwrap_or(0);
         let mut total_value = U256::zero();
         
-        for i in 0..count {
-            if let Some(asset) = self.user_collateral_assets.get(&(user, i)) {
-                if let Some(amount) = self.user_collateral.get(&(user, asset)) {
-                    if amount > U256::zero() {
-                        let value = oracle.get_asset_value(asset, amount);
-                        total_value = total_value + value;
-                    }
-                }
-            }
+        for (asset, amount) in self.user_collateral_positions(user) {
+            let value = oracle.get_asset_value(asset, amount);
+            total_value = total_value + value;
         }
         
         total_value
     }
     
     /// Get user's collateral value weighted by liquidation threshold
-    fn get_user_collateral_value_with_threshold(&self, user: Address) -> U256 {
+    pub fn get_user_collateral_value_with_threshold(&self, user: Address) -> U256 {
         let oracle_address = self.price_oracle.get_or_revert_with(LendingError::OracleNotInitialized);
         let oracle = PriceOracleContractRef::new(self.env(), oracle_address);
         let scale = self.scale.get_or_default();
         
-        let count = self.user_collateral_count.get(&user).unwrap_or(0);
         let mut total_value = U256::zero();
         
-        for i in 0..count {
-            if let Some(asset) = self.user_collateral_assets.get(&(user, i)) {
-                if let Some(amount) = self.user_collateral.get(&(user, asset)) {
-                    if amount > U256::zero() {
-                        let config = self.collateral_configs.get(&asset)
-                            .unwrap_or_revert_with(&self.env(), LendingError::UnsupportedCollateral);
-                        
-                        let value = oracle.get_asset_value(asset, amount);
-                        let weighted_value = (value * config.liquidation_threshold) / scale;
-                        total_value = total_value + weighted_value;
-                    }
-                }
-            }
+        for (asset, amount) in self.user_collateral_positions(user) {
+            let config = self.collateral_configs.get(&asset)
+                .unwrap_or_revert_with(&self.env(), LendingError::UnsupportedCollateral);
+            
+            let value = oracle.get_asset_value(asset, amount);
+            let weighted_value = (value * config.liquidation_threshold) / scale;
+            total_value = total_value + weighted_value;
         }
         
         total_value
@@ -369,29 +431,61 @@ impl CollateralManager {
         let oracle = PriceOracleContractRef::new(self.env(), oracle_address);
         let scale = self.scale.get_or_default();
         
-        let count = self.user_collateral_count.get(&user).unwrap_or(0);
         let mut max_borrow = U256::zero();
         
-        for i in 0..count {
-            if let Some(asset) = self.user_collateral_assets.get(&(user, i)) {
-                if let Some(amount) = self.user_collateral.get(&(user, asset)) {
-                    if amount > U256::zero() {
-                        let config = self.collateral_configs.get(&asset)
-                            .unwrap_or_revert_with(&self.env(), LendingError::UnsupportedCollateral);
-                        
-                        let value = oracle.get_asset_value(asset, amount);
-                        let borrow_power = (value * config.ltv) / scale;
-                        max_borrow = max_borrow + borrow_power;
-                    }
-                }
-            }
+        for (asset, amount) in self.user_collateral_positions(user) {
+            let config = self.collateral_configs.get(&asset)
+                .unwrap_or_revert_with(&self.env(), LendingError::UnsupportedCollateral);
+            
+            let value = oracle.get_asset_value(asset, amount);
+            let borrow_power = (value * config.ltv) / scale;
+            max_borrow = max_borrow + borrow_power;
         }
         
         max_borrow
     }
     
+    /// Get every collateral position of a user, valued and weighted, in one call
+    pub fn get_user_collateral_positions(&self, user: Address) -> CollateralPortfolio {
+        let oracle_address = self.price_oracle.get_or_revert_with(LendingError::OracleNotInitialized);
+        let oracle = PriceOracleContractRef::new(self.env(), oracle_address);
+        let scale = self.scale.get_or_default();
+        
+        let mut portfolio = CollateralPortfolio {
+            positions: Vec::new(),
+            total_value: U256::zero(),
+            total_borrow_power: U256::zero(),
+            total_threshold_value: U256::zero(),
+        };
+        
+        for (asset, amount) in self.user_collateral_positions(user) {
+            let config = self.collateral_configs.get(&asset)
+                .unwrap_or_revert_with(&self.env(), LendingError::UnsupportedCollateral);
+            
+            let value = oracle.get_asset_value(asset, amount);
+            let borrow_power = (value * config.ltv) / scale;
+            let threshold_value = (value * config.liquidation_threshold) / scale;
+            
+            portfolio.total_value = portfolio.total_value + value;
+            portfolio.total_borrow_power = portfolio.total_borrow_power + borrow_power;
+            portfolio.total_threshold_value = portfolio.total_threshold_value + threshold_value;
+            portfolio.positions.push(CollateralAssetValue {
+                asset,
+                amount,
+                value,
+                borrow_power,
+                threshold_value,
+            });
+        }
+        
+        portfolio
+    }
+    
     /// Get user's collateral balance for an asset
     pub fn get_user_collateral(&self, user: Address, asset: Address) -> U256 {
+        if self.aecto_vault.get_or_default() == Some(asset) {
+            return self.pledged_aecto(user);
+        }
         self.user_collateral.get(&(user, asset)).unwrap_or(U256::zero())
     }
     
@@ -413,15 +507,138 @@ impl CollateralManager {
         health_factor < min_health
     }
     
+    /// Set the lending pool allowed to move user collateral (admin only)
+    pub fn set_lending_pool(&mut self, lending_pool: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.lending_pool.set(Some(lending_pool));
+    }
+    
+    /// Get the lending pool allowed to move user collateral
+    pub fn get_lending_pool(&self) -> Option<Address> {
+        self.lending_pool.get_or_default()
+    }
+    
+    /// Set the aECTO vault whose pledged balances count as collateral (admin only)
+    /// aECTO must also be registered with `add_collateral` and priced by the oracle
+    pub fn set_aecto_vault(&mut self, aecto_vault: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.aecto_vault.set(Some(aecto_vault));
+    }
+    
+    /// Get the aECTO vault whose pledged balances count as collateral
+    pub fn get_aecto_vault(&self) -> Option<Address> {
+        self.aecto_vault.get_or_default()
+    }
+    
     // ========================================
-    // Admin Functions
+    // Access Control
     // ========================================
     
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
-        if caller != admin {
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    fn only_lending_pool(&self) {
+        if self.lending_pool.get_or_default() != Some(self.env().caller()) {
             self.env().revert(LendingError::Unauthorized);
         }
     }
+    
+    /// Debt (principal + interest) the lending pool holds against a user
+    fn user_debt(&self, user: Address) -> U256 {
+        let pool_address = self.lending_pool.get_or_default()
+            .unwrap_or_revert_with(&self.env(), LendingError::InvalidConfiguration);
+        LendingPoolContractRef::new(self.env(), pool_address)
+            .get_borrow_position(user)
+            .map(|position| position.principal + position.interest_accrued)
+            .unwrap_or_default()
+    }
+    
+    /// Publish an indebted user's health factor after a collateral change
+    fn notify_health_factor(&self, user: Address) {
+        if self.lending_pool.get_or_default().is_none() {
+            return;
+        }
+        let debt = self.user_debt(user);
+        if debt == U256::zero() {
+            return;
+        }
+        
+        self.env().emit_event(HealthFactorChanged {
+            user,
+            health_factor: self.calculate_health_factor_internal(user, debt),
+            debt,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Non-zero collateral balances of a user, including pledged aECTO
+    fn user_collateral_positions(&self, user: Address) -> Vec<(Address, U256)> {
+        let count = self.user_collateral_count.get(&user).unwrap_or(0);
+        let mut positions = Vec::new();
+        
+        for i in 0..count {
+            if let Some(asset) = self.user_collateral_assets.get(&(user, i)) {
+                if let Some(amount) = self.user_collateral.get(&(user, asset)) {
+                    if amount > U256::zero() {
+                        positions.push((asset, amount));
+                    }
+                }
+            }
+        }
+        
+        if let Some(aecto_vault) = self.aecto_vault.get_or_default() {
+            let pledged = self.pledged_aecto(user);
+            if pledged > U256::zero() {
+                positions.push((aecto_vault, pledged));
+            }
+        }
+        
+        positions
+    }
+    
+    /// aECTO balance a user has pledged as collateral (zero if not pledged)
+    fn pledged_aecto(&self, user: Address) -> U256 {
+        match self.aecto_vault.get_or_default() {
+            Some(aecto_vault) => {
+                let vault = AectoVaultContractRef::new(self.env(), aecto_vault);
+                if vault.is_pledged(user) {
+                    vault.balance_of(user)
+                } else {
+                    U256::zero()
+                }
+            }
+            None => U256::zero(),
+        }
+    }
+    
+    /// Debit `amount` of a user's collateral and transfer it to `to`
+    fn move_collateral(&mut self, user: Address, asset: Address, amount: U256, to: Address) {
+        if amount == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        
+        // Pledged aECTO never left the user's wallet, so the vault moves it
+        if self.aecto_vault.get_or_default() == Some(asset) {
+            if self.pledged_aecto(user) < amount {
+                self.env().revert(LendingError::InsufficientCollateralDeposit);
+            }
+            AectoVaultContractRef::new(self.env(), asset).seize_pledged(user, to, amount);
+            return;
+        }
+        
+        let current_balance = self.user_collateral.get(&(user, asset)).unwrap_or_default();
+        if current_balance < amount {
+            self.env().revert(LendingError::InsufficientCollateralDeposit);
+        }
+        self.user_collateral.set(&(user, asset), current_balance - amount);
+        
+        let mut token = Cep18TokenContractRef::new(self.env(), asset);
+        token.transfer(to, amount);
+    }
 }
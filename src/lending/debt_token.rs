@@ -0,0 +1,170 @@
+//! Debt Token (dECTO) - Non-transferable token tracking ECTO borrows
+//!
+//! The lending pool mints dECTO when a user borrows and burns it as the debt
+//! is repaid, liquidated or written off, so a borrower's debt can be read with
+//! a standard CEP-18 `balance_of`. Transfers and approvals always revert.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::LendingError;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+use crate::incentives::lending_incentives::LendingIncentivesContractRef;
+
+/// dECTO debt token
+#[odra::module]
+pub struct DebtToken {
+    /// Name of the token
+    name: Var<String>,
+    /// Symbol of the token
+    symbol: Var<String>,
+    /// Decimals
+    decimals: Var<u8>,
+    /// Total outstanding debt
+    total_supply: Var<U256>,
+    /// Borrower balances
+    balances: Mapping<Address, U256>,
+    /// Lending pool address (can mint/burn)
+    lending_pool: Var<Address>,
+    /// Incentives controller notified of every balance change
+    incentives_controller: Var<Option<Address>>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+}
+
+#[odra::module]
+impl DebtToken {
+    /// Initialize the debt token
+    pub fn init(&mut self, lending_pool_address: Address) {
+        let caller = self.env().caller();
+
+        self.name.set(String::from("Debt ECTO"));
+        self.symbol.set(String::from("dECTO"));
+        self.decimals.set(18);
+        self.total_supply.set(U256::zero());
+        self.lending_pool.set(lending_pool_address);
+
+        self.access.grant(ADMIN_ROLE, caller);
+    }
+
+    // ========================================
+    // CEP-18 Token Functions
+    // ========================================
+
+    pub fn name(&self) -> String {
+        self.name.get_or_default()
+    }
+
+    pub fn symbol(&self) -> String {
+        self.symbol.get_or_default()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals.get_or_default()
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get_or_default()
+    }
+
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.balances.get(&owner).unwrap_or_default()
+    }
+
+    /// Debt cannot be handed to someone else
+    pub fn transfer(&mut self, _recipient: Address, _amount: U256) {
+        self.env().revert(LendingError::NonTransferable);
+    }
+
+    /// Debt cannot be handed to someone else
+    pub fn transfer_from(&mut self, _owner: Address, _recipient: Address, _amount: U256) {
+        self.env().revert(LendingError::NonTransferable);
+    }
+
+    /// Debt cannot be handed to someone else
+    pub fn approve(&mut self, _spender: Address, _amount: U256) {
+        self.env().revert(LendingError::NonTransferable);
+    }
+
+    pub fn allowance(&self, _owner: Address, _spender: Address) -> U256 {
+        U256::zero()
+    }
+
+    // ========================================
+    // Debt Accounting (Lending Pool Only)
+    // ========================================
+
+    /// Mint dECTO to a borrower (lending pool only)
+    pub fn mint(&mut self, to: Address, amount: U256) {
+        self.only_lending_pool();
+
+        self.total_supply.set(self.total_supply() + amount);
+        self.balances.set(&to, self.balance_of(to) + amount);
+
+        self.notify_incentives(to);
+    }
+
+    /// Burn dECTO from a borrower, capped at their balance (lending pool only)
+    pub fn burn(&mut self, from: Address, amount: U256) {
+        self.only_lending_pool();
+
+        let balance = self.balance_of(from);
+        let burned = if amount > balance { balance } else { amount };
+
+        self.balances.set(&from, balance - burned);
+        self.total_supply.set(self.total_supply() - burned);
+
+        self.notify_incentives(from);
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Update the lending pool address (admin only)
+    pub fn set_lending_pool(&mut self, lending_pool: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.lending_pool.set(lending_pool);
+    }
+
+    /// Get the lending pool address
+    pub fn get_lending_pool(&self) -> Address {
+        self.lending_pool.get_or_revert_with(LendingError::InvalidConfiguration)
+    }
+
+    /// Set the incentives controller notified of balance changes (admin only)
+    pub fn set_incentives_controller(&mut self, controller: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.incentives_controller.set(Some(controller));
+    }
+
+    /// Get the incentives controller notified of balance changes
+    pub fn get_incentives_controller(&self) -> Option<Address> {
+        self.incentives_controller.get_or_default()
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    /// Report a borrower's new balance to the incentives controller, if any
+    fn notify_incentives(&self, holder: Address) {
+        if let Some(controller) = self.incentives_controller.get_or_default() {
+            LendingIncentivesContractRef::new(self.env(), controller)
+                .handle_action(holder, self.balance_of(holder), self.total_supply());
+        }
+    }
+
+    fn only_lending_pool(&self) {
+        let caller = self.env().caller();
+        let pool = self.lending_pool.get_or_revert_with(LendingError::Unauthorized);
+        if caller != pool {
+            self.env().revert(LendingError::Unauthorized);
+        }
+    }
+}
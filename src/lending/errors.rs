@@ -94,4 +94,68 @@ pub enum LendingError {
     MathUnderflow = 34,
     /// Division by zero
     DivisionByZero = 35,
+    
+    // Swap Errors
+    /// Swap would need more collateral than the caller allowed
+    SlippageExceeded = 36,
+    
+    // Auction Errors
+    /// Auction does not exist
+    AuctionNotFound = 37,
+    /// Auction has already closed
+    AuctionNotActive = 38,
+    /// Borrower already has an active auction for this collateral
+    AuctionAlreadyActive = 39,
+    
+    // Oracle Feeder Errors
+    /// Price submission deviates too far from the current median
+    PriceDeviationTooHigh = 40,
+    /// Address is already a whitelisted feeder
+    FeederAlreadyRegistered = 41,
+    /// Address is not a whitelisted feeder
+    FeederNotRegistered = 42,
+    /// Price is older than the asset's heartbeat
+    StalePrice = 43,
+    
+    // Debt Token Errors
+    /// Debt tokens cannot be transferred or approved
+    NonTransferable = 44,
+    
+    // Liquidation Grace Errors
+    /// Liquidations are held back while borrowers recover from an outage
+    LiquidationGracePeriod = 45,
+    
+    // Timelock Errors
+    /// No parameter change is queued
+    NoPendingUpdate = 46,
+    /// The queued change's delay has not passed yet
+    TimelockNotElapsed = 47,
+    
+    // Withdrawal Limit Errors
+    /// Withdrawal exceeds what suppliers may take out this block
+    WithdrawalLimitExceeded = 48,
+    
+    // Supply Cap Errors
+    /// Deposit would take total supplied ECTO above the supply cap
+    SupplyCapExceeded = 49,
+    
+    // CDP Errors
+    /// CDP vault not found
+    VaultNotFound = 50,
+    
+    /// Action would leave the CDP vault below its liquidation ratio
+    VaultUnsafe = 51,
+    
+    /// Minting would take the collateral type's debt above its ceiling
+    DebtCeilingExceeded = 52,
+    
+    // Close Factor Errors
+    /// Repayment exceeds the close factor share of the debt
+    ExceedsCloseFactor = 53,
+    
+    // Safety Module Errors
+    /// Unstaking outside the window that follows a completed cooldown
+    CooldownNotComplete = 54,
+    /// Shortfalls drained the backstop while shares are still outstanding
+    BackstopDepleted = 55,
 }
@@ -2,6 +2,7 @@
 
 use odra::prelude::*;
 use odra::casper_types::U256;
+use super::lending_pool::PauseAction;
 
 // ============================================================================
 // Deposit/Withdrawal Events
@@ -12,6 +13,8 @@ use odra::casper_types::U256;
 pub struct Deposited {
     /// Address that deposited
     pub user: Address,
+    /// Address credited with the aECTO
+    pub receiver: Address,
     /// Amount of ECTO deposited
     pub amount: U256,
     /// Amount of aECTO minted
@@ -33,6 +36,15 @@ pub struct Withdrawn {
     pub timestamp: u64,
 }
 
+/// Event emitted when ECTO held by the aECTO vault is moved into the pool
+#[odra::event]
+pub struct LiquidityMigrated {
+    /// Amount of ECTO moved
+    pub amount: U256,
+    /// Timestamp of migration
+    pub timestamp: u64,
+}
+
 // ============================================================================
 // Borrowing Events
 // ============================================================================
@@ -55,8 +67,10 @@ pub struct Borrowed {
 /// Event emitted when borrowed ECTO is repaid
 #[odra::event]
 pub struct Repaid {
-    /// Address that repaid
+    /// Borrower whose debt was repaid
     pub borrower: Address,
+    /// Address that paid the ECTO
+    pub repaid_by: Address,
     /// Amount of ECTO repaid
     pub amount: U256,
     /// Interest paid
@@ -65,6 +79,36 @@ pub struct Repaid {
     pub timestamp: u64,
 }
 
+/// Emitted when a borrower's collateral is sold to repay their debt
+#[odra::event]
+pub struct Deleveraged {
+    /// Borrower whose position was deleveraged
+    pub borrower: Address,
+    /// Borrower or approved delegate that triggered it
+    pub caller: Address,
+    /// Collateral asset sold
+    pub collateral_asset: Address,
+    /// Amount of collateral sold
+    pub collateral_sold: U256,
+    /// Amount of ECTO debt repaid
+    pub debt_repaid: U256,
+    /// Timestamp of deleverage
+    pub timestamp: u64,
+}
+
+/// Emitted when a borrower approves or revokes a deleverage delegate
+#[odra::event]
+pub struct DeleverageDelegateUpdated {
+    /// Borrower granting the approval
+    pub borrower: Address,
+    /// Delegate allowed to deleverage the borrower
+    pub delegate: Address,
+    /// Whether the delegate is approved
+    pub approved: bool,
+    /// Timestamp of update
+    pub timestamp: u64,
+}
+
 // ============================================================================
 // Collateral Events
 // ============================================================================
@@ -95,10 +139,50 @@ pub struct CollateralWithdrawn {
     pub timestamp: u64,
 }
 
+/// Event emitted when collateral is seized from a liquidated borrower
+#[odra::event]
+pub struct CollateralSeized {
+    /// Borrower whose collateral was seized
+    pub borrower: Address,
+    /// Collateral asset address
+    pub asset: Address,
+    /// Amount of collateral seized
+    pub amount: U256,
+    /// Recipient of the seized collateral
+    pub to: Address,
+    /// Timestamp of seizure
+    pub timestamp: u64,
+}
+
+/// Event emitted when a holder pledges or releases aECTO as collateral
+#[odra::event]
+pub struct AectoCollateralPledged {
+    /// aECTO holder
+    pub user: Address,
+    /// Whether the balance is now pledged
+    pub enabled: bool,
+    /// Timestamp of change
+    pub timestamp: u64,
+}
+
 // ============================================================================
 // Liquidation Events
 // ============================================================================
 
+/// Event emitted when a borrow, repayment, liquidation or collateral change
+/// moves a borrower's health factor
+#[odra::event]
+pub struct HealthFactorChanged {
+    /// Borrower address
+    pub user: Address,
+    /// New health factor (scaled by 1e18, U256::MAX without debt)
+    pub health_factor: U256,
+    /// Debt after the change
+    pub debt: U256,
+    /// Timestamp of change
+    pub timestamp: u64,
+}
+
 /// Event emitted when a position is liquidated
 #[odra::event]
 pub struct Liquidated {
@@ -118,6 +202,150 @@ pub struct Liquidated {
     pub timestamp: u64,
 }
 
+/// Event emitted when a liquidator is paid the flat bounty from reserves
+#[odra::event]
+pub struct LiquidationBountyPaid {
+    /// Address of the liquidator
+    pub liquidator: Address,
+    /// Collateral asset liquidated
+    pub collateral_asset: Address,
+    /// ECTO paid from reserves
+    pub amount: U256,
+    /// Timestamp of payment
+    pub timestamp: u64,
+}
+
+/// Event emitted when the fee collector collects protocol reserves
+#[odra::event]
+pub struct ReservesCollected {
+    /// Address of the fee collector
+    pub collector: Address,
+    /// ECTO collected
+    pub amount: U256,
+    /// Timestamp of collection
+    pub timestamp: u64,
+}
+
+/// Event emitted when a Dutch auction starts for a borrower's collateral
+#[odra::event]
+pub struct AuctionStarted {
+    /// Auction id
+    pub auction_id: u64,
+    /// Borrower whose collateral is auctioned
+    pub borrower: Address,
+    /// Collateral asset being sold
+    pub collateral_asset: Address,
+    /// Amount of collateral for sale
+    pub collateral_amount: U256,
+    /// ECTO debt to be raised
+    pub debt_amount: U256,
+    /// Starting price (ECTO per collateral unit, scaled by 1e18)
+    pub start_price: U256,
+    /// Floor price (ECTO per collateral unit, scaled by 1e18)
+    pub floor_price: U256,
+    /// Timestamp of start
+    pub timestamp: u64,
+}
+
+/// Event emitted when a keeper takes collateral from an auction
+#[odra::event]
+pub struct AuctionTaken {
+    /// Auction id
+    pub auction_id: u64,
+    /// Address that bought the collateral
+    pub taker: Address,
+    /// Amount of collateral bought
+    pub collateral_amount: U256,
+    /// Amount of ECTO paid towards the debt
+    pub ecto_paid: U256,
+    /// Price paid (ECTO per collateral unit, scaled by 1e18)
+    pub price: U256,
+    /// Timestamp of take
+    pub timestamp: u64,
+}
+
+/// Event emitted when an auction closes
+#[odra::event]
+pub struct AuctionClosed {
+    /// Auction id
+    pub auction_id: u64,
+    /// Collateral left unsold
+    pub collateral_remaining: U256,
+    /// Debt left unraised
+    pub debt_remaining: U256,
+    /// Timestamp of close
+    pub timestamp: u64,
+}
+
+/// Event emitted when a liquidation leaves debt without collateral behind it
+#[odra::event]
+pub struct BadDebtRealized {
+    /// Borrower whose debt was written off
+    pub borrower: Address,
+    /// Total debt written off
+    pub bad_debt: U256,
+    /// Portion burned against protocol reserves
+    pub covered_by_reserves: U256,
+    /// Portion drawn from the safety module
+    pub covered_by_safety_module: U256,
+    /// Portion absorbed by lenders
+    pub uncovered: U256,
+    /// Timestamp of write-off
+    pub timestamp: u64,
+}
+
+// ============================================================================
+// Safety Module Events
+// ============================================================================
+
+/// Event emitted when ECTO is staked into the safety module
+#[odra::event]
+pub struct SafetyModuleStaked {
+    /// Address that staked
+    pub staker: Address,
+    /// Amount of ECTO staked
+    pub amount: U256,
+    /// Shares minted
+    pub shares: U256,
+    /// Timestamp of stake
+    pub timestamp: u64,
+}
+
+/// Event emitted when ECTO is unstaked from the safety module
+#[odra::event]
+pub struct SafetyModuleUnstaked {
+    /// Address that unstaked
+    pub staker: Address,
+    /// Amount of ECTO returned
+    pub amount: U256,
+    /// Shares burned
+    pub shares: U256,
+    /// Timestamp of unstake
+    pub timestamp: u64,
+}
+
+/// Event emitted when a safety module staker starts the unstake cooldown
+#[odra::event]
+pub struct SafetyModuleCooldownStarted {
+    /// Address that started the cooldown
+    pub staker: Address,
+    /// Timestamp from which the staker may unstake
+    pub unstake_from: u64,
+    /// Timestamp after which the unstake window closes
+    pub unstake_until: u64,
+}
+
+/// Event emitted when the safety module covers a lending pool shortfall
+#[odra::event]
+pub struct ShortfallCovered {
+    /// Amount of ECTO sent to the lending pool
+    pub amount: U256,
+    /// ECTO left staked
+    pub total_staked: U256,
+    /// Timestamp of cover
+    pub timestamp: u64,
+}
+
 // ============================================================================
 // Interest Rate Events
 // ============================================================================
@@ -146,6 +374,160 @@ pub struct InterestAccrued {
     pub timestamp: u64,
 }
 
+/// Event emitted when a feeder submits a price
+#[odra::event]
+pub struct PriceSubmitted {
+    /// Feeder that submitted
+    pub feeder: Address,
+    /// Asset priced
+    pub asset: Address,
+    /// Submitted price (scaled by 1e18)
+    pub price: U256,
+    /// Published median after the submission (zero if not yet published)
+    pub median: U256,
+    /// Timestamp of submission
+    pub timestamp: u64,
+}
+
+/// Event emitted when the oracle serves a price for a valuation
+#[odra::event]
+pub struct PriceServed {
+    /// Asset priced
+    pub asset: Address,
+    /// Price served (scaled by 1e18)
+    pub price: U256,
+    /// Whether the fallback TWAP served instead of the primary feed
+    pub from_fallback: bool,
+    /// Timestamp of valuation
+    pub timestamp: u64,
+}
+
+/// Event emitted when a derived asset's exchange rate is synced
+#[odra::event]
+pub struct DerivedRateSynced {
+    /// Derived asset (e.g. sCSPR)
+    pub asset: Address,
+    /// Rate now in use, after the increase cap (scaled by 1e18)
+    pub rate: U256,
+    /// Rate reported by the StakingManager (scaled by 1e18)
+    pub live_rate: U256,
+    /// Timestamp of sync
+    pub timestamp: u64,
+}
+
+// ============================================================================
+// CDP Vault Events
+// ============================================================================
+
+/// Event emitted when a CDP vault is opened
+#[odra::event]
+pub struct VaultOpened {
+    /// Vault ID
+    pub vault_id: u64,
+    /// Vault owner
+    pub owner: Address,
+    /// Collateral asset of the vault
+    pub collateral_asset: Address,
+}
+
+/// Event emitted when collateral is deposited into or withdrawn from a CDP vault
+#[odra::event]
+pub struct VaultCollateralChanged {
+    /// Vault ID
+    pub vault_id: u64,
+    /// Amount added (zero on withdrawal)
+    pub deposited: U256,
+    /// Amount removed (zero on deposit)
+    pub withdrawn: U256,
+    /// Collateral held after the change
+    pub collateral: U256,
+}
+
+/// Event emitted when ECTO is minted against a CDP vault
+#[odra::event]
+pub struct VaultDebtMinted {
+    /// Vault ID
+    pub vault_id: u64,
+    /// ECTO minted
+    pub amount: U256,
+    /// Vault debt after minting
+    pub debt: U256,
+}
+
+/// Event emitted when a CDP vault's debt is repaid
+#[odra::event]
+pub struct VaultDebtRepaid {
+    /// Vault ID
+    pub vault_id: u64,
+    /// Address that repaid
+    pub payer: Address,
+    /// ECTO repaid and burned
+    pub amount: U256,
+    /// Vault debt after repayment
+    pub debt: U256,
+}
+
+/// Event emitted when an unsafe CDP vault is liquidated
+#[odra::event]
+pub struct VaultLiquidated {
+    /// Vault ID
+    pub vault_id: u64,
+    /// Address of the liquidator
+    pub liquidator: Address,
+    /// ECTO repaid and burned
+    pub debt_covered: U256,
+    /// Collateral sent to the liquidator
+    pub collateral_seized: U256,
+    /// Debt left without collateral, added to bad debt
+    pub bad_debt: U256,
+}
+
+/// Event emitted when stability fees accrue on a collateral type
+#[odra::event]
+pub struct StabilityFeesAccrued {
+    /// Collateral asset
+    pub asset: Address,
+    /// ECTO minted to the fee recipient
+    pub amount: U256,
+    /// Accumulated rate after accrual (RAY)
+    pub rate: U256,
+}
+
+// ============================================================================
+// Savings Events
+// ============================================================================
+
+/// Event emitted when governance changes the sECTO savings rate
+#[odra::event]
+pub struct SavingsRateUpdated {
+    /// Previous annual savings rate (RAY)
+    pub old_rate: U256,
+    /// New annual savings rate (RAY)
+    pub new_rate: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when ECTO is added to the sECTO yield buffer
+#[odra::event]
+pub struct SavingsFunded {
+    /// Address that paid in the ECTO
+    pub funder: Address,
+    /// ECTO added
+    pub amount: U256,
+    /// Yield buffer after funding
+    pub buffer: U256,
+}
+
+/// Event emitted when savings yield is credited to sECTO holders
+#[odra::event]
+pub struct SavingsAccrued {
+    /// ECTO moved from the yield buffer to holders
+    pub amount: U256,
+    /// Total ECTO owed to holders after accrual
+    pub total_assets: U256,
+}
+
 // ============================================================================
 // Configuration Events
 // ============================================================================
@@ -180,6 +562,23 @@ pub struct CollateralUpdated {
     pub updated_by: Address,
 }
 
+/// Event emitted when an interest rate parameter change is queued
+#[odra::event]
+pub struct InterestRateParamsScheduled {
+    /// Base rate (scaled by 1e18)
+    pub base_rate: U256,
+    /// Optimal utilization rate (scaled by 1e18)
+    pub optimal_utilization: U256,
+    /// Slope 1 (rate increase before optimal)
+    pub slope1: U256,
+    /// Slope 2 (rate increase after optimal)
+    pub slope2: U256,
+    /// Earliest execution time
+    pub eta: u64,
+    /// Scheduled by
+    pub scheduled_by: Address,
+}
+
 /// Event emitted when interest rate parameters are updated
 #[odra::event]
 pub struct InterestRateParamsUpdated {
@@ -217,6 +616,19 @@ pub struct ContractUnpaused {
     pub timestamp: u64,
 }
 
+/// Event emitted when the guardian pauses or resumes a single action
+#[odra::event]
+pub struct ActionPauseUpdated {
+    /// Action switched
+    pub action: PauseAction,
+    /// Whether the action is now paused
+    pub paused: bool,
+    /// Guardian that made the change
+    pub updated_by: Address,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
 /// Event emitted when reserve factor is updated
 #[odra::event]
 pub struct ReserveFactorUpdated {
@@ -5,10 +5,16 @@
 //! - Optimal utilization: Target utilization rate (e.g., 80%)
 //! - Slope 1: Rate increase before optimal utilization
 //! - Slope 2: Steep rate increase after optimal utilization
+//!
+//! Parameter changes are queued behind `update_delay` seconds so borrowers can
+//! see a rate change coming before it takes effect.
 
 use odra::prelude::*;
 use odra::casper_types::U256;
 use super::errors::LendingError;
+use super::events::*;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+use crate::math::{percent_mul, WAD};
 
 /// Interest rate strategy parameters
 #[odra::odra_type]
@@ -30,6 +36,15 @@ pub struct InterestRateParams {
     pub slope2: U256,
 }
 
+/// Parameter change waiting out the update delay
+#[odra::odra_type]
+pub struct PendingParamsUpdate {
+    /// Parameters to apply
+    pub params: InterestRateParams,
+    /// Earliest time the update can be executed
+    pub eta: u64,
+}
+
 /// Interest Rate Strategy contract
 #[odra::module]
 pub struct InterestRateStrategy {
@@ -38,6 +53,15 @@ pub struct InterestRateStrategy {
     
     /// Scale factor for calculations (1e18)
     scale: Var<U256>,
+    
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    
+    /// Seconds a parameter change must wait before it can be executed
+    update_delay: Var<u64>,
+    
+    /// Queued parameter change, if any
+    pending_update: Var<Option<PendingParamsUpdate>>,
 }
 
 #[odra::module]
@@ -65,6 +89,7 @@ impl InterestRateStrategy {
         
         self.params.set(params);
         self.scale.set(U256::from(1_000_000_000_000_000_000u128)); // 1e18
+        self.access.grant(ADMIN_ROLE, self.env().caller());
     }
     
     /// Calculate borrow rate based on utilization
@@ -135,11 +160,10 @@ impl InterestRateStrategy {
             return U256::zero();
         }
         
-        let scale = self.scale.get_or_default();
-        
         // supply_rate = borrow_rate * utilization * (1 - reserve_factor)
-        let rate_to_pool = (borrow_rate * (scale - reserve_factor)) / scale;
-        (rate_to_pool * utilization) / scale
+        percent_mul(borrow_rate, U256::from(WAD) - reserve_factor)
+            .and_then(|rate_to_pool| percent_mul(rate_to_pool, utilization))
+            .unwrap_or_else(|_| self.env().revert(LendingError::MathOverflow))
     }
     
     /// Calculate utilization rate
@@ -175,15 +199,22 @@ impl InterestRateStrategy {
         self.params.get_or_revert_with(LendingError::InvalidConfiguration)
     }
     
-    /// Update interest rate parameters (admin only)
-    pub fn update_params(
+    /// Queue new interest rate parameters, executable after the update delay
+    /// (admin only). Replaces any update already queued.
+    pub fn schedule_params_update(
         &mut self,
         base_rate: U256,
         optimal_utilization: U256,
         slope1: U256,
         slope2: U256,
     ) {
-        // TODO: Add admin check
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        
+        // The rate formula divides by both optimal and (1 - optimal)
+        let scale = self.scale.get_or_default();
+        if optimal_utilization == U256::zero() || optimal_utilization >= scale {
+            self.env().revert(LendingError::InvalidConfiguration);
+        }
         
         let params = InterestRateParams {
             base_rate,
@@ -191,14 +222,117 @@ impl InterestRateStrategy {
             slope1,
             slope2,
         };
+        let eta = self.env().get_block_time() + self.update_delay.get_or_default();
+        self.pending_update.set(Some(PendingParamsUpdate { params, eta }));
         
-        self.params.set(params);
+        self.env().emit_event(InterestRateParamsScheduled {
+            base_rate,
+            optimal_utilization,
+            slope1,
+            slope2,
+            eta,
+            scheduled_by: self.env().caller(),
+        });
+    }
+    
+    /// Apply the queued parameters once the update delay has passed (admin only)
+    pub fn execute_params_update(&mut self) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        
+        let pending = self.pending_update.get_or_default()
+            .unwrap_or_revert_with(&self.env(), LendingError::NoPendingUpdate);
+        if self.env().get_block_time() < pending.eta {
+            self.env().revert(LendingError::TimelockNotElapsed);
+        }
+        
+        self.pending_update.set(None);
+        self.params.set(pending.params.clone());
+        
+        self.env().emit_event(InterestRateParamsUpdated {
+            base_rate: pending.params.base_rate,
+            optimal_utilization: pending.params.optimal_utilization,
+            slope1: pending.params.slope1,
+            slope2: pending.params.slope2,
+            updated_by: self.env().caller(),
+        });
+    }
+    
+    /// Drop the queued parameter change (admin only)
+    pub fn cancel_params_update(&mut self) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if self.pending_update.get_or_default().is_none() {
+            self.env().revert(LendingError::NoPendingUpdate);
+        }
+        self.pending_update.set(None);
+    }
+    
+    /// Get the queued parameter change, if any
+    pub fn get_pending_update(&self) -> Option<PendingParamsUpdate> {
+        self.pending_update.get_or_default()
+    }
+    
+    /// Set the delay applied to newly scheduled parameter changes (admin only)
+    pub fn set_update_delay(&mut self, seconds: u64) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.update_delay.set(seconds);
+    }
+    
+    /// Get the delay applied to newly scheduled parameter changes
+    pub fn get_update_delay(&self) -> u64 {
+        self.update_delay.get_or_default()
+    }
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use odra::host::Deployer;
+    
+    #[test]
+    fn test_params_update_waits_out_delay() {
+        let env = odra_test::env();
+        let mut strategy = InterestRateStrategy::deploy(&env, InterestRateStrategyInitArgs {
+            base_rate: U256::from(20_000_000_000_000_000u128),
+            optimal_utilization: U256::from(800_000_000_000_000_000u128),
+            slope1: U256::from(40_000_000_000_000_000u128),
+            slope2: U256::from(750_000_000_000_000_000u128),
+        });
+        strategy.set_update_delay(86_400);
+        
+        let new_base_rate = U256::from(30_000_000_000_000_000u128);
+        strategy.schedule_params_update(
+            new_base_rate,
+            U256::from(800_000_000_000_000_000u128),
+            U256::from(40_000_000_000_000_000u128),
+            U256::from(750_000_000_000_000_000u128),
+        );
+        assert_eq!(strategy.get_pending_update().unwrap().eta, env.block_time() + 86_400);
+        
+        env.advance_block_time(86_399);
+        assert_eq!(
+            strategy.try_execute_params_update().unwrap_err(),
+            LendingError::TimelockNotElapsed.into()
+        );
+        assert_eq!(strategy.get_params().base_rate, U256::from(20_000_000_000_000_000u128));
+        
+        env.advance_block_time(1);
+        strategy.execute_params_update();
+        assert_eq!(strategy.get_params().base_rate, new_base_rate);
+        assert!(strategy.get_pending_update().is_none());
+        assert_eq!(
+            strategy.try_execute_params_update().unwrap_err(),
+            LendingError::NoPendingUpdate.into()
+        );
+    }
     
     #[test]
     fn test_utilization_calculation() {
@@ -12,12 +12,17 @@ use odra::casper_types::U256;
 use odra::ContractRef;
 use super::errors::LendingError;
 use super::events::*;
-use super::aecto_vault::AectoVaultContractRef;
 use super::collateral_manager::CollateralManagerContractRef;
+use super::debt_token::DebtTokenContractRef;
 use super::interest_rate::InterestRateStrategyContractRef;
 use super::liquidation::LiquidationEngineContractRef;
 use super::price_oracle::PriceOracleContractRef;
+use super::safety_module::SafetyModuleContractRef;
+use crate::dex::router::RouterContractRef;
+use crate::incentives::points_ledger::{PointsAction, PointsLedgerContractRef};
 use crate::token::Cep18TokenContractRef;
+use crate::math::{apr_to_apy, percent_mul, ray_to_wad, wad_to_ray};
+use crate::access::{AccessControl, Role, ADMIN_ROLE, GUARDIAN_ROLE};
 
 /// User's borrow position
 #[odra::odra_type]
@@ -32,6 +37,35 @@ pub struct BorrowPosition {
     pub last_update: u64,
 }
 
+/// Pool actions the guardian can switch off individually
+/// Repayments and withdrawals are never pausable
+#[odra::odra_type]
+pub enum PauseAction {
+    /// ECTO deposits
+    Deposit,
+    /// New borrows
+    Borrow,
+    /// Liquidations and auction settlements
+    Liquidation,
+}
+
+/// Aggregated account state for frontends and bots
+#[odra::odra_type]
+pub struct UserAccountData {
+    /// Total collateral value in ECTO
+    pub total_collateral_value: U256,
+    /// Total debt (principal + interest) in ECTO
+    pub total_debt: U256,
+    /// ECTO still borrowable under the user's LTV
+    pub available_borrow: U256,
+    /// Collateral-weighted liquidation threshold (scaled by 1e18)
+    pub current_liquidation_threshold: U256,
+    /// Collateral-weighted loan-to-value ratio (scaled by 1e18)
+    pub ltv: U256,
+    /// Health factor (scaled by 1e18, U256::MAX without debt)
+    pub health_factor: U256,
+}
+
 /// Lending Pool contract
 #[odra::module]
 pub struct LendingPool {
@@ -61,10 +95,51 @@ pub struct LendingPool {
     reserve_factor: Var<U256>,
     /// Total reserves accumulated
     total_reserves: Var<U256>,
-    /// Admin address
-    admin: Var<Address>,
-    /// Paused state
-    paused: Var<bool>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Whether deposits are paused
+    deposit_paused: Var<bool>,
+    /// Whether new borrows are paused
+    borrow_paused: Var<bool>,
+    /// Whether liquidations are paused
+    liquidation_paused: Var<bool>,
+    /// DEX router used to swap collateral into ECTO
+    dex_router: Var<Option<Address>>,
+    /// Dutch auction module allowed to settle auction takes
+    liquidation_auction: Var<Option<Address>>,
+    /// Staked ECTO backstop drawn on after reserves to cover bad debt
+    safety_module: Var<Option<Address>>,
+    /// dECTO token mirroring every borrower's debt
+    debt_token: Var<Option<Address>>,
+    /// Flat ECTO bounty paid from reserves per liquidation (collateral asset -> amount)
+    liquidation_bounties: Mapping<Address, U256>,
+    /// Addresses with open debt by index
+    borrowers: Mapping<u32, Address>,
+    /// Position of each open borrower in `borrowers`, plus one (0 = not listed)
+    borrower_slots: Mapping<Address, u32>,
+    /// Number of addresses with open debt
+    borrower_count: Var<u32>,
+    /// Seconds liquidations stay blocked after an unpause or oracle recovery
+    liquidation_grace_period: Var<u64>,
+    /// When liquidations were last unpaused
+    liquidations_unpaused_at: Var<u64>,
+    /// Addresses allowed to deleverage a borrower (borrower + delegate -> approved)
+    deleverage_delegates: Mapping<(Address, Address), bool>,
+    /// Utilization above which supplier withdrawals are capped per block
+    /// (scaled by 1e18); zero disables the limit
+    withdrawal_limit_utilization: Var<U256>,
+    /// ECTO suppliers may withdraw per block while the limit is active
+    withdrawal_cap_per_block: Var<U256>,
+    /// Block time of the last supplier withdrawal
+    last_withdrawal_time: Var<u64>,
+    /// ECTO withdrawn by suppliers at `last_withdrawal_time`
+    withdrawn_this_block: Var<U256>,
+    /// Most ECTO the pool accepts from suppliers in total; zero means no cap
+    supply_cap: Var<U256>,
+    /// Fee distributor allowed to collect protocol reserves
+    fee_collector: Var<Option<Address>>,
+    /// PointsLedger credited with borrows
+    points_ledger: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -97,102 +172,73 @@ impl LendingPool {
         self.reserve_factor.set(U256::from(100_000_000_000_000_000u128)); // 0.1 * 1e18
         self.total_reserves.set(U256::zero());
         
-        self.admin.set(caller);
-        self.paused.set(false);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(GUARDIAN_ROLE, caller);
     }
     
     // ========================================
-    // Deposit/Withdrawal (via aECTO vault)
+    // Liquidity (aECTO Vault Only)
     // ========================================
     
-    /// Deposit ECTO and receive aECTO
-    /// Note: Users should call aECTO vault directly for CEP-4626 interface
-    pub fn deposit(&mut self, amount: U256) -> U256 {
-        self.ensure_not_paused();
+    /// Credit ECTO the aECTO vault moved into the pool as lender liquidity
+    /// (aECTO vault only; lenders deposit through `AectoVault::deposit`)
+    pub fn supply_liquidity(&mut self, user: Address, receiver: Address, amount: U256, shares: U256) {
+        self.ensure_not_paused(PauseAction::Deposit);
+        self.only_aecto_vault();
         self.accrue_interest();
         
-        let caller = self.env().caller();
-        
-        // Transfer ECTO from user to pool
-        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
-        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
-        ecto_token.transfer_from(caller, Address::from(self.env().self_address()), amount);
+        if amount > self.get_available_supply() {
+            self.env().revert(LendingError::SupplyCapExceeded);
+        }
         
-        // Update liquidity
         let current_liquidity = self.total_liquidity.get_or_default();
         self.total_liquidity.set(current_liquidity + amount);
         
-        // Mint aECTO via vault
-        let vault_address = self.aecto_vault.get_or_revert_with(LendingError::InvalidConfiguration);
-        let mut vault = AectoVaultContractRef::new(self.env(), vault_address);
-        
-        // Calculate shares
-        let shares = vault.convert_to_shares(amount);
-        vault.mint(caller, shares);
-        
-        // Update total assets in vault
-        let new_total_assets = current_liquidity + amount + self.total_borrows.get_or_default();
-        vault.update_total_assets(new_total_assets);
-        
         // Update interest rates
         self.update_interest_rates();
         
         let timestamp = self.env().get_block_time();
         self.env().emit_event(Deposited {
-            user: caller,
+            user,
+            receiver,
             amount,
             shares,
             timestamp,
         });
-        
-        shares
     }
     
-    /// Withdraw ECTO by burning aECTO
-    pub fn withdraw(&mut self, amount: U256) -> U256 {
-        self.ensure_not_paused();
+    /// Send liquidity to a withdrawing lender whose aECTO the vault burned
+    /// (aECTO vault only; lenders withdraw through `AectoVault::withdraw`)
+    pub fn release_liquidity(&mut self, user: Address, receiver: Address, amount: U256, shares: U256) {
+        self.only_aecto_vault();
         self.accrue_interest();
         
-        let caller = self.env().caller();
-        
         // Check liquidity
         let current_liquidity = self.total_liquidity.get_or_default();
         if amount > current_liquidity {
             self.env().revert(LendingError::InsufficientLiquidity);
         }
-        
-        // Calculate shares to burn
-        let vault_address = self.aecto_vault.get_or_revert_with(LendingError::InvalidConfiguration);
-        let mut vault = AectoVaultContractRef::new(self.env(), vault_address);
-        let shares = vault.convert_to_shares(amount);
-        
-        // Burn aECTO
-        vault.burn(caller, shares);
-        
-        // Update liquidity
+        if amount > self.get_available_withdrawal() {
+            self.env().revert(LendingError::WithdrawalLimitExceeded);
+        }
+        self.record_withdrawal(amount);
         self.total_liquidity.set(current_liquidity - amount);
         
-        // Update total assets in vault
-        let new_total_assets = current_liquidity - amount + self.total_borrows.get_or_default();
-        vault.update_total_assets(new_total_assets);
-        
-        // Transfer ECTO to user
+        // Transfer ECTO to receiver
         let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
         let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
-        ecto_token.transfer(caller, amount);
+        ecto_token.transfer(receiver, amount);
         
         // Update interest rates
         self.update_interest_rates();
         
         let timestamp = self.env().get_block_time();
         self.env().emit_event(Withdrawn {
-            user: caller,
+            user,
             amount,
             shares,
             timestamp,
         });
-        
-        shares
     }
     
     // ========================================
@@ -201,7 +247,7 @@ impl LendingPool {
     
     /// Borrow ECTO against collateral
     pub fn borrow(&mut self, amount: U256, collateral_asset: Address) {
-        self.ensure_not_paused();
+        self.ensure_not_paused(PauseAction::Borrow);
         self.accrue_interest();
         
         let caller = self.env().caller();
@@ -220,6 +266,12 @@ impl LendingPool {
         let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
         let collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
         
+        // Never lend against a stale price
+        let oracle_address = self.price_oracle.get_or_revert_with(LendingError::InvalidConfiguration);
+        if !PriceOracleContractRef::new(self.env(), oracle_address).is_price_fresh(collateral_asset) {
+            self.env().revert(LendingError::StalePrice);
+        }
+        
         // Check user has collateral
         let user_collateral = collateral_mgr.get_user_collateral(caller, collateral_asset);
         if user_collateral == U256::zero() {
@@ -264,6 +316,7 @@ impl LendingPool {
         let total_borrows = self.total_borrows.get_or_default();
         self.total_borrows.set(total_borrows + amount);
         self.total_liquidity.set(current_liquidity - amount);
+        self.mint_debt(caller, amount);
         
         // Transfer ECTO to borrower
         let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
@@ -282,91 +335,147 @@ impl LendingPool {
             borrow_rate,
             timestamp,
         });
+        
+        if let Some(ledger) = self.points_ledger.get_or_default() {
+            PointsLedgerContractRef::new(self.env(), ledger).credit(caller, PointsAction::Borrow, amount);
+        }
+        
+        self.on_position_changed(caller);
     }
     
     /// Repay borrowed ECTO
     pub fn repay(&mut self, amount: U256) {
-        self.ensure_not_paused();
+        let caller = self.env().caller();
+        self.repay_internal(caller, caller, amount);
+    }
+    
+    /// Repay another borrower's debt with the caller's ECTO
+    /// Lets protection bots, employers or a user's other wallet top up a position
+    pub fn repay_for(&mut self, borrower: Address, amount: U256) {
+        let caller = self.env().caller();
+        self.repay_internal(caller, borrower, amount);
+    }
+    
+    /// Repay debt by selling collateral for ECTO through the DEX router
+    /// Sells just enough collateral to buy `debt_amount` of ECTO (capped at the
+    /// outstanding debt), reverting if that needs more than `max_collateral`.
+    /// Collateral sales halt with liquidations and while the price is stale.
+    pub fn repay_with_collateral(
+        &mut self,
+        collateral_asset: Address,
+        max_collateral: U256,
+        debt_amount: U256,
+    ) {
+        self.ensure_not_paused(PauseAction::Liquidation);
         self.accrue_interest();
         
         let caller = self.env().caller();
         
-        if amount == U256::zero() {
+        if debt_amount == U256::zero() {
             self.env().revert(LendingError::ZeroAmount);
         }
         
-        // Get borrow position
+        // The health check after the sale needs a current price
+        let oracle_address = self.price_oracle.get_or_revert_with(LendingError::InvalidConfiguration);
+        if !PriceOracleContractRef::new(self.env(), oracle_address).is_price_fresh(collateral_asset) {
+            self.env().revert(LendingError::StalePrice);
+        }
+        
         let position = self.borrow_positions.get(&caller)
             .unwrap_or_revert_with(&self.env(), LendingError::NoBorrowPosition);
         
         let total_debt = position.principal + position.interest_accrued;
-        
-        // Calculate actual repayment amount
-        let repay_amount = if amount > total_debt {
+        let repay_amount = if debt_amount > total_debt {
             total_debt
         } else {
-            amount
+            debt_amount
         };
+        if repay_amount == U256::zero() {
+            self.env().revert(LendingError::NoBorrowPosition);
+        }
         
-        // Transfer ECTO from user to pool
+        let router_address = self.dex_router.get_or_default()
+            .unwrap_or_revert_with(&self.env(), LendingError::InvalidConfiguration);
+        let mut router = RouterContractRef::new(self.env(), router_address);
         let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
-        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
-        ecto_token.transfer_from(caller, Address::from(self.env().self_address()), repay_amount);
-        
-        // Calculate interest paid
-        let interest_paid = if repay_amount >= position.interest_accrued {
-            position.interest_accrued
-        } else {
-            repay_amount
-        };
-        
-        let principal_paid = repay_amount - interest_paid;
+        let path = vec![collateral_asset, ecto_address];
         
-        // Update position
-        let new_debt = total_debt - repay_amount;
-        if new_debt == U256::zero() {
-            // Fully repaid, remove position
-            self.borrow_positions.set(&caller, BorrowPosition {
-                user: caller,
-                principal: U256::zero(),
-                interest_accrued: U256::zero(),
-                last_update: self.env().get_block_time(),
-            });
-        } else {
-            self.borrow_positions.set(&caller, BorrowPosition {
-                user: caller,
-                principal: position.principal - principal_paid,
-                interest_accrued: position.interest_accrued - interest_paid,
-                last_update: self.env().get_block_time(),
-            });
+        // Quote the collateral needed and enforce the caller's slippage bound
+        let collateral_needed = router.get_amounts_in(repay_amount, path.clone())[0];
+        if collateral_needed > max_collateral {
+            self.env().revert(LendingError::SlippageExceeded);
         }
         
-        // Update totals
-        let total_borrows = self.total_borrows.get_or_default();
-        self.total_borrows.set(total_borrows - repay_amount);
-        
-        let current_liquidity = self.total_liquidity.get_or_default();
-        self.total_liquidity.set(current_liquidity + repay_amount);
+        // Pull the collateral into the pool and sell it for exactly `repay_amount` ECTO
+        let self_address = self.env().self_address();
+        let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
+        collateral_mgr.release_collateral(caller, collateral_asset, collateral_needed, self_address);
+        
+        let mut collateral_token = Cep18TokenContractRef::new(self.env(), collateral_asset);
+        collateral_token.increase_allowance(router_address, collateral_needed);
+        router.swap_tokens_for_exact_tokens(
+            repay_amount,
+            collateral_needed,
+            path,
+            self_address,
+            self.env().get_block_time(),
+        );
         
-        // Allocate interest to reserves
-        let reserve_factor = self.reserve_factor.get_or_default();
-        let scale = U256::from(1_000_000_000_000_000_000u128); // 1e18
-        let reserves_added = (interest_paid * reserve_factor) / scale;
-        let total_reserves = self.total_reserves.get_or_default();
-        self.total_reserves.set(total_reserves + reserves_added);
+        self.settle_repayment(caller, caller, position, repay_amount);
         
-        // Update interest rates
-        self.update_interest_rates();
+        // Selling collateral must not leave the remaining debt undercollateralized
+        let remaining_debt = total_debt - repay_amount;
+        if remaining_debt > U256::zero() {
+            let health_factor = collateral_mgr.calculate_health_factor(caller, remaining_debt);
+            let scale = U256::from(1_000_000_000_000_000_000u128); // 1e18
+            if health_factor < scale {
+                self.env().revert(LendingError::HealthFactorTooLow);
+            }
+        }
+    }
+    
+    /// Sell up to `amount` of the caller's collateral through the DEX router
+    /// and repay their debt with the proceeds, reverting if that repays less
+    /// than `min_debt_repaid`
+    pub fn deleverage(&mut self, collateral_asset: Address, amount: U256, min_debt_repaid: U256) {
+        let caller = self.env().caller();
+        self.deleverage_internal(caller, collateral_asset, amount, min_debt_repaid);
+    }
+    
+    /// Deleverage another borrower's position (approved delegates only)
+    pub fn deleverage_for(
+        &mut self,
+        borrower: Address,
+        collateral_asset: Address,
+        amount: U256,
+        min_debt_repaid: U256,
+    ) {
+        let caller = self.env().caller();
+        if !self.is_deleverage_delegate(borrower, caller) {
+            self.env().revert(LendingError::Unauthorized);
+        }
+        self.deleverage_internal(borrower, collateral_asset, amount, min_debt_repaid);
+    }
+    
+    /// Allow or disallow `delegate` to deleverage the caller's position
+    pub fn set_deleverage_delegate(&mut self, delegate: Address, approved: bool) {
+        let borrower = self.env().caller();
+        self.deleverage_delegates.set(&(borrower, delegate), approved);
         
-        let timestamp = self.env().get_block_time();
-        self.env().emit_event(Repaid {
-            borrower: caller,
-            amount: repay_amount,
-            interest: interest_paid,
-            timestamp,
+        self.env().emit_event(DeleverageDelegateUpdated {
+            borrower,
+            delegate,
+            approved,
+            timestamp: self.env().get_block_time(),
         });
     }
     
+    /// Check whether `delegate` may deleverage `borrower`'s position
+    pub fn is_deleverage_delegate(&self, borrower: Address, delegate: Address) -> bool {
+        self.deleverage_delegates.get(&(borrower, delegate)).unwrap_or_default()
+    }
+    
     // ========================================
     // Liquidation
     // ========================================
@@ -378,7 +487,8 @@ impl LendingPool {
         debt_to_cover: U256,
         collateral_asset: Address,
     ) {
-        self.ensure_not_paused();
+        self.ensure_not_paused(PauseAction::Liquidation);
+        self.ensure_liquidation_grace_elapsed(collateral_asset);
         self.accrue_interest();
         
         let liquidator = self.env().caller();
@@ -395,7 +505,7 @@ impl LendingPool {
         
         // Check if position can be liquidated
         let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
-        let collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
+        let mut collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
         
         if !collateral_mgr.can_liquidate(borrower, total_debt) {
             self.env().revert(LendingError::PositionHealthy);
@@ -426,44 +536,19 @@ impl LendingPool {
         let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
         ecto_token.transfer_from(liquidator, Address::from(self.env().self_address()), actual_debt_covered);
         
-        // Update borrower's debt
-        let new_debt = total_debt - actual_debt_covered;
-        if new_debt == U256::zero() {
-            self.borrow_positions.set(&borrower, BorrowPosition {
-                user: borrower,
-                principal: U256::zero(),
-                interest_accrued: U256::zero(),
-                last_update: self.env().get_block_time(),
-            });
-        } else {
-            // Reduce principal proportionally
-            let principal_covered = (position.principal * actual_debt_covered) / total_debt;
-            let interest_covered = actual_debt_covered - principal_covered;
-            
-            self.borrow_positions.set(&borrower, BorrowPosition {
-                user: borrower,
-                principal: position.principal - principal_covered,
-                interest_accrued: position.interest_accrued - interest_covered,
-                last_update: self.env().get_block_time(),
-            });
-        }
-        
-        // Transfer collateral from borrower to liquidator
-        // This is done through collateral manager
-        let collateral_amount_in_tokens = oracle.get_asset_amount(collateral_asset, collateral_to_seize);
-        
-        // Note: In a full implementation, we'd need to handle the collateral transfer
-        // For now, we emit the event with the amounts
-        
-        // Update totals
-        let total_borrows = self.total_borrows.get_or_default();
-        self.total_borrows.set(total_borrows - actual_debt_covered);
+        self.reduce_liquidated_debt(borrower, position, actual_debt_covered);
         
-        let current_liquidity = self.total_liquidity.get_or_default();
-        self.total_liquidity.set(current_liquidity + actual_debt_covered);
+        // Transfer collateral from borrower to liquidator through the collateral manager
+        // Seizing the full value takes every token so no rounding dust is left behind
+        let collateral_amount_in_tokens = if collateral_to_seize == collateral_value {
+            borrower_collateral
+        } else {
+            oracle.get_asset_amount(collateral_asset, collateral_to_seize)
+        };
+        collateral_mgr.seize(borrower, collateral_asset, collateral_amount_in_tokens, liquidator);
         
-        // Update interest rates
-        self.update_interest_rates();
+        self.realize_bad_debt_if_insolvent(borrower);
+        self.pay_liquidation_bounty(liquidator, collateral_asset);
         
         let timestamp = self.env().get_block_time();
         let liquidation_bonus = collateral_to_seize - actual_debt_covered;
@@ -476,6 +561,67 @@ impl LendingPool {
             liquidation_bonus,
             timestamp,
         });
+        
+        self.on_position_changed(borrower);
+    }
+    
+    /// Settle a Dutch auction take (liquidation auction only)
+    /// Pulls `debt_repaid` ECTO from the taker against the borrower's debt and
+    /// sends `collateral_amount` of the borrower's collateral to the taker
+    pub fn settle_auction_take(
+        &mut self,
+        taker: Address,
+        borrower: Address,
+        collateral_asset: Address,
+        debt_repaid: U256,
+        collateral_amount: U256,
+    ) {
+        self.ensure_not_paused(PauseAction::Liquidation);
+        self.ensure_liquidation_grace_elapsed(collateral_asset);
+        if self.liquidation_auction.get_or_default() != Some(self.env().caller()) {
+            self.env().revert(LendingError::Unauthorized);
+        }
+        self.accrue_interest();
+        
+        let position = self.borrow_positions.get(&borrower)
+            .unwrap_or_revert_with(&self.env(), LendingError::NoBorrowPosition);
+        let total_debt = position.principal + position.interest_accrued;
+        if debt_repaid > total_debt {
+            self.env().revert(LendingError::ExceedsDebtAmount);
+        }
+        
+        // The auction price was fixed at start; the position must still be
+        // liquidatable now, and each take is held to the close factor
+        let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
+        if !collateral_mgr.can_liquidate(borrower, total_debt) {
+            self.env().revert(LendingError::PositionHealthy);
+        }
+        if debt_repaid > self.close_factor_debt(total_debt) {
+            self.env().revert(LendingError::ExceedsCloseFactor);
+        }
+        
+        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
+        ecto_token.transfer_from(taker, Address::from(self.env().self_address()), debt_repaid);
+        
+        self.reduce_liquidated_debt(borrower, position, debt_repaid);
+        
+        collateral_mgr.seize(borrower, collateral_asset, collateral_amount, taker);
+        
+        self.realize_bad_debt_if_insolvent(borrower);
+        
+        self.env().emit_event(Liquidated {
+            borrower,
+            liquidator: taker,
+            collateral_asset,
+            debt_covered: debt_repaid,
+            collateral_seized: collateral_amount,
+            liquidation_bonus: U256::zero(),
+            timestamp: self.env().get_block_time(),
+        });
+        
+        self.on_position_changed(borrower);
     }
     
     // ========================================
@@ -528,6 +674,54 @@ impl LendingPool {
         self.borrow_positions.get(&user)
     }
     
+    /// Get the most debt one liquidation or auction take may repay for a borrower
+    /// Zero when the position is not liquidatable
+    pub fn get_max_liquidatable_debt(&self, borrower: Address) -> U256 {
+        let total_debt = self.borrow_positions.get(&borrower)
+            .map(|position| position.principal + position.interest_accrued)
+            .unwrap_or_default();
+        if total_debt == U256::zero() {
+            return U256::zero();
+        }
+        let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        if !CollateralManagerContractRef::new(self.env(), collateral_mgr_address).can_liquidate(borrower, total_debt) {
+            return U256::zero();
+        }
+        self.close_factor_debt(total_debt)
+    }
+    
+    /// Get collateral, debt, borrowing power and health for a user in one call
+    pub fn get_user_account_data(&self, user: Address) -> UserAccountData {
+        let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        let collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
+        
+        let total_debt = self.borrow_positions.get(&user)
+            .map(|position| position.principal + position.interest_accrued)
+            .unwrap_or_default();
+        let total_collateral_value = collateral_mgr.get_user_collateral_value(user);
+        let max_borrow = collateral_mgr.get_max_borrow_amount(user);
+        
+        let scale = U256::from(1_000_000_000_000_000_000u128); // 1e18
+        let (ltv, current_liquidation_threshold) = if total_collateral_value == U256::zero() {
+            (U256::zero(), U256::zero())
+        } else {
+            let threshold_value = collateral_mgr.get_user_collateral_value_with_threshold(user);
+            (
+                (max_borrow * scale) / total_collateral_value,
+                (threshold_value * scale) / total_collateral_value,
+            )
+        };
+        
+        UserAccountData {
+            total_collateral_value,
+            total_debt,
+            available_borrow: max_borrow.saturating_sub(total_debt),
+            current_liquidation_threshold,
+            ltv,
+            health_factor: collateral_mgr.calculate_health_factor(user, total_debt),
+        }
+    }
+    
     pub fn get_total_borrows(&self) -> U256 {
         self.total_borrows.get_or_default()
     }
@@ -544,6 +738,16 @@ impl LendingPool {
         self.supply_rate.get_or_default()
     }
     
+    /// Get the borrow rate compounded every second for a year (scaled by 1e18)
+    pub fn get_borrow_apy(&self) -> U256 {
+        self.compounded_yield(self.get_borrow_rate())
+    }
+    
+    /// Get the supply rate compounded every second for a year (scaled by 1e18)
+    pub fn get_supply_apy(&self) -> U256 {
+        self.compounded_yield(self.get_supply_rate())
+    }
+    
     pub fn get_utilization_rate(&self) -> U256 {
         let total_borrows = self.total_borrows.get_or_default();
         let total_liquidity = self.total_liquidity.get_or_default();
@@ -565,32 +769,63 @@ impl LendingPool {
     // Admin Functions
     // ========================================
     
+    /// Pause deposits, borrows and liquidations at once (guardian only)
     pub fn pause(&mut self) {
-        self.only_admin();
-        self.paused.set(true);
+        self.access.require_role(GUARDIAN_ROLE, LendingError::Unauthorized);
+        self.deposit_paused.set(true);
+        self.borrow_paused.set(true);
+        self.liquidation_paused.set(true);
         
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
         let timestamp = self.env().get_block_time();
         self.env().emit_event(ContractPaused {
-            paused_by: admin,
+            paused_by: self.env().caller(),
             timestamp,
         });
     }
     
+    /// Resume every pausable action (guardian only)
     pub fn unpause(&mut self) {
-        self.only_admin();
-        self.paused.set(false);
+        self.access.require_role(GUARDIAN_ROLE, LendingError::Unauthorized);
+        self.resume_liquidations();
+        self.deposit_paused.set(false);
+        self.borrow_paused.set(false);
         
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
         let timestamp = self.env().get_block_time();
         self.env().emit_event(ContractUnpaused {
-            unpaused_by: admin,
+            unpaused_by: self.env().caller(),
             timestamp,
         });
     }
     
+    /// Pause or resume a single action (guardian only)
+    pub fn set_action_paused(&mut self, action: PauseAction, paused: bool) {
+        self.access.require_role(GUARDIAN_ROLE, LendingError::Unauthorized);
+        match action {
+            PauseAction::Deposit => self.deposit_paused.set(paused),
+            PauseAction::Borrow => self.borrow_paused.set(paused),
+            PauseAction::Liquidation if !paused => self.resume_liquidations(),
+            PauseAction::Liquidation => self.liquidation_paused.set(true),
+        }
+        
+        self.env().emit_event(ActionPauseUpdated {
+            action,
+            paused,
+            updated_by: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Check whether an action is paused
+    pub fn is_action_paused(&self, action: PauseAction) -> bool {
+        match action {
+            PauseAction::Deposit => self.deposit_paused.get_or_default(),
+            PauseAction::Borrow => self.borrow_paused.get_or_default(),
+            PauseAction::Liquidation => self.liquidation_paused.get_or_default(),
+        }
+    }
+    
     pub fn set_reserve_factor(&mut self, new_factor: U256) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
         
         let scale = U256::from(1_000_000_000_000_000_000u128); // 1e18
         if new_factor > scale {
@@ -600,25 +835,1704 @@ impl LendingPool {
         let old_factor = self.reserve_factor.get_or_default();
         self.reserve_factor.set(new_factor);
         
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
         self.env().emit_event(ReserveFactorUpdated {
             old_factor,
             new_factor,
-            updated_by: admin,
+            updated_by: self.env().caller(),
         });
     }
     
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
-        if caller != admin {
-            self.env().revert(LendingError::Unauthorized);
+    /// Set the DEX router used by `repay_with_collateral` and `deleverage` (admin only)
+    pub fn set_dex_router(&mut self, router: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.dex_router.set(Some(router));
+    }
+    
+    /// Get the DEX router used by `repay_with_collateral` and `deleverage`
+    pub fn get_dex_router(&self) -> Option<Address> {
+        self.dex_router.get_or_default()
+    }
+    
+    /// Set the Dutch auction module allowed to settle auction takes (admin only)
+    pub fn set_liquidation_auction(&mut self, auction: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.liquidation_auction.set(Some(auction));
+    }
+    
+    /// Get the Dutch auction module allowed to settle auction takes
+    pub fn get_liquidation_auction(&self) -> Option<Address> {
+        self.liquidation_auction.get_or_default()
+    }
+    
+    /// Set the safety module drawn on to cover bad debt (admin only)
+    pub fn set_safety_module(&mut self, safety_module: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.safety_module.set(Some(safety_module));
+    }
+    
+    /// Get the safety module drawn on to cover bad debt
+    pub fn get_safety_module(&self) -> Option<Address> {
+        self.safety_module.get_or_default()
+    }
+    
+    /// Get the number of addresses with open debt
+    pub fn get_borrower_count(&self) -> u32 {
+        self.borrower_count.get_or_default()
+    }
+    
+    /// Get up to `limit` addresses with open debt, starting at index `start`
+    pub fn get_borrowers(&self, start: u32, limit: u32) -> Vec<Address> {
+        let end = self.page_end(start, limit);
+        (start..end).filter_map(|i| self.borrowers.get(&i)).collect()
+    }
+    
+    /// Get the liquidatable borrowers among up to `limit` open positions
+    /// starting at index `start`, so bots can scan the registry in pages
+    pub fn get_unhealthy_positions(&self, start: u32, limit: u32) -> Vec<Address> {
+        let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        let collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
+        
+        self.get_borrowers(start, limit)
+            .into_iter()
+            .filter(|borrower| collateral_mgr.can_liquidate(*borrower, self.debt_of(*borrower)))
+            .collect()
+    }
+    
+    /// Get the ECTO owed to aECTO holders: liquidity plus borrows, less the
+    /// protocol's reserves
+    pub fn get_total_assets(&self) -> U256 {
+        let liquidity = self.total_liquidity.get_or_default();
+        let borrows = self.total_borrows.get_or_default();
+        (liquidity + borrows).saturating_sub(self.total_reserves.get_or_default())
+    }
+    
+    /// Get total protocol reserves
+    pub fn get_total_reserves(&self) -> U256 {
+        self.total_reserves.get_or_default()
+    }
+    
+    /// Top up protocol reserves with the caller's ECTO
+    pub fn add_reserves(&mut self, amount: U256) {
+        if amount == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
         }
+        
+        let caller = self.env().caller();
+        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
+        ecto_token.transfer_from(caller, Address::from(self.env().self_address()), amount);
+        
+        self.total_reserves.set(self.total_reserves.get_or_default() + amount);
+        self.total_liquidity.set(self.total_liquidity.get_or_default() + amount);
+        self.update_interest_rates();
     }
     
-    fn ensure_not_paused(&self) {
-        if self.paused.get_or_default() {
-            self.env().revert(LendingError::ContractPaused);
+    /// Send `amount` of protocol reserves to the fee collector (fee collector only)
+    /// 
+    /// Only reserves held as idle liquidity can be collected.
+    pub fn collect_reserves(&mut self, amount: U256) {
+        let caller = self.env().caller();
+        if self.fee_collector.get_or_default() != Some(caller) {
+            self.env().revert(LendingError::Unauthorized);
+        }
+        if amount == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        
+        let total_reserves = self.total_reserves.get_or_default();
+        let total_liquidity = self.total_liquidity.get_or_default();
+        if amount > total_reserves || amount > total_liquidity {
+            self.env().revert(LendingError::InsufficientLiquidity);
         }
+        
+        self.total_reserves.set(total_reserves - amount);
+        self.total_liquidity.set(total_liquidity - amount);
+        self.update_interest_rates();
+        
+        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
+        ecto_token.transfer(caller, amount);
+        
+        self.env().emit_event(ReservesCollected {
+            collector: caller,
+            amount,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Set the fee distributor allowed to collect protocol reserves (admin only)
+    pub fn set_fee_collector(&mut self, collector: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.fee_collector.set(Some(collector));
+    }
+    
+    /// Get the fee distributor allowed to collect protocol reserves
+    pub fn get_fee_collector(&self) -> Option<Address> {
+        self.fee_collector.get_or_default()
+    }
+    
+    /// Set the PointsLedger credited with borrows (admin only)
+    pub fn set_points_ledger(&mut self, ledger: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.points_ledger.set(Some(ledger));
+    }
+    
+    /// Get the PointsLedger credited with borrows
+    pub fn get_points_ledger(&self) -> Option<Address> {
+        self.points_ledger.get_or_default()
+    }
+    
+    /// Set how long liquidations stay blocked after liquidations are
+    /// unpaused or a collateral price recovers from staleness (admin only)
+    pub fn set_liquidation_grace_period(&mut self, grace_period: u64) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.liquidation_grace_period.set(grace_period);
+    }
+    
+    /// Get how long liquidations stay blocked after an outage
+    pub fn get_liquidation_grace_period(&self) -> u64 {
+        self.liquidation_grace_period.get_or_default()
+    }
+    
+    /// Cap supplier withdrawals at `cap_per_block` ECTO per block while
+    /// utilization is above `utilization_threshold` (admin only)
+    /// A zero threshold disables the limit
+    pub fn set_withdrawal_limit(&mut self, utilization_threshold: U256, cap_per_block: U256) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.withdrawal_limit_utilization.set(utilization_threshold);
+        self.withdrawal_cap_per_block.set(cap_per_block);
+    }
+    
+    /// Get the withdrawal limit as (utilization threshold, cap per block)
+    pub fn get_withdrawal_limit(&self) -> (U256, U256) {
+        (
+            self.withdrawal_limit_utilization.get_or_default(),
+            self.withdrawal_cap_per_block.get_or_default(),
+        )
+    }
+    
+    /// Cap total supplied ECTO (liquidity + borrows - reserves) at `cap`
+    /// (admin only); zero removes the cap
+    pub fn set_supply_cap(&mut self, cap: U256) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.supply_cap.set(cap);
+    }
+    
+    /// Get the supply cap (zero means no cap)
+    pub fn get_supply_cap(&self) -> U256 {
+        self.supply_cap.get_or_default()
+    }
+    
+    /// Get how much more ECTO suppliers can deposit right now
+    /// Zero while deposits are paused, `U256::MAX` without a supply cap
+    pub fn get_available_supply(&self) -> U256 {
+        if self.is_action_paused(PauseAction::Deposit) {
+            return U256::zero();
+        }
+        let cap = self.supply_cap.get_or_default();
+        if cap == U256::zero() {
+            return U256::MAX;
+        }
+        cap.saturating_sub(self.get_total_assets())
+    }
+    
+    /// Get how much ECTO suppliers can withdraw in total right now
+    /// Bounded by liquidity, and by what is left of this block's cap while
+    /// utilization is above the withdrawal limit threshold
+    pub fn get_available_withdrawal(&self) -> U256 {
+        let liquidity = self.total_liquidity.get_or_default();
+        
+        let threshold = self.withdrawal_limit_utilization.get_or_default();
+        if threshold == U256::zero() || self.get_utilization_rate() <= threshold {
+            return liquidity;
+        }
+        
+        let cap = self.withdrawal_cap_per_block.get_or_default();
+        let remaining = cap.saturating_sub(self.withdrawn_in_current_block());
+        if remaining > liquidity {
+            liquidity
+        } else {
+            remaining
+        }
+    }
+    
+    /// Set the flat ECTO bounty paid from reserves to liquidators of a
+    /// collateral asset, on top of the collateral bonus (admin only)
+    pub fn set_liquidation_bounty(&mut self, collateral_asset: Address, bounty: U256) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.liquidation_bounties.set(&collateral_asset, bounty);
+    }
+    
+    /// Get the flat ECTO bounty paid to liquidators of a collateral asset
+    pub fn get_liquidation_bounty(&self, collateral_asset: Address) -> U256 {
+        self.liquidation_bounties.get(&collateral_asset).unwrap_or_default()
+    }
+    
+    /// Set the dECTO token minted on borrow and burned on repay (admin only)
+    pub fn set_debt_token(&mut self, debt_token: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.debt_token.set(Some(debt_token));
+    }
+    
+    /// Get the dECTO token minted on borrow and burned on repay
+    pub fn get_debt_token(&self) -> Option<Address> {
+        self.debt_token.get_or_default()
+    }
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    
+    /// Pull `amount` of ECTO from `payer` and apply it to `borrower`'s debt
+    fn repay_internal(&mut self, payer: Address, borrower: Address, amount: U256) {
+        self.accrue_interest();
+        
+        if amount == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        
+        // Get borrow position
+        let position = self.borrow_positions.get(&borrower)
+            .unwrap_or_revert_with(&self.env(), LendingError::NoBorrowPosition);
+        
+        let total_debt = position.principal + position.interest_accrued;
+        
+        // Calculate actual repayment amount
+        let repay_amount = if amount > total_debt {
+            total_debt
+        } else {
+            amount
+        };
+        
+        // Transfer ECTO from payer to pool
+        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
+        ecto_token.transfer_from(payer, Address::from(self.env().self_address()), repay_amount);
+        
+        self.settle_repayment(payer, borrower, position, repay_amount);
+    }
+    
+    /// Sell `borrower`'s collateral for ECTO and apply it to their debt
+    ///
+    /// When `amount` would buy more than the outstanding debt, only the
+    /// collateral needed to clear the debt is sold.
+    fn deleverage_internal(
+        &mut self,
+        borrower: Address,
+        collateral_asset: Address,
+        amount: U256,
+        min_debt_repaid: U256,
+    ) {
+        self.accrue_interest();
+        
+        if amount == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        
+        let position = self.borrow_positions.get(&borrower)
+            .unwrap_or_revert_with(&self.env(), LendingError::NoBorrowPosition);
+        let total_debt = position.principal + position.interest_accrued;
+        if total_debt == U256::zero() {
+            self.env().revert(LendingError::NoBorrowPosition);
+        }
+        
+        let router_address = self.dex_router.get_or_default()
+            .unwrap_or_revert_with(&self.env(), LendingError::InvalidConfiguration);
+        let mut router = RouterContractRef::new(self.env(), router_address);
+        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
+        let path = vec![collateral_asset, ecto_address];
+        
+        let quoted_out = router.get_amounts_out(amount, path.clone())[1];
+        let (collateral_sold, repay_amount) = if quoted_out > total_debt {
+            (router.get_amounts_in(total_debt, path.clone())[0], total_debt)
+        } else {
+            (amount, quoted_out)
+        };
+        if repay_amount < min_debt_repaid {
+            self.env().revert(LendingError::SlippageExceeded);
+        }
+        
+        let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
+        let health_factor_before = collateral_mgr.calculate_health_factor(borrower, total_debt);
+        
+        // Pull the collateral into the pool and sell it for ECTO
+        let self_address = self.env().self_address();
+        collateral_mgr.release_collateral(borrower, collateral_asset, collateral_sold, self_address);
+        
+        let mut collateral_token = Cep18TokenContractRef::new(self.env(), collateral_asset);
+        collateral_token.increase_allowance(router_address, collateral_sold);
+        router.swap_exact_tokens_for_tokens(
+            collateral_sold,
+            repay_amount,
+            path,
+            self_address,
+            self.env().get_block_time(),
+        );
+        
+        self.settle_repayment(borrower, borrower, position, repay_amount);
+        
+        // An unhealthy result is only allowed if it improves on where the
+        // borrower started
+        let remaining_debt = total_debt - repay_amount;
+        if remaining_debt > U256::zero() {
+            let health_factor = collateral_mgr.calculate_health_factor(borrower, remaining_debt);
+            let scale = U256::from(1_000_000_000_000_000_000u128); // 1e18
+            if health_factor < scale && health_factor < health_factor_before {
+                self.env().revert(LendingError::HealthFactorTooLow);
+            }
+        }
+        
+        self.env().emit_event(Deleveraged {
+            borrower,
+            caller: self.env().caller(),
+            collateral_asset,
+            collateral_sold,
+            debt_repaid: repay_amount,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Apply ECTO already held by the pool to `borrower`'s debt
+    fn settle_repayment(
+        &mut self,
+        payer: Address,
+        borrower: Address,
+        position: BorrowPosition,
+        repay_amount: U256,
+    ) {
+        let total_debt = position.principal + position.interest_accrued;
+        
+        // Calculate interest paid
+        let interest_paid = if repay_amount >= position.interest_accrued {
+            position.interest_accrued
+        } else {
+            repay_amount
+        };
+        
+        let principal_paid = repay_amount - interest_paid;
+        
+        // Update position
+        let new_debt = total_debt - repay_amount;
+        if new_debt == U256::zero() {
+            // Fully repaid, remove position
+            self.borrow_positions.set(&borrower, BorrowPosition {
+                user: borrower,
+                principal: U256::zero(),
+                interest_accrued: U256::zero(),
+                last_update: self.env().get_block_time(),
+            });
+        } else {
+            self.borrow_positions.set(&borrower, BorrowPosition {
+                user: borrower,
+                principal: position.principal - principal_paid,
+                interest_accrued: position.interest_accrued - interest_paid,
+                last_update: self.env().get_block_time(),
+            });
+        }
+        
+        // Update totals
+        let total_borrows = self.total_borrows.get_or_default();
+        self.total_borrows.set(total_borrows - repay_amount);
+        self.burn_debt(borrower, repay_amount);
+        
+        let current_liquidity = self.total_liquidity.get_or_default();
+        self.total_liquidity.set(current_liquidity + repay_amount);
+        
+        // Allocate interest to reserves
+        let reserves_added = percent_mul(interest_paid, self.reserve_factor.get_or_default())
+            .unwrap_or_else(|_| self.env().revert(LendingError::MathOverflow));
+        let total_reserves = self.total_reserves.get_or_default();
+        self.total_reserves.set(total_reserves + reserves_added);
+        
+        // Update interest rates
+        self.update_interest_rates();
+        
+        let timestamp = self.env().get_block_time();
+        self.env().emit_event(Repaid {
+            borrower,
+            repaid_by: payer,
+            amount: repay_amount,
+            interest: interest_paid,
+            timestamp,
+        });
+        
+        self.on_position_changed(borrower);
+    }
+    
+    /// Reduce a liquidated borrower's debt, splitting the cover pro rata
+    /// between principal and interest, and return the ECTO to liquidity
+    fn reduce_liquidated_debt(&mut self, borrower: Address, position: BorrowPosition, debt_covered: U256) {
+        let total_debt = position.principal + position.interest_accrued;
+        let new_debt = total_debt - debt_covered;
+        if new_debt == U256::zero() {
+            self.borrow_positions.set(&borrower, BorrowPosition {
+                user: borrower,
+                principal: U256::zero(),
+                interest_accrued: U256::zero(),
+                last_update: self.env().get_block_time(),
+            });
+        } else {
+            // Reduce principal proportionally
+            let principal_covered = (position.principal * debt_covered) / total_debt;
+            let interest_covered = debt_covered - principal_covered;
+            
+            self.borrow_positions.set(&borrower, BorrowPosition {
+                user: borrower,
+                principal: position.principal - principal_covered,
+                interest_accrued: position.interest_accrued - interest_covered,
+                last_update: self.env().get_block_time(),
+            });
+        }
+        
+        // Update totals
+        let total_borrows = self.total_borrows.get_or_default();
+        self.total_borrows.set(total_borrows - debt_covered);
+        self.burn_debt(borrower, debt_covered);
+        
+        let current_liquidity = self.total_liquidity.get_or_default();
+        self.total_liquidity.set(current_liquidity + debt_covered);
+        
+        // Update interest rates
+        self.update_interest_rates();
+    }
+    
+    /// Write off debt left on a borrower with no collateral remaining
+    /// The shortfall is burned against reserves first, then drawn from the
+    /// safety module; anything beyond that is absorbed by lenders
+    fn realize_bad_debt_if_insolvent(&mut self, borrower: Address) {
+        let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        let collateral_mgr = CollateralManagerContractRef::new(self.env(), collateral_mgr_address);
+        if collateral_mgr.get_user_collateral_value(borrower) > U256::zero() {
+            return;
+        }
+        
+        let bad_debt = match self.borrow_positions.get(&borrower) {
+            Some(position) => position.principal + position.interest_accrued,
+            None => return,
+        };
+        if bad_debt == U256::zero() {
+            return;
+        }
+        
+        self.borrow_positions.set(&borrower, BorrowPosition {
+            user: borrower,
+            principal: U256::zero(),
+            interest_accrued: U256::zero(),
+            last_update: self.env().get_block_time(),
+        });
+        let total_borrows = self.total_borrows.get_or_default();
+        self.total_borrows.set(total_borrows - bad_debt);
+        self.burn_debt(borrower, bad_debt);
+        
+        // Reserves absorb the loss first
+        let total_reserves = self.total_reserves.get_or_default();
+        let covered_by_reserves = if bad_debt > total_reserves {
+            total_reserves
+        } else {
+            bad_debt
+        };
+        self.total_reserves.set(total_reserves - covered_by_reserves);
+        
+        // Then the safety module backstop
+        let mut remaining = bad_debt - covered_by_reserves;
+        let mut covered_by_safety_module = U256::zero();
+        if remaining > U256::zero() {
+            if let Some(safety_module_address) = self.safety_module.get_or_default() {
+                let mut safety_module = SafetyModuleContractRef::new(self.env(), safety_module_address);
+                covered_by_safety_module = safety_module.cover_shortfall(remaining);
+                remaining = remaining - covered_by_safety_module;
+                
+                let current_liquidity = self.total_liquidity.get_or_default();
+                self.total_liquidity.set(current_liquidity + covered_by_safety_module);
+            }
+        }
+        
+        // Lenders' assets (see `get_total_assets`) only fall by the part
+        // nothing else could cover
+        self.update_interest_rates();
+        
+        self.env().emit_event(BadDebtRealized {
+            borrower,
+            bad_debt,
+            covered_by_reserves,
+            covered_by_safety_module,
+            uncovered: remaining,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Keep the open-debt registry in sync and publish the new health factor
+    fn on_position_changed(&mut self, user: Address) {
+        let debt = self.debt_of(user);
+        let slot = self.borrower_slots.get(&user).unwrap_or_default();
+        
+        if debt > U256::zero() && slot == 0 {
+            let count = self.borrower_count.get_or_default();
+            self.borrowers.set(&count, user);
+            self.borrower_slots.set(&user, count + 1);
+            self.borrower_count.set(count + 1);
+        } else if debt == U256::zero() && slot > 0 {
+            // Swap with the last borrower to keep indices dense
+            let count = self.borrower_count.get_or_default();
+            let index = slot - 1;
+            let last = count - 1;
+            if index != last {
+                let last_borrower = self.borrowers.get(&last)
+                    .unwrap_or_revert_with(&self.env(), LendingError::NoBorrowPosition);
+                self.borrowers.set(&index, last_borrower);
+                self.borrower_slots.set(&last_borrower, slot);
+            }
+            self.borrower_slots.set(&user, 0);
+            self.borrower_count.set(last);
+        }
+        
+        let collateral_mgr_address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        let health_factor = CollateralManagerContractRef::new(self.env(), collateral_mgr_address)
+            .calculate_health_factor(user, debt);
+        self.env().emit_event(HealthFactorChanged {
+            user,
+            health_factor,
+            debt,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Current debt (principal + interest) of a user
+    fn debt_of(&self, user: Address) -> U256 {
+        self.borrow_positions.get(&user)
+            .map(|position| position.principal + position.interest_accrued)
+            .unwrap_or_default()
+    }
+    
+    /// Exclusive end index of a registry page
+    fn page_end(&self, start: u32, limit: u32) -> u32 {
+        let count = self.borrower_count.get_or_default();
+        let end = start.saturating_add(limit);
+        if end > count {
+            count
+        } else {
+            end
+        }
+    }
+    
+    /// Pay the collateral asset's liquidation bounty out of reserves,
+    /// capped at what the reserves and idle liquidity can cover
+    fn pay_liquidation_bounty(&mut self, liquidator: Address, collateral_asset: Address) {
+        let total_reserves = self.total_reserves.get_or_default();
+        let current_liquidity = self.total_liquidity.get_or_default();
+        let available = if total_reserves > current_liquidity {
+            current_liquidity
+        } else {
+            total_reserves
+        };
+        let configured = self.get_liquidation_bounty(collateral_asset);
+        let bounty = if configured > available {
+            available
+        } else {
+            configured
+        };
+        if bounty == U256::zero() {
+            return;
+        }
+        
+        self.total_reserves.set(total_reserves - bounty);
+        self.total_liquidity.set(current_liquidity - bounty);
+        
+        let ecto_address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut ecto_token = Cep18TokenContractRef::new(self.env(), ecto_address);
+        ecto_token.transfer(liquidator, bounty);
+        
+        self.update_interest_rates();
+        
+        self.env().emit_event(LiquidationBountyPaid {
+            liquidator,
+            collateral_asset,
+            amount: bounty,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Mint dECTO for new debt, if a debt token is configured
+    fn mint_debt(&self, borrower: Address, amount: U256) {
+        if let Some(debt_token) = self.debt_token.get_or_default() {
+            DebtTokenContractRef::new(self.env(), debt_token).mint(borrower, amount);
+        }
+    }
+    
+    /// Burn dECTO for settled debt, if a debt token is configured
+    fn burn_debt(&self, borrower: Address, amount: U256) {
+        if let Some(debt_token) = self.debt_token.get_or_default() {
+            DebtTokenContractRef::new(self.env(), debt_token).burn(borrower, amount);
+        }
+    }
+    
+    /// ECTO already withdrawn by suppliers in the current block
+    fn withdrawn_in_current_block(&self) -> U256 {
+        if self.last_withdrawal_time.get_or_default() == self.env().get_block_time() {
+            self.withdrawn_this_block.get_or_default()
+        } else {
+            U256::zero()
+        }
+    }
+    
+    /// Count a supplier withdrawal against the current block's cap
+    fn record_withdrawal(&mut self, amount: U256) {
+        let withdrawn = self.withdrawn_in_current_block();
+        self.last_withdrawal_time.set(self.env().get_block_time());
+        self.withdrawn_this_block.set(withdrawn + amount);
+    }
+    
+    /// Unpause liquidations, starting the grace window if they were paused
+    fn resume_liquidations(&mut self) {
+        if self.liquidation_paused.get_or_default() {
+            self.liquidations_unpaused_at.set(self.env().get_block_time());
+        }
+        self.liquidation_paused.set(false);
+    }
+    
+    /// Revert while borrowers are still inside the grace window that follows
+    /// a liquidation unpause or the collateral price recovering from staleness
+    fn ensure_liquidation_grace_elapsed(&self, collateral_asset: Address) {
+        let grace_period = self.liquidation_grace_period.get_or_default();
+        if grace_period == 0 {
+            return;
+        }
+        
+        let oracle_address = self.price_oracle.get_or_revert_with(LendingError::InvalidConfiguration);
+        let recovered_at = PriceOracleContractRef::new(self.env(), oracle_address).get_recovered_at(collateral_asset);
+        let unpaused_at = self.liquidations_unpaused_at.get_or_default();
+        let outage_ended_at = if recovered_at > unpaused_at { recovered_at } else { unpaused_at };
+        
+        if self.env().get_block_time() < outage_ended_at + grace_period {
+            self.env().revert(LendingError::LiquidationGracePeriod);
+        }
+    }
+    
+    fn only_aecto_vault(&self) {
+        let vault = self.aecto_vault.get_or_revert_with(LendingError::InvalidConfiguration);
+        if self.env().caller() != vault {
+            self.env().revert(LendingError::Unauthorized);
+        }
+    }
+    
+    /// Annual yield (1e18) of an annual rate (1e18) compounded every second
+    fn compounded_yield(&self, rate: U256) -> U256 {
+        wad_to_ray(rate)
+            .and_then(apr_to_apy)
+            .map(ray_to_wad)
+            .unwrap_or_else(|_| self.env().revert(LendingError::MathOverflow))
+    }
+    
+    /// Close factor share of `total_debt` (the whole debt once it is dust)
+    fn close_factor_debt(&self, total_debt: U256) -> U256 {
+        let engine_address = self.liquidation_engine.get_or_revert_with(LendingError::InvalidConfiguration);
+        LiquidationEngineContractRef::new(self.env(), engine_address).get_max_liquidatable_debt(total_debt)
+    }
+    
+    fn ensure_not_paused(&self, action: PauseAction) {
+        if self.is_action_paused(action) {
+            self.env().revert(LendingError::ContractPaused);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lending::aecto_vault::{AectoVault, AectoVaultInitArgs, AectoVaultHostRef};
+    use crate::lending::collateral_manager::{CollateralManager, CollateralManagerInitArgs, CollateralManagerHostRef};
+    use crate::lending::debt_token::{DebtToken, DebtTokenInitArgs, DebtTokenHostRef};
+    use crate::lending::interest_rate::{InterestRateStrategy, InterestRateStrategyInitArgs};
+    use crate::lending::liquidation::LiquidationEngine;
+    use crate::lending::price_oracle::{PriceOracle, PriceOracleHostRef};
+    use crate::lending::safety_module::{SafetyModule, SafetyModuleInitArgs};
+    use crate::cep4626::compliance::{check_compliance, VaultUnderTest};
+    use crate::cep4626::SharePriceUpdated;
+    use crate::tokens::{EctoToken, EctoTokenHostRef, UsdcToken, WethToken, WethTokenHostRef};
+    use crate::errors::TokenError;
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+
+    #[test]
+    fn test_deposit_mints_to_third_party_receiver() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let depositor = env.get_account(1);
+        let receiver = env.get_account(2);
+        let placeholder = env.get_account(9);
+
+        env.set_caller(admin);
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let strategy = InterestRateStrategy::deploy(&env, InterestRateStrategyInitArgs {
+            base_rate: U256::zero(),
+            optimal_utilization: U256::from(800_000_000_000_000_000u128),
+            slope1: U256::from(40_000_000_000_000_000u128),
+            slope2: U256::from(750_000_000_000_000_000u128),
+        });
+        let mut vault = AectoVault::deploy(&env, AectoVaultInitArgs {
+            ecto_token_address: ecto.address(),
+            lending_pool_address: placeholder,
+        });
+        let pool = LendingPool::deploy(&env, LendingPoolInitArgs {
+            aecto_vault_address: vault.address(),
+            collateral_manager_address: placeholder,
+            interest_rate_strategy_address: strategy.address(),
+            liquidation_engine_address: placeholder,
+            price_oracle_address: placeholder,
+            ecto_token_address: ecto.address(),
+        });
+        vault.set_lending_pool(pool.address());
+
+        let amount = U256::from(1_000);
+        ecto.mint(depositor, amount);
+        env.set_caller(depositor);
+        ecto.approve(vault.address(), amount);
+
+        // No aECTO approval is needed for a different receiver
+        let shares = vault.deposit(amount, receiver);
+
+        assert_eq!(shares, amount);
+        assert_eq!(vault.balance_of(receiver), shares);
+        assert_eq!(vault.balance_of(depositor), U256::zero());
+        assert_eq!(ecto.balance_of(depositor), U256::zero());
+        assert_eq!(pool.get_total_liquidity(), amount);
+    }
+
+    #[test]
+    fn test_vault_deposits_share_pool_accounting() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let admin = env.get_account(0);
+        let depositor = env.get_account(4);
+
+        // 200 idle + 800 borrowed back the lender's 1000 shares
+        assert_eq!(m.vault.total_assets(), U256::from(1_000));
+        assert_eq!(m.vault.total_assets(), m.pool.get_total_assets());
+
+        m.ecto.mint(depositor, U256::from(500));
+        env.set_caller(depositor);
+        m.ecto.approve(m.vault.address(), U256::from(500));
+        assert_eq!(m.vault.deposit(U256::from(500), depositor), U256::from(500));
+        assert_eq!(m.pool.get_total_liquidity(), U256::from(700));
+        assert_eq!(m.ecto.balance_of(m.pool.address()), U256::from(700));
+        assert_eq!(m.ecto.balance_of(m.vault.address()), U256::zero());
+
+        // Reserves sit in the pool but do not belong to aECTO holders
+        env.set_caller(admin);
+        m.ecto.mint(admin, U256::from(50));
+        m.ecto.approve(m.pool.address(), U256::from(50));
+        m.pool.add_reserves(U256::from(50));
+        assert_eq!(m.vault.total_assets(), U256::from(1_500));
+        assert_eq!(m.vault.convert_to_assets(U256::from(500)), U256::from(500));
+
+        // Liquidity can only be moved through the vault
+        assert_eq!(
+            m.pool.try_supply_liquidity(admin, admin, U256::from(1), U256::from(1)).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+        assert_eq!(
+            m.pool.try_release_liquidity(admin, admin, U256::from(1), U256::from(1)).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+
+        env.set_caller(depositor);
+        m.vault.redeem(U256::from(500), depositor, depositor);
+        assert_eq!(m.ecto.balance_of(depositor), U256::from(500));
+        assert_eq!(m.vault.total_assets(), U256::from(1_000));
+    }
+
+    #[test]
+    fn test_migrating_vault_held_ecto_into_pool() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+
+        // ECTO left in the vault by the old direct-deposit path
+        m.ecto.mint(m.vault.address(), U256::from(300));
+        assert_eq!(m.vault.total_assets(), U256::from(1_000));
+
+        assert_eq!(m.vault.migrate_liquidity_to_pool(), U256::from(300));
+        assert_eq!(m.ecto.balance_of(m.vault.address()), U256::zero());
+        assert_eq!(m.pool.get_total_liquidity(), U256::from(500));
+        assert_eq!(m.vault.total_assets(), U256::from(1_300));
+        assert_eq!(m.vault.total_supply(), U256::from(1_000));
+    }
+
+    struct Market {
+        ecto: EctoTokenHostRef,
+        weth: WethTokenHostRef,
+        oracle: PriceOracleHostRef,
+        collateral_mgr: CollateralManagerHostRef,
+        vault: AectoVaultHostRef,
+        debt: DebtTokenHostRef,
+        pool: LendingPoolHostRef,
+    }
+
+    /// Deploy a market with WETH collateral (80% LTV, 85% threshold, 5% bonus)
+    /// where a lender supplies 1000 ECTO and the borrower posts 1000 WETH at
+    /// 1 ECTO and borrows 800
+    fn deploy_market(env: &HostEnv) -> Market {
+        let admin = env.get_account(0);
+        let lender = env.get_account(1);
+        let borrower = env.get_account(2);
+        let scale = U256::from(1_000_000_000_000_000_000u128);
+
+        env.set_caller(admin);
+        let mut ecto = EctoToken::deploy(env, NoArgs);
+        let mut weth = WethToken::deploy(env, NoArgs);
+        let mut oracle = PriceOracle::deploy(env, NoArgs);
+        oracle.set_price(weth.address(), scale);
+        let mut collateral_mgr = CollateralManager::deploy(env, CollateralManagerInitArgs {
+            price_oracle_address: oracle.address(),
+        });
+        collateral_mgr.add_collateral(
+            weth.address(),
+            U256::from(800_000_000_000_000_000u128), // 80% LTV
+            U256::from(850_000_000_000_000_000u128), // 85% threshold
+            U256::from(50_000_000_000_000_000u128),  // 5% bonus
+        );
+        let engine = LiquidationEngine::deploy(env, NoArgs);
+        let strategy = InterestRateStrategy::deploy(env, InterestRateStrategyInitArgs {
+            base_rate: U256::zero(),
+            optimal_utilization: U256::from(800_000_000_000_000_000u128),
+            slope1: U256::from(40_000_000_000_000_000u128),
+            slope2: U256::from(750_000_000_000_000_000u128),
+        });
+        let mut vault = AectoVault::deploy(env, AectoVaultInitArgs {
+            ecto_token_address: ecto.address(),
+            lending_pool_address: admin,
+        });
+        let mut pool = LendingPool::deploy(env, LendingPoolInitArgs {
+            aecto_vault_address: vault.address(),
+            collateral_manager_address: collateral_mgr.address(),
+            interest_rate_strategy_address: strategy.address(),
+            liquidation_engine_address: engine.address(),
+            price_oracle_address: oracle.address(),
+            ecto_token_address: ecto.address(),
+        });
+        vault.set_lending_pool(pool.address());
+        collateral_mgr.set_lending_pool(pool.address());
+        let debt = DebtToken::deploy(env, DebtTokenInitArgs {
+            lending_pool_address: pool.address(),
+        });
+        pool.set_debt_token(debt.address());
+
+        ecto.mint(lender, U256::from(1_000));
+        env.set_caller(lender);
+        ecto.approve(vault.address(), U256::from(1_000));
+        vault.deposit(U256::from(1_000), lender);
+
+        weth.mint(borrower, U256::from(1_000));
+        env.set_caller(borrower);
+        weth.approve(collateral_mgr.address(), U256::from(1_000));
+        collateral_mgr.deposit_collateral(weth.address(), U256::from(1_000));
+        pool.borrow(U256::from(800), weth.address());
+
+        env.set_caller(admin);
+        Market { ecto, weth, oracle, collateral_mgr, vault, debt, pool }
+    }
+
+    #[test]
+    fn test_liquidation_seizes_collateral_to_liquidator() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+        let liquidator = env.get_account(3);
+
+        // Collateral drops 10%: 900 * 0.85 = 765 < 800 debt
+        m.oracle.set_price(m.weth.address(), U256::from(900_000_000_000_000_000u128));
+
+        m.ecto.mint(liquidator, U256::from(400));
+        env.set_caller(liquidator);
+        m.ecto.approve(m.pool.address(), U256::from(400));
+        m.pool.liquidate(borrower, U256::from(400), m.weth.address());
+
+        // 400 ECTO of debt plus a 5% bonus, valued at 0.9 ECTO per WETH
+        let seized = U256::from(466);
+        assert_eq!(m.weth.balance_of(liquidator), seized);
+        assert_eq!(
+            m.collateral_mgr.get_user_collateral(borrower, m.weth.address()),
+            U256::from(1_000) - seized
+        );
+        assert_eq!(m.weth.balance_of(m.collateral_mgr.address()), U256::from(1_000) - seized);
+    }
+
+    #[test]
+    fn test_blacklisted_usdc_collateral_is_frozen_but_liquidatable() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let liquidator = env.get_account(3);
+        let borrower = env.get_account(4);
+
+        let mut usdc = UsdcToken::deploy(&env, NoArgs);
+        m.oracle.set_price(usdc.address(), U256::from(1_000_000_000_000_000_000u128));
+        m.collateral_mgr.add_collateral(
+            usdc.address(),
+            U256::from(800_000_000_000_000_000u128),
+            U256::from(850_000_000_000_000_000u128),
+            U256::from(50_000_000_000_000_000u128),
+        );
+        usdc.mint(borrower, U256::from(1_000));
+        env.set_caller(borrower);
+        usdc.approve(m.collateral_mgr.address(), U256::from(1_000));
+        m.collateral_mgr.deposit_collateral(usdc.address(), U256::from(1_000));
+        m.pool.borrow(U256::from(100), usdc.address());
+
+        // Frozen collateral cannot be withdrawn
+        env.set_caller(env.get_account(0));
+        usdc.blacklist(borrower);
+        env.set_caller(borrower);
+        assert_eq!(
+            m.collateral_mgr.try_withdraw_collateral(usdc.address(), U256::from(100)).unwrap_err(),
+            TokenError::AccountBlacklisted.into()
+        );
+
+        // USDC drops to 0.1: 100 * 0.85 = 85 < 100 debt
+        env.set_caller(env.get_account(0));
+        m.oracle.set_price(usdc.address(), U256::from(100_000_000_000_000_000u128));
+        m.ecto.mint(liquidator, U256::from(50));
+        env.set_caller(liquidator);
+        m.ecto.approve(m.pool.address(), U256::from(50));
+
+        // A blacklisted liquidator cannot receive the seized USDC
+        env.set_caller(env.get_account(0));
+        usdc.blacklist(liquidator);
+        env.set_caller(liquidator);
+        assert_eq!(
+            m.pool.try_liquidate(borrower, U256::from(50), usdc.address()).unwrap_err(),
+            TokenError::AccountBlacklisted.into()
+        );
+
+        // A frozen borrower's collateral can still be seized by a clean liquidator
+        env.set_caller(env.get_account(0));
+        usdc.unblacklist(liquidator);
+        env.set_caller(liquidator);
+        m.pool.liquidate(borrower, U256::from(50), usdc.address());
+        assert_eq!(usdc.balance_of(liquidator), U256::from(525));
+    }
+
+    #[test]
+    fn test_apy_compounds_the_annual_rates() {
+        let env = odra_test::env();
+        let m = deploy_market(&env);
+
+        // 80% utilization sits at the kink: 4% borrow rate, compounding to ~4.08%
+        let borrow_rate = m.pool.get_borrow_rate();
+        assert_eq!(borrow_rate, U256::from(40_000_000_000_000_000u128));
+        let borrow_apy = m.pool.get_borrow_apy();
+        assert!(borrow_apy > U256::from(40_810_000_000_000_000u128));
+        assert!(borrow_apy < U256::from(40_820_000_000_000_000u128));
+        assert!(m.pool.get_supply_apy() > m.pool.get_supply_rate());
+    }
+
+    #[test]
+    fn test_indebted_borrower_cannot_drain_collateral() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+
+        // 800 debt needs at least 942 WETH at an 85% threshold
+        env.set_caller(borrower);
+        assert_eq!(
+            m.collateral_mgr.try_withdraw_collateral(m.weth.address(), U256::from(1_000)).unwrap_err(),
+            LendingError::CannotWithdrawCollateral.into()
+        );
+        assert_eq!(
+            m.collateral_mgr.try_withdraw_collateral(m.weth.address(), U256::from(100)).unwrap_err(),
+            LendingError::CannotWithdrawCollateral.into()
+        );
+
+        m.collateral_mgr.withdraw_collateral(m.weth.address(), U256::from(50));
+        assert_eq!(m.weth.balance_of(borrower), U256::from(50));
+
+        // Once repaid, everything can come out
+        m.ecto.approve(m.pool.address(), U256::from(800));
+        m.pool.repay(U256::from(800));
+        m.collateral_mgr.withdraw_collateral(m.weth.address(), U256::from(950));
+        assert_eq!(m.weth.balance_of(borrower), U256::from(1_000));
+    }
+
+    #[test]
+    fn test_borrower_registry_surfaces_unhealthy_positions() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+
+        assert_eq!(m.pool.get_borrower_count(), 1);
+        assert_eq!(m.pool.get_borrowers(0, 10), vec![borrower]);
+        assert!(m.pool.get_unhealthy_positions(0, 10).is_empty());
+
+        m.oracle.set_price(m.weth.address(), U256::from(900_000_000_000_000_000u128));
+        assert_eq!(m.pool.get_unhealthy_positions(0, 10), vec![borrower]);
+        assert!(m.pool.get_unhealthy_positions(1, 10).is_empty());
+
+        // Clearing the debt drops the borrower from the registry
+        env.set_caller(borrower);
+        m.ecto.approve(m.pool.address(), U256::from(800));
+        m.pool.repay(U256::from(800));
+        assert_eq!(m.pool.get_borrower_count(), 0);
+        assert!(m.pool.get_borrowers(0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_collateral_portfolio_lists_every_position() {
+        let env = odra_test::env();
+        let m = deploy_market(&env);
+        let borrower = env.get_account(2);
+
+        let portfolio = m.collateral_mgr.get_user_collateral_positions(borrower);
+        assert_eq!(portfolio.positions.len(), 1);
+        let weth = &portfolio.positions[0];
+        assert_eq!(weth.asset, m.weth.address());
+        assert_eq!(weth.amount, U256::from(1_000));
+        assert_eq!(weth.value, U256::from(1_000));
+        assert_eq!(weth.borrow_power, U256::from(800));
+        assert_eq!(weth.threshold_value, U256::from(850));
+        assert_eq!(portfolio.total_borrow_power, m.collateral_mgr.get_max_borrow_amount(borrower));
+        assert_eq!(
+            portfolio.total_threshold_value,
+            m.collateral_mgr.get_user_collateral_value_with_threshold(borrower)
+        );
+    }
+
+    #[test]
+    fn test_liquidation_pays_bounty_from_reserves() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let admin = env.get_account(0);
+        let borrower = env.get_account(2);
+        let liquidator = env.get_account(3);
+
+        m.pool.set_liquidation_bounty(m.weth.address(), U256::from(10));
+        m.ecto.mint(admin, U256::from(6));
+        m.ecto.approve(m.pool.address(), U256::from(6));
+        m.pool.add_reserves(U256::from(6));
+
+        m.oracle.set_price(m.weth.address(), U256::from(900_000_000_000_000_000u128));
+        m.ecto.mint(liquidator, U256::from(100));
+        env.set_caller(liquidator);
+        m.ecto.approve(m.pool.address(), U256::from(100));
+        m.pool.liquidate(borrower, U256::from(100), m.weth.address());
+
+        // The 10 ECTO bounty is capped at the 6 held in reserves
+        assert_eq!(m.ecto.balance_of(liquidator), U256::from(6));
+        assert_eq!(m.pool.get_total_reserves(), U256::zero());
+    }
+
+    #[test]
+    fn test_fee_collector_collects_reserves() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let admin = env.get_account(0);
+        let collector = env.get_account(5);
+
+        m.ecto.mint(admin, U256::from(50));
+        m.ecto.approve(m.pool.address(), U256::from(50));
+        m.pool.add_reserves(U256::from(50));
+        m.pool.set_fee_collector(collector);
+        assert_eq!(m.pool.try_collect_reserves(U256::from(30)).unwrap_err(), LendingError::Unauthorized.into());
+
+        env.set_caller(collector);
+        m.pool.collect_reserves(U256::from(30));
+        assert_eq!(m.ecto.balance_of(collector), U256::from(30));
+        assert_eq!(m.pool.get_total_reserves(), U256::from(20));
+        assert_eq!(m.pool.get_total_liquidity(), U256::from(220));
+        assert_eq!(
+            m.pool.try_collect_reserves(U256::from(21)).unwrap_err(),
+            LendingError::InsufficientLiquidity.into()
+        );
+    }
+
+    #[test]
+    fn test_insolvent_liquidation_realizes_bad_debt() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+        let liquidator = env.get_account(3);
+        let staker = env.get_account(4);
+
+        let mut safety_module = SafetyModule::deploy(&env, SafetyModuleInitArgs {
+            ecto_token_address: m.ecto.address(),
+            lending_pool_address: m.pool.address(),
+        });
+        m.pool.set_safety_module(safety_module.address());
+
+        m.ecto.mint(staker, U256::from(100));
+        env.set_caller(staker);
+        m.ecto.approve(safety_module.address(), U256::from(100));
+        safety_module.stake(U256::from(100));
+
+        // Collateral crashes to 300 ECTO of value against 800 of debt
+        env.set_caller(env.get_account(0));
+        m.oracle.set_price(m.weth.address(), U256::from(300_000_000_000_000_000u128));
+
+        m.ecto.mint(liquidator, U256::from(400));
+        env.set_caller(liquidator);
+        m.ecto.approve(m.pool.address(), U256::from(400));
+        m.pool.liquidate(borrower, U256::from(400), m.weth.address());
+
+        // All collateral goes for 300 / 1.05 = 286 ECTO, leaving 514 of bad debt:
+        // no reserves, 100 from the safety module, 414 absorbed by lenders
+        assert_eq!(m.weth.balance_of(liquidator), U256::from(1_000));
+        assert_eq!(m.ecto.balance_of(liquidator), U256::from(114));
+        let position = m.pool.get_borrow_position(borrower).unwrap();
+        assert_eq!(position.principal + position.interest_accrued, U256::zero());
+        assert_eq!(m.pool.get_total_borrows(), U256::zero());
+        assert_eq!(safety_module.get_total_staked(), U256::zero());
+        assert_eq!(safety_module.balance_of(staker), U256::zero());
+        assert_eq!(m.pool.get_total_liquidity(), U256::from(586));
+        assert_eq!(m.vault.get_total_assets(), U256::from(586));
+    }
+
+    #[test]
+    fn test_user_account_data_aggregates_position() {
+        let env = odra_test::env();
+        let m = deploy_market(&env);
+        let borrower = env.get_account(2);
+
+        let data = m.pool.get_user_account_data(borrower);
+        assert_eq!(data.total_collateral_value, U256::from(1_000));
+        assert_eq!(data.total_debt, U256::from(800));
+        assert_eq!(data.available_borrow, U256::zero());
+        assert_eq!(data.current_liquidation_threshold, U256::from(850_000_000_000_000_000u128));
+        assert_eq!(data.ltv, U256::from(800_000_000_000_000_000u128));
+        // 1000 * 0.85 / 800
+        assert_eq!(data.health_factor, U256::from(1_062_500_000_000_000_000u128));
+
+        let lender_data = m.pool.get_user_account_data(env.get_account(1));
+        assert_eq!(lender_data.total_debt, U256::zero());
+        assert_eq!(lender_data.health_factor, U256::MAX);
+        assert_eq!(lender_data.ltv, U256::zero());
+    }
+
+    #[test]
+    fn test_pledged_aecto_blocks_unhealthy_transfers() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let lender = env.get_account(1);
+        let recipient = env.get_account(5);
+
+        // Register aECTO as in-place collateral priced at 1 ECTO
+        m.collateral_mgr.add_collateral(
+            m.vault.address(),
+            U256::from(800_000_000_000_000_000u128),
+            U256::from(850_000_000_000_000_000u128),
+            U256::from(50_000_000_000_000_000u128),
+        );
+        m.oracle.set_price(m.vault.address(), U256::from(1_000_000_000_000_000_000u128));
+        m.collateral_mgr.set_aecto_vault(m.vault.address());
+        m.vault.set_collateral_manager(m.collateral_mgr.address());
+
+        // Lender loops: pledges 1000 aECTO and borrows 150 ECTO against it
+        env.set_caller(lender);
+        m.vault.pledge_as_collateral(true);
+        assert_eq!(m.collateral_mgr.get_user_collateral(lender, m.vault.address()), U256::from(1_000));
+        m.pool.borrow(U256::from(150), m.vault.address());
+
+        // 100 * 0.85 < 150: would leave the position unhealthy
+        assert_eq!(
+            m.vault.try_transfer(recipient, U256::from(900)).unwrap_err(),
+            LendingError::HealthFactorTooLow.into()
+        );
+        assert_eq!(
+            m.vault.try_pledge_as_collateral(false).unwrap_err(),
+            LendingError::HealthFactorTooLow.into()
+        );
+
+        // 500 * 0.85 >= 150 stays healthy
+        m.vault.transfer(recipient, U256::from(500));
+        assert_eq!(m.vault.balance_of(recipient), U256::from(500));
+        assert_eq!(m.collateral_mgr.get_user_collateral(lender, m.vault.address()), U256::from(500));
+    }
+
+    #[test]
+    fn test_stale_price_blocks_borrow_but_not_repay() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+
+        m.oracle.set_heartbeat(m.weth.address(), 600);
+        env.advance_block_time(601);
+
+        env.set_caller(borrower);
+        assert_eq!(
+            m.pool.try_borrow(U256::from(10), m.weth.address()).unwrap_err(),
+            LendingError::StalePrice.into()
+        );
+
+        m.ecto.approve(m.pool.address(), U256::from(100));
+        m.pool.repay(U256::from(100));
+        let position = m.pool.get_borrow_position(borrower).unwrap();
+        assert_eq!(position.principal + position.interest_accrued, U256::from(700));
+    }
+
+    #[test]
+    fn test_third_party_repays_a_borrowers_debt() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+        let payer = env.get_account(5);
+
+        m.ecto.mint(payer, U256::from(300));
+        env.set_caller(payer);
+        m.ecto.approve(m.pool.address(), U256::from(300));
+        m.pool.repay_for(borrower, U256::from(300));
+
+        // The payer's ECTO reduces the borrower's debt; the borrowed ECTO stays put
+        let position = m.pool.get_borrow_position(borrower).unwrap();
+        assert_eq!(position.principal + position.interest_accrued, U256::from(500));
+        assert_eq!(m.debt.balance_of(borrower), U256::from(500));
+        assert_eq!(m.ecto.balance_of(payer), U256::zero());
+        assert_eq!(m.ecto.balance_of(borrower), U256::from(800));
+        assert_eq!(m.pool.get_total_liquidity(), U256::from(500));
+        assert!(env.emitted_event(&m.pool, Repaid {
+            borrower,
+            repaid_by: payer,
+            amount: U256::from(300),
+            interest: U256::zero(),
+            timestamp: env.block_time(),
+        }));
+    }
+
+    #[test]
+    fn test_repay_with_collateral_respects_pause_and_stale_prices() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+        let weth = m.weth.address();
+
+        m.pool.set_action_paused(PauseAction::Liquidation, true);
+        env.set_caller(borrower);
+        assert_eq!(
+            m.pool.try_repay_with_collateral(weth, U256::from(200), U256::from(100)).unwrap_err(),
+            LendingError::ContractPaused.into()
+        );
+
+        env.set_caller(env.get_account(0));
+        m.pool.set_action_paused(PauseAction::Liquidation, false);
+        m.oracle.set_heartbeat(weth, 600);
+        env.advance_block_time(601);
+        env.set_caller(borrower);
+        assert_eq!(
+            m.pool.try_repay_with_collateral(weth, U256::from(200), U256::from(100)).unwrap_err(),
+            LendingError::StalePrice.into()
+        );
+
+        // With a fresh price, the missing DEX router is what stops it
+        env.set_caller(env.get_account(0));
+        m.oracle.set_price(weth, U256::from(1_000_000_000_000_000_000u128));
+        env.set_caller(borrower);
+        assert_eq!(
+            m.pool.try_repay_with_collateral(weth, U256::from(200), U256::from(100)).unwrap_err(),
+            LendingError::InvalidConfiguration.into()
+        );
+    }
+
+    #[test]
+    fn test_liquidations_wait_out_grace_period_after_outage() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+        let liquidator = env.get_account(3);
+
+        m.pool.set_liquidation_grace_period(300);
+        m.oracle.set_heartbeat(m.weth.address(), 600);
+        env.advance_block_time(601);
+
+        // The price comes back below the liquidation threshold
+        m.oracle.set_price(m.weth.address(), U256::from(900_000_000_000_000_000u128));
+        assert_eq!(m.oracle.get_recovered_at(m.weth.address()), env.block_time());
+
+        m.ecto.mint(liquidator, U256::from(100));
+        env.set_caller(liquidator);
+        m.ecto.approve(m.pool.address(), U256::from(100));
+        assert_eq!(
+            m.pool.try_liquidate(borrower, U256::from(100), m.weth.address()).unwrap_err(),
+            LendingError::LiquidationGracePeriod.into()
+        );
+
+        // Unpausing liquidations restarts the window
+        env.advance_block_time(200);
+        env.set_caller(env.get_account(0));
+        m.pool.set_action_paused(PauseAction::Liquidation, true);
+        m.pool.set_action_paused(PauseAction::Liquidation, false);
+        env.advance_block_time(200);
+        env.set_caller(liquidator);
+        assert_eq!(
+            m.pool.try_liquidate(borrower, U256::from(100), m.weth.address()).unwrap_err(),
+            LendingError::LiquidationGracePeriod.into()
+        );
+
+        env.advance_block_time(100);
+        m.pool.liquidate(borrower, U256::from(100), m.weth.address());
+    }
+
+    #[test]
+    fn test_only_approved_delegates_deleverage_a_borrower() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+        let delegate = env.get_account(5);
+
+        env.set_caller(delegate);
+        assert_eq!(
+            m.pool.try_deleverage_for(borrower, m.weth.address(), U256::from(100), U256::zero()).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+
+        env.set_caller(borrower);
+        m.pool.set_deleverage_delegate(delegate, true);
+        assert!(m.pool.is_deleverage_delegate(borrower, delegate));
+
+        // Past the approval check, the missing DEX router is what stops it
+        env.set_caller(delegate);
+        assert_eq!(
+            m.pool.try_deleverage_for(borrower, m.weth.address(), U256::from(100), U256::zero()).unwrap_err(),
+            LendingError::InvalidConfiguration.into()
+        );
+    }
+
+    #[test]
+    fn test_withdrawals_capped_per_block_at_high_utilization() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let lender = env.get_account(1);
+
+        // 800 of 1000 borrowed is 80% utilization, above the 70% threshold
+        m.pool.set_withdrawal_limit(U256::from(700_000_000_000_000_000u128), U256::from(50));
+        assert_eq!(m.vault.max_withdraw(lender), U256::from(50));
+
+        env.set_caller(lender);
+        m.vault.withdraw(U256::from(30), lender, lender);
+        assert_eq!(m.vault.max_withdraw(lender), U256::from(20));
+        assert_eq!(
+            m.vault.try_withdraw(U256::from(30), lender, lender).unwrap_err(),
+            LendingError::WithdrawalLimitExceeded.into()
+        );
+
+        // The cap resets with the next block
+        env.advance_block_time(1);
+        m.vault.withdraw(U256::from(30), lender, lender);
+        assert_eq!(m.ecto.balance_of(lender), U256::from(60));
+    }
+
+    #[test]
+    fn test_vault_fees_are_previewed_and_paid_to_recipient() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let lender = env.get_account(1);
+        let depositor = env.get_account(3);
+        let treasury = env.get_account(6);
+
+        env.set_caller(lender);
+        assert_eq!(
+            m.vault.try_set_fees(100, 100, treasury).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+        env.set_caller(env.get_account(0));
+        assert_eq!(
+            m.vault.try_set_fees(1_001, 100, treasury).unwrap_err(),
+            LendingError::InvalidConfiguration.into()
+        );
+        m.vault.set_fees(100, 100, treasury); // 1% in, 1% out
+
+        // 10 of the 1010 deposited is the entry fee
+        m.ecto.mint(depositor, U256::from(1_010));
+        env.set_caller(depositor);
+        m.ecto.approve(m.vault.address(), U256::from(1_010));
+        assert_eq!(m.vault.preview_deposit(U256::from(1_010)), U256::from(1_000));
+        assert_eq!(m.vault.deposit(U256::from(1_010), depositor), U256::from(1_000));
+        assert_eq!(m.ecto.balance_of(treasury), U256::from(10));
+
+        // Withdrawing 100 burns the shares for 101 and pays 1 to the treasury
+        env.set_caller(lender);
+        assert_eq!(m.vault.preview_withdraw(U256::from(100)), U256::from(101));
+        assert_eq!(m.vault.withdraw(U256::from(100), lender, lender), U256::from(101));
+        assert_eq!(m.ecto.balance_of(lender), U256::from(100));
+        assert_eq!(m.ecto.balance_of(treasury), U256::from(11));
+    }
+
+    /// aECTO driven through the shared CEP-4626 compliance checks
+    struct AectoUnderTest<'a> {
+        env: &'a HostEnv,
+        market: &'a mut Market,
+    }
+
+    impl AectoUnderTest<'_> {
+        /// Mint `amount` ECTO to `user` and approve the vault to pull it
+        fn fund(&mut self, user: Address, amount: U256) {
+            self.env.set_caller(self.env.get_account(0));
+            self.market.ecto.mint(user, amount);
+            self.env.set_caller(user);
+            let vault = self.market.vault.address();
+            self.market.ecto.approve(vault, amount);
+        }
+    }
+
+    impl VaultUnderTest for AectoUnderTest<'_> {
+        fn total_assets(&self) -> U256 {
+            self.market.vault.total_assets()
+        }
+
+        fn total_supply(&self) -> U256 {
+            self.market.vault.total_supply()
+        }
+
+        fn share_balance(&self, owner: Address) -> U256 {
+            self.market.vault.balance_of(owner)
+        }
+
+        fn convert_to_shares(&self, assets: U256) -> U256 {
+            self.market.vault.convert_to_shares(assets)
+        }
+
+        fn convert_to_assets(&self, shares: U256) -> U256 {
+            self.market.vault.convert_to_assets(shares)
+        }
+
+        fn max_deposit(&self, receiver: Address) -> U256 {
+            self.market.vault.max_deposit(receiver)
+        }
+
+        fn max_mint(&self, receiver: Address) -> U256 {
+            self.market.vault.max_mint(receiver)
+        }
+
+        fn max_withdraw(&self, owner: Address) -> U256 {
+            self.market.vault.max_withdraw(owner)
+        }
+
+        fn max_redeem(&self, owner: Address) -> U256 {
+            self.market.vault.max_redeem(owner)
+        }
+
+        fn preview_deposit(&self, assets: U256) -> U256 {
+            self.market.vault.preview_deposit(assets)
+        }
+
+        fn preview_mint(&self, shares: U256) -> U256 {
+            self.market.vault.preview_mint(shares)
+        }
+
+        fn preview_withdraw(&self, assets: U256) -> U256 {
+            self.market.vault.preview_withdraw(assets)
+        }
+
+        fn preview_redeem(&self, shares: U256) -> U256 {
+            self.market.vault.preview_redeem(shares)
+        }
+
+        fn deposit(&mut self, user: Address, assets: U256) -> U256 {
+            self.fund(user, assets);
+            self.market.vault.deposit(assets, user)
+        }
+
+        fn mint(&mut self, user: Address, shares: U256) -> U256 {
+            let cost = self.market.vault.preview_mint(shares);
+            self.fund(user, cost);
+            self.market.vault.mint(shares, user)
+        }
+
+        fn withdraw(&mut self, user: Address, assets: U256) -> U256 {
+            self.env.set_caller(user);
+            self.market.vault.withdraw(assets, user, user)
+        }
+
+        fn redeem(&mut self, user: Address, shares: U256) -> U256 {
+            self.env.set_caller(user);
+            self.market.vault.redeem(shares, user, user)
+        }
+    }
+
+    #[test]
+    fn test_aecto_vault_is_cep4626_compliant() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+
+        let user = env.get_account(3);
+        check_compliance(&mut AectoUnderTest { env: &env, market: &mut m }, user, U256::from(10_007));
+    }
+
+    #[test]
+    fn test_vault_emits_share_price_on_deposit_and_withdraw() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let lender = env.get_account(1);
+        let one = U256::from(1_000_000_000_000_000_000u128);
+
+        // deploy_market's deposit left 1000 ECTO backing 1000 aECTO
+        assert!(env.emitted_event(&m.vault, SharePriceUpdated {
+            total_assets: U256::from(1_000),
+            total_shares: U256::from(1_000),
+            price: one,
+        }));
+
+        env.set_caller(lender);
+        m.vault.withdraw(U256::from(100), lender, lender);
+        assert!(env.emitted_event(&m.vault, SharePriceUpdated {
+            total_assets: U256::from(900),
+            total_shares: U256::from(900),
+            price: one,
+        }));
+    }
+
+    #[test]
+    fn test_vault_flows_check_shares_and_spend_allowances() {
+        use crate::cep4626::Withdraw;
+
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let lender = env.get_account(1);
+        let (spender, receiver) = (env.get_account(4), env.get_account(5));
+
+        // A deposit that mints no shares is rejected
+        env.set_caller(lender);
+        assert_eq!(
+            m.vault.try_deposit(U256::zero(), lender).unwrap_err(),
+            LendingError::InsufficientBalance.into()
+        );
+
+        // Redeeming for someone else needs an allowance, which is spent
+        env.set_caller(spender);
+        assert_eq!(
+            m.vault.try_redeem(U256::from(100), receiver, lender).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+        env.set_caller(lender);
+        m.vault.approve(spender, U256::from(100));
+        env.set_caller(spender);
+        assert_eq!(m.vault.redeem(U256::from(100), receiver, lender), U256::from(100));
+        assert!(env.emitted_event(&m.vault, Withdraw {
+            sender: spender,
+            receiver,
+            owner: lender,
+            assets: U256::from(100),
+            shares: U256::from(100),
+        }));
+        assert_eq!(m.vault.allowance(lender, spender), U256::zero());
+        assert_eq!(m.vault.balance_of(lender), U256::from(900));
+        assert_eq!(m.ecto.balance_of(receiver), U256::from(100));
+
+        // Owners cannot burn more shares than they hold
+        env.set_caller(lender);
+        assert_eq!(
+            m.vault.try_redeem(U256::from(901), lender, lender).unwrap_err(),
+            LendingError::InsufficientBalance.into()
+        );
+    }
+
+    #[test]
+    fn test_vault_deposit_limits_follow_supply_cap_and_pause() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let depositor = env.get_account(3);
+        assert_eq!(m.vault.max_deposit(depositor), U256::MAX);
+
+        // 1000 ECTO is already supplied
+        m.pool.set_supply_cap(U256::from(1_200));
+        assert_eq!(m.vault.max_deposit(depositor), U256::from(200));
+        assert_eq!(m.vault.max_mint(depositor), U256::from(200));
+
+        m.ecto.mint(depositor, U256::from(300));
+        env.set_caller(depositor);
+        m.ecto.approve(m.vault.address(), U256::from(300));
+        assert_eq!(
+            m.vault.try_deposit(U256::from(201), depositor).unwrap_err(),
+            LendingError::SupplyCapExceeded.into()
+        );
+        m.vault.deposit(U256::from(200), depositor);
+        assert_eq!(m.vault.max_deposit(depositor), U256::zero());
+
+        env.set_caller(env.get_account(0));
+        m.pool.set_supply_cap(U256::zero());
+        m.pool.set_action_paused(PauseAction::Deposit, true);
+        assert_eq!(m.vault.max_deposit(depositor), U256::zero());
+        assert_eq!(m.vault.max_mint(depositor), U256::zero());
+    }
+
+    #[test]
+    fn test_guardian_pauses_borrow_without_blocking_exits() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let lender = env.get_account(1);
+        let borrower = env.get_account(2);
+        let guardian = env.get_account(5);
+
+        env.set_caller(guardian);
+        assert_eq!(
+            m.pool.try_set_action_paused(PauseAction::Borrow, true).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+
+        env.set_caller(env.get_account(0));
+        m.pool.grant_role(GUARDIAN_ROLE, guardian);
+        env.set_caller(guardian);
+        m.pool.set_action_paused(PauseAction::Borrow, true);
+        assert!(m.pool.is_action_paused(PauseAction::Borrow));
+        assert!(!m.pool.is_action_paused(PauseAction::Deposit));
+
+        env.set_caller(borrower);
+        assert_eq!(
+            m.pool.try_borrow(U256::from(10), m.weth.address()).unwrap_err(),
+            LendingError::ContractPaused.into()
+        );
+        m.ecto.approve(m.pool.address(), U256::from(100));
+        m.pool.repay(U256::from(100));
+
+        env.set_caller(lender);
+        m.vault.withdraw(U256::from(100), lender, lender);
+        assert_eq!(m.ecto.balance_of(lender), U256::from(100));
+    }
+
+    #[test]
+    fn test_debt_token_tracks_borrows_and_repayments() {
+        let env = odra_test::env();
+        let mut m = deploy_market(&env);
+        let borrower = env.get_account(2);
+        let recipient = env.get_account(5);
+
+        assert_eq!(m.debt.balance_of(borrower), U256::from(800));
+        assert_eq!(m.debt.total_supply(), m.pool.get_total_borrows());
+
+        env.set_caller(borrower);
+        m.ecto.approve(m.pool.address(), U256::from(300));
+        m.pool.repay(U256::from(300));
+        assert_eq!(m.debt.balance_of(borrower), U256::from(500));
+
+        assert_eq!(
+            m.debt.try_transfer(recipient, U256::from(100)).unwrap_err(),
+            LendingError::NonTransferable.into()
+        );
+        assert_eq!(
+            m.debt.try_mint(borrower, U256::from(100)).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn test_seize_only_callable_by_lending_pool() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let attacker = env.get_account(1);
+
+        env.set_caller(admin);
+        let oracle = PriceOracle::deploy(&env, NoArgs);
+        let mut collateral_mgr = CollateralManager::deploy(&env, CollateralManagerInitArgs {
+            price_oracle_address: oracle.address(),
+        });
+        collateral_mgr.set_lending_pool(env.get_account(9));
+
+        env.set_caller(attacker);
+        assert_eq!(
+            collateral_mgr
+                .try_seize(admin, oracle.address(), U256::one(), attacker)
+                .unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
     }
 }
@@ -7,6 +7,8 @@ use odra::prelude::*;
 use odra::casper_types::U256;
 use super::errors::LendingError;
 use super::events::*;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+use crate::math::{percent_div, percent_mul};
 
 /// Liquidation parameters
 #[odra::odra_type]
@@ -24,10 +26,13 @@ pub struct LiquidationParams {
 pub struct LiquidationEngine {
     /// Liquidation parameters
     params: Var<LiquidationParams>,
-    /// Admin address
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     /// Scale factor (1e18)
     scale: Var<U256>,
+    /// Debt (in ECTO) at or below which a position may be closed in full,
+    /// so tiny positions are not left behind by the close factor
+    dust_threshold: Var<U256>,
 }
 
 #[odra::module]
@@ -35,7 +40,7 @@ impl LiquidationEngine {
     /// Initialize the liquidation engine
     pub fn init(&mut self) {
         let caller = self.env().caller();
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
         self.scale.set(U256::from(1_000_000_000_000_000_000u128)); // 1e18
         
         // Default parameters
@@ -44,6 +49,7 @@ impl LiquidationEngine {
             liquidation_threshold: U256::from(1_000_000_000_000_000_000u128), // 1.0
         };
         self.params.set(params);
+        self.dust_threshold.set(U256::zero());
     }
     
     /// Calculate liquidation amounts
@@ -55,7 +61,8 @@ impl LiquidationEngine {
     /// * `liquidation_bonus` - Bonus percentage (scaled by 1e18)
     /// 
     /// # Returns
-    /// (actual_debt_to_cover, collateral_to_seize)
+    /// (actual_debt_to_cover, collateral_to_seize), where collateral_to_seize
+    /// is capped at `collateral_value`
     pub fn calculate_liquidation_amounts(
         &self,
         debt_to_cover: U256,
@@ -66,8 +73,7 @@ impl LiquidationEngine {
         let params = self.params.get_or_revert_with(LendingError::InvalidConfiguration);
         let scale = self.scale.get_or_default();
         
-        // Calculate maximum debt that can be covered (close factor)
-        let max_debt_to_cover = (total_debt * params.max_liquidation_close_factor) / scale;
+        let max_debt_to_cover = self.max_debt_to_cover(total_debt, &params, scale);
         
         // Actual debt to cover is minimum of requested and maximum
         let actual_debt = if debt_to_cover > max_debt_to_cover {
@@ -79,11 +85,15 @@ impl LiquidationEngine {
         // Calculate collateral to seize with bonus
         // collateral_to_seize = debt_to_cover * (1 + liquidation_bonus)
         let bonus_multiplier = scale + liquidation_bonus;
-        let collateral_to_seize = (actual_debt * bonus_multiplier) / scale;
+        let collateral_to_seize = percent_mul(actual_debt, bonus_multiplier)
+            .unwrap_or_else(|_| self.env().revert(LendingError::LiquidationBonusFailed));
         
-        // Check if there's enough collateral
+        // Not enough collateral: seize all of it and cover only what it pays for,
+        // leaving the rest of the debt to be realized as bad debt
         if collateral_to_seize > collateral_value {
-            self.env().revert(LendingError::InsufficientCollateralForLiquidation);
+            let covered_debt = percent_div(collateral_value, bonus_multiplier)
+                .unwrap_or_else(|_| self.env().revert(LendingError::LiquidationBonusFailed));
+            return (covered_debt, collateral_value);
         }
         
         (actual_debt, collateral_to_seize)
@@ -101,21 +111,38 @@ impl LiquidationEngine {
         health_factor < params.liquidation_threshold
     }
     
+    /// Get the maximum debt a single liquidation call may repay
+    pub fn get_max_liquidatable_debt(&self, total_debt: U256) -> U256 {
+        let params = self.params.get_or_revert_with(LendingError::InvalidConfiguration);
+        self.max_debt_to_cover(total_debt, &params, self.scale.get_or_default())
+    }
+    
     /// Get liquidation parameters
     pub fn get_params(&self) -> LiquidationParams {
         self.params.get_or_revert_with(LendingError::InvalidConfiguration)
     }
     
+    /// Get the dust threshold below which positions may be fully liquidated
+    pub fn get_dust_threshold(&self) -> U256 {
+        self.dust_threshold.get_or_default()
+    }
+    
+    /// Set the dust threshold below which positions may be fully liquidated (admin only)
+    pub fn set_dust_threshold(&mut self, dust_threshold: U256) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.dust_threshold.set(dust_threshold);
+    }
+    
     /// Update liquidation parameters (admin only)
     pub fn update_params(
         &mut self,
         max_liquidation_close_factor: U256,
         liquidation_threshold: U256,
     ) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
         
         let scale = self.scale.get_or_default();
-        if max_liquidation_close_factor > scale {
+        if max_liquidation_close_factor == U256::zero() || max_liquidation_close_factor > scale {
             self.env().revert(LendingError::InvalidConfiguration);
         }
         
@@ -126,11 +153,73 @@ impl LiquidationEngine {
         self.params.set(params);
     }
     
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
-        if caller != admin {
-            self.env().revert(LendingError::Unauthorized);
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    /// Maximum debt coverable in one call: the close factor share of the debt,
+    /// or the whole debt once it is at or below the dust threshold
+    fn max_debt_to_cover(&self, total_debt: U256, params: &LiquidationParams, scale: U256) -> U256 {
+        if total_debt <= self.dust_threshold.get_or_default() {
+            return total_debt;
         }
+        (total_debt * params.max_liquidation_close_factor) / scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::host::{Deployer, NoArgs};
+
+    #[test]
+    fn test_close_factor_caps_debt_per_call() {
+        let env = odra_test::env();
+        let engine = LiquidationEngine::deploy(&env, NoArgs);
+
+        // Default close factor is 50%
+        let (debt, seize) = engine.calculate_liquidation_amounts(
+            U256::from(1_000),
+            U256::from(1_000),
+            U256::from(10_000),
+            U256::from(50_000_000_000_000_000u128), // 5% bonus
+        );
+        assert_eq!(debt, U256::from(500));
+        assert_eq!(seize, U256::from(525));
+    }
+
+    #[test]
+    fn test_dust_positions_can_be_closed_in_full() {
+        let env = odra_test::env();
+        let mut engine = LiquidationEngine::deploy(&env, NoArgs);
+        engine.set_dust_threshold(U256::from(100));
+
+        assert_eq!(engine.get_max_liquidatable_debt(U256::from(100)), U256::from(100));
+        assert_eq!(engine.get_max_liquidatable_debt(U256::from(101)), U256::from(50));
+
+        let (debt, _) = engine.calculate_liquidation_amounts(
+            U256::from(100),
+            U256::from(100),
+            U256::from(10_000),
+            U256::zero(),
+        );
+        assert_eq!(debt, U256::from(100));
+    }
+
+    #[test]
+    fn test_only_admin_sets_dust_threshold() {
+        let env = odra_test::env();
+        let mut engine = LiquidationEngine::deploy(&env, NoArgs);
+
+        env.set_caller(env.get_account(1));
+        assert_eq!(
+            engine.try_set_dust_threshold(U256::from(100)).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
     }
 }
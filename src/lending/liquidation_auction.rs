@@ -0,0 +1,534 @@
+//! Liquidation Auction - Dutch auction path for undercollateralized positions
+//!
+//! Instead of paying a fixed liquidation bonus, the borrower's collateral is
+//! offered at a price that starts above the oracle price and declines linearly
+//! to a floor. Keepers take any portion of the lot once the price suits them,
+//! so collateral clears at the market discount rather than a preset one.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::LendingError;
+use super::events::*;
+use super::collateral_manager::CollateralManagerContractRef;
+use super::lending_pool::LendingPoolContractRef;
+use super::price_oracle::PriceOracleContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// A collateral auction for one borrower and asset
+#[odra::odra_type]
+pub struct Auction {
+    /// Auction id
+    pub id: u64,
+    /// Borrower whose collateral is auctioned
+    pub borrower: Address,
+    /// Collateral asset being sold
+    pub collateral_asset: Address,
+    /// Collateral still for sale
+    pub collateral_remaining: U256,
+    /// ECTO debt still to be raised
+    pub debt_remaining: U256,
+    /// Starting price in ECTO per collateral unit (scaled by 1e18)
+    pub start_price: U256,
+    /// Floor price in ECTO per collateral unit (scaled by 1e18)
+    pub floor_price: U256,
+    /// Auction start timestamp
+    pub start_time: u64,
+    /// Seconds for the price to decline from start to floor
+    pub duration: u64,
+    /// Whether the auction can still be taken
+    pub is_active: bool,
+}
+
+/// Liquidation Auction contract
+#[odra::module]
+pub struct LiquidationAuction {
+    /// Lending pool address
+    lending_pool: Var<Address>,
+    /// Collateral manager address
+    collateral_manager: Var<Address>,
+    /// Price oracle address
+    price_oracle: Var<Address>,
+    /// Auctions by id
+    auctions: Mapping<u64, Auction>,
+    /// Active auction id per (borrower, asset), 0 when none
+    active_auctions: Mapping<(Address, Address), u64>,
+    /// Next auction id (ids start at 1)
+    next_auction_id: Var<u64>,
+    /// Starting price as a multiple of the oracle price (scaled by 1e18)
+    /// Example: 105% = 1.05 * 1e18
+    start_premium: Var<U256>,
+    /// Floor price as a multiple of the oracle price (scaled by 1e18)
+    /// Example: 80% = 0.80 * 1e18
+    floor_ratio: Var<U256>,
+    /// Seconds for the price to decline from start to floor
+    duration: Var<u64>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Scale factor (1e18)
+    scale: Var<U256>,
+}
+
+#[odra::module]
+impl LiquidationAuction {
+    /// Initialize the liquidation auction
+    pub fn init(
+        &mut self,
+        lending_pool_address: Address,
+        collateral_manager_address: Address,
+        price_oracle_address: Address,
+    ) {
+        let caller = self.env().caller();
+        self.access.grant(ADMIN_ROLE, caller);
+
+        self.lending_pool.set(lending_pool_address);
+        self.collateral_manager.set(collateral_manager_address);
+        self.price_oracle.set(price_oracle_address);
+        self.next_auction_id.set(1);
+
+        self.scale.set(U256::from(1_000_000_000_000_000_000u128)); // 1e18
+        self.start_premium.set(U256::from(1_050_000_000_000_000_000u128)); // 105%
+        self.floor_ratio.set(U256::from(800_000_000_000_000_000u128)); // 80%
+        self.duration.set(3600); // 1 hour
+    }
+
+    // ========================================
+    // Auctions
+    // ========================================
+
+    /// Start an auction for a liquidatable borrower's collateral
+    ///
+    /// # Returns
+    /// The auction id
+    pub fn start_auction(&mut self, borrower: Address, collateral_asset: Address) -> u64 {
+        if self.active_auctions.get(&(borrower, collateral_asset)).unwrap_or(0) != 0 {
+            self.env().revert(LendingError::AuctionAlreadyActive);
+        }
+
+        let pool = LendingPoolContractRef::new(self.env(), self.lending_pool.get_or_revert_with(LendingError::InvalidConfiguration));
+        let position = pool.get_borrow_position(borrower)
+            .unwrap_or_revert_with(&self.env(), LendingError::NoBorrowPosition);
+        let total_debt = position.principal + position.interest_accrued;
+        if total_debt == U256::zero() {
+            self.env().revert(LendingError::NoBorrowPosition);
+        }
+
+        let collateral_mgr = self.collateral_manager_ref();
+        if !collateral_mgr.can_liquidate(borrower, total_debt) {
+            self.env().revert(LendingError::PositionHealthy);
+        }
+
+        let collateral = collateral_mgr.get_user_collateral(borrower, collateral_asset);
+        if collateral == U256::zero() {
+            self.env().revert(LendingError::InsufficientCollateralForLiquidation);
+        }
+
+        let oracle_address = self.price_oracle.get_or_revert_with(LendingError::InvalidConfiguration);
+        let oracle_price = PriceOracleContractRef::new(self.env(), oracle_address).get_price(collateral_asset);
+        let scale = self.scale.get_or_default();
+        let start_price = (oracle_price * self.start_premium.get_or_default()) / scale;
+        let floor_price = (oracle_price * self.floor_ratio.get_or_default()) / scale;
+
+        let id = self.next_auction_id.get_or_default();
+        self.next_auction_id.set(id + 1);
+
+        let start_time = self.env().get_block_time();
+        let duration = self.duration.get_or_default();
+        self.auctions.set(&id, Auction {
+            id,
+            borrower,
+            collateral_asset,
+            collateral_remaining: collateral,
+            debt_remaining: total_debt,
+            start_price,
+            floor_price,
+            start_time,
+            duration,
+            is_active: true,
+        });
+        self.active_auctions.set(&(borrower, collateral_asset), id);
+
+        self.env().emit_event(AuctionStarted {
+            auction_id: id,
+            borrower,
+            collateral_asset,
+            collateral_amount: collateral,
+            debt_amount: total_debt,
+            start_price,
+            floor_price,
+            timestamp: start_time,
+        });
+
+        id
+    }
+
+    /// Buy up to `max_collateral` from an auction at the current price
+    /// The caller pays ECTO (approved to the lending pool) towards the
+    /// borrower's debt and receives the collateral
+    ///
+    /// # Arguments
+    /// * `auction_id` - Auction to take from
+    /// * `max_collateral` - Maximum collateral to buy
+    /// * `max_price` - Maximum acceptable price (ECTO per collateral unit, scaled by 1e18)
+    ///
+    /// # Returns
+    /// (collateral_bought, ecto_paid)
+    pub fn take(&mut self, auction_id: u64, max_collateral: U256, max_price: U256) -> (U256, U256) {
+        let mut auction = self.auctions.get(&auction_id)
+            .unwrap_or_revert_with(&self.env(), LendingError::AuctionNotFound);
+        if !auction.is_active {
+            self.env().revert(LendingError::AuctionNotActive);
+        }
+        if max_collateral == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+
+        let price = self.price_at(&auction, self.env().get_block_time());
+        if price > max_price {
+            self.env().revert(LendingError::SlippageExceeded);
+        }
+
+        // Only a still-liquidatable borrower can be bought out, and each take
+        // repays at most the close factor share of the current debt
+        let pool_address = self.lending_pool.get_or_revert_with(LendingError::InvalidConfiguration);
+        let mut pool = LendingPoolContractRef::new(self.env(), pool_address);
+        let max_debt = pool.get_max_liquidatable_debt(auction.borrower);
+        if max_debt == U256::zero() {
+            self.env().revert(LendingError::PositionHealthy);
+        }
+        let debt_limit = if max_debt < auction.debt_remaining { max_debt } else { auction.debt_remaining };
+
+        // Buy the requested amount, but never raise more than the debt limit
+        let scale = self.scale.get_or_default();
+        let mut collateral = if max_collateral > auction.collateral_remaining {
+            auction.collateral_remaining
+        } else {
+            max_collateral
+        };
+        let mut payment = (collateral * price) / scale;
+        if payment > debt_limit {
+            payment = debt_limit;
+            collateral = (payment * scale) / price;
+        }
+        if payment == U256::zero() || collateral == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+
+        let taker = self.env().caller();
+        pool.settle_auction_take(taker, auction.borrower, auction.collateral_asset, payment, collateral);
+
+        auction.collateral_remaining = auction.collateral_remaining - collateral;
+        auction.debt_remaining = auction.debt_remaining - payment;
+
+        let timestamp = self.env().get_block_time();
+        self.env().emit_event(AuctionTaken {
+            auction_id,
+            taker,
+            collateral_amount: collateral,
+            ecto_paid: payment,
+            price,
+            timestamp,
+        });
+
+        if auction.collateral_remaining == U256::zero() || auction.debt_remaining == U256::zero() {
+            self.close_auction(&mut auction);
+        }
+        self.auctions.set(&auction_id, auction);
+
+        (collateral, payment)
+    }
+
+    /// Close an auction whose borrower is no longer liquidatable
+    pub fn cancel_auction(&mut self, auction_id: u64) {
+        let mut auction = self.auctions.get(&auction_id)
+            .unwrap_or_revert_with(&self.env(), LendingError::AuctionNotFound);
+        if !auction.is_active {
+            self.env().revert(LendingError::AuctionNotActive);
+        }
+
+        let pool = LendingPoolContractRef::new(self.env(), self.lending_pool.get_or_revert_with(LendingError::InvalidConfiguration));
+        let debt = pool.get_borrow_position(auction.borrower)
+            .map(|position| position.principal + position.interest_accrued)
+            .unwrap_or_default();
+        if self.collateral_manager_ref().can_liquidate(auction.borrower, debt) {
+            self.env().revert(LendingError::OperationNotAllowed);
+        }
+
+        self.close_auction(&mut auction);
+        self.auctions.set(&auction_id, auction);
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get an auction
+    pub fn get_auction(&self, auction_id: u64) -> Option<Auction> {
+        self.auctions.get(&auction_id)
+    }
+
+    /// Get the active auction id for a borrower and asset (0 when none)
+    pub fn get_active_auction(&self, borrower: Address, collateral_asset: Address) -> u64 {
+        self.active_auctions.get(&(borrower, collateral_asset)).unwrap_or(0)
+    }
+
+    /// Get the current price of an auction (ECTO per collateral unit, scaled by 1e18)
+    pub fn get_current_price(&self, auction_id: u64) -> U256 {
+        let auction = self.auctions.get(&auction_id)
+            .unwrap_or_revert_with(&self.env(), LendingError::AuctionNotFound);
+        self.price_at(&auction, self.env().get_block_time())
+    }
+
+    /// Get auction parameters (start_premium, floor_ratio, duration)
+    pub fn get_auction_params(&self) -> (U256, U256, u64) {
+        (
+            self.start_premium.get_or_default(),
+            self.floor_ratio.get_or_default(),
+            self.duration.get_or_default(),
+        )
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Update auction parameters (admin only)
+    /// Only affects auctions started afterwards
+    pub fn set_auction_params(&mut self, start_premium: U256, floor_ratio: U256, duration: u64) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+
+        if floor_ratio == U256::zero() || floor_ratio > start_premium || duration == 0 {
+            self.env().revert(LendingError::InvalidConfiguration);
+        }
+
+        self.start_premium.set(start_premium);
+        self.floor_ratio.set(floor_ratio);
+        self.duration.set(duration);
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    fn collateral_manager_ref(&self) -> CollateralManagerContractRef {
+        let address = self.collateral_manager.get_or_revert_with(LendingError::InvalidConfiguration);
+        CollateralManagerContractRef::new(self.env(), address)
+    }
+
+    /// Linear decline from the start price to the floor over the auction duration
+    fn price_at(&self, auction: &Auction, now: u64) -> U256 {
+        let elapsed = now.saturating_sub(auction.start_time);
+        if elapsed >= auction.duration {
+            return auction.floor_price;
+        }
+
+        let decline = auction.start_price - auction.floor_price;
+        auction.start_price - (decline * U256::from(elapsed)) / U256::from(auction.duration)
+    }
+
+    fn close_auction(&mut self, auction: &mut Auction) {
+        auction.is_active = false;
+        self.active_auctions.set(&(auction.borrower, auction.collateral_asset), 0);
+
+        self.env().emit_event(AuctionClosed {
+            auction_id: auction.id,
+            collateral_remaining: auction.collateral_remaining,
+            debt_remaining: auction.debt_remaining,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lending::aecto_vault::{AectoVault, AectoVaultInitArgs};
+    use crate::lending::collateral_manager::{CollateralManager, CollateralManagerInitArgs, CollateralManagerHostRef};
+    use crate::lending::interest_rate::{InterestRateStrategy, InterestRateStrategyInitArgs};
+    use crate::lending::lending_pool::{LendingPool, LendingPoolInitArgs, LendingPoolHostRef};
+    use crate::lending::liquidation::LiquidationEngine;
+    use crate::lending::price_oracle::{PriceOracle, PriceOracleHostRef};
+    use crate::tokens::{EctoToken, EctoTokenHostRef, WethToken, WethTokenHostRef};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+
+    struct Setup {
+        ecto: EctoTokenHostRef,
+        weth: WethTokenHostRef,
+        oracle: PriceOracleHostRef,
+        collateral_mgr: CollateralManagerHostRef,
+        pool: LendingPoolHostRef,
+        auction: LiquidationAuctionHostRef,
+    }
+
+    /// Borrower posts 1000 WETH at 1 ECTO, borrows 800 ECTO, then WETH drops to 0.9
+    fn setup(env: &HostEnv) -> Setup {
+        let admin = env.get_account(0);
+        let lender = env.get_account(1);
+        let borrower = env.get_account(2);
+        let scale = U256::from(1_000_000_000_000_000_000u128);
+
+        env.set_caller(admin);
+        let mut ecto = EctoToken::deploy(env, NoArgs);
+        let mut weth = WethToken::deploy(env, NoArgs);
+        let mut oracle = PriceOracle::deploy(env, NoArgs);
+        oracle.set_price(weth.address(), scale);
+        let mut collateral_mgr = CollateralManager::deploy(env, CollateralManagerInitArgs {
+            price_oracle_address: oracle.address(),
+        });
+        collateral_mgr.add_collateral(
+            weth.address(),
+            U256::from(800_000_000_000_000_000u128),
+            U256::from(850_000_000_000_000_000u128),
+            U256::from(50_000_000_000_000_000u128),
+        );
+        let engine = LiquidationEngine::deploy(env, NoArgs);
+        let strategy = InterestRateStrategy::deploy(env, InterestRateStrategyInitArgs {
+            base_rate: U256::zero(),
+            optimal_utilization: U256::from(800_000_000_000_000_000u128),
+            slope1: U256::from(40_000_000_000_000_000u128),
+            slope2: U256::from(750_000_000_000_000_000u128),
+        });
+        let mut vault = AectoVault::deploy(env, AectoVaultInitArgs {
+            ecto_token_address: ecto.address(),
+            lending_pool_address: admin,
+        });
+        let mut pool = LendingPool::deploy(env, LendingPoolInitArgs {
+            aecto_vault_address: vault.address(),
+            collateral_manager_address: collateral_mgr.address(),
+            interest_rate_strategy_address: strategy.address(),
+            liquidation_engine_address: engine.address(),
+            price_oracle_address: oracle.address(),
+            ecto_token_address: ecto.address(),
+        });
+        let auction = LiquidationAuction::deploy(env, LiquidationAuctionInitArgs {
+            lending_pool_address: pool.address(),
+            collateral_manager_address: collateral_mgr.address(),
+            price_oracle_address: oracle.address(),
+        });
+        vault.set_lending_pool(pool.address());
+        collateral_mgr.set_lending_pool(pool.address());
+        pool.set_liquidation_auction(auction.address());
+
+        ecto.mint(lender, U256::from(1_000));
+        env.set_caller(lender);
+        ecto.approve(vault.address(), U256::from(1_000));
+        vault.deposit(U256::from(1_000), lender);
+
+        weth.mint(borrower, U256::from(1_000));
+        env.set_caller(borrower);
+        weth.approve(collateral_mgr.address(), U256::from(1_000));
+        collateral_mgr.deposit_collateral(weth.address(), U256::from(1_000));
+        pool.borrow(U256::from(800), weth.address());
+
+        env.set_caller(admin);
+        oracle.set_price(weth.address(), U256::from(900_000_000_000_000_000u128));
+
+        Setup { ecto, weth, oracle, collateral_mgr, pool, auction }
+    }
+
+    #[test]
+    fn test_price_declines_and_takes_settle_partially() {
+        let env = odra_test::env();
+        let mut s = setup(&env);
+        let borrower = env.get_account(2);
+        let keeper = env.get_account(3);
+
+        let id = s.auction.start_auction(borrower, s.weth.address());
+        // Starts at 105% of the 0.9 oracle price
+        assert_eq!(s.auction.get_current_price(id), U256::from(945_000_000_000_000_000u128));
+
+        // Halfway to the 80% floor
+        env.advance_block_time(1800);
+        let price = s.auction.get_current_price(id);
+        assert_eq!(price, U256::from(832_500_000_000_000_000u128));
+
+        s.ecto.mint(keeper, U256::from(800));
+        env.set_caller(keeper);
+        s.ecto.approve(s.pool.address(), U256::from(800));
+
+        // Partial take
+        let (bought, paid) = s.auction.take(id, U256::from(400), price);
+        assert_eq!(bought, U256::from(400));
+        assert_eq!(paid, U256::from(333));
+        assert_eq!(s.weth.balance_of(keeper), U256::from(400));
+        assert_eq!(s.collateral_mgr.get_user_collateral(borrower, s.weth.address()), U256::from(600));
+        let position = s.pool.get_borrow_position(borrower).unwrap();
+        assert_eq!(position.principal + position.interest_accrued, U256::from(467));
+        assert!(s.auction.get_auction(id).unwrap().is_active);
+
+        // Oversized take is capped at the close factor (half the remaining debt)
+        assert_eq!(s.pool.get_max_liquidatable_debt(borrower), U256::from(233));
+        let (bought, paid) = s.auction.take(id, U256::from(10_000), price);
+        assert_eq!(paid, U256::from(233));
+        assert_eq!(bought, U256::from(279));
+        let position = s.pool.get_borrow_position(borrower).unwrap();
+        assert_eq!(position.principal + position.interest_accrued, U256::from(234));
+
+        // 321 WETH at 0.9 now covers the 234 debt, so the rest is no longer for sale
+        assert_eq!(s.pool.get_max_liquidatable_debt(borrower), U256::zero());
+        assert_eq!(
+            s.auction.try_take(id, U256::from(10_000), price).unwrap_err(),
+            LendingError::PositionHealthy.into()
+        );
+        s.auction.cancel_auction(id);
+        assert!(!s.auction.get_auction(id).unwrap().is_active);
+        assert_eq!(s.auction.get_active_auction(borrower, s.weth.address()), 0);
+    }
+
+    #[test]
+    fn test_pool_rejects_takes_on_recovered_or_oversized_positions() {
+        let env = odra_test::env();
+        let mut s = setup(&env);
+        let (admin, borrower, keeper) = (env.get_account(0), env.get_account(2), env.get_account(3));
+
+        // Settling directly, the pool holds every take to the close factor
+        s.pool.set_liquidation_auction(admin);
+        s.ecto.mint(keeper, U256::from(800));
+        env.set_caller(keeper);
+        s.ecto.approve(s.pool.address(), U256::from(800));
+        env.set_caller(admin);
+        assert_eq!(
+            s.pool
+                .try_settle_auction_take(keeper, borrower, s.weth.address(), U256::from(401), U256::from(400))
+                .unwrap_err(),
+            LendingError::ExceedsCloseFactor.into()
+        );
+
+        // Once the price recovers the borrower cannot be bought out
+        s.oracle.set_price(s.weth.address(), U256::from(1_000_000_000_000_000_000u128));
+        assert_eq!(
+            s.pool
+                .try_settle_auction_take(keeper, borrower, s.weth.address(), U256::from(100), U256::from(100))
+                .unwrap_err(),
+            LendingError::PositionHealthy.into()
+        );
+    }
+
+    #[test]
+    fn test_take_reverts_above_max_price() {
+        let env = odra_test::env();
+        let mut s = setup(&env);
+        let borrower = env.get_account(2);
+
+        let id = s.auction.start_auction(borrower, s.weth.address());
+        assert_eq!(
+            s.auction
+                .try_take(id, U256::from(100), U256::from(900_000_000_000_000_000u128))
+                .unwrap_err(),
+            LendingError::SlippageExceeded.into()
+        );
+        assert_eq!(
+            s.auction.try_start_auction(borrower, s.weth.address()).unwrap_err(),
+            LendingError::AuctionAlreadyActive.into()
+        );
+    }
+}
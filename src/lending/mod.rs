@@ -5,13 +5,24 @@
 //! 
 //! **CEP-4626 Compliant**: The lending pool implements CEP-4626 for aECTO,
 //! providing a standardized interface for interest-bearing deposits.
+//! 
+//! **ECTO Issuance**: The CDP engine mints ECTO against collateral vaults
+//! with a stability fee, liquidated through the LiquidationEngine.
+//! 
+//! **ECTO Savings**: The sECTO vault (CEP-4626) pays passive ECTO holders a
+//! governance-set savings rate out of protocol revenue.
 
 pub mod aecto_vault;
 pub mod lending_pool;
 pub mod interest_rate;
 pub mod collateral_manager;
+pub mod debt_token;
 pub mod liquidation;
+pub mod liquidation_auction;
 pub mod price_oracle;
+pub mod safety_module;
+pub mod cdp_engine;
+pub mod secto_vault;
 pub mod errors;
 pub mod events;
 
@@ -19,7 +30,12 @@ pub use aecto_vault::AectoVault;
 pub use lending_pool::LendingPool;
 pub use interest_rate::InterestRateStrategy;
 pub use collateral_manager::CollateralManager;
+pub use debt_token::DebtToken;
 pub use liquidation::LiquidationEngine;
+pub use liquidation_auction::LiquidationAuction;
 pub use price_oracle::PriceOracle;
+pub use safety_module::SafetyModule;
+pub use cdp_engine::CdpEngine;
+pub use secto_vault::SectoVault;
 pub use errors::LendingError;
 pub use events::*;
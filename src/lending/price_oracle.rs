@@ -1,10 +1,17 @@
 //! Price Oracle - Provides asset prices for collateral valuation
 //! 
-//! Initially uses DEX prices, can be upgraded to use external oracles
+//! Initially uses DEX prices, can be upgraded to use external oracles.
+//! Prices are submitted by a whitelist of feeders; the published price is
+//! the median of their fresh submissions.
 
 use odra::prelude::*;
 use odra::casper_types::U256;
+use odra::ContractRef;
 use super::errors::LendingError;
+use super::events::{DerivedRateSynced, PriceServed, PriceSubmitted};
+use crate::access::{AccessControl, Role, ADMIN_ROLE, KEEPER_ROLE, ORACLE_ROLE};
+use crate::dex::pair::PairContractRef;
+use crate::lst::staking_manager::StakingManagerContractRef;
 
 /// Price feed data for an asset
 #[odra::odra_type]
@@ -20,20 +27,94 @@ pub struct PriceFeed {
     pub is_active: bool,
 }
 
+/// Source that served a price
+#[odra::odra_type]
+pub enum PriceSource {
+    /// Feeder median
+    Primary,
+    /// Fallback DEX TWAP
+    Fallback,
+}
+
+/// Fallback DEX TWAP source for an asset
+#[odra::odra_type]
+pub struct FallbackSource {
+    /// DEX pair of the asset against ECTO
+    pub pair: Address,
+    /// TWAP window in seconds
+    pub window: u64,
+    /// Whether the fallback may be consulted
+    pub is_enabled: bool,
+}
+
+/// Price derived from a base asset and a StakingManager exchange rate
+/// (e.g. sCSPR = CSPR price × CSPR per sCSPR)
+#[odra::odra_type]
+pub struct DerivedPrice {
+    /// Asset whose price is multiplied by the rate (e.g. CSPR)
+    pub base_asset: Address,
+    /// StakingManager providing the exchange rate
+    pub staking_manager: Address,
+    /// Last accepted rate in base units per derived unit (scaled by 1e18)
+    pub last_rate: U256,
+    /// Maximum rate increase accepted per sync (scaled by 1e18)
+    /// Example: 1% = 0.01 * 1e18
+    pub max_rate_increase: U256,
+    /// Whether the derived price is used
+    pub is_enabled: bool,
+}
+
+/// A single feeder's latest price for an asset
+#[odra::odra_type]
+pub struct PriceSubmission {
+    /// Submitted price in ECTO (scaled by 1e18)
+    pub price: U256,
+    /// Timestamp of submission
+    pub timestamp: u64,
+}
+
 /// Price Oracle contract
 #[odra::module]
 pub struct PriceOracle {
     /// Price feeds for each asset
     price_feeds: Mapping<Address, PriceFeed>,
     
-    /// Admin address
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     
     /// Maximum price staleness (in seconds)
     max_staleness: Var<u64>,
     
     /// Scale factor (1e18)
     scale: Var<U256>,
+    
+    /// Per-asset heartbeat (in seconds) overriding `max_staleness`
+    heartbeats: Mapping<Address, u64>,
+    
+    /// Per-asset fallback TWAP source
+    fallback_sources: Mapping<Address, FallbackSource>,
+    /// Maximum deviation of the primary price from the fallback before the
+    /// fallback is served instead (scaled by 1e18); zero disables the check
+    max_source_deviation: Var<U256>,
+    
+    /// Assets priced from a base asset and an exchange rate instead of a feed
+    derived_prices: Mapping<Address, DerivedPrice>,
+    
+    /// Whitelisted feeders by index
+    feeders: Mapping<u32, Address>,
+    /// Number of whitelisted feeders
+    feeder_count: Var<u32>,
+    /// Whether an address is a whitelisted feeder
+    is_feeder: Mapping<Address, bool>,
+    /// Latest submission per (asset, feeder)
+    submissions: Mapping<(Address, Address), PriceSubmission>,
+    /// Fresh submissions required before the median is published
+    min_submissions: Var<u32>,
+    /// Maximum deviation of a submission from the current median (scaled by 1e18)
+    /// Example: 10% = 0.10 * 1e18; zero disables the check
+    max_deviation: Var<U256>,
+    /// When each asset's price was last republished after going stale
+    recovered_at: Mapping<Address, u64>,
 }
 
 #[odra::module]
@@ -41,31 +122,72 @@ impl PriceOracle {
     /// Initialize the price oracle
     pub fn init(&mut self) {
         let caller = self.env().caller();
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
         self.max_staleness.set(3600); // 1 hour default
         self.scale.set(U256::from(1_000_000_000_000_000_000u128)); // 1e18
+        self.min_submissions.set(1);
+        self.max_deviation.set(U256::zero());
+        self.add_feeder_internal(caller);
     }
     
-    /// Set price for an asset (admin only)
+    /// Submit a price for an asset (whitelisted feeders only)
+    /// The published price becomes the median of all fresh submissions once
+    /// at least `min_submissions` feeders have reported
     /// 
     /// # Arguments
     /// * `asset` - Asset address
     /// * `price` - Price in ECTO (scaled by 1e18)
     pub fn set_price(&mut self, asset: Address, price: U256) {
-        self.only_admin();
+        let caller = self.env().caller();
+        if !self.is_feeder(caller) {
+            self.env().revert(LendingError::Unauthorized);
+        }
         
         if price == U256::zero() {
             self.env().revert(LendingError::InvalidPrice);
         }
         
-        let feed = PriceFeed {
+        // Reject outliers against the current fresh median
+        let max_deviation = self.max_deviation.get_or_default();
+        if max_deviation > U256::zero() {
+            if let Some(feed) = self.price_feeds.get(&asset) {
+                if feed.is_active && !self.is_stale(asset, feed.last_update) {
+                    if self.deviation(price, feed.price) > max_deviation {
+                        self.env().revert(LendingError::PriceDeviationTooHigh);
+                    }
+                }
+            }
+        }
+        
+        let timestamp = self.env().get_block_time();
+        self.submissions.set(&(asset, caller), PriceSubmission { price, timestamp });
+        
+        let mut median = U256::zero();
+        let mut prices = self.fresh_submissions(asset);
+        if prices.len() as u32 >= self.min_submissions.get_or_default() {
+            median = median_price(&mut prices);
+            let previous = self.price_feeds.get(&asset);
+            if let Some(feed) = &previous {
+                if self.is_stale(asset, feed.last_update) {
+                    self.recovered_at.set(&asset, timestamp);
+                }
+            }
+            let is_active = previous.map(|feed| feed.is_active).unwrap_or(true);
+            self.price_feeds.set(&asset, PriceFeed {
+                asset,
+                price: median,
+                last_update: timestamp,
+                is_active,
+            });
+        }
+        
+        self.env().emit_event(PriceSubmitted {
+            feeder: caller,
             asset,
             price,
-            last_update: self.env().get_block_time(),
-            is_active: true,
-        };
-        
-        self.price_feeds.set(&asset, feed);
+            median,
+            timestamp,
+        });
     }
     
     /// Get price for an asset
@@ -76,22 +198,34 @@ impl PriceOracle {
     /// # Returns
     /// Price in ECTO (scaled by 1e18)
     pub fn get_price(&self, asset: Address) -> U256 {
-        let feed = self.price_feeds.get(&asset)
-            .unwrap_or_revert_with(&self.env(), LendingError::PriceFeedNotAvailable);
-        
-        if !feed.is_active {
-            self.env().revert(LendingError::PriceFeedNotAvailable);
-        }
+        let (price, source) = self.resolve_price(asset);
         
-        // Check if price is stale
-        let current_time = self.env().get_block_time();
-        let max_staleness = self.max_staleness.get_or_default();
+        self.env().emit_event(PriceServed {
+            asset,
+            price,
+            from_fallback: source == PriceSource::Fallback,
+            timestamp: self.env().get_block_time(),
+        });
         
-        if current_time - feed.last_update > max_staleness {
-            self.env().revert(LendingError::InvalidPrice);
+        price
+    }
+    
+    /// Check whether an asset has a usable price: an active primary price
+    /// within its heartbeat, or an enabled fallback source
+    pub fn is_price_fresh(&self, asset: Address) -> bool {
+        if let Some(derived) = self.derived_price(asset) {
+            return self.is_price_fresh(derived.base_asset);
         }
-        
-        feed.price
+        if self.primary_price(asset).is_some() {
+            return true;
+        }
+        self.fallback_source(asset).is_some()
+    }
+    
+    /// Get when an asset's price was last republished after going stale
+    /// (zero if it never has)
+    pub fn get_recovered_at(&self, asset: Address) -> u64 {
+        self.recovered_at.get(&asset).unwrap_or_default()
     }
     
     /// Get price with staleness check disabled (for testing)
@@ -135,7 +269,7 @@ impl PriceOracle {
     
     /// Disable a price feed (admin only)
     pub fn disable_feed(&mut self, asset: Address) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
         
         let mut feed = self.price_feeds.get(&asset)
             .unwrap_or_revert_with(&self.env(), LendingError::PriceFeedNotAvailable);
@@ -146,7 +280,7 @@ impl PriceOracle {
     
     /// Enable a price feed (admin only)
     pub fn enable_feed(&mut self, asset: Address) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
         
         let mut feed = self.price_feeds.get(&asset)
             .unwrap_or_revert_with(&self.env(), LendingError::PriceFeedNotAvailable);
@@ -157,28 +291,382 @@ impl PriceOracle {
     
     /// Update max staleness period (admin only)
     pub fn set_max_staleness(&mut self, seconds: u64) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
         self.max_staleness.set(seconds);
     }
     
-    /// Get admin address
-    pub fn get_admin(&self) -> Address {
-        self.admin.get_or_revert_with(LendingError::Unauthorized)
+    /// Set the heartbeat for an asset, overriding the default staleness (admin only)
+    /// Zero falls back to `max_staleness`
+    pub fn set_heartbeat(&mut self, asset: Address, seconds: u64) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.heartbeats.set(&asset, seconds);
     }
     
-    /// Check if caller is admin
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(LendingError::Unauthorized);
-        if caller != admin {
-            self.env().revert(LendingError::Unauthorized);
+    /// Set the fallback DEX TWAP for an asset (admin only)
+    /// The pair must trade the asset against ECTO
+    pub fn set_fallback_source(&mut self, asset: Address, pair: Address, window: u64) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if window == 0 {
+            self.env().revert(LendingError::InvalidConfiguration);
         }
+        self.fallback_sources.set(&asset, FallbackSource {
+            pair,
+            window,
+            is_enabled: true,
+        });
+    }
+    
+    /// Stop consulting the fallback source for an asset (admin only)
+    pub fn disable_fallback_source(&mut self, asset: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        let mut source = self.fallback_sources.get(&asset)
+            .unwrap_or_revert_with(&self.env(), LendingError::PriceFeedNotAvailable);
+        source.is_enabled = false;
+        self.fallback_sources.set(&asset, source);
+    }
+    
+    /// Get the fallback source configured for an asset
+    pub fn get_fallback_source(&self, asset: Address) -> Option<FallbackSource> {
+        self.fallback_sources.get(&asset)
+    }
+    
+    /// Set the primary/fallback deviation that switches to the fallback (admin only)
+    /// Scaled by 1e18; zero disables the check
+    pub fn set_max_source_deviation(&mut self, max_deviation: U256) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.max_source_deviation.set(max_deviation);
+    }
+    
+    /// Get the TWAP of an asset in ECTO from its fallback source (scaled by 1e18)
+    pub fn get_fallback_price(&self, asset: Address) -> U256 {
+        let source = self.fallback_source(asset)
+            .unwrap_or_revert_with(&self.env(), LendingError::PriceFeedNotAvailable);
+        self.twap(asset, &source)
+    }
+    
+    /// Price an asset as `base price × StakingManager exchange rate` (admin only)
+    /// Intended for sCSPR, so it needs no feed of its own
+    ///
+    /// # Arguments
+    /// * `asset` - Derived asset (e.g. sCSPR)
+    /// * `base_asset` - Asset whose price is multiplied (e.g. CSPR)
+    /// * `staking_manager` - StakingManager providing the exchange rate
+    /// * `max_rate_increase` - Maximum rate increase per sync (scaled by 1e18)
+    pub fn set_derived_price(
+        &mut self,
+        asset: Address,
+        base_asset: Address,
+        staking_manager: Address,
+        max_rate_increase: U256,
+    ) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if asset == base_asset {
+            self.env().revert(LendingError::InvalidConfiguration);
+        }
+        
+        let last_rate = self.live_rate(staking_manager);
+        self.derived_prices.set(&asset, DerivedPrice {
+            base_asset,
+            staking_manager,
+            last_rate,
+            max_rate_increase,
+            is_enabled: true,
+        });
+    }
+    
+    /// Stop deriving an asset's price (admin only); it falls back to its feed
+    pub fn disable_derived_price(&mut self, asset: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        let mut derived = self.derived_prices.get(&asset)
+            .unwrap_or_revert_with(&self.env(), LendingError::PriceFeedNotAvailable);
+        derived.is_enabled = false;
+        self.derived_prices.set(&asset, derived);
+    }
+    
+    /// Record the current exchange rate for a derived asset (oracle, keeper or admin)
+    /// Increases beyond `max_rate_increase` are capped, so a manipulated rate
+    /// can only move the price by the configured step per sync; only trusted
+    /// roles sync, so the steps cannot be chained within a block
+    ///
+    /// # Returns
+    /// The rate now in use
+    pub fn sync_exchange_rate(&mut self, asset: Address) -> U256 {
+        self.access.require_any_role(&[ORACLE_ROLE, KEEPER_ROLE, ADMIN_ROLE], LendingError::Unauthorized);
+        
+        let mut derived = self.derived_price(asset)
+            .unwrap_or_revert_with(&self.env(), LendingError::PriceFeedNotAvailable);
+        
+        let live_rate = self.live_rate(derived.staking_manager);
+        let rate = self.capped_rate(&derived, live_rate);
+        derived.last_rate = rate;
+        self.derived_prices.set(&asset, derived);
+        
+        self.env().emit_event(DerivedRateSynced {
+            asset,
+            rate,
+            live_rate,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        rate
+    }
+    
+    /// Get the derived price configuration for an asset
+    pub fn get_derived_price(&self, asset: Address) -> Option<DerivedPrice> {
+        self.derived_prices.get(&asset)
+    }
+    
+    /// Get the exchange rate currently applied to a derived asset (scaled by 1e18)
+    pub fn get_derived_rate(&self, asset: Address) -> U256 {
+        let derived = self.derived_price(asset)
+            .unwrap_or_revert_with(&self.env(), LendingError::PriceFeedNotAvailable);
+        self.capped_rate(&derived, self.live_rate(derived.staking_manager))
+    }
+    
+    /// Get the maximum price age accepted for an asset
+    pub fn get_heartbeat(&self, asset: Address) -> u64 {
+        match self.heartbeats.get(&asset).unwrap_or_default() {
+            0 => self.max_staleness.get_or_default(),
+            heartbeat => heartbeat,
+        }
+    }
+    
+    // ========================================
+    // Feeder Management
+    // ========================================
+    
+    /// Whitelist a price feeder (admin only)
+    pub fn add_feeder(&mut self, feeder: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if self.is_feeder(feeder) {
+            self.env().revert(LendingError::FeederAlreadyRegistered);
+        }
+        self.add_feeder_internal(feeder);
+    }
+    
+    /// Remove a price feeder (admin only); its submissions stop counting
+    pub fn remove_feeder(&mut self, feeder: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if !self.is_feeder(feeder) {
+            self.env().revert(LendingError::FeederNotRegistered);
+        }
+        
+        let count = self.feeder_count.get_or_default();
+        for i in 0..count {
+            if self.feeders.get(&i) == Some(feeder) {
+                // Swap with the last feeder to keep indices dense
+                let last = count - 1;
+                if i != last {
+                    let last_feeder = self.feeders.get(&last)
+                        .unwrap_or_revert_with(&self.env(), LendingError::FeederNotRegistered);
+                    self.feeders.set(&i, last_feeder);
+                }
+                break;
+            }
+        }
+        
+        self.feeder_count.set(count - 1);
+        self.is_feeder.set(&feeder, false);
+    }
+    
+    /// Set the number of fresh submissions needed to publish a median (admin only)
+    pub fn set_min_submissions(&mut self, min_submissions: u32) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if min_submissions == 0 {
+            self.env().revert(LendingError::InvalidConfiguration);
+        }
+        self.min_submissions.set(min_submissions);
+    }
+    
+    /// Set the maximum deviation of a submission from the median (admin only)
+    /// Scaled by 1e18; zero disables the check
+    pub fn set_max_deviation(&mut self, max_deviation: U256) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.max_deviation.set(max_deviation);
+    }
+    
+    /// Check whether an address is a whitelisted feeder
+    pub fn is_feeder(&self, account: Address) -> bool {
+        self.is_feeder.get(&account).unwrap_or_default()
+    }
+    
+    /// Get all whitelisted feeders
+    pub fn get_feeders(&self) -> Vec<Address> {
+        let count = self.feeder_count.get_or_default();
+        (0..count).filter_map(|i| self.feeders.get(&i)).collect()
+    }
+    
+    /// Get a feeder's latest submission for an asset
+    pub fn get_submission(&self, asset: Address, feeder: Address) -> Option<PriceSubmission> {
+        self.submissions.get(&(asset, feeder))
+    }
+    
+    /// Get the minimum fresh submissions needed to publish a median
+    pub fn get_min_submissions(&self) -> u32 {
+        self.min_submissions.get_or_default()
+    }
+    
+    /// Get the maximum deviation of a submission from the median
+    pub fn get_max_deviation(&self) -> U256 {
+        self.max_deviation.get_or_default()
+    }
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    fn add_feeder_internal(&mut self, feeder: Address) {
+        let count = self.feeder_count.get_or_default();
+        self.feeders.set(&count, feeder);
+        self.feeder_count.set(count + 1);
+        self.is_feeder.set(&feeder, true);
+    }
+    
+    /// Price for an asset and the source that served it
+    /// The fallback serves when the primary is missing or stale, or deviates
+    /// from the fallback beyond `max_source_deviation`; disabled feeds revert
+    fn resolve_price(&self, asset: Address) -> (U256, PriceSource) {
+        if let Some(derived) = self.derived_price(asset) {
+            let (base_price, source) = self.resolve_price(derived.base_asset);
+            let rate = self.capped_rate(&derived, self.live_rate(derived.staking_manager));
+            let price = (base_price * rate) / self.scale.get_or_default();
+            return (price, source);
+        }
+        
+        if let Some(feed) = self.price_feeds.get(&asset) {
+            if !feed.is_active {
+                self.env().revert(LendingError::PriceFeedNotAvailable);
+            }
+        }
+        
+        let fallback = self.fallback_source(asset);
+        match (self.primary_price(asset), fallback) {
+            (Some(primary), Some(source)) => {
+                let max_deviation = self.max_source_deviation.get_or_default();
+                if max_deviation > U256::zero() {
+                    let twap = self.twap(asset, &source);
+                    if self.deviation(primary, twap) > max_deviation {
+                        return (twap, PriceSource::Fallback);
+                    }
+                }
+                (primary, PriceSource::Primary)
+            }
+            (Some(primary), None) => (primary, PriceSource::Primary),
+            (None, Some(source)) => (self.twap(asset, &source), PriceSource::Fallback),
+            (None, None) => {
+                if self.price_feeds.get(&asset).is_some() {
+                    self.env().revert(LendingError::StalePrice)
+                }
+                self.env().revert(LendingError::PriceFeedNotAvailable)
+            }
+        }
+    }
+    
+    /// Active primary price within its heartbeat
+    fn primary_price(&self, asset: Address) -> Option<U256> {
+        self.price_feeds.get(&asset)
+            .filter(|feed| feed.is_active && !self.is_stale(asset, feed.last_update))
+            .map(|feed| feed.price)
+    }
+    
+    fn derived_price(&self, asset: Address) -> Option<DerivedPrice> {
+        self.derived_prices.get(&asset).filter(|derived| derived.is_enabled)
+    }
+    
+    /// CSPR per sCSPR from the StakingManager (1:1 before any stake)
+    fn live_rate(&self, staking_manager: Address) -> U256 {
+        let scale = self.scale.get_or_default();
+        let rate = StakingManagerContractRef::new(self.env(), staking_manager).get_cspr_by_scspr(scale);
+        if rate == U256::zero() {
+            return scale;
+        }
+        rate
+    }
+    
+    /// Live rate limited to `max_rate_increase` above the last accepted rate
+    /// Decreases (e.g. slashing) pass through uncapped
+    fn capped_rate(&self, derived: &DerivedPrice, live_rate: U256) -> U256 {
+        let scale = self.scale.get_or_default();
+        let cap = derived.last_rate + (derived.last_rate * derived.max_rate_increase) / scale;
+        if live_rate > cap {
+            cap
+        } else {
+            live_rate
+        }
+    }
+    
+    fn fallback_source(&self, asset: Address) -> Option<FallbackSource> {
+        self.fallback_sources.get(&asset).filter(|source| source.is_enabled)
+    }
+    
+    /// Time-weighted price of `asset` in ECTO over the source window
+    fn twap(&self, asset: Address, source: &FallbackSource) -> U256 {
+        let pair = PairContractRef::new(self.env(), source.pair);
+        let (price0_now, price1_now) = pair.observe(0);
+        let (price0_then, price1_then) = pair.observe(source.window);
+        
+        // Cumulative prices wrap, so take the wrapping difference
+        let cumulative = if pair.token0() == asset {
+            price0_now.overflowing_sub(price0_then).0
+        } else {
+            price1_now.overflowing_sub(price1_then).0
+        };
+        let price = cumulative / U256::from(source.window);
+        if price == U256::zero() {
+            self.env().revert(LendingError::InvalidPrice);
+        }
+        price
+    }
+    
+    /// Relative difference between two prices (scaled by 1e18)
+    fn deviation(&self, price: U256, reference: U256) -> U256 {
+        let difference = if price > reference {
+            price - reference
+        } else {
+            reference - price
+        };
+        (difference * self.scale.get_or_default()) / reference
+    }
+    
+    fn is_stale(&self, asset: Address, last_update: u64) -> bool {
+        let current_time = self.env().get_block_time();
+        current_time - last_update > self.get_heartbeat(asset)
+    }
+    
+    /// Fresh submissions for an asset from current feeders
+    fn fresh_submissions(&self, asset: Address) -> Vec<U256> {
+        self.get_feeders()
+            .into_iter()
+            .filter_map(|feeder| self.submissions.get(&(asset, feeder)))
+            .filter(|submission| !self.is_stale(asset, submission.timestamp))
+            .map(|submission| submission.price)
+            .collect()
+    }
+}
+
+/// Median of a non-empty set of prices (mean of the middle two when even)
+fn median_price(prices: &mut [U256]) -> U256 {
+    prices.sort();
+    let mid = prices.len() / 2;
+    if prices.len() % 2 == 0 {
+        (prices[mid - 1] + prices[mid]) / 2
+    } else {
+        prices[mid]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::pair::{Pair, PairInitArgs};
+    use crate::lst::staking_manager::{StakingManager, StakingManagerInitArgs};
+    use crate::tokens::{EctoToken, WethToken};
+    use odra::host::{Deployer, HostRef, NoArgs};
     
     #[test]
     fn test_set_and_get_price() {
@@ -194,4 +682,157 @@ mod tests {
     fn test_stale_price_rejection() {
         // Test that stale prices are rejected
     }
+    
+    #[test]
+    fn test_per_asset_heartbeat() {
+        let env = odra_test::env();
+        let fast = env.get_account(8);
+        let slow = env.get_account(9);
+        let mut oracle = PriceOracle::deploy(&env, NoArgs);
+        oracle.set_heartbeat(fast, 60);
+        oracle.set_price(fast, U256::from(100));
+        oracle.set_price(slow, U256::from(100));
+        
+        env.advance_block_time(61);
+        assert!(!oracle.is_price_fresh(fast));
+        assert_eq!(
+            oracle.try_get_asset_value(fast, U256::one()).unwrap_err(),
+            LendingError::StalePrice.into()
+        );
+        // Other assets keep the default staleness window
+        assert!(oracle.is_price_fresh(slow));
+        assert_eq!(oracle.get_heartbeat(slow), 3600);
+    }
+    
+    #[test]
+    fn test_fallback_twap_serves_stale_or_deviating_primary() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let one = U256::from(1_000_000_000_000_000_000u128);
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        let mut weth = WethToken::deploy(&env, NoArgs);
+        let mut pair = Pair::deploy(&env, PairInitArgs {
+            token0: weth.address(),
+            token1: ecto.address(),
+            factory: admin,
+        });
+        
+        // 1 WETH = 2 ECTO on the DEX
+        weth.mint(pair.address(), U256::from(1_000));
+        ecto.mint(pair.address(), U256::from(2_000));
+        pair.sync();
+        
+        let mut oracle = PriceOracle::deploy(&env, NoArgs);
+        oracle.set_heartbeat(weth.address(), 60);
+        oracle.set_price(weth.address(), one * 2);
+        env.advance_block_time(100);
+        
+        // Stale primary and no fallback
+        assert_eq!(
+            oracle.try_get_price(weth.address()).unwrap_err(),
+            LendingError::StalePrice.into()
+        );
+        
+        oracle.set_fallback_source(weth.address(), pair.address(), 100);
+        assert!(oracle.is_price_fresh(weth.address()));
+        assert_eq!(oracle.get_asset_value(weth.address(), U256::from(10)), U256::from(20));
+        
+        // Fresh primary far from the TWAP is overridden only when bounded
+        oracle.set_price(weth.address(), one * 3);
+        assert_eq!(oracle.get_price(weth.address()), one * 3);
+        oracle.set_max_source_deviation(one / 10); // 10%
+        assert_eq!(oracle.get_price(weth.address()), one * 2);
+    }
+    
+    #[test]
+    fn test_derived_price_follows_base_asset() {
+        let env = odra_test::env();
+        let cspr = env.get_account(8);
+        let scspr = env.get_account(9);
+        let one = U256::from(1_000_000_000_000_000_000u128);
+        let staking_manager = StakingManager::deploy(&env, StakingManagerInitArgs {
+            scspr_token_address: scspr,
+        });
+        
+        let mut oracle = PriceOracle::deploy(&env, NoArgs);
+        oracle.set_price(cspr, one / 50);
+        oracle.set_derived_price(scspr, cspr, staking_manager.address(), one / 100);
+        
+        // No sCSPR has been minted yet, so the rate is 1:1
+        assert_eq!(oracle.get_derived_rate(scspr), one);
+        assert_eq!(oracle.get_price(scspr), one / 50);
+        assert!(oracle.is_price_fresh(scspr));
+        
+        // Only trusted roles may step the accepted rate forward
+        let keeper = env.get_account(3);
+        env.set_caller(env.get_account(4));
+        assert_eq!(
+            oracle.try_sync_exchange_rate(scspr).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+        env.set_caller(env.get_account(0));
+        oracle.grant_role(KEEPER_ROLE, keeper);
+        env.set_caller(keeper);
+        assert_eq!(oracle.sync_exchange_rate(scspr), one);
+        env.set_caller(env.get_account(0));
+        
+        // Staleness follows the base feed
+        oracle.set_heartbeat(cspr, 60);
+        env.advance_block_time(61);
+        assert!(!oracle.is_price_fresh(scspr));
+        assert_eq!(
+            oracle.try_get_price(scspr).unwrap_err(),
+            LendingError::StalePrice.into()
+        );
+    }
+    
+    #[test]
+    fn test_median_of_feeder_submissions() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let asset = env.get_account(9);
+        let mut oracle = PriceOracle::deploy(&env, NoArgs);
+        oracle.add_feeder(env.get_account(1));
+        oracle.add_feeder(env.get_account(2));
+        oracle.set_min_submissions(3);
+        
+        env.set_caller(admin);
+        oracle.set_price(asset, U256::from(100));
+        env.set_caller(env.get_account(1));
+        oracle.set_price(asset, U256::from(130));
+        // Not enough submissions yet
+        assert!(oracle.try_get_price(asset).is_err());
+        
+        env.set_caller(env.get_account(2));
+        oracle.set_price(asset, U256::from(110));
+        assert_eq!(oracle.get_price(asset), U256::from(110));
+        
+        // A removed feeder's submission no longer counts
+        env.set_caller(admin);
+        oracle.set_min_submissions(1);
+        oracle.remove_feeder(env.get_account(1));
+        oracle.set_price(asset, U256::from(100));
+        assert_eq!(oracle.get_price(asset), U256::from(105));
+    }
+    
+    #[test]
+    fn test_rejects_outliers_and_unlisted_feeders() {
+        let env = odra_test::env();
+        let asset = env.get_account(9);
+        let mut oracle = PriceOracle::deploy(&env, NoArgs);
+        oracle.set_max_deviation(U256::from(100_000_000_000_000_000u128)); // 10%
+        oracle.set_price(asset, U256::from(1_000));
+        
+        assert_eq!(
+            oracle.try_set_price(asset, U256::from(1_200)).unwrap_err(),
+            LendingError::PriceDeviationTooHigh.into()
+        );
+        oracle.set_price(asset, U256::from(1_100));
+        
+        env.set_caller(env.get_account(1));
+        assert_eq!(
+            oracle.try_set_price(asset, U256::from(1_100)).unwrap_err(),
+            LendingError::Unauthorized.into()
+        );
+    }
 }
@@ -0,0 +1,408 @@
+//! Safety Module - Staked ECTO backstop for lending pool bad debt
+//!
+//! Stakers deposit ECTO and receive shares. When a liquidation leaves debt
+//! that neither collateral nor reserves can cover, the lending pool draws the
+//! remainder from this module, reducing every staker's ECTO pro rata.
+//!
+//! Unstaking takes a cooldown, followed by a window in which the staker may
+//! exit, so stakers cannot leave ahead of a shortfall they see coming.
+//! Once a shortfall drains the module entirely, the outstanding shares are
+//! worthless and new stakes are refused rather than diluted by them.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+use super::errors::LendingError;
+use super::events::*;
+use crate::token::Cep18TokenContractRef;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// Default seconds between starting the cooldown and unstaking (10 days)
+pub const DEFAULT_COOLDOWN_SECONDS: u64 = 864_000;
+
+/// Default seconds the unstake window stays open after the cooldown (2 days)
+pub const DEFAULT_UNSTAKE_WINDOW_SECONDS: u64 = 172_800;
+
+/// Safety Module contract
+#[odra::module]
+pub struct SafetyModule {
+    /// ECTO token address
+    ecto_token: Var<Address>,
+    /// Lending pool allowed to draw from the backstop
+    lending_pool: Var<Address>,
+    /// Staker shares
+    shares: Mapping<Address, U256>,
+    /// Total shares outstanding
+    total_shares: Var<U256>,
+    /// ECTO backing all shares
+    total_staked: Var<U256>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Timestamp each staker started their cooldown (0 when none)
+    cooldowns: Mapping<Address, u64>,
+    /// Seconds between starting the cooldown and unstaking
+    cooldown_seconds: Var<u64>,
+    /// Seconds the unstake window stays open after the cooldown
+    unstake_window_seconds: Var<u64>,
+}
+
+#[odra::module]
+impl SafetyModule {
+    /// Initialize the safety module
+    pub fn init(&mut self, ecto_token_address: Address, lending_pool_address: Address) {
+        let caller = self.env().caller();
+        self.access.grant(ADMIN_ROLE, caller);
+
+        self.ecto_token.set(ecto_token_address);
+        self.lending_pool.set(lending_pool_address);
+        self.total_shares.set(U256::zero());
+        self.total_staked.set(U256::zero());
+        self.cooldown_seconds.set(DEFAULT_COOLDOWN_SECONDS);
+        self.unstake_window_seconds.set(DEFAULT_UNSTAKE_WINDOW_SECONDS);
+    }
+
+    // ========================================
+    // Staking
+    // ========================================
+
+    /// Stake ECTO into the backstop
+    /// Staking again restarts any cooldown the caller had running
+    ///
+    /// # Returns
+    /// The shares minted
+    pub fn stake(&mut self, amount: U256) -> U256 {
+        if amount == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        if self.is_depleted() {
+            self.env().revert(LendingError::BackstopDepleted);
+        }
+
+        let caller = self.env().caller();
+        let shares = self.convert_to_shares(amount);
+        if shares == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+
+        self.ecto_ref().transfer_from(caller, self.env().self_address(), amount);
+
+        self.shares.set(&caller, self.shares_of(caller) + shares);
+        self.total_shares.set(self.total_shares.get_or_default() + shares);
+        self.total_staked.set(self.total_staked.get_or_default() + amount);
+        self.cooldowns.set(&caller, 0);
+
+        self.env().emit_event(SafetyModuleStaked {
+            staker: caller,
+            amount,
+            shares,
+            timestamp: self.env().get_block_time(),
+        });
+
+        shares
+    }
+
+    /// Start the cooldown after which the caller may unstake
+    pub fn start_cooldown(&mut self) {
+        let caller = self.env().caller();
+        if self.shares_of(caller) == U256::zero() {
+            self.env().revert(LendingError::InsufficientBalance);
+        }
+
+        let now = self.env().get_block_time();
+        self.cooldowns.set(&caller, now);
+        let (unstake_from, unstake_until) = self.unstake_window(now);
+
+        self.env().emit_event(SafetyModuleCooldownStarted {
+            staker: caller,
+            unstake_from,
+            unstake_until,
+        });
+    }
+
+    /// Burn shares for their current ECTO value
+    /// Only possible inside the window that follows the caller's cooldown
+    ///
+    /// # Returns
+    /// The ECTO returned
+    pub fn unstake(&mut self, shares: U256) -> U256 {
+        if shares == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+
+        let caller = self.env().caller();
+        if !self.can_unstake(caller) {
+            self.env().revert(LendingError::CooldownNotComplete);
+        }
+        let current_shares = self.shares_of(caller);
+        if current_shares < shares {
+            self.env().revert(LendingError::InsufficientBalance);
+        }
+
+        let amount = self.convert_to_assets(shares);
+        self.shares.set(&caller, current_shares - shares);
+        self.total_shares.set(self.total_shares.get_or_default() - shares);
+        self.total_staked.set(self.total_staked.get_or_default() - amount);
+
+        self.ecto_ref().transfer(caller, amount);
+
+        self.env().emit_event(SafetyModuleUnstaked {
+            staker: caller,
+            amount,
+            shares,
+            timestamp: self.env().get_block_time(),
+        });
+
+        amount
+    }
+
+    // ========================================
+    // Backstop (Lending Pool Only)
+    // ========================================
+
+    /// Send up to `amount` ECTO to the lending pool to cover bad debt
+    ///
+    /// # Returns
+    /// The ECTO actually sent
+    pub fn cover_shortfall(&mut self, amount: U256) -> U256 {
+        let pool = self.lending_pool.get_or_revert_with(LendingError::InvalidConfiguration);
+        if self.env().caller() != pool {
+            self.env().revert(LendingError::Unauthorized);
+        }
+
+        let total_staked = self.total_staked.get_or_default();
+        let covered = if amount > total_staked {
+            total_staked
+        } else {
+            amount
+        };
+        if covered == U256::zero() {
+            return covered;
+        }
+
+        self.total_staked.set(total_staked - covered);
+        self.ecto_ref().transfer(pool, covered);
+
+        self.env().emit_event(ShortfallCovered {
+            amount: covered,
+            total_staked: total_staked - covered,
+            timestamp: self.env().get_block_time(),
+        });
+
+        covered
+    }
+
+    // ========================================
+    // View Functions
+    // ========================================
+
+    /// Get the shares of a staker
+    pub fn shares_of(&self, staker: Address) -> U256 {
+        self.shares.get(&staker).unwrap_or_default()
+    }
+
+    /// Get the ECTO value of a staker's shares
+    pub fn balance_of(&self, staker: Address) -> U256 {
+        self.convert_to_assets(self.shares_of(staker))
+    }
+
+    /// Get the total ECTO backing the module
+    pub fn get_total_staked(&self) -> U256 {
+        self.total_staked.get_or_default()
+    }
+
+    /// Get the total shares outstanding
+    pub fn get_total_shares(&self) -> U256 {
+        self.total_shares.get_or_default()
+    }
+
+    /// Get the timestamp a staker started their cooldown (0 when none)
+    pub fn get_cooldown_start(&self, staker: Address) -> u64 {
+        self.cooldowns.get(&staker).unwrap_or_default()
+    }
+
+    /// Get the cooldown and unstake window lengths in seconds
+    pub fn get_cooldown_params(&self) -> (u64, u64) {
+        (self.cooldown_seconds.get_or_default(), self.unstake_window_seconds.get_or_default())
+    }
+
+    /// Check whether a staker is inside their unstake window
+    pub fn can_unstake(&self, staker: Address) -> bool {
+        let start = self.get_cooldown_start(staker);
+        if start == 0 {
+            return false;
+        }
+        let (unstake_from, unstake_until) = self.unstake_window(start);
+        let now = self.env().get_block_time();
+        now >= unstake_from && now <= unstake_until
+    }
+
+    /// Check whether shortfalls drained the backstop while shares are outstanding
+    pub fn is_depleted(&self) -> bool {
+        self.total_staked.get_or_default() == U256::zero()
+            && self.total_shares.get_or_default() > U256::zero()
+    }
+
+    /// Convert ECTO to shares (zero once the backstop is depleted)
+    pub fn convert_to_shares(&self, amount: U256) -> U256 {
+        let total_shares = self.total_shares.get_or_default();
+        let total_staked = self.total_staked.get_or_default();
+
+        if total_shares == U256::zero() {
+            return amount; // 1:1 initial rate
+        }
+        if total_staked == U256::zero() {
+            return U256::zero();
+        }
+
+        // shares = (amount * total_shares) / total_staked
+        (amount * total_shares) / total_staked
+    }
+
+    /// Convert shares to ECTO
+    pub fn convert_to_assets(&self, shares: U256) -> U256 {
+        let total_shares = self.total_shares.get_or_default();
+
+        if total_shares == U256::zero() {
+            return U256::zero();
+        }
+
+        // amount = (shares * total_staked) / total_shares
+        (shares * self.total_staked.get_or_default()) / total_shares
+    }
+
+    // ========================================
+    // Admin Functions
+    // ========================================
+
+    /// Set the lending pool allowed to draw from the backstop (admin only)
+    pub fn set_lending_pool(&mut self, lending_pool: Address) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        self.lending_pool.set(lending_pool);
+    }
+
+    /// Set the cooldown and unstake window lengths in seconds (admin only)
+    pub fn set_cooldown_params(&mut self, cooldown_seconds: u64, unstake_window_seconds: u64) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if unstake_window_seconds == 0 {
+            self.env().revert(LendingError::InvalidConfiguration);
+        }
+        self.cooldown_seconds.set(cooldown_seconds);
+        self.unstake_window_seconds.set(unstake_window_seconds);
+    }
+
+    /// Get the lending pool allowed to draw from the backstop
+    pub fn get_lending_pool(&self) -> Address {
+        self.lending_pool.get_or_revert_with(LendingError::InvalidConfiguration)
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // ========================================
+    // Internal Functions
+    // ========================================
+
+    /// First and last timestamps a cooldown started at `start` allows unstaking
+    fn unstake_window(&self, start: u64) -> (u64, u64) {
+        let unstake_from = start + self.cooldown_seconds.get_or_default();
+        (unstake_from, unstake_from + self.unstake_window_seconds.get_or_default())
+    }
+
+    fn ecto_ref(&self) -> Cep18TokenContractRef {
+        let address = self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration);
+        Cep18TokenContractRef::new(self.env(), address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::{EctoToken, EctoTokenHostRef};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+
+    /// Deploy ECTO and a safety module drawn on by account 5, with
+    /// `stakers` each staking their amount
+    fn setup(env: &HostEnv, stakers: &[(Address, u64)]) -> (EctoTokenHostRef, SafetyModuleHostRef) {
+        env.set_caller(env.get_account(0));
+        let mut ecto = EctoToken::deploy(env, NoArgs);
+        let mut module = SafetyModule::deploy(env, SafetyModuleInitArgs {
+            ecto_token_address: ecto.address(),
+            lending_pool_address: env.get_account(5),
+        });
+        env.advance_block_time(100);
+
+        for (staker, amount) in stakers {
+            env.set_caller(env.get_account(0));
+            ecto.mint(*staker, U256::from(*amount));
+            env.set_caller(*staker);
+            ecto.approve(module.address(), U256::from(*amount));
+            module.stake(U256::from(*amount));
+        }
+        (ecto, module)
+    }
+
+    #[test]
+    fn test_unstake_waits_for_the_cooldown_window() {
+        let env = odra_test::env();
+        let staker = env.get_account(1);
+        let (ecto, mut module) = setup(&env, &[(staker, 1_000)]);
+        assert_eq!(module.shares_of(staker), U256::from(1_000));
+        assert_eq!(module.get_total_staked(), U256::from(1_000));
+
+        // No exit without a completed cooldown
+        env.set_caller(staker);
+        assert_eq!(module.try_unstake(U256::from(400)).unwrap_err(), LendingError::CooldownNotComplete.into());
+        module.start_cooldown();
+        let started = env.block_time();
+        assert!(env.emitted_event(&module, SafetyModuleCooldownStarted {
+            staker,
+            unstake_from: started + DEFAULT_COOLDOWN_SECONDS,
+            unstake_until: started + DEFAULT_COOLDOWN_SECONDS + DEFAULT_UNSTAKE_WINDOW_SECONDS,
+        }));
+        assert_eq!(module.try_unstake(U256::from(400)).unwrap_err(), LendingError::CooldownNotComplete.into());
+
+        env.advance_block_time(DEFAULT_COOLDOWN_SECONDS);
+        assert_eq!(module.unstake(U256::from(400)), U256::from(400));
+        assert_eq!(ecto.balance_of(staker), U256::from(400));
+        assert_eq!(module.balance_of(staker), U256::from(600));
+
+        // The window closes again
+        env.advance_block_time(DEFAULT_UNSTAKE_WINDOW_SECONDS + 1);
+        assert_eq!(module.try_unstake(U256::from(100)).unwrap_err(), LendingError::CooldownNotComplete.into());
+    }
+
+    #[test]
+    fn test_shortfalls_cut_stakes_pro_rata_and_a_drained_module_refuses_stakes() {
+        let env = odra_test::env();
+        let (alice, bob, carol, pool) = (env.get_account(1), env.get_account(2), env.get_account(3), env.get_account(5));
+        let (mut ecto, mut module) = setup(&env, &[(alice, 1_000), (bob, 500)]);
+
+        // Bob sees a shortfall coming but cannot leave ahead of it
+        env.set_caller(bob);
+        module.start_cooldown();
+        assert_eq!(module.try_unstake(U256::from(500)).unwrap_err(), LendingError::CooldownNotComplete.into());
+
+        assert_eq!(module.try_cover_shortfall(U256::from(300)).unwrap_err(), LendingError::Unauthorized.into());
+        env.set_caller(pool);
+        assert_eq!(module.cover_shortfall(U256::from(300)), U256::from(300));
+        assert_eq!(ecto.balance_of(pool), U256::from(300));
+        assert_eq!(module.balance_of(alice), U256::from(800));
+        assert_eq!(module.balance_of(bob), U256::from(400));
+
+        // Draining the module leaves worthless shares that new stakers must not share with
+        assert_eq!(module.cover_shortfall(U256::from(5_000)), U256::from(1_200));
+        assert!(module.is_depleted());
+        assert_eq!(module.convert_to_shares(U256::from(100)), U256::zero());
+        env.set_caller(env.get_account(0));
+        ecto.mint(carol, U256::from(100));
+        env.set_caller(carol);
+        ecto.approve(module.address(), U256::from(100));
+        assert_eq!(module.try_stake(U256::from(100)).unwrap_err(), LendingError::BackstopDepleted.into());
+    }
+}
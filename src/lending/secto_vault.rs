@@ -0,0 +1,506 @@
+//! sECTO Vault - CEP-4626 savings vault for ECTO
+//!
+//! Users deposit ECTO and receive sECTO shares that grow at a savings rate
+//! set by governance, compounding every second.
+//!
+//! The yield is not printed: protocol revenue (lending reserves, PSM fees)
+//! is paid into a yield buffer with `fund`, and accrual moves ECTO from the
+//! buffer to holders. Once the buffer is empty, sECTO stops growing until it
+//! is topped up again.
+
+use odra::prelude::*;
+use odra::casper_types::bytesrepr::Bytes;
+use odra::casper_types::{PublicKey, U256};
+use odra::ContractRef;
+use super::errors::LendingError;
+use super::events::{SavingsAccrued, SavingsFunded, SavingsRateUpdated};
+use crate::access::{AccessControl, Role, ADMIN_ROLE, PAUSER_ROLE};
+use odra::OdraError;
+use crate::cep4626::{base, Cep4626Hooks, Cep4626Vault};
+use crate::math::{compound_apr, RAY};
+use crate::token::{Cep18Base, Cep18TokenContractRef, TokenMetadata};
+
+/// Highest savings rate governance may set (100% APR, RAY)
+pub const MAX_SAVINGS_RATE: u128 = RAY;
+
+/// sECTO Vault - Savings ECTO token
+#[odra::module]
+pub struct SectoVault {
+    /// sECTO share ledger (CEP-18)
+    token: SubModule<Cep18Base>,
+    
+    /// Underlying ECTO token address
+    ecto_token: Var<Address>,
+    /// ECTO owed to sECTO holders as of the last accrual
+    total_assets: Var<U256>,
+    /// ECTO held to pay future savings yield
+    yield_buffer: Var<U256>,
+    /// Annual savings rate (RAY)
+    savings_rate: Var<U256>,
+    /// Timestamp of the last accrual
+    last_accrual: Var<u64>,
+    
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Paused state
+    paused: Var<bool>,
+}
+
+#[odra::module]
+impl SectoVault {
+    /// Initialize the sECTO vault
+    pub fn init(&mut self, ecto_token_address: Address, savings_rate: U256) {
+        let caller = self.env().caller();
+        
+        self.token.init(String::from("Savings ECTO"), String::from("sECTO"), 18);
+        
+        self.ecto_token.set(ecto_token_address);
+        self.total_assets.set(U256::zero());
+        self.yield_buffer.set(U256::zero());
+        self.savings_rate.set(savings_rate);
+        self.last_accrual.set(self.env().get_block_time());
+        
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(PAUSER_ROLE, caller);
+        self.paused.set(false);
+    }
+    
+    // ========================================
+    // CEP-18 Token Functions
+    // ========================================
+    
+    delegate! {
+        to self.token {
+            fn name(&self) -> String;
+            fn symbol(&self) -> String;
+            fn decimals(&self) -> u8;
+            fn total_supply(&self) -> U256;
+            fn balance_of(&self, owner: Address) -> U256;
+            fn allowance(&self, owner: Address, spender: Address) -> U256;
+            fn transfer(&mut self, to: Address, amount: U256) -> bool;
+            fn approve(&mut self, spender: Address, amount: U256) -> bool;
+            fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool;
+            fn permit(&mut self, owner: Address, spender: Address, value: U256, deadline: u64, public_key: PublicKey, signature: Bytes);
+            fn nonces(&self, owner: Address) -> u64;
+            fn domain_separator(&self) -> [u8; 32];
+            fn permit_digest(&self, owner: Address, spender: Address, value: U256, nonce: u64, deadline: u64) -> [u8; 32];
+            fn contract_version(&self) -> String;
+            fn metadata(&self) -> TokenMetadata;
+        }
+    }
+    
+    // ========================================
+    // CEP-4626 Entry Points
+    // ========================================
+    
+    /// Deposit the caller's ECTO and mint sECTO to `receiver`
+    pub fn deposit(&mut self, assets: U256, receiver: Address) -> U256 {
+        Cep4626Vault::deposit(self, assets, receiver)
+    }
+    
+    /// Mint exactly `shares` sECTO to `receiver` for the caller's ECTO
+    pub fn mint(&mut self, shares: U256, receiver: Address) -> U256 {
+        Cep4626Vault::mint(self, shares, receiver)
+    }
+    
+    /// Burn `owner`'s sECTO for `assets` ECTO sent to `receiver`
+    pub fn withdraw(&mut self, assets: U256, receiver: Address, owner: Address) -> U256 {
+        Cep4626Vault::withdraw(self, assets, receiver, owner)
+    }
+    
+    /// Burn exactly `shares` of `owner`'s sECTO for ECTO sent to `receiver`
+    pub fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
+        Cep4626Vault::redeem(self, shares, receiver, owner)
+    }
+    
+    /// Get the most ECTO `receiver` can deposit right now
+    pub fn max_deposit(&self, receiver: Address) -> U256 {
+        Cep4626Vault::max_deposit(self, receiver)
+    }
+    
+    /// Get the most sECTO `receiver` can mint right now
+    pub fn max_mint(&self, receiver: Address) -> U256 {
+        Cep4626Vault::max_mint(self, receiver)
+    }
+    
+    /// Get the most ECTO `owner` can withdraw right now
+    pub fn max_withdraw(&self, owner: Address) -> U256 {
+        Cep4626Vault::max_withdraw(self, owner)
+    }
+    
+    /// Get the most sECTO `owner` can redeem right now
+    pub fn max_redeem(&self, owner: Address) -> U256 {
+        Cep4626Vault::max_redeem(self, owner)
+    }
+    
+    /// Get total ECTO owed to sECTO holders, including yield not yet accrued
+    pub fn total_assets(&self) -> U256 {
+        self.get_total_assets()
+    }
+    
+    /// Get total ECTO owed to sECTO holders, including yield not yet accrued
+    pub fn get_total_assets(&self) -> U256 {
+        self.total_assets.get_or_default() + self.pending_yield()
+    }
+    
+    /// Convert assets to shares (public wrapper for CEP-4626)
+    pub fn convert_to_shares(&self, assets: U256) -> U256 {
+        base::convert_to_shares(self, assets)
+    }
+    
+    /// Convert shares to assets (public wrapper for CEP-4626)
+    pub fn convert_to_assets(&self, shares: U256) -> U256 {
+        base::convert_to_assets(self, shares)
+    }
+    
+    /// Shares minted for depositing `assets`
+    pub fn preview_deposit(&self, assets: U256) -> U256 {
+        base::preview_deposit(self, assets)
+    }
+    
+    /// ECTO needed to mint `shares`
+    pub fn preview_mint(&self, shares: U256) -> U256 {
+        base::preview_mint(self, shares)
+    }
+    
+    /// Shares burned to withdraw `assets`
+    pub fn preview_withdraw(&self, assets: U256) -> U256 {
+        base::preview_withdraw(self, assets)
+    }
+    
+    /// ECTO received for redeeming `shares`
+    pub fn preview_redeem(&self, shares: U256) -> U256 {
+        base::preview_redeem(self, shares)
+    }
+    
+    // ========================================
+    // Savings Functions
+    // ========================================
+    
+    /// Pay the caller's ECTO into the yield buffer
+    /// Called with protocol revenue, e.g. collected lending reserves and PSM fees
+    pub fn fund(&mut self, amount: U256) {
+        if amount == U256::zero() {
+            self.env().revert(LendingError::ZeroAmount);
+        }
+        self.accrue();
+        
+        let funder = self.env().caller();
+        Cep18TokenContractRef::new(self.env(), self.asset())
+            .transfer_from(funder, self.env().self_address(), amount);
+        let buffer = self.yield_buffer.get_or_default() + amount;
+        self.yield_buffer.set(buffer);
+        
+        self.env().emit_event(SavingsFunded {
+            funder,
+            amount,
+            buffer,
+        });
+    }
+    
+    /// Credit yield earned so far to sECTO holders (anyone)
+    pub fn drip(&mut self) {
+        self.accrue();
+    }
+    
+    /// Get the ECTO left to pay future savings yield, after pending accrual
+    pub fn get_yield_buffer(&self) -> U256 {
+        self.yield_buffer.get_or_default() - self.pending_yield()
+    }
+    
+    /// Get the annual savings rate (RAY)
+    pub fn get_savings_rate(&self) -> U256 {
+        self.savings_rate.get_or_default()
+    }
+    
+    // ========================================
+    // Admin Functions
+    // ========================================
+    
+    /// Set the annual savings rate (RAY, admin/governance only)
+    /// Yield up to now accrues at the previous rate
+    pub fn set_savings_rate(&mut self, new_rate: U256) {
+        self.access.require_role(ADMIN_ROLE, LendingError::Unauthorized);
+        if new_rate > U256::from(MAX_SAVINGS_RATE) {
+            self.env().revert(LendingError::InvalidConfiguration);
+        }
+        self.accrue();
+        
+        let old_rate = self.savings_rate.get_or_default();
+        self.savings_rate.set(new_rate);
+        
+        self.env().emit_event(SavingsRateUpdated {
+            old_rate,
+            new_rate,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    pub fn pause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, LendingError::Unauthorized);
+        self.paused.set(true);
+    }
+    
+    pub fn unpause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, LendingError::Unauthorized);
+        self.paused.set(false);
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        self.paused.get_or_default()
+    }
+    
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+    
+    /// Yield earned since the last accrual, capped at the yield buffer
+    fn pending_yield(&self) -> U256 {
+        let elapsed = self.env().get_block_time().saturating_sub(self.last_accrual.get_or_default());
+        let total_assets = self.total_assets.get_or_default();
+        if elapsed == 0 || total_assets == U256::zero() {
+            return U256::zero();
+        }
+        
+        let growth = compound_apr(self.savings_rate.get_or_default(), elapsed)
+            .unwrap_or_else(|_| self.env().revert(LendingError::MathOverflow));
+        let earned = total_assets * (growth - U256::from(RAY)) / U256::from(RAY);
+        earned.min(self.yield_buffer.get_or_default())
+    }
+    
+    /// Move yield earned since the last accrual from the buffer to holders
+    fn accrue(&mut self) {
+        let earned = self.pending_yield();
+        self.last_accrual.set(self.env().get_block_time());
+        if earned == U256::zero() {
+            return;
+        }
+        
+        let total_assets = self.total_assets.get_or_default() + earned;
+        self.total_assets.set(total_assets);
+        self.yield_buffer.set(self.yield_buffer.get_or_default() - earned);
+        
+        self.env().emit_event(SavingsAccrued {
+            amount: earned,
+            total_assets,
+        });
+    }
+    
+    fn ensure_not_paused(&self) {
+        if self.paused.get_or_default() {
+            self.env().revert(LendingError::ContractPaused);
+        }
+    }
+}
+
+// ============================================================================
+// CEP-4626 Implementation
+// ============================================================================
+
+impl Cep4626Hooks for SectoVault {
+    fn vault_total_assets(&self) -> U256 {
+        self.get_total_assets()
+    }
+    
+    fn vault_total_shares(&self) -> U256 {
+        self.token.total_supply()
+    }
+    
+    fn vault_shares_of(&self, owner: Address) -> U256 {
+        self.token.balance_of(owner)
+    }
+    
+    fn mint_vault_shares(&mut self, to: Address, shares: U256) {
+        self.token.mint(to, shares);
+    }
+    
+    fn burn_vault_shares(&mut self, from: Address, shares: U256) {
+        self.token.burn(from, shares);
+    }
+    
+    fn insufficient_shares_error(&self) -> OdraError {
+        LendingError::InsufficientBalance.into()
+    }
+    
+    fn before_deposit(&mut self, caller: Address, _receiver: Address, assets: U256, _shares: U256) {
+        self.ensure_not_paused();
+        // Shares were priced including pending yield, so credit it first
+        self.accrue();
+        
+        Cep18TokenContractRef::new(self.env(), self.asset())
+            .transfer_from(caller, self.env().self_address(), assets);
+        self.total_assets.set(self.total_assets.get_or_default() + assets);
+    }
+    
+    fn before_withdraw(
+        &mut self,
+        caller: Address,
+        _receiver: Address,
+        owner: Address,
+        _assets: U256,
+        shares: U256,
+    ) {
+        self.ensure_not_paused();
+        self.accrue();
+        
+        // Check allowance if caller is not owner
+        if caller != owner {
+            let allowance = self.allowance(owner, caller);
+            if allowance < shares {
+                self.env().revert(LendingError::Unauthorized);
+            }
+            self.token.raw_approve(owner, caller, allowance - shares);
+        }
+    }
+    
+    fn after_withdraw(
+        &mut self,
+        _caller: Address,
+        receiver: Address,
+        _owner: Address,
+        assets: U256,
+        _shares: U256,
+    ) {
+        self.total_assets.set(self.total_assets.get_or_default() - assets);
+        Cep18TokenContractRef::new(self.env(), self.asset()).transfer(receiver, assets);
+    }
+}
+
+impl Cep4626Vault for SectoVault {
+    fn asset(&self) -> Address {
+        self.ecto_token.get_or_revert_with(LendingError::InvalidConfiguration)
+    }
+    
+    fn total_assets(&self) -> U256 {
+        self.get_total_assets()
+    }
+    
+    fn convert_to_shares(&self, assets: U256) -> U256 {
+        base::convert_to_shares(self, assets)
+    }
+    
+    fn convert_to_assets(&self, shares: U256) -> U256 {
+        base::convert_to_assets(self, shares)
+    }
+    
+    fn max_deposit(&self, _receiver: Address) -> U256 {
+        if self.paused.get_or_default() {
+            return U256::zero();
+        }
+        U256::MAX
+    }
+    
+    fn max_mint(&self, _receiver: Address) -> U256 {
+        if self.paused.get_or_default() {
+            return U256::zero();
+        }
+        U256::MAX
+    }
+    
+    fn max_withdraw(&self, owner: Address) -> U256 {
+        if self.paused.get_or_default() {
+            return U256::zero();
+        }
+        base::max_withdraw(self, owner)
+    }
+    
+    fn max_redeem(&self, owner: Address) -> U256 {
+        if self.paused.get_or_default() {
+            return U256::zero();
+        }
+        base::max_redeem(self, owner)
+    }
+    
+    fn preview_deposit(&self, assets: U256) -> U256 {
+        base::preview_deposit(self, assets)
+    }
+    
+    fn preview_mint(&self, shares: U256) -> U256 {
+        base::preview_mint(self, shares)
+    }
+    
+    fn preview_withdraw(&self, assets: U256) -> U256 {
+        base::preview_withdraw(self, assets)
+    }
+    
+    fn preview_redeem(&self, shares: U256) -> U256 {
+        base::preview_redeem(self, shares)
+    }
+    
+    fn deposit(&mut self, assets: U256, receiver: Address) -> U256 {
+        base::deposit(self, assets, receiver)
+    }
+    
+    fn mint(&mut self, shares: U256, receiver: Address) -> U256 {
+        base::mint(self, shares, receiver)
+    }
+    
+    fn withdraw(&mut self, assets: U256, receiver: Address, owner: Address) -> U256 {
+        base::withdraw(self, assets, receiver, owner)
+    }
+    
+    fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
+        base::redeem(self, shares, receiver, owner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::SECONDS_PER_YEAR;
+    use crate::tokens::EctoToken;
+    use odra::host::{Deployer, HostRef, NoArgs};
+
+    #[test]
+    fn test_savings_rate_paid_from_buffer() {
+        let env = odra_test::env();
+        let (admin, saver) = (env.get_account(0), env.get_account(1));
+        env.set_caller(admin);
+
+        let mut ecto = EctoToken::deploy(&env, NoArgs);
+        // 5% savings rate
+        let mut vault = SectoVault::deploy(&env, SectoVaultInitArgs {
+            ecto_token_address: ecto.address(),
+            savings_rate: U256::from(RAY / 20),
+        });
+        ecto.mint(admin, U256::from(100_000));
+        ecto.mint(saver, U256::from(1_000_000));
+
+        env.set_caller(saver);
+        ecto.approve(vault.address(), U256::from(1_000_000));
+        let shares = vault.deposit(U256::from(1_000_000), saver);
+
+        // No buffer, no yield
+        env.advance_block_time(SECONDS_PER_YEAR);
+        assert_eq!(vault.total_assets(), U256::from(1_000_000));
+
+        env.set_caller(admin);
+        ecto.approve(vault.address(), U256::from(100_000));
+        vault.fund(U256::from(100_000));
+
+        // A year at 5% compounds to ~5.13%
+        env.advance_block_time(SECONDS_PER_YEAR);
+        let total = vault.total_assets();
+        assert!(total > U256::from(1_051_000) && total < U256::from(1_052_000));
+        assert_eq!(vault.get_yield_buffer(), U256::from(1_100_000) - total);
+
+        // Raising the rate drains the rest of the buffer, then growth stops
+        vault.set_savings_rate(U256::from(RAY));
+        env.advance_block_time(SECONDS_PER_YEAR);
+        assert_eq!(vault.total_assets(), U256::from(1_100_000));
+        assert_eq!(vault.get_yield_buffer(), U256::zero());
+
+        env.set_caller(saver);
+        // The virtual share keeps a unit of rounding in the vault
+        vault.redeem(shares, saver, saver);
+        assert_eq!(ecto.balance_of(saver), U256::from(1_099_999));
+        assert_eq!(vault.total_assets(), U256::from(1));
+    }
+}
@@ -13,6 +13,9 @@ pub mod errors;
 pub mod events;
 pub mod math;
 
+// Role-based access control
+pub mod access;
+
 // CEP-4626: Tokenized Vault Standard
 pub mod cep4626;
 
@@ -27,3 +30,6 @@ pub mod farming;
 
 // Incentive System modules
 pub mod incentives;
+
+// Governance modules
+pub mod governance;
@@ -0,0 +1,188 @@
+//! Delegation backend for the Staking Manager
+//!
+//! The StakingManager never talks to the auction system directly; it goes
+//! through the `DelegationBackend` trait. The production implementation on
+//! `ContractEnv` calls the Casper auction contract (which Odra's VM mocks in
+//! tests). The StakingManager can instead be pointed at a backend contract
+//! implementing `DelegationContract`, such as `MockDelegationBackend`, which
+//! its `Delegator` then routes every auction call through.
+
+use odra::prelude::*;
+use odra::casper_types::{PublicKey, U256, U512};
+use odra::{ContractEnv, ContractRef};
+use super::errors::LstError;
+
+/// Operations the StakingManager needs from the auction system
+pub trait DelegationBackend {
+    /// Delegate `amount` motes from the contract purse to `validator`
+    fn delegate_stake(&self, validator: &PublicKey, amount: U512);
+
+    /// Start undelegating `amount` motes from `validator`
+    /// Funds return to the contract purse after the unbonding period
+    fn undelegate_stake(&self, validator: &PublicKey, amount: U512);
+
+    /// Amount currently delegated to `validator` by the contract
+    fn delegated_stake(&self, validator: &PublicKey) -> U512;
+
+    /// Rewards earned by a delegation of `principal` to `validator`
+    /// The auction compounds rewards into the delegation, so claiming them
+    /// amounts to recognising the growth above the tracked principal
+    fn accrued_rewards(&self, validator: &PublicKey, principal: U512) -> U512 {
+        self.delegated_stake(validator).saturating_sub(principal)
+    }
+}
+
+impl DelegationBackend for ContractEnv {
+    fn delegate_stake(&self, validator: &PublicKey, amount: U512) {
+        self.delegate(validator.clone(), amount);
+    }
+
+    fn undelegate_stake(&self, validator: &PublicKey, amount: U512) {
+        self.undelegate(validator.clone(), amount);
+    }
+
+    fn delegated_stake(&self, validator: &PublicKey) -> U512 {
+        self.delegated_amount(validator.clone())
+    }
+}
+
+/// Delegation backend deployed as a separate contract
+/// 
+/// Stake is tracked per calling contract. Undelegated CSPR is sent back to
+/// the caller once it has unbonded.
+#[odra::external_contract]
+pub trait DelegationContract {
+    /// Delegate the attached CSPR to `validator`
+    fn delegate_stake(&mut self, validator: PublicKey);
+
+    /// Start undelegating `amount` motes from `validator`
+    fn undelegate_stake(&mut self, validator: PublicKey, amount: U512);
+
+    /// Amount the caller currently has delegated to `validator`
+    fn delegated_stake(&self, validator: PublicKey) -> U512;
+}
+
+/// The StakingManager's route to the auction system
+/// 
+/// Calls the configured backend contract if there is one, otherwise the
+/// Casper auction through `ContractEnv`.
+pub struct Delegator {
+    env: Rc<ContractEnv>,
+    backend: Option<Address>,
+}
+
+impl Delegator {
+    /// Route through `backend`, or the auction if `None`
+    pub fn new(env: Rc<ContractEnv>, backend: Option<Address>) -> Self {
+        Self { env, backend }
+    }
+}
+
+impl DelegationBackend for Delegator {
+    fn delegate_stake(&self, validator: &PublicKey, amount: U512) {
+        match self.backend {
+            Some(backend) => DelegationContractContractRef::new(self.env.clone(), backend)
+                .with_tokens(amount)
+                .delegate_stake(validator.clone()),
+            None => self.env.delegate_stake(validator, amount),
+        }
+    }
+
+    fn undelegate_stake(&self, validator: &PublicKey, amount: U512) {
+        match self.backend {
+            Some(backend) => DelegationContractContractRef::new(self.env.clone(), backend)
+                .undelegate_stake(validator.clone(), amount),
+            None => self.env.undelegate_stake(validator, amount),
+        }
+    }
+
+    fn delegated_stake(&self, validator: &PublicKey) -> U512 {
+        match self.backend {
+            Some(backend) => DelegationContractContractRef::new(self.env.clone(), backend)
+                .delegated_stake(validator.clone()),
+            None => self.env.delegated_stake(validator),
+        }
+    }
+}
+
+/// Stand-in for the auction system, for tests
+/// 
+/// Holds delegated CSPR in its own purse and returns undelegated CSPR to the
+/// delegator straight away. `add_rewards` grows a delegation the way the
+/// auction compounds validator rewards.
+#[odra::module]
+pub struct MockDelegationBackend {
+    /// Delegated motes per (delegator, validator)
+    delegations: Mapping<(Address, PublicKey), U512>,
+}
+
+#[odra::module]
+impl MockDelegationBackend {
+    /// Delegate the attached CSPR to `validator`
+    #[odra(payable)]
+    pub fn delegate_stake(&mut self, validator: PublicKey) {
+        let key = (self.env().caller(), validator);
+        let delegated = self.delegations.get(&key).unwrap_or_default();
+        self.delegations.set(&key, delegated + self.env().attached_value());
+    }
+
+    /// Undelegate `amount` motes from `validator`, returning them immediately
+    pub fn undelegate_stake(&mut self, validator: PublicKey, amount: U512) {
+        let caller = self.env().caller();
+        let key = (caller, validator);
+        let delegated = self.delegations.get(&key).unwrap_or_default();
+        if amount > delegated {
+            self.env().revert(LstError::UnstakingFailed);
+        }
+        self.delegations.set(&key, delegated - amount);
+        self.env().transfer_tokens(&caller, &amount);
+    }
+
+    /// Amount the caller has delegated to `validator`
+    pub fn delegated_stake(&self, validator: PublicKey) -> U512 {
+        self.delegations.get(&(self.env().caller(), validator)).unwrap_or_default()
+    }
+
+    /// Credit the attached CSPR to `delegator`'s delegation as rewards
+    #[odra(payable)]
+    pub fn add_rewards(&mut self, delegator: Address, validator: PublicKey) {
+        let key = (delegator, validator);
+        let delegated = self.delegations.get(&key).unwrap_or_default();
+        self.delegations.set(&key, delegated + self.env().attached_value());
+    }
+
+    /// Cut `delegator`'s delegation to `validator` by `amount`, as a slashing would
+    /// The slashed motes stay in the mock's purse
+    pub fn slash(&mut self, delegator: Address, validator: PublicKey, amount: U512) {
+        let key = (delegator, validator);
+        let delegated = self.delegations.get(&key).unwrap_or_default();
+        self.delegations.set(&key, delegated.saturating_sub(amount));
+    }
+
+    /// Amount `delegator` has delegated to `validator`
+    pub fn delegation_of(&self, delegator: Address, validator: PublicKey) -> U512 {
+        self.delegations.get(&(delegator, validator)).unwrap_or_default()
+    }
+}
+
+/// A pending undelegation created by an unstake request
+#[odra::odra_type]
+pub struct Undelegation {
+    /// Validator the stake is unbonding from
+    pub validator: Address,
+    /// Amount of CSPR unbonding
+    pub amount: U256,
+}
+
+/// Stake moving between validators, waiting for unbonding to finish
+#[odra::odra_type]
+pub struct Redelegation {
+    /// Validator the stake was undelegated from
+    pub from_validator: Address,
+    /// Validator the stake will be delegated to
+    pub to_validator: Address,
+    /// Amount of CSPR being moved
+    pub amount: U256,
+    /// Timestamp after which the unbonded CSPR can be delegated again
+    pub ready_at: u64,
+}
@@ -66,4 +66,43 @@ pub enum LstError {
     
     /// Transfer to validator failed
     TransferToValidatorFailed = 220,
+    
+    /// Reported slash exceeds the per-report cap
+    SlashTooLarge = 221,
+    
+    /// Unstake request has already matured
+    UnstakeRequestMatured = 222,
+    
+    /// Reward sync called before the minimum interval elapsed
+    RewardSyncTooSoon = 223,
+    
+    /// Synced rewards imply an APR above the configured maximum
+    RewardsExceedMaxApr = 224,
+    
+    /// Referrer cannot be the staker
+    InvalidReferrer = 225,
+    
+    /// Stake would exceed the TVL deposit cap
+    DepositCapExceeded = 226,
+    
+    /// Operation is disabled in emergency exit mode
+    EmergencyModeActive = 227,
+    
+    /// Emergency exit mode is not active
+    EmergencyModeInactive = 228,
+    
+    /// Staking is restricted to allowlisted addresses
+    NotAllowlisted = 229,
+    
+    /// Deposit would exceed the address's deposit cap
+    AddressDepositCapExceeded = 230,
+    
+    /// The request's CSPR has not finished unbonding (or earlier requests come first)
+    FundsStillUnbonding = 231,
+    
+    /// Stake is still delegated or unbonding through the current backend
+    DelegationsOutstanding = 232,
+    
+    /// Open unstake requests are still claimed through the current unstake NFT
+    UnstakeNftsOutstanding = 233,
 }
@@ -140,3 +140,259 @@ pub struct UnstakingPeriodUpdated {
     /// Updated by (admin address)
     pub updated_by: Address,
 }
+
+/// Event emitted when CSPR is delegated to a validator
+#[odra::event]
+pub struct Delegated {
+    /// Validator address
+    pub validator: Address,
+    /// Amount of CSPR delegated
+    pub cspr_amount: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when CSPR starts unbonding from a validator
+#[odra::event]
+pub struct Undelegated {
+    /// Validator address
+    pub validator: Address,
+    /// Amount of CSPR undelegated
+    pub cspr_amount: U256,
+    /// Unstake request that triggered the undelegation
+    pub request_id: u64,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when stake is moved between validators
+#[odra::event]
+pub struct StakeRebalanced {
+    /// Validator the stake is moved from
+    pub from_validator: Address,
+    /// Validator the stake is moved to
+    pub to_validator: Address,
+    /// Amount of CSPR moved
+    pub amount: U256,
+    /// Stake of the source validator before the move
+    pub from_stake_before: U256,
+    /// Stake of the source validator after the move
+    pub from_stake_after: U256,
+    /// Stake of the target validator before the move
+    pub to_stake_before: U256,
+    /// Stake of the target validator once the move completes
+    pub to_stake_after: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when a slashing loss is reported
+#[odra::event]
+pub struct SlashReported {
+    /// Slashed validator
+    pub validator: Address,
+    /// Amount of CSPR lost
+    pub amount: U256,
+    /// Validator stake after the slash
+    pub validator_stake_after: U256,
+    /// Total CSPR staked after the slash
+    pub total_cspr_staked: U256,
+    /// New exchange rate (sCSPR per CSPR, scaled by 1e18)
+    pub new_exchange_rate: U256,
+    /// Reporter address
+    pub reported_by: Address,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when the protocol commission is taken from rewards
+#[odra::event]
+pub struct ProtocolFeeAccrued {
+    /// Treasury receiving the commission
+    pub treasury: Address,
+    /// Commission in CSPR
+    pub fee_cspr: U256,
+    /// sCSPR minted to the treasury
+    pub fee_scspr: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when validator rewards are compounded by a keeper
+#[odra::event]
+pub struct RewardsCompounded {
+    /// Caller receiving the tip
+    pub caller: Address,
+    /// Rewards compounded in CSPR
+    pub rewards_amount: U256,
+    /// sCSPR minted to the caller as a tip
+    pub tip_scspr: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when matured unstake requests are funded from unbonded CSPR
+#[odra::event]
+pub struct WithdrawalsFunded {
+    /// Number of requests funded
+    pub funded_requests: u64,
+    /// CSPR reserved for the funded requests
+    pub funded_amount: U256,
+    /// Total CSPR reserved for funded requests
+    pub unbonded_liquidity: U256,
+    /// Next request waiting to be funded
+    pub next_request_id: u64,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when an unstake request is cancelled
+#[odra::event]
+pub struct UnstakeCancelled {
+    /// Address of the user who cancelled
+    pub user: Address,
+    /// Cancelled unstake request ID
+    pub request_id: u64,
+    /// Amount of CSPR the request was for
+    pub cspr_amount: U256,
+    /// Amount of sCSPR re-minted
+    pub scspr_amount: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when an unstake NFT is minted, transferred or burned
+#[odra::event]
+pub struct UnstakeNftTransfer {
+    /// Previous owner (None when minted)
+    pub from: Option<Address>,
+    /// New owner (None when burned)
+    pub to: Option<Address>,
+    /// Token ID (equal to the unstake request ID)
+    pub token_id: u64,
+}
+
+/// Event emitted when a spender is approved for an unstake NFT
+#[odra::event]
+pub struct UnstakeNftApproval {
+    /// Owner of the token
+    pub owner: Address,
+    /// Approved spender (None when revoked)
+    pub spender: Option<Address>,
+    /// Token ID (equal to the unstake request ID)
+    pub token_id: u64,
+}
+
+/// Event emitted when a staker's referrer is recorded
+#[odra::event]
+pub struct ReferralRecorded {
+    /// Referred staker
+    pub staker: Address,
+    /// Referrer credited with the staker's referred stake
+    pub referrer: Address,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when a referrer claims referral rewards
+#[odra::event]
+pub struct ReferralRewardsClaimed {
+    /// Referrer claiming
+    pub referrer: Address,
+    /// Amount of sCSPR claimed
+    pub scspr_amount: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when the TVL deposit cap is updated
+#[odra::event]
+pub struct DepositCapUpdated {
+    /// New maximum total CSPR staked (0 = no cap)
+    pub cap: U256,
+    /// Address that updated the cap
+    pub updated_by: Address,
+}
+
+/// Event emitted when allowlist-only staking is switched on or off
+#[odra::event]
+pub struct AllowlistModeUpdated {
+    /// Whether only allowlisted addresses may stake
+    pub enabled: bool,
+    /// Address that updated the mode
+    pub updated_by: Address,
+}
+
+/// Event emitted when an address is added to or removed from the allowlist
+#[odra::event]
+pub struct AllowlistUpdated {
+    /// Address whose status changed
+    pub account: Address,
+    /// Whether the address may stake
+    pub allowed: bool,
+    /// Address that updated the list
+    pub updated_by: Address,
+}
+
+/// Event emitted when an address's deposit cap is updated
+#[odra::event]
+pub struct AddressDepositCapUpdated {
+    /// Address the cap applies to
+    pub account: Address,
+    /// New maximum CSPR deposited by the address (0 = no cap)
+    pub cap: U256,
+    /// Address that updated the cap
+    pub updated_by: Address,
+}
+
+/// Event emitted when emergency exit mode is enabled
+#[odra::event]
+pub struct EmergencyModeEnabled {
+    /// Total CSPR undelegated from validators
+    pub undelegated: U256,
+    /// Timestamp after which emergency redemptions open
+    pub unlock_at: u64,
+    /// Address that enabled emergency mode
+    pub enabled_by: Address,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when sCSPR is redeemed in emergency mode
+#[odra::event]
+pub struct EmergencyRedeemed {
+    /// Holder redeeming
+    pub holder: Address,
+    /// Amount of sCSPR burned
+    pub scspr_amount: U256,
+    /// Amount of CSPR received
+    pub cspr_amount: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when sCSPR is wrapped into stCSPR
+#[odra::event]
+pub struct StCsprWrapped {
+    /// Account wrapping
+    pub account: Address,
+    /// Amount of sCSPR wrapped
+    pub scspr_amount: U256,
+    /// Amount of stCSPR credited
+    pub stcspr_amount: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
+
+/// Event emitted when stCSPR is unwrapped into sCSPR
+#[odra::event]
+pub struct StCsprUnwrapped {
+    /// Account unwrapping
+    pub account: Address,
+    /// Amount of sCSPR returned
+    pub scspr_amount: U256,
+    /// Amount of stCSPR debited
+    pub stcspr_amount: U256,
+    /// Timestamp
+    pub timestamp: u64,
+}
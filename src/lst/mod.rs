@@ -6,6 +6,9 @@
 
 pub mod scspr_token;
 pub mod staking_manager;
+pub mod unstake_nft;
+pub mod st_cspr;
+pub mod delegation;
 pub mod errors;
 pub mod events;
 
@@ -14,5 +17,8 @@ mod tests;
 
 pub use scspr_token::ScsprToken;
 pub use staking_manager::StakingManager;
+pub use unstake_nft::UnstakeNft;
+pub use st_cspr::StCsprToken;
+pub use delegation::{DelegationBackend, Delegator, MockDelegationBackend, Redelegation, Undelegation};
 pub use errors::LstError;
 pub use events::*;
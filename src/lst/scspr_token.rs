@@ -2,33 +2,32 @@
 //! 
 //! This token represents staked CSPR that continues to earn rewards
 //! while remaining liquid and composable in DeFi applications.
+//! 
+//! Supports signature-based approvals (`permit`) so integrations can pull
+//! sCSPR in the same deploy that the owner signs off-chain.
 
 use odra::prelude::*;
-use odra::casper_types::U256;
-use crate::events::{Transfer, Approval};
+use odra::casper_types::bytesrepr::Bytes;
+use odra::casper_types::{PublicKey, U256};
+use odra::ContractRef;
+use crate::token::{Cep18Base, TokenMetadata};
 use crate::errors::TokenError;
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+use crate::incentives::incentive_manager::IncentiveManagerContractRef;
 
 /// sCSPR Token - Staked CSPR liquid token
 /// This token is minted when users stake CSPR and burned when they unstake.
 /// The exchange rate between sCSPR and CSPR increases over time as rewards accumulate.
 #[odra::module]
 pub struct ScsprToken {
-    /// Token name
-    name: Var<String>,
-    /// Token symbol
-    symbol: Var<String>,
-    /// Token decimals (18 to match CSPR)
-    decimals: Var<u8>,
-    /// Total supply of sCSPR tokens
-    total_supply: Var<U256>,
-    /// Balance mapping: owner -> balance
-    balances: Mapping<Address, U256>,
-    /// Allowance mapping: owner -> spender -> amount
-    allowances: Mapping<(Address, Address), U256>,
-    /// Staking manager contract address (only this contract can mint/burn)
+    /// CEP-18 ledger (18 decimals to match CSPR)
+    token: SubModule<Cep18Base>,
+    /// Staking manager contract address (the only minter)
     staking_manager: Var<Address>,
-    /// Contract admin
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+    /// Incentive manager notified of balance changes (None disables)
+    incentive_manager: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -36,42 +35,30 @@ impl ScsprToken {
     /// Initialize the sCSPR token
     pub fn init(&mut self, staking_manager: Address) {
         let caller = self.env().caller();
-        self.name.set(String::from("Staked CSPR"));
-        self.symbol.set(String::from("sCSPR"));
-        self.decimals.set(18);
-        self.total_supply.set(U256::zero());
+        self.token.init(String::from("Staked CSPR"), String::from("sCSPR"), 18);
+        self.token.set_minter(staking_manager, true);
         self.staking_manager.set(staking_manager);
-        self.admin.set(caller);
-    }
-
-    /// Get the token name
-    pub fn name(&self) -> String {
-        self.name.get_or_default()
-    }
-
-    /// Get the token symbol
-    pub fn symbol(&self) -> String {
-        self.symbol.get_or_default()
-    }
-
-    /// Get the token decimals
-    pub fn decimals(&self) -> u8 {
-        self.decimals.get_or_default()
-    }
-
-    /// Get the total supply
-    pub fn total_supply(&self) -> U256 {
-        self.total_supply.get_or_default()
-    }
-
-    /// Get the balance of an address
-    pub fn balance_of(&self, owner: Address) -> U256 {
-        self.balances.get(&owner).unwrap_or_default()
-    }
-
-    /// Get the allowance for a spender
-    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
-        self.allowances.get(&(owner, spender)).unwrap_or_default()
+        self.access.grant(ADMIN_ROLE, caller);
+    }
+
+    delegate! {
+        to self.token {
+            fn name(&self) -> String;
+            fn symbol(&self) -> String;
+            fn decimals(&self) -> u8;
+            fn total_supply(&self) -> U256;
+            fn balance_of(&self, owner: Address) -> U256;
+            fn allowance(&self, owner: Address, spender: Address) -> U256;
+            fn approve(&mut self, spender: Address, amount: U256) -> bool;
+            fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn permit(&mut self, owner: Address, spender: Address, value: U256, deadline: u64, public_key: PublicKey, signature: Bytes);
+            fn nonces(&self, owner: Address) -> u64;
+            fn domain_separator(&self) -> [u8; 32];
+            fn permit_digest(&self, owner: Address, spender: Address, value: U256, nonce: u64, deadline: u64) -> [u8; 32];
+            fn contract_version(&self) -> String;
+            fn metadata(&self) -> TokenMetadata;
+        }
     }
 
     /// Transfer tokens to another address
@@ -81,64 +68,26 @@ impl ScsprToken {
         true
     }
 
-    /// Approve a spender to spend tokens
-    pub fn approve(&mut self, spender: Address, amount: U256) -> bool {
-        let caller = self.env().caller();
-        self.approve_internal(caller, spender, amount);
-        true
-    }
-
     /// Transfer tokens from one address to another (requires approval)
     pub fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool {
         let caller = self.env().caller();
-        let current_allowance = self.allowance(from, caller);
-        
-        if current_allowance < amount {
-            self.env().revert(TokenError::InsufficientAllowance);
-        }
-        
-        self.approve_internal(from, caller, current_allowance - amount);
-        self.transfer_internal(from, to, amount);
+        self.token.raw_transfer_from(caller, from, to, amount);
+        self.notify_incentives(Some(from), Some(to));
         true
     }
 
     /// Mint new sCSPR tokens (only callable by staking manager)
     pub fn mint(&mut self, to: Address, amount: U256) {
-        self.only_staking_manager();
-        
-        let current_supply = self.total_supply();
-        let new_supply = current_supply + amount;
-        self.total_supply.set(new_supply);
-
-        let current_balance = self.balance_of(to);
-        self.balances.set(&to, current_balance + amount);
-
-        self.env().emit_event(Transfer {
-            from: Address::from(self.env().self_address()),
-            to,
-            value: amount,
-        });
+        self.token.require_minter();
+        self.token.mint(to, amount);
+        self.notify_incentives(None, Some(to));
     }
 
     /// Burn sCSPR tokens (only callable by staking manager)
     pub fn burn(&mut self, from: Address, amount: U256) {
-        self.only_staking_manager();
-        
-        let current_balance = self.balance_of(from);
-        if current_balance < amount {
-            self.env().revert(TokenError::InsufficientBalance);
-        }
-
-        self.balances.set(&from, current_balance - amount);
-        
-        let current_supply = self.total_supply();
-        self.total_supply.set(current_supply - amount);
-
-        self.env().emit_event(Transfer {
-            from,
-            to: Address::from(self.env().self_address()),
-            value: amount,
-        });
+        self.token.require_minter();
+        self.token.burn(from, amount);
+        self.notify_incentives(Some(from), None);
     }
 
     /// Get the staking manager address
@@ -148,62 +97,46 @@ impl ScsprToken {
 
     /// Update the staking manager address (admin only)
     pub fn set_staking_manager(&mut self, new_manager: Address) {
-        self.only_admin();
+        self.access.require_role(ADMIN_ROLE, TokenError::InsufficientAllowance); // Using existing error for unauthorized
+        if let Some(old_manager) = self.staking_manager.get() {
+            self.token.set_minter(old_manager, false);
+        }
+        self.token.set_minter(new_manager, true);
         self.staking_manager.set(new_manager);
     }
 
-    /// Get the admin address
-    pub fn get_admin(&self) -> Address {
-        self.admin.get_or_revert_with(TokenError::InsufficientAllowance)
+    /// Set the incentive manager notified on every balance change (admin only)
+    pub fn set_incentive_manager(&mut self, incentive_manager: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, TokenError::InsufficientAllowance); // Using existing error for unauthorized
+        self.incentive_manager.set(incentive_manager);
     }
 
-    /// Transfer admin rights (admin only)
-    pub fn transfer_admin(&mut self, new_admin: Address) {
-        self.only_admin();
-        self.admin.set(new_admin);
+    /// Get the incentive manager notified on balance changes
+    pub fn get_incentive_manager(&self) -> Option<Address> {
+        self.incentive_manager.get_or_default()
     }
 
-    // Internal functions
-
-    fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
-        let from_balance = self.balance_of(from);
-        if from_balance < amount {
-            self.env().revert(TokenError::InsufficientBalance);
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
         }
-
-        self.balances.set(&from, from_balance - amount);
-        let to_balance = self.balance_of(to);
-        self.balances.set(&to, to_balance + amount);
-
-        self.env().emit_event(Transfer {
-            from,
-            to,
-            value: amount,
-        });
     }
 
-    fn approve_internal(&mut self, owner: Address, spender: Address, amount: U256) {
-        self.allowances.set(&(owner, spender), amount);
-        self.env().emit_event(Approval {
-            owner,
-            spender,
-            value: amount,
-        });
-    }
+    // Internal functions
 
-    fn only_staking_manager(&self) {
-        let caller = self.env().caller();
-        let manager = self.staking_manager.get_or_revert_with(TokenError::InsufficientAllowance);
-        if caller != manager {
-            self.env().revert(TokenError::InsufficientAllowance); // Using existing error for unauthorized
-        }
+    fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
+        self.token.raw_transfer(from, to, amount);
+        self.notify_incentives(Some(from), Some(to));
     }
 
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(TokenError::InsufficientAllowance);
-        if caller != admin {
-            self.env().revert(TokenError::InsufficientAllowance); // Using existing error for unauthorized
+    /// Let the incentive manager refresh boost tiers and gas discounts
+    fn notify_incentives(&self, from: Option<Address>, to: Option<Address>) {
+        if let Some(manager) = self.incentive_manager.get_or_default() {
+            IncentiveManagerContractRef::new(self.env(), manager).on_scspr_transfer(from, to);
         }
     }
+
 }
@@ -0,0 +1,215 @@
+//! stCSPR Token - Rebasing wrapper around sCSPR
+//!
+//! sCSPR balances stay fixed while its exchange rate grows. stCSPR wraps
+//! sCSPR and reports balances in CSPR instead, so a holder's `balance_of`
+//! increases as rewards accrue. The `Cep18Base` ledger counts each holder's
+//! sCSPR shares; every amount in the CEP-18 interface, and in the events, is
+//! converted at the StakingManager exchange rate. Allowances are kept in
+//! stCSPR.
+
+use odra::prelude::*;
+use odra::casper_types::bytesrepr::Bytes;
+use odra::casper_types::{PublicKey, U256};
+use odra::ContractRef;
+use crate::events::{Burn, Mint, Transfer, TransferFrom};
+use crate::errors::TokenError;
+use crate::token::{Cep18Base, TokenMetadata};
+use super::events::{StCsprUnwrapped, StCsprWrapped};
+use super::scspr_token::ScsprTokenContractRef;
+use super::staking_manager::StakingManagerContractRef;
+
+/// stCSPR Token - Rebasing staked CSPR
+/// Balances are denominated in CSPR and grow with the sCSPR exchange rate.
+#[odra::module]
+pub struct StCsprToken {
+    /// CEP-18 ledger of sCSPR shares (allowances in stCSPR)
+    token: SubModule<Cep18Base>,
+    /// sCSPR token contract address
+    scspr_token: Var<Address>,
+    /// Staking manager contract address (source of the exchange rate)
+    staking_manager: Var<Address>,
+}
+
+#[odra::module]
+impl StCsprToken {
+    /// Initialize the stCSPR token
+    pub fn init(&mut self, scspr_token: Address, staking_manager: Address) {
+        self.token.init(String::from("Rebasing Staked CSPR"), String::from("stCSPR"), 18);
+        self.scspr_token.set(scspr_token);
+        self.staking_manager.set(staking_manager);
+    }
+
+    delegate! {
+        to self.token {
+            fn name(&self) -> String;
+            fn symbol(&self) -> String;
+            fn decimals(&self) -> u8;
+            fn allowance(&self, owner: Address, spender: Address) -> U256;
+            fn approve(&mut self, spender: Address, amount: U256) -> bool;
+            fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn permit(&mut self, owner: Address, spender: Address, value: U256, deadline: u64, public_key: PublicKey, signature: Bytes);
+            fn nonces(&self, owner: Address) -> u64;
+            fn domain_separator(&self) -> [u8; 32];
+            fn permit_digest(&self, owner: Address, spender: Address, value: U256, nonce: u64, deadline: u64) -> [u8; 32];
+            fn contract_version(&self) -> String;
+        }
+    }
+
+    /// Get the total supply (CSPR value of all wrapped sCSPR)
+    pub fn total_supply(&self) -> U256 {
+        self.get_amount_by_shares(self.total_shares())
+    }
+
+    /// Get the balance of an address (CSPR value of its wrapped sCSPR)
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.get_amount_by_shares(self.shares_of(owner))
+    }
+
+    /// Get everything a wallet needs to display the token
+    pub fn metadata(&self) -> TokenMetadata {
+        TokenMetadata {
+            total_supply: self.total_supply(),
+            ..self.token.metadata()
+        }
+    }
+
+    /// Get the total sCSPR shares held by the wrapper
+    pub fn total_shares(&self) -> U256 {
+        self.token.total_supply()
+    }
+
+    /// Get the sCSPR shares of an address
+    pub fn shares_of(&self, owner: Address) -> U256 {
+        self.token.balance_of(owner)
+    }
+
+    /// Get the stCSPR amount worth a given number of sCSPR shares
+    pub fn get_amount_by_shares(&self, shares: U256) -> U256 {
+        self.staking_manager_ref().get_cspr_by_scspr(shares)
+    }
+
+    /// Get the sCSPR shares backing a given stCSPR amount (rounded down)
+    pub fn get_shares_by_amount(&self, amount: U256) -> U256 {
+        self.staking_manager_ref().get_scspr_by_cspr(amount)
+    }
+
+    /// Transfer tokens to another address
+    ///
+    /// Moves the shares `amount` is worth, rounded down; the events report
+    /// the stCSPR those shares are worth.
+    pub fn transfer(&mut self, to: Address, amount: U256) -> bool {
+        let caller = self.env().caller();
+        let moved = self.move_amount(caller, to, amount);
+        self.env().emit_event(Transfer {
+            sender: caller,
+            recipient: to,
+            amount: moved,
+        });
+        true
+    }
+
+    /// Transfer tokens from one address to another (requires approval)
+    pub fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool {
+        let caller = self.env().caller();
+        let moved = self.move_amount(from, to, amount);
+        self.token.spend_allowance(from, caller, moved);
+        self.env().emit_event(TransferFrom {
+            spender: caller,
+            owner: from,
+            recipient: to,
+            amount: moved,
+        });
+        true
+    }
+
+    /// Wrap sCSPR into stCSPR (the caller must have approved the wrapper)
+    ///
+    /// # Returns
+    /// The amount of stCSPR credited
+    pub fn wrap(&mut self, scspr_amount: U256) -> U256 {
+        if scspr_amount.is_zero() {
+            self.env().revert(TokenError::ZeroShares);
+        }
+
+        let caller = self.env().caller();
+        let self_address = self.env().self_address();
+        self.scspr_token_ref().transfer_from(caller, self_address, scspr_amount);
+        self.token.credit(caller, scspr_amount);
+
+        let amount = self.get_amount_by_shares(scspr_amount);
+        self.env().emit_event(Mint {
+            recipient: caller,
+            amount,
+        });
+        self.env().emit_event(StCsprWrapped {
+            account: caller,
+            scspr_amount,
+            stcspr_amount: amount,
+            timestamp: self.env().get_block_time(),
+        });
+
+        amount
+    }
+
+    /// Unwrap stCSPR back into sCSPR
+    ///
+    /// # Returns
+    /// The amount of sCSPR returned
+    pub fn unwrap(&mut self, amount: U256) -> U256 {
+        let caller = self.env().caller();
+        let scspr_amount = self.shares_for(amount);
+        self.token.debit(caller, scspr_amount);
+        self.scspr_token_ref().transfer(caller, scspr_amount);
+
+        let amount = self.get_amount_by_shares(scspr_amount);
+        self.env().emit_event(Burn {
+            owner: caller,
+            amount,
+        });
+        self.env().emit_event(StCsprUnwrapped {
+            account: caller,
+            scspr_amount,
+            stcspr_amount: amount,
+            timestamp: self.env().get_block_time(),
+        });
+
+        scspr_amount
+    }
+
+    /// Get the sCSPR token address
+    pub fn get_scspr_token(&self) -> Address {
+        self.scspr_token.get_or_revert_with(TokenError::NotConfigured)
+    }
+
+    /// Get the staking manager address
+    pub fn get_staking_manager(&self) -> Address {
+        self.staking_manager.get_or_revert_with(TokenError::NotConfigured)
+    }
+
+    // Internal functions
+
+    fn scspr_token_ref(&self) -> ScsprTokenContractRef {
+        ScsprTokenContractRef::new(self.env(), self.get_scspr_token())
+    }
+
+    fn staking_manager_ref(&self) -> StakingManagerContractRef {
+        StakingManagerContractRef::new(self.env(), self.get_staking_manager())
+    }
+
+    /// Shares `amount` is worth, reverting if that rounds down to zero
+    fn shares_for(&self, amount: U256) -> U256 {
+        let shares = self.get_shares_by_amount(amount);
+        if shares.is_zero() {
+            self.env().revert(TokenError::ZeroShares);
+        }
+        shares
+    }
+
+    /// Move the shares `amount` is worth; returns the stCSPR they are worth
+    fn move_amount(&mut self, from: Address, to: Address, amount: U256) -> U256 {
+        let shares = self.shares_for(amount);
+        self.token.move_balance(from, to, shares);
+        self.get_amount_by_shares(shares)
+    }
+}
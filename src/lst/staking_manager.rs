@@ -7,12 +7,18 @@
 //! for liquid staking, providing a standardized interface for CSPR staking.
 
 use odra::prelude::*;
-use odra::casper_types::{U256, U512};
+use odra::casper_types::{PublicKey, U256, U512};
 use odra::ContractRef;
+use super::delegation::{DelegationBackend, Delegator, Redelegation, Undelegation};
 use super::errors::LstError;
 use super::events::*;
 use super::scspr_token::ScsprTokenContractRef;
-use crate::cep4626::{Cep4626Vault, Deposit as Cep4626Deposit, Withdraw as Cep4626Withdraw};
+use super::unstake_nft::UnstakeNftContractRef;
+use crate::incentives::referral_registry::ReferralRegistryContractRef;
+use odra::OdraError;
+use crate::cep4626::{base, Cep4626AsyncRedeem, Cep4626Hooks, Cep4626Vault, RedeemClaimed, RedeemRequest};
+use crate::math::{from_u512, to_u512, BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::access::{AccessControl, Role, ADMIN_ROLE, KEEPER_ROLE, ORACLE_ROLE, PAUSER_ROLE, REGISTRAR_ROLE};
 
 /// Represents an unstaking request
 #[odra::odra_type]
@@ -25,6 +31,29 @@ pub struct UnstakeRequest {
     pub withdrawable_at: u64,
     /// Whether the request has been processed
     pub processed: bool,
+    /// Whether the request was cancelled (and its sCSPR re-minted)
+    pub cancelled: bool,
+}
+
+/// Maximum protocol commission on staking rewards (20%)
+pub const MAX_PROTOCOL_FEE_BPS: u32 = 2_000;
+
+/// Maximum tip paid to the caller of `compound` (1% of the rewards)
+pub const MAX_COMPOUND_TIP_BPS: u32 = 100;
+
+/// Queue entries a withdrawal walks while funding the queue; longer
+/// backlogs are funded in batches with `process_withdrawal_queue`
+pub const MAX_REQUESTS_FUNDED_PER_CLAIM: u64 = 50;
+
+/// Strategy used to pick the validator that receives new stake
+#[odra::odra_type]
+pub enum AllocationStrategy {
+    /// Cycle through approved validators
+    RoundRobin,
+    /// Pick the validator furthest below its target weight
+    Weighted,
+    /// Fill validators in list order up to their caps
+    FillToCap,
 }
 
 /// Staking Manager contract
@@ -66,14 +95,128 @@ pub struct StakingManager {
     /// Next unstake request ID
     next_unstake_request_id: Var<u64>,
     
-    /// Contract admin
-    admin: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
     
     /// Whether the contract is paused
     paused: Var<bool>,
     
     /// Exchange rate scaling factor (1e18)
     exchange_rate_scale: Var<U256>,
+    
+    /// Validator public keys used for auction delegation
+    validator_public_keys: Mapping<Address, PublicKey>,
+    
+    /// CSPR currently unbonding per validator
+    pending_undelegations: Mapping<Address, U256>,
+    
+    /// Total CSPR currently unbonding
+    total_pending_undelegation: Var<U256>,
+    
+    /// Undelegations created by each unstake request: request_id -> Vec<Undelegation>
+    request_undelegations: Mapping<u64, Vec<Undelegation>>,
+    
+    /// CSPR that has finished unbonding and is reserved for funded unstake requests
+    unbonded_liquidity: Var<U256>,
+    
+    /// Next unstake request to fund; every open request below it is funded
+    withdrawal_queue_head: Var<u64>,
+    
+    /// Strategy used to allocate new stake across validators
+    allocation_strategy: Var<AllocationStrategy>,
+    
+    /// Target weight per validator (used by the Weighted strategy)
+    validator_weights: Mapping<Address, u32>,
+    
+    /// Maximum stake per validator (used by the FillToCap strategy, 0 = no cap)
+    validator_caps: Mapping<Address, U256>,
+    
+    /// Next validator index for the RoundRobin strategy
+    round_robin_index: Var<u32>,
+    
+    /// Admin override: when set, all new stake goes to this validator
+    override_validator: Var<Option<Address>>,
+    
+    /// Stake unbonding from one validator before being delegated to another
+    pending_redelegations: Var<Vec<Redelegation>>,
+    
+    /// Oracle allowed to report slashing (in addition to the admin)
+    slash_reporter: Var<Option<Address>>,
+    
+    /// Maximum slash per report, in bps of the validator's stake
+    max_slash_bps: Var<u32>,
+    
+    /// Protocol commission on rewards, in bps
+    protocol_fee_bps: Var<u32>,
+    
+    /// Treasury receiving the protocol commission as sCSPR
+    treasury: Var<Option<Address>>,
+    
+    /// NFT collection representing unstake requests, if enabled
+    unstake_nft: Var<Option<Address>>,
+    
+    /// Protocol referral registry notified of stakes, if enabled
+    referral_registry: Var<Option<Address>>,
+    
+    /// Maximum APR a reward sync may imply, in bps
+    max_reward_apr_bps: Var<u32>,
+    
+    /// Minimum seconds between reward syncs
+    min_reward_sync_interval: Var<u64>,
+    
+    /// Timestamp of the last reward sync
+    last_reward_sync: Var<u64>,
+    
+    /// Maximum total CSPR staked (0 = no cap)
+    deposit_cap: Var<U256>,
+    
+    /// Whether only allowlisted addresses may stake
+    allowlist_enabled: Var<bool>,
+    
+    /// Addresses allowed to stake while the allowlist is enabled
+    allowlist: Mapping<Address, bool>,
+    
+    /// Per-address CSPR deposit caps (0 = no cap)
+    address_deposit_caps: Mapping<Address, U256>,
+    
+    /// Net CSPR deposited per address, counted against its cap
+    address_deposits: Mapping<Address, U256>,
+    
+    /// Share of compounded rewards paid to the `compound` caller, in bps
+    compound_tip_bps: Var<u32>,
+    
+    /// Whether emergency exit mode is active (irreversible)
+    emergency_mode: Var<bool>,
+    
+    /// Timestamp after which emergency redemptions open
+    emergency_unlock_at: Var<u64>,
+    
+    /// Share of the protocol commission paid to referrers, in bps
+    referral_bps: Var<u32>,
+    
+    /// Referrer of each staker (set on their first referred stake)
+    referrers: Mapping<Address, Address>,
+    
+    /// sCSPR a staker minted through referred stakes
+    staker_referred_shares: Mapping<Address, U256>,
+    
+    /// sCSPR attributed to each referrer
+    referred_shares: Mapping<Address, U256>,
+    
+    /// Total sCSPR attributed to referrers
+    total_referred_shares: Var<U256>,
+    
+    /// Accumulated referral sCSPR per referred share (scaled by 1e18)
+    acc_referral_per_share: Var<U256>,
+    
+    /// Referral accumulator already accounted for per referrer
+    referral_debt: Mapping<Address, U256>,
+    
+    /// Settled, unclaimed referral sCSPR per referrer
+    referral_rewards: Mapping<Address, U256>,
+    
+    /// Contract delegations are routed through, if not the Casper auction
+    delegation_backend: Var<Option<Address>>,
 }
 
 #[odra::module]
@@ -91,159 +234,177 @@ impl StakingManager {
         self.minimum_stake.set(U256::from(100_000_000_000u64)); // 100 CSPR minimum (9 decimals)
         self.unstaking_period.set(57_600); // ~16 hours (7 eras)
         self.next_unstake_request_id.set(0);
-        self.admin.set(caller);
+        self.access.grant(ADMIN_ROLE, caller);
+        self.access.grant(PAUSER_ROLE, caller);
+        self.access.grant(REGISTRAR_ROLE, caller);
         self.paused.set(false);
         self.exchange_rate_scale.set(U256::from(1_000_000_000_000_000_000u128)); // 1e18
         self.validator_count.set(0);
+        self.allocation_strategy.set(AllocationStrategy::RoundRobin);
+        self.override_validator.set(None);
+        self.slash_reporter.set(None);
+        self.max_slash_bps.set(1_000); // 10% of a validator's stake per report
+        self.protocol_fee_bps.set(0);
+        self.treasury.set(None);
+        self.unstake_nft.set(None);
+        self.referral_registry.set(None);
+        self.max_reward_apr_bps.set(2_000); // 20% APR
+        self.min_reward_sync_interval.set(3_600); // 1 hour
+        self.last_reward_sync.set(self.env().get_block_time());
+        self.referral_bps.set(0);
+        self.deposit_cap.set(U256::zero());
+        self.allowlist_enabled.set(false);
+        self.compound_tip_bps.set(10); // 0.1%
+        self.emergency_mode.set(false);
     }
 
     /// Stake CSPR and receive sCSPR
     /// 
-    /// # Arguments
-    /// * `validator` - The validator address to delegate to
-    /// * `cspr_amount` - Amount of CSPR to stake
+    /// The CSPR attached to the call is staked. The validator is chosen by the
+    /// allocation strategy (or the admin override)
     /// 
     /// # Returns
     /// The amount of sCSPR minted
-    pub fn stake(&mut self, validator: Address, cspr_amount: U256) -> U256 {
-        self.ensure_not_paused();
-        
+    #[odra(payable)]
+    pub fn stake(&mut self) -> U256 {
+        let cspr_amount = self.attached_cspr();
         let caller = self.env().caller();
-        
-        // Validate amount
-        if cspr_amount == U256::zero() {
-            self.env().revert(LstError::InvalidAmount);
-        }
-        
-        let minimum = self.minimum_stake.get_or_default();
-        if cspr_amount < minimum {
-            self.env().revert(LstError::BelowMinimumStake);
+        self.stake_cspr(cspr_amount, caller)
+    }
+
+    /// Stake CSPR on behalf of a referrer
+    /// 
+    /// Stakes the attached CSPR like `stake`. The staker's first referrer is
+    /// recorded permanently. The sCSPR minted here earns the referrer
+    /// `referral_bps` of the protocol commission it generates, claimable with
+    /// `claim_referral_rewards`.
+    /// 
+    /// # Returns
+    /// The amount of sCSPR minted
+    #[odra(payable)]
+    pub fn stake_with_referral(&mut self, referrer: Address) -> U256 {
+        let caller = self.env().caller();
+        if referrer == caller {
+            self.env().revert(LstError::InvalidReferrer);
         }
         
-        // Validate validator
-        if !self.validators.get(&validator).unwrap_or(false) {
-            self.env().revert(LstError::InvalidValidator);
-        }
+        let referrer = match self.referrers.get(&caller) {
+            Some(existing) => existing,
+            None => {
+                self.referrers.set(&caller, referrer);
+                self.env().emit_event(ReferralRecorded {
+                    staker: caller,
+                    referrer,
+                    timestamp: self.env().get_block_time(),
+                });
+                referrer
+            }
+        };
         
-        // Calculate sCSPR amount based on current exchange rate
-        let scspr_amount = self.calculate_scspr_amount(cspr_amount);
+        let cspr_amount = self.attached_cspr();
+        let scspr_amount = self.stake_cspr(cspr_amount, caller);
         
-        // Update total staked
-        let current_total = self.total_cspr_staked.get_or_default();
-        self.total_cspr_staked.set(current_total + cspr_amount);
+        self.settle_referrer(referrer);
+        let staker_shares = self.staker_referred_shares.get(&caller).unwrap_or_default();
+        self.staker_referred_shares.set(&caller, staker_shares + scspr_amount);
+        let shares = self.referred_shares.get(&referrer).unwrap_or_default();
+        self.set_referred_shares(referrer, shares + scspr_amount);
+        let total = self.total_referred_shares.get_or_default();
+        self.total_referred_shares.set(total + scspr_amount);
         
-        // Update total sCSPR supply
-        let current_supply = self.total_scspr_supply.get_or_default();
-        self.total_scspr_supply.set(current_supply + scspr_amount);
+        scspr_amount
+    }
+
+    /// Claim accrued referral rewards (paid in sCSPR)
+    /// 
+    /// # Returns
+    /// The amount of sCSPR claimed
+    pub fn claim_referral_rewards(&mut self) -> U256 {
+        let caller = self.env().caller();
+        self.settle_referrer(caller);
         
-        // Update validator stake
-        let validator_stake = self.validator_stakes.get(&validator).unwrap_or_default();
-        self.validator_stakes.set(&validator, validator_stake + cspr_amount);
+        let amount = self.referral_rewards.get(&caller).unwrap_or_default();
+        if amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.referral_rewards.set(&caller, U256::zero());
         
-        // Mint sCSPR to the user
-        let token_address = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
+        let token_address = self.scspr_token_address.get_or_revert_with(LstError::RewardsDistributionFailed);
         let mut token = ScsprTokenContractRef::new(self.env(), token_address);
-        token.mint(caller, scspr_amount);
+        token.transfer(caller, amount);
         
-        // TODO: Actual delegation to Casper validator would happen here
-        // This would use Casper's native staking system calls
-        
-        // Emit event
-        let exchange_rate = self.get_exchange_rate();
-        let timestamp = self.env().get_block_time();
-        self.env().emit_event(Staked {
-            staker: caller,
-            cspr_amount,
-            scspr_amount,
-            validator,
-            exchange_rate,
-            timestamp,
+        self.env().emit_event(ReferralRewardsClaimed {
+            referrer: caller,
+            scspr_amount: amount,
+            timestamp: self.env().get_block_time(),
         });
         
-        scspr_amount
+        amount
     }
 
     /// Unstake sCSPR and initiate withdrawal
     /// 
+    /// Shorthand for `request_redeem(scspr_amount, caller, caller)`.
+    /// 
     /// # Arguments
     /// * `scspr_amount` - Amount of sCSPR to unstake
     /// 
     /// # Returns
     /// The unstake request ID
     pub fn unstake(&mut self, scspr_amount: U256) -> u64 {
-        self.ensure_not_paused();
-        
         let caller = self.env().caller();
-        
-        // Validate amount
-        if scspr_amount == U256::zero() {
-            self.env().revert(LstError::InvalidAmount);
-        }
-        
-        // Check user's sCSPR balance
-        let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
-        let token = ScsprTokenContractRef::new(self.env(), token_address);
-        let user_balance = token.balance_of(caller);
-        if user_balance < scspr_amount {
-            self.env().revert(LstError::InsufficientScsprBalance);
-        }
-        
-        // Calculate CSPR amount based on current exchange rate
-        let cspr_amount = self.calculate_cspr_amount(scspr_amount);
-        
-        // Burn sCSPR from user
-        let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
-        let mut token = ScsprTokenContractRef::new(self.env(), token_address);
-        token.burn(caller, scspr_amount);
-        
-        // Update total sCSPR supply
-        let current_supply = self.total_scspr_supply.get_or_default();
-        self.total_scspr_supply.set(current_supply - scspr_amount);
-        
-        // Create unstake request
-        let request_id = self.next_unstake_request_id.get_or_default();
-        let timestamp = self.env().get_block_time();
-        let unstaking_period = self.unstaking_period.get_or_default();
-        let withdrawable_at = timestamp + unstaking_period;
-        
-        let request = UnstakeRequest {
-            user: caller,
-            cspr_amount,
-            withdrawable_at,
-            processed: false,
-        };
-        
-        self.unstake_requests.set(&request_id, request);
-        
-        // Add to user's request list
-        let mut user_requests = self.user_unstake_requests.get(&caller).unwrap_or_default();
-        user_requests.push(request_id);
-        self.user_unstake_requests.set(&caller, user_requests);
-        
-        // Increment request ID
-        self.next_unstake_request_id.set(request_id + 1);
-        
-        // TODO: Actual undelegation from Casper validator would happen here
-        
-        // Emit event
-        let exchange_rate = self.get_exchange_rate();
-        self.env().emit_event(Unstaked {
-            unstaker: caller,
-            scspr_amount,
-            cspr_amount,
-            request_id,
-            exchange_rate,
-            withdrawable_at,
-        });
-        
-        request_id
+        self.request_redeem(scspr_amount, caller, caller)
     }
 
     /// Withdraw unstaked CSPR after the unstaking period
     /// 
+    /// Same as `claim_redeem`, kept for existing integrations.
+    /// 
     /// # Arguments
     /// * `request_id` - The unstake request ID
     pub fn withdraw_unstaked(&mut self, request_id: u64) {
+        self.claim_redeem(request_id);
+    }
+
+    /// Burn `owner`'s sCSPR into an unstake request controlled by `controller`
+    /// (CEP-4626 async redeem)
+    /// 
+    /// The CSPR owed is fixed at the current exchange rate. A caller other
+    /// than `owner` needs an sCSPR allowance.
+    /// 
+    /// # Returns
+    /// The unstake request ID
+    pub fn request_redeem(&mut self, shares: U256, controller: Address, owner: Address) -> u64 {
+        // Burns the sCSPR and opens the request in the withdraw hooks
+        let request_id = self.next_unstake_request_id.get_or_default();
+        base::redeem(self, shares, controller, owner);
+        request_id
+    }
+
+    /// CSPR owed to a request that is still unbonding or waiting for liquidity
+    pub fn pending_redeem_request(&self, request_id: u64) -> U256 {
+        match self.unstake_requests.get(&request_id) {
+            Some(request) if !request.processed && !self.is_request_claimable(request_id) => request.cspr_amount,
+            _ => U256::zero(),
+        }
+    }
+
+    /// CSPR owed to a request that its controller can claim now
+    pub fn claimable_redeem_request(&self, request_id: u64) -> U256 {
+        match self.unstake_requests.get(&request_id) {
+            Some(request) if !request.processed && self.is_request_claimable(request_id) => request.cspr_amount,
+            _ => U256::zero(),
+        }
+    }
+
+    /// Claim the CSPR of a matured, funded unstake request (CEP-4626 async redeem)
+    /// 
+    /// If the request has an unstake NFT, the current NFT owner claims
+    /// (and the NFT is burned); otherwise only the request's controller can.
+    /// 
+    /// # Returns
+    /// The amount of CSPR paid
+    pub fn claim_redeem(&mut self, request_id: u64) -> U256 {
         self.ensure_not_paused();
         
         let caller = self.env().caller();
@@ -253,7 +414,7 @@ impl StakingManager {
             .unwrap_or_else(|| self.env().revert(LstError::InvalidUnstakeRequestId));
         
         // Verify request belongs to caller
-        if request.user != caller {
+        if self.request_owner(request_id, &request) != caller {
             self.env().revert(LstError::Unauthorized);
         }
         
@@ -268,13 +429,21 @@ impl StakingManager {
             self.env().revert(LstError::UnstakingPeriodNotComplete);
         }
         
+        // Only requests funded from unbonded liquidity, in FIFO order, can be paid
+        self.fund_withdrawal_queue(MAX_REQUESTS_FUNDED_PER_CLAIM);
+        if request_id >= self.withdrawal_queue_head.get_or_default() {
+            self.env().revert(LstError::FundsStillUnbonding);
+        }
+        
         // Mark as processed
         request.processed = true;
         self.unstake_requests.set(&request_id, request.clone());
         
-        // Update total staked
-        let current_total = self.total_cspr_staked.get_or_default();
-        self.total_cspr_staked.set(current_total - request.cspr_amount);
+        // Release the request's reserved liquidity
+        let unbonded = self.unbonded_liquidity.get_or_default();
+        self.unbonded_liquidity.set(unbonded - request.cspr_amount);
+        
+        self.burn_unstake_nft(request_id);
         
         // Transfer CSPR to user
         let cspr_amount_u512 = U512::from(request.cspr_amount.as_u128());
@@ -288,58 +457,448 @@ impl StakingManager {
             request_id,
             timestamp,
         });
+        self.env().emit_event(RedeemClaimed {
+            controller: caller,
+            request_id,
+            assets: request.cspr_amount,
+        });
+        
+        request.cspr_amount
     }
 
-    /// Distribute staking rewards (called periodically by admin or keeper)
-    /// This updates the exchange rate based on accumulated rewards
+    /// Cancel a pending unstake request and get sCSPR back
     /// 
-    /// # Arguments
-    /// * `rewards_amount` - Amount of CSPR rewards earned
-    pub fn distribute_rewards(&mut self, rewards_amount: U256) {
-        self.only_admin();
+    /// Only possible before the request matures. sCSPR is re-minted at the
+    /// current exchange rate, and the unbonding CSPR is queued to be delegated
+    /// back to the same validators once it returns.
+    /// 
+    /// # Returns
+    /// The amount of sCSPR minted
+    pub fn cancel_unstake(&mut self, request_id: u64) -> U256 {
+        self.ensure_not_paused();
+        self.ensure_not_emergency();
         
-        if rewards_amount == U256::zero() {
-            return;
+        let caller = self.env().caller();
+        let mut request = self.unstake_requests.get(&request_id)
+            .unwrap_or_else(|| self.env().revert(LstError::InvalidUnstakeRequestId));
+        
+        if self.request_owner(request_id, &request) != caller {
+            self.env().revert(LstError::Unauthorized);
+        }
+        if request.processed {
+            self.env().revert(LstError::UnstakeRequestAlreadyProcessed);
+        }
+        if self.env().get_block_time() >= request.withdrawable_at {
+            self.env().revert(LstError::UnstakeRequestMatured);
         }
         
-        // Update total CSPR staked (includes rewards)
+        request.processed = true;
+        request.cancelled = true;
+        self.unstake_requests.set(&request_id, request.clone());
+        self.burn_unstake_nft(request_id);
+        
+        // Re-mint sCSPR at the current exchange rate
+        let scspr_amount = self.calculate_scspr_amount(request.cspr_amount);
+        let current_supply = self.total_scspr_supply.get_or_default();
+        self.total_scspr_supply.set(current_supply + scspr_amount);
         let current_total = self.total_cspr_staked.get_or_default();
-        let new_total = current_total + rewards_amount;
-        self.total_cspr_staked.set(new_total);
+        self.total_cspr_staked.set(current_total + request.cspr_amount);
         
-        // Calculate new exchange rate
-        let new_rate = self.get_exchange_rate();
-        let total_scspr = self.total_scspr_supply.get_or_default();
+        let token_address = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
+        let mut token = ScsprTokenContractRef::new(self.env(), token_address);
+        token.mint(caller, scspr_amount);
         
-        // Emit event
-        let timestamp = self.env().get_block_time();
-        self.env().emit_event(RewardsDistributed {
-            rewards_amount,
-            total_cspr_staked: new_total,
-            total_scspr_supply: total_scspr,
-            new_exchange_rate: new_rate,
-            timestamp,
+        // Unbonding cannot be stopped, so delegate the CSPR again once it returns
+        let mut redelegations = self.pending_redelegations.get_or_default();
+        for undelegation in self.request_undelegations.get(&request_id).unwrap_or_default() {
+            let pending = self.pending_undelegations.get(&undelegation.validator).unwrap_or_default();
+            self.pending_undelegations.set(&undelegation.validator, pending.saturating_sub(undelegation.amount));
+            let total_pending = self.total_pending_undelegation.get_or_default();
+            self.total_pending_undelegation.set(total_pending.saturating_sub(undelegation.amount));
+            
+            redelegations.push(Redelegation {
+                from_validator: undelegation.validator,
+                to_validator: undelegation.validator,
+                amount: undelegation.amount,
+                ready_at: request.withdrawable_at,
+            });
+        }
+        self.pending_redelegations.set(redelegations);
+        
+        self.env().emit_event(UnstakeCancelled {
+            user: caller,
+            request_id,
+            cspr_amount: request.cspr_amount,
+            scspr_amount,
+            timestamp: self.env().get_block_time(),
         });
+        base::emit_share_price(self);
+        
+        scspr_amount
     }
 
-    // View functions
-
-    /// Get the current exchange rate (sCSPR per CSPR, scaled by 1e18)
-    /// If no sCSPR exists, rate is 1:1
-    pub fn get_exchange_rate(&self) -> U256 {
-        let total_scspr = self.total_scspr_supply.get_or_default();
-        let total_cspr = self.total_cspr_staked.get_or_default();
+    /// Redeem sCSPR for a pro-rata share of the returned CSPR (emergency mode only)
+    /// 
+    /// Bypasses the unstake request queue. Opens once the emergency
+    /// undelegations have finished unbonding; CSPR owed to existing unstake
+    /// requests is left for them to withdraw. Any shortfall (e.g. from
+    /// slashing) is shared pro-rata, and the exchange rate is written down to
+    /// the CSPR left for the remaining holders.
+    /// 
+    /// # Returns
+    /// The amount of CSPR sent to the caller
+    pub fn emergency_redeem(&mut self, scspr_amount: U256) -> U256 {
+        if !self.emergency_mode.get_or_default() {
+            self.env().revert(LstError::EmergencyModeInactive);
+        }
+        if self.env().get_block_time() < self.emergency_unlock_at.get_or_default() {
+            self.env().revert(LstError::UnstakingPeriodNotComplete);
+        }
+        if scspr_amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
         
-        if total_scspr == U256::zero() || total_cspr == U256::zero() {
-            // Initial rate: 1 sCSPR = 1 CSPR (scaled by 1e18)
-            return self.exchange_rate_scale.get_or_default();
+        let caller = self.env().caller();
+        let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
+        let mut token = ScsprTokenContractRef::new(self.env(), token_address);
+        if token.balance_of(caller) < scspr_amount {
+            self.env().revert(LstError::InsufficientScsprBalance);
         }
         
-        // Rate = (total_scspr * 1e18) / total_cspr
-        // This gives us how much sCSPR per CSPR
-        let scale = self.exchange_rate_scale.get_or_default();
-        (total_scspr * scale) / total_cspr
-    }
+        // Pro-rata share of the CSPR held by the contract, net of unstake requests
+        let balance = U256::from(self.env().self_balance().as_u128());
+        let owed = self.total_pending_undelegation.get_or_default() + self.unbonded_liquidity.get_or_default();
+        let liquid = balance.saturating_sub(owed);
+        let total_supply = self.total_scspr_supply.get_or_default();
+        let cspr_amount = scspr_amount * liquid / total_supply;
+        
+        token.burn(caller, scspr_amount);
+        self.total_scspr_supply.set(total_supply - scspr_amount);
+        self.total_cspr_staked.set(liquid - cspr_amount);
+        self.release_referred_shares(caller, scspr_amount);
+        
+        self.env().transfer_tokens(&caller, &U512::from(cspr_amount.as_u128()));
+        
+        self.env().emit_event(EmergencyRedeemed {
+            holder: caller,
+            scspr_amount,
+            cspr_amount,
+            timestamp: self.env().get_block_time(),
+        });
+        base::emit_share_price(self);
+        
+        cspr_amount
+    }
+
+    /// Fund matured unstake requests from CSPR that has finished unbonding
+    /// 
+    /// Requests are funded strictly in request order; processing stops at the
+    /// first open request that is not mature or not yet covered by the
+    /// contract balance, or after `max_requests` queue entries. Withdrawals
+    /// call this automatically for up to `MAX_REQUESTS_FUNDED_PER_CLAIM`.
+    /// 
+    /// # Returns
+    /// The number of requests funded
+    pub fn process_withdrawal_queue(&mut self, max_requests: u64) -> u64 {
+        self.fund_withdrawal_queue(max_requests)
+    }
+
+    /// Distribute staking rewards (called periodically by a reward oracle or keeper)
+    /// This updates the exchange rate based on accumulated rewards
+    /// 
+    /// Syncs must be at least `min_reward_sync_interval` apart, and the rewards
+    /// may not exceed `max_reward_apr_bps` of the total stake pro-rated over
+    /// the time since the last sync. The rewards are recorded against the
+    /// validators' tracked stakes so `compound` does not count them again.
+    /// 
+    /// # Arguments
+    /// * `rewards_amount` - Amount of CSPR rewards earned
+    pub fn distribute_rewards(&mut self, rewards_amount: U256) {
+        self.access.require_any_role(&[ORACLE_ROLE, KEEPER_ROLE, ADMIN_ROLE], LstError::Unauthorized);
+        
+        self.check_reward_sync(rewards_amount);
+        self.record_validator_rewards(rewards_amount);
+        self.apply_rewards(rewards_amount);
+    }
+
+    /// Compound validator rewards into the exchange rate (permissionless)
+    /// 
+    /// Claims the rewards each validator's delegation has earned since it was
+    /// last tracked and adds them to the total stake. Rewards already reported
+    /// through `distribute_rewards` are part of the tracked stake and are not
+    /// counted again. Subject to the same interval and APR bounds as
+    /// `distribute_rewards`. The caller is tipped `compound_tip_bps` of the
+    /// rewards in sCSPR.
+    /// 
+    /// # Returns
+    /// The amount of CSPR rewards compounded
+    pub fn compound(&mut self) -> U256 {
+        self.ensure_not_paused();
+        self.ensure_not_emergency();
+        
+        let mut rewards = U256::zero();
+        for i in 0..self.validator_count.get_or_default() {
+            let validator = match self.validator_list.get(&i) {
+                Some(validator) => validator,
+                None => continue,
+            };
+            let public_key = match self.validator_public_keys.get(&validator) {
+                Some(public_key) => public_key,
+                None => continue,
+            };
+            
+            let stake = self.validator_stakes.get(&validator).unwrap_or_default();
+            let accrued = self.delegator().accrued_rewards(&public_key, to_u512(stake));
+            if !accrued.is_zero() {
+                let accrued = from_u512(accrued)
+                    .unwrap_or_else(|| self.env().revert(LstError::TotalStakedOverflow));
+                self.validator_stakes.set(&validator, stake + accrued);
+                rewards += accrued;
+            }
+        }
+        if rewards.is_zero() {
+            return rewards;
+        }
+        
+        self.check_reward_sync(rewards);
+        self.apply_rewards(rewards);
+        
+        // Tip the caller in sCSPR worth `tip_cspr` at the new rate
+        let caller = self.env().caller();
+        let tip_cspr = rewards * U256::from(self.compound_tip_bps.get_or_default()) / U256::from(BPS_DENOMINATOR);
+        let total_staked = self.total_cspr_staked.get_or_default();
+        let total_scspr = self.total_scspr_supply.get_or_default();
+        let tip_scspr = if total_scspr.is_zero() || tip_cspr.is_zero() {
+            U256::zero()
+        } else {
+            tip_cspr * total_scspr / (total_staked - tip_cspr)
+        };
+        if !tip_scspr.is_zero() {
+            self.total_scspr_supply.set(total_scspr + tip_scspr);
+            let token_address = self.scspr_token_address.get_or_revert_with(LstError::RewardsDistributionFailed);
+            let mut token = ScsprTokenContractRef::new(self.env(), token_address);
+            token.mint(caller, tip_scspr);
+            base::emit_share_price(self);
+        }
+        
+        self.env().emit_event(RewardsCompounded {
+            caller,
+            rewards_amount: rewards,
+            tip_scspr,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        rewards
+    }
+
+    /// Move stake from one validator to another (admin only)
+    /// 
+    /// The stake is undelegated now and delegated to `to_validator` once it has
+    /// unbonded, via `complete_redelegations`
+    pub fn rebalance(&mut self, from_validator: Address, to_validator: Address, amount: U256) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.rebalance_internal(from_validator, to_validator, amount);
+    }
+
+    /// Move stake toward the validators' target weights (keeper or admin)
+    /// 
+    /// Completes any matured redelegations, then moves stake from the most
+    /// overweight validator to the most underweight one. Validators that are
+    /// no longer approved have a target of zero.
+    pub fn auto_rebalance(&mut self) {
+        self.access.require_any_role(&[KEEPER_ROLE, ADMIN_ROLE], LstError::Unauthorized);
+        self.complete_redelegations();
+        
+        let validators = self.get_validators();
+        let total_weight: u64 = validators.iter()
+            .filter(|validator| self.is_validator_approved(**validator))
+            .map(|validator| self.get_validator_weight(*validator) as u64)
+            .sum();
+        if total_weight == 0 {
+            return;
+        }
+        let total_stake = validators.iter()
+            .fold(U256::zero(), |sum, validator| sum + self.get_validator_stake(*validator));
+        
+        let mut over: Option<(Address, U256)> = None;
+        let mut under: Option<(Address, U256)> = None;
+        for validator in validators.iter() {
+            let weight = if self.is_validator_approved(*validator) {
+                self.get_validator_weight(*validator)
+            } else {
+                0
+            };
+            let target = total_stake * U256::from(weight) / U256::from(total_weight);
+            let stake = self.get_validator_stake(*validator);
+            
+            let largest_excess = over.map(|(_, excess)| excess).unwrap_or_default();
+            if stake > target && stake - target > largest_excess {
+                over = Some((*validator, stake - target));
+            }
+            let largest_deficit = under.map(|(_, deficit)| deficit).unwrap_or_default();
+            if target > stake && weight > 0 && target - stake > largest_deficit {
+                under = Some((*validator, target - stake));
+            }
+        }
+        
+        if let (Some((from_validator, excess)), Some((to_validator, deficit))) = (over, under) {
+            self.rebalance_internal(from_validator, to_validator, excess.min(deficit));
+        }
+    }
+
+    /// Delegate stake from redelegations whose unbonding period has passed
+    pub fn complete_redelegations(&mut self) {
+        self.ensure_not_emergency();
+        let now = self.env().get_block_time();
+        let (ready, waiting): (Vec<Redelegation>, Vec<Redelegation>) = self.pending_redelegations
+            .get_or_default()
+            .into_iter()
+            .partition(|redelegation| redelegation.ready_at <= now);
+        self.pending_redelegations.set(waiting);
+        
+        for redelegation in ready {
+            let stake = self.validator_stakes.get(&redelegation.to_validator).unwrap_or_default();
+            self.validator_stakes.set(&redelegation.to_validator, stake + redelegation.amount);
+            self.delegate_to(redelegation.to_validator, redelegation.amount);
+        }
+    }
+
+    /// Report a slashing loss on a validator (slash reporter or admin)
+    /// 
+    /// Reduces the validator's stake and `total_cspr_staked`, so the loss is
+    /// shared by all sCSPR holders through the exchange rate
+    pub fn report_slash(&mut self, validator: Address, amount: U256) {
+        let caller = self.env().caller();
+        if self.slash_reporter.get_or_default() != Some(caller) {
+            self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        }
+        
+        if amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        
+        let validator_stake = self.validator_stakes.get(&validator).unwrap_or_default();
+        let max_slash = validator_stake * U256::from(self.max_slash_bps.get_or_default())
+            / U256::from(BPS_DENOMINATOR);
+        if amount > max_slash {
+            self.env().revert(LstError::SlashTooLarge);
+        }
+        
+        let validator_stake_after = validator_stake - amount;
+        self.validator_stakes.set(&validator, validator_stake_after);
+        
+        let total_cspr_staked = self.total_cspr_staked.get_or_default().saturating_sub(amount);
+        self.total_cspr_staked.set(total_cspr_staked);
+        
+        self.env().emit_event(SlashReported {
+            validator,
+            amount,
+            validator_stake_after,
+            total_cspr_staked,
+            new_exchange_rate: self.get_exchange_rate(),
+            reported_by: caller,
+            timestamp: self.env().get_block_time(),
+        });
+        base::emit_share_price(self);
+    }
+
+    // CEP-4626 entry points (forward to the `Cep4626Vault` implementation)
+
+    /// Stake the attached CSPR (`assets`) and mint sCSPR to `receiver`
+    #[odra(payable)]
+    pub fn deposit(&mut self, assets: U256, receiver: Address) -> U256 {
+        Cep4626Vault::deposit(self, assets, receiver)
+    }
+
+    /// Mint exactly `shares` sCSPR to `receiver`; the attached CSPR must be `preview_mint(shares)`
+    #[odra(payable)]
+    pub fn mint(&mut self, shares: U256, receiver: Address) -> U256 {
+        Cep4626Vault::mint(self, shares, receiver)
+    }
+
+    /// Burn `owner`'s sCSPR worth `assets` into an unstake request for `receiver`
+    pub fn withdraw(&mut self, assets: U256, receiver: Address, owner: Address) -> U256 {
+        Cep4626Vault::withdraw(self, assets, receiver, owner)
+    }
+
+    /// Burn exactly `shares` of `owner`'s sCSPR into an unstake request for `receiver`
+    pub fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
+        Cep4626Vault::redeem(self, shares, receiver, owner)
+    }
+
+    /// Total CSPR managed for sCSPR holders
+    pub fn total_assets(&self) -> U256 {
+        Cep4626Vault::total_assets(self)
+    }
+
+    /// Convert CSPR to sCSPR at the current exchange rate
+    pub fn convert_to_shares(&self, assets: U256) -> U256 {
+        Cep4626Vault::convert_to_shares(self, assets)
+    }
+
+    /// Convert sCSPR to CSPR at the current exchange rate
+    pub fn convert_to_assets(&self, shares: U256) -> U256 {
+        Cep4626Vault::convert_to_assets(self, shares)
+    }
+
+    /// Maximum CSPR `receiver` can stake right now
+    pub fn max_deposit(&self, receiver: Address) -> U256 {
+        Cep4626Vault::max_deposit(self, receiver)
+    }
+
+    /// Maximum sCSPR `receiver` can mint right now
+    pub fn max_mint(&self, receiver: Address) -> U256 {
+        Cep4626Vault::max_mint(self, receiver)
+    }
+
+    /// Maximum CSPR `owner` can unstake right now
+    pub fn max_withdraw(&self, owner: Address) -> U256 {
+        Cep4626Vault::max_withdraw(self, owner)
+    }
+
+    /// Maximum sCSPR `owner` can unstake right now
+    pub fn max_redeem(&self, owner: Address) -> U256 {
+        Cep4626Vault::max_redeem(self, owner)
+    }
+
+    /// sCSPR minted for staking `assets`
+    pub fn preview_deposit(&self, assets: U256) -> U256 {
+        Cep4626Vault::preview_deposit(self, assets)
+    }
+
+    /// CSPR needed to mint `shares`
+    pub fn preview_mint(&self, shares: U256) -> U256 {
+        Cep4626Vault::preview_mint(self, shares)
+    }
+
+    /// sCSPR burned to unstake `assets`
+    pub fn preview_withdraw(&self, assets: U256) -> U256 {
+        Cep4626Vault::preview_withdraw(self, assets)
+    }
+
+    /// CSPR owed for unstaking `shares`
+    pub fn preview_redeem(&self, shares: U256) -> U256 {
+        Cep4626Vault::preview_redeem(self, shares)
+    }
+
+    // View functions
+
+    /// Get the current exchange rate (sCSPR per CSPR, scaled by 1e18)
+    /// If no sCSPR exists, rate is 1:1
+    pub fn get_exchange_rate(&self) -> U256 {
+        let total_scspr = self.total_scspr_supply.get_or_default();
+        let total_cspr = self.total_cspr_staked.get_or_default();
+        
+        if total_scspr == U256::zero() || total_cspr == U256::zero() {
+            // Initial rate: 1 sCSPR = 1 CSPR (scaled by 1e18)
+            return self.exchange_rate_scale.get_or_default();
+        }
+        
+        // Rate = (total_scspr * 1e18) / total_cspr
+        // This gives us how much sCSPR per CSPR
+        let scale = self.exchange_rate_scale.get_or_default();
+        (total_scspr * scale) / total_cspr
+    }
 
     /// Get the amount of CSPR for a given amount of sCSPR
     pub fn get_cspr_by_scspr(&self, scspr_amount: U256) -> U256 {
@@ -351,6 +910,11 @@ impl StakingManager {
         self.calculate_scspr_amount(cspr_amount)
     }
 
+    /// Get the sCSPR token address
+    pub fn get_scspr_token(&self) -> Address {
+        self.scspr_token_address.get_or_revert_with(LstError::StakingFailed)
+    }
+
     /// Get total CSPR staked
     pub fn get_total_cspr_staked(&self) -> U256 {
         self.total_cspr_staked.get_or_default()
@@ -361,191 +925,1241 @@ impl StakingManager {
         self.total_scspr_supply.get_or_default()
     }
 
+    /// Get the CSPR that has finished unbonding and is reserved for withdrawals
+    pub fn get_unbonded_liquidity(&self) -> U256 {
+        self.unbonded_liquidity.get_or_default()
+    }
+
+    /// Get the next unstake request waiting to be funded
+    pub fn get_withdrawal_queue_head(&self) -> u64 {
+        self.withdrawal_queue_head.get_or_default()
+    }
+
+    /// Check if an unstake request is funded and ready to withdraw
+    pub fn is_withdrawal_funded(&self, request_id: u64) -> bool {
+        match self.unstake_requests.get(&request_id) {
+            Some(request) => !request.processed && request_id < self.withdrawal_queue_head.get_or_default(),
+            None => false,
+        }
+    }
+
     /// Get unstake request details
     pub fn get_unstake_request(&self, request_id: u64) -> Option<UnstakeRequest> {
         self.unstake_requests.get(&request_id)
     }
 
-    /// Get all unstake request IDs for a user
-    pub fn get_user_unstake_requests(&self, user: Address) -> Vec<u64> {
-        self.user_unstake_requests.get(&user).unwrap_or_default()
+    /// Get all unstake request IDs for a user
+    pub fn get_user_unstake_requests(&self, user: Address) -> Vec<u64> {
+        self.user_unstake_requests.get(&user).unwrap_or_default()
+    }
+
+    /// Get minimum stake amount
+    pub fn get_minimum_stake(&self) -> U256 {
+        self.minimum_stake.get_or_default()
+    }
+
+    /// Get unstaking period
+    pub fn get_unstaking_period(&self) -> u64 {
+        self.unstaking_period.get_or_default()
+    }
+
+    /// Check if a validator is approved
+    pub fn is_validator_approved(&self, validator: Address) -> bool {
+        self.validators.get(&validator).unwrap_or(false)
+    }
+
+    /// Get all approved validators
+    pub fn get_validators(&self) -> Vec<Address> {
+        let count = self.validator_count.get_or_default();
+        let mut validators = Vec::new();
+        for i in 0..count {
+            if let Some(validator) = self.validator_list.get(&i) {
+                validators.push(validator);
+            }
+        }
+        validators
+    }
+
+    /// Get stake amount for a validator
+    pub fn get_validator_stake(&self, validator: Address) -> U256 {
+        self.validator_stakes.get(&validator).unwrap_or_default()
+    }
+
+    /// Get the current allocation strategy
+    pub fn get_allocation_strategy(&self) -> AllocationStrategy {
+        self.allocation_strategy.get().unwrap_or(AllocationStrategy::RoundRobin)
+    }
+
+    /// Get a validator's target weight
+    pub fn get_validator_weight(&self, validator: Address) -> u32 {
+        self.validator_weights.get(&validator).unwrap_or_default()
+    }
+
+    /// Get a validator's stake cap (0 = no cap)
+    pub fn get_validator_cap(&self, validator: Address) -> U256 {
+        self.validator_caps.get(&validator).unwrap_or_default()
+    }
+
+    /// Get stake waiting to be delegated to a new validator
+    pub fn get_pending_redelegations(&self) -> Vec<Redelegation> {
+        self.pending_redelegations.get_or_default()
+    }
+
+    /// Get the protocol commission on rewards, in bps
+    pub fn get_protocol_fee_bps(&self) -> u32 {
+        self.protocol_fee_bps.get_or_default()
+    }
+
+    /// Get the treasury receiving the protocol commission, if set
+    pub fn get_treasury(&self) -> Option<Address> {
+        self.treasury.get_or_default()
+    }
+
+    /// Get the unstake request NFT collection, if enabled
+    pub fn get_unstake_nft(&self) -> Option<Address> {
+        self.unstake_nft.get_or_default()
+    }
+
+    /// Get the protocol referral registry notified of stakes, if enabled
+    pub fn get_referral_registry(&self) -> Option<Address> {
+        self.referral_registry.get_or_default()
+    }
+
+    /// Get the delegation backend contract, if not the Casper auction
+    pub fn get_delegation_backend(&self) -> Option<Address> {
+        self.delegation_backend.get_or_default()
+    }
+
+    /// Get the maximum APR a reward sync may imply, in bps
+    pub fn get_max_reward_apr_bps(&self) -> u32 {
+        self.max_reward_apr_bps.get_or_default()
+    }
+
+    /// Get the minimum seconds between reward syncs
+    pub fn get_min_reward_sync_interval(&self) -> u64 {
+        self.min_reward_sync_interval.get_or_default()
+    }
+
+    /// Get the timestamp of the last reward sync
+    pub fn get_last_reward_sync(&self) -> u64 {
+        self.last_reward_sync.get_or_default()
+    }
+
+    /// Get the largest reward amount a sync would accept right now
+    pub fn get_max_sync_rewards(&self) -> U256 {
+        let elapsed = self.env().get_block_time().saturating_sub(self.last_reward_sync.get_or_default());
+        self.max_sync_rewards(elapsed)
+    }
+
+    /// Get the maximum total CSPR staked (0 = no cap)
+    pub fn get_deposit_cap(&self) -> U256 {
+        self.deposit_cap.get_or_default()
+    }
+
+    /// Check if staking is restricted to allowlisted addresses
+    pub fn is_allowlist_enabled(&self) -> bool {
+        self.allowlist_enabled.get_or_default()
+    }
+
+    /// Check if an address is on the staking allowlist
+    pub fn is_allowlisted(&self, account: Address) -> bool {
+        self.allowlist.get(&account).unwrap_or(false)
+    }
+
+    /// Get an address's CSPR deposit cap (0 = no cap)
+    pub fn get_address_deposit_cap(&self, account: Address) -> U256 {
+        self.address_deposit_caps.get(&account).unwrap_or_default()
+    }
+
+    /// Get the net CSPR an address has deposited
+    pub fn get_address_deposits(&self, account: Address) -> U256 {
+        self.address_deposits.get(&account).unwrap_or_default()
+    }
+
+    /// Get the tip paid to `compound` callers, in bps
+    pub fn get_compound_tip_bps(&self) -> u32 {
+        self.compound_tip_bps.get_or_default()
+    }
+
+    /// Get the share of the protocol commission paid to referrers, in bps
+    pub fn get_referral_bps(&self) -> u32 {
+        self.referral_bps.get_or_default()
+    }
+
+    /// Get a staker's referrer, if any
+    pub fn get_referrer(&self, staker: Address) -> Option<Address> {
+        self.referrers.get(&staker)
+    }
+
+    /// Get the sCSPR attributed to a referrer
+    pub fn get_referred_shares(&self, referrer: Address) -> U256 {
+        self.referred_shares.get(&referrer).unwrap_or_default()
+    }
+
+    /// Get a referrer's claimable referral rewards (sCSPR)
+    pub fn pending_referral_rewards(&self, referrer: Address) -> U256 {
+        let settled = self.referral_rewards.get(&referrer).unwrap_or_default();
+        settled + self.unsettled_referral_rewards(referrer)
+    }
+
+    /// Get the slash reporter oracle, if set
+    pub fn get_slash_reporter(&self) -> Option<Address> {
+        self.slash_reporter.get_or_default()
+    }
+
+    /// Get the maximum slash per report, in bps of the validator's stake
+    pub fn get_max_slash_bps(&self) -> u32 {
+        self.max_slash_bps.get_or_default()
+    }
+
+    /// Get the admin override validator, if set
+    pub fn get_override_validator(&self) -> Option<Address> {
+        self.override_validator.get_or_default()
+    }
+
+    /// Get the public key used to delegate to a validator
+    pub fn get_validator_public_key(&self, validator: Address) -> Option<PublicKey> {
+        self.validator_public_keys.get(&validator)
+    }
+
+    /// Get the amount actually delegated to a validator in the auction system
+    pub fn get_delegated_amount(&self, validator: Address) -> U256 {
+        match self.validator_public_keys.get(&validator) {
+            Some(public_key) => U256::from(self.delegator().delegated_stake(&public_key).as_u128()),
+            None => U256::zero(),
+        }
+    }
+
+    /// Get CSPR currently unbonding from a validator
+    pub fn get_pending_undelegation(&self, validator: Address) -> U256 {
+        self.pending_undelegations.get(&validator).unwrap_or_default()
+    }
+
+    /// Get total CSPR currently unbonding
+    pub fn get_total_pending_undelegation(&self) -> U256 {
+        self.total_pending_undelegation.get_or_default()
+    }
+
+    /// Get the undelegations created by an unstake request
+    pub fn get_request_undelegations(&self, request_id: u64) -> Vec<Undelegation> {
+        self.request_undelegations.get(&request_id).unwrap_or_default()
+    }
+
+    // Admin functions
+
+    /// Add a validator to the approved list (registrar only)
+    pub fn add_validator(&mut self, validator: Address) {
+        self.access.require_role(REGISTRAR_ROLE, LstError::Unauthorized);
+        
+        if !self.validators.get(&validator).unwrap_or(false) {
+            self.validators.set(&validator, true);
+            let count = self.validator_count.get_or_default();
+            self.validator_list.set(&count, validator);
+            self.validator_count.set(count + 1);
+            
+            let timestamp = self.env().get_block_time();
+            self.env().emit_event(ValidatorAdded {
+                validator,
+                added_by: self.env().caller(),
+                timestamp,
+            });
+        }
+    }
+
+    /// Set the public key used to delegate to a validator (registrar only)
+    pub fn set_validator_public_key(&mut self, validator: Address, public_key: PublicKey) {
+        self.access.require_role(REGISTRAR_ROLE, LstError::Unauthorized);
+        self.validator_public_keys.set(&validator, public_key);
+    }
+
+    /// Set the strategy used to allocate new stake
+    pub fn set_allocation_strategy(&mut self, strategy: AllocationStrategy) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.allocation_strategy.set(strategy);
+    }
+
+    /// Set a validator's target weight for the Weighted strategy
+    pub fn set_validator_weight(&mut self, validator: Address, weight: u32) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.validator_weights.set(&validator, weight);
+    }
+
+    /// Set a validator's stake cap for the FillToCap strategy (0 = no cap)
+    pub fn set_validator_cap(&mut self, validator: Address, cap: U256) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.validator_caps.set(&validator, cap);
+    }
+
+    /// Set the protocol commission on rewards (at most MAX_PROTOCOL_FEE_BPS)
+    pub fn set_protocol_fee(&mut self, fee_bps: u32, treasury: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        if fee_bps > MAX_PROTOCOL_FEE_BPS {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.protocol_fee_bps.set(fee_bps);
+        self.treasury.set(treasury);
+    }
+
+    /// Enable (or disable with `None`) unstake request NFTs
+    /// 
+    /// Requests created while disabled stay claimable by the original unstaker.
+    /// The NFT contract cannot be changed while any of its NFTs are
+    /// outstanding, since their requests are claimed through it.
+    pub fn set_unstake_nft(&mut self, unstake_nft: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        let current = self.unstake_nft.get_or_default();
+        if let Some(nft_address) = current {
+            if unstake_nft != current
+                && UnstakeNftContractRef::new(self.env(), nft_address).total_supply() > 0
+            {
+                self.env().revert(LstError::UnstakeNftsOutstanding);
+            }
+        }
+        self.unstake_nft.set(unstake_nft);
+    }
+
+    /// Enable (or disable with `None`) reporting stakes to the protocol referral registry
+    /// 
+    /// The registry binds a staker's referral on their first interaction; the
+    /// sCSPR referral program of `stake_with_referral` is separate.
+    pub fn set_referral_registry(&mut self, referral_registry: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.referral_registry.set(referral_registry);
+    }
+
+    /// Route delegations through a backend contract, or the Casper auction with `None`
+    /// 
+    /// Only possible while nothing is staked or unbonding, since stake held
+    /// by the old backend could not be undelegated through the new one.
+    pub fn set_delegation_backend(&mut self, backend: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        if !self.total_cspr_staked.get_or_default().is_zero()
+            || !self.total_pending_undelegation.get_or_default().is_zero()
+        {
+            self.env().revert(LstError::DelegationsOutstanding);
+        }
+        self.delegation_backend.set(backend);
+    }
+
+    /// Set the reward sync bounds (max implied APR in bps, min seconds between syncs)
+    pub fn set_reward_sync_bounds(&mut self, max_apr_bps: u32, min_interval: u64) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        if max_apr_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.max_reward_apr_bps.set(max_apr_bps);
+        self.min_reward_sync_interval.set(min_interval);
+    }
+
+    /// Set the maximum total CSPR staked (0 = no cap)
+    pub fn set_deposit_cap(&mut self, cap: U256) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.deposit_cap.set(cap);
+        
+        self.env().emit_event(DepositCapUpdated {
+            cap,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Restrict staking to allowlisted addresses, or open it to everyone (admin only)
+    pub fn set_allowlist_enabled(&mut self, enabled: bool) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.allowlist_enabled.set(enabled);
+        
+        self.env().emit_event(AllowlistModeUpdated {
+            enabled,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Add or remove an address from the staking allowlist (registrar only)
+    pub fn set_allowlisted(&mut self, account: Address, allowed: bool) {
+        self.access.require_role(REGISTRAR_ROLE, LstError::Unauthorized);
+        self.allowlist.set(&account, allowed);
+        
+        self.env().emit_event(AllowlistUpdated {
+            account,
+            allowed,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Set an address's CSPR deposit cap (0 = no cap)
+    pub fn set_address_deposit_cap(&mut self, account: Address, cap: U256) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.address_deposit_caps.set(&account, cap);
+        
+        self.env().emit_event(AddressDepositCapUpdated {
+            account,
+            cap,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Set the tip paid to `compound` callers (at most MAX_COMPOUND_TIP_BPS)
+    pub fn set_compound_tip_bps(&mut self, tip_bps: u32) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        if tip_bps > MAX_COMPOUND_TIP_BPS {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.compound_tip_bps.set(tip_bps);
+    }
+
+    /// Set the share of the protocol commission paid to referrers, in bps
+    pub fn set_referral_bps(&mut self, referral_bps: u32) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        if referral_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.referral_bps.set(referral_bps);
+    }
+
+    /// Set the oracle allowed to report slashing
+    pub fn set_slash_reporter(&mut self, reporter: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.slash_reporter.set(reporter);
+    }
+
+    /// Set the maximum slash per report, in bps of the validator's stake
+    pub fn set_max_slash_bps(&mut self, max_slash_bps: u32) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        if max_slash_bps as u128 > BPS_DENOMINATOR {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        self.max_slash_bps.set(max_slash_bps);
+    }
+
+    /// Force all new stake to one validator, or clear the override with `None`
+    pub fn set_override_validator(&mut self, validator: Option<Address>) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        if let Some(validator) = validator {
+            if !self.validators.get(&validator).unwrap_or(false) {
+                self.env().revert(LstError::InvalidValidator);
+            }
+        }
+        self.override_validator.set(validator);
+    }
+
+    /// Remove a validator from the approved list (registrar only)
+    pub fn remove_validator(&mut self, validator: Address) {
+        self.access.require_role(REGISTRAR_ROLE, LstError::Unauthorized);
+        
+        if self.validators.get(&validator).unwrap_or(false) {
+            self.validators.set(&validator, false);
+            // Note: We don't remove from validator_list to keep indices stable
+            // The validator is just marked as not approved
+            
+            let timestamp = self.env().get_block_time();
+            self.env().emit_event(ValidatorRemoved {
+                validator,
+                removed_by: self.env().caller(),
+                timestamp,
+            });
+        }
+    }
+
+    /// Update minimum stake amount
+    pub fn set_minimum_stake(&mut self, new_minimum: U256) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        let old_minimum = self.minimum_stake.get_or_default();
+        self.minimum_stake.set(new_minimum);
+        
+        self.env().emit_event(MinimumStakeUpdated {
+            old_minimum,
+            new_minimum,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Update unstaking period
+    pub fn set_unstaking_period(&mut self, new_period: u64) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        let old_period = self.unstaking_period.get_or_default();
+        self.unstaking_period.set(new_period);
+        
+        self.env().emit_event(UnstakingPeriodUpdated {
+            old_period,
+            new_period,
+            updated_by: self.env().caller(),
+        });
+    }
+
+    /// Pause the contract
+    pub fn pause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, LstError::Unauthorized);
+        self.paused.set(true);
+        
+        let timestamp = self.env().get_block_time();
+        self.env().emit_event(ContractPaused {
+            paused_by: self.env().caller(),
+            timestamp,
+        });
+    }
+
+    /// Unpause the contract
+    pub fn unpause(&mut self) {
+        self.access.require_role(PAUSER_ROLE, LstError::Unauthorized);
+        self.paused.set(false);
+        
+        let timestamp = self.env().get_block_time();
+        self.env().emit_event(ContractUnpaused {
+            unpaused_by: self.env().caller(),
+            timestamp,
+        });
+    }
+
+    /// Enter emergency exit mode (admin only, irreversible)
+    /// 
+    /// Disables staking, undelegates everything from every validator and
+    /// opens `emergency_redeem` once the unbonding period has passed.
+    pub fn enable_emergency_mode(&mut self) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.ensure_not_emergency();
+        
+        let mut undelegated = U256::zero();
+        for i in 0..self.validator_count.get_or_default() {
+            let validator = match self.validator_list.get(&i) {
+                Some(validator) => validator,
+                None => continue,
+            };
+            self.validator_stakes.set(&validator, U256::zero());
+            
+            if let Some(public_key) = self.validator_public_keys.get(&validator) {
+                let delegated = self.delegator().delegated_stake(&public_key);
+                if !delegated.is_zero() {
+                    self.delegator().undelegate_stake(&public_key, delegated);
+                    undelegated += U256::from(delegated.as_u128());
+                }
+            }
+        }
+        
+        // Redelegations will never happen; their CSPR is returned with the rest
+        self.pending_redelegations.set(Vec::new());
+        
+        let timestamp = self.env().get_block_time();
+        let unlock_at = timestamp + self.unstaking_period.get_or_default();
+        self.emergency_mode.set(true);
+        self.emergency_unlock_at.set(unlock_at);
+        
+        self.env().emit_event(EmergencyModeEnabled {
+            undelegated,
+            unlock_at,
+            enabled_by: self.env().caller(),
+            timestamp,
+        });
+    }
+
+    /// Check if emergency exit mode is active
+    pub fn is_emergency_mode(&self) -> bool {
+        self.emergency_mode.get_or_default()
+    }
+
+    /// Get the timestamp after which emergency redemptions open
+    pub fn get_emergency_unlock_at(&self) -> u64 {
+        self.emergency_unlock_at.get_or_default()
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.get_or_default()
+    }
+
+    // Access control
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // Internal helper functions
+
+    fn calculate_scspr_amount(&self, cspr_amount: U256) -> U256 {
+        base::convert_to_shares(self, cspr_amount)
+    }
+
+    fn calculate_cspr_amount(&self, scspr_amount: U256) -> U256 {
+        base::convert_to_assets(self, scspr_amount)
+    }
+
+    /// Pick the validator for new stake using the override or allocation strategy
+    fn select_validator(&mut self, cspr_amount: U256) -> Address {
+        if let Some(validator) = self.override_validator.get_or_default() {
+            return validator;
+        }
+        
+        let approved: Vec<Address> = self.get_validators()
+            .into_iter()
+            .filter(|validator| self.validators.get(validator).unwrap_or(false))
+            .collect();
+        if approved.is_empty() {
+            self.env().revert(LstError::InvalidValidator);
+        }
+        
+        match self.get_allocation_strategy() {
+            AllocationStrategy::RoundRobin => {
+                let index = self.round_robin_index.get_or_default();
+                self.round_robin_index.set(index.wrapping_add(1));
+                approved[index as usize % approved.len()]
+            }
+            AllocationStrategy::Weighted => {
+                let total_weight: u64 = approved.iter()
+                    .map(|validator| self.get_validator_weight(*validator) as u64)
+                    .sum();
+                if total_weight == 0 {
+                    return approved[0];
+                }
+                
+                // Pick the validator with the largest shortfall against its target
+                let total_after: U256 = approved.iter()
+                    .fold(cspr_amount, |sum, validator| sum + self.get_validator_stake(*validator));
+                let mut best = approved[0];
+                let mut best_shortfall = U256::zero();
+                for validator in approved.iter() {
+                    let target = total_after * U256::from(self.get_validator_weight(*validator))
+                        / U256::from(total_weight);
+                    let shortfall = target.saturating_sub(self.get_validator_stake(*validator));
+                    if shortfall > best_shortfall {
+                        best = *validator;
+                        best_shortfall = shortfall;
+                    }
+                }
+                best
+            }
+            AllocationStrategy::FillToCap => {
+                for validator in approved.iter() {
+                    let cap = self.get_validator_cap(*validator);
+                    if cap.is_zero() || self.get_validator_stake(*validator) + cspr_amount <= cap {
+                        return *validator;
+                    }
+                }
+                self.env().revert(LstError::ValidatorDelegationLimitReached)
+            }
+        }
+    }
+
+    /// Add rewards to the staked total, taking the protocol commission
+    /// 
+    /// The commission is paid by minting sCSPR to the treasury worth
+    /// `protocol_fee_bps` of the rewards at the post-reward exchange rate
+    fn apply_rewards(&mut self, rewards_amount: U256) {
+        if rewards_amount == U256::zero() {
+            return;
+        }
+        
+        // Update total CSPR staked (includes rewards)
+        let current_total = self.total_cspr_staked.get_or_default();
+        let new_total = current_total + rewards_amount;
+        self.total_cspr_staked.set(new_total);
+        
+        // Take the protocol commission
+        let fee_bps = self.protocol_fee_bps.get_or_default();
+        if let Some(treasury) = self.treasury.get_or_default().filter(|_| fee_bps > 0) {
+            let fee_cspr = rewards_amount * U256::from(fee_bps) / U256::from(BPS_DENOMINATOR);
+            let total_scspr = self.total_scspr_supply.get_or_default();
+            
+            // shares such that shares / (supply + shares) = fee / total
+            let fee_scspr = if total_scspr.is_zero() || fee_cspr.is_zero() {
+                U256::zero()
+            } else {
+                fee_cspr * total_scspr / (new_total - fee_cspr)
+            };
+            
+            if !fee_scspr.is_zero() {
+                self.total_scspr_supply.set(total_scspr + fee_scspr);
+                let token_address = self.scspr_token_address.get_or_revert_with(LstError::RewardsDistributionFailed);
+                let mut token = ScsprTokenContractRef::new(self.env(), token_address);
+                
+                // Referrers get their share of the commission earned by referred sCSPR
+                let referral_scspr = self.accrue_referral_rewards(fee_scspr, total_scspr);
+                if !referral_scspr.is_zero() {
+                    token.mint(self.env().self_address(), referral_scspr);
+                }
+                token.mint(treasury, fee_scspr - referral_scspr);
+                
+                self.env().emit_event(ProtocolFeeAccrued {
+                    treasury,
+                    fee_cspr,
+                    fee_scspr,
+                    timestamp: self.env().get_block_time(),
+                });
+            }
+        }
+        
+        // Calculate new exchange rate
+        let new_rate = self.get_exchange_rate();
+        let total_scspr = self.total_scspr_supply.get_or_default();
+        
+        // Emit event
+        let timestamp = self.env().get_block_time();
+        self.env().emit_event(RewardsDistributed {
+            rewards_amount,
+            total_cspr_staked: new_total,
+            total_scspr_supply: total_scspr,
+            new_exchange_rate: new_rate,
+            timestamp,
+        });
+        base::emit_share_price(self);
+    }
+
+    /// Carve the referrers' share out of a commission and update the accumulator
+    /// 
+    /// Returns the sCSPR (out of `fee_scspr`) reserved for referrers.
+    fn accrue_referral_rewards(&mut self, fee_scspr: U256, supply_before_fee: U256) -> U256 {
+        let referral_bps = self.referral_bps.get_or_default();
+        let total_referred = self.total_referred_shares.get_or_default().min(supply_before_fee);
+        if referral_bps == 0 || total_referred.is_zero() {
+            return U256::zero();
+        }
+        
+        let referral_scspr = fee_scspr * U256::from(referral_bps) * total_referred
+            / (U256::from(BPS_DENOMINATOR) * supply_before_fee);
+        if referral_scspr.is_zero() {
+            return U256::zero();
+        }
+        
+        let scale = self.exchange_rate_scale.get_or_default();
+        let acc = self.acc_referral_per_share.get_or_default();
+        self.acc_referral_per_share.set(acc + referral_scspr * scale / total_referred);
+        referral_scspr
+    }
+
+    /// Referral rewards earned since the referrer was last settled
+    fn unsettled_referral_rewards(&self, referrer: Address) -> U256 {
+        let shares = self.referred_shares.get(&referrer).unwrap_or_default();
+        let scale = self.exchange_rate_scale.get_or_default();
+        let earned = shares * self.acc_referral_per_share.get_or_default() / scale;
+        earned.saturating_sub(self.referral_debt.get(&referrer).unwrap_or_default())
+    }
+
+    /// Move a referrer's unsettled rewards into their claimable balance
+    fn settle_referrer(&mut self, referrer: Address) {
+        let unsettled = self.unsettled_referral_rewards(referrer);
+        if !unsettled.is_zero() {
+            let settled = self.referral_rewards.get(&referrer).unwrap_or_default();
+            self.referral_rewards.set(&referrer, settled + unsettled);
+        }
+        let shares = self.referred_shares.get(&referrer).unwrap_or_default();
+        self.set_referred_shares(referrer, shares);
+    }
+
+    /// Update a referrer's shares and reset their accumulator debt
+    fn set_referred_shares(&mut self, referrer: Address, shares: U256) {
+        let scale = self.exchange_rate_scale.get_or_default();
+        self.referred_shares.set(&referrer, shares);
+        self.referral_debt.set(&referrer, shares * self.acc_referral_per_share.get_or_default() / scale);
+    }
+
+    /// Stop attributing unstaked sCSPR to the staker's referrer
+    fn release_referred_shares(&mut self, staker: Address, scspr_amount: U256) {
+        let referrer = match self.referrers.get(&staker) {
+            Some(referrer) => referrer,
+            None => return,
+        };
+        let staker_shares = self.staker_referred_shares.get(&staker).unwrap_or_default();
+        let released = staker_shares.min(scspr_amount);
+        if released.is_zero() {
+            return;
+        }
+        
+        self.settle_referrer(referrer);
+        self.staker_referred_shares.set(&staker, staker_shares - released);
+        let shares = self.referred_shares.get(&referrer).unwrap_or_default();
+        self.set_referred_shares(referrer, shares.saturating_sub(released));
+        let total = self.total_referred_shares.get_or_default();
+        self.total_referred_shares.set(total.saturating_sub(released));
+    }
+
+    /// Open an unstake request for sCSPR already burned from `owner`, paying
+    /// `receiver` once the CSPR has unbonded
+    fn open_unstake_request(
+        &mut self,
+        owner: Address,
+        receiver: Address,
+        scspr_amount: U256,
+        cspr_amount: U256,
+    ) -> u64 {
+        // Unstaked sCSPR stops earning referral rewards
+        self.release_referred_shares(owner, scspr_amount);
+        
+        // Free up the owner's per-address deposit cap
+        let deposited = self.address_deposits.get(&owner).unwrap_or_default();
+        self.address_deposits.set(&owner, deposited.saturating_sub(cspr_amount));
+        
+        // The CSPR is owed to the request from now on, so it stops backing
+        // the remaining sCSPR
+        let current_total = self.total_cspr_staked.get_or_default();
+        self.total_cspr_staked.set(current_total - cspr_amount);
+        
+        // Create unstake request
+        let request_id = self.next_unstake_request_id.get_or_default();
+        let timestamp = self.env().get_block_time();
+        let unstaking_period = self.unstaking_period.get_or_default();
+        let withdrawable_at = timestamp + unstaking_period;
+        
+        let request = UnstakeRequest {
+            user: receiver,
+            cspr_amount,
+            withdrawable_at,
+            processed: false,
+            cancelled: false,
+        };
+        
+        self.unstake_requests.set(&request_id, request);
+        
+        // Add to the receiver's request list
+        let mut user_requests = self.user_unstake_requests.get(&receiver).unwrap_or_default();
+        user_requests.push(request_id);
+        self.user_unstake_requests.set(&receiver, user_requests);
+        
+        // Increment request ID
+        self.next_unstake_request_id.set(request_id + 1);
+        
+        // Represent the request as a transferable NFT
+        if let Some(nft_address) = self.unstake_nft.get_or_default() {
+            UnstakeNftContractRef::new(self.env(), nft_address).mint(receiver, request_id);
+        }
+        
+        // Undelegate the CSPR from validators; it unbonds during the unstaking period
+        let undelegations = self.undelegate_for(request_id, cspr_amount);
+        self.request_undelegations.set(&request_id, undelegations);
+        
+        // Emit event
+        let exchange_rate = self.get_exchange_rate();
+        self.env().emit_event(Unstaked {
+            unstaker: owner,
+            scspr_amount,
+            cspr_amount,
+            request_id,
+            exchange_rate,
+            withdrawable_at,
+        });
+        self.env().emit_event(RedeemRequest {
+            controller: receiver,
+            owner,
+            request_id,
+            sender: self.env().caller(),
+            shares: scspr_amount,
+            assets: cspr_amount,
+        });
+        
+        request_id
+    }
+
+    /// Stake `cspr_amount` of CSPR already received by the contract, minting sCSPR to `receiver`
+    fn stake_cspr(&mut self, cspr_amount: U256, receiver: Address) -> U256 {
+        // Calculate sCSPR amount based on current exchange rate
+        let scspr_amount = self.calculate_scspr_amount(cspr_amount);
+        self.stake_cspr_for_shares(cspr_amount, scspr_amount, receiver)
+    }
+
+    /// Stake `cspr_amount` of CSPR already received by the contract, minting
+    /// exactly `scspr_amount` sCSPR to `receiver`
+    fn stake_cspr_for_shares(&mut self, cspr_amount: U256, scspr_amount: U256, receiver: Address) -> U256 {
+        self.ensure_not_paused();
+        self.ensure_not_emergency();
+        
+        // Validate amount
+        if cspr_amount == U256::zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        
+        let minimum = self.minimum_stake.get_or_default();
+        if cspr_amount < minimum {
+            self.env().revert(LstError::BelowMinimumStake);
+        }
+        
+        if self.allowlist_enabled.get_or_default() && !self.is_allowlisted(receiver) {
+            self.env().revert(LstError::NotAllowlisted);
+        }
+        
+        if cspr_amount > self.deposit_headroom() {
+            self.env().revert(LstError::DepositCapExceeded);
+        }
+        
+        if cspr_amount > self.address_deposit_headroom(receiver) {
+            self.env().revert(LstError::AddressDepositCapExceeded);
+        }
+        
+        // Select and validate validator
+        let validator = self.select_validator(cspr_amount);
+        if !self.validators.get(&validator).unwrap_or(false) {
+            self.env().revert(LstError::InvalidValidator);
+        }
+        
+        // Update total staked
+        let current_total = self.total_cspr_staked.get_or_default();
+        self.total_cspr_staked.set(current_total + cspr_amount);
+        
+        // Update validator stake
+        let validator_stake = self.validator_stakes.get(&validator).unwrap_or_default();
+        self.validator_stakes.set(&validator, validator_stake + cspr_amount);
+        
+        let deposited = self.address_deposits.get(&receiver).unwrap_or_default();
+        self.address_deposits.set(&receiver, deposited + cspr_amount);
+        
+        // Mint sCSPR to the receiver
+        base::deposit_exact(self, receiver, cspr_amount, U256::zero(), scspr_amount);
+        
+        // Delegate the CSPR to the validator through the auction system
+        self.delegate_to(validator, cspr_amount);
+        
+        if let Some(registry) = self.referral_registry.get_or_default() {
+            ReferralRegistryContractRef::new(self.env(), registry).record_interaction(receiver);
+        }
+        
+        // Emit event
+        let exchange_rate = self.get_exchange_rate();
+        let timestamp = self.env().get_block_time();
+        self.env().emit_event(Staked {
+            staker: receiver,
+            cspr_amount,
+            scspr_amount,
+            validator,
+            exchange_rate,
+            timestamp,
+        });
+        
+        scspr_amount
+    }
+
+    /// Queue head once matured requests the purse covers are funded, in order,
+    /// walking at most `max_requests` queue entries
+    fn fundable_queue_head(&self, max_requests: u64) -> u64 {
+        let now = self.env().get_block_time();
+        let mut head = self.withdrawal_queue_head.get_or_default();
+        let end = self.next_unstake_request_id.get_or_default()
+            .min(head.saturating_add(max_requests));
+        
+        // CSPR in the purse not already reserved for withdrawals or redelegations
+        let redelegating = self.pending_redelegations.get_or_default()
+            .iter()
+            .filter(|redelegation| redelegation.ready_at <= now)
+            .fold(U256::zero(), |total, redelegation| total + redelegation.amount);
+        let balance = U256::from(self.env().self_balance().as_u128());
+        let mut available = balance.saturating_sub(self.unbonded_liquidity.get_or_default() + redelegating);
+        
+        while head < end {
+            let request = match self.unstake_requests.get(&head) {
+                Some(request) => request,
+                None => break,
+            };
+            if !request.processed {
+                if now < request.withdrawable_at || available < request.cspr_amount {
+                    break;
+                }
+                available -= request.cspr_amount;
+            }
+            head += 1;
+        }
+        head
     }
 
-    /// Get minimum stake amount
-    pub fn get_minimum_stake(&self) -> U256 {
-        self.minimum_stake.get_or_default()
+    /// Whether a request is funded, or would be by the next `fund_withdrawal_queue`
+    fn is_request_claimable(&self, request_id: u64) -> bool {
+        let head = self.withdrawal_queue_head.get_or_default();
+        request_id < head || request_id < self.fundable_queue_head(request_id + 1 - head)
     }
 
-    /// Get unstaking period
-    pub fn get_unstaking_period(&self) -> u64 {
-        self.unstaking_period.get_or_default()
+    /// Move matured, covered requests from unbonding into unbonded liquidity,
+    /// walking at most `max_requests` queue entries
+    fn fund_withdrawal_queue(&mut self, max_requests: u64) -> u64 {
+        let now = self.env().get_block_time();
+        let start = self.withdrawal_queue_head.get_or_default();
+        let head = self.fundable_queue_head(max_requests);
+        let mut unbonded = self.unbonded_liquidity.get_or_default();
+        
+        let mut funded_requests = 0u64;
+        let mut funded_amount = U256::zero();
+        for request_id in start..head {
+            let request = match self.unstake_requests.get(&request_id) {
+                Some(request) if !request.processed => request,
+                _ => continue,
+            };
+            unbonded += request.cspr_amount;
+            funded_requests += 1;
+            funded_amount += request.cspr_amount;
+            
+            // The request's undelegations have finished unbonding
+            for undelegation in self.request_undelegations.get(&request_id).unwrap_or_default() {
+                let pending = self.pending_undelegations.get(&undelegation.validator).unwrap_or_default();
+                self.pending_undelegations.set(&undelegation.validator, pending.saturating_sub(undelegation.amount));
+                let total_pending = self.total_pending_undelegation.get_or_default();
+                self.total_pending_undelegation.set(total_pending.saturating_sub(undelegation.amount));
+            }
+        }
+        
+        self.withdrawal_queue_head.set(head);
+        self.unbonded_liquidity.set(unbonded);
+        
+        if funded_requests > 0 {
+            self.env().emit_event(WithdrawalsFunded {
+                funded_requests,
+                funded_amount,
+                unbonded_liquidity: unbonded,
+                next_request_id: head,
+                timestamp: now,
+            });
+        }
+        
+        funded_requests
     }
 
-    /// Check if a validator is approved
-    pub fn is_validator_approved(&self, validator: Address) -> bool {
-        self.validators.get(&validator).unwrap_or(false)
+    /// Enforce the reward sync interval and APR bound, then mark the sync
+    fn check_reward_sync(&mut self, rewards_amount: U256) {
+        let now = self.env().get_block_time();
+        let elapsed = now.saturating_sub(self.last_reward_sync.get_or_default());
+        if elapsed < self.min_reward_sync_interval.get_or_default() {
+            self.env().revert(LstError::RewardSyncTooSoon);
+        }
+        if rewards_amount > self.max_sync_rewards(elapsed) {
+            self.env().revert(LstError::RewardsExceedMaxApr);
+        }
+        self.last_reward_sync.set(now);
     }
 
-    /// Get all approved validators
-    pub fn get_validators(&self) -> Vec<Address> {
+    /// Spread reported rewards over the validators' tracked stakes, pro rata
+    /// 
+    /// The auction compounds rewards into each delegation, so the tracked
+    /// stakes must grow with them or `compound` would claim them again.
+    /// Rounding dust goes to the last validator with stake.
+    fn record_validator_rewards(&mut self, rewards_amount: U256) {
         let count = self.validator_count.get_or_default();
-        let mut validators = Vec::new();
+        let mut staked = Vec::new();
+        let mut total_tracked = U256::zero();
         for i in 0..count {
             if let Some(validator) = self.validator_list.get(&i) {
-                validators.push(validator);
+                let stake = self.validator_stakes.get(&validator).unwrap_or_default();
+                if !stake.is_zero() {
+                    total_tracked += stake;
+                    staked.push((validator, stake));
+                }
             }
         }
-        validators
-    }
-
-    /// Get stake amount for a validator
-    pub fn get_validator_stake(&self, validator: Address) -> U256 {
-        self.validator_stakes.get(&validator).unwrap_or_default()
-    }
-
-    // Admin functions
-
-    /// Add a validator to the approved list
-    pub fn add_validator(&mut self, validator: Address) {
-        self.only_admin();
         
-        if !self.validators.get(&validator).unwrap_or(false) {
-            self.validators.set(&validator, true);
-            let count = self.validator_count.get_or_default();
-            self.validator_list.set(&count, validator);
-            self.validator_count.set(count + 1);
-            
-            let timestamp = self.env().get_block_time();
-            let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
-            self.env().emit_event(ValidatorAdded {
-                validator,
-                added_by: admin,
-                timestamp,
-            });
+        let mut remaining = rewards_amount;
+        let last = staked.len().saturating_sub(1);
+        for (index, (validator, stake)) in staked.into_iter().enumerate() {
+            let share = if index == last {
+                remaining
+            } else {
+                rewards_amount * stake / total_tracked
+            };
+            remaining -= share;
+            self.validator_stakes.set(&validator, stake + share);
         }
     }
 
-    /// Remove a validator from the approved list
-    pub fn remove_validator(&mut self, validator: Address) {
-        self.only_admin();
-        
-        if self.validators.get(&validator).unwrap_or(false) {
-            self.validators.set(&validator, false);
-            // Note: We don't remove from validator_list to keep indices stable
-            // The validator is just marked as not approved
-            
-            let timestamp = self.env().get_block_time();
-            let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
-            self.env().emit_event(ValidatorRemoved {
-                validator,
-                removed_by: admin,
-                timestamp,
-            });
-        }
+    /// Largest reward a sync may apply after `elapsed` seconds
+    fn max_sync_rewards(&self, elapsed: u64) -> U256 {
+        let total_staked = self.total_cspr_staked.get_or_default();
+        let max_apr_bps = self.max_reward_apr_bps.get_or_default();
+        total_staked * U256::from(max_apr_bps) * U256::from(elapsed)
+            / (U256::from(BPS_DENOMINATOR) * U256::from(SECONDS_PER_YEAR))
     }
 
-    /// Update minimum stake amount
-    pub fn set_minimum_stake(&mut self, new_minimum: U256) {
-        self.only_admin();
-        let old_minimum = self.minimum_stake.get_or_default();
-        self.minimum_stake.set(new_minimum);
+    /// Undelegate stake from one validator and queue it for another
+    fn rebalance_internal(&mut self, from_validator: Address, to_validator: Address, amount: U256) {
+        self.ensure_not_emergency();
+        if amount.is_zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        if from_validator == to_validator || !self.validators.get(&to_validator).unwrap_or(false) {
+            self.env().revert(LstError::InvalidValidator);
+        }
         
-        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
-        self.env().emit_event(MinimumStakeUpdated {
-            old_minimum,
-            new_minimum,
-            updated_by: admin,
+        let from_stake_before = self.get_validator_stake(from_validator);
+        if amount > from_stake_before {
+            self.env().revert(LstError::InvalidAmount);
+        }
+        let to_stake = self.get_validator_stake(to_validator);
+        
+        let public_key = self.validator_public_keys.get(&from_validator)
+            .unwrap_or_revert_with(&self.env(), LstError::InvalidValidator);
+        self.delegator().undelegate_stake(&public_key, U512::from(amount.as_u128()));
+        self.validator_stakes.set(&from_validator, from_stake_before - amount);
+        
+        let now = self.env().get_block_time();
+        let mut pending = self.pending_redelegations.get_or_default();
+        pending.push(Redelegation {
+            from_validator,
+            to_validator,
+            amount,
+            ready_at: now + self.unstaking_period.get_or_default(),
+        });
+        self.pending_redelegations.set(pending);
+        
+        self.env().emit_event(StakeRebalanced {
+            from_validator,
+            to_validator,
+            amount,
+            from_stake_before,
+            from_stake_after: from_stake_before - amount,
+            to_stake_before: to_stake,
+            to_stake_after: to_stake + amount,
+            timestamp: now,
         });
     }
 
-    /// Update unstaking period
-    pub fn set_unstaking_period(&mut self, new_period: u64) {
-        self.only_admin();
-        let old_period = self.unstaking_period.get_or_default();
-        self.unstaking_period.set(new_period);
-        
-        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
-        self.env().emit_event(UnstakingPeriodUpdated {
-            old_period,
-            new_period,
-            updated_by: admin,
-        });
+    /// Route to the auction system: the backend contract or the Casper auction
+    fn delegator(&self) -> Delegator {
+        Delegator::new(self.env(), self.delegation_backend.get_or_default())
     }
 
-    /// Pause the contract
-    pub fn pause(&mut self) {
-        self.only_admin();
-        self.paused.set(true);
+    /// Delegate CSPR held by the contract to a validator
+    fn delegate_to(&mut self, validator: Address, cspr_amount: U256) {
+        let public_key = self.validator_public_keys.get(&validator)
+            .unwrap_or_revert_with(&self.env(), LstError::InvalidValidator);
+        self.delegator().delegate_stake(&public_key, U512::from(cspr_amount.as_u128()));
         
-        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
-        let timestamp = self.env().get_block_time();
-        self.env().emit_event(ContractPaused {
-            paused_by: admin,
-            timestamp,
+        self.env().emit_event(Delegated {
+            validator,
+            cspr_amount,
+            timestamp: self.env().get_block_time(),
         });
     }
 
-    /// Unpause the contract
-    pub fn unpause(&mut self) {
-        self.only_admin();
-        self.paused.set(false);
+    /// Undelegate `cspr_amount` across validators in list order
+    /// Returns the undelegations made, which stay pending until the request is withdrawn
+    fn undelegate_for(&mut self, request_id: u64, cspr_amount: U256) -> Vec<Undelegation> {
+        let mut undelegations = Vec::new();
+        let mut remaining = cspr_amount;
+        let count = self.validator_count.get_or_default();
         
-        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
-        let timestamp = self.env().get_block_time();
-        self.env().emit_event(ContractUnpaused {
-            unpaused_by: admin,
-            timestamp,
-        });
+        for i in 0..count {
+            if remaining.is_zero() {
+                break;
+            }
+            let validator = match self.validator_list.get(&i) {
+                Some(validator) => validator,
+                None => continue,
+            };
+            let public_key = match self.validator_public_keys.get(&validator) {
+                Some(public_key) => public_key,
+                None => continue,
+            };
+            
+            // Delegations include compounded rewards, so use the auction's view
+            let delegated = U256::from(self.delegator().delegated_stake(&public_key).as_u128());
+            let amount = remaining.min(delegated);
+            if amount.is_zero() {
+                continue;
+            }
+            
+            self.delegator().undelegate_stake(&public_key, U512::from(amount.as_u128()));
+            remaining = remaining - amount;
+            
+            let stake = self.validator_stakes.get(&validator).unwrap_or_default();
+            self.validator_stakes.set(&validator, stake.saturating_sub(amount));
+            let pending = self.pending_undelegations.get(&validator).unwrap_or_default();
+            self.pending_undelegations.set(&validator, pending + amount);
+            let total_pending = self.total_pending_undelegation.get_or_default();
+            self.total_pending_undelegation.set(total_pending + amount);
+            
+            self.env().emit_event(Undelegated {
+                validator,
+                cspr_amount: amount,
+                request_id,
+                timestamp: self.env().get_block_time(),
+            });
+            
+            undelegations.push(Undelegation { validator, amount });
+        }
+        
+        if !remaining.is_zero() {
+            self.env().revert(LstError::UnstakingFailed);
+        }
+        
+        undelegations
     }
 
-    /// Transfer admin rights
-    pub fn transfer_admin(&mut self, new_admin: Address) {
-        self.only_admin();
-        self.admin.set(new_admin);
+    /// Current holder of an unstake request: the NFT owner if one was minted
+    fn request_owner(&self, request_id: u64, request: &UnstakeRequest) -> Address {
+        if let Some(nft_address) = self.unstake_nft.get_or_default() {
+            let nft = UnstakeNftContractRef::new(self.env(), nft_address);
+            if nft.exists(request_id) {
+                return nft.owner_of(request_id);
+            }
+        }
+        request.user
     }
 
-    /// Get admin address
-    pub fn get_admin(&self) -> Address {
-        self.admin.get_or_revert_with(LstError::Unauthorized)
+    /// Burn a settled request's NFT, if it has one
+    fn burn_unstake_nft(&mut self, request_id: u64) {
+        if let Some(nft_address) = self.unstake_nft.get_or_default() {
+            let mut nft = UnstakeNftContractRef::new(self.env(), nft_address);
+            if nft.exists(request_id) {
+                nft.burn(request_id);
+            }
+        }
     }
 
-    /// Check if contract is paused
-    pub fn is_paused(&self) -> bool {
-        self.paused.get_or_default()
+    /// Largest single stake `select_validator` can place right now
+    fn validator_headroom(&self) -> U256 {
+        if self.override_validator.get_or_default().is_some() {
+            return U256::MAX;
+        }
+        
+        let approved: Vec<Address> = self.get_validators()
+            .into_iter()
+            .filter(|validator| self.validators.get(validator).unwrap_or(false))
+            .collect();
+        if approved.is_empty() {
+            return U256::zero();
+        }
+        if !matches!(self.get_allocation_strategy(), AllocationStrategy::FillToCap) {
+            return U256::MAX;
+        }
+        
+        // Fill-to-cap places the whole stake with one validator under its cap
+        let mut headroom = U256::zero();
+        for validator in approved {
+            let cap = self.get_validator_cap(validator);
+            if cap.is_zero() {
+                return U256::MAX;
+            }
+            let room = cap.saturating_sub(self.get_validator_stake(validator));
+            if room > headroom {
+                headroom = room;
+            }
+        }
+        headroom
     }
 
-    // Internal helper functions
+    /// CSPR that can still be staked before hitting the deposit cap
+    fn deposit_headroom(&self) -> U256 {
+        let cap = self.deposit_cap.get_or_default();
+        if cap.is_zero() {
+            return U256::MAX;
+        }
+        cap.saturating_sub(self.total_cspr_staked.get_or_default())
+    }
 
-    fn calculate_scspr_amount(&self, cspr_amount: U256) -> U256 {
-        let total_scspr = self.total_scspr_supply.get_or_default();
-        let total_cspr = self.total_cspr_staked.get_or_default();
-        
-        if total_scspr == U256::zero() || total_cspr == U256::zero() {
-            // Initial stake: 1:1 ratio
-            return cspr_amount;
+    /// Remaining CSPR an address may deposit under its own cap
+    fn address_deposit_headroom(&self, account: Address) -> U256 {
+        let cap = self.get_address_deposit_cap(account);
+        if cap.is_zero() {
+            return U256::MAX;
         }
-        
-        // sCSPR = (cspr_amount * total_scspr) / total_cspr
-        (cspr_amount * total_scspr) / total_cspr
+        cap.saturating_sub(self.get_address_deposits(account))
     }
 
-    fn calculate_cspr_amount(&self, scspr_amount: U256) -> U256 {
-        let total_scspr = self.total_scspr_supply.get_or_default();
-        let total_cspr = self.total_cspr_staked.get_or_default();
-        
-        if total_scspr == U256::zero() {
-            return U256::zero();
+    /// CSPR attached to the current call, as U256
+    fn attached_cspr(&self) -> U256 {
+        let attached = self.env().attached_value();
+        if attached > U512::from(u128::MAX) {
+            self.env().revert(LstError::InvalidAmount);
         }
-        
-        // CSPR = (scspr_amount * total_cspr) / total_scspr
-        (scspr_amount * total_cspr) / total_scspr
+        U256::from(attached.as_u128())
     }
 
-    fn only_admin(&self) {
-        let caller = self.env().caller();
-        let admin = self.admin.get_or_revert_with(LstError::Unauthorized);
-        if caller != admin {
-            self.env().revert(LstError::Unauthorized);
+    fn ensure_not_emergency(&self) {
+        if self.emergency_mode.get_or_default() {
+            self.env().revert(LstError::EmergencyModeActive);
         }
     }
 
@@ -585,53 +2199,56 @@ impl Cep4626Vault for StakingManager {
     
     fn convert_to_shares(&self, assets: U256) -> U256 {
         // Convert CSPR to sCSPR shares
-        self.calculate_scspr_amount(assets)
+        base::convert_to_shares(self, assets)
     }
     
     fn convert_to_assets(&self, shares: U256) -> U256 {
         // Convert sCSPR shares to CSPR
-        self.calculate_cspr_amount(shares)
+        base::convert_to_assets(self, shares)
     }
     
     // ========================================
     // Deposit/Withdrawal Limits
     // ========================================
     
-    fn max_deposit(&self, _receiver: Address) -> U256 {
-        if self.paused.get_or_default() {
+    fn max_deposit(&self, receiver: Address) -> U256 {
+        if self.paused.get_or_default() || self.emergency_mode.get_or_default() {
+            return U256::zero();
+        }
+        if self.allowlist_enabled.get_or_default() && !self.is_allowlisted(receiver) {
             return U256::zero();
         }
-        // No maximum deposit limit for liquid staking
-        U256::MAX
+        // Remaining headroom under the global and per-address caps, and
+        // what the validator set can take in one stake
+        self.deposit_headroom()
+            .min(self.address_deposit_headroom(receiver))
+            .min(self.validator_headroom())
     }
     
-    fn max_mint(&self, _receiver: Address) -> U256 {
-        if self.paused.get_or_default() {
-            return U256::zero();
+    fn max_mint(&self, receiver: Address) -> U256 {
+        // Shares mintable within the deposit caps
+        let headroom = self.max_deposit(receiver);
+        if headroom == U256::MAX {
+            return U256::MAX;
         }
-        // No maximum mint limit
-        U256::MAX
+        self.convert_to_shares(headroom)
     }
     
     fn max_withdraw(&self, owner: Address) -> U256 {
-        if self.paused.get_or_default() {
+        // Emergency mode undelegates everything, so only `emergency_redeem` exits
+        if self.paused.get_or_default() || self.emergency_mode.get_or_default() {
             return U256::zero();
         }
         // Maximum withdrawal is the user's sCSPR balance converted to CSPR
-        let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
-        let token = ScsprTokenContractRef::new(self.env(), token_address);
-        let user_shares = token.balance_of(owner);
-        self.convert_to_assets(user_shares)
+        base::max_withdraw(self, owner)
     }
     
     fn max_redeem(&self, owner: Address) -> U256 {
-        if self.paused.get_or_default() {
+        if self.paused.get_or_default() || self.emergency_mode.get_or_default() {
             return U256::zero();
         }
         // Maximum redeem is the user's sCSPR balance
-        let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
-        let token = ScsprTokenContractRef::new(self.env(), token_address);
-        token.balance_of(owner)
+        base::max_redeem(self, owner)
     }
     
     // ========================================
@@ -639,23 +2256,23 @@ impl Cep4626Vault for StakingManager {
     // ========================================
     
     fn preview_deposit(&self, assets: U256) -> U256 {
-        // Preview how many sCSPR shares would be minted for assets
-        self.convert_to_shares(assets)
+        // Preview how many sCSPR shares would be minted for assets (rounded down)
+        base::preview_deposit(self, assets)
     }
     
     fn preview_mint(&self, shares: U256) -> U256 {
-        // Preview how many CSPR assets are needed to mint shares
-        self.convert_to_assets(shares)
+        // Preview how many CSPR assets are needed to mint shares (rounded up)
+        base::preview_mint(self, shares)
     }
     
     fn preview_withdraw(&self, assets: U256) -> U256 {
-        // Preview how many sCSPR shares would be burned to withdraw assets
-        self.convert_to_shares(assets)
+        // Preview how many sCSPR shares would be burned to withdraw assets (rounded up)
+        base::preview_withdraw(self, assets)
     }
     
     fn preview_redeem(&self, shares: U256) -> U256 {
-        // Preview how many CSPR assets would be received for redeeming shares
-        self.convert_to_assets(shares)
+        // Preview how many CSPR assets would be received for redeeming shares (rounded down)
+        base::preview_redeem(self, shares)
     }
     
     // ========================================
@@ -664,48 +2281,21 @@ impl Cep4626Vault for StakingManager {
     
     fn deposit(&mut self, assets: U256, receiver: Address) -> U256 {
         // CEP-4626 deposit: stake CSPR and mint sCSPR to receiver
-        // Note: For liquid staking, we need a validator parameter
-        // We'll use the first available validator
-        let validators = self.get_validators();
-        if validators.is_empty() {
-            self.env().revert(LstError::InvalidValidator);
-        }
-        let validator = validators[0];
-        
-        // Perform the stake operation
-        let shares = self.stake(validator, assets);
-        
-        // If receiver is different from caller, transfer shares
-        let caller = self.env().caller();
-        if receiver != caller {
-            let token_address = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
-            let mut token = ScsprTokenContractRef::new(self.env(), token_address);
-            token.transfer_from(caller, receiver, shares);
+        // The attached CSPR must match `assets`; the validator is chosen by the allocation strategy
+        if self.attached_cspr() != assets {
+            self.env().revert(LstError::InvalidAmount);
         }
-        
-        // Emit CEP-4626 Deposit event
-        self.env().emit_event(Cep4626Deposit {
-            sender: caller,
-            owner: receiver,
-            assets,
-            shares,
-        });
-        
-        shares
+        self.stake_cspr(assets, receiver)
     }
     
     fn mint(&mut self, shares: U256, receiver: Address) -> U256 {
-        // CEP-4626 mint: calculate required CSPR and stake to mint exact shares
-        let assets = self.convert_to_assets(shares);
-        
-        // Use deposit to perform the operation
-        let actual_shares = self.deposit(assets, receiver);
-        
-        // Verify we minted at least the requested shares
-        if actual_shares < shares {
-            self.env().revert(LstError::InsufficientScsprBalance);
+        // CEP-4626 mint: the attached CSPR must be exactly what the shares
+        // cost, and exactly `shares` are minted (not what the CSPR would buy)
+        let assets = base::preview_mint(self, shares);
+        if self.attached_cspr() != assets {
+            self.env().revert(LstError::InvalidAmount);
         }
-        
+        self.stake_cspr_for_shares(assets, shares, receiver);
         assets
     }
     
@@ -714,72 +2304,114 @@ impl Cep4626Vault for StakingManager {
     // ========================================
     
     fn withdraw(&mut self, assets: U256, receiver: Address, owner: Address) -> U256 {
-        // CEP-4626 withdraw: burn sCSPR shares to withdraw exact CSPR amount
-        // Note: Liquid staking has an unstaking period, so this initiates withdrawal
-        
-        let caller = self.env().caller();
-        
-        // Calculate shares needed
-        let shares = self.convert_to_shares(assets);
-        
-        // Check allowance if caller is not owner
-        if caller != owner {
-            let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
-            let token = ScsprTokenContractRef::new(self.env(), token_address);
-            let allowance = token.allowance(owner, caller);
-            if allowance < shares {
-                self.env().revert(LstError::Unauthorized);
-            }
-        }
-        
-        // Initiate unstaking (this burns shares and creates withdrawal request)
-        let _request_id = self.unstake(shares);
+        // CEP-4626 withdraw: burn owner's sCSPR and open an unstake request
+        // paying receiver; the CSPR is claimed once it has unbonded
+        base::withdraw(self, assets, receiver, owner)
+    }
+    
+    fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
+        // CEP-4626 redeem: burn exact sCSPR shares and open an unstake request
+        base::redeem(self, shares, receiver, owner)
+    }
+}
+
+// ============================================================================
+// CEP-4626 Async Redeem Implementation
+// ============================================================================
+
+/// Unstake requests are the async redeem requests: pending while the CSPR
+/// unbonds, claimable once the withdrawal queue can fund them
+impl Cep4626AsyncRedeem for StakingManager {
+    fn request_redeem(&mut self, shares: U256, controller: Address, owner: Address) -> u64 {
+        StakingManager::request_redeem(self, shares, controller, owner)
+    }
+    
+    fn pending_redeem_request(&self, request_id: u64) -> U256 {
+        StakingManager::pending_redeem_request(self, request_id)
+    }
+    
+    fn claimable_redeem_request(&self, request_id: u64) -> U256 {
+        StakingManager::claimable_redeem_request(self, request_id)
+    }
+    
+    fn claim_redeem(&mut self, request_id: u64) -> U256 {
+        StakingManager::claim_redeem(self, request_id)
+    }
+}
+
+// ============================================================================
+// CEP-4626 Vault Hooks
+// ============================================================================
+
+/// sCSPR lives in its own token contract, so the share ledger hooks go
+/// through it; `total_scspr_supply` mirrors its supply for pricing
+impl Cep4626Hooks for StakingManager {
+    fn vault_total_assets(&self) -> U256 {
+        self.total_cspr_staked.get_or_default()
+    }
+    
+    fn vault_total_shares(&self) -> U256 {
+        self.total_scspr_supply.get_or_default()
+    }
+    
+    fn vault_shares_of(&self, owner: Address) -> U256 {
+        let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
+        ScsprTokenContractRef::new(self.env(), token_address).balance_of(owner)
+    }
+    
+    fn mint_vault_shares(&mut self, to: Address, shares: U256) {
+        let current_supply = self.total_scspr_supply.get_or_default();
+        self.total_scspr_supply.set(current_supply + shares);
         
-        // Emit CEP-4626 Withdraw event
-        self.env().emit_event(Cep4626Withdraw {
-            sender: caller,
-            receiver,
-            owner,
-            assets,
-            shares,
-        });
+        let token_address = self.scspr_token_address.get_or_revert_with(LstError::StakingFailed);
+        ScsprTokenContractRef::new(self.env(), token_address).mint(to, shares);
+    }
+    
+    fn burn_vault_shares(&mut self, from: Address, shares: U256) {
+        let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
+        ScsprTokenContractRef::new(self.env(), token_address).burn(from, shares);
         
-        // Note: Actual withdrawal happens later via withdraw(request_id)
-        // For CEP-4626 compliance, we return shares burned
-        shares
+        let current_supply = self.total_scspr_supply.get_or_default();
+        self.total_scspr_supply.set(current_supply - shares);
     }
     
-    fn redeem(&mut self, shares: U256, receiver: Address, owner: Address) -> U256 {
-        // CEP-4626 redeem: burn exact sCSPR shares to withdraw CSPR
-        let caller = self.env().caller();
+    fn insufficient_shares_error(&self) -> OdraError {
+        LstError::InsufficientScsprBalance.into()
+    }
+    
+    fn before_withdraw(
+        &mut self,
+        caller: Address,
+        _receiver: Address,
+        owner: Address,
+        _assets: U256,
+        shares: U256,
+    ) {
+        self.ensure_not_paused();
+        self.ensure_not_emergency();
+        
+        if shares == U256::zero() {
+            self.env().revert(LstError::InvalidAmount);
+        }
         
         // Check allowance if caller is not owner
         if caller != owner {
             let token_address = self.scspr_token_address.get_or_revert_with(LstError::UnstakingFailed);
             let token = ScsprTokenContractRef::new(self.env(), token_address);
-            let allowance = token.allowance(owner, caller);
-            if allowance < shares {
+            if token.allowance(owner, caller) < shares {
                 self.env().revert(LstError::Unauthorized);
             }
         }
-        
-        // Calculate assets to receive
-        let assets = self.convert_to_assets(shares);
-        
-        // Initiate unstaking (this burns shares and creates withdrawal request)
-        let _request_id = self.unstake(shares);
-        
-        // Emit CEP-4626 Withdraw event
-        self.env().emit_event(Cep4626Withdraw {
-            sender: caller,
-            receiver,
-            owner,
-            assets,
-            shares,
-        });
-        
-        // Note: Actual withdrawal happens later via withdraw_unstaked(request_id)
-        // For CEP-4626 compliance, we return assets that will be received
-        assets
+    }
+    
+    fn after_withdraw(
+        &mut self,
+        _caller: Address,
+        receiver: Address,
+        owner: Address,
+        assets: U256,
+        shares: U256,
+    ) {
+        self.open_unstake_request(owner, receiver, shares, assets);
     }
 }
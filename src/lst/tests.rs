@@ -2,9 +2,14 @@
 
 #[cfg(test)]
 mod tests {
-    use odra::host::{Deployer, HostRef, NoArgs};
-    use odra::casper_types::U256;
-    use crate::lst::{ScsprToken, StakingManager};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+    use odra::casper_types::{U256, U512};
+    use odra::prelude::*;
+    use crate::cep4626::compliance::{check_compliance, VaultUnderTest};
+    use crate::lst::{LstError, MockDelegationBackend, ScsprToken, StakingManager};
+    use crate::lst::delegation::MockDelegationBackendHostRef;
+    use crate::lst::scspr_token::{ScsprTokenHostRef, ScsprTokenInitArgs};
+    use crate::lst::staking_manager::{StakingManagerHostRef, StakingManagerInitArgs};
 
     #[test]
     fn test_scspr_token_initialization() {
@@ -105,7 +110,7 @@ mod tests {
         
         // Distribute rewards (10% APY)
         let rewards = U256::from(100_000_000_000u64); // 100 CSPR
-        let admin = staking_manager.get_admin();
+        let admin = env.get_account(0); // deployer holds ADMIN_ROLE
         env.set_caller(admin);
         staking_manager.distribute_rewards(rewards);
         
@@ -226,6 +231,34 @@ mod tests {
         assert_eq!(scspr_token.balance_of(user2), transfer_amount);
     }
 
+    #[test]
+    fn test_scspr_minter_role_follows_the_staking_manager() {
+        use crate::errors::TokenError;
+        use crate::events::{Burn, Mint};
+
+        let env = odra_test::env();
+        let (admin, old_manager, new_manager, user) =
+            (env.get_account(0), env.get_account(1), env.get_account(2), env.get_account(3));
+        env.set_caller(admin);
+        let mut token = ScsprToken::deploy(&env, ScsprTokenInitArgs { staking_manager: old_manager });
+
+        // Only the staking manager holds the minter role
+        assert_eq!(token.try_mint(user, U256::from(100)).unwrap_err(), TokenError::Unauthorized.into());
+        env.set_caller(old_manager);
+        token.mint(user, U256::from(100));
+        assert!(env.emitted_event(&token, Mint { recipient: user, amount: U256::from(100) }));
+
+        // Replacing the manager moves the role
+        env.set_caller(admin);
+        token.set_staking_manager(new_manager);
+        env.set_caller(old_manager);
+        assert_eq!(token.try_burn(user, U256::from(40)).unwrap_err(), TokenError::Unauthorized.into());
+        env.set_caller(new_manager);
+        token.burn(user, U256::from(40));
+        assert!(env.emitted_event(&token, Burn { owner: user, amount: U256::from(40) }));
+        assert_eq!(token.total_supply(), U256::from(60));
+    }
+
     #[test]
     fn test_validator_stake_tracking() {
         let env = odra_test::env();
@@ -257,4 +290,490 @@ mod tests {
         assert_eq!(staking_manager.get_validator_stake(validator1), stake_amount1);
         assert_eq!(staking_manager.get_validator_stake(validator2), stake_amount2);
     }
+
+    /// Deploy sCSPR and a StakingManager delegating to one validator through a mock backend
+    fn deploy_with_backend(env: &HostEnv) -> (ScsprTokenHostRef, StakingManagerHostRef, MockDelegationBackendHostRef) {
+        let admin = env.get_account(0);
+        let validator = env.get_account(2);
+
+        env.set_caller(admin);
+        let mut token = ScsprToken::deploy(env, ScsprTokenInitArgs { staking_manager: admin });
+        let mut manager = StakingManager::deploy(env, StakingManagerInitArgs {
+            scspr_token_address: token.address(),
+        });
+        let backend = MockDelegationBackend::deploy(env, NoArgs);
+        token.set_staking_manager(manager.address());
+        manager.set_delegation_backend(Some(backend.address()));
+        manager.add_validator(validator);
+        manager.set_validator_public_key(validator, env.get_validator(0));
+        manager.set_minimum_stake(U256::one());
+        (token, manager, backend)
+    }
+
+    #[test]
+    fn test_delegations_route_through_the_backend() {
+        let env = odra_test::env();
+        let (_, mut manager, mut backend) = deploy_with_backend(&env);
+        let validator = env.get_account(2);
+        let staker = env.get_account(3);
+
+        env.set_caller(staker);
+        manager.with_tokens(U512::from(1_000_000u64)).stake();
+
+        // The stake sits with the mock backend, which the manager reads back
+        assert_eq!(backend.delegation_of(manager.address(), env.get_validator(0)), U512::from(1_000_000u64));
+        assert_eq!(manager.get_delegated_amount(validator), U256::from(1_000_000u64));
+
+        // Rewards credited by the backend show up as delegated stake
+        env.set_caller(env.get_account(0));
+        backend.with_tokens(U512::from(500u64)).add_rewards(manager.address(), env.get_validator(0));
+        assert_eq!(manager.get_delegated_amount(validator), U256::from(1_000_500u64));
+
+        // The backend cannot be swapped while stake is delegated through it
+        assert_eq!(
+            manager.try_set_delegation_backend(None).unwrap_err(),
+            LstError::DelegationsOutstanding.into()
+        );
+    }
+
+    #[test]
+    fn test_compound_does_not_recount_reported_rewards() {
+        let env = odra_test::env();
+        let (_, mut manager, mut backend) = deploy_with_backend(&env);
+        let admin = env.get_account(0);
+        let validator = env.get_account(2);
+        let staker = env.get_account(3);
+        let keeper = env.get_account(4);
+
+        env.set_caller(staker);
+        manager.with_tokens(U512::from(1_000_000u64)).stake();
+
+        // The auction compounds 50k of rewards, which the oracle also reports
+        env.advance_block_time(365 * 24 * 3600);
+        env.set_caller(admin);
+        backend.with_tokens(U512::from(50_000u64)).add_rewards(manager.address(), env.get_validator(0));
+        manager.distribute_rewards(U256::from(50_000));
+        assert_eq!(manager.get_validator_stake(validator), U256::from(1_050_000));
+
+        // compound finds nothing new to claim
+        env.advance_block_time(3_600);
+        env.set_caller(keeper);
+        assert_eq!(manager.compound(), U256::zero());
+        assert_eq!(manager.get_total_cspr_staked(), U256::from(1_050_000));
+    }
+
+    #[test]
+    fn test_compound_respects_the_reward_sync_bound() {
+        let env = odra_test::env();
+        let (_, mut manager, mut backend) = deploy_with_backend(&env);
+        let admin = env.get_account(0);
+        let staker = env.get_account(3);
+        let keeper = env.get_account(4);
+
+        env.set_caller(staker);
+        manager.with_tokens(U512::from(1_000_000u64)).stake();
+
+        // 1000 of rewards after a day is far above the 20% APR bound (~548)
+        env.advance_block_time(24 * 3600);
+        env.set_caller(admin);
+        backend.with_tokens(U512::from(1_000u64)).add_rewards(manager.address(), env.get_validator(0));
+        env.set_caller(keeper);
+        assert_eq!(manager.try_compound().unwrap_err(), LstError::RewardsExceedMaxApr.into());
+
+        // Within the bound once enough time has passed
+        env.advance_block_time(30 * 24 * 3600);
+        assert_eq!(manager.compound(), U256::from(1_000));
+        assert_eq!(manager.get_total_cspr_staked(), U256::from(1_001_000));
+    }
+
+    #[test]
+    fn test_withdrawal_queue_funds_in_order_and_in_batches() {
+        let env = odra_test::env();
+        let (_, mut manager, _) = deploy_with_backend(&env);
+        let admin = env.get_account(0);
+        let stakers = [env.get_account(3), env.get_account(4), env.get_account(5)];
+
+        let mut shares = Vec::new();
+        for staker in stakers {
+            env.set_caller(staker);
+            shares.push(manager.with_tokens(U512::from(1_000_000u64)).stake());
+        }
+
+        // The first request unbonds for a day, the later ones for an hour
+        env.set_caller(admin);
+        manager.set_unstaking_period(86_400);
+        env.set_caller(stakers[0]);
+        let first = manager.unstake(shares[0]);
+        env.set_caller(admin);
+        manager.set_unstaking_period(3_600);
+        let mut later = Vec::new();
+        for (staker, amount) in stakers.iter().zip(&shares).skip(1) {
+            env.set_caller(*staker);
+            later.push(manager.unstake(*amount));
+        }
+
+        // Matured later requests wait behind the first one
+        env.advance_block_time(3_600);
+        assert_eq!(manager.process_withdrawal_queue(10), 0);
+        assert!(!manager.is_withdrawal_funded(later[0]));
+        env.set_caller(stakers[1]);
+        assert_eq!(
+            manager.try_withdraw_unstaked(later[0]).unwrap_err(),
+            LstError::FundsStillUnbonding.into()
+        );
+
+        // Once the head matures the queue is funded in order, two at a time
+        env.advance_block_time(86_400);
+        assert_eq!(manager.process_withdrawal_queue(2), 2);
+        assert!(manager.is_withdrawal_funded(first));
+        assert!(manager.is_withdrawal_funded(later[0]));
+        assert!(!manager.is_withdrawal_funded(later[1]));
+        assert_eq!(manager.get_withdrawal_queue_head(), later[1]);
+
+        assert_eq!(manager.process_withdrawal_queue(2), 1);
+        assert!(manager.is_withdrawal_funded(later[1]));
+        assert_eq!(manager.process_withdrawal_queue(2), 0);
+    }
+
+    #[test]
+    fn test_async_redeem_moves_from_pending_to_claimable() {
+        use crate::cep4626::{RedeemClaimed, RedeemRequest};
+
+        let env = odra_test::env();
+        let (_, mut manager, _) = deploy_with_backend(&env);
+        let (staker, controller) = (env.get_account(3), env.get_account(4));
+
+        env.set_caller(staker);
+        let shares = manager.with_tokens(U512::from(1_000_000u64)).stake();
+        let request_id = manager.request_redeem(shares, controller, staker);
+        assert!(env.emitted_event(&manager, RedeemRequest {
+            controller,
+            owner: staker,
+            request_id,
+            sender: staker,
+            shares,
+            assets: U256::from(1_000_000),
+        }));
+
+        // The assets stay pending while they unbond
+        assert_eq!(manager.pending_redeem_request(request_id), U256::from(1_000_000));
+        assert_eq!(manager.claimable_redeem_request(request_id), U256::zero());
+        assert_eq!(manager.pending_redeem_request(request_id + 1), U256::zero());
+        env.set_caller(controller);
+        assert_eq!(
+            manager.try_claim_redeem(request_id).unwrap_err(),
+            LstError::UnstakingPeriodNotComplete.into()
+        );
+
+        // Once unbonded they are claimable, by the controller only
+        env.advance_block_time(manager.get_unstaking_period());
+        assert_eq!(manager.pending_redeem_request(request_id), U256::zero());
+        assert_eq!(manager.claimable_redeem_request(request_id), U256::from(1_000_000));
+        env.set_caller(staker);
+        assert_eq!(
+            manager.try_claim_redeem(request_id).unwrap_err(),
+            LstError::Unauthorized.into()
+        );
+        env.set_caller(controller);
+        assert_eq!(manager.claim_redeem(request_id), U256::from(1_000_000));
+        assert!(env.emitted_event(&manager, RedeemClaimed {
+            controller,
+            request_id,
+            assets: U256::from(1_000_000),
+        }));
+        assert_eq!(manager.claimable_redeem_request(request_id), U256::zero());
+    }
+
+    #[test]
+    fn test_emergency_redeem_shares_a_slashing_haircut() {
+        use crate::lst::events::EmergencyRedeemed;
+
+        let env = odra_test::env();
+        let (_, mut manager, mut backend) = deploy_with_backend(&env);
+        let admin = env.get_account(0);
+        let (first, second) = (env.get_account(3), env.get_account(4));
+
+        env.set_caller(first);
+        let first_shares = manager.with_tokens(U512::from(1_000_000u64)).stake();
+        env.set_caller(second);
+        let second_shares = manager.with_tokens(U512::from(1_000_000u64)).stake();
+
+        // 10% of the delegation is slashed before everything is undelegated
+        env.set_caller(admin);
+        backend.slash(manager.address(), env.get_validator(0), U512::from(200_000u64));
+        manager.enable_emergency_mode();
+        env.set_caller(first);
+        assert_eq!(
+            manager.try_emergency_redeem(first_shares).unwrap_err(),
+            LstError::UnstakingPeriodNotComplete.into()
+        );
+
+        // Each holder gets their share of the 1.8M returned, not the 2M staked
+        env.advance_block_time(manager.get_unstaking_period());
+        assert_eq!(manager.emergency_redeem(first_shares), U256::from(900_000));
+        assert!(env.emitted_event(&manager, EmergencyRedeemed {
+            holder: first,
+            scspr_amount: first_shares,
+            cspr_amount: U256::from(900_000),
+            timestamp: env.block_time(),
+        }));
+
+        // The remaining holder is valued at what they can actually redeem
+        assert_eq!(manager.get_total_cspr_staked(), U256::from(900_000));
+        assert_eq!(manager.get_cspr_by_scspr(second_shares), U256::from(900_000));
+        env.set_caller(second);
+        assert_eq!(manager.emergency_redeem(second_shares), U256::from(900_000));
+        assert_eq!(manager.get_total_cspr_staked(), U256::zero());
+    }
+
+    #[test]
+    fn test_unstake_nft_cannot_be_removed_while_claims_are_open() {
+        use crate::lst::UnstakeNft;
+        use crate::lst::unstake_nft::UnstakeNftInitArgs;
+
+        let env = odra_test::env();
+        let (_, mut manager, _) = deploy_with_backend(&env);
+        let admin = env.get_account(0);
+        let (staker, buyer) = (env.get_account(3), env.get_account(4));
+        let mut nft = UnstakeNft::deploy(&env, UnstakeNftInitArgs { staking_manager: manager.address() });
+        manager.set_unstake_nft(Some(nft.address()));
+
+        env.set_caller(staker);
+        let shares = manager.with_tokens(U512::from(1_000_000u64)).stake();
+        let request_id = manager.unstake(shares);
+        nft.transfer(request_id, staker, buyer);
+
+        // Dropping or replacing the NFT would strand the buyer's claim
+        env.set_caller(admin);
+        assert_eq!(
+            manager.try_set_unstake_nft(None).unwrap_err(),
+            LstError::UnstakeNftsOutstanding.into()
+        );
+        assert_eq!(
+            manager.try_set_unstake_nft(Some(env.get_account(5))).unwrap_err(),
+            LstError::UnstakeNftsOutstanding.into()
+        );
+
+        env.advance_block_time(manager.get_unstaking_period());
+        env.set_caller(buyer);
+        manager.withdraw_unstaked(request_id);
+        assert_eq!(nft.total_supply(), 0);
+
+        env.set_caller(admin);
+        manager.set_unstake_nft(None);
+        assert_eq!(manager.get_unstake_nft(), None);
+    }
+
+    #[test]
+    fn test_allowlist_mode_and_address_deposit_caps() {
+        use crate::lst::events::{AddressDepositCapUpdated, AllowlistUpdated};
+
+        let env = odra_test::env();
+        let (_, mut manager, _) = deploy_with_backend(&env);
+        let admin = env.get_account(0);
+        let (listed, outsider) = (env.get_account(3), env.get_account(4));
+
+        manager.set_allowlist_enabled(true);
+        manager.set_allowlisted(listed, true);
+        assert!(env.emitted_event(&manager, AllowlistUpdated {
+            account: listed,
+            allowed: true,
+            updated_by: admin,
+        }));
+        manager.set_address_deposit_cap(listed, U256::from(1_500_000));
+        assert!(env.emitted_event(&manager, AddressDepositCapUpdated {
+            account: listed,
+            cap: U256::from(1_500_000),
+            updated_by: admin,
+        }));
+
+        // Only listed addresses may stake while the allowlist is on
+        assert_eq!(manager.max_deposit(outsider), U256::zero());
+        env.set_caller(outsider);
+        assert_eq!(
+            manager.with_tokens(U512::from(1_000_000u64)).try_stake().unwrap_err(),
+            LstError::NotAllowlisted.into()
+        );
+
+        // Listed addresses are held to their own cap
+        env.set_caller(listed);
+        manager.with_tokens(U512::from(1_000_000u64)).stake();
+        assert_eq!(manager.get_address_deposits(listed), U256::from(1_000_000));
+        assert_eq!(manager.max_deposit(listed), U256::from(500_000));
+        assert_eq!(
+            manager.with_tokens(U512::from(600_000u64)).try_stake().unwrap_err(),
+            LstError::AddressDepositCapExceeded.into()
+        );
+
+        // Only the admin can switch the mode, and switching it off opens staking
+        assert_eq!(
+            manager.try_set_allowlist_enabled(false).unwrap_err(),
+            LstError::Unauthorized.into()
+        );
+        env.set_caller(admin);
+        manager.set_allowlist_enabled(false);
+        env.set_caller(outsider);
+        manager.with_tokens(U512::from(1_000_000u64)).stake();
+    }
+
+    #[test]
+    fn test_stcspr_rebases_and_rejects_zero_share_transfers() {
+        use crate::errors::TokenError;
+        use crate::events::Transfer;
+        use crate::lst::StCsprToken;
+        use crate::lst::st_cspr::StCsprTokenInitArgs;
+
+        let env = odra_test::env();
+        let (mut scspr, mut manager, _) = deploy_with_backend(&env);
+        let (admin, staker, other) = (env.get_account(0), env.get_account(3), env.get_account(4));
+        let mut stcspr = StCsprToken::deploy(&env, StCsprTokenInitArgs {
+            scspr_token: scspr.address(),
+            staking_manager: manager.address(),
+        });
+
+        env.set_caller(staker);
+        let shares = manager.with_tokens(U512::from(1_000_000u64)).stake();
+        scspr.approve(stcspr.address(), shares);
+        assert_eq!(stcspr.wrap(shares), manager.get_cspr_by_scspr(shares));
+        assert_eq!(stcspr.shares_of(staker), shares);
+
+        // Rewards raise the stCSPR balance without moving shares
+        let before = stcspr.balance_of(staker);
+        env.advance_block_time(365 * 24 * 3600);
+        env.set_caller(admin);
+        manager.distribute_rewards(U256::from(100_000));
+        assert!(stcspr.balance_of(staker) > before);
+        assert_eq!(stcspr.total_supply(), stcspr.balance_of(staker));
+        assert_eq!(stcspr.shares_of(staker), shares);
+
+        // Transfers move the shares the amount is worth and report their value
+        env.set_caller(staker);
+        let amount = U256::from(550_000);
+        stcspr.transfer(other, amount);
+        let moved = manager.get_scspr_by_cspr(amount);
+        assert_eq!(stcspr.shares_of(other), moved);
+        assert!(env.emitted_event(&stcspr, Transfer {
+            sender: staker,
+            recipient: other,
+            amount: manager.get_cspr_by_scspr(moved),
+        }));
+
+        // One mote is worth less than a share
+        assert_eq!(stcspr.try_transfer(other, U256::one()).unwrap_err(), TokenError::ZeroShares.into());
+
+        env.set_caller(other);
+        let returned = stcspr.unwrap(stcspr.balance_of(other));
+        assert_eq!(scspr.balance_of(other), returned);
+        assert_eq!(stcspr.shares_of(other), moved - returned);
+    }
+
+    /// sCSPR driven through the shared CEP-4626 compliance checks
+    struct StakingUnderTest<'a> {
+        env: &'a HostEnv,
+        manager: StakingManagerHostRef,
+        token: ScsprTokenHostRef,
+    }
+
+    impl VaultUnderTest for StakingUnderTest<'_> {
+        fn total_assets(&self) -> U256 {
+            self.manager.total_assets()
+        }
+
+        fn total_supply(&self) -> U256 {
+            self.token.total_supply()
+        }
+
+        fn share_balance(&self, owner: Address) -> U256 {
+            self.token.balance_of(owner)
+        }
+
+        fn convert_to_shares(&self, assets: U256) -> U256 {
+            self.manager.convert_to_shares(assets)
+        }
+
+        fn convert_to_assets(&self, shares: U256) -> U256 {
+            self.manager.convert_to_assets(shares)
+        }
+
+        fn max_deposit(&self, receiver: Address) -> U256 {
+            self.manager.max_deposit(receiver)
+        }
+
+        fn max_mint(&self, receiver: Address) -> U256 {
+            self.manager.max_mint(receiver)
+        }
+
+        fn max_withdraw(&self, owner: Address) -> U256 {
+            self.manager.max_withdraw(owner)
+        }
+
+        fn max_redeem(&self, owner: Address) -> U256 {
+            self.manager.max_redeem(owner)
+        }
+
+        fn preview_deposit(&self, assets: U256) -> U256 {
+            self.manager.preview_deposit(assets)
+        }
+
+        fn preview_mint(&self, shares: U256) -> U256 {
+            self.manager.preview_mint(shares)
+        }
+
+        fn preview_withdraw(&self, assets: U256) -> U256 {
+            self.manager.preview_withdraw(assets)
+        }
+
+        fn preview_redeem(&self, shares: U256) -> U256 {
+            self.manager.preview_redeem(shares)
+        }
+
+        fn deposit(&mut self, user: Address, assets: U256) -> U256 {
+            self.env.set_caller(user);
+            self.manager.with_tokens(U512::from(assets.as_u128())).deposit(assets, user)
+        }
+
+        fn mint(&mut self, user: Address, shares: U256) -> U256 {
+            let cost = self.manager.preview_mint(shares);
+            self.env.set_caller(user);
+            self.manager.with_tokens(U512::from(cost.as_u128())).mint(shares, user)
+        }
+
+        fn withdraw(&mut self, user: Address, assets: U256) -> U256 {
+            self.env.set_caller(user);
+            self.manager.withdraw(assets, user, user)
+        }
+
+        fn redeem(&mut self, user: Address, shares: U256) -> U256 {
+            self.env.set_caller(user);
+            self.manager.redeem(shares, user, user)
+        }
+    }
+
+    #[test]
+    fn test_staking_manager_is_cep4626_compliant() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let validator = env.get_account(2);
+        let staker = env.get_account(3);
+
+        env.set_caller(admin);
+        let mut token = ScsprToken::deploy(&env, ScsprTokenInitArgs { staking_manager: admin });
+        let mut manager = StakingManager::deploy(&env, StakingManagerInitArgs {
+            scspr_token_address: token.address(),
+        });
+        token.set_staking_manager(manager.address());
+        manager.add_validator(validator);
+        manager.set_validator_public_key(validator, env.get_validator(0));
+        manager.set_minimum_stake(U256::one());
+
+        // An existing staker and a year of rewards move the rate off 1:1
+        env.set_caller(staker);
+        manager.with_tokens(U512::from(1_000_000u64)).stake();
+        env.advance_block_time(365 * 24 * 3600);
+        env.set_caller(admin);
+        manager.distribute_rewards(U256::from(30_007));
+
+        let user = env.get_account(4);
+        check_compliance(&mut StakingUnderTest { env: &env, manager, token }, user, U256::from(100_003));
+    }
 }
@@ -0,0 +1,220 @@
+//! Unstake NFT - Transferable claims on pending withdrawals
+//!
+//! Every unstake request is represented by a CEP-78 style NFT whose token ID
+//! is the request ID. Whoever holds the NFT when the request matures can
+//! withdraw the CSPR, so pending withdrawals can be transferred or sold while
+//! the stake is unbonding.
+
+use odra::prelude::*;
+use super::errors::LstError;
+use super::events::{UnstakeNftApproval, UnstakeNftTransfer};
+use crate::access::{AccessControl, Role, ADMIN_ROLE};
+
+/// Unstake request NFT collection
+/// Tokens are minted by the staking manager on unstake and burned on withdrawal.
+#[odra::module]
+pub struct UnstakeNft {
+    /// Collection name
+    name: Var<String>,
+    /// Collection symbol
+    symbol: Var<String>,
+    /// Number of tokens currently in existence
+    total_supply: Var<u64>,
+    /// Owner mapping: token ID -> owner
+    owners: Mapping<u64, Option<Address>>,
+    /// Balance mapping: owner -> number of tokens
+    balances: Mapping<Address, u64>,
+    /// Approved spender per token: token ID -> spender
+    approvals: Mapping<u64, Option<Address>>,
+    /// Operator approvals: (owner, operator) -> approved
+    operators: Mapping<(Address, Address), bool>,
+    /// Staking manager contract address (only this contract can mint/burn)
+    staking_manager: Var<Address>,
+    /// Role-based access control
+    access: SubModule<AccessControl>,
+}
+
+#[odra::module]
+impl UnstakeNft {
+    /// Initialize the unstake NFT collection
+    pub fn init(&mut self, staking_manager: Address) {
+        let caller = self.env().caller();
+        self.name.set(String::from("Ectoplasm Unstake Request"));
+        self.symbol.set(String::from("sCSPR-UNSTAKE"));
+        self.total_supply.set(0);
+        self.staking_manager.set(staking_manager);
+        self.access.grant(ADMIN_ROLE, caller);
+    }
+
+    /// Get the collection name
+    pub fn name(&self) -> String {
+        self.name.get_or_default()
+    }
+
+    /// Get the collection symbol
+    pub fn symbol(&self) -> String {
+        self.symbol.get_or_default()
+    }
+
+    /// Get the number of tokens in existence
+    pub fn total_supply(&self) -> u64 {
+        self.total_supply.get_or_default()
+    }
+
+    /// Get the number of tokens held by an address
+    pub fn balance_of(&self, owner: Address) -> u64 {
+        self.balances.get(&owner).unwrap_or_default()
+    }
+
+    /// Get the owner of a token (reverts if it does not exist)
+    pub fn owner_of(&self, token_id: u64) -> Address {
+        self.owners.get(&token_id)
+            .flatten()
+            .unwrap_or_else(|| self.env().revert(LstError::InvalidUnstakeRequestId))
+    }
+
+    /// Check whether a token exists
+    pub fn exists(&self, token_id: u64) -> bool {
+        self.owners.get(&token_id).flatten().is_some()
+    }
+
+    /// Get the approved spender of a token
+    pub fn get_approved(&self, token_id: u64) -> Option<Address> {
+        self.approvals.get(&token_id).flatten()
+    }
+
+    /// Check whether an operator may move all of an owner's tokens
+    pub fn is_approved_for_all(&self, owner: Address, operator: Address) -> bool {
+        self.operators.get(&(owner, operator)).unwrap_or(false)
+    }
+
+    /// Transfer a token (caller must be the owner, approved spender or operator)
+    pub fn transfer(&mut self, token_id: u64, from: Address, to: Address) {
+        let caller = self.env().caller();
+        let owner = self.owner_of(token_id);
+        if owner != from {
+            self.env().revert(LstError::Unauthorized);
+        }
+        if caller != owner
+            && self.get_approved(token_id) != Some(caller)
+            && !self.is_approved_for_all(owner, caller)
+        {
+            self.env().revert(LstError::Unauthorized);
+        }
+
+        self.approvals.set(&token_id, None);
+        self.owners.set(&token_id, Some(to));
+        self.balances.set(&from, self.balance_of(from) - 1);
+        self.balances.set(&to, self.balance_of(to) + 1);
+
+        self.env().emit_event(UnstakeNftTransfer {
+            from: Some(from),
+            to: Some(to),
+            token_id,
+        });
+    }
+
+    /// Approve a spender for a single token (owner or operator only)
+    pub fn approve(&mut self, spender: Address, token_id: u64) {
+        let caller = self.env().caller();
+        let owner = self.owner_of(token_id);
+        if caller != owner && !self.is_approved_for_all(owner, caller) {
+            self.env().revert(LstError::Unauthorized);
+        }
+
+        self.approvals.set(&token_id, Some(spender));
+
+        self.env().emit_event(UnstakeNftApproval {
+            owner,
+            spender: Some(spender),
+            token_id,
+        });
+    }
+
+    /// Clear the approved spender of a token (owner or operator only)
+    pub fn revoke(&mut self, token_id: u64) {
+        let caller = self.env().caller();
+        let owner = self.owner_of(token_id);
+        if caller != owner && !self.is_approved_for_all(owner, caller) {
+            self.env().revert(LstError::Unauthorized);
+        }
+
+        self.approvals.set(&token_id, None);
+
+        self.env().emit_event(UnstakeNftApproval {
+            owner,
+            spender: None,
+            token_id,
+        });
+    }
+
+    /// Allow or disallow an operator to move all of the caller's tokens
+    pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) {
+        let caller = self.env().caller();
+        self.operators.set(&(caller, operator), approved);
+    }
+
+    /// Mint the NFT for an unstake request (only callable by staking manager)
+    pub fn mint(&mut self, to: Address, token_id: u64) {
+        self.only_staking_manager();
+
+        if self.exists(token_id) {
+            self.env().revert(LstError::UnstakeRequestAlreadyProcessed);
+        }
+
+        self.owners.set(&token_id, Some(to));
+        self.balances.set(&to, self.balance_of(to) + 1);
+        self.total_supply.set(self.total_supply() + 1);
+
+        self.env().emit_event(UnstakeNftTransfer {
+            from: None,
+            to: Some(to),
+            token_id,
+        });
+    }
+
+    /// Burn the NFT of a settled unstake request (only callable by staking manager)
+    pub fn burn(&mut self, token_id: u64) {
+        self.only_staking_manager();
+
+        let owner = self.owner_of(token_id);
+        self.owners.set(&token_id, None);
+        self.approvals.set(&token_id, None);
+        self.balances.set(&owner, self.balance_of(owner) - 1);
+        self.total_supply.set(self.total_supply() - 1);
+
+        self.env().emit_event(UnstakeNftTransfer {
+            from: Some(owner),
+            to: None,
+            token_id,
+        });
+    }
+
+    /// Get the staking manager address
+    pub fn get_staking_manager(&self) -> Address {
+        self.staking_manager.get_or_revert_with(LstError::Unauthorized)
+    }
+
+    /// Update the staking manager address (admin only)
+    pub fn set_staking_manager(&mut self, new_manager: Address) {
+        self.access.require_role(ADMIN_ROLE, LstError::Unauthorized);
+        self.staking_manager.set(new_manager);
+    }
+
+    delegate! {
+        to self.access {
+            fn has_role(&self, role: Role, account: Address) -> bool;
+            fn grant_role(&mut self, role: Role, account: Address);
+            fn revoke_role(&mut self, role: Role, account: Address);
+            fn renounce_role(&mut self, role: Role);
+        }
+    }
+
+    // Internal functions
+
+    fn only_staking_manager(&self) {
+        if self.env().caller() != self.get_staking_manager() {
+            self.env().revert(LstError::Unauthorized);
+        }
+    }
+}
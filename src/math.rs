@@ -1,6 +1,6 @@
 //! Mathematical utilities for the DEX smart contract
 //! Implements safe math operations and AMM formulas
-use odra::casper_types::U256;
+use odra::casper_types::{U256, U512};
 use crate::errors::DexError;
 
 /// Minimum liquidity that is locked forever to prevent division by zero
@@ -12,6 +12,207 @@ pub const FEE_DENOMINATOR: u128 = 1000;
 /// Fee numerator (0.3% fee)
 pub const FEE_NUMERATOR: u128 = 3;
 
+/// Basis points denominator (100% = 10000 bps)
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+/// RAY fixed-point unit (1.0 = 1e27), used for interest indexes and per-second rates
+pub const RAY: u128 = 1_000_000_000_000_000_000_000_000_000;
+
+/// Half a RAY, for rounding to nearest
+pub const HALF_RAY: u128 = RAY / 2;
+
+/// Ratio between RAY and WAD (1e18) precision
+pub const WAD_RAY_RATIO: u128 = 1_000_000_000;
+
+/// WAD fixed-point unit (1.0 = 1e18), used for lending percentages such as
+/// the reserve factor and liquidation bonus
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// Seconds in a (365 day) year
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Multiply two U256 values into a full-width U512 product
+pub fn full_mul(a: U256, b: U256) -> U512 {
+    to_u512(a) * to_u512(b)
+}
+
+/// Integer square root of a full-width product
+///
+/// The root of any U512 fits in a U256.
+pub fn sqrt_full(n: U512) -> U256 {
+    if n.is_zero() {
+        return U256::zero();
+    }
+    let mut x = n;
+    let mut y = n / 2 + n % 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    from_u512(x).unwrap_or(U256::MAX)
+}
+
+/// Calculate `a * b / denominator` with a 512-bit intermediate product
+///
+/// The product never overflows; only a quotient that does not fit in U256 errors.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256, DexError> {
+    if denominator.is_zero() {
+        return Err(DexError::DivisionByZero);
+    }
+    let result = full_mul(a, b) / to_u512(denominator);
+    from_u512(result).ok_or(DexError::Overflow)
+}
+
+/// Multiply two RAY values, rounding half up
+pub fn ray_mul(a: U256, b: U256) -> Result<U256, DexError> {
+    let result = (full_mul(a, b) + U512::from(HALF_RAY)) / U512::from(RAY);
+    from_u512(result).ok_or(DexError::Overflow)
+}
+
+/// Divide two RAY values, rounding half up
+pub fn ray_div(a: U256, b: U256) -> Result<U256, DexError> {
+    if b.is_zero() {
+        return Err(DexError::DivisionByZero);
+    }
+    let result = (full_mul(a, U256::from(RAY)) + to_u512(b / 2)) / to_u512(b);
+    from_u512(result).ok_or(DexError::Overflow)
+}
+
+/// Apply a percentage (WAD, 1e18 = 100%) to a value, rounding half up
+pub fn percent_mul(value: U256, percentage: U256) -> Result<U256, DexError> {
+    let result = (full_mul(value, percentage) + U512::from(WAD / 2)) / U512::from(WAD);
+    from_u512(result).ok_or(DexError::Overflow)
+}
+
+/// Divide a value by a percentage (WAD, 1e18 = 100%), rounding half up
+pub fn percent_div(value: U256, percentage: U256) -> Result<U256, DexError> {
+    if percentage.is_zero() {
+        return Err(DexError::DivisionByZero);
+    }
+    let result = (full_mul(value, U256::from(WAD)) + to_u512(percentage / 2)) / to_u512(percentage);
+    from_u512(result).ok_or(DexError::Overflow)
+}
+
+/// `bps` basis points of an amount, rounding half up
+pub fn bps_of(amount: U256, bps: u32) -> Result<U256, DexError> {
+    let result = (full_mul(amount, U256::from(bps)) + U512::from(BPS_DENOMINATOR / 2))
+        / U512::from(BPS_DENOMINATOR);
+    from_u512(result).ok_or(DexError::Overflow)
+}
+
+/// Raise a fixed-point value (where `scale` is 1.0) to an integer power
+///
+/// Binary exponentiation, rounding each product half up.
+pub fn rpow(base: U256, exp: u64, scale: U256) -> Result<U256, DexError> {
+    if scale.is_zero() {
+        return Err(DexError::DivisionByZero);
+    }
+    let half = to_u512(scale / 2);
+    let mul = |a: U256, b: U256| from_u512((full_mul(a, b) + half) / to_u512(scale)).ok_or(DexError::Overflow);
+
+    let mut base = base;
+    let mut exponent = exp;
+    let mut result = scale;
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            result = mul(result, base)?;
+        }
+        exponent /= 2;
+        if exponent > 0 {
+            base = mul(base, base)?;
+        }
+    }
+    Ok(result)
+}
+
+/// Raise a RAY value to an integer power (exponentiation by squaring)
+pub fn ray_pow(x: U256, n: u64) -> Result<U256, DexError> {
+    rpow(x, n, U256::from(RAY))
+}
+
+/// Per-second rate (RAY) of an annual percentage rate (RAY), rounded half up
+pub fn apr_to_per_second(apr: U256) -> U256 {
+    let seconds = U256::from(SECONDS_PER_YEAR);
+    (apr + seconds / 2) / seconds
+}
+
+/// Annual percentage rate (RAY) of a per-second rate (RAY), without compounding
+pub fn per_second_to_apr(rate_per_second: U256) -> Result<U256, DexError> {
+    SafeMath::mul(rate_per_second, U256::from(SECONDS_PER_YEAR))
+}
+
+/// Growth factor (RAY) of an annual rate (RAY) compounded every second for `elapsed` seconds
+///
+/// Exact counterpart of `compounded_interest`: `(1 + rate_per_second)^elapsed` via `rpow`.
+pub fn compound_apr(apr: U256, elapsed: u64) -> Result<U256, DexError> {
+    rpow(U256::from(RAY) + apr_to_per_second(apr), elapsed, U256::from(RAY))
+}
+
+/// Annual percentage yield (RAY) of an annual rate (RAY) compounded every second
+pub fn apr_to_apy(apr: U256) -> Result<U256, DexError> {
+    Ok(compound_apr(apr, SECONDS_PER_YEAR)? - U256::from(RAY))
+}
+
+/// Convert a WAD (1e18) value to RAY
+pub fn wad_to_ray(a: U256) -> Result<U256, DexError> {
+    SafeMath::mul(a, U256::from(WAD_RAY_RATIO))
+}
+
+/// Convert a RAY value to WAD (1e18), rounding half up
+pub fn ray_to_wad(a: U256) -> U256 {
+    let ratio = U256::from(WAD_RAY_RATIO);
+    let result = a / ratio;
+    if a % ratio >= ratio / 2 { result + 1 } else { result }
+}
+
+/// Growth factor (RAY) of an annual rate (RAY) compounded every second for `elapsed` seconds
+///
+/// Uses the first three terms of the binomial expansion of
+/// `(1 + rate / SECONDS_PER_YEAR)^elapsed`, which is cheap and slightly
+/// undercharges relative to exact compounding. Use `compound_apr` for the exact value.
+pub fn compounded_interest(annual_rate: U256, elapsed: u64) -> Result<U256, DexError> {
+    if elapsed == 0 {
+        return Ok(U256::from(RAY));
+    }
+
+    let exp = U256::from(elapsed);
+    let exp_minus_one = U256::from(elapsed - 1);
+    let exp_minus_two = U256::from(elapsed.saturating_sub(2));
+
+    let rate_per_second = annual_rate / U256::from(SECONDS_PER_YEAR);
+    let base_power_two = ray_mul(rate_per_second, rate_per_second)?;
+    let base_power_three = ray_mul(base_power_two, rate_per_second)?;
+
+    let first_term = SafeMath::mul(rate_per_second, exp)?;
+    let second_term = SafeMath::mul(SafeMath::mul(exp, exp_minus_one)?, base_power_two)? / 2;
+    let third_term = SafeMath::mul(
+        SafeMath::mul(SafeMath::mul(exp, exp_minus_one)?, exp_minus_two)?,
+        base_power_three,
+    )? / 6;
+
+    SafeMath::add(
+        SafeMath::add(U256::from(RAY), first_term)?,
+        SafeMath::add(second_term, third_term)?,
+    )
+}
+
+/// Widen a U256 into a U512
+pub fn to_u512(value: U256) -> U512 {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    U512::from_little_endian(&bytes)
+}
+
+/// Narrow a U512 into a U256, returning None if it does not fit
+pub fn from_u512(value: U512) -> Option<U256> {
+    if value > to_u512(U256::MAX) {
+        return None;
+    }
+    let mut bytes = [0u8; 64];
+    value.to_little_endian(&mut bytes);
+    Some(U256::from_little_endian(&bytes[..32]))
+}
+
 /// Safe math operations for U256
 pub struct SafeMath;
 
@@ -90,13 +291,12 @@ impl AmmMath {
             amount_in,
             U256::from(FEE_DENOMINATOR - FEE_NUMERATOR),
         )?;
-        let numerator = SafeMath::mul(amount_in_with_fee, reserve_out)?;
         let denominator = SafeMath::add(
             SafeMath::mul(reserve_in, U256::from(FEE_DENOMINATOR))?,
             amount_in_with_fee,
         )?;
 
-        SafeMath::div(numerator, denominator)
+        mul_div(amount_in_with_fee, reserve_out, denominator)
     }
 
     /// Calculate the amount of input tokens required for a given output amount
@@ -116,16 +316,17 @@ impl AmmMath {
             return Err(DexError::InsufficientLiquidity);
         }
 
-        let numerator = SafeMath::mul(
-            SafeMath::mul(reserve_in, amount_out)?,
-            U256::from(FEE_DENOMINATOR),
-        )?;
         let denominator = SafeMath::mul(
             SafeMath::sub(reserve_out, amount_out)?,
             U256::from(FEE_DENOMINATOR - FEE_NUMERATOR),
         )?;
+        let amount_in = mul_div(
+            reserve_in,
+            SafeMath::mul(amount_out, U256::from(FEE_DENOMINATOR))?,
+            denominator,
+        )?;
 
-        SafeMath::add(SafeMath::div(numerator, denominator)?, U256::one())
+        SafeMath::add(amount_in, U256::one())
     }
 
     /// Calculate the optimal amount of token B given an amount of token A
@@ -143,7 +344,7 @@ impl AmmMath {
             return Err(DexError::InsufficientLiquidity);
         }
 
-        SafeMath::div(SafeMath::mul(amount_a, reserve_b)?, reserve_a)
+        mul_div(amount_a, reserve_b, reserve_a)
     }
 
     /// Calculate the amount of liquidity tokens to mint
@@ -233,6 +434,136 @@ mod tests {
         assert_eq!(SafeMath::sqrt(U256::from(100)), U256::from(10));
     }
 
+    #[test]
+    fn test_sqrt_full() {
+        assert_eq!(sqrt_full(U512::zero()), U256::zero());
+        assert_eq!(sqrt_full(U512::from(2)), U256::one());
+        assert_eq!(sqrt_full(U512::from(99)), U256::from(9));
+
+        // A 1e80 product no longer fits in U256, but its root does
+        let amount = U256::from(10u128.pow(20)) * U256::from(10u128.pow(20));
+        assert_eq!(sqrt_full(full_mul(amount, amount)), amount);
+        assert_eq!(sqrt_full(U512::MAX), U256::MAX);
+    }
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(
+            mul_div(U256::from(10), U256::from(20), U256::from(8)).unwrap(),
+            U256::from(25)
+        );
+        // Intermediate product exceeds U256 but the quotient fits
+        assert_eq!(mul_div(U256::MAX, U256::MAX, U256::MAX).unwrap(), U256::MAX);
+        assert_eq!(
+            mul_div(U256::MAX, U256::from(2), U256::from(4)).unwrap(),
+            U256::MAX / 2
+        );
+        assert!(matches!(
+            mul_div(U256::MAX, U256::from(2), U256::one()),
+            Err(DexError::Overflow)
+        ));
+        assert!(matches!(
+            mul_div(U256::one(), U256::one(), U256::zero()),
+            Err(DexError::DivisionByZero)
+        ));
+    }
+
+    #[test]
+    fn test_ray_mul_div() {
+        let ray = U256::from(RAY);
+        let one_and_half = U256::from(RAY + HALF_RAY);
+
+        assert_eq!(ray_mul(one_and_half, ray * 2).unwrap(), ray * 3);
+        assert_eq!(ray_div(ray * 3, ray * 2).unwrap(), one_and_half);
+        // Half a unit rounds up
+        assert_eq!(ray_mul(U256::one(), U256::from(HALF_RAY)).unwrap(), U256::one());
+        assert_eq!(ray_div(U256::one(), ray * 2).unwrap(), U256::one());
+        assert!(matches!(ray_div(ray, U256::zero()), Err(DexError::DivisionByZero)));
+        assert!(matches!(ray_mul(U256::MAX, ray * 2), Err(DexError::Overflow)));
+
+        assert_eq!(wad_to_ray(U256::from(10u128.pow(18))).unwrap(), ray);
+        assert_eq!(ray_to_wad(U256::from(1_500_000_000u128)), U256::from(2));
+        assert_eq!(ray_to_wad(U256::from(1_499_999_999u128)), U256::one());
+    }
+
+    #[test]
+    fn test_ray_pow() {
+        let ray = U256::from(RAY);
+        assert_eq!(ray_pow(ray * 2, 0).unwrap(), ray);
+        assert_eq!(ray_pow(ray * 2, 1).unwrap(), ray * 2);
+        assert_eq!(ray_pow(ray * 2, 10).unwrap(), ray * 1024);
+        assert_eq!(ray_pow(U256::from(HALF_RAY), 3).unwrap(), ray / 8);
+    }
+
+    #[test]
+    fn test_percentage_helpers_round_half_up() {
+        let wad = U256::from(WAD);
+        // 10% reserve factor of 15 is 1.5, rounded up to 2; of 14 is 1.4, rounded to 1
+        assert_eq!(percent_mul(U256::from(15), wad / 10).unwrap(), U256::from(2));
+        assert_eq!(percent_mul(U256::from(14), wad / 10).unwrap(), U256::one());
+        // 300 of collateral at a 5% bonus covers 285.71, rounded to 286
+        let bonus_multiplier = wad + wad / 20;
+        assert_eq!(percent_mul(U256::from(400), bonus_multiplier).unwrap(), U256::from(420));
+        assert_eq!(percent_div(U256::from(300), bonus_multiplier).unwrap(), U256::from(286));
+        assert!(matches!(percent_div(U256::one(), U256::zero()), Err(DexError::DivisionByZero)));
+
+        assert_eq!(bps_of(U256::from(1_000), 1_000).unwrap(), U256::from(100));
+        assert_eq!(bps_of(U256::from(5), 1_000).unwrap(), U256::one());
+        assert_eq!(bps_of(U256::from(4), 1_000).unwrap(), U256::zero());
+    }
+
+    #[test]
+    fn test_rpow_and_rate_conversions() {
+        // Any scale works: 1.5^3 = 3.375 at 1e3 precision
+        assert_eq!(rpow(U256::from(1_500), 3, U256::from(1_000)).unwrap(), U256::from(3_375));
+        assert_eq!(rpow(U256::from(7), 0, U256::from(1_000)).unwrap(), U256::from(1_000));
+        assert!(matches!(rpow(U256::one(), 1, U256::zero()), Err(DexError::DivisionByZero)));
+
+        let apr = U256::from(RAY / 20);
+        let rate_per_second = apr_to_per_second(apr);
+        assert_eq!(rate_per_second, (apr + U256::from(SECONDS_PER_YEAR / 2)) / U256::from(SECONDS_PER_YEAR));
+        let round_trip = per_second_to_apr(rate_per_second).unwrap();
+        assert!(apr - round_trip < U256::from(SECONDS_PER_YEAR));
+
+        // 5% APR compounded every second yields just under e^0.05 - 1
+        let apy = apr_to_apy(apr).unwrap();
+        let e_pow_five_percent_minus_one = U256::from(51_271_096_376_024_039_697_517_636u128);
+        assert!(e_pow_five_percent_minus_one - apy < U256::from(10u128.pow(18)));
+        assert_eq!(compound_apr(apr, 1).unwrap(), U256::from(RAY) + rate_per_second);
+    }
+
+    #[test]
+    fn test_compounded_interest_matches_exp() {
+        // 5% APR compounded every second for a year approaches e^0.05
+        let e_pow_five_percent = U256::from(1_051_271_096_376_024_039_697_517_636u128);
+        let rate = U256::from(RAY / 20);
+        let rate_per_second = rate / U256::from(SECONDS_PER_YEAR);
+
+        let exact = ray_pow(U256::from(RAY) + rate_per_second, SECONDS_PER_YEAR).unwrap();
+        assert!(e_pow_five_percent - exact < U256::from(10u128.pow(18))); // within 1e-9
+
+        let approx = compounded_interest(rate, SECONDS_PER_YEAR).unwrap();
+        assert!(approx <= exact);
+        assert!(e_pow_five_percent - approx < U256::from(10u128.pow(21))); // within 1e-6
+
+        assert_eq!(compounded_interest(rate, 0).unwrap(), U256::from(RAY));
+        assert_eq!(
+            compounded_interest(rate, 1).unwrap(),
+            U256::from(RAY) + rate_per_second
+        );
+    }
+
+    #[test]
+    fn test_get_amount_out_large_reserves() {
+        // 1e60 reserves overflow U256 in amount_in_with_fee * reserve_out without mul_div
+        let reserve = U256::from(10u128.pow(30)) * U256::from(10u128.pow(30));
+        let amount_in = U256::from(10u128.pow(30)) * U256::from(10u128.pow(20));
+
+        let amount_out = AmmMath::get_amount_out(amount_in, reserve, reserve).unwrap();
+        assert!(amount_out < amount_in);
+        assert!(amount_out > U256::zero());
+    }
+
     #[test]
     fn test_get_amount_out() {
         let amount_in = U256::from(1000);
@@ -1,13 +1,36 @@
 //! CEP-18 compatible token implementation for LP tokens
 //! This module provides the LP (Liquidity Provider) token functionality
+//! and the `Cep18Base` ledger the protocol's tokens are built on
 use odra::prelude::*;
-use odra::casper_types::U256;
-use crate::events::{Transfer, Approval};
+use odra::casper_types::bytesrepr::{Bytes, ToBytes};
+use odra::casper_types::{PublicKey, U256};
+use crate::events::{Burn, DecreaseAllowance, IncreaseAllowance, Mint, SetAllowance, Transfer, TransferFrom};
 use crate::errors::TokenError;
 
-/// LP Token module implementing CEP-18 standard
-#[odra::module]
-pub struct LpToken {
+/// Metadata wallets and explorers read to display a token
+#[odra::odra_type]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: U256,
+    pub contract_version: String,
+}
+
+/// CEP-18 ledger shared by the protocol's tokens
+///
+/// Holds metadata, balances and allowances and implements the standard
+/// transfer/approve flows once. Token modules embed it as a submodule and
+/// delegate their CEP-18 entry points to it; `mint`/`burn` are unrestricted
+/// here, so a token gates them itself, usually with `require_minter`.
+///
+/// Supports signature-based approvals (`permit`) so integrations can pull
+/// tokens in the same deploy that the owner signs off-chain.
+///
+/// Emits the CEP-18 standard events, whose schemas are registered through
+/// the Casper Event Standard when any token embedding the ledger is installed.
+#[odra::module(events = [Mint, Burn, SetAllowance, IncreaseAllowance, DecreaseAllowance, Transfer, TransferFrom])]
+pub struct Cep18Base {
     /// Token name
     name: Var<String>,
     /// Token symbol
@@ -20,15 +43,19 @@ pub struct LpToken {
     balances: Mapping<Address, U256>,
     /// Allowance mapping: owner -> spender -> amount
     allowances: Mapping<(Address, Address), U256>,
+    /// Addresses allowed to mint and burn
+    minters: Mapping<Address, bool>,
+    /// Permit nonces: owner -> next nonce
+    nonces: Mapping<Address, u64>,
 }
 
 #[odra::module]
-impl LpToken {
-    /// Initialize the LP token with name and symbol
-    pub fn init(&mut self, name: String, symbol: String) {
+impl Cep18Base {
+    /// Initialize the token metadata
+    pub fn init(&mut self, name: String, symbol: String, decimals: u8) {
         self.name.set(name);
         self.symbol.set(symbol);
-        self.decimals.set(18);
+        self.decimals.set(decimals);
         self.total_supply.set(U256::zero());
     }
 
@@ -65,93 +92,271 @@ impl LpToken {
     /// Transfer tokens to another address
     pub fn transfer(&mut self, to: Address, amount: U256) -> bool {
         let caller = self.env().caller();
-        self.transfer_internal(caller, to, amount);
+        self.raw_transfer(caller, to, amount);
         true
     }
 
     /// Approve a spender to spend tokens
     pub fn approve(&mut self, spender: Address, amount: U256) -> bool {
         let caller = self.env().caller();
-        self.approve_internal(caller, spender, amount);
+        self.raw_approve(caller, spender, amount);
+        true
+    }
+
+    /// Raise the caller's allowance for a spender by `amount`
+    /// Unlike `approve`, cannot be front-run into spending the old allowance too
+    pub fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool {
+        let owner = self.env().caller();
+        let allowance = self.allowance(owner, spender).saturating_add(amount);
+        self.allowances.set(&(owner, spender), allowance);
+        self.env().emit_event(IncreaseAllowance { owner, spender, allowance, inc_by: amount });
+        true
+    }
+
+    /// Lower the caller's allowance for a spender by `amount` (down to zero)
+    pub fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool {
+        let owner = self.env().caller();
+        let allowance = self.allowance(owner, spender).saturating_sub(amount);
+        self.allowances.set(&(owner, spender), allowance);
+        self.env().emit_event(DecreaseAllowance { owner, spender, allowance, decr_by: amount });
         true
     }
 
     /// Transfer tokens from one address to another (requires approval)
     pub fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool {
         let caller = self.env().caller();
-        let current_allowance = self.allowance(from, caller);
-        
-        if current_allowance < amount {
-            self.env().revert(TokenError::InsufficientAllowance);
-        }
-        
-        self.approve_internal(from, caller, current_allowance - amount);
-        self.transfer_internal(from, to, amount);
+        self.raw_transfer_from(caller, from, to, amount);
         true
     }
 
-    /// Mint new tokens (internal function)
+    /// Approve a spender with an off-chain signature from the owner
+    ///
+    /// The owner signs `permit_digest(owner, spender, value, nonce, deadline)`
+    /// with the key behind `public_key`; anyone can submit it before `deadline`.
+    pub fn permit(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        deadline: u64,
+        public_key: PublicKey,
+        signature: Bytes,
+    ) {
+        if self.env().get_block_time() > deadline {
+            self.env().revert(TokenError::PermitExpired);
+        }
+        if Address::Account(public_key.to_account_hash()) != owner {
+            self.env().revert(TokenError::InvalidSignature);
+        }
+
+        let nonce = self.nonces(owner);
+        let digest = self.permit_digest(owner, spender, value, nonce, deadline);
+        if !self.env().verify_signature(&Bytes::from(digest.to_vec()), &signature, &public_key) {
+            self.env().revert(TokenError::InvalidSignature);
+        }
+
+        self.nonces.set(&owner, nonce + 1);
+        self.raw_approve(owner, spender, value);
+    }
+
+    /// Get the next permit nonce of an owner
+    pub fn nonces(&self, owner: Address) -> u64 {
+        self.nonces.get(&owner).unwrap_or_default()
+    }
+
+    /// Get the permit domain separator (binds signatures to this token contract)
+    pub fn domain_separator(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        data.extend_from_slice(self.name().as_bytes());
+        data.extend_from_slice(b"1");
+        data.extend(self.serialize(&self.env().self_address()));
+        self.env().hash(data)
+    }
+
+    /// Get the digest an owner signs to approve `spender` for `value`
+    pub fn permit_digest(
+        &self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        nonce: u64,
+        deadline: u64,
+    ) -> [u8; 32] {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"Permit");
+        data.extend_from_slice(&self.domain_separator());
+        data.extend(self.serialize(&(owner, spender, value, nonce, deadline)));
+        self.env().hash(data)
+    }
+
+    /// Get the version of the token contract code
+    pub fn contract_version(&self) -> String {
+        String::from(env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Get everything a wallet needs to display the token
+    pub fn metadata(&self) -> TokenMetadata {
+        TokenMetadata {
+            name: self.name(),
+            symbol: self.symbol(),
+            decimals: self.decimals(),
+            total_supply: self.total_supply(),
+            contract_version: self.contract_version(),
+        }
+    }
+
+    /// Mint new tokens (the token checks who may mint)
     pub fn mint(&mut self, to: Address, amount: U256) {
-        let current_supply = self.total_supply();
-        let new_supply = current_supply + amount;
-        self.total_supply.set(new_supply);
+        self.credit(to, amount);
+
+        self.env().emit_event(Mint {
+            recipient: to,
+            amount,
+        });
+    }
+
+    /// Burn tokens (the token checks who may burn)
+    pub fn burn(&mut self, from: Address, amount: U256) {
+        self.debit(from, amount);
+
+        self.env().emit_event(Burn {
+            owner: from,
+            amount,
+        });
+    }
 
-        let current_balance = self.balance_of(to);
-        self.balances.set(&to, current_balance + amount);
+    /// Move tokens between addresses without checking the caller
+    pub fn raw_transfer(&mut self, from: Address, to: Address, amount: U256) {
+        self.move_balance(from, to, amount);
 
         self.env().emit_event(Transfer {
-            from: Address::from(self.env().self_address()),
-            to,
-            value: amount,
+            sender: from,
+            recipient: to,
+            amount,
         });
     }
 
-    /// Burn tokens (internal function)
-    pub fn burn(&mut self, from: Address, amount: U256) {
+    /// Move tokens on behalf of `owner`, using up `spender`'s allowance
+    pub fn raw_transfer_from(&mut self, spender: Address, owner: Address, recipient: Address, amount: U256) {
+        self.spend_allowance(owner, spender, amount);
+        self.move_balance(owner, recipient, amount);
+
+        self.env().emit_event(TransferFrom {
+            spender,
+            owner,
+            recipient,
+            amount,
+        });
+    }
+
+    /// Set an allowance without checking the caller
+    pub fn raw_approve(&mut self, owner: Address, spender: Address, amount: U256) {
+        self.allowances.set(&(owner, spender), amount);
+
+        self.env().emit_event(SetAllowance {
+            owner,
+            spender,
+            allowance: amount,
+        });
+    }
+
+    /// Use up `amount` of the allowance `owner` gave `spender`
+    /// Like the CEP-18 reference, spending an allowance emits no event of its own.
+    pub fn spend_allowance(&mut self, owner: Address, spender: Address, amount: U256) {
+        let current_allowance = self.allowance(owner, spender);
+        if current_allowance < amount {
+            self.env().revert(TokenError::InsufficientAllowance);
+        }
+        self.allowances.set(&(owner, spender), current_allowance - amount);
+    }
+
+    /// Allow or disallow an address to mint and burn (the token checks access)
+    pub fn set_minter(&mut self, account: Address, allowed: bool) {
+        self.minters.set(&account, allowed);
+    }
+
+    /// Check whether an address may mint and burn
+    pub fn is_minter(&self, account: Address) -> bool {
+        self.minters.get(&account).unwrap_or_default()
+    }
+
+    /// Revert unless the caller may mint and burn
+    pub fn require_minter(&self) {
+        if !self.is_minter(self.env().caller()) {
+            self.env().revert(TokenError::Unauthorized);
+        }
+    }
+
+    /// Add to a balance and the supply without emitting an event
+    /// For tokens whose ledger units differ from their CEP-18 amounts, which emit their own events.
+    pub fn credit(&mut self, to: Address, amount: U256) {
+        self.total_supply.set(self.total_supply() + amount);
+        self.balances.set(&to, self.balance_of(to) + amount);
+    }
+
+    /// Take from a balance and the supply without emitting an event
+    pub fn debit(&mut self, from: Address, amount: U256) {
         let current_balance = self.balance_of(from);
         if current_balance < amount {
             self.env().revert(TokenError::InsufficientBalance);
         }
 
         self.balances.set(&from, current_balance - amount);
-
-        let current_supply = self.total_supply();
-        self.total_supply.set(current_supply - amount);
-
-        self.env().emit_event(Transfer {
-            from,
-            to: Address::from(self.env().self_address()),
-            value: amount,
-        });
+        self.total_supply.set(self.total_supply() - amount);
     }
 
-    /// Internal transfer function
-    fn transfer_internal(&mut self, from: Address, to: Address, amount: U256) {
+    /// Move tokens between addresses without checking the caller or emitting an event
+    pub fn move_balance(&mut self, from: Address, to: Address, amount: U256) {
         let from_balance = self.balance_of(from);
         if from_balance < amount {
             self.env().revert(TokenError::InsufficientBalance);
         }
 
         self.balances.set(&from, from_balance - amount);
-        let to_balance = self.balance_of(to);
-        self.balances.set(&to, to_balance + amount);
+        self.balances.set(&to, self.balance_of(to) + amount);
+    }
 
-        self.env().emit_event(Transfer {
-            from,
-            to,
-            value: amount,
-        });
+    fn serialize<T: ToBytes>(&self, value: &T) -> Vec<u8> {
+        value.to_bytes().unwrap_or_else(|_| self.env().revert(TokenError::InvalidSignature))
     }
+}
 
-    /// Internal approve function
-    fn approve_internal(&mut self, owner: Address, spender: Address, amount: U256) {
-        self.allowances.set(&(owner, spender), amount);
+/// LP Token module implementing CEP-18 standard
+#[odra::module]
+pub struct LpToken {
+    /// CEP-18 ledger
+    token: SubModule<Cep18Base>,
+}
 
-        self.env().emit_event(Approval {
-            owner,
-            spender,
-            value: amount,
-        });
+#[odra::module]
+impl LpToken {
+    /// Initialize the LP token with name and symbol
+    pub fn init(&mut self, name: String, symbol: String) {
+        self.token.init(name, symbol, 18);
+    }
+
+    delegate! {
+        to self.token {
+            fn name(&self) -> String;
+            fn symbol(&self) -> String;
+            fn decimals(&self) -> u8;
+            fn total_supply(&self) -> U256;
+            fn balance_of(&self, owner: Address) -> U256;
+            fn allowance(&self, owner: Address, spender: Address) -> U256;
+            fn transfer(&mut self, to: Address, amount: U256) -> bool;
+            fn approve(&mut self, spender: Address, amount: U256) -> bool;
+            fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool;
+            fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> bool;
+            fn permit(&mut self, owner: Address, spender: Address, value: U256, deadline: u64, public_key: PublicKey, signature: Bytes);
+            fn nonces(&self, owner: Address) -> u64;
+            fn domain_separator(&self) -> [u8; 32];
+            fn permit_digest(&self, owner: Address, spender: Address, value: U256, nonce: u64, deadline: u64) -> [u8; 32];
+            fn contract_version(&self) -> String;
+            fn metadata(&self) -> TokenMetadata;
+            fn mint(&mut self, to: Address, amount: U256);
+            fn burn(&mut self, from: Address, amount: U256);
+        }
     }
 }
 
@@ -170,6 +375,12 @@ pub trait Cep18Token {
     /// Approve a spender
     fn approve(&mut self, spender: Address, amount: U256) -> bool;
     
+    /// Raise a spender's allowance
+    fn increase_allowance(&mut self, spender: Address, amount: U256) -> bool;
+    
+    /// Lower a spender's allowance
+    fn decrease_allowance(&mut self, spender: Address, amount: U256) -> bool;
+    
     /// Get allowance
     fn allowance(&self, owner: Address, spender: Address) -> U256;
     
@@ -210,6 +421,31 @@ mod tests {
         assert_eq!(token.total_supply(), U256::zero());
     }
 
+    #[test]
+    fn test_metadata_and_cep18_events() {
+        let (env, mut token) = setup();
+        let (owner, spender, recipient) = (env.get_account(0), env.get_account(1), env.get_account(2));
+
+        token.mint(owner, U256::from(1_000));
+        token.approve(spender, U256::from(400));
+        env.set_caller(spender);
+        token.transfer_from(owner, recipient, U256::from(300));
+
+        assert!(env.emitted_event(&token, Mint { recipient: owner, amount: U256::from(1_000) }));
+        assert!(env.emitted_event(&token, SetAllowance { owner, spender, allowance: U256::from(400) }));
+        assert!(env.emitted_event(&token, TransferFrom {
+            spender,
+            owner,
+            recipient,
+            amount: U256::from(300),
+        }));
+
+        let metadata = token.metadata();
+        assert_eq!(metadata.symbol, "LP");
+        assert_eq!(metadata.total_supply, U256::from(1_000));
+        assert_eq!(metadata.contract_version, token.contract_version());
+    }
+
     #[test]
     fn test_mint_and_burn() {
         let (env, mut token) = setup();
@@ -240,4 +476,42 @@ mod tests {
         assert_eq!(token.balance_of(user1), U256::from(500));
         assert_eq!(token.balance_of(user2), U256::from(500));
     }
+
+    #[test]
+    fn test_increase_and_decrease_allowance() {
+        let (env, mut token) = setup();
+        let owner = env.get_account(0);
+        let spender = env.get_account(1);
+
+        token.approve(spender, U256::from(100));
+        token.increase_allowance(spender, U256::from(50));
+        assert_eq!(token.allowance(owner, spender), U256::from(150));
+        token.decrease_allowance(spender, U256::from(60));
+        assert_eq!(token.allowance(owner, spender), U256::from(90));
+        // Decreasing past zero leaves no allowance rather than reverting
+        token.decrease_allowance(spender, U256::from(1_000));
+        assert_eq!(token.allowance(owner, spender), U256::zero());
+    }
+
+    #[test]
+    fn test_permit() {
+        let (env, mut token) = setup();
+        let owner = env.get_account(1);
+        let spender = env.get_account(2);
+        let value = U256::from(700);
+        let deadline = env.block_time() + 3_600;
+
+        let digest = token.permit_digest(owner, spender, value, 0, deadline);
+        let signature = env.sign_message(&Bytes::from(digest.to_vec()), &owner);
+
+        // Anyone can submit the owner's signature, but only once
+        env.set_caller(spender);
+        token.permit(owner, spender, value, deadline, env.public_key(&owner), signature.clone());
+        assert_eq!(token.allowance(owner, spender), value);
+        assert_eq!(token.nonces(owner), 1);
+        assert_eq!(
+            token.try_permit(owner, spender, value, deadline, env.public_key(&owner), signature).unwrap_err(),
+            TokenError::InvalidSignature.into()
+        );
+    }
 }
\ No newline at end of file